@@ -3,6 +3,7 @@ use std::path::Path;
 use std::path::PathBuf;
 
 use codex_core::CODEX_APPLY_PATCH_ARG1;
+use codex_core::CODEX_LINUX_SANDBOX_ARG1;
 #[cfg(unix)]
 use std::os::unix::fs::symlink;
 use tempfile::TempDir;
@@ -53,6 +54,10 @@ where
     }
 
     let argv1 = args.next().unwrap_or_default();
+    if argv1 == CODEX_LINUX_SANDBOX_ARG1 {
+        // Same as the alias above, for callers that cannot set argv[0].
+        codex_linux_sandbox::run_main();
+    }
     if argv1 == CODEX_APPLY_PATCH_ARG1 {
         let patch_arg = args.next().and_then(|s| s.to_str().map(|s| s.to_owned()));
         let exit_code = match patch_arg {
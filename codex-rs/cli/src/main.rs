@@ -12,8 +12,14 @@ use codex_cli::login::run_login_with_api_key;
 use codex_cli::login::run_login_with_chatgpt;
 use codex_cli::login::run_logout;
 use codex_cli::proto;
+use codex_cli::sessions::ArchiveCommand;
+use codex_cli::sessions::ExportCommand;
+use codex_cli::sessions::PruneCommand;
+use codex_cli::sessions::RemoveCommand;
 use codex_common::CliConfigOverrides;
 use codex_exec::Cli as ExecCli;
+use codex_exec::Command as ExecCommand;
+use codex_exec::ReviewArgs as ExecReviewArgs;
 use codex_tui::Cli as TuiCli;
 use std::path::PathBuf;
 
@@ -50,6 +56,9 @@ enum Subcommand {
     #[clap(visible_alias = "e")]
     Exec(ExecCli),
 
+    /// Review code changes non-interactively and report prioritized findings.
+    Review(ReviewCommand),
+
     /// Manage login.
     Login(LoginCommand),
 
@@ -76,6 +85,9 @@ enum Subcommand {
     /// Resume a previous interactive session (picker by default; use --last to continue the most recent).
     Resume(ResumeCommand),
 
+    /// Manage recorded sessions.
+    Sessions(SessionsCommand),
+
     /// Internal: generate TypeScript protocol bindings.
     #[clap(hide = true)]
     GenerateTs(GenerateTsCommand),
@@ -98,6 +110,45 @@ struct ResumeCommand {
     /// Continue the most recent session without showing the picker.
     #[arg(long = "last", default_value_t = false, conflicts_with = "session_id")]
     last: bool,
+
+    /// Only show sessions whose messages contain all of these words. With
+    /// --last, continue the most recent of them.
+    #[arg(long = "search", value_name = "QUERY", conflicts_with = "session_id")]
+    search: Option<String>,
+}
+
+#[derive(Debug, Parser)]
+struct SessionsCommand {
+    #[command(subcommand)]
+    action: SessionsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum SessionsSubcommand {
+    /// Export a session as Markdown, a self-contained HTML page, or JSON.
+    Export(ExportCommand),
+
+    /// Apply the retention policy: delete old sessions and compress older ones.
+    Prune(PruneCommand),
+
+    /// Move sessions into the archived sessions directory.
+    Archive(ArchiveCommand),
+
+    /// Delete sessions.
+    Rm(RemoveCommand),
+}
+
+#[derive(Debug, Parser)]
+struct ReviewCommand {
+    #[clap(flatten)]
+    target: ExecReviewArgs,
+
+    /// Print events to stdout as JSONL.
+    #[arg(long = "json", default_value_t = false)]
+    json: bool,
+
+    #[clap(skip)]
+    config_overrides: CliConfigOverrides,
 }
 
 #[derive(Debug, Parser)]
@@ -182,17 +233,36 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             );
             codex_exec::run_main(exec_cli, codex_linux_sandbox_exe).await?;
         }
+        Some(Subcommand::Review(ReviewCommand {
+            target,
+            json,
+            mut config_overrides,
+        })) => {
+            // `codex review` is shorthand for `codex exec review` with default
+            // exec options.
+            prepend_config_flags(&mut config_overrides, root_config_overrides.clone());
+            let mut exec_cli = ExecCli::parse_from(["codex"]);
+            exec_cli.command = Some(ExecCommand::Review(target));
+            exec_cli.json = json;
+            exec_cli.config_overrides = config_overrides;
+            codex_exec::run_main(exec_cli, codex_linux_sandbox_exe).await?;
+        }
         Some(Subcommand::Mcp) => {
             codex_mcp_server::run_main(codex_linux_sandbox_exe, root_config_overrides.clone())
                 .await?;
         }
-        Some(Subcommand::Resume(ResumeCommand { session_id, last })) => {
+        Some(Subcommand::Resume(ResumeCommand {
+            session_id,
+            last,
+            search,
+        })) => {
             // Start with the parsed interactive CLI so resume shares the same
             // configuration surface area as `codex` without additional flags.
             let resume_session_id = session_id;
             interactive.resume_picker = resume_session_id.is_none() && !last;
             interactive.resume_last = last;
             interactive.resume_session_id = resume_session_id;
+            interactive.resume_search = search;
 
             // Propagate any root-level config overrides (e.g. `-c key=value`).
             prepend_config_flags(
@@ -201,6 +271,33 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             );
             codex_tui::run_main(interactive, codex_linux_sandbox_exe).await?;
         }
+        Some(Subcommand::Sessions(sessions_cli)) => match sessions_cli.action {
+            SessionsSubcommand::Export(mut export_cli) => {
+                prepend_config_flags(
+                    &mut export_cli.config_overrides,
+                    root_config_overrides.clone(),
+                );
+                codex_cli::sessions::run_export(export_cli).await?;
+            }
+            SessionsSubcommand::Prune(mut prune_cli) => {
+                prepend_config_flags(
+                    &mut prune_cli.config_overrides,
+                    root_config_overrides.clone(),
+                );
+                codex_cli::sessions::run_prune(prune_cli).await?;
+            }
+            SessionsSubcommand::Archive(mut archive_cli) => {
+                prepend_config_flags(
+                    &mut archive_cli.config_overrides,
+                    root_config_overrides.clone(),
+                );
+                codex_cli::sessions::run_archive(archive_cli).await?;
+            }
+            SessionsSubcommand::Rm(mut rm_cli) => {
+                prepend_config_flags(&mut rm_cli.config_overrides, root_config_overrides.clone());
+                codex_cli::sessions::run_remove(rm_cli).await?;
+            }
+        },
         Some(Subcommand::Login(mut login_cli)) => {
             prepend_config_flags(
                 &mut login_cli.config_overrides,
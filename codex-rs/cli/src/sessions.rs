@@ -0,0 +1,239 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use clap::Parser;
+use clap::ValueEnum;
+use codex_common::CliConfigOverrides;
+use codex_core::ARCHIVED_SESSIONS_SUBDIR;
+use codex_core::SESSIONS_SUBDIR;
+use codex_core::archive_rollout;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::delete_rollout;
+use codex_core::export_rollout;
+use codex_core::find_conversation_path_by_id_str;
+use codex_core::is_recorded_rollout;
+use codex_core::prune_sessions;
+use codex_protocol::mcp_protocol::ExportFormat;
+
+#[derive(Debug, Parser)]
+pub struct ExportCommand {
+    /// Session id (UUID) or path to a rollout file.
+    #[arg(value_name = "SESSION")]
+    pub session: String,
+
+    /// Output format. Defaults to the one matching the extension of --output,
+    /// or Markdown.
+    #[arg(long = "format", short = 'f', value_enum)]
+    pub format: Option<ExportFormatArg>,
+
+    /// Write the export to this file instead of stdout.
+    #[arg(long = "output", short = 'o', value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    #[clap(skip)]
+    pub config_overrides: CliConfigOverrides,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ExportFormatArg {
+    #[value(alias = "md")]
+    Markdown,
+    Html,
+    Json,
+}
+
+impl From<ExportFormatArg> for ExportFormat {
+    fn from(format: ExportFormatArg) -> Self {
+        match format {
+            ExportFormatArg::Markdown => ExportFormat::Markdown,
+            ExportFormatArg::Html => ExportFormat::Html,
+            ExportFormatArg::Json => ExportFormat::Json,
+        }
+    }
+}
+
+#[derive(Debug, Parser)]
+pub struct PruneCommand {
+    /// Show what would be deleted and compressed without doing it.
+    #[arg(long = "dry-run", default_value_t = false)]
+    pub dry_run: bool,
+
+    /// Delete sessions not written to in this many days. Overrides
+    /// `sessions.max_age_days`.
+    #[arg(long = "max-age-days", value_name = "DAYS")]
+    pub max_age_days: Option<u64>,
+
+    /// Delete the least recently written sessions until all of them take at
+    /// most this many bytes. Overrides `sessions.max_total_bytes`.
+    #[arg(long = "max-total-bytes", value_name = "BYTES")]
+    pub max_total_bytes: Option<u64>,
+
+    /// Gzip sessions not written to in this many days. Overrides
+    /// `sessions.compress_after_days`.
+    #[arg(long = "compress-after-days", value_name = "DAYS")]
+    pub compress_after_days: Option<u64>,
+
+    #[clap(skip)]
+    pub config_overrides: CliConfigOverrides,
+}
+
+#[derive(Debug, Parser)]
+pub struct ArchiveCommand {
+    /// Session ids (UUIDs) or paths to rollout files.
+    #[arg(value_name = "SESSION", required = true)]
+    pub sessions: Vec<String>,
+
+    #[clap(skip)]
+    pub config_overrides: CliConfigOverrides,
+}
+
+#[derive(Debug, Parser)]
+pub struct RemoveCommand {
+    /// Session ids (UUIDs) or paths to rollout files.
+    #[arg(value_name = "SESSION", required = true)]
+    pub sessions: Vec<String>,
+
+    #[clap(skip)]
+    pub config_overrides: CliConfigOverrides,
+}
+
+pub async fn run_export(cmd: ExportCommand) -> anyhow::Result<()> {
+    let ExportCommand {
+        session,
+        format,
+        output,
+        config_overrides,
+    } = cmd;
+    let config = load_config(config_overrides)?;
+
+    let path = resolve_session_path(&config.codex_home, &session).await?;
+    let format = match format {
+        Some(format) => format.into(),
+        None => output
+            .as_deref()
+            .and_then(format_for_extension)
+            .unwrap_or_default(),
+    };
+    let content = export_rollout(&path, format).await?;
+    match output {
+        Some(output) => tokio::fs::write(&output, content).await?,
+        None => print!("{content}"),
+    }
+    Ok(())
+}
+
+pub async fn run_prune(cmd: PruneCommand) -> anyhow::Result<()> {
+    let PruneCommand {
+        dry_run,
+        max_age_days,
+        max_total_bytes,
+        compress_after_days,
+        config_overrides,
+    } = cmd;
+    let config = load_config(config_overrides)?;
+
+    let mut retention = config.sessions;
+    retention.max_age_days = max_age_days.or(retention.max_age_days);
+    retention.max_total_bytes = max_total_bytes.or(retention.max_total_bytes);
+    retention.compress_after_days = compress_after_days.or(retention.compress_after_days);
+    if !retention.is_enabled() {
+        anyhow::bail!(
+            "no retention limit set; configure [sessions] in config.toml or pass --max-age-days, --max-total-bytes or --compress-after-days"
+        );
+    }
+
+    let report = prune_sessions(&config.codex_home, &retention, dry_run).await?;
+    let (delete_verb, compress_verb) = if dry_run {
+        ("Would delete", "Would compress")
+    } else {
+        ("Deleted", "Compressed")
+    };
+    for path in &report.deleted {
+        println!("{delete_verb} {}", path.display());
+    }
+    for path in &report.compressed {
+        println!("{compress_verb} {}", path.display());
+    }
+    println!(
+        "{delete_verb} {} session(s) ({} bytes), {} {} session(s).",
+        report.deleted.len(),
+        report.deleted_bytes,
+        compress_verb.to_lowercase(),
+        report.compressed.len()
+    );
+    Ok(())
+}
+
+pub async fn run_archive(cmd: ArchiveCommand) -> anyhow::Result<()> {
+    let config = load_config(cmd.config_overrides)?;
+    for session in &cmd.sessions {
+        let path = resolve_session_path(&config.codex_home, session).await?;
+        let archived = archive_rollout(&config.codex_home, &path).await?;
+        println!("Archived {}", archived.display());
+    }
+    Ok(())
+}
+
+pub async fn run_remove(cmd: RemoveCommand) -> anyhow::Result<()> {
+    let config = load_config(cmd.config_overrides)?;
+    for session in &cmd.sessions {
+        let path = resolve_session_path(&config.codex_home, session).await?;
+        delete_rollout(&config.codex_home, &path).await?;
+        println!("Deleted {}", path.display());
+    }
+    Ok(())
+}
+
+fn load_config(config_overrides: CliConfigOverrides) -> anyhow::Result<Config> {
+    let cli_overrides = config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    Ok(Config::load_with_cli_overrides(
+        cli_overrides,
+        ConfigOverrides::default(),
+    )?)
+}
+
+/// Returns the rollout file `session` refers to: the path of one, or the id
+/// of a session, in the sessions or archived sessions directory. Paths to
+/// any other file are rejected.
+async fn resolve_session_path(codex_home: &Path, session: &str) -> anyhow::Result<PathBuf> {
+    let path = PathBuf::from(session);
+    if path.is_file() {
+        if !is_recorded_rollout(codex_home, &path) {
+            anyhow::bail!(
+                "{session} is not a rollout file in {} or {}",
+                codex_home.join(SESSIONS_SUBDIR).display(),
+                codex_home.join(ARCHIVED_SESSIONS_SUBDIR).display()
+            );
+        }
+        return Ok(path);
+    }
+    if let Some(path) = find_conversation_path_by_id_str(codex_home, session).await? {
+        return Ok(path);
+    }
+    if let Ok(mut dir) = tokio::fs::read_dir(codex_home.join(ARCHIVED_SESSIONS_SUBDIR)).await {
+        while let Some(entry) = dir.next_entry().await? {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with("rollout-")
+                && name
+                    .trim_end_matches(".gz")
+                    .ends_with(&format!("{session}.jsonl"))
+            {
+                return Ok(entry.path());
+            }
+        }
+    }
+    anyhow::bail!("no session found for {session}")
+}
+
+fn format_for_extension(path: &Path) -> Option<ExportFormat> {
+    match path.extension()?.to_str()? {
+        "md" | "markdown" => Some(ExportFormat::Markdown),
+        "html" | "htm" => Some(ExportFormat::Html),
+        "json" => Some(ExportFormat::Json),
+        _ => None,
+    }
+}
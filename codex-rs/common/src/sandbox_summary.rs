@@ -9,6 +9,9 @@ pub fn summarize_sandbox_policy(sandbox_policy: &SandboxPolicy) -> String {
             network_access,
             exclude_tmpdir_env_var,
             exclude_slash_tmp,
+            readable_roots,
+            deny_read: _,
+            allowed_hosts,
         } => {
             let mut summary = "workspace-write".to_string();
 
@@ -29,6 +32,14 @@ pub fn summarize_sandbox_policy(sandbox_policy: &SandboxPolicy) -> String {
             summary.push_str(&format!(" [{}]", writable_entries.join(", ")));
             if *network_access {
                 summary.push_str(" (network access enabled)");
+            } else if !allowed_hosts.is_empty() {
+                summary.push_str(&format!(
+                    " (network allowed for {})",
+                    allowed_hosts.join(", ")
+                ));
+            }
+            if readable_roots.is_some() {
+                summary.push_str(" (reads restricted)");
             }
             summary
         }
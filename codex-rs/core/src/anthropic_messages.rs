@@ -0,0 +1,699 @@
+//! Implementation for the Anthropic Messages API:
+//! https://docs.anthropic.com/en/docs/build-with-claude/streaming
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use bytes::Bytes;
+use codex_protocol::config_types::ReasoningEffort as ReasoningEffortConfig;
+use codex_protocol::mcp_protocol::ConversationId;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ReasoningItemReasoningSummary;
+use codex_protocol::models::ResponseItem;
+use eventsource_stream::Eventsource;
+use futures::Stream;
+use futures::StreamExt;
+use futures::TryStreamExt;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json::json;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use tracing::debug;
+use tracing::trace;
+
+use crate::ModelProviderInfo;
+use crate::client_common::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::client_common::ResponseStream;
+use crate::client_common::thinking_budget_tokens;
+use crate::config::Config;
+use crate::error::CodexErr;
+use crate::error::Result;
+use crate::openai_tools::create_tools_json_for_anthropic_messages_api;
+use crate::protocol::TokenUsage;
+use crate::stream_recording::record_response;
+use crate::stream_recording::replay_next_response;
+use crate::util::backoff;
+use crate::util::split_base64_data_url;
+
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// `max_tokens` is required by the Messages API. This is used when
+/// `model_max_output_tokens` is not configured.
+const DEFAULT_MAX_TOKENS: u64 = 8_192;
+
+/// Smallest thinking budget accepted by the Messages API.
+const MIN_THINKING_BUDGET: u64 = 1_024;
+
+pub(crate) async fn stream_anthropic_messages(
+    prompt: &Prompt,
+    config: &Config,
+    effort: Option<ReasoningEffortConfig>,
+    client: &reqwest::Client,
+    provider: &ModelProviderInfo,
+    conversation_id: ConversationId,
+) -> Result<ResponseStream> {
+    let max_tokens = config.model_max_output_tokens.unwrap_or(DEFAULT_MAX_TOKENS);
+    // Extended thinking is opt-in through `model_supports_reasoning_summaries`
+    // and must leave room for the answer within `max_tokens`.
+    let thinking_budget = config
+        .model_family
+        .supports_reasoning_summaries
+        .then(|| thinking_budget_tokens(effort).min(max_tokens / 2))
+        .filter(|budget| *budget >= MIN_THINKING_BUDGET);
+
+    let mut payload = json!({
+        "model": config.model,
+        "max_tokens": max_tokens,
+        "system": prompt.get_full_instructions(&config.model_family),
+        "messages": create_messages(&prompt.get_formatted_input(), thinking_budget.is_some()),
+        "stream": true,
+    });
+    if let Some(obj) = payload.as_object_mut() {
+        let tools_json = create_tools_json_for_anthropic_messages_api(&prompt.tools)?;
+        if !tools_json.is_empty() {
+            obj.insert("tools".to_string(), json!(tools_json));
+        }
+        if let Some(budget) = thinking_budget {
+            obj.insert(
+                "thinking".to_string(),
+                json!({"type": "enabled", "budget_tokens": budget}),
+            );
+        }
+    }
+
+    debug!(
+        "POST to {}: {}",
+        provider.get_full_url(&None, &config.model),
+        serde_json::to_string_pretty(&payload).unwrap_or_default()
+    );
+
+    if let Some(recorded) = replay_next_response(conversation_id, &payload) {
+        let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+        tokio::spawn(process_anthropic_sse(
+            recorded?,
+            tx_event,
+            provider.stream_idle_timeout(),
+        ));
+        return Ok(ResponseStream { rx_event });
+    }
+
+    let mut attempt = 0;
+    let max_retries = provider.request_max_retries();
+    loop {
+        attempt += 1;
+
+        let mut req_builder = provider
+            .create_request_builder(client, &None, &config.model)
+            .await?;
+        if !provider.has_http_header("anthropic-version") {
+            req_builder = req_builder.header("anthropic-version", ANTHROPIC_VERSION);
+        }
+
+        let res = req_builder
+            .header(reqwest::header::ACCEPT, "text/event-stream")
+            .json(&payload)
+            .send()
+            .await;
+
+        match res {
+            Ok(resp) if resp.status().is_success() => {
+                let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+                let stream = record_response(
+                    conversation_id,
+                    &payload,
+                    resp.bytes_stream().map_err(CodexErr::Reqwest),
+                );
+                tokio::spawn(process_anthropic_sse(
+                    stream,
+                    tx_event,
+                    provider.stream_idle_timeout(),
+                ));
+                return Ok(ResponseStream { rx_event });
+            }
+            Ok(res) => {
+                let status = res.status();
+                // 529 is Anthropic's "overloaded" status and is covered by
+                // `is_server_error`.
+                if !(status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()) {
+                    let body = (res.text().await).unwrap_or_default();
+                    return Err(CodexErr::UnexpectedStatus(status, body));
+                }
+
+                if attempt > max_retries {
+                    return Err(CodexErr::RetryLimit(status));
+                }
+
+                let retry_after_secs = res
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|s| s.parse::<u64>().ok());
+
+                let delay = retry_after_secs
+                    .map(|s| Duration::from_millis(s * 1_000))
+                    .unwrap_or_else(|| backoff(attempt));
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => {
+                if attempt > max_retries {
+                    return Err(e.into());
+                }
+                let delay = backoff(attempt);
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+}
+
+/// Converts the conversation history into Messages API `messages`.
+/// Consecutive items of the same role are merged into a single message
+/// because the API expects user and assistant turns to alternate. Thinking
+/// blocks are only sent back when thinking is enabled for the request, as
+/// the API requires them to carry the signature it produced.
+fn create_messages(input: &[ResponseItem], include_thinking: bool) -> Vec<Value> {
+    let mut messages: Vec<Value> = Vec::new();
+    let mut push_block = |role: &str, block: Value| {
+        if let Some(last) = messages.last_mut()
+            && last["role"] == role
+            && let Some(content) = last["content"].as_array_mut()
+        {
+            content.push(block);
+            return;
+        }
+        messages.push(json!({"role": role, "content": [block]}));
+    };
+
+    for item in input {
+        match item {
+            ResponseItem::Message { role, content, .. } => {
+                let role = if role == "assistant" {
+                    "assistant"
+                } else {
+                    "user"
+                };
+                for block in content.iter().filter_map(content_block) {
+                    push_block(role, block);
+                }
+            }
+            ResponseItem::Reasoning {
+                summary,
+                encrypted_content: Some(signature),
+                ..
+            } if include_thinking => {
+                let thinking = summary
+                    .iter()
+                    .map(|ReasoningItemReasoningSummary::SummaryText { text }| text.as_str())
+                    .collect::<String>();
+                push_block(
+                    "assistant",
+                    json!({"type": "thinking", "thinking": thinking, "signature": signature}),
+                );
+            }
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                ..
+            } => {
+                let input = serde_json::from_str::<Value>(arguments)
+                    .ok()
+                    .filter(Value::is_object)
+                    .unwrap_or_else(|| json!({}));
+                push_block(
+                    "assistant",
+                    json!({"type": "tool_use", "id": call_id, "name": name, "input": input}),
+                );
+            }
+            ResponseItem::FunctionCallOutput { call_id, output } => {
+                push_block(
+                    "user",
+                    json!({
+                        "type": "tool_result",
+                        "tool_use_id": call_id,
+                        "content": output.content,
+                        "is_error": output.success == Some(false),
+                    }),
+                );
+            }
+            ResponseItem::Reasoning { .. }
+            | ResponseItem::LocalShellCall { .. }
+            | ResponseItem::CustomToolCall { .. }
+            | ResponseItem::CustomToolCallOutput { .. }
+            | ResponseItem::WebSearchCall { .. }
+            | ResponseItem::Other => {
+                // These items cannot be produced by this API; omit them.
+                continue;
+            }
+        }
+    }
+    messages
+}
+
+fn content_block(item: &ContentItem) -> Option<Value> {
+    match item {
+        // The API rejects empty text blocks.
+        ContentItem::InputText { text } | ContentItem::OutputText { text } => {
+            (!text.is_empty()).then(|| json!({"type": "text", "text": text}))
+        }
+        ContentItem::InputImage { image_url } => {
+            let source = match split_base64_data_url(image_url) {
+                Some((media_type, data)) => {
+                    json!({"type": "base64", "media_type": media_type, "data": data})
+                }
+                None => json!({"type": "url", "url": image_url}),
+            };
+            Some(json!({"type": "image", "source": source}))
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamEvent {
+    MessageStart {
+        message: MessageStart,
+    },
+    ContentBlockStart {
+        index: usize,
+        content_block: ContentBlock,
+    },
+    ContentBlockDelta {
+        index: usize,
+        delta: ContentBlockDelta,
+    },
+    ContentBlockStop {
+        index: usize,
+    },
+    MessageDelta {
+        #[serde(default)]
+        usage: Option<Usage>,
+    },
+    MessageStop,
+    Error {
+        error: ApiError,
+    },
+    /// `ping` and any event types added to the API later.
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageStart {
+    id: String,
+    #[serde(default)]
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentBlock {
+    Text {
+        #[serde(default)]
+        text: String,
+    },
+    Thinking {
+        #[serde(default)]
+        thinking: String,
+        #[serde(default)]
+        signature: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentBlockDelta {
+    TextDelta {
+        text: String,
+    },
+    ThinkingDelta {
+        thinking: String,
+    },
+    SignatureDelta {
+        signature: String,
+    },
+    InputJsonDelta {
+        partial_json: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Usage {
+    input_tokens: Option<u64>,
+    cache_creation_input_tokens: Option<u64>,
+    cache_read_input_tokens: Option<u64>,
+    output_tokens: Option<u64>,
+}
+
+impl Usage {
+    /// `message_delta` reports cumulative counts, so later values replace
+    /// earlier ones.
+    fn merge(&mut self, other: Usage) {
+        self.input_tokens = other.input_tokens.or(self.input_tokens);
+        self.cache_creation_input_tokens = other
+            .cache_creation_input_tokens
+            .or(self.cache_creation_input_tokens);
+        self.cache_read_input_tokens = other
+            .cache_read_input_tokens
+            .or(self.cache_read_input_tokens);
+        self.output_tokens = other.output_tokens.or(self.output_tokens);
+    }
+}
+
+impl From<Usage> for TokenUsage {
+    fn from(usage: Usage) -> Self {
+        // Unlike OpenAI, Anthropic does not count cached tokens as input.
+        let cached_input_tokens = usage.cache_read_input_tokens.unwrap_or(0);
+        let input_tokens = usage.input_tokens.unwrap_or(0)
+            + usage.cache_creation_input_tokens.unwrap_or(0)
+            + cached_input_tokens;
+        let output_tokens = usage.output_tokens.unwrap_or(0);
+        TokenUsage {
+            input_tokens,
+            cached_input_tokens,
+            output_tokens,
+            reasoning_output_tokens: 0,
+            total_tokens: input_tokens + output_tokens,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiError {
+    message: String,
+}
+
+/// A content block that is still being streamed.
+enum BlockState {
+    Text(String),
+    Thinking {
+        text: String,
+        signature: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: String,
+    },
+}
+
+impl BlockState {
+    fn into_item(self) -> Option<ResponseItem> {
+        match self {
+            BlockState::Text(text) if text.is_empty() => None,
+            BlockState::Text(text) => Some(ResponseItem::Message {
+                id: None,
+                role: "assistant".to_string(),
+                content: vec![ContentItem::OutputText { text }],
+            }),
+            // Claude returns summarized thinking, so it is surfaced as a
+            // reasoning summary. The signature is kept so that the block can
+            // be sent back on the next request.
+            BlockState::Thinking { text, signature } => Some(ResponseItem::Reasoning {
+                id: String::new(),
+                summary: vec![ReasoningItemReasoningSummary::SummaryText { text }],
+                content: None,
+                encrypted_content: (!signature.is_empty()).then_some(signature),
+            }),
+            BlockState::ToolUse { id, name, input } => Some(ResponseItem::FunctionCall {
+                id: None,
+                name,
+                arguments: if input.is_empty() {
+                    "{}".to_string()
+                } else {
+                    input
+                },
+                call_id: id,
+                thought_signature: None,
+            }),
+        }
+    }
+}
+
+/// SSE processor for the Messages streaming format. Every content block is
+/// forwarded as a [`ResponseEvent::OutputItemDone`] once it is complete, and
+/// `message_stop` maps to [`ResponseEvent::Completed`].
+async fn process_anthropic_sse<S>(
+    stream: S,
+    tx_event: mpsc::Sender<Result<ResponseEvent>>,
+    idle_timeout: Duration,
+) where
+    S: Stream<Item = Result<Bytes>> + Unpin,
+{
+    let mut stream = stream.eventsource();
+
+    let mut response_id = String::new();
+    let mut usage = Usage::default();
+    let mut blocks: HashMap<usize, BlockState> = HashMap::new();
+
+    loop {
+        let sse = match timeout(idle_timeout, stream.next()).await {
+            Ok(Some(Ok(ev))) => ev,
+            Ok(Some(Err(e))) => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(e.to_string(), None)))
+                    .await;
+                return;
+            }
+            Ok(None) => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(
+                        "stream closed before message_stop".into(),
+                        None,
+                    )))
+                    .await;
+                return;
+            }
+            Err(_) => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(
+                        "idle timeout waiting for SSE".into(),
+                        None,
+                    )))
+                    .await;
+                return;
+            }
+        };
+
+        let event: StreamEvent = match serde_json::from_str(&sse.data) {
+            Ok(event) => event,
+            Err(e) => {
+                debug!(
+                    "Failed to parse Anthropic SSE event: {e}, data: {}",
+                    &sse.data
+                );
+                continue;
+            }
+        };
+        trace!("anthropic_messages received SSE event: {event:?}");
+
+        match event {
+            StreamEvent::MessageStart { message } => {
+                response_id = message.id;
+                if let Some(message_usage) = message.usage {
+                    usage.merge(message_usage);
+                }
+            }
+            StreamEvent::ContentBlockStart {
+                index,
+                content_block,
+            } => {
+                let state = match content_block {
+                    ContentBlock::Text { text } => BlockState::Text(text),
+                    ContentBlock::Thinking {
+                        thinking,
+                        signature,
+                    } => BlockState::Thinking {
+                        text: thinking,
+                        signature,
+                    },
+                    ContentBlock::ToolUse { id, name } => BlockState::ToolUse {
+                        id,
+                        name,
+                        input: String::new(),
+                    },
+                    ContentBlock::Other => continue,
+                };
+                blocks.insert(index, state);
+            }
+            StreamEvent::ContentBlockDelta { index, delta } => {
+                let event = match (blocks.get_mut(&index), delta) {
+                    (
+                        Some(BlockState::Text(text)),
+                        ContentBlockDelta::TextDelta { text: delta },
+                    ) => {
+                        text.push_str(&delta);
+                        ResponseEvent::OutputTextDelta(delta)
+                    }
+                    (
+                        Some(BlockState::Thinking { text, .. }),
+                        ContentBlockDelta::ThinkingDelta { thinking },
+                    ) => {
+                        text.push_str(&thinking);
+                        ResponseEvent::ReasoningSummaryDelta(thinking)
+                    }
+                    (
+                        Some(BlockState::Thinking { signature, .. }),
+                        ContentBlockDelta::SignatureDelta { signature: delta },
+                    ) => {
+                        signature.push_str(&delta);
+                        continue;
+                    }
+                    (
+                        Some(BlockState::ToolUse { input, .. }),
+                        ContentBlockDelta::InputJsonDelta { partial_json },
+                    ) => {
+                        input.push_str(&partial_json);
+                        continue;
+                    }
+                    _ => continue,
+                };
+                if tx_event.send(Ok(event)).await.is_err() {
+                    return;
+                }
+            }
+            StreamEvent::ContentBlockStop { index } => {
+                if let Some(item) = blocks.remove(&index).and_then(BlockState::into_item)
+                    && tx_event
+                        .send(Ok(ResponseEvent::OutputItemDone(item)))
+                        .await
+                        .is_err()
+                {
+                    return;
+                }
+            }
+            StreamEvent::MessageDelta {
+                usage: Some(delta_usage),
+            } => usage.merge(delta_usage),
+            StreamEvent::MessageStop => {
+                let _ = tx_event
+                    .send(Ok(ResponseEvent::Completed {
+                        response_id,
+                        token_usage: Some(usage.into()),
+                    }))
+                    .await;
+                return;
+            }
+            StreamEvent::Error { error } => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(error.message, None)))
+                    .await;
+                return;
+            }
+            StreamEvent::MessageDelta { usage: None } | StreamEvent::Other => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn create_messages_merges_roles_and_maps_tool_calls() {
+        let input = vec![
+            ResponseItem::Message {
+                id: None,
+                role: "user".to_string(),
+                content: vec![
+                    ContentItem::InputText {
+                        text: "<environment_context/>".to_string(),
+                    },
+                    ContentItem::InputImage {
+                        image_url: "data:image/png;base64,AAAA".to_string(),
+                    },
+                ],
+            },
+            ResponseItem::Message {
+                id: None,
+                role: "user".to_string(),
+                content: vec![ContentItem::InputText {
+                    text: "list files".to_string(),
+                }],
+            },
+            ResponseItem::Reasoning {
+                id: String::new(),
+                summary: vec![ReasoningItemReasoningSummary::SummaryText {
+                    text: "Run ls.".to_string(),
+                }],
+                content: None,
+                encrypted_content: Some("sig".to_string()),
+            },
+            ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: r#"{"command":["ls"]}"#.to_string(),
+                call_id: "toolu_1".to_string(),
+                thought_signature: None,
+            },
+            ResponseItem::FunctionCallOutput {
+                call_id: "toolu_1".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "README.md".to_string(),
+                    success: Some(false),
+                },
+            },
+        ];
+
+        let expected_assistant_blocks = json!([
+            {"type": "thinking", "thinking": "Run ls.", "signature": "sig"},
+            {"type": "tool_use", "id": "toolu_1", "name": "shell", "input": {"command": ["ls"]}},
+        ]);
+        assert_eq!(
+            create_messages(&input, true),
+            vec![
+                json!({"role": "user", "content": [
+                    {"type": "text", "text": "<environment_context/>"},
+                    {"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "AAAA"}},
+                    {"type": "text", "text": "list files"},
+                ]}),
+                json!({"role": "assistant", "content": expected_assistant_blocks}),
+                json!({"role": "user", "content": [
+                    {"type": "tool_result", "tool_use_id": "toolu_1", "content": "README.md", "is_error": true},
+                ]}),
+            ]
+        );
+
+        // Without thinking enabled the reasoning is dropped.
+        assert_eq!(
+            create_messages(&input, false)[1]["content"],
+            json!([expected_assistant_blocks[1]])
+        );
+    }
+
+    #[test]
+    fn usage_counts_cached_tokens_as_input() {
+        let mut usage = Usage {
+            input_tokens: Some(10),
+            cache_creation_input_tokens: Some(5),
+            cache_read_input_tokens: Some(100),
+            output_tokens: Some(1),
+        };
+        usage.merge(Usage {
+            output_tokens: Some(42),
+            ..Default::default()
+        });
+        assert_eq!(
+            TokenUsage::from(usage),
+            TokenUsage {
+                input_tokens: 115,
+                cached_input_tokens: 100,
+                output_tokens: 42,
+                reasoning_output_tokens: 0,
+                total_tokens: 157,
+            }
+        );
+    }
+}
@@ -0,0 +1,521 @@
+//! Per-turn checkpoints of the files in the session's repository.
+//!
+//! Before every turn started by the user, the state of the git repository
+//! containing `cwd` is written to a shadow git repository under
+//! `$CODEX_HOME/checkpoints`, so that edits made by `apply_patch` and by shell
+//! commands alike can be rolled back afterwards. The shadow repository never
+//! touches the user's own `.git`: it has an index file per conversation, and
+//! each checkpoint is a tree referenced by
+//! `refs/checkpoints/<conversation id>/<turn>`, where `turn` is the 0-based
+//! index of the user message that started the turn.
+//!
+//! Files ignored by the repository, through its `.gitignore` files, its
+//! `info/exclude` or the user's global excludes file, are neither recorded
+//! nor restored. The last two are read from the shadow repository's git dir
+//! and config by default, so they are copied to a per-conversation excludes
+//! file before every checkpoint and restore.
+//!
+//! Checkpoints are opt-in (`checkpoints = true`) because recording one stages
+//! the whole work tree. Only the latest [`MAX_CHECKPOINTS_PER_CONVERSATION`]
+//! checkpoints of a conversation are kept, and the checkpoints of
+//! conversations that have not recorded one in [`STALE_CONVERSATION_AGE`] are
+//! dropped, after which the shadow repository is garbage collected.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use codex_protocol::mcp_protocol::ConversationId;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::git_info::run_git;
+use crate::git_info::run_git_with_env;
+
+/// Prefix of the refs that point at checkpoints.
+const REF_PREFIX: &str = "refs/checkpoints";
+
+/// Number of checkpoints kept per conversation; older ones are dropped.
+const MAX_CHECKPOINTS_PER_CONVERSATION: usize = 100;
+
+/// Conversations that have not recorded a checkpoint for this long lose
+/// their checkpoints.
+const STALE_CONVERSATION_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Extension of the per-conversation index files.
+const INDEX_FILE_EXTENSION: &str = "index";
+
+/// Extension of the per-conversation excludes files.
+const EXCLUDES_FILE_EXTENSION: &str = "exclude";
+
+pub(crate) struct Checkpoints {
+    git_dir: PathBuf,
+    index_file: PathBuf,
+    /// Ignore rules of the user's repository that git would otherwise not
+    /// apply to the shadow repository.
+    excludes_file: PathBuf,
+    work_tree: PathBuf,
+    conversation_id: ConversationId,
+    /// Number of checkpoints kept; [`MAX_CHECKPOINTS_PER_CONVERSATION`]
+    /// outside tests.
+    max_checkpoints: usize,
+    /// Turn the next checkpoint is recorded for. The lock also keeps git
+    /// commands on this conversation's index from running concurrently.
+    next_turn: Mutex<usize>,
+}
+
+impl Checkpoints {
+    /// Opens the checkpoints of a conversation working in `cwd`. Returns
+    /// `None` if `cwd` is not inside a git repository.
+    ///
+    /// `next_turn` is the number of user turns already in the conversation.
+    /// A conversation forked from `base` inherits the base's checkpoints of
+    /// those turns.
+    pub(crate) async fn open(
+        codex_home: &Path,
+        conversation_id: ConversationId,
+        cwd: &Path,
+        next_turn: usize,
+        base: Option<ConversationId>,
+    ) -> Result<Option<Self>, String> {
+        let Some(work_tree) = crate::git_info::get_git_repo_root(cwd) else {
+            return Ok(None);
+        };
+        let dir = codex_home.join("checkpoints");
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("failed to create checkpoints directory: {e}"))?;
+        let checkpoints = Self {
+            git_dir: dir.join("shadow.git"),
+            index_file: dir.join(format!("{conversation_id}.{INDEX_FILE_EXTENSION}")),
+            excludes_file: dir.join(format!("{conversation_id}.{EXCLUDES_FILE_EXTENSION}")),
+            work_tree,
+            conversation_id,
+            max_checkpoints: MAX_CHECKPOINTS_PER_CONVERSATION,
+            next_turn: Mutex::new(next_turn),
+        };
+        if !checkpoints.git_dir.join("HEAD").exists() {
+            let git_dir = checkpoints.git_dir.to_string_lossy();
+            run_git(&["init", "--quiet", "--bare", &git_dir], &dir).await?;
+        }
+
+        if let Some(base) = base
+            && base != conversation_id
+        {
+            for (turn, tree) in checkpoints.list(&base).await? {
+                if turn < next_turn {
+                    let name = checkpoints.ref_name(turn);
+                    checkpoints.git(&["update-ref", &name, &tree]).await?;
+                }
+            }
+        }
+
+        let git_dir = checkpoints.git_dir.clone();
+        tokio::spawn(async move {
+            if let Err(e) = drop_stale_conversations(&dir, &git_dir, conversation_id).await {
+                warn!("failed to clean up checkpoints: {e}");
+            }
+        });
+        Ok(Some(checkpoints))
+    }
+
+    /// Records the current state of the repository as the checkpoint of the
+    /// next turn and returns that turn.
+    pub(crate) async fn create(&self) -> Result<usize, String> {
+        let mut next_turn = self.next_turn.lock().await;
+        let turn = *next_turn;
+        self.write_excludes().await?;
+        self.git(&["add", "--all"]).await?;
+        let tree = self.git(&["write-tree"]).await?;
+        self.git(&["update-ref", &self.ref_name(turn), &tree])
+            .await?;
+        *next_turn += 1;
+
+        let checkpoints = self.list(&self.conversation_id).await?;
+        let excess = checkpoints.len().saturating_sub(self.max_checkpoints);
+        for (old, _) in &checkpoints[..excess] {
+            self.git(&["update-ref", "-d", &self.ref_name(*old)])
+                .await?;
+        }
+        Ok(turn)
+    }
+
+    /// Restores the files to the checkpoint recorded before `turn`, or before
+    /// the latest turn if `turn` is `None`, and forgets that checkpoint and
+    /// all later ones. Returns the turn that was restored.
+    pub(crate) async fn restore(&self, turn: Option<usize>) -> Result<usize, String> {
+        let _guard = self.next_turn.lock().await;
+        let checkpoints = self.list(&self.conversation_id).await?;
+        let (turn, tree) = match turn {
+            Some(turn) => checkpoints
+                .iter()
+                .find(|(t, _)| *t == turn)
+                .ok_or_else(|| format!("no checkpoint was recorded before turn {}", turn + 1))?,
+            None => checkpoints
+                .last()
+                .ok_or_else(|| "there is nothing to undo".to_string())?,
+        };
+
+        // Stage everything first so that files created since the checkpoint
+        // are known to the index and get removed.
+        self.write_excludes().await?;
+        self.git(&["add", "--all"]).await?;
+        self.git(&["read-tree", "-u", "--reset", tree]).await?;
+
+        for (later, _) in checkpoints.iter().filter(|(t, _)| t >= turn) {
+            self.git(&["update-ref", "-d", &self.ref_name(*later)])
+                .await?;
+        }
+        Ok(*turn)
+    }
+
+    /// Returns the checkpoints of `conversation_id` ordered by turn.
+    async fn list(&self, conversation_id: &ConversationId) -> Result<Vec<(usize, String)>, String> {
+        let prefix = format!("{REF_PREFIX}/{conversation_id}/");
+        let output = self
+            .git(&["for-each-ref", "--format=%(refname) %(objectname)", &prefix])
+            .await?;
+        let mut checkpoints: Vec<(usize, String)> = output
+            .lines()
+            .filter_map(|line| {
+                let (name, tree) = line.split_once(' ')?;
+                let turn = name.strip_prefix(&prefix)?.parse().ok()?;
+                Some((turn, tree.to_string()))
+            })
+            .collect();
+        checkpoints.sort_unstable();
+        Ok(checkpoints)
+    }
+
+    /// Copies the user repository's `info/exclude` and the user's global
+    /// excludes file to this conversation's excludes file.
+    async fn write_excludes(&self) -> Result<(), String> {
+        let repo_excludes = run_git(
+            &["rev-parse", "--git-path", "info/exclude"],
+            &self.work_tree,
+        )
+        .await?;
+        // Fails when `core.excludesFile` is not set.
+        let global_excludes = run_git(
+            &["config", "--path", "--get", "core.excludesFile"],
+            &self.work_tree,
+        )
+        .await
+        .ok()
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .or_else(default_global_excludes_file);
+
+        let mut excludes = String::new();
+        for path in [Some(self.work_tree.join(repo_excludes)), global_excludes]
+            .into_iter()
+            .flatten()
+        {
+            if let Ok(rules) = tokio::fs::read_to_string(&path).await {
+                excludes.push_str(&rules);
+                excludes.push('\n');
+            }
+        }
+        tokio::fs::write(&self.excludes_file, excludes)
+            .await
+            .map_err(|e| format!("failed to write {}: {e}", self.excludes_file.display()))
+    }
+
+    fn ref_name(&self, turn: usize) -> String {
+        format!("{REF_PREFIX}/{}/{turn}", self.conversation_id)
+    }
+
+    /// Runs git against the shadow repository with this conversation's index
+    /// and the user's repository as the work tree.
+    async fn git(&self, args: &[&str]) -> Result<String, String> {
+        let git_dir = format!("--git-dir={}", self.git_dir.to_string_lossy());
+        let work_tree = format!("--work-tree={}", self.work_tree.to_string_lossy());
+        let excludes = format!("core.excludesFile={}", self.excludes_file.to_string_lossy());
+        let args: Vec<&str> = [
+            git_dir.as_str(),
+            work_tree.as_str(),
+            "-c",
+            excludes.as_str(),
+        ]
+        .into_iter()
+        .chain(args.iter().copied())
+        .collect();
+        run_git_with_env(
+            &args,
+            &self.work_tree,
+            &[("GIT_INDEX_FILE", &self.index_file)],
+        )
+        .await
+    }
+}
+
+/// Where git looks for the global excludes file when `core.excludesFile` is
+/// not set.
+fn default_global_excludes_file() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| Some(PathBuf::from(std::env::var_os("HOME")?).join(".config")))?;
+    Some(config_home.join("git").join("ignore"))
+}
+
+/// Drops the checkpoints of the conversations, other than `current`, that
+/// have not recorded one in [`STALE_CONVERSATION_AGE`], then lets git collect
+/// the objects only they referenced.
+async fn drop_stale_conversations(
+    dir: &Path,
+    git_dir: &Path,
+    current: ConversationId,
+) -> Result<(), String> {
+    let now = SystemTime::now();
+    let mut stale = Vec::new();
+    let mut entries = tokio::fs::read_dir(dir)
+        .await
+        .map_err(|e| format!("failed to read {}: {e}", dir.display()))?;
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        let Some(id) = path
+            .extension()
+            .is_some_and(|ext| ext == INDEX_FILE_EXTENSION)
+            .then(|| path.file_stem())
+            .flatten()
+            .and_then(|stem| stem.to_str())
+        else {
+            continue;
+        };
+        if id == current.to_string() {
+            continue;
+        }
+        let idle = entry
+            .metadata()
+            .await
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok());
+        if idle.is_some_and(|idle| idle > STALE_CONVERSATION_AGE) {
+            stale.push((id.to_string(), path.clone()));
+        }
+    }
+    if stale.is_empty() {
+        return Ok(());
+    }
+
+    let git_dir_arg = format!("--git-dir={}", git_dir.to_string_lossy());
+    for (id, index_file) in stale {
+        let prefix = format!("{REF_PREFIX}/{id}/");
+        let refs = run_git(
+            &[&git_dir_arg, "for-each-ref", "--format=%(refname)", &prefix],
+            dir,
+        )
+        .await?;
+        for name in refs.lines() {
+            run_git(&[&git_dir_arg, "update-ref", "-d", name], dir).await?;
+        }
+        let _ = tokio::fs::remove_file(&index_file).await;
+        let _ = tokio::fs::remove_file(index_file.with_extension(EXCLUDES_FILE_EXTENSION)).await;
+    }
+    run_git(&[&git_dir_arg, "gc", "--auto", "--quiet"], dir).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use tempfile::TempDir;
+
+    async fn create_test_git_repo(temp_dir: &TempDir) -> PathBuf {
+        let repo = temp_dir.path().join("repo");
+        fs::create_dir_all(&repo).expect("create repo");
+        run_git(&["init", "--quiet"], &repo)
+            .await
+            .expect("git init");
+        fs::write(repo.join(".gitignore"), "ignored.txt\n").expect("write file");
+        fs::write(repo.join("tracked.txt"), "original\n").expect("write file");
+        repo
+    }
+
+    async fn open(temp_dir: &TempDir, repo: &Path, id: ConversationId) -> Checkpoints {
+        Checkpoints::open(&temp_dir.path().join("home"), id, repo, 0, None)
+            .await
+            .expect("open checkpoints")
+            .expect("repo is a git repository")
+    }
+
+    #[tokio::test]
+    async fn undo_restores_edited_created_and_deleted_files() {
+        let temp_dir = TempDir::new().expect("tempdir");
+        let repo = create_test_git_repo(&temp_dir).await;
+        fs::write(repo.join("deleted.txt"), "keep me\n").expect("write file");
+        let checkpoints = open(&temp_dir, &repo, ConversationId::new()).await;
+
+        assert_eq!(checkpoints.create().await, Ok(0));
+        fs::write(repo.join("tracked.txt"), "changed\n").expect("write file");
+        fs::write(repo.join("created.txt"), "new\n").expect("write file");
+        fs::remove_file(repo.join("deleted.txt")).expect("remove file");
+        fs::write(repo.join("ignored.txt"), "ignored\n").expect("write file");
+
+        assert_eq!(checkpoints.restore(None).await, Ok(0));
+
+        assert_eq!(
+            fs::read_to_string(repo.join("tracked.txt")).ok(),
+            Some("original\n".to_string())
+        );
+        assert_eq!(
+            fs::read_to_string(repo.join("deleted.txt")).ok(),
+            Some("keep me\n".to_string())
+        );
+        assert!(!repo.join("created.txt").exists());
+        assert!(repo.join("ignored.txt").exists());
+        assert_eq!(
+            checkpoints.restore(None).await,
+            Err("there is nothing to undo".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn files_excluded_by_the_repository_are_kept() {
+        let temp_dir = TempDir::new().expect("tempdir");
+        let repo = create_test_git_repo(&temp_dir).await;
+        fs::write(
+            repo.join(".git").join("info").join("exclude"),
+            "local.txt\n",
+        )
+        .expect("write exclude");
+        let checkpoints = open(&temp_dir, &repo, ConversationId::new()).await;
+
+        checkpoints.create().await.expect("create checkpoint");
+        fs::write(repo.join("local.txt"), "not tracked\n").expect("write file");
+        fs::write(repo.join("created.txt"), "new\n").expect("write file");
+
+        assert_eq!(checkpoints.restore(None).await, Ok(0));
+        assert!(repo.join("local.txt").exists());
+        assert!(!repo.join("created.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn restoring_a_turn_drops_later_checkpoints() {
+        let temp_dir = TempDir::new().expect("tempdir");
+        let repo = create_test_git_repo(&temp_dir).await;
+        let checkpoints = open(&temp_dir, &repo, ConversationId::new()).await;
+
+        for content in ["first\n", "second\n", "third\n"] {
+            checkpoints.create().await.expect("create checkpoint");
+            fs::write(repo.join("tracked.txt"), content).expect("write file");
+        }
+
+        assert_eq!(checkpoints.restore(Some(1)).await, Ok(1));
+        assert_eq!(
+            fs::read_to_string(repo.join("tracked.txt")).ok(),
+            Some("first\n".to_string())
+        );
+        assert!(checkpoints.restore(Some(2)).await.is_err());
+        assert_eq!(checkpoints.restore(None).await, Ok(0));
+        assert_eq!(
+            fs::read_to_string(repo.join("tracked.txt")).ok(),
+            Some("original\n".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn forked_conversation_inherits_earlier_checkpoints() {
+        let temp_dir = TempDir::new().expect("tempdir");
+        let repo = create_test_git_repo(&temp_dir).await;
+        let base_id = ConversationId::new();
+        let base = open(&temp_dir, &repo, base_id).await;
+        for content in ["first\n", "second\n"] {
+            base.create().await.expect("create checkpoint");
+            fs::write(repo.join("tracked.txt"), content).expect("write file");
+        }
+
+        let fork = Checkpoints::open(
+            &temp_dir.path().join("home"),
+            ConversationId::new(),
+            &repo,
+            1,
+            Some(base_id),
+        )
+        .await
+        .expect("open checkpoints")
+        .expect("repo is a git repository");
+
+        assert!(fork.restore(Some(1)).await.is_err());
+        assert_eq!(fork.create().await, Ok(1));
+        assert_eq!(fork.restore(Some(0)).await, Ok(0));
+        assert_eq!(
+            fs::read_to_string(repo.join("tracked.txt")).ok(),
+            Some("original\n".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn only_the_latest_checkpoints_are_kept() {
+        let temp_dir = TempDir::new().expect("tempdir");
+        let repo = create_test_git_repo(&temp_dir).await;
+        let mut checkpoints = open(&temp_dir, &repo, ConversationId::new()).await;
+        checkpoints.max_checkpoints = 3;
+
+        for _ in 0..5 {
+            checkpoints.create().await.expect("create checkpoint");
+        }
+        let turns: Vec<usize> = checkpoints
+            .list(&checkpoints.conversation_id)
+            .await
+            .expect("list checkpoints")
+            .into_iter()
+            .map(|(turn, _)| turn)
+            .collect();
+        assert_eq!(turns, vec![2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn stale_conversations_are_dropped() {
+        let temp_dir = TempDir::new().expect("tempdir");
+        let repo = create_test_git_repo(&temp_dir).await;
+        let stale_id = ConversationId::new();
+        let stale = open(&temp_dir, &repo, stale_id).await;
+        stale.create().await.expect("create checkpoint");
+        let modified = SystemTime::now() - STALE_CONVERSATION_AGE - Duration::from_secs(60);
+        fs::File::options()
+            .write(true)
+            .open(&stale.index_file)
+            .and_then(|file| file.set_modified(modified))
+            .expect("age index file");
+
+        let current = open(&temp_dir, &repo, ConversationId::new()).await;
+        current.create().await.expect("create checkpoint");
+        drop_stale_conversations(
+            &temp_dir.path().join("home").join("checkpoints"),
+            &current.git_dir,
+            current.conversation_id,
+        )
+        .await
+        .expect("drop stale conversations");
+
+        assert_eq!(current.list(&stale_id).await, Ok(Vec::new()));
+        assert!(!stale.index_file.exists());
+        assert_eq!(
+            current
+                .list(&current.conversation_id)
+                .await
+                .map(|c| c.len()),
+            Ok(1)
+        );
+    }
+
+    #[tokio::test]
+    async fn no_checkpoints_outside_git_repositories() {
+        let temp_dir = TempDir::new().expect("tempdir");
+        let checkpoints = Checkpoints::open(
+            &temp_dir.path().join("home"),
+            ConversationId::new(),
+            temp_dir.path(),
+            0,
+            None,
+        )
+        .await
+        .expect("open checkpoints");
+        assert!(checkpoints.is_none());
+    }
+}
@@ -21,6 +21,7 @@ use tracing::debug;
 use tracing::trace;
 use tracing::warn;
 
+use crate::anthropic_messages::stream_anthropic_messages;
 use crate::chat_completions::AggregateStreamExt;
 use crate::chat_completions::stream_chat_completions;
 use crate::client_common::Prompt;
@@ -35,12 +36,15 @@ use crate::error::CodexErr;
 use crate::error::Result;
 use crate::error::UsageLimitReachedError;
 use crate::flags::CODEX_RS_SSE_FIXTURE;
+use crate::gemini::stream_gemini;
 use crate::model_family::ModelFamily;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::WireApi;
 use crate::openai_model_info::get_model_info;
 use crate::openai_tools::create_tools_json_for_responses_api;
 use crate::protocol::TokenUsage;
+use crate::stream_recording::record_response;
+use crate::stream_recording::replay_next_response;
 use crate::token_data::PlanType;
 use crate::util::backoff;
 use codex_protocol::config_types::ReasoningEffort as ReasoningEffortConfig;
@@ -110,8 +114,8 @@ impl ModelClient {
         })
     }
 
-    /// Dispatches to either the Responses or Chat implementation depending on
-    /// the provider config.  Public callers always invoke `stream()` – the
+    /// Dispatches to the Responses, Chat, Anthropic or Gemini implementation
+    /// depending on the provider config.  Public callers always invoke `stream()` – the
     /// specialised helpers are private to avoid accidental misuse.
     pub async fn stream(&self, prompt: &Prompt) -> Result<ResponseStream> {
         match self.provider.wire_api {
@@ -123,6 +127,7 @@ impl ModelClient {
                     &self.config.model_family,
                     &self.client,
                     &self.provider,
+                    self.conversation_id,
                 )
                 .await?;
 
@@ -151,6 +156,28 @@ impl ModelClient {
 
                 Ok(ResponseStream { rx_event: rx })
             }
+            WireApi::Anthropic => {
+                stream_anthropic_messages(
+                    prompt,
+                    &self.config,
+                    self.effort,
+                    &self.client,
+                    &self.provider,
+                    self.conversation_id,
+                )
+                .await
+            }
+            WireApi::Gemini => {
+                stream_gemini(
+                    prompt,
+                    &self.config,
+                    self.effort,
+                    &self.client,
+                    &self.provider,
+                    self.conversation_id,
+                )
+                .await
+            }
         }
     }
 
@@ -223,6 +250,16 @@ impl ModelClient {
         }
         let payload_body = serde_json::to_string(&payload_json)?;
 
+        if let Some(recorded) = replay_next_response(self.conversation_id, &payload_json) {
+            let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+            tokio::spawn(process_sse(
+                recorded?,
+                tx_event,
+                self.provider.stream_idle_timeout(),
+            ));
+            return Ok(ResponseStream { rx_event });
+        }
+
         let mut attempt = 0;
         let max_retries = self.provider.request_max_retries();
 
@@ -234,13 +271,13 @@ impl ModelClient {
 
             trace!(
                 "POST to {}: {}",
-                self.provider.get_full_url(&auth),
+                self.provider.get_full_url(&auth, &self.config.model),
                 payload_body.as_str()
             );
 
             let mut req_builder = self
                 .provider
-                .create_request_builder(&self.client, &auth)
+                .create_request_builder(&self.client, &auth, &self.config.model)
                 .await?;
 
             req_builder = req_builder
@@ -275,7 +312,11 @@ impl ModelClient {
                     let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
 
                     // spawn task to process SSE
-                    let stream = resp.bytes_stream().map_err(CodexErr::Reqwest);
+                    let stream = record_response(
+                        self.conversation_id,
+                        &payload_json,
+                        resp.bytes_stream().map_err(CodexErr::Reqwest),
+                    );
                     tokio::spawn(process_sse(
                         stream,
                         tx_event,
@@ -389,6 +430,11 @@ impl ModelClient {
     pub fn get_auth_manager(&self) -> Option<Arc<AuthManager>> {
         self.auth_manager.clone()
     }
+
+    /// Returns the configuration the client was created with.
+    pub(crate) fn get_config(&self) -> Arc<Config> {
+        self.config.clone()
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
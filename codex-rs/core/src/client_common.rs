@@ -151,6 +151,17 @@ pub(crate) fn create_reasoning_param_for_request(
     })
 }
 
+/// Thinking budget, in tokens, for APIs that take a budget instead of a
+/// reasoning effort (Anthropic Messages and Gemini).
+pub(crate) fn thinking_budget_tokens(effort: Option<ReasoningEffortConfig>) -> u64 {
+    match effort.unwrap_or_default() {
+        ReasoningEffortConfig::Minimal => 1_024,
+        ReasoningEffortConfig::Low => 4_096,
+        ReasoningEffortConfig::Medium => 8_192,
+        ReasoningEffortConfig::High => 16_384,
+    }
+}
+
 pub(crate) fn create_text_param_for_request(
     verbosity: Option<VerbosityConfig>,
 ) -> Option<TextControls> {
@@ -1,6 +1,8 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -43,12 +45,14 @@ use crate::apply_patch::ApplyPatchExec;
 use crate::apply_patch::CODEX_APPLY_PATCH_ARG1;
 use crate::apply_patch::InternalApplyPatchInvocation;
 use crate::apply_patch::convert_apply_patch_to_protocol;
+use crate::checkpoint::Checkpoints;
 use crate::client::ModelClient;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::config::Config;
 use crate::config_types::ShellEnvironmentPolicy;
 use crate::conversation_history::ConversationHistory;
+use crate::delegate::handle_delegate_tool_call;
 use crate::environment_context::EnvironmentContext;
 use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
@@ -56,6 +60,7 @@ use crate::error::SandboxErr;
 use crate::error::get_error_message_ui;
 use crate::exec::ExecParams;
 use crate::exec::ExecToolCallOutput;
+use crate::exec::PtySandbox;
 use crate::exec::SandboxType;
 use crate::exec::StdoutStream;
 use crate::exec::StreamOutput;
@@ -66,7 +71,18 @@ use crate::exec_command::ExecSessionManager;
 use crate::exec_command::WRITE_STDIN_TOOL_NAME;
 use crate::exec_command::WriteStdinParams;
 use crate::exec_env::create_env;
+use crate::exec_policy::ExecPolicy;
+use crate::git_worktree::apply_worktree_to_config;
+use crate::git_worktree::create_worktree;
+use crate::git_worktree::resolve_worktree;
 use crate::mcp_connection_manager::McpConnectionManager;
+use crate::mcp_resources::LIST_MCP_RESOURCES_TOOL;
+use crate::mcp_resources::LIST_MCP_RESOURCES_TOOL_NAME;
+use crate::mcp_resources::READ_MCP_RESOURCE_TOOL;
+use crate::mcp_resources::READ_MCP_RESOURCE_TOOL_NAME;
+use crate::mcp_resources::handle_list_mcp_resources;
+use crate::mcp_resources::handle_read_mcp_resource;
+use crate::mcp_resources::resolve_resource_mentions;
 use crate::mcp_tool_call::handle_mcp_tool_call;
 use crate::model_family::find_family_for_model;
 use crate::openai_model_info::get_model_info;
@@ -84,18 +100,27 @@ use crate::protocol::AgentReasoningSectionBreakEvent;
 use crate::protocol::ApplyPatchApprovalRequestEvent;
 use crate::protocol::AskForApproval;
 use crate::protocol::BackgroundEventEvent;
+use crate::protocol::CheckpointRestoredEvent;
 use crate::protocol::ErrorEvent;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecApprovalRequestEvent;
 use crate::protocol::ExecCommandBeginEvent;
 use crate::protocol::ExecCommandEndEvent;
+use crate::protocol::ExitedReviewModeEvent;
 use crate::protocol::FileChange;
+use crate::protocol::GetMcpPromptResponseEvent;
 use crate::protocol::InputItem;
+use crate::protocol::InputMessageKind;
+use crate::protocol::InputQueueUpdatedEvent;
 use crate::protocol::ListCustomPromptsResponseEvent;
+use crate::protocol::McpInvocation;
+use crate::protocol::McpListResourcesResponseEvent;
+use crate::protocol::McpToolCallApprovalRequestEvent;
 use crate::protocol::Op;
 use crate::protocol::PatchApplyBeginEvent;
 use crate::protocol::PatchApplyEndEvent;
+use crate::protocol::QueuedInput;
 use crate::protocol::ReviewDecision;
 use crate::protocol::ReviewOutputEvent;
 use crate::protocol::SandboxPolicy;
@@ -107,6 +132,9 @@ use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
 use crate::protocol::TurnDiffEvent;
 use crate::protocol::WebSearchBeginEvent;
+use crate::protocol::WorktreeInfo;
+use crate::protocol::WorktreeResolution;
+use crate::protocol::WorktreeResolvedEvent;
 use crate::rollout::RolloutRecorder;
 use crate::rollout::RolloutRecorderParams;
 use crate::safety::SafetyCheck;
@@ -117,6 +145,7 @@ use crate::turn_diff_tracker::TurnDiffTracker;
 use crate::unified_exec::UnifiedExecSessionManager;
 use crate::user_instructions::UserInstructions;
 use crate::user_notification::UserNotification;
+use crate::user_notification::UserNotifier;
 use crate::util::backoff;
 use codex_protocol::config_types::ReasoningEffort as ReasoningEffortConfig;
 use codex_protocol::config_types::ReasoningSummary as ReasoningSummaryConfig;
@@ -184,6 +213,20 @@ impl Codex {
         let (tx_sub, rx_sub) = async_channel::bounded(SUBMISSION_CHANNEL_CAPACITY);
         let (tx_event, rx_event) = async_channel::unbounded();
 
+        let mut config = config;
+        let worktree = if config.worktree {
+            let worktree = create_worktree(&config.cwd, &config.codex_home)
+                .await
+                .map_err(|e| {
+                    error!("Failed to create git worktree: {e}");
+                    CodexErr::Worktree(e)
+                })?;
+            apply_worktree_to_config(&mut config, &worktree);
+            Some(worktree)
+        } else {
+            None
+        };
+
         let user_instructions = get_user_instructions(&config).await;
 
         let config = Arc::new(config);
@@ -199,6 +242,7 @@ impl Codex {
             sandbox_policy: config.sandbox_policy.clone(),
             notify: config.notify.clone(),
             cwd: config.cwd.clone(),
+            worktree,
         };
 
         // Generate a unique ID for the lifetime of this Codex session.
@@ -265,17 +309,42 @@ impl Codex {
 #[derive(Default)]
 struct State {
     approved_commands: HashSet<Vec<String>>,
+    /// (server, tool) pairs the user approved for the session.
+    approved_mcp_tools: HashSet<(String, String)>,
     current_task: Option<AgentTask>,
     pending_approvals: HashMap<String, oneshot::Sender<ReviewDecision>>,
-    pending_input: Vec<ResponseInputItem>,
+    pending_input: Vec<PendingInput>,
+    queued_input: VecDeque<QueuedTurn>,
     history: ConversationHistory,
     token_info: Option<TokenUsageInfo>,
     next_internal_sub_id: u64,
+    /// Set while the worktree is merged or discarded. No task may start
+    /// meanwhile, since it would work in a worktree that is going away.
+    resolving_worktree: bool,
+    /// Set once the worktree was merged or discarded and removed. `cwd` lay
+    /// inside it, so no task may start afterwards.
+    worktree_removed: bool,
+}
+
+/// Input waiting to be added to the current turn.
+struct PendingInput {
+    items: Vec<InputItem>,
+    /// Whether the user sent the input to steer the turn, as opposed to a tool
+    /// attaching it.
+    steering: bool,
+}
+
+/// Input queued by the user to run as its own task.
+struct QueuedTurn {
+    sub_id: String,
+    items: Vec<InputItem>,
+    turn_context: Arc<TurnContext>,
 }
 
 /// Context for an initialized model agent
 ///
 /// A session has at most 1 running task at a time, and can be interrupted by user input.
+/// Input queued while a task is running starts the next task once it completes.
 pub(crate) struct Session {
     conversation_id: ConversationId,
     tx_event: Sender<Event>,
@@ -285,9 +354,8 @@ pub(crate) struct Session {
     session_manager: ExecSessionManager,
     unified_exec_manager: UnifiedExecSessionManager,
 
-    /// External notifier command (will be passed as args to exec()). When
-    /// `None` this feature is disabled.
-    notify: Option<Vec<String>>,
+    /// Delivers user notifications to the `notify` command and webhook.
+    notifier: UserNotifier,
 
     /// Optional rollout recorder for persisting the conversation transcript so
     /// sessions can be replayed or inspected later.
@@ -296,6 +364,17 @@ pub(crate) struct Session {
     codex_linux_sandbox_exe: Option<PathBuf>,
     user_shell: shell::Shell,
     show_raw_agent_reasoning: bool,
+
+    /// Policies from `$CODEX_HOME` and the project that declare which
+    /// commands may run without asking the user.
+    exec_policy: Option<ExecPolicy>,
+
+    /// Git worktree the session works in until it is resolved.
+    worktree: Mutex<Option<WorktreeInfo>>,
+
+    /// Checkpoints of the repository's files taken before each user turn.
+    /// `None` if disabled or if `cwd` is not inside a git repository.
+    checkpoints: Option<Checkpoints>,
 }
 
 /// The context needed for a single turn of the conversation.
@@ -358,6 +437,9 @@ struct ConfigureSession {
     /// `ConfigureSession` operation so that the business-logic layer can
     /// operate deterministically.
     cwd: PathBuf,
+
+    /// Git worktree created for the session, if any. `cwd` lies inside it.
+    worktree: Option<WorktreeInfo>,
 }
 
 impl Session {
@@ -379,6 +461,7 @@ impl Session {
             sandbox_policy,
             notify,
             cwd,
+            worktree,
         } = configure_session;
         debug!("Configuring session: model={model}; provider={provider:?}");
         if !cwd.is_absolute() {
@@ -402,27 +485,84 @@ impl Session {
         // Error messages to dispatch after SessionConfigured is sent.
         let mut post_session_configured_error_events = Vec::<Event>::new();
 
+        // Checkpoints are numbered by the user messages shown in the
+        // transcript, so count those already in the history. A forked
+        // conversation inherits its base's checkpoints.
+        let initial_messages = initial_history.get_event_msgs();
+        let prior_user_turns = initial_messages.as_ref().map_or(0, |msgs| {
+            msgs.iter()
+                .filter(|msg| match msg {
+                    EventMsg::UserMessage(event) => {
+                        matches!(event.kind, None | Some(InputMessageKind::Plain))
+                            && !event.message.trim().is_empty()
+                    }
+                    _ => false,
+                })
+                .count()
+        });
+        let forked_from = match &initial_history {
+            InitialHistory::Forked(items) => items.iter().find_map(|item| match item {
+                RolloutItem::SessionMeta(meta_line) => Some(meta_line.meta.id),
+                _ => None,
+            }),
+            InitialHistory::New | InitialHistory::Resumed(_) => None,
+        };
+
         // Kick off independent async setup tasks in parallel to reduce startup latency.
         //
         // - initialize RolloutRecorder with new or resumed session info
         // - spin up MCP connection manager
         // - perform default shell discovery
         // - load history metadata
+        // - open the checkpoints of the repository
         let rollout_fut = RolloutRecorder::new(&config, rollout_params);
 
-        let mcp_fut = McpConnectionManager::new(config.mcp_servers.clone());
+        let mcp_fut = McpConnectionManager::new(
+            config.mcp_servers.clone(),
+            config.codex_linux_sandbox_exe.clone(),
+            cwd.clone(),
+        );
         let default_shell_fut = shell::default_user_shell();
         let history_meta_fut = crate::message_history::history_metadata(&config);
+        let checkpoints_fut = async {
+            if config.checkpoints {
+                Checkpoints::open(
+                    &config.codex_home,
+                    conversation_id,
+                    &cwd,
+                    prior_user_turns,
+                    forked_from,
+                )
+                .await
+            } else {
+                Ok(None)
+            }
+        };
 
         // Join all independent futures.
-        let (rollout_recorder, mcp_res, default_shell, (history_log_id, history_entry_count)) =
-            tokio::join!(rollout_fut, mcp_fut, default_shell_fut, history_meta_fut);
+        let (
+            rollout_recorder,
+            mcp_res,
+            default_shell,
+            (history_log_id, history_entry_count),
+            checkpoints,
+        ) = tokio::join!(
+            rollout_fut,
+            mcp_fut,
+            default_shell_fut,
+            history_meta_fut,
+            checkpoints_fut
+        );
 
         let rollout_recorder = rollout_recorder.map_err(|e| {
             error!("failed to initialize rollout recorder: {e:#}");
             anyhow::anyhow!("failed to initialize rollout recorder: {e:#}")
         })?;
         let rollout_path = rollout_recorder.rollout_path.clone();
+        let checkpoints = checkpoints.unwrap_or_else(|e| {
+            error!("failed to open checkpoints, turns cannot be undone: {e}");
+            None
+        });
         // Create the mutable state for the Session.
         let state = State {
             history: ConversationHistory::new(),
@@ -455,6 +595,16 @@ impl Session {
             }
         }
 
+        let (exec_policy, exec_policy_errors) = ExecPolicy::load(&config.codex_home, &cwd);
+        for err in exec_policy_errors {
+            let message = format!("Ignoring exec policy: {err}");
+            error!("{message}");
+            post_session_configured_error_events.push(Event {
+                id: INITIAL_SUBMIT_ID.to_owned(),
+                msg: EventMsg::Error(ErrorEvent { message }),
+            });
+        }
+
         // Now that the conversation id is final (may have been updated by resume),
         // construct the model client.
         let client = ModelClient::new(
@@ -476,6 +626,7 @@ impl Session {
                 include_web_search_request: config.tools_web_search_request,
                 use_streamable_shell_tool: config.use_experimental_streamable_shell_tool,
                 include_view_image_tool: config.include_view_image_tool,
+                include_delegate_tool: config.include_delegate_tool,
                 experimental_unified_exec_tool: config.use_experimental_unified_exec_tool,
             }),
             user_instructions,
@@ -492,17 +643,19 @@ impl Session {
             mcp_connection_manager,
             session_manager: ExecSessionManager::default(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
-            notify,
+            notifier: UserNotifier::new(notify, &config.notifications),
             state: Mutex::new(state),
             rollout: Mutex::new(Some(rollout_recorder)),
             codex_linux_sandbox_exe: config.codex_linux_sandbox_exe.clone(),
             user_shell: default_shell,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
+            exec_policy,
+            worktree: Mutex::new(worktree.clone()),
+            checkpoints,
         });
 
         // Dispatch the SessionConfiguredEvent first and then report any errors.
         // If resuming, include converted initial messages in the payload so UIs can render them immediately.
-        let initial_messages = initial_history.get_event_msgs();
         sess.record_initial_history(&turn_context, initial_history)
             .await;
 
@@ -516,6 +669,7 @@ impl Session {
                 history_entry_count,
                 initial_messages,
                 rollout_path,
+                worktree,
             }),
         })
         .chain(post_session_configured_error_events.into_iter());
@@ -609,6 +763,13 @@ impl Session {
             warn!("Overwriting existing pending approval for sub_id: {event_id}");
         }
 
+        self.maybe_notify(UserNotification::ExecApprovalRequest {
+            turn_id: event_id.clone(),
+            call_id: call_id.clone(),
+            command: command.clone(),
+            cwd: cwd.clone(),
+            reason: reason.clone(),
+        });
         let event = Event {
             id: event_id,
             msg: EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
@@ -641,11 +802,20 @@ impl Session {
             warn!("Overwriting existing pending approval for sub_id: {event_id}");
         }
 
+        let changes = convert_apply_patch_to_protocol(action);
+        let mut files: Vec<PathBuf> = changes.keys().cloned().collect();
+        files.sort();
+        self.maybe_notify(UserNotification::ApplyPatchApprovalRequest {
+            turn_id: event_id.clone(),
+            call_id: call_id.clone(),
+            files,
+            reason: reason.clone(),
+        });
         let event = Event {
             id: event_id,
             msg: EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
                 call_id,
-                changes: convert_apply_patch_to_protocol(action),
+                changes,
                 reason,
                 grant_root,
             }),
@@ -654,6 +824,34 @@ impl Session {
         rx_approve
     }
 
+    pub async fn request_mcp_tool_call_approval(
+        &self,
+        sub_id: String,
+        call_id: String,
+        invocation: McpInvocation,
+    ) -> oneshot::Receiver<ReviewDecision> {
+        // Add the tx_approve callback to the map before sending the request.
+        let (tx_approve, rx_approve) = oneshot::channel();
+        let event_id = sub_id.clone();
+        let prev_entry = {
+            let mut state = self.state.lock_unchecked();
+            state.pending_approvals.insert(sub_id, tx_approve)
+        };
+        if prev_entry.is_some() {
+            warn!("Overwriting existing pending approval for sub_id: {event_id}");
+        }
+
+        let event = Event {
+            id: event_id,
+            msg: EventMsg::McpToolCallApprovalRequest(McpToolCallApprovalRequestEvent {
+                call_id,
+                invocation,
+            }),
+        };
+        self.send_event(event).await;
+        rx_approve
+    }
+
     pub fn notify_approval(&self, sub_id: &str, decision: ReviewDecision) {
         let entry = {
             let mut state = self.state.lock_unchecked();
@@ -674,6 +872,24 @@ impl Session {
         state.approved_commands.insert(cmd);
     }
 
+    /// Whether calling `tool` of `server` needs the user's approval: the
+    /// server's configuration asks for it and the user has not approved the
+    /// tool for the session.
+    pub(crate) fn mcp_tool_call_requires_approval(&self, server: &str, tool: &str) -> bool {
+        if !self.mcp_connection_manager.requires_approval(server, tool) {
+            return false;
+        }
+        let state = self.state.lock_unchecked();
+        !state
+            .approved_mcp_tools
+            .contains(&(server.to_string(), tool.to_string()))
+    }
+
+    pub(crate) fn add_approved_mcp_tool(&self, server: String, tool: String) {
+        let mut state = self.state.lock_unchecked();
+        state.approved_mcp_tools.insert((server, tool));
+    }
+
     /// Records input items: always append to conversation history and
     /// persist these response items to rollout.
     async fn record_conversation_items(&self, items: &[ResponseItem]) {
@@ -846,6 +1062,7 @@ impl Session {
             duration,
             exit_code,
             timed_out: _,
+            denied_hosts,
         } = output;
         // Send full stdout/stderr to clients; do not truncate.
         let stdout = stdout.text.clone();
@@ -869,6 +1086,7 @@ impl Session {
                 exit_code: *exit_code,
                 duration: *duration,
                 formatted_output,
+                denied_hosts: denied_hosts.clone(),
             })
         };
 
@@ -930,6 +1148,12 @@ impl Session {
                     aggregated_output: StreamOutput::new(get_error_message_ui(e)),
                     duration: Duration::default(),
                     timed_out: false,
+                    denied_hosts: match e {
+                        CodexErr::Sandbox(SandboxErr::Denied { output }) => {
+                            output.denied_hosts.clone()
+                        }
+                        _ => Vec::new(),
+                    },
                 };
                 &output_stderr
             }
@@ -960,11 +1184,14 @@ impl Session {
     }
 
     async fn notify_stream_error(&self, sub_id: &str, message: impl Into<String>) {
+        let message = message.into();
+        self.maybe_notify(UserNotification::StreamError {
+            turn_id: sub_id.to_string(),
+            message: message.clone(),
+        });
         let event = Event {
             id: sub_id.to_string(),
-            msg: EventMsg::StreamError(StreamErrorEvent {
-                message: message.into(),
-            }),
+            msg: EventMsg::StreamError(StreamErrorEvent { message }),
         };
         self.send_event(event).await;
     }
@@ -977,26 +1204,142 @@ impl Session {
 
     /// Returns the input if there was no task running to inject into
     pub fn inject_input(&self, input: Vec<InputItem>) -> Result<(), Vec<InputItem>> {
+        self.push_pending_input(input, false)
+    }
+
+    /// Adds input from the user to the current turn. Returns the input if
+    /// there was no task running to steer.
+    pub fn steer_input(&self, input: Vec<InputItem>) -> Result<(), Vec<InputItem>> {
+        self.push_pending_input(input, true)
+    }
+
+    fn push_pending_input(
+        &self,
+        items: Vec<InputItem>,
+        steering: bool,
+    ) -> Result<(), Vec<InputItem>> {
         let mut state = self.state.lock_unchecked();
         if state.current_task.is_some() {
-            state.pending_input.push(input.into());
+            state.pending_input.push(PendingInput { items, steering });
             Ok(())
         } else {
-            Err(input)
+            Err(items)
         }
     }
 
-    pub fn get_pending_input(&self) -> Vec<ResponseInputItem> {
-        let mut state = self.state.lock_unchecked();
-        if state.pending_input.is_empty() {
-            Vec::with_capacity(0)
+    /// Why `op` cannot run because of the session's worktree, if it cannot.
+    fn blocked_by_worktree(&self, op: &Op) -> Option<&'static str> {
+        let state = self.state.lock_unchecked();
+        if state.worktree_removed && (starts_task(op) || matches!(op, Op::QueueUserInput { .. })) {
+            Some("the worktree of this conversation was removed; start a new conversation")
+        } else if state.resolving_worktree && starts_task(op) {
+            Some("cannot start a task while the worktree is being resolved")
         } else {
-            let mut ret = Vec::new();
-            std::mem::swap(&mut ret, &mut state.pending_input);
-            ret
+            None
+        }
+    }
+
+    fn has_pending_input(&self) -> bool {
+        !self.state.lock_unchecked().pending_input.is_empty()
+    }
+
+    /// Takes the input waiting to be added to the current turn, reporting the
+    /// change to clients if it included steering input. Each item comes with
+    /// whether it is steering input, i.e. a message from the user.
+    pub async fn get_pending_input(&self, sub_id: &str) -> Vec<(ResponseInputItem, bool)> {
+        let pending_input = std::mem::take(&mut self.state.lock_unchecked().pending_input);
+        if pending_input.iter().any(|input| input.steering) {
+            self.send_input_queue_updated(sub_id).await;
+        }
+        pending_input
+            .into_iter()
+            .map(|input| (ResponseInputItem::from(input.items), input.steering))
+            .collect()
+    }
+
+    fn queue_input(&self, sub_id: String, items: Vec<InputItem>, turn_context: Arc<TurnContext>) {
+        self.state
+            .lock_unchecked()
+            .queued_input
+            .push_back(QueuedTurn {
+                sub_id,
+                items,
+                turn_context,
+            });
+    }
+
+    /// Returns false if no input with this id is queued.
+    fn update_queued_input(&self, id: &str, items: Vec<InputItem>) -> bool {
+        let mut state = self.state.lock_unchecked();
+        match state.queued_input.iter_mut().find(|q| q.sub_id == id) {
+            Some(queued) => {
+                queued.items = items;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns false if no input with this id is queued.
+    fn remove_queued_input(&self, id: &str) -> bool {
+        let mut state = self.state.lock_unchecked();
+        let len = state.queued_input.len();
+        state.queued_input.retain(|q| q.sub_id != id);
+        state.queued_input.len() != len
+    }
+
+    /// Starts the oldest queued input as a new task if no task is running.
+    /// Returns whether a task was started.
+    async fn maybe_start_queued_input(self: &Arc<Self>) -> bool {
+        let sub_id = {
+            let mut state = self.state.lock_unchecked();
+            if state.current_task.is_some() || state.resolving_worktree || state.worktree_removed {
+                return false;
+            }
+            let Some(QueuedTurn {
+                sub_id,
+                items,
+                turn_context,
+            }) = state.queued_input.pop_front()
+            else {
+                return false;
+            };
+            let task = AgentTask::spawn(Arc::clone(self), turn_context, sub_id.clone(), items);
+            state.current_task = Some(task);
+            sub_id
+        };
+        self.send_input_queue_updated(&sub_id).await;
+        true
+    }
+
+    fn input_queue(&self) -> InputQueueUpdatedEvent {
+        let state = self.state.lock_unchecked();
+        InputQueueUpdatedEvent {
+            steering: state
+                .pending_input
+                .iter()
+                .filter(|input| input.steering)
+                .map(|input| input.items.clone())
+                .collect(),
+            queued: state
+                .queued_input
+                .iter()
+                .map(|queued| QueuedInput {
+                    id: queued.sub_id.clone(),
+                    items: queued.items.clone(),
+                })
+                .collect(),
         }
     }
 
+    async fn send_input_queue_updated(&self, sub_id: &str) {
+        let event = Event {
+            id: sub_id.to_string(),
+            msg: EventMsg::InputQueueUpdated(self.input_queue()),
+        };
+        self.send_event(event).await;
+    }
+
     pub async fn call_tool(
         &self,
         server: &str,
@@ -1009,43 +1352,109 @@ impl Session {
             .await
     }
 
-    fn interrupt_task(&self) {
+    /// Returns whether steering input that had not been sent yet was dropped.
+    fn interrupt_task(&self) -> bool {
         info!("interrupt received: abort current task, if any");
         let mut state = self.state.lock_unchecked();
         state.pending_approvals.clear();
+        let dropped_steering = state.pending_input.iter().any(|input| input.steering);
         state.pending_input.clear();
         if let Some(task) = state.current_task.take() {
             task.abort(TurnAbortReason::Interrupted);
         }
+        dropped_steering
     }
 
-    /// Spawn the configured notifier (if any) with the given JSON payload as
-    /// the last argument. Failures are logged but otherwise ignored so that
-    /// notification issues do not interfere with the main workflow.
-    fn maybe_notify(&self, notification: UserNotification) {
-        let Some(notify_command) = &self.notify else {
-            return;
+    /// Resolves the session's worktree and reports the outcome. The worktree
+    /// is kept if resolving it fails.
+    async fn resolve_worktree(&self, sub_id: &str, resolution: WorktreeResolution) {
+        let worktree = {
+            let mut worktree = self.worktree.lock_unchecked();
+            let mut state = self.state.lock_unchecked();
+            if state.current_task.is_some() {
+                Err("cannot resolve the worktree while a task is running".to_string())
+            } else if let Some(worktree) = worktree.take() {
+                state.resolving_worktree = true;
+                Ok(worktree)
+            } else {
+                Err("this conversation does not use a git worktree".to_string())
+            }
         };
+        let result = match worktree {
+            Ok(worktree) => {
+                let result = resolve_worktree(&worktree, resolution).await;
+                if result.is_err() {
+                    *self.worktree.lock_unchecked() = Some(worktree);
+                }
+                let mut state = self.state.lock_unchecked();
+                state.resolving_worktree = false;
+                state.worktree_removed = result.is_ok();
+                result
+            }
+            Err(err) => Err(err),
+        };
+        let msg = match result {
+            Ok(summary) => EventMsg::WorktreeResolved(WorktreeResolvedEvent {
+                resolution,
+                summary,
+            }),
+            Err(message) => EventMsg::Error(ErrorEvent { message }),
+        };
+        self.send_event(Event {
+            id: sub_id.to_string(),
+            msg,
+        })
+        .await;
+    }
 
-        if notify_command.is_empty() {
-            return;
-        }
-
-        let Ok(json) = serde_json::to_string(&notification) else {
-            error!("failed to serialise notification payload");
-            return;
+    /// Restores the files to the checkpoint taken before `turn`, or before the
+    /// latest turn, and reports the outcome.
+    async fn restore_checkpoint(&self, sub_id: &str, turn: Option<usize>) {
+        let result = if self.state.lock_unchecked().current_task.is_some() {
+            Err("cannot restore a checkpoint while a task is running".to_string())
+        } else {
+            match &self.checkpoints {
+                Some(checkpoints) => checkpoints.restore(turn).await,
+                None => Err(
+                    "no checkpoints are taken; set `checkpoints = true` in config.toml and work inside a git repository"
+                        .to_string(),
+                ),
+            }
         };
+        let msg = match result {
+            Ok(turn) => EventMsg::CheckpointRestored(CheckpointRestoredEvent { turn }),
+            Err(message) => EventMsg::Error(ErrorEvent { message }),
+        };
+        self.send_event(Event {
+            id: sub_id.to_string(),
+            msg,
+        })
+        .await;
+    }
 
-        let mut command = std::process::Command::new(&notify_command[0]);
-        if notify_command.len() > 1 {
-            command.args(&notify_command[1..]);
+    /// Records a checkpoint of the repository's files before the user message
+    /// `input`. Checkpoints are numbered like the user messages clients show,
+    /// which leave out messages without text, so those get none.
+    async fn create_checkpoint(&self, input: &ResponseInputItem) {
+        let has_text = matches!(
+            input,
+            ResponseInputItem::Message { content, .. } if content.iter().any(|item| matches!(
+                item,
+                ContentItem::InputText { text } if !text.trim().is_empty()
+            ))
+        );
+        if has_text
+            && let Some(checkpoints) = &self.checkpoints
+            && let Err(e) = checkpoints.create().await
+        {
+            warn!("failed to create checkpoint: {e}");
         }
-        command.arg(json);
+    }
 
-        // Fire-and-forget – we do not wait for completion.
-        if let Err(e) = command.spawn() {
-            warn!("failed to spawn notifier '{}': {e}", notify_command[0]);
-        }
+    /// Deliver `notification` to the configured notifier command and webhook,
+    /// if its kind is enabled.
+    fn maybe_notify(&self, notification: UserNotification) {
+        self.notifier.notify(&notification);
     }
 }
 
@@ -1161,6 +1570,10 @@ impl AgentTask {
                 });
             }
             self.handle.abort();
+            self.sess.maybe_notify(UserNotification::TurnAborted {
+                turn_id: self.sub_id.clone(),
+                reason: reason.clone(),
+            });
             let event = Event {
                 id: self.sub_id,
                 msg: EventMsg::TurnAborted(TurnAbortedEvent { reason }),
@@ -1184,9 +1597,21 @@ async fn submission_loop(
     // To break out of this loop, send Op::Shutdown.
     while let Ok(sub) = rx_sub.recv().await {
         debug!(?sub, "Submission");
+        if let Some(message) = sess.blocked_by_worktree(&sub.op) {
+            sess.send_event(Event {
+                id: sub.id,
+                msg: EventMsg::Error(ErrorEvent {
+                    message: message.to_string(),
+                }),
+            })
+            .await;
+            continue;
+        }
         match sub.op {
             Op::Interrupt => {
-                sess.interrupt_task();
+                if sess.interrupt_task() {
+                    sess.send_input_queue_updated(&sub.id).await;
+                }
             }
             Op::OverrideTurnContext {
                 cwd,
@@ -1247,6 +1672,7 @@ async fn submission_loop(
                     include_web_search_request: config.tools_web_search_request,
                     use_streamable_shell_tool: config.use_experimental_streamable_shell_tool,
                     include_view_image_tool: config.include_view_image_tool,
+                    include_delegate_tool: config.include_delegate_tool,
                     experimental_unified_exec_tool: config.use_experimental_unified_exec_tool,
                 });
 
@@ -1278,14 +1704,39 @@ async fn submission_loop(
                 }
             }
             Op::UserInput { items } => {
-                // attempt to inject input into current task
-                if let Err(items) = sess.inject_input(items) {
-                    // no current task, spawn a new one
-                    let task =
-                        AgentTask::spawn(sess.clone(), Arc::clone(&turn_context), sub.id, items);
-                    sess.set_task(task);
+                // attempt to steer the current task with the input
+                match sess.steer_input(items) {
+                    Ok(()) => sess.send_input_queue_updated(&sub.id).await,
+                    Err(items) => {
+                        // no current task, spawn a new one
+                        let task = AgentTask::spawn(
+                            sess.clone(),
+                            Arc::clone(&turn_context),
+                            sub.id,
+                            items,
+                        );
+                        sess.set_task(task);
+                    }
+                }
+            }
+            Op::QueueUserInput { items } => {
+                sess.queue_input(sub.id.clone(), items, Arc::clone(&turn_context));
+                if !sess.maybe_start_queued_input().await {
+                    sess.send_input_queue_updated(&sub.id).await;
                 }
             }
+            Op::UpdateQueuedInput { id, items } => {
+                if !sess.update_queued_input(&id, items) {
+                    warn!("no queued input with id {id} to update");
+                }
+                sess.send_input_queue_updated(&sub.id).await;
+            }
+            Op::RemoveQueuedInput { id } => {
+                if !sess.remove_queued_input(&id) {
+                    warn!("no queued input with id {id} to remove");
+                }
+                sess.send_input_queue_updated(&sub.id).await;
+            }
             Op::UserTurn {
                 items,
                 cwd,
@@ -1295,8 +1746,8 @@ async fn submission_loop(
                 effort,
                 summary,
             } => {
-                // attempt to inject input into current task
-                if let Err(items) = sess.inject_input(items) {
+                // attempt to steer the current task with the input
+                if let Err(items) = sess.steer_input(items) {
                     // Derive a fresh TurnContext for this turn using the provided overrides.
                     let provider = turn_context.client.get_provider();
                     let auth_manager = turn_context.client.get_auth_manager();
@@ -1336,6 +1787,7 @@ async fn submission_loop(
                             use_streamable_shell_tool: config
                                 .use_experimental_streamable_shell_tool,
                             include_view_image_tool: config.include_view_image_tool,
+                            include_delegate_tool: config.include_delegate_tool,
                             experimental_unified_exec_tool: config
                                 .use_experimental_unified_exec_tool,
                         }),
@@ -1352,6 +1804,8 @@ async fn submission_loop(
                     let task =
                         AgentTask::spawn(sess.clone(), Arc::new(fresh_turn_context), sub.id, items);
                     sess.set_task(task);
+                } else {
+                    sess.send_input_queue_updated(&sub.id).await;
                 }
             }
             Op::ExecApproval { id, decision } => match decision {
@@ -1366,6 +1820,12 @@ async fn submission_loop(
                 }
                 other => sess.notify_approval(&id, other),
             },
+            Op::McpToolCallApproval { id, decision } => match decision {
+                ReviewDecision::Abort => {
+                    sess.interrupt_task();
+                }
+                other => sess.notify_approval(&id, other),
+            },
             Op::AddToHistory { text } => {
                 let id = sess.conversation_id;
                 let config = config.clone();
@@ -1423,24 +1883,64 @@ async fn submission_loop(
                 };
                 sess.send_event(event).await;
             }
+            Op::ListMcpResources => {
+                let sub_id = sub.id.clone();
+
+                // Resources are listed once at startup, so this is a cheap
+                // lookup as well.
+                let resources = sess.mcp_connection_manager.list_all_resources();
+                let event = Event {
+                    id: sub_id,
+                    msg: EventMsg::McpListResourcesResponse(McpListResourcesResponseEvent {
+                        resources,
+                    }),
+                };
+                sess.send_event(event).await;
+            }
             Op::ListCustomPrompts => {
                 let sub_id = sub.id.clone();
 
                 let custom_prompts: Vec<CustomPrompt> =
-                    if let Some(dir) = crate::custom_prompts::default_prompts_dir() {
-                        crate::custom_prompts::discover_prompts_in(&dir).await
-                    } else {
-                        Vec::new()
-                    };
+                    crate::custom_prompts::discover_prompts(&turn_context.cwd).await;
 
                 let event = Event {
                     id: sub_id,
                     msg: EventMsg::ListCustomPromptsResponse(ListCustomPromptsResponseEvent {
                         custom_prompts,
+                        mcp_prompts: sess.mcp_connection_manager.list_all_prompts(),
                     }),
                 };
                 sess.send_event(event).await;
             }
+            Op::GetMcpPrompt {
+                server,
+                name,
+                arguments,
+            } => {
+                let sess_clone = sess.clone();
+                let sub_id = sub.id.clone();
+
+                // Servers may take a while to render a prompt; do not block
+                // the submission loop while waiting.
+                tokio::spawn(async move {
+                    let arguments = (!arguments.is_empty())
+                        .then(|| serde_json::to_value(arguments).unwrap_or_default());
+                    let result = sess_clone
+                        .mcp_connection_manager
+                        .get_prompt(&server, &name, arguments, None)
+                        .await
+                        .map_err(|e| format!("{e:#}"));
+                    let event = Event {
+                        id: sub_id,
+                        msg: EventMsg::GetMcpPromptResponse(GetMcpPromptResponseEvent {
+                            server,
+                            name,
+                            result,
+                        }),
+                    };
+                    sess_clone.send_event(event).await;
+                });
+            }
             Op::Compact => {
                 // Attempt to inject input into current task
                 if let Err(items) = sess.inject_input(vec![InputItem::Text {
@@ -1507,6 +2007,27 @@ async fn submission_loop(
                 };
                 sess.send_event(event).await;
             }
+            Op::ResolveWorktree { resolution } => {
+                let sess = sess.clone();
+                tokio::spawn(async move {
+                    sess.resolve_worktree(&sub.id, resolution).await;
+                    // Input queued meanwhile may start now, unless the
+                    // worktree it would run in was removed.
+                    sess.maybe_start_queued_input().await;
+                });
+            }
+            Op::Undo => {
+                let sess = sess.clone();
+                tokio::spawn(async move {
+                    sess.restore_checkpoint(&sub.id, None).await;
+                });
+            }
+            Op::RestoreCheckpoint { turn } => {
+                let sess = sess.clone();
+                tokio::spawn(async move {
+                    sess.restore_checkpoint(&sub.id, Some(turn)).await;
+                });
+            }
             Op::Review { review_request } => {
                 spawn_review_thread(
                     sess.clone(),
@@ -1525,6 +2046,19 @@ async fn submission_loop(
     debug!("Agent loop exited");
 }
 
+/// Whether `op` starts a task or changes the files of the working directory.
+fn starts_task(op: &Op) -> bool {
+    matches!(
+        op,
+        Op::UserInput { .. }
+            | Op::UserTurn { .. }
+            | Op::Compact
+            | Op::Review { .. }
+            | Op::Undo
+            | Op::RestoreCheckpoint { .. }
+    )
+}
+
 /// Spawn a review thread using the given prompt.
 async fn spawn_review_thread(
     sess: Arc<Session>,
@@ -1545,6 +2079,7 @@ async fn spawn_review_thread(
         include_web_search_request: false,
         use_streamable_shell_tool: false,
         include_view_image_tool: false,
+        include_delegate_tool: false,
         experimental_unified_exec_tool: config.use_experimental_unified_exec_tool,
     });
 
@@ -1635,6 +2170,9 @@ async fn run_task(
     };
     sess.send_event(event).await;
 
+    // Contents of `@server:uri` MCP resource mentions are recorded as a
+    // separate message so they are not shown as part of the user's message.
+    let mentioned_resources = resolve_resource_mentions(&sess.mcp_connection_manager, &input).await;
     let initial_input_for_turn: ResponseInputItem = ResponseInputItem::from(input);
     // For review threads, keep an isolated in-memory history so the
     // model sees a fresh conversation without the parent session's history.
@@ -1643,9 +2181,14 @@ async fn run_task(
     let mut review_thread_history: Vec<ResponseItem> = Vec::new();
     if is_review_mode {
         review_thread_history.push(initial_input_for_turn.into());
+        review_thread_history.extend(mentioned_resources);
     } else {
+        sess.create_checkpoint(&initial_input_for_turn).await;
         sess.record_input_and_rollout_usermsg(&initial_input_for_turn)
             .await;
+        if let Some(resources) = mentioned_resources {
+            sess.record_conversation_items(&[resources]).await;
+        }
     }
 
     let mut last_agent_message: Option<String> = None;
@@ -1658,11 +2201,7 @@ async fn run_task(
         // Note that pending_input would be something like a message the user
         // submitted through the UI while the model was running. Though the UI
         // may support this, the model might not.
-        let pending_input = sess
-            .get_pending_input()
-            .into_iter()
-            .map(ResponseItem::from)
-            .collect::<Vec<ResponseItem>>();
+        let pending_input = sess.get_pending_input(&sub_id).await;
 
         // Construct the input that we will send to the model.
         //
@@ -1675,13 +2214,27 @@ async fn run_task(
         //   only record the new items that originated in this turn so that it
         //   represents an append-only log without duplicates.
         let turn_input: Vec<ResponseItem> = if is_review_mode {
-            if !pending_input.is_empty() {
-                review_thread_history.extend(pending_input);
-            }
+            review_thread_history.extend(
+                pending_input
+                    .into_iter()
+                    .map(|(input, _)| ResponseItem::from(input)),
+            );
             review_thread_history.clone()
         } else {
-            sess.record_conversation_items(&pending_input).await;
-            sess.turn_input_with_history(pending_input)
+            let mut pending_items = Vec::with_capacity(pending_input.len());
+            for (input, steering) in pending_input {
+                if steering {
+                    // A message steering the turn is a user message like the
+                    // one that started the turn, so it gets a checkpoint too.
+                    sess.create_checkpoint(&input).await;
+                    sess.record_input_and_rollout_usermsg(&input).await;
+                } else {
+                    sess.record_conversation_items(&[ResponseItem::from(input.clone())])
+                        .await;
+                }
+                pending_items.push(ResponseItem::from(input));
+            }
+            sess.turn_input_with_history(pending_items)
         };
 
         let turn_input_messages: Vec<String> = turn_input
@@ -1849,7 +2402,9 @@ async fn run_task(
 
                 auto_compact_recently_attempted = false;
 
-                if responses.is_empty() {
+                // Input sent while the model was answering steers the task
+                // into another turn.
+                if responses.is_empty() && !sess.has_pending_input() {
                     last_agent_message = get_last_assistant_message_from_turn(
                         &items_to_record_in_conversation_history,
                     );
@@ -1899,6 +2454,7 @@ async fn run_task(
         msg: EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }),
     };
     sess.send_event(event).await;
+    sess.maybe_start_queued_input().await;
 }
 
 /// Parse the review output; when not valid JSON, build a structured
@@ -1933,10 +2489,14 @@ async fn run_turn(
     sub_id: String,
     input: Vec<ResponseItem>,
 ) -> CodexResult<TurnRunResult> {
-    let tools = get_openai_tools(
+    let mut tools = get_openai_tools(
         &turn_context.tools_config,
         Some(sess.mcp_connection_manager.list_all_tools()),
     );
+    if sess.mcp_connection_manager.has_resources() {
+        tools.push(LIST_MCP_RESOURCES_TOOL.clone());
+        tools.push(READ_MCP_RESOURCE_TOOL.clone());
+    }
 
     let prompt = Prompt {
         input,
@@ -2319,6 +2879,8 @@ async fn handle_response_item(
 
 async fn handle_unified_exec_tool_call(
     sess: &Session,
+    turn_context: &TurnContext,
+    sub_id: &str,
     call_id: String,
     session_id: Option<String>,
     arguments: Vec<String>,
@@ -2341,10 +2903,27 @@ async fn handle_unified_exec_tool_call(
         None
     };
 
+    // Input sent to an existing session goes to a process that was already
+    // approved and sandboxed when the session was created.
+    let sandbox_type = if parsed_session_id.is_some() {
+        SandboxType::None
+    } else {
+        match assess_pty_command(sess, turn_context, sub_id, &call_id, &arguments).await {
+            Ok(sandbox_type) => sandbox_type,
+            Err(output) => return *output,
+        }
+    };
+
     let request = crate::unified_exec::UnifiedExecRequest {
         session_id: parsed_session_id,
         input_chunks: &arguments,
         timeout_ms,
+        sandbox: PtySandbox {
+            sandbox_type,
+            sandbox_policy: &turn_context.sandbox_policy,
+            codex_linux_sandbox_exe: &sess.codex_linux_sandbox_exe,
+            cwd: &turn_context.cwd,
+        },
     };
 
     let result = sess.unified_exec_manager.handle_request(request).await;
@@ -2435,6 +3014,8 @@ async fn handle_function_call(
 
             handle_unified_exec_tool_call(
                 sess,
+                turn_context,
+                &sub_id,
                 call_id,
                 args.session_id,
                 args.input,
@@ -2504,8 +3085,16 @@ async fn handle_function_call(
             .await
         }
         "update_plan" => handle_update_plan(sess, arguments, sub_id, call_id).await,
+        "delegate" => {
+            handle_delegate_tool_call(sess, turn_context, &sub_id, call_id, arguments).await
+        }
+        LIST_MCP_RESOURCES_TOOL_NAME => {
+            handle_list_mcp_resources(&sess.mcp_connection_manager, arguments, call_id)
+        }
+        READ_MCP_RESOURCE_TOOL_NAME => {
+            handle_read_mcp_resource(&sess.mcp_connection_manager, arguments, call_id).await
+        }
         EXEC_COMMAND_TOOL_NAME => {
-            // TODO(mbolin): Sandbox check.
             let exec_params = match serde_json::from_str::<ExecCommandParams>(&arguments) {
                 Ok(params) => params,
                 Err(e) => {
@@ -2518,9 +3107,29 @@ async fn handle_function_call(
                     };
                 }
             };
+            let sandbox_type = match assess_pty_command(
+                sess,
+                turn_context,
+                &sub_id,
+                &call_id,
+                &exec_params.command(),
+            )
+            .await
+            {
+                Ok(sandbox_type) => sandbox_type,
+                Err(output) => return *output,
+            };
             let result = sess
                 .session_manager
-                .handle_exec_command_request(exec_params)
+                .handle_exec_command_request(
+                    exec_params,
+                    PtySandbox {
+                        sandbox_type,
+                        sandbox_policy: &turn_context.sandbox_policy,
+                        codex_linux_sandbox_exe: &sess.codex_linux_sandbox_exe,
+                        cwd: &turn_context.cwd,
+                    },
+                )
                 .await;
             let function_call_output = crate::exec_command::result_into_payload(result);
             ResponseInputItem::FunctionCallOutput {
@@ -2780,6 +3389,8 @@ async fn handle_container_exec_with_params(
                     turn_context.approval_policy,
                     &turn_context.sandbox_policy,
                     &state.approved_commands,
+                    sess.exec_policy.as_ref(),
+                    &params.cwd,
                     params.with_escalated_permissions.unwrap_or(false),
                 )
             };
@@ -2788,48 +3399,19 @@ async fn handle_container_exec_with_params(
         }
     };
 
-    let sandbox_type = match safety {
-        SafetyCheck::AutoApprove { sandbox_type } => sandbox_type,
-        SafetyCheck::AskUser => {
-            let rx_approve = sess
-                .request_command_approval(
-                    sub_id.clone(),
-                    call_id.clone(),
-                    params.command.clone(),
-                    params.cwd.clone(),
-                    params.justification.clone(),
-                )
-                .await;
-            match rx_approve.await.unwrap_or_default() {
-                ReviewDecision::Approved => (),
-                ReviewDecision::ApprovedForSession => {
-                    sess.add_approved_command(params.command.clone());
-                }
-                ReviewDecision::Denied | ReviewDecision::Abort => {
-                    return ResponseInputItem::FunctionCallOutput {
-                        call_id,
-                        output: FunctionCallOutputPayload {
-                            content: "exec command rejected by user".to_string(),
-                            success: None,
-                        },
-                    };
-                }
-            }
-            // No sandboxing is applied because the user has given
-            // explicit approval. Often, we end up in this case because
-            // the command cannot be run in a sandbox, such as
-            // installing a new dependency that requires network access.
-            SandboxType::None
-        }
-        SafetyCheck::Reject { reason } => {
-            return ResponseInputItem::FunctionCallOutput {
-                call_id,
-                output: FunctionCallOutputPayload {
-                    content: format!("exec command rejected: {reason}"),
-                    success: None,
-                },
-            };
-        }
+    let sandbox_type = match resolve_sandbox_type(
+        safety,
+        sess,
+        &sub_id,
+        &call_id,
+        &params.command,
+        &params.cwd,
+        params.justification.clone(),
+    )
+    .await
+    {
+        Ok(sandbox_type) => sandbox_type,
+        Err(output) => return *output,
     };
 
     let exec_command_context = ExecCommandContext {
@@ -2907,6 +3489,94 @@ async fn handle_container_exec_with_params(
     }
 }
 
+/// Turns the result of a safety check into the sandbox the command should run
+/// under, asking the user for approval first when required. Returns the output
+/// to send back to the model when the command must not run.
+async fn resolve_sandbox_type(
+    safety: SafetyCheck,
+    sess: &Session,
+    sub_id: &str,
+    call_id: &str,
+    command: &[String],
+    cwd: &Path,
+    justification: Option<String>,
+) -> Result<SandboxType, Box<ResponseInputItem>> {
+    match safety {
+        SafetyCheck::AutoApprove { sandbox_type } => Ok(sandbox_type),
+        SafetyCheck::AskUser => {
+            let rx_approve = sess
+                .request_command_approval(
+                    sub_id.to_string(),
+                    call_id.to_string(),
+                    command.to_vec(),
+                    cwd.to_path_buf(),
+                    justification,
+                )
+                .await;
+            match rx_approve.await.unwrap_or_default() {
+                ReviewDecision::Approved => (),
+                ReviewDecision::ApprovedForSession => {
+                    sess.add_approved_command(command.to_vec());
+                }
+                ReviewDecision::Denied | ReviewDecision::Abort => {
+                    return Err(Box::new(ResponseInputItem::FunctionCallOutput {
+                        call_id: call_id.to_string(),
+                        output: FunctionCallOutputPayload {
+                            content: "exec command rejected by user".to_string(),
+                            success: None,
+                        },
+                    }));
+                }
+            }
+            // No sandboxing is applied because the user has given
+            // explicit approval. Often, we end up in this case because
+            // the command cannot be run in a sandbox, such as
+            // installing a new dependency that requires network access.
+            Ok(SandboxType::None)
+        }
+        SafetyCheck::Reject { reason } => Err(Box::new(ResponseInputItem::FunctionCallOutput {
+            call_id: call_id.to_string(),
+            output: FunctionCallOutputPayload {
+                content: format!("exec command rejected: {reason}"),
+                success: None,
+            },
+        })),
+    }
+}
+
+/// Runs the safety checks and approval flow for a command that will be
+/// spawned in a PTY and returns the sandbox to launch it under.
+async fn assess_pty_command(
+    sess: &Session,
+    turn_context: &TurnContext,
+    sub_id: &str,
+    call_id: &str,
+    command: &[String],
+) -> Result<SandboxType, Box<ResponseInputItem>> {
+    let safety = {
+        let state = sess.state.lock_unchecked();
+        assess_command_safety(
+            command,
+            turn_context.approval_policy,
+            &turn_context.sandbox_policy,
+            &state.approved_commands,
+            sess.exec_policy.as_ref(),
+            &turn_context.cwd,
+            false,
+        )
+    };
+    resolve_sandbox_type(
+        safety,
+        sess,
+        sub_id,
+        call_id,
+        command,
+        &turn_context.cwd,
+        None,
+    )
+    .await
+}
+
 async fn handle_sandbox_error(
     turn_diff_tracker: &mut TurnDiffTracker,
     params: ExecParams,
@@ -3061,6 +3731,20 @@ fn format_exec_output_str(exec_output: &ExecToolCallOutput) -> String {
         s = &prefixed_str;
     }
 
+    let suffixed_str: String;
+    if !exec_output.denied_hosts.is_empty() {
+        let separator = if s.is_empty() || s.ends_with('\n') {
+            ""
+        } else {
+            "\n"
+        };
+        suffixed_str = format!(
+            "{s}{separator}sandbox denied network access to: {}",
+            exec_output.denied_hosts.join(", ")
+        );
+        s = &suffixed_str;
+    }
+
     let total_lines = s.lines().count();
     if s.len() <= MODEL_FORMAT_MAX_BYTES && total_lines <= MODEL_FORMAT_MAX_LINES {
         return s.to_string();
@@ -3253,7 +3937,7 @@ async fn exit_review_mode(
 ) {
     let event = Event {
         id: task_sub_id,
-        msg: EventMsg::ExitedReviewMode(res),
+        msg: EventMsg::ExitedReviewMode(ExitedReviewModeEvent { review_output: res }),
     };
     session.send_event(event).await;
 }
@@ -3354,6 +4038,7 @@ mod tests {
             aggregated_output: StreamOutput::new(full),
             duration: StdDuration::from_secs(1),
             timed_out: false,
+            denied_hosts: Vec::new(),
         };
 
         let out = format_exec_output_str(&exec);
@@ -3397,6 +4082,7 @@ mod tests {
             aggregated_output: StreamOutput::new(full.clone()),
             duration: StdDuration::from_secs(1),
             timed_out: false,
+            denied_hosts: Vec::new(),
         };
 
         let out = format_exec_output_str(&exec);
@@ -3428,6 +4114,7 @@ mod tests {
             aggregated_output: StreamOutput::new("Command output".to_string()),
             duration: StdDuration::from_secs(1),
             timed_out: true,
+            denied_hosts: Vec::new(),
         };
 
         let out = format_exec_output_str(&exec);
@@ -3438,6 +4125,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn includes_denied_hosts_message() {
+        let exec = ExecToolCallOutput {
+            exit_code: 56,
+            stdout: StreamOutput::new(String::new()),
+            stderr: StreamOutput::new(String::new()),
+            aggregated_output: StreamOutput::new("curl: (56) CONNECT tunnel failed\n".to_string()),
+            duration: StdDuration::from_secs(1),
+            timed_out: false,
+            denied_hosts: vec!["example.com".to_string(), "evil.test".to_string()],
+        };
+
+        let out = format_exec_output_str(&exec);
+
+        assert_eq!(
+            out,
+            "curl: (56) CONNECT tunnel failed\nsandbox denied network access to: example.com, evil.test"
+        );
+    }
+
     #[test]
     fn falls_back_to_content_when_structured_is_null() {
         let ctr = CallToolResult {
@@ -3526,6 +4233,7 @@ mod tests {
             include_web_search_request: config.tools_web_search_request,
             use_streamable_shell_tool: config.use_experimental_streamable_shell_tool,
             include_view_image_tool: config.include_view_image_tool,
+            include_delegate_tool: config.include_delegate_tool,
             experimental_unified_exec_tool: config.use_experimental_unified_exec_tool,
         });
         let turn_context = TurnContext {
@@ -3545,7 +4253,7 @@ mod tests {
             mcp_connection_manager: McpConnectionManager::default(),
             session_manager: ExecSessionManager::default(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
-            notify: None,
+            notifier: UserNotifier::default(),
             rollout: Mutex::new(None),
             state: Mutex::new(State {
                 history: ConversationHistory::new(),
@@ -3554,6 +4262,9 @@ mod tests {
             codex_linux_sandbox_exe: None,
             user_shell: shell::Shell::Unknown,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
+            exec_policy: None,
+            worktree: Mutex::new(None),
+            checkpoints: None,
         };
         (session, turn_context)
     }
@@ -202,6 +202,9 @@ async fn run_compact_task_inner(
         }),
     };
     sess.send_event(event).await;
+    if remove_task_on_completion {
+        sess.maybe_start_queued_input().await;
+    }
 }
 
 fn content_items_to_text(content: &[ContentItem]) -> Option<String> {
@@ -1,8 +1,11 @@
+use crate::auth::get_auth_file;
 use crate::config_profile::ConfigProfile;
 use crate::config_types::History;
 use crate::config_types::McpServerConfig;
+use crate::config_types::Notifications;
 use crate::config_types::ReasoningSummaryFormat;
 use crate::config_types::SandboxWorkspaceWrite;
+use crate::config_types::SessionRetention;
 use crate::config_types::ShellEnvironmentPolicy;
 use crate::config_types::ShellEnvironmentPolicyToml;
 use crate::config_types::Tui;
@@ -114,6 +117,10 @@ pub struct Config {
     /// If unset the feature is disabled.
     pub notify: Option<Vec<String>>,
 
+    /// Which notifications are delivered to `notify` and to the optional
+    /// webhook.
+    pub notifications: Notifications,
+
     /// The directory that should be treated as the current working directory
     /// for the session. All relative paths inside the business-logic layer are
     /// resolved against this path.
@@ -135,6 +142,9 @@ pub struct Config {
     /// Settings that govern if and what will be written to `~/.codex/history.jsonl`.
     pub history: History,
 
+    /// Retention of the recorded sessions in `~/.codex/sessions`.
+    pub sessions: SessionRetention,
+
     /// Optional URI-based file opener. If set, citations to files in the model
     /// output will be hyperlinked using the specified URI scheme.
     pub file_opener: UriBasedFileOpener,
@@ -179,6 +189,18 @@ pub struct Config {
     /// Include the `view_image` tool that lets the agent attach a local image path to context.
     pub include_view_image_tool: bool,
 
+    /// Include the `delegate` tool that lets the agent run sub-agents in
+    /// parallel conversations.
+    pub include_delegate_tool: bool,
+
+    /// Run the conversation in a dedicated git worktree on a fresh branch
+    /// instead of the checkout containing `cwd`.
+    pub worktree: bool,
+
+    /// Take a checkpoint of the files of the git repository containing `cwd`
+    /// before every user turn so that the turn's edits can be undone.
+    pub checkpoints: bool,
+
     /// The active profile name used to derive this `Config` (if any).
     pub active_profile: Option<String>,
 
@@ -186,6 +208,9 @@ pub struct Config {
     /// All characters are inserted as they are received, and no buffering
     /// or placeholder replacement will occur for fast keypress bursts.
     pub disable_paste_burst: bool,
+
+    /// Settings specific to the TUI from the `[tui]` table.
+    pub tui: Tui,
 }
 
 impl Config {
@@ -546,6 +571,9 @@ pub struct ConfigToml {
     #[serde(default)]
     pub notify: Option<Vec<String>>,
 
+    /// Which notifications are delivered, and an optional webhook sink.
+    pub notifications: Option<Notifications>,
+
     /// System instructions.
     pub instructions: Option<String>,
 
@@ -571,6 +599,10 @@ pub struct ConfigToml {
     #[serde(default)]
     pub history: Option<History>,
 
+    /// Retention of the recorded sessions in `~/.codex/sessions`.
+    #[serde(default)]
+    pub sessions: Option<SessionRetention>,
+
     /// Optional URI-based file opener. If set, citations to files in the model
     /// output will be hyperlinked using the specified URI scheme.
     pub file_opener: Option<UriBasedFileOpener>,
@@ -611,6 +643,10 @@ pub struct ConfigToml {
     /// Nested tools section for feature toggles
     pub tools: Option<ToolsToml>,
 
+    /// When set to `true`, a checkpoint of the repository's files is taken
+    /// before each turn so that it can be undone. Defaults to `false`.
+    pub checkpoints: Option<bool>,
+
     /// When true, disables burst-paste detection for typed input entirely.
     /// All characters are inserted as they are received, and no buffering
     /// or placeholder replacement will occur for fast keypress bursts.
@@ -653,6 +689,10 @@ pub struct ToolsToml {
     /// Enable the `view_image` tool that lets the agent attach local images.
     #[serde(default)]
     pub view_image: Option<bool>,
+
+    /// Enable the `delegate` tool that lets the agent run sub-agents.
+    #[serde(default)]
+    pub delegate: Option<bool>,
 }
 
 impl From<ToolsToml> for Tools {
@@ -660,13 +700,28 @@ impl From<ToolsToml> for Tools {
         Self {
             web_search: tools_toml.web_search,
             view_image: tools_toml.view_image,
+            delegate: tools_toml.delegate,
         }
     }
 }
 
+/// Secrets that stay unreadable from within the sandbox once reads are
+/// restricted with `sandbox_workspace_write.readable_roots`.
+fn default_deny_read_paths(codex_home: &Path) -> Vec<PathBuf> {
+    let mut paths = vec![get_auth_file(codex_home)];
+    if let Some(home) = home_dir() {
+        paths.extend([".ssh", ".aws", ".gnupg"].map(|dir| home.join(dir)));
+    }
+    paths
+}
+
 impl ConfigToml {
     /// Derive the effective sandbox policy from the configuration.
-    fn derive_sandbox_policy(&self, sandbox_mode_override: Option<SandboxMode>) -> SandboxPolicy {
+    fn derive_sandbox_policy(
+        &self,
+        sandbox_mode_override: Option<SandboxMode>,
+        codex_home: &Path,
+    ) -> SandboxPolicy {
         let resolved_sandbox_mode = sandbox_mode_override
             .or(self.sandbox_mode)
             .unwrap_or_default();
@@ -678,11 +733,23 @@ impl ConfigToml {
                     network_access,
                     exclude_tmpdir_env_var,
                     exclude_slash_tmp,
+                    readable_roots,
+                    deny_read,
+                    allowed_hosts,
                 }) => SandboxPolicy::WorkspaceWrite {
                     writable_roots: writable_roots.clone(),
                     network_access: *network_access,
                     exclude_tmpdir_env_var: *exclude_tmpdir_env_var,
                     exclude_slash_tmp: *exclude_slash_tmp,
+                    readable_roots: readable_roots.clone(),
+                    deny_read: if readable_roots.is_some() {
+                        let mut paths = default_deny_read_paths(codex_home);
+                        paths.extend(deny_read.iter().cloned());
+                        paths
+                    } else {
+                        Vec::new()
+                    },
+                    allowed_hosts: allowed_hosts.clone(),
                 },
                 None => SandboxPolicy::new_workspace_write_policy(),
             },
@@ -755,6 +822,7 @@ pub struct ConfigOverrides {
     pub include_view_image_tool: Option<bool>,
     pub show_raw_agent_reasoning: Option<bool>,
     pub tools_web_search_request: Option<bool>,
+    pub worktree: Option<bool>,
 }
 
 impl Config {
@@ -783,6 +851,7 @@ impl Config {
             include_view_image_tool,
             show_raw_agent_reasoning,
             tools_web_search_request: override_tools_web_search_request,
+            worktree,
         } = overrides;
 
         let active_profile_name = config_profile_key
@@ -803,7 +872,7 @@ impl Config {
             None => ConfigProfile::default(),
         };
 
-        let sandbox_policy = cfg.derive_sandbox_policy(sandbox_mode);
+        let sandbox_policy = cfg.derive_sandbox_policy(sandbox_mode, &codex_home);
 
         let mut model_providers = built_in_model_providers();
         // Merge user-defined providers into the built-in list.
@@ -856,6 +925,8 @@ impl Config {
             .or(cfg.tools.as_ref().and_then(|t| t.view_image))
             .unwrap_or(true);
 
+        let include_delegate_tool = cfg.tools.as_ref().and_then(|t| t.delegate).unwrap_or(false);
+
         let model = model
             .or(config_profile.model)
             .or(cfg.model)
@@ -919,6 +990,7 @@ impl Config {
             sandbox_policy,
             shell_environment_policy,
             notify: cfg.notify,
+            notifications: cfg.notifications.unwrap_or_default(),
             user_instructions,
             base_instructions,
             mcp_servers: cfg.mcp_servers,
@@ -926,6 +998,7 @@ impl Config {
             project_doc_max_bytes: cfg.project_doc_max_bytes.unwrap_or(PROJECT_DOC_MAX_BYTES),
             codex_home,
             history,
+            sessions: cfg.sessions.unwrap_or_default(),
             file_opener: cfg.file_opener.unwrap_or(UriBasedFileOpener::VsCode),
             codex_linux_sandbox_exe,
 
@@ -956,8 +1029,12 @@ impl Config {
                 .experimental_use_unified_exec_tool
                 .unwrap_or(false),
             include_view_image_tool,
+            include_delegate_tool,
+            worktree: worktree.unwrap_or(false),
+            checkpoints: cfg.checkpoints.unwrap_or(false),
             active_profile: active_profile_name,
             disable_paste_burst: cfg.disable_paste_burst.unwrap_or(false),
+            tui: cfg.tui.unwrap_or_default(),
         };
         Ok(config)
     }
@@ -1067,9 +1144,18 @@ pub fn log_dir(cfg: &Config) -> std::io::Result<PathBuf> {
 
 #[cfg(test)]
 mod tests {
+    use crate::config_types::EditingMode;
     use crate::config_types::HistoryPersistence;
+    use crate::config_types::KeyBindingsToml;
+    use crate::config_types::McpHttpTransport;
+    use crate::config_types::McpServerTransportConfig;
+    use crate::config_types::McpToolApproval;
 
     use super::*;
+    use crate::config_types::NotificationKind;
+    use crate::config_types::NotificationWebhook;
+    use crate::config_types::SensitivePattern;
+    use crate::config_types::TuiKeymap;
     use pretty_assertions::assert_eq;
 
     use tempfile::TempDir;
@@ -1086,6 +1172,7 @@ persistence = "save-all"
             Some(History {
                 persistence: HistoryPersistence::SaveAll,
                 max_bytes: None,
+                sensitive_patterns: Vec::new(),
             }),
             history_with_persistence_cfg.history
         );
@@ -1101,9 +1188,204 @@ persistence = "none"
             Some(History {
                 persistence: HistoryPersistence::None,
                 max_bytes: None,
+                sensitive_patterns: Vec::new(),
             }),
             history_no_persistence_cfg.history
         );
+
+        let history_with_limits = r#"
+[history]
+max_bytes = 1048576
+sensitive_patterns = ["sk-[A-Za-z0-9]+"]
+"#;
+
+        let history_with_limits_cfg = toml::from_str::<ConfigToml>(history_with_limits)
+            .expect("TOML deserialization should succeed");
+        assert_eq!(
+            Some(History {
+                persistence: HistoryPersistence::SaveAll,
+                max_bytes: Some(1_048_576),
+                sensitive_patterns: vec![
+                    SensitivePattern::try_from("sk-[A-Za-z0-9]+".to_string())
+                        .expect("valid pattern")
+                ],
+            }),
+            history_with_limits_cfg.history
+        );
+
+        let history_with_invalid_pattern = r#"
+[history]
+sensitive_patterns = ["(unclosed"]
+"#;
+        let err = toml::from_str::<ConfigToml>(history_with_invalid_pattern)
+            .expect_err("an invalid pattern should be a config error");
+        assert!(
+            err.to_string()
+                .contains("invalid history sensitive pattern `(unclosed`"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_notifications_parsing() {
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[notifications]
+kinds = ["exec-approval-request", "turn-aborted"]
+
+[notifications.webhook]
+url = "https://hooks.example.com/codex"
+http_headers = { Authorization = "Bearer token" }
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        assert_eq!(
+            Some(Notifications {
+                kinds: vec![
+                    NotificationKind::ExecApprovalRequest,
+                    NotificationKind::TurnAborted,
+                ],
+                webhook: Some(NotificationWebhook {
+                    url: "https://hooks.example.com/codex".to_string(),
+                    http_headers: HashMap::from([(
+                        "Authorization".to_string(),
+                        "Bearer token".to_string(),
+                    )]),
+                }),
+            }),
+            cfg.notifications
+        );
+
+        let cfg = toml::from_str::<ConfigToml>("[notifications]\n")
+            .expect("TOML deserialization should succeed");
+        assert_eq!(Some(Notifications::default()), cfg.notifications);
+        assert_eq!(
+            vec![NotificationKind::AgentTurnComplete],
+            Notifications::default().kinds
+        );
+    }
+
+    #[test]
+    fn test_sessions_parsing() {
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[sessions]
+max_age_days = 30
+max_total_bytes = 1073741824
+compress_after_days = 7
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        assert_eq!(
+            Some(SessionRetention {
+                max_age_days: Some(30),
+                max_total_bytes: Some(1_073_741_824),
+                keep_archived: true,
+                compress_after_days: Some(7),
+            }),
+            cfg.sessions
+        );
+
+        let cfg = toml::from_str::<ConfigToml>("[sessions]\nkeep_archived = false\n")
+            .expect("TOML deserialization should succeed");
+        assert_eq!(
+            Some(SessionRetention {
+                keep_archived: false,
+                ..Default::default()
+            }),
+            cfg.sessions
+        );
+    }
+
+    #[test]
+    fn test_tui_keymap_and_theme_parsing() {
+        let cfg = toml::from_str::<ConfigToml>(
+            r##"
+[tui.keymap]
+transcript = "ctrl-o"
+backtrack = ["esc", "ctrl-b"]
+editing_mode = "vi"
+
+[tui.theme]
+accent = "#5fafd7"
+"##,
+        )
+        .expect("TOML deserialization should succeed");
+        let tui = cfg.tui.expect("tui table");
+        assert_eq!(
+            TuiKeymap {
+                transcript: Some(KeyBindingsToml::One("ctrl-o".to_string())),
+                backtrack: Some(KeyBindingsToml::Many(vec![
+                    "esc".to_string(),
+                    "ctrl-b".to_string(),
+                ])),
+                editing_mode: EditingMode::Vi,
+                ..Default::default()
+            },
+            tui.keymap
+        );
+        assert_eq!(Some("#5fafd7".to_string()), tui.theme.accent);
+
+        // Misspelled actions are reported instead of silently ignored.
+        assert!(toml::from_str::<ConfigToml>("[tui.keymap]\ntranscrip = \"ctrl-o\"\n").is_err());
+    }
+
+    #[test]
+    fn test_mcp_servers_parsing() {
+        let mcp_servers = r#"
+[mcp_servers.local]
+command = "npx"
+args = ["-y", "mcp-server"]
+disabled_tools = ["delete_file"]
+approval = "untrusted"
+
+[mcp_servers.remote]
+url = "https://mcp.example.com/sse"
+transport = "sse"
+bearer_token_env_var = "MCP_TOKEN"
+http_headers = { "X-Team" = "infra" }
+"#;
+        let cfg =
+            toml::from_str::<ConfigToml>(mcp_servers).expect("TOML deserialization should succeed");
+        assert_eq!(
+            Some(&McpServerConfig {
+                transport: McpServerTransportConfig::Stdio {
+                    command: "npx".to_string(),
+                    args: vec!["-y".to_string(), "mcp-server".to_string()],
+                    env: None,
+                },
+                startup_timeout_ms: None,
+                enabled_tools: None,
+                disabled_tools: vec!["delete_file".to_string()],
+                approval: McpToolApproval::Untrusted,
+                sandbox: None,
+            }),
+            cfg.mcp_servers.get("local")
+        );
+        assert_eq!(
+            Some(&McpServerConfig {
+                transport: McpServerTransportConfig::Http {
+                    url: "https://mcp.example.com/sse".to_string(),
+                    transport: McpHttpTransport::Sse,
+                    http_headers: Some(HashMap::from([(
+                        "X-Team".to_string(),
+                        "infra".to_string()
+                    )])),
+                    env_http_headers: None,
+                    bearer_token_env_var: Some("MCP_TOKEN".to_string()),
+                },
+                startup_timeout_ms: None,
+                enabled_tools: None,
+                disabled_tools: Vec::new(),
+                approval: McpToolApproval::Never,
+                sandbox: None,
+            }),
+            cfg.mcp_servers.get("remote")
+        );
+
+        let local = &cfg.mcp_servers["local"];
+        assert!(local.is_tool_enabled("read_file"));
+        assert!(!local.is_tool_enabled("delete_file"));
     }
 
     #[test]
@@ -1119,7 +1401,8 @@ network_access = false  # This should be ignored.
         let sandbox_mode_override = None;
         assert_eq!(
             SandboxPolicy::DangerFullAccess,
-            sandbox_full_access_cfg.derive_sandbox_policy(sandbox_mode_override)
+            sandbox_full_access_cfg
+                .derive_sandbox_policy(sandbox_mode_override, Path::new("/codex_home"))
         );
 
         let sandbox_read_only = r#"
@@ -1134,7 +1417,8 @@ network_access = true  # This should be ignored.
         let sandbox_mode_override = None;
         assert_eq!(
             SandboxPolicy::ReadOnly,
-            sandbox_read_only_cfg.derive_sandbox_policy(sandbox_mode_override)
+            sandbox_read_only_cfg
+                .derive_sandbox_policy(sandbox_mode_override, Path::new("/codex_home"))
         );
 
         let sandbox_workspace_write = r#"
@@ -1146,6 +1430,7 @@ writable_roots = [
 ]
 exclude_tmpdir_env_var = true
 exclude_slash_tmp = true
+allowed_hosts = ["crates.io", "*.registry.internal"]
 "#;
 
         let sandbox_workspace_write_cfg = toml::from_str::<ConfigToml>(sandbox_workspace_write)
@@ -1157,8 +1442,42 @@ exclude_slash_tmp = true
                 network_access: false,
                 exclude_tmpdir_env_var: true,
                 exclude_slash_tmp: true,
+                readable_roots: None,
+                deny_read: Vec::new(),
+                allowed_hosts: vec!["crates.io".to_string(), "*.registry.internal".to_string()],
+            },
+            sandbox_workspace_write_cfg
+                .derive_sandbox_policy(sandbox_mode_override, Path::new("/codex_home"))
+        );
+
+        let sandbox_restricted_read = r#"
+sandbox_mode = "workspace-write"
+
+[sandbox_workspace_write]
+readable_roots = ["/opt/toolchains"]
+deny_read = ["/opt/toolchains/secrets"]
+"#;
+
+        let sandbox_restricted_read_cfg = toml::from_str::<ConfigToml>(sandbox_restricted_read)
+            .expect("TOML deserialization should succeed");
+        let sandbox_mode_override = None;
+        let mut expected_deny_read = vec![PathBuf::from("/codex_home/auth.json")];
+        if let Some(home) = home_dir() {
+            expected_deny_read.extend([home.join(".ssh"), home.join(".aws"), home.join(".gnupg")]);
+        }
+        expected_deny_read.push(PathBuf::from("/opt/toolchains/secrets"));
+        assert_eq!(
+            SandboxPolicy::WorkspaceWrite {
+                writable_roots: Vec::new(),
+                network_access: false,
+                exclude_tmpdir_env_var: false,
+                exclude_slash_tmp: false,
+                readable_roots: Some(vec![PathBuf::from("/opt/toolchains")]),
+                deny_read: expected_deny_read,
+                allowed_hosts: Vec::new(),
             },
-            sandbox_workspace_write_cfg.derive_sandbox_policy(sandbox_mode_override)
+            sandbox_restricted_read_cfg
+                .derive_sandbox_policy(sandbox_mode_override, Path::new("/codex_home"))
         );
     }
 
@@ -1457,12 +1776,14 @@ model_verbosity = "high"
                 shell_environment_policy: ShellEnvironmentPolicy::default(),
                 user_instructions: None,
                 notify: None,
+                notifications: Notifications::default(),
                 cwd: fixture.cwd(),
                 mcp_servers: HashMap::new(),
                 model_providers: fixture.model_provider_map.clone(),
                 project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
                 codex_home: fixture.codex_home(),
                 history: History::default(),
+                sessions: SessionRetention::default(),
                 file_opener: UriBasedFileOpener::VsCode,
                 codex_linux_sandbox_exe: None,
                 hide_agent_reasoning: false,
@@ -1478,8 +1799,12 @@ model_verbosity = "high"
                 use_experimental_streamable_shell_tool: false,
                 use_experimental_unified_exec_tool: false,
                 include_view_image_tool: true,
+                include_delegate_tool: false,
+                worktree: false,
+                checkpoints: false,
                 active_profile: Some("o3".to_string()),
                 disable_paste_burst: false,
+                tui: Tui::default(),
             },
             o3_profile_config
         );
@@ -1514,12 +1839,14 @@ model_verbosity = "high"
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            notifications: Notifications::default(),
             cwd: fixture.cwd(),
             mcp_servers: HashMap::new(),
             model_providers: fixture.model_provider_map.clone(),
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             codex_home: fixture.codex_home(),
             history: History::default(),
+            sessions: SessionRetention::default(),
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -1535,8 +1862,12 @@ model_verbosity = "high"
             use_experimental_streamable_shell_tool: false,
             use_experimental_unified_exec_tool: false,
             include_view_image_tool: true,
+            include_delegate_tool: false,
+            worktree: false,
+            checkpoints: false,
             active_profile: Some("gpt3".to_string()),
             disable_paste_burst: false,
+            tui: Tui::default(),
         };
 
         assert_eq!(expected_gpt3_profile_config, gpt3_profile_config);
@@ -1586,12 +1917,14 @@ model_verbosity = "high"
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            notifications: Notifications::default(),
             cwd: fixture.cwd(),
             mcp_servers: HashMap::new(),
             model_providers: fixture.model_provider_map.clone(),
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             codex_home: fixture.codex_home(),
             history: History::default(),
+            sessions: SessionRetention::default(),
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -1607,8 +1940,12 @@ model_verbosity = "high"
             use_experimental_streamable_shell_tool: false,
             use_experimental_unified_exec_tool: false,
             include_view_image_tool: true,
+            include_delegate_tool: false,
+            worktree: false,
+            checkpoints: false,
             active_profile: Some("zdr".to_string()),
             disable_paste_burst: false,
+            tui: Tui::default(),
         };
 
         assert_eq!(expected_zdr_profile_config, zdr_profile_config);
@@ -1644,12 +1981,14 @@ model_verbosity = "high"
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            notifications: Notifications::default(),
             cwd: fixture.cwd(),
             mcp_servers: HashMap::new(),
             model_providers: fixture.model_provider_map.clone(),
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             codex_home: fixture.codex_home(),
             history: History::default(),
+            sessions: SessionRetention::default(),
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -1665,8 +2004,12 @@ model_verbosity = "high"
             use_experimental_streamable_shell_tool: false,
             use_experimental_unified_exec_tool: false,
             include_view_image_tool: true,
+            include_delegate_tool: false,
+            worktree: false,
+            checkpoints: false,
             active_profile: Some("gpt5".to_string()),
             disable_paste_burst: false,
+            tui: Tui::default(),
         };
 
         assert_eq!(expected_gpt5_profile_config, gpt5_profile_config);
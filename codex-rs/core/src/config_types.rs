@@ -3,6 +3,7 @@
 // Note this file should generally be restricted to simple struct/enum
 // definitions that do not contain business logic.
 
+use regex_lite::Regex;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use wildmatch::WildMatchPattern;
@@ -11,17 +12,124 @@ use serde::Deserialize;
 
 #[derive(Deserialize, Debug, Clone, PartialEq)]
 pub struct McpServerConfig {
-    pub command: String,
+    /// How to reach the server: either a `command` to spawn or a `url`.
+    #[serde(flatten)]
+    pub transport: McpServerTransportConfig,
 
+    /// Startup timeout in milliseconds for initializing MCP server & initially listing tools.
     #[serde(default)]
-    pub args: Vec<String>,
+    pub startup_timeout_ms: Option<u64>,
 
+    /// Tools of the server offered to the model. When unset, all of them are.
     #[serde(default)]
-    pub env: Option<HashMap<String, String>>,
+    pub enabled_tools: Option<Vec<String>>,
 
-    /// Startup timeout in milliseconds for initializing MCP server & initially listing tools.
+    /// Tools of the server never offered to the model, even if listed in
+    /// `enabled_tools`.
     #[serde(default)]
-    pub startup_timeout_ms: Option<u64>,
+    pub disabled_tools: Vec<String>,
+
+    /// When to ask the user before calling one of the server's tools.
+    #[serde(default)]
+    pub approval: McpToolApproval,
+
+    /// When set, a stdio server is launched inside the Codex sandbox instead
+    /// of with the user's full privileges.
+    #[serde(default)]
+    pub sandbox: Option<McpServerSandbox>,
+}
+
+impl McpServerConfig {
+    /// Whether the tool named `tool` is offered to the model.
+    pub fn is_tool_enabled(&self, tool: &str) -> bool {
+        let enabled = self
+            .enabled_tools
+            .as_ref()
+            .is_none_or(|enabled| enabled.iter().any(|name| name == tool));
+        enabled && !self.disabled_tools.iter().any(|name| name == tool)
+    }
+}
+
+/// When to ask the user before Codex calls a tool of an MCP server.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum McpToolApproval {
+    /// Ask before every call, unless the user approved the tool for the
+    /// session.
+    Always,
+
+    /// Call tools without asking.
+    #[default]
+    Never,
+
+    /// Ask before calling tools that do not declare themselves read-only
+    /// through the `readOnlyHint` annotation.
+    Untrusted,
+}
+
+/// Sandbox for a stdio MCP server. The server may write to the session's
+/// working directory, the temporary directories and `writable_roots`.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct McpServerSandbox {
+    /// Additional folders the server may write to.
+    #[serde(default)]
+    pub writable_roots: Vec<PathBuf>,
+
+    /// Whether the server may access the network.
+    #[serde(default)]
+    pub network_access: bool,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum McpServerTransportConfig {
+    /// Launch the server as a subprocess and communicate over its stdio.
+    Stdio {
+        command: String,
+
+        #[serde(default)]
+        args: Vec<String>,
+
+        #[serde(default)]
+        env: Option<HashMap<String, String>>,
+    },
+
+    /// Connect to a server that is already running as an HTTP service.
+    Http {
+        url: String,
+
+        /// Which HTTP transport the server speaks.
+        #[serde(default)]
+        transport: McpHttpTransport,
+
+        /// Additional HTTP headers to include in every request where the
+        /// (key, value) pairs are the header name and value.
+        #[serde(default)]
+        http_headers: Option<HashMap<String, String>>,
+
+        /// HTTP headers whose values are read from environment variables where
+        /// the (key, value) pairs are the header name and the _environment
+        /// variable_ whose value should be used. Unset or empty variables are
+        /// skipped.
+        #[serde(default)]
+        env_http_headers: Option<HashMap<String, String>>,
+
+        /// Environment variable holding a token sent as
+        /// `Authorization: Bearer <token>`.
+        #[serde(default)]
+        bearer_token_env_var: Option<String>,
+    },
+}
+
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum McpHttpTransport {
+    /// The Streamable HTTP transport (MCP 2025-03-26 and later).
+    #[default]
+    StreamableHttp,
+
+    /// The legacy HTTP+SSE transport (MCP 2024-11-05).
+    Sse,
 }
 
 #[derive(Deserialize, Debug, Copy, Clone, PartialEq)]
@@ -59,11 +167,45 @@ impl UriBasedFileOpener {
 #[derive(Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct History {
     /// If true, history entries will not be written to disk.
+    #[serde(default)]
     pub persistence: HistoryPersistence,
 
-    /// If set, the maximum size of the history file in bytes.
-    /// TODO(mbolin): Not currently honored.
+    /// If set, the maximum size of the history file in bytes. When an append
+    /// pushes the file past this size, the oldest entries are dropped.
     pub max_bytes: Option<usize>,
+
+    /// Regular expressions for text that must never be written to disk.
+    /// Matches are replaced with `[REDACTED]` before an entry is appended.
+    #[serde(default)]
+    pub sensitive_patterns: Vec<SensitivePattern>,
+}
+
+/// A regular expression of `history.sensitive_patterns`. It is compiled when
+/// the config is loaded, so an invalid pattern is a config error.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(try_from = "String")]
+pub struct SensitivePattern(Regex);
+
+impl SensitivePattern {
+    pub fn regex(&self) -> &Regex {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for SensitivePattern {
+    type Error = String;
+
+    fn try_from(pattern: String) -> Result<Self, Self::Error> {
+        Regex::new(&pattern)
+            .map(Self)
+            .map_err(|e| format!("invalid history sensitive pattern `{pattern}`: {e}"))
+    }
+}
+
+impl PartialEq for SensitivePattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
 }
 
 #[derive(Deserialize, Debug, Copy, Clone, PartialEq, Default)]
@@ -76,9 +218,259 @@ pub enum HistoryPersistence {
     None,
 }
 
+/// Retention of the recorded sessions in `~/.codex/sessions`, enforced when a
+/// new session starts and by `codex sessions prune`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct SessionRetention {
+    /// Delete sessions not written to in this many days.
+    pub max_age_days: Option<u64>,
+
+    /// Delete the least recently written sessions while all of them together
+    /// take more than this many bytes.
+    pub max_total_bytes: Option<u64>,
+
+    /// Never delete archived sessions, nor count them towards
+    /// `max_total_bytes`. Defaults to true.
+    #[serde(default = "default_keep_archived")]
+    pub keep_archived: bool,
+
+    /// Gzip sessions not written to in this many days. Compressed
+    /// sessions can still be listed, searched, exported and resumed.
+    pub compress_after_days: Option<u64>,
+}
+
+impl SessionRetention {
+    /// Whether any limit is set.
+    pub fn is_enabled(&self) -> bool {
+        self.max_age_days.is_some()
+            || self.max_total_bytes.is_some()
+            || self.compress_after_days.is_some()
+    }
+}
+
+impl Default for SessionRetention {
+    fn default() -> Self {
+        Self {
+            max_age_days: None,
+            max_total_bytes: None,
+            keep_archived: default_keep_archived(),
+            compress_after_days: None,
+        }
+    }
+}
+
+fn default_keep_archived() -> bool {
+    true
+}
+
+/// Settings that choose which events trigger a user notification and where,
+/// besides the `notify` program, notifications are delivered.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Notifications {
+    /// Kinds of notifications to deliver. Defaults to `agent-turn-complete`
+    /// only.
+    #[serde(default = "default_notification_kinds")]
+    pub kinds: Vec<NotificationKind>,
+
+    /// Optional endpoint that receives each notification as an HTTP POST
+    /// with the JSON payload as the body.
+    #[serde(default)]
+    pub webhook: Option<NotificationWebhook>,
+}
+
+impl Default for Notifications {
+    fn default() -> Self {
+        Self {
+            kinds: default_notification_kinds(),
+            webhook: None,
+        }
+    }
+}
+
+fn default_notification_kinds() -> Vec<NotificationKind> {
+    vec![NotificationKind::AgentTurnComplete]
+}
+
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum NotificationKind {
+    /// The agent finished a turn.
+    AgentTurnComplete,
+    /// A command is waiting for the user's approval.
+    ExecApprovalRequest,
+    /// A patch is waiting for the user's approval.
+    ApplyPatchApprovalRequest,
+    /// The model stream failed and the turn is being retried.
+    StreamError,
+    /// A turn was interrupted or replaced before it finished.
+    TurnAborted,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct NotificationWebhook {
+    pub url: String,
+
+    /// Additional HTTP headers to send with each request, e.g. an
+    /// `Authorization` header.
+    #[serde(default)]
+    pub http_headers: HashMap<String, String>,
+}
+
 /// Collection of settings that are specific to the TUI.
 #[derive(Deserialize, Debug, Clone, PartialEq, Default)]
-pub struct Tui {}
+pub struct Tui {
+    /// Colors used to highlight code blocks and diffs.
+    #[serde(default)]
+    pub syntax_theme: SyntaxTheme,
+
+    /// Key bindings from the `[tui.keymap]` table.
+    #[serde(default)]
+    pub keymap: TuiKeymap,
+
+    /// Colors from the `[tui.theme]` table.
+    #[serde(default)]
+    pub theme: TuiTheme,
+}
+
+/// Key bindings for the TUI. Each action takes one key (`"ctrl-t"`) or a list
+/// of keys (`["esc", "ctrl-b"]`); actions that are not set keep their default
+/// keys. The key names are parsed by the TUI.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct TuiKeymap {
+    /// Opens and closes the transcript overlay. Defaults to `ctrl-t`.
+    pub transcript: Option<KeyBindingsToml>,
+
+    /// Starts backtracking to an earlier message. Defaults to `esc`.
+    pub backtrack: Option<KeyBindingsToml>,
+
+    /// Submits the message in the composer. Defaults to `enter`.
+    pub submit: Option<KeyBindingsToml>,
+
+    /// Approves a command or patch once. Defaults to `y`.
+    pub approve: Option<KeyBindingsToml>,
+
+    /// Approves a command for the rest of the session. Defaults to `a`.
+    pub approve_for_session: Option<KeyBindingsToml>,
+
+    /// Denies a command or patch. Defaults to `n`.
+    pub deny: Option<KeyBindingsToml>,
+
+    /// Inserts a newline in the composer. Defaults to `ctrl-j` and `ctrl-m`.
+    pub newline: Option<KeyBindingsToml>,
+
+    /// Moves to the start of the line. Defaults to `ctrl-a` and `home`.
+    pub line_start: Option<KeyBindingsToml>,
+
+    /// Moves to the end of the line. Defaults to `ctrl-e` and `end`.
+    pub line_end: Option<KeyBindingsToml>,
+
+    /// Moves to the previous word. Defaults to `alt-b`, `alt-left` and
+    /// `ctrl-left`.
+    pub word_left: Option<KeyBindingsToml>,
+
+    /// Moves past the next word. Defaults to `alt-f`, `alt-right` and
+    /// `ctrl-right`.
+    pub word_right: Option<KeyBindingsToml>,
+
+    /// Deletes the word before the cursor. Defaults to `ctrl-w`,
+    /// `alt-backspace` and `ctrl-alt-h`.
+    pub delete_word_backward: Option<KeyBindingsToml>,
+
+    /// Deletes the word after the cursor. Defaults to `alt-delete`.
+    pub delete_word_forward: Option<KeyBindingsToml>,
+
+    /// Deletes from the start of the line to the cursor. Defaults to `ctrl-u`.
+    pub kill_line_start: Option<KeyBindingsToml>,
+
+    /// Deletes from the cursor to the end of the line. Defaults to `ctrl-k`.
+    pub kill_line_end: Option<KeyBindingsToml>,
+
+    /// Scrolls the transcript and diff pagers up a line. Defaults to `up`.
+    pub scroll_up: Option<KeyBindingsToml>,
+
+    /// Scrolls the pagers down a line. Defaults to `down`.
+    pub scroll_down: Option<KeyBindingsToml>,
+
+    /// Scrolls the pagers up a page. Defaults to `pageup`.
+    pub page_up: Option<KeyBindingsToml>,
+
+    /// Scrolls the pagers down a page. Defaults to `pagedown` and `space`.
+    pub page_down: Option<KeyBindingsToml>,
+
+    /// Jumps to the top of the pagers. Defaults to `home`.
+    pub scroll_to_top: Option<KeyBindingsToml>,
+
+    /// Jumps to the bottom of the pagers. Defaults to `end`.
+    pub scroll_to_bottom: Option<KeyBindingsToml>,
+
+    /// Closes the pagers. Defaults to `q` and `ctrl-c`.
+    pub close_pager: Option<KeyBindingsToml>,
+
+    /// How the composer is edited.
+    #[serde(default)]
+    pub editing_mode: EditingMode,
+}
+
+/// One key or a list of keys bound to the same action.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum KeyBindingsToml {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyBindingsToml {
+    pub fn keys(&self) -> &[String] {
+        match self {
+            KeyBindingsToml::One(key) => std::slice::from_ref(key),
+            KeyBindingsToml::Many(keys) => keys,
+        }
+    }
+}
+
+/// Editing keys of the TUI composer.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum EditingMode {
+    /// Emacs-style keys such as `ctrl-a` and `alt-b`.
+    #[default]
+    Emacs,
+    /// Vi-style insert and normal modes.
+    Vi,
+}
+
+/// Colors of the TUI. Each takes an ANSI color name (`"cyan"`,
+/// `"light-blue"`) or a hex color (`"#5fafd7"`); colors that are not set keep
+/// their defaults.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct TuiTheme {
+    /// Key hints, selections and status indicators. Defaults to `cyan`.
+    pub accent: Option<String>,
+
+    /// Successes and additions. Defaults to `green`.
+    pub success: Option<String>,
+
+    /// Errors, failures and deletions. Defaults to `red`.
+    pub error: Option<String>,
+
+    /// Labels of Codex itself, such as `codex` and `thinking`. Defaults to
+    /// `magenta`.
+    pub brand: Option<String>,
+}
+
+/// Color theme for highlighting code in the TUI.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum SyntaxTheme {
+    /// The colors of `[tui.theme]`, which default to the terminal's own ANSI
+    /// colors.
+    #[default]
+    Ansi,
+    /// Do not highlight code.
+    None,
+}
 
 #[derive(Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct SandboxWorkspaceWrite {
@@ -90,6 +482,21 @@ pub struct SandboxWorkspaceWrite {
     pub exclude_tmpdir_env_var: bool,
     #[serde(default)]
     pub exclude_slash_tmp: bool,
+
+    /// When set, restricts reads to these folders in addition to the
+    /// workspace, the writable roots and the platform's system directories.
+    #[serde(default)]
+    pub readable_roots: Option<Vec<PathBuf>>,
+
+    /// Extra paths that stay unreadable when `readable_roots` is set, on top
+    /// of the built-in list of credential locations.
+    #[serde(default)]
+    pub deny_read: Vec<PathBuf>,
+
+    /// Hosts (exact names or `*.domain` wildcards) that sandboxed commands
+    /// may reach through a filtering proxy while `network_access` is off.
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
 }
 
 impl From<SandboxWorkspaceWrite> for codex_protocol::mcp_protocol::SandboxSettings {
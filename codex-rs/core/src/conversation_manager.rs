@@ -238,6 +238,7 @@ mod tests {
                 name: "tool".to_string(),
                 arguments: "{}".to_string(),
                 call_id: "c1".to_string(),
+                thought_signature: None,
             },
             assistant_msg("a4"),
         ];
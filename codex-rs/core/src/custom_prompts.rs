@@ -1,9 +1,17 @@
+use codex_protocol::config_types::ReasoningEffort;
 use codex_protocol::custom_prompts::CustomPrompt;
+use mcp_types::ContentBlock;
+use mcp_types::EmbeddedResourceResource;
+use mcp_types::GetPromptResult;
+use mcp_types::Prompt as McpPrompt;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::path::Path;
 use std::path::PathBuf;
 use tokio::fs;
 
+use crate::git_info::get_git_repo_root;
+
 /// Return the default prompts directory: `$CODEX_HOME/prompts`.
 /// If `CODEX_HOME` cannot be resolved, returns `None`.
 pub fn default_prompts_dir() -> Option<PathBuf> {
@@ -12,6 +20,35 @@ pub fn default_prompts_dir() -> Option<PathBuf> {
         .map(|home| home.join("prompts"))
 }
 
+/// Return the project prompts directory: `.codex/prompts` at the root of the
+/// git repository containing `cwd`, or under `cwd` itself outside a repository.
+pub fn project_prompts_dir(cwd: &Path) -> PathBuf {
+    get_git_repo_root(cwd)
+        .unwrap_or_else(|| cwd.to_path_buf())
+        .join(".codex")
+        .join("prompts")
+}
+
+/// Discover prompts from `$CODEX_HOME/prompts` and the project prompts
+/// directory for `cwd`. A project prompt replaces a global prompt with the
+/// same name. Returns entries sorted by name.
+pub async fn discover_prompts(cwd: &Path) -> Vec<CustomPrompt> {
+    discover_layered_prompts(default_prompts_dir().as_deref(), &project_prompts_dir(cwd)).await
+}
+
+async fn discover_layered_prompts(
+    global_dir: Option<&Path>,
+    project_dir: &Path,
+) -> Vec<CustomPrompt> {
+    let mut prompts = discover_prompts_in(project_dir).await;
+    if let Some(dir) = global_dir {
+        let project_names: HashSet<String> = prompts.iter().map(|p| p.name.clone()).collect();
+        prompts.extend(discover_prompts_in_excluding(dir, &project_names).await);
+    }
+    prompts.sort_by(|a, b| a.name.cmp(&b.name));
+    prompts
+}
+
 /// Discover prompt files in the given directory, returning entries sorted by name.
 /// Non-files are ignored. If the directory does not exist or cannot be read, returns empty.
 pub async fn discover_prompts_in(dir: &Path) -> Vec<CustomPrompt> {
@@ -63,16 +100,193 @@ pub async fn discover_prompts_in_excluding(
             Ok(s) => s,
             Err(_) => continue,
         };
-        out.push(CustomPrompt {
-            name,
-            path,
-            content,
-        });
+        out.push(parse_prompt(name, path, &content));
     }
     out.sort_by(|a, b| a.name.cmp(&b.name));
     out
 }
 
+/// Build a prompt from a file's contents, reading the optional frontmatter:
+///
+/// ```text
+/// ---
+/// description: Review a file for security issues
+/// argument-hint: <file> [focus]
+/// model: gpt-5
+/// effort: high
+/// ---
+/// Review $1, paying special attention to $2.
+/// ```
+///
+/// This is not a YAML parser; only a subset of YAML is understood:
+/// - each line holds one `key: value` pair, split at the first `:`, so the
+///   value may itself contain colons;
+/// - a value wrapped in matching `"` or `'` quotes is unquoted, without
+///   processing escapes;
+/// - blank lines, lines starting with `#` and lines without a `:` are skipped;
+/// - indented lines (nested mappings, lists, continued values) and block
+///   scalars (`|`, `>`) are ignored, as are unknown keys and unknown efforts.
+pub fn parse_prompt(name: String, path: PathBuf, raw: &str) -> CustomPrompt {
+    let mut prompt = CustomPrompt {
+        name,
+        path,
+        ..Default::default()
+    };
+    let Some((frontmatter, body)) = split_frontmatter(raw) else {
+        prompt.content = raw.to_string();
+        return prompt;
+    };
+    prompt.content = body.to_string();
+
+    for line in frontmatter.lines() {
+        if line.starts_with(char::is_whitespace) {
+            continue;
+        }
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if value.is_empty() || value.starts_with(['|', '>']) {
+            continue;
+        }
+        let value = unquote(value);
+        match key.trim() {
+            "description" => prompt.description = Some(value.to_string()),
+            "argument-hint" | "argument_hint" => prompt.argument_hint = Some(value.to_string()),
+            "model" => prompt.model = Some(value.to_string()),
+            "effort" | "reasoning-effort" | "reasoning_effort" => {
+                prompt.effort = parse_reasoning_effort(value);
+            }
+            _ => {}
+        }
+    }
+    prompt
+}
+
+/// Splits `---` delimited frontmatter from the body. Returns `None` when the
+/// file does not start with a frontmatter block.
+fn split_frontmatter(raw: &str) -> Option<(&str, &str)> {
+    let rest = raw
+        .strip_prefix("---\n")
+        .or_else(|| raw.strip_prefix("---\r\n"))?;
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            let body = &rest[offset + line.len()..];
+            return Some((&rest[..offset], body));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|v| v.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
+
+fn parse_reasoning_effort(value: &str) -> Option<ReasoningEffort> {
+    match value.to_ascii_lowercase().as_str() {
+        "minimal" => Some(ReasoningEffort::Minimal),
+        "low" => Some(ReasoningEffort::Low),
+        "medium" => Some(ReasoningEffort::Medium),
+        "high" => Some(ReasoningEffort::High),
+        _ => None,
+    }
+}
+
+/// Substitute the arguments a prompt was invoked with. `$ARGUMENTS` becomes
+/// the full argument string and `$1`..`$9` the individual arguments, split
+/// like a shell would (quotes group words). Placeholders without a matching
+/// argument become empty; `$$` produces a literal `$`.
+pub fn expand_prompt_arguments(content: &str, arguments: &str) -> String {
+    let arguments = arguments.trim();
+    let positional: Vec<String> = shlex::split(arguments)
+        .unwrap_or_else(|| arguments.split_whitespace().map(str::to_string).collect());
+
+    let mut out = String::with_capacity(content.len() + arguments.len());
+    let mut rest = content;
+    while let Some(idx) = rest.find('$') {
+        out.push_str(&rest[..idx]);
+        let after = &rest[idx + 1..];
+        if let Some(tail) = after.strip_prefix("ARGUMENTS") {
+            out.push_str(arguments);
+            rest = tail;
+        } else if let Some(tail) = after.strip_prefix('$') {
+            out.push('$');
+            rest = tail;
+        } else if let Some(digit) = after.chars().next().filter(|c| ('1'..='9').contains(c)) {
+            let n = digit as usize - '1' as usize;
+            if let Some(arg) = positional.get(n) {
+                out.push_str(arg);
+            }
+            rest = &after[1..];
+        } else {
+            out.push('$');
+            rest = after;
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Split the arguments an MCP prompt was invoked with and assign them, in
+/// order, to the arguments the prompt declares. Extra words are appended to
+/// the last declared argument.
+pub fn mcp_prompt_arguments(prompt: &McpPrompt, arguments: &str) -> HashMap<String, String> {
+    let arguments = arguments.trim();
+    let mut values: Vec<String> = shlex::split(arguments)
+        .unwrap_or_else(|| arguments.split_whitespace().map(str::to_string).collect());
+    let names: Vec<&str> = prompt
+        .arguments
+        .iter()
+        .flatten()
+        .map(|argument| argument.name.as_str())
+        .collect();
+    if names.is_empty() {
+        return HashMap::new();
+    }
+    if values.len() > names.len() {
+        let rest = values.split_off(names.len() - 1).join(" ");
+        values.push(rest);
+    }
+    names
+        .into_iter()
+        .zip(values)
+        .map(|(name, value)| (name.to_string(), value))
+        .collect()
+}
+
+/// Flatten the messages of a rendered MCP prompt into the text of a single
+/// user message. Images and audio are skipped.
+pub fn mcp_prompt_text(result: &GetPromptResult) -> String {
+    result
+        .messages
+        .iter()
+        .filter_map(|message| match &message.content {
+            ContentBlock::TextContent(text) => Some(text.text.clone()),
+            ContentBlock::EmbeddedResource(embedded) => match &embedded.resource {
+                EmbeddedResourceResource::TextResourceContents(text) => Some(text.text.clone()),
+                EmbeddedResourceResource::BlobResourceContents(_) => None,
+            },
+            ContentBlock::ResourceLink(link) => Some(link.uri.clone()),
+            ContentBlock::ImageContent(_) | ContentBlock::AudioContent(_) => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -112,6 +326,143 @@ mod tests {
         assert_eq!(names, vec!["foo"]);
     }
 
+    #[tokio::test]
+    async fn project_prompts_override_global_ones() {
+        let tmp = tempdir().expect("create TempDir");
+        let cwd = tmp.path().join("repo");
+        let project_dir = project_prompts_dir(&cwd);
+        assert_eq!(project_dir, cwd.join(".codex").join("prompts"));
+        fs::create_dir_all(&project_dir).unwrap();
+        fs::write(project_dir.join("review.md"), b"project review").unwrap();
+        fs::write(project_dir.join("deploy.md"), b"deploy").unwrap();
+        let global_dir = tmp.path().join("home").join("prompts");
+        fs::create_dir_all(&global_dir).unwrap();
+        fs::write(global_dir.join("review.md"), b"global review").unwrap();
+        fs::write(global_dir.join("explain.md"), b"explain").unwrap();
+
+        let found = discover_layered_prompts(Some(&global_dir), &project_dir).await;
+        let found: Vec<(String, String)> = found.into_iter().map(|e| (e.name, e.content)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("deploy".to_string(), "deploy".to_string()),
+                ("explain".to_string(), "explain".to_string()),
+                ("review".to_string(), "project review".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_frontmatter() {
+        let prompt = parse_prompt(
+            "audit".to_string(),
+            PathBuf::from("/prompts/audit.md"),
+            "---\ndescription: \"Audit a file\"\nargument-hint: <file> [focus]\nmodel: gpt-5\neffort: High\nunknown: ignored\n---\nAudit $1.\n",
+        );
+        assert_eq!(
+            prompt,
+            CustomPrompt {
+                name: "audit".to_string(),
+                path: PathBuf::from("/prompts/audit.md"),
+                content: "Audit $1.\n".to_string(),
+                description: Some("Audit a file".to_string()),
+                argument_hint: Some("<file> [focus]".to_string()),
+                model: Some("gpt-5".to_string()),
+                effort: Some(ReasoningEffort::High),
+            }
+        );
+
+        // Without a closing delimiter the whole file is the prompt.
+        let prompt = parse_prompt(
+            "plain".to_string(),
+            PathBuf::from("plain.md"),
+            "---\nnot frontmatter",
+        );
+        assert_eq!(prompt.content, "---\nnot frontmatter");
+        assert_eq!(prompt.description, None);
+    }
+
+    #[test]
+    fn frontmatter_supports_only_flat_pairs() {
+        let prompt = parse_prompt(
+            "subset".to_string(),
+            PathBuf::from("subset.md"),
+            "---\n\
+             # a comment: not a key\n\
+             description: Compare: old vs new\n\
+             argument_hint: '<a> \"<b>\"'\n\
+             model: >\n  folded-model\n\
+             reasoning_effort: extreme\n\
+             nested:\n  description: from a mapping\n\
+             no colon here\n\
+             ---\n\
+             Body\n",
+        );
+        assert_eq!(
+            prompt,
+            CustomPrompt {
+                name: "subset".to_string(),
+                path: PathBuf::from("subset.md"),
+                content: "Body\n".to_string(),
+                description: Some("Compare: old vs new".to_string()),
+                argument_hint: Some("<a> \"<b>\"".to_string()),
+                model: None,
+                effort: None,
+            }
+        );
+    }
+
+    #[test]
+    fn expands_arguments() {
+        let content = "Fix $1 in $2 ($ARGUMENTS) for $$5, not $3.";
+        assert_eq!(
+            expand_prompt_arguments(content, r#" "login bug" src/auth.rs "#),
+            r#"Fix login bug in src/auth.rs ("login bug" src/auth.rs) for $5, not ."#
+        );
+        assert_eq!(
+            expand_prompt_arguments("cost: $ARGS $", ""),
+            "cost: $ARGS $"
+        );
+    }
+
+    #[test]
+    fn assigns_mcp_prompt_arguments_in_order() {
+        let prompt: McpPrompt = serde_json::from_value(serde_json::json!({
+            "name": "release-notes",
+            "arguments": [{ "name": "version" }, { "name": "focus" }],
+        }))
+        .unwrap();
+        assert_eq!(
+            mcp_prompt_arguments(&prompt, r#"1.2.0 "breaking changes" only"#),
+            HashMap::from([
+                ("version".to_string(), "1.2.0".to_string()),
+                ("focus".to_string(), "breaking changes only".to_string()),
+            ])
+        );
+        assert_eq!(
+            mcp_prompt_arguments(&prompt, "1.2.0"),
+            HashMap::from([("version".to_string(), "1.2.0".to_string())])
+        );
+    }
+
+    #[test]
+    fn flattens_mcp_prompt_messages() {
+        let result: GetPromptResult = serde_json::from_value(serde_json::json!({
+            "messages": [
+                { "role": "user", "content": { "type": "text", "text": "Write release notes." } },
+                { "role": "user", "content": { "type": "image", "data": "AAAA", "mimeType": "image/png" } },
+                { "role": "user", "content": { "type": "resource", "resource": {
+                    "uri": "docs://changelog", "text": "- fixed a bug"
+                } } },
+            ],
+        }))
+        .unwrap();
+        assert_eq!(
+            mcp_prompt_text(&result),
+            "Write release notes.\n\n- fixed a bug"
+        );
+    }
+
     #[tokio::test]
     async fn skips_non_utf8_files() {
         let tmp = tempdir().expect("create TempDir");
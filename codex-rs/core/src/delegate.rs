@@ -0,0 +1,469 @@
+//! The `delegate` tool: runs sub-agents ("workers") in parallel conversations
+//! of their own and returns their final messages to the model.
+//!
+//! Every worker is a separate [`Codex`] session in the parent's working
+//! directory, so it is recorded as its own rollout. Workers never ask for
+//! approval, cannot delegate further, and run in a sandbox that is at most as
+//! permissive as the parent's. Since they cannot ask, they only run without a
+//! sandbox when the parent would not ask either. Their events are forwarded
+//! to the parent wrapped in [`EventMsg::DelegateEvent`].
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use codex_protocol::config_types::SandboxMode;
+use codex_protocol::models::FunctionCallOutputPayload;
+use codex_protocol::models::ResponseInputItem;
+use futures::future::join_all;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::AuthManager;
+use crate::codex::Codex;
+use crate::codex::CodexSpawnOk;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::config::Config;
+use crate::config_types::Notifications;
+use crate::protocol::AskForApproval;
+use crate::protocol::DelegateBeginEvent;
+use crate::protocol::DelegateEndEvent;
+use crate::protocol::DelegateEventEvent;
+use crate::protocol::ErrorEvent;
+use crate::protocol::Event;
+use crate::protocol::EventMsg;
+use crate::protocol::InitialHistory;
+use crate::protocol::InputItem;
+use crate::protocol::Op;
+use crate::protocol::ReviewDecision;
+use crate::protocol::SandboxPolicy;
+use crate::protocol::TaskCompleteEvent;
+
+/// Maximum number of workers a single `delegate` call may start.
+pub(crate) const MAX_DELEGATE_TASKS: usize = 8;
+
+/// Names of the optional built-in tools that the `tools` argument can keep.
+const OPTIONAL_TOOLS: [&str; 4] = ["update_plan", "apply_patch", "view_image", "web_search"];
+
+#[derive(Debug, Deserialize)]
+struct DelegateArgs {
+    tasks: Vec<DelegateTask>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DelegateTask {
+    prompt: String,
+    #[serde(default)]
+    instructions: Option<String>,
+    #[serde(default)]
+    tools: Option<Vec<String>>,
+    #[serde(default)]
+    sandbox: Option<SandboxMode>,
+}
+
+pub(crate) async fn handle_delegate_tool_call(
+    sess: &Session,
+    turn_context: &TurnContext,
+    sub_id: &str,
+    call_id: String,
+    arguments: String,
+) -> ResponseInputItem {
+    let (content, success) = match delegate(sess, turn_context, sub_id, &call_id, arguments).await {
+        Ok(results) => {
+            let success = results.iter().all(Result::is_ok);
+            (results_to_output(&results), success)
+        }
+        Err(err) => (err, false),
+    };
+    ResponseInputItem::FunctionCallOutput {
+        call_id,
+        output: FunctionCallOutputPayload {
+            content,
+            success: Some(success),
+        },
+    }
+}
+
+/// Validates the arguments, runs all workers and returns their results, or
+/// an error if no worker could be started.
+async fn delegate(
+    sess: &Session,
+    turn_context: &TurnContext,
+    sub_id: &str,
+    call_id: &str,
+    arguments: String,
+) -> Result<Vec<Result<String, String>>, String> {
+    let args = serde_json::from_str::<DelegateArgs>(&arguments)
+        .map_err(|e| format!("failed to parse function arguments: {e}"))?;
+    if args.tasks.is_empty() {
+        return Err("`tasks` must not be empty".to_string());
+    }
+    if args.tasks.len() > MAX_DELEGATE_TASKS {
+        return Err(format!(
+            "at most {MAX_DELEGATE_TASKS} tasks can be delegated at once"
+        ));
+    }
+    let auth_manager = turn_context
+        .client
+        .get_auth_manager()
+        .ok_or_else(|| "delegating requires an authenticated session".to_string())?;
+
+    let parent_config = turn_context.client.get_config();
+    let mut workers = Vec::with_capacity(args.tasks.len());
+    for (index, task) in args.tasks.into_iter().enumerate() {
+        let config = worker_config(&parent_config, turn_context, &task)
+            .map_err(|e| format!("task {}: {e}", index + 1))?;
+        workers.push((config, task.prompt));
+    }
+
+    sess.send_event(Event {
+        id: sub_id.to_string(),
+        msg: EventMsg::DelegateBegin(DelegateBeginEvent {
+            call_id: call_id.to_string(),
+            tasks: workers.iter().map(|(_, prompt)| prompt.clone()).collect(),
+        }),
+    })
+    .await;
+
+    let results = join_all(
+        workers
+            .into_iter()
+            .enumerate()
+            .map(|(worker, (config, prompt))| {
+                run_worker(
+                    sess,
+                    sub_id,
+                    call_id,
+                    worker,
+                    config,
+                    auth_manager.clone(),
+                    prompt,
+                )
+            }),
+    )
+    .await;
+
+    sess.send_event(Event {
+        id: sub_id.to_string(),
+        msg: EventMsg::DelegateEnd(DelegateEndEvent {
+            call_id: call_id.to_string(),
+            results: results.clone(),
+        }),
+    })
+    .await;
+
+    Ok(results)
+}
+
+/// Derives the configuration of a worker from the parent's.
+fn worker_config(
+    parent: &Config,
+    turn_context: &TurnContext,
+    task: &DelegateTask,
+) -> Result<Config, String> {
+    let mut config = parent.clone();
+    config.cwd = turn_context.cwd.clone();
+    config.approval_policy = AskForApproval::Never;
+    config.sandbox_policy = worker_sandbox_policy(
+        &turn_context.sandbox_policy,
+        turn_context.approval_policy,
+        task.sandbox,
+    )?;
+    config.base_instructions = turn_context.base_instructions.clone();
+    config.model_reasoning_effort = turn_context.client.get_reasoning_effort();
+    config.model_reasoning_summary = turn_context.client.get_reasoning_summary();
+    config.include_delegate_tool = false;
+    config.worktree = false;
+    config.checkpoints = false;
+    config.notify = None;
+    config.notifications = Notifications::default();
+
+    if let Some(instructions) = &task.instructions {
+        config.user_instructions = Some(match config.user_instructions.take() {
+            Some(existing) => format!("{existing}\n\n{instructions}"),
+            None => instructions.clone(),
+        });
+    }
+
+    if let Some(tools) = &task.tools {
+        let tools: HashSet<&str> = tools.iter().map(String::as_str).collect();
+        if let Some(unknown) = tools
+            .iter()
+            .find(|name| !OPTIONAL_TOOLS.contains(name) && !parent.mcp_servers.contains_key(**name))
+        {
+            return Err(format!("unknown tool `{unknown}`"));
+        }
+        config.include_plan_tool &= tools.contains("update_plan");
+        config.include_apply_patch_tool &= tools.contains("apply_patch");
+        config.include_view_image_tool &= tools.contains("view_image");
+        config.tools_web_search_request &= tools.contains("web_search");
+        config
+            .mcp_servers
+            .retain(|name, _| tools.contains(name.as_str()));
+    }
+
+    Ok(config)
+}
+
+/// Resolves the sandbox a worker asked for, refusing anything more permissive
+/// than the parent's sandbox. Workers run with [`AskForApproval::Never`], so
+/// running one without a sandbox is refused unless the parent does not ask
+/// for approval either.
+fn worker_sandbox_policy(
+    parent: &SandboxPolicy,
+    parent_approval: AskForApproval,
+    requested: Option<SandboxMode>,
+) -> Result<SandboxPolicy, String> {
+    let policy = resolve_worker_sandbox_policy(parent, requested)?;
+    if matches!(policy, SandboxPolicy::DangerFullAccess) && parent_approval != AskForApproval::Never
+    {
+        return Err(format!(
+            "workers cannot ask for approval, so without the `never` approval policy they need a sandbox; request `{}` or `{}`",
+            SandboxMode::WorkspaceWrite,
+            SandboxMode::ReadOnly
+        ));
+    }
+    Ok(policy)
+}
+
+fn resolve_worker_sandbox_policy(
+    parent: &SandboxPolicy,
+    requested: Option<SandboxMode>,
+) -> Result<SandboxPolicy, String> {
+    match (requested, parent) {
+        (None, _) => Ok(parent.clone()),
+        // `read-only` can read the entire disk, so it is refused when the
+        // parent only reads its readable roots.
+        (Some(SandboxMode::ReadOnly), _) if parent.has_full_disk_read_access() => {
+            Ok(SandboxPolicy::new_read_only_policy())
+        }
+        (Some(SandboxMode::ReadOnly), _) => Err(format!(
+            "sandbox `{}` can read files the current sandbox cannot; leave `sandbox` unset to keep the current sandbox",
+            SandboxMode::ReadOnly
+        )),
+        (Some(SandboxMode::WorkspaceWrite), SandboxPolicy::WorkspaceWrite { .. }) => {
+            Ok(parent.clone())
+        }
+        (Some(SandboxMode::WorkspaceWrite), SandboxPolicy::DangerFullAccess) => {
+            Ok(SandboxPolicy::new_workspace_write_policy())
+        }
+        (Some(SandboxMode::DangerFullAccess), SandboxPolicy::DangerFullAccess) => {
+            Ok(SandboxPolicy::DangerFullAccess)
+        }
+        (Some(requested), _) => Err(format!(
+            "sandbox `{requested}` is more permissive than the current sandbox"
+        )),
+    }
+}
+
+/// Runs one worker to completion and returns its final message.
+async fn run_worker(
+    sess: &Session,
+    sub_id: &str,
+    call_id: &str,
+    worker: usize,
+    config: Config,
+    auth_manager: Arc<AuthManager>,
+    prompt: String,
+) -> Result<String, String> {
+    let forward = |msg: EventMsg| {
+        sess.send_event(Event {
+            id: sub_id.to_string(),
+            msg: EventMsg::DelegateEvent(DelegateEventEvent {
+                call_id: call_id.to_string(),
+                worker,
+                msg: Box::new(msg),
+            }),
+        })
+    };
+
+    let CodexSpawnOk { codex, .. } =
+        match Codex::spawn(config, auth_manager, InitialHistory::New).await {
+            Ok(spawned) => spawned,
+            Err(e) => {
+                let message = format!("failed to start worker: {e}");
+                forward(EventMsg::Error(ErrorEvent {
+                    message: message.clone(),
+                }))
+                .await;
+                return Err(message);
+            }
+        };
+    let codex = WorkerGuard(Arc::new(codex));
+
+    let task_id = codex
+        .0
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text { text: prompt }],
+        })
+        .await
+        .map_err(|e| format!("failed to start worker: {e}"))?;
+
+    let mut last_error = None;
+    loop {
+        let event = codex.0.next_event().await.map_err(|e| e.to_string())?;
+        // Workers run unattended, so MCP tool calls that need the user's
+        // approval are denied.
+        if let EventMsg::McpToolCallApprovalRequest(_) = &event.msg {
+            codex
+                .0
+                .submit(Op::McpToolCallApproval {
+                    id: event.id.clone(),
+                    decision: ReviewDecision::Denied,
+                })
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        let result = if event.id != task_id {
+            None
+        } else {
+            match &event.msg {
+                EventMsg::Error(ErrorEvent { message }) => {
+                    last_error = Some(message.clone());
+                    None
+                }
+                EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) => {
+                    Some(match (last_agent_message, last_error.take()) {
+                        (None, Some(error)) => Err(error),
+                        (message, _) => Ok(message.clone().unwrap_or_default()),
+                    })
+                }
+                EventMsg::TurnAborted(_) => Some(Err("worker was interrupted".to_string())),
+                _ => None,
+            }
+        };
+        forward(event.msg).await;
+        if let Some(result) = result {
+            return result;
+        }
+    }
+}
+
+/// Stops a worker's conversation when the worker finishes, or when the
+/// parent's task is aborted while the worker is still running.
+struct WorkerGuard(Arc<Codex>);
+
+impl Drop for WorkerGuard {
+    fn drop(&mut self) {
+        let codex = self.0.clone();
+        tokio::spawn(async move {
+            let _ = codex.submit(Op::Interrupt).await;
+            let _ = codex.submit(Op::Shutdown).await;
+        });
+    }
+}
+
+/// Formats the results of all workers as the output of the function call.
+fn results_to_output(results: &[Result<String, String>]) -> String {
+    let workers: Vec<_> = results
+        .iter()
+        .enumerate()
+        .map(|(index, result)| match result {
+            Ok(message) => json!({ "task": index + 1, "message": message }),
+            Err(error) => json!({ "task": index + 1, "error": error }),
+        })
+        .collect();
+    json!({ "results": workers }).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn worker_sandbox_is_never_broader_than_parent() {
+        let read_only = SandboxPolicy::new_read_only_policy();
+        let workspace_write = SandboxPolicy::new_workspace_write_policy();
+        let full = SandboxPolicy::DangerFullAccess;
+        let never = AskForApproval::Never;
+
+        assert_eq!(
+            worker_sandbox_policy(&workspace_write, never, None),
+            Ok(workspace_write.clone())
+        );
+        assert_eq!(
+            worker_sandbox_policy(&full, never, Some(SandboxMode::ReadOnly)),
+            Ok(read_only.clone())
+        );
+        assert_eq!(
+            worker_sandbox_policy(&full, never, Some(SandboxMode::WorkspaceWrite)),
+            Ok(workspace_write.clone())
+        );
+        assert_eq!(
+            worker_sandbox_policy(&full, never, Some(SandboxMode::DangerFullAccess)),
+            Ok(full.clone())
+        );
+        assert!(
+            worker_sandbox_policy(&read_only, never, Some(SandboxMode::WorkspaceWrite)).is_err()
+        );
+        assert!(
+            worker_sandbox_policy(&workspace_write, never, Some(SandboxMode::DangerFullAccess))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn read_only_worker_never_gains_read_access() {
+        let restricted = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![],
+            network_access: false,
+            exclude_tmpdir_env_var: false,
+            exclude_slash_tmp: false,
+            readable_roots: Some(vec![PathBuf::from("/workspace")]),
+            deny_read: vec![PathBuf::from("/workspace/.env")],
+            allowed_hosts: vec!["example.com".to_string()],
+        };
+
+        assert!(
+            worker_sandbox_policy(
+                &restricted,
+                AskForApproval::Never,
+                Some(SandboxMode::ReadOnly)
+            )
+            .is_err()
+        );
+        assert_eq!(
+            worker_sandbox_policy(&restricted, AskForApproval::Never, None),
+            Ok(restricted.clone())
+        );
+    }
+
+    #[test]
+    fn unsandboxed_workers_require_never_approval_policy() {
+        let full = SandboxPolicy::DangerFullAccess;
+        for approval in [
+            AskForApproval::UnlessTrusted,
+            AskForApproval::OnFailure,
+            AskForApproval::OnRequest,
+        ] {
+            // The parent would ask before running commands outside the
+            // sandbox, which workers cannot do.
+            assert!(worker_sandbox_policy(&full, approval, None).is_err());
+            assert!(
+                worker_sandbox_policy(&full, approval, Some(SandboxMode::DangerFullAccess))
+                    .is_err()
+            );
+            assert_eq!(
+                worker_sandbox_policy(&full, approval, Some(SandboxMode::WorkspaceWrite)),
+                Ok(SandboxPolicy::new_workspace_write_policy())
+            );
+        }
+    }
+
+    #[test]
+    fn results_are_reported_per_task() {
+        let output = results_to_output(&[Ok("done".to_string()), Err("failed".to_string())]);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&output).ok(),
+            Some(json!({
+                "results": [
+                    { "task": 1, "message": "done" },
+                    { "task": 2, "error": "failed" },
+                ]
+            }))
+        );
+    }
+}
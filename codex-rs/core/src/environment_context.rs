@@ -27,6 +27,8 @@ pub(crate) struct EnvironmentContext {
     pub sandbox_mode: Option<SandboxMode>,
     pub network_access: Option<NetworkAccess>,
     pub writable_roots: Option<Vec<PathBuf>>,
+    pub readable_roots: Option<Vec<PathBuf>>,
+    pub deny_read: Option<Vec<PathBuf>>,
     pub shell: Option<Shell>,
 }
 
@@ -37,6 +39,14 @@ impl EnvironmentContext {
         sandbox_policy: Option<SandboxPolicy>,
         shell: Option<Shell>,
     ) -> Self {
+        let readable_roots = match &sandbox_policy {
+            Some(SandboxPolicy::WorkspaceWrite { readable_roots, .. }) => readable_roots.clone(),
+            _ => None,
+        };
+        let deny_read = sandbox_policy
+            .as_ref()
+            .map(SandboxPolicy::get_denied_read_paths)
+            .filter(|paths| !paths.is_empty());
         Self {
             cwd,
             approval_policy,
@@ -68,6 +78,8 @@ impl EnvironmentContext {
                 }
                 _ => None,
             },
+            readable_roots,
+            deny_read,
             shell,
         }
     }
@@ -84,6 +96,8 @@ impl EnvironmentContext {
     ///   <approval_policy>...</approval_policy>
     ///   <sandbox_mode>...</sandbox_mode>
     ///   <writable_roots>...</writable_roots>
+    ///   <readable_roots>...</readable_roots>
+    ///   <deny_read>...</deny_read>
     ///   <network_access>...</network_access>
     ///   <shell>...</shell>
     /// </environment_context>
@@ -116,6 +130,23 @@ impl EnvironmentContext {
             }
             lines.push("  </writable_roots>".to_string());
         }
+        if let Some(readable_roots) = self.readable_roots {
+            lines.push("  <readable_roots>".to_string());
+            for readable_root in readable_roots {
+                lines.push(format!(
+                    "    <root>{}</root>",
+                    readable_root.to_string_lossy()
+                ));
+            }
+            lines.push("  </readable_roots>".to_string());
+        }
+        if let Some(deny_read) = self.deny_read {
+            lines.push("  <deny_read>".to_string());
+            for path in deny_read {
+                lines.push(format!("    <path>{}</path>", path.to_string_lossy()));
+            }
+            lines.push("  </deny_read>".to_string());
+        }
         if let Some(shell) = self.shell
             && let Some(shell_name) = shell.name()
         {
@@ -149,6 +180,9 @@ mod tests {
             network_access,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            readable_roots: None,
+            deny_read: Vec::new(),
+            allowed_hosts: Vec::new(),
         }
     }
 
@@ -175,6 +209,39 @@ mod tests {
         assert_eq!(context.serialize_to_xml(), expected);
     }
 
+    #[test]
+    fn serialize_restricted_read_environment_context() {
+        let context = EnvironmentContext::new(
+            Some(PathBuf::from("/repo")),
+            Some(AskForApproval::OnRequest),
+            Some(SandboxPolicy::WorkspaceWrite {
+                writable_roots: vec![],
+                network_access: false,
+                exclude_tmpdir_env_var: false,
+                exclude_slash_tmp: false,
+                readable_roots: Some(vec![PathBuf::from("/home/user/.cargo")]),
+                deny_read: vec![PathBuf::from("/home/user/.ssh")],
+                allowed_hosts: Vec::new(),
+            }),
+            None,
+        );
+
+        let expected = r#"<environment_context>
+  <cwd>/repo</cwd>
+  <approval_policy>on-request</approval_policy>
+  <sandbox_mode>workspace-write</sandbox_mode>
+  <network_access>restricted</network_access>
+  <readable_roots>
+    <root>/home/user/.cargo</root>
+  </readable_roots>
+  <deny_read>
+    <path>/home/user/.ssh</path>
+  </deny_read>
+</environment_context>"#;
+
+        assert_eq!(context.serialize_to_xml(), expected);
+    }
+
     #[test]
     fn serialize_read_only_environment_context() {
         let context = EnvironmentContext::new(
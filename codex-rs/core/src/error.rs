@@ -97,6 +97,10 @@ pub enum CodexErr {
     #[error("internal error; agent loop died unexpectedly")]
     InternalAgentDied,
 
+    /// Creating the git worktree requested for a conversation failed.
+    #[error("failed to create git worktree: {0}")]
+    Worktree(String),
+
     /// Sandbox error
     #[error("sandbox error: {0}")]
     Sandbox(#[from] SandboxErr),
@@ -2,13 +2,16 @@
 use std::os::unix::process::ExitStatusExt;
 
 use std::collections::HashMap;
+use std::ffi::OsString;
 use std::io;
+use std::path::Path;
 use std::path::PathBuf;
 use std::process::ExitStatus;
 use std::time::Duration;
 use std::time::Instant;
 
 use async_channel::Sender;
+use portable_pty::CommandBuilder;
 use tokio::io::AsyncRead;
 use tokio::io::AsyncReadExt;
 use tokio::io::BufReader;
@@ -17,13 +20,21 @@ use tokio::process::Child;
 use crate::error::CodexErr;
 use crate::error::Result;
 use crate::error::SandboxErr;
+use crate::landlock::CODEX_LINUX_SANDBOX_ARG1;
+use crate::landlock::create_linux_sandbox_command_args;
 use crate::landlock::spawn_command_under_linux_sandbox;
+use crate::network_proxy::NetworkProxy;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecCommandOutputDeltaEvent;
 use crate::protocol::ExecOutputStream;
 use crate::protocol::SandboxPolicy;
+use crate::seatbelt::MACOS_PATH_TO_SEATBELT_EXECUTABLE;
+use crate::seatbelt::create_seatbelt_command_args;
 use crate::seatbelt::spawn_command_under_seatbelt;
+use crate::spawn::CODEX_NETWORK_PROXY_SOCKET_ENV_VAR;
+use crate::spawn::CODEX_SANDBOX_ENV_VAR;
+use crate::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
 use crate::spawn::StdioPolicy;
 use crate::spawn::spawn_child_async;
 
@@ -88,6 +99,7 @@ pub async fn process_exec_tool_call(
     let start = Instant::now();
 
     let timeout_duration = params.timeout_duration();
+    let mut denied_hosts = Vec::new();
 
     let raw_output_result: std::result::Result<RawExecToolCallOutput, CodexErr> = match sandbox_type
     {
@@ -108,12 +120,30 @@ pub async fn process_exec_tool_call(
         }
         SandboxType::LinuxSeccomp => {
             let ExecParams {
-                command, cwd, env, ..
+                command,
+                cwd,
+                mut env,
+                ..
             } = params;
 
             let codex_linux_sandbox_exe = codex_linux_sandbox_exe
                 .as_ref()
                 .ok_or(CodexErr::LandlockSandboxExecutableNotProvided)?;
+
+            // The helper routes traffic for allowlisted hosts through this
+            // proxy; without an allowlist it blocks the network entirely.
+            let allowed_hosts = sandbox_policy.get_allowed_hosts();
+            let network_proxy = if allowed_hosts.is_empty() {
+                None
+            } else {
+                let proxy = NetworkProxy::start(allowed_hosts.to_vec())?;
+                env.insert(
+                    CODEX_NETWORK_PROXY_SOCKET_ENV_VAR.to_string(),
+                    proxy.socket_path().to_string_lossy().into_owned(),
+                );
+                Some(proxy)
+            };
+
             let child = spawn_command_under_linux_sandbox(
                 codex_linux_sandbox_exe,
                 command,
@@ -124,7 +154,11 @@ pub async fn process_exec_tool_call(
             )
             .await?;
 
-            consume_truncated_output(child, timeout_duration, stdout_stream).await
+            let output = consume_truncated_output(child, timeout_duration, stdout_stream).await;
+            if let Some(proxy) = network_proxy {
+                denied_hosts = proxy.denied_hosts();
+            }
+            output
         }
     };
     let duration = start.elapsed();
@@ -159,6 +193,7 @@ pub async fn process_exec_tool_call(
                 aggregated_output,
                 duration,
                 timed_out,
+                denied_hosts,
             };
 
             if timed_out {
@@ -182,6 +217,59 @@ pub async fn process_exec_tool_call(
     }
 }
 
+/// Sandbox settings for commands that run in a PTY (the `exec_command` and
+/// `unified_exec` tools). Those sessions are spawned through `portable_pty`
+/// rather than [`process_exec_tool_call`], but must be confined the same way.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PtySandbox<'a> {
+    pub sandbox_type: SandboxType,
+    pub sandbox_policy: &'a SandboxPolicy,
+    pub codex_linux_sandbox_exe: &'a Option<PathBuf>,
+    pub cwd: &'a Path,
+}
+
+impl PtySandbox<'_> {
+    /// Returns a PTY command that runs `command` under this sandbox.
+    pub(crate) fn command_builder(&self, command: Vec<String>) -> Result<CommandBuilder> {
+        let mut command_builder = match self.sandbox_type {
+            SandboxType::None => {
+                CommandBuilder::from_argv(command.into_iter().map(OsString::from).collect())
+            }
+            SandboxType::MacosSeatbelt => {
+                let mut command_builder = CommandBuilder::new(MACOS_PATH_TO_SEATBELT_EXECUTABLE);
+                command_builder.args(create_seatbelt_command_args(
+                    command,
+                    self.sandbox_policy,
+                    self.cwd,
+                ));
+                command_builder.env(CODEX_SANDBOX_ENV_VAR, "seatbelt");
+                command_builder
+            }
+            SandboxType::LinuxSeccomp => {
+                let codex_linux_sandbox_exe = self
+                    .codex_linux_sandbox_exe
+                    .as_ref()
+                    .ok_or(CodexErr::LandlockSandboxExecutableNotProvided)?;
+                // portable_pty always uses the program path as argv[0], so the
+                // helper is selected with an argument instead of an alias.
+                let mut command_builder = CommandBuilder::new(codex_linux_sandbox_exe);
+                command_builder.arg(CODEX_LINUX_SANDBOX_ARG1);
+                command_builder.args(create_linux_sandbox_command_args(
+                    command,
+                    self.sandbox_policy,
+                    self.cwd,
+                ));
+                command_builder
+            }
+        };
+        command_builder.cwd(self.cwd);
+        if !self.sandbox_policy.has_full_network_access() {
+            command_builder.env(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR, "1");
+        }
+        Ok(command_builder)
+    }
+}
+
 /// We don't have a fully deterministic way to tell if our command failed
 /// because of the sandbox - a command in the user's zshrc file might hit an
 /// error, but the command itself might fail or succeed for other reasons.
@@ -247,6 +335,8 @@ pub struct ExecToolCallOutput {
     pub aggregated_output: StreamOutput<String>,
     pub duration: Duration,
     pub timed_out: bool,
+    /// Hosts the sandbox's network proxy refused to connect to.
+    pub denied_hosts: Vec<String>,
 }
 
 async fn exec(
@@ -426,3 +516,90 @@ fn synthetic_exit_status(code: i32) -> ExitStatus {
     #[expect(clippy::unwrap_used)]
     std::process::ExitStatus::from_raw(code.try_into().unwrap())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn argv(command_builder: &CommandBuilder) -> Vec<String> {
+        command_builder
+            .get_argv()
+            .iter()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn pty_command_without_sandbox_runs_command_directly() {
+        let cwd = PathBuf::from("/work");
+        let sandbox = PtySandbox {
+            sandbox_type: SandboxType::None,
+            sandbox_policy: &SandboxPolicy::DangerFullAccess,
+            codex_linux_sandbox_exe: &None,
+            cwd: &cwd,
+        };
+
+        let command_builder = sandbox
+            .command_builder(vec!["bash".to_string(), "-i".to_string()])
+            .unwrap();
+
+        assert_eq!(vec!["bash", "-i"], argv(&command_builder));
+        assert_eq!(Some(&OsString::from("/work")), command_builder.get_cwd());
+        assert_eq!(
+            None,
+            command_builder.get_env(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR)
+        );
+    }
+
+    #[test]
+    fn pty_command_under_linux_sandbox_reinvokes_helper() {
+        let cwd = PathBuf::from("/work");
+        let sandbox_policy = SandboxPolicy::new_read_only_policy();
+        let sandbox = PtySandbox {
+            sandbox_type: SandboxType::LinuxSeccomp,
+            sandbox_policy: &sandbox_policy,
+            codex_linux_sandbox_exe: &Some(PathBuf::from("/usr/bin/codex")),
+            cwd: &cwd,
+        };
+
+        let command_builder = sandbox
+            .command_builder(vec!["bash".to_string(), "-i".to_string()])
+            .unwrap();
+
+        let expected = vec![
+            "/usr/bin/codex".to_string(),
+            CODEX_LINUX_SANDBOX_ARG1.to_string(),
+            "/work".to_string(),
+            serde_json::to_string(&sandbox_policy).unwrap(),
+            "--".to_string(),
+            "bash".to_string(),
+            "-i".to_string(),
+        ];
+        assert_eq!(expected, argv(&command_builder));
+        assert_eq!(
+            Some(std::ffi::OsStr::new("1")),
+            command_builder.get_env(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR)
+        );
+    }
+
+    #[test]
+    fn pty_command_under_linux_sandbox_requires_helper() {
+        let cwd = PathBuf::from("/work");
+        let sandbox = PtySandbox {
+            sandbox_type: SandboxType::LinuxSeccomp,
+            sandbox_policy: &SandboxPolicy::new_read_only_policy(),
+            codex_linux_sandbox_exe: &None,
+            cwd: &cwd,
+        };
+
+        let err = sandbox
+            .command_builder(vec!["bash".to_string()])
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            CodexErr::LandlockSandboxExecutableNotProvided
+        ));
+    }
+}
@@ -6,7 +6,6 @@ use std::sync::Mutex as StdMutex;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicU32;
 
-use portable_pty::CommandBuilder;
 use portable_pty::PtySize;
 use portable_pty::native_pty_system;
 use tokio::sync::Mutex;
@@ -16,6 +15,7 @@ use tokio::time::Duration;
 use tokio::time::Instant;
 use tokio::time::timeout;
 
+use crate::exec::PtySandbox;
 use crate::exec_command::exec_command_params::ExecCommandParams;
 use crate::exec_command::exec_command_params::WriteStdinParams;
 use crate::exec_command::exec_command_session::ExecCommandSession;
@@ -83,9 +83,10 @@ pub fn result_into_payload(result: Result<ExecCommandOutput, String>) -> Functio
 
 impl SessionManager {
     /// Processes the request and is required to send a response via `outgoing`.
-    pub async fn handle_exec_command_request(
+    pub(crate) async fn handle_exec_command_request(
         &self,
         params: ExecCommandParams,
+        sandbox: PtySandbox<'_>,
     ) -> Result<ExecCommandOutput, String> {
         // Allocate a session id.
         let session_id = SessionId(
@@ -93,15 +94,14 @@ impl SessionManager {
                 .fetch_add(1, std::sync::atomic::Ordering::SeqCst),
         );
 
-        let (session, mut exit_rx) =
-            create_exec_command_session(params.clone())
-                .await
-                .map_err(|err| {
-                    format!(
-                        "failed to create exec command session for session id {}: {err}",
-                        session_id.0
-                    )
-                })?;
+        let (session, mut exit_rx) = create_exec_command_session(&params, sandbox)
+            .await
+            .map_err(|err| {
+                format!(
+                    "failed to create exec command session for session id {}: {err}",
+                    session_id.0
+                )
+            })?;
 
         // Insert into session map.
         let mut output_rx = session.output_receiver();
@@ -244,16 +244,9 @@ impl SessionManager {
 
 /// Spawn PTY and child process per spawn_exec_command_session logic.
 async fn create_exec_command_session(
-    params: ExecCommandParams,
+    params: &ExecCommandParams,
+    sandbox: PtySandbox<'_>,
 ) -> anyhow::Result<(ExecCommandSession, oneshot::Receiver<i32>)> {
-    let ExecCommandParams {
-        cmd,
-        yield_time_ms: _,
-        max_output_tokens: _,
-        shell,
-        login,
-    } = params;
-
     // Use the native pty implementation for the system
     let pty_system = native_pty_system();
 
@@ -266,10 +259,7 @@ async fn create_exec_command_session(
     })?;
 
     // Spawn a shell into the pty
-    let mut command_builder = CommandBuilder::new(shell);
-    let shell_mode_opt = if login { "-lc" } else { "-c" };
-    command_builder.arg(shell_mode_opt);
-    command_builder.arg(cmd);
+    let command_builder = sandbox.command_builder(params.command())?;
 
     let mut child = pair.slave.spawn_command(command_builder)?;
     // Obtain a killer that can signal the process independently of `.wait()`.
@@ -366,8 +356,10 @@ mod tests {
     #[allow(clippy::print_stderr)]
     #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
     async fn session_manager_streams_and_truncates_from_now() {
+        use crate::exec::SandboxType;
         use crate::exec_command::exec_command_params::ExecCommandParams;
         use crate::exec_command::exec_command_params::WriteStdinParams;
+        use crate::protocol::SandboxPolicy;
         use tokio::time::sleep;
 
         let session_manager = SessionManager::default();
@@ -392,8 +384,15 @@ PY"#
             shell: "/bin/bash".to_string(),
             login: false,
         };
+        let cwd = std::env::temp_dir();
+        let sandbox = PtySandbox {
+            sandbox_type: SandboxType::None,
+            sandbox_policy: &SandboxPolicy::DangerFullAccess,
+            codex_linux_sandbox_exe: &None,
+            cwd: &cwd,
+        };
         let initial_output = match session_manager
-            .handle_exec_command_request(params.clone())
+            .handle_exec_command_request(params.clone(), sandbox)
             .await
         {
             Ok(v) => v,
@@ -0,0 +1,458 @@
+//! User- and project-defined execution policies.
+//!
+//! Policies are written in the Starlark dialect understood by
+//! `codex-execpolicy` (`define_program`, `flag`, `opt`, `ARG_RFILES`, ...)
+//! and declare which programs/flags may run without asking the user. A
+//! command that matches a policy is only auto-approved if every file it may
+//! write resolves to a location inside the sandbox's writable roots.
+//!
+//! A project policy ships with the repository, so it is not trusted as much
+//! as the user's own: commands it allows are still run in the sandbox.
+
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_execpolicy::ArgType;
+use codex_execpolicy::ExecCall;
+use codex_execpolicy::ExecvChecker;
+use codex_execpolicy::MatchedExec;
+use codex_execpolicy::PolicyParser;
+use codex_execpolicy::ValidExec;
+use tracing::debug;
+
+use crate::bash::try_parse_bash;
+use crate::bash::try_parse_word_only_commands_sequence;
+use crate::git_info::get_git_repo_root;
+use crate::protocol::SandboxPolicy;
+
+/// Name of the policy file looked up in `$CODEX_HOME` and in the `.codex`
+/// directory at the root of the project.
+pub(crate) const EXEC_POLICY_FILENAME: &str = "exec.policy";
+
+/// Outcome of checking a command against the loaded policies.
+#[derive(Debug, PartialEq)]
+pub(crate) enum ExecPolicyVerdict {
+    /// Every program in the command matched a policy and all of the files it
+    /// may write are inside the writable roots. `from_project` is set when
+    /// any of the programs was only allowed by the project policy.
+    Allowed {
+        writes_files: bool,
+        from_project: bool,
+    },
+
+    /// At least one program in the command is explicitly forbidden.
+    Forbidden { reason: String },
+
+    /// No policy vouches for the command; fall back to the default checks.
+    Unmatched,
+}
+
+pub(crate) struct ExecPolicy {
+    /// One checker per policy file, in load order (user before project).
+    checkers: Vec<PolicyChecker>,
+}
+
+struct PolicyChecker {
+    checker: ExecvChecker,
+    /// Whether the policy comes from the project rather than `$CODEX_HOME`.
+    from_project: bool,
+}
+
+impl ExecPolicy {
+    /// Load `$CODEX_HOME/exec.policy` and `<project root>/.codex/exec.policy`,
+    /// where the project root is the enclosing git repository of `cwd` (or
+    /// `cwd` itself). Returns `None` when neither file exists, along with a
+    /// message for every file that could not be read or parsed.
+    pub(crate) fn load(codex_home: &Path, cwd: &Path) -> (Option<Self>, Vec<String>) {
+        let project_root = get_git_repo_root(cwd).unwrap_or_else(|| cwd.to_path_buf());
+        let candidates = [
+            (codex_home.join(EXEC_POLICY_FILENAME), false),
+            (project_root.join(".codex").join(EXEC_POLICY_FILENAME), true),
+        ];
+
+        let mut checkers = Vec::new();
+        let mut errors = Vec::new();
+        for (path, from_project) in candidates {
+            if !path.is_file() {
+                continue;
+            }
+            let source = match std::fs::read_to_string(&path) {
+                Ok(source) => source,
+                Err(e) => {
+                    errors.push(format!("failed to read {}: {e}", path.display()));
+                    continue;
+                }
+            };
+            match PolicyParser::new(&path.to_string_lossy(), &source).parse() {
+                Ok(policy) => {
+                    debug!("loaded exec policy from {}", path.display());
+                    checkers.push(PolicyChecker {
+                        checker: ExecvChecker::new(policy),
+                        from_project,
+                    });
+                }
+                Err(e) => errors.push(format!("failed to parse {}: {e}", path.display())),
+            }
+        }
+
+        if checkers.is_empty() {
+            (None, errors)
+        } else {
+            (Some(Self { checkers }), errors)
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn from_source(source: &str) -> Self {
+        #![allow(clippy::expect_used)]
+        let policy = PolicyParser::new("#test", source)
+            .parse()
+            .expect("test policy should parse");
+        Self {
+            checkers: vec![PolicyChecker {
+                checker: ExecvChecker::new(policy),
+                from_project: false,
+            }],
+        }
+    }
+
+    /// Check `command` against the loaded policies. `bash -lc` invocations
+    /// whose script is a sequence of plain commands are checked command by
+    /// command; the script is allowed only if every command is.
+    pub(crate) fn evaluate(
+        &self,
+        command: &[String],
+        sandbox_policy: &SandboxPolicy,
+        cwd: &Path,
+    ) -> ExecPolicyVerdict {
+        let commands = match command {
+            [bash, flag, script] if bash == "bash" && flag == "-lc" => {
+                match try_parse_bash(script)
+                    .and_then(|tree| try_parse_word_only_commands_sequence(&tree, script))
+                {
+                    Some(commands) if !commands.is_empty() => commands,
+                    _ => return ExecPolicyVerdict::Unmatched,
+                }
+            }
+            _ => vec![command.to_vec()],
+        };
+
+        let writable_folders = writable_folders(sandbox_policy, cwd);
+        let mut all_allowed = true;
+        let mut writes_files = false;
+        let mut from_project = false;
+        for command in &commands {
+            match self.evaluate_single(command, sandbox_policy, cwd, &writable_folders) {
+                ExecPolicyVerdict::Forbidden { reason } => {
+                    return ExecPolicyVerdict::Forbidden { reason };
+                }
+                ExecPolicyVerdict::Allowed {
+                    writes_files: writes,
+                    from_project: project,
+                } => {
+                    writes_files |= writes;
+                    from_project |= project;
+                }
+                ExecPolicyVerdict::Unmatched => all_allowed = false,
+            }
+        }
+
+        if all_allowed {
+            ExecPolicyVerdict::Allowed {
+                writes_files,
+                from_project,
+            }
+        } else {
+            ExecPolicyVerdict::Unmatched
+        }
+    }
+
+    fn evaluate_single(
+        &self,
+        command: &[String],
+        sandbox_policy: &SandboxPolicy,
+        cwd: &Path,
+        writable_folders: &[PathBuf],
+    ) -> ExecPolicyVerdict {
+        let Some((program, args)) = command.split_first() else {
+            return ExecPolicyVerdict::Unmatched;
+        };
+        let exec_call = ExecCall {
+            program: program.clone(),
+            args: args.to_vec(),
+        };
+
+        for PolicyChecker {
+            checker,
+            from_project,
+        } in &self.checkers
+        {
+            match checker.r#match(&exec_call) {
+                Ok(MatchedExec::Forbidden { reason, .. }) => {
+                    return ExecPolicyVerdict::Forbidden { reason };
+                }
+                Ok(MatchedExec::Match { exec }) => {
+                    let writes_files = exec.might_write_files();
+                    let readable_folders = [PathBuf::from("/")];
+                    let checked = checker.check(
+                        exec.clone(),
+                        &Some(cwd.as_os_str().to_os_string()),
+                        &readable_folders,
+                        writable_folders,
+                    );
+                    match checked {
+                        Ok(_) if writes_avoid_read_only_subpaths(&exec, sandbox_policy, cwd) => {
+                            return ExecPolicyVerdict::Allowed {
+                                writes_files,
+                                from_project: *from_project,
+                            };
+                        }
+                        Ok(_) => {}
+                        Err(e) => debug!("exec policy match for {exec_call} rejected: {e:?}"),
+                    }
+                }
+                Err(_) => {}
+            }
+        }
+
+        ExecPolicyVerdict::Unmatched
+    }
+}
+
+/// Folders that files written by a policy-matched command must live in.
+fn writable_folders(sandbox_policy: &SandboxPolicy, cwd: &Path) -> Vec<PathBuf> {
+    match sandbox_policy {
+        SandboxPolicy::DangerFullAccess => vec![PathBuf::from("/")],
+        SandboxPolicy::ReadOnly | SandboxPolicy::WorkspaceWrite { .. } => sandbox_policy
+            .get_writable_roots_with_cwd(cwd)
+            .into_iter()
+            .map(|writable_root| writable_root.root)
+            .collect(),
+    }
+}
+
+/// `ExecvChecker::check` only knows about the writable roots themselves, so
+/// also reject writes into read-only subpaths such as `.git`.
+fn writes_avoid_read_only_subpaths(
+    exec: &ValidExec,
+    sandbox_policy: &SandboxPolicy,
+    cwd: &Path,
+) -> bool {
+    let writable_roots = sandbox_policy.get_writable_roots_with_cwd(cwd);
+    exec.args
+        .iter()
+        .map(|arg| (&arg.r#type, &arg.value))
+        .chain(exec.opts.iter().map(|opt| (&opt.r#type, &opt.value)))
+        .filter(|(arg_type, _)| matches!(arg_type, ArgType::WriteableFile))
+        .all(|(_, value)| {
+            let path = normalize(&cwd.join(value));
+            writable_roots.iter().all(|writable_root| {
+                !path.starts_with(&writable_root.root) || writable_root.is_path_writable(&path)
+            })
+        })
+}
+
+/// Resolve `.` and `..` components without touching the filesystem.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                out.pop();
+            }
+            Component::CurDir => {}
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    const POLICY: &str = r#"
+define_program(
+    program="ls",
+    options=[flag("-l")],
+    args=[ARG_RFILES_OR_CWD],
+)
+
+define_program(
+    program="cp",
+    args=[ARG_RFILE, ARG_WFILE],
+)
+
+forbid_program_regex(
+    regex="^rm$",
+    reason="use apply_patch to delete files",
+)
+"#;
+
+    fn vec_str(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn workspace_write() -> SandboxPolicy {
+        SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![],
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+            readable_roots: None,
+            deny_read: Vec::new(),
+            allowed_hosts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn read_only_command_is_allowed() {
+        let policy = ExecPolicy::from_source(POLICY);
+        let cwd = TempDir::new().unwrap();
+        assert_eq!(
+            policy.evaluate(
+                &vec_str(&["ls", "-l", "src"]),
+                &SandboxPolicy::ReadOnly,
+                cwd.path()
+            ),
+            ExecPolicyVerdict::Allowed {
+                writes_files: false,
+                from_project: false,
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_flag_is_unmatched() {
+        let policy = ExecPolicy::from_source(POLICY);
+        let cwd = TempDir::new().unwrap();
+        assert_eq!(
+            policy.evaluate(
+                &vec_str(&["ls", "-R"]),
+                &SandboxPolicy::ReadOnly,
+                cwd.path()
+            ),
+            ExecPolicyVerdict::Unmatched
+        );
+    }
+
+    #[test]
+    fn writes_are_checked_against_writable_roots() {
+        let policy = ExecPolicy::from_source(POLICY);
+        let cwd = TempDir::new().unwrap();
+        let cp_inside = vec_str(&["cp", "a.txt", "b.txt"]);
+        assert_eq!(
+            policy.evaluate(&cp_inside, &workspace_write(), cwd.path()),
+            ExecPolicyVerdict::Allowed {
+                writes_files: true,
+                from_project: false,
+            }
+        );
+        assert_eq!(
+            policy.evaluate(&cp_inside, &SandboxPolicy::ReadOnly, cwd.path()),
+            ExecPolicyVerdict::Unmatched
+        );
+
+        let cp_outside = vec_str(&["cp", "a.txt", "../b.txt"]);
+        assert_eq!(
+            policy.evaluate(&cp_outside, &workspace_write(), cwd.path()),
+            ExecPolicyVerdict::Unmatched
+        );
+    }
+
+    #[test]
+    fn writes_into_read_only_subpaths_are_unmatched() {
+        let policy = ExecPolicy::from_source(POLICY);
+        let cwd = TempDir::new().unwrap();
+        std::fs::create_dir(cwd.path().join(".git")).unwrap();
+        assert_eq!(
+            policy.evaluate(
+                &vec_str(&["cp", "a.txt", "src/../.git/config"]),
+                &workspace_write(),
+                cwd.path()
+            ),
+            ExecPolicyVerdict::Unmatched
+        );
+    }
+
+    #[test]
+    fn bash_scripts_require_every_command_to_match() {
+        let policy = ExecPolicy::from_source(POLICY);
+        let cwd = TempDir::new().unwrap();
+        assert_eq!(
+            policy.evaluate(
+                &vec_str(&["bash", "-lc", "ls -l && cp a.txt b.txt"]),
+                &workspace_write(),
+                cwd.path()
+            ),
+            ExecPolicyVerdict::Allowed {
+                writes_files: true,
+                from_project: false,
+            }
+        );
+        assert_eq!(
+            policy.evaluate(
+                &vec_str(&["bash", "-lc", "ls -l && git status"]),
+                &workspace_write(),
+                cwd.path()
+            ),
+            ExecPolicyVerdict::Unmatched
+        );
+        assert_eq!(
+            policy.evaluate(
+                &vec_str(&["bash", "-lc", "ls -l; rm -rf src"]),
+                &workspace_write(),
+                cwd.path()
+            ),
+            ExecPolicyVerdict::Forbidden {
+                reason: "use apply_patch to delete files".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn load_reads_user_and_project_policies() {
+        let codex_home = TempDir::new().unwrap();
+        let project = TempDir::new().unwrap();
+        std::fs::write(codex_home.path().join(EXEC_POLICY_FILENAME), POLICY).unwrap();
+        std::fs::create_dir(project.path().join(".codex")).unwrap();
+        std::fs::write(
+            project.path().join(".codex").join(EXEC_POLICY_FILENAME),
+            "define_program(",
+        )
+        .unwrap();
+
+        let (policy, errors) = ExecPolicy::load(codex_home.path(), project.path());
+        assert!(policy.is_some());
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("failed to parse"));
+
+        // Commands allowed only by the project policy are flagged as such.
+        let empty_home = TempDir::new().unwrap();
+        std::fs::write(
+            project.path().join(".codex").join(EXEC_POLICY_FILENAME),
+            POLICY,
+        )
+        .unwrap();
+        let (policy, errors) = ExecPolicy::load(empty_home.path(), project.path());
+        assert!(errors.is_empty());
+        assert_eq!(
+            policy.unwrap().evaluate(
+                &vec_str(&["ls", "-l"]),
+                &SandboxPolicy::ReadOnly,
+                project.path()
+            ),
+            ExecPolicyVerdict::Allowed {
+                writes_files: false,
+                from_project: true,
+            }
+        );
+
+        let (policy, errors) = ExecPolicy::load(empty_home.path(), empty_home.path());
+        assert!(policy.is_none());
+        assert!(errors.is_empty());
+    }
+}
@@ -13,4 +13,9 @@ env_flags! {
 
     /// Fixture path for offline tests (see client.rs).
     pub CODEX_RS_SSE_FIXTURE: Option<&str> = None;
+
+    /// Directory to record model requests and responses to, and to replay
+    /// them from (see stream_recording.rs).
+    pub CODEX_RS_RECORD_DIR: Option<&str> = None;
+    pub CODEX_RS_REPLAY_DIR: Option<&str> = None;
 }
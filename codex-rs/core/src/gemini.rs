@@ -0,0 +1,551 @@
+//! Implementation for the Gemini API:
+//! https://ai.google.dev/api/generate-content#method:-models.streamgeneratecontent
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use bytes::Bytes;
+use codex_protocol::config_types::ReasoningEffort as ReasoningEffortConfig;
+use codex_protocol::mcp_protocol::ConversationId;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ReasoningItemReasoningSummary;
+use codex_protocol::models::ResponseItem;
+use eventsource_stream::Eventsource;
+use futures::Stream;
+use futures::StreamExt;
+use futures::TryStreamExt;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json::json;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use tracing::debug;
+use tracing::trace;
+use uuid::Uuid;
+
+use crate::ModelProviderInfo;
+use crate::client_common::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::client_common::ResponseStream;
+use crate::client_common::thinking_budget_tokens;
+use crate::config::Config;
+use crate::error::CodexErr;
+use crate::error::Result;
+use crate::openai_tools::create_function_declarations_for_gemini_api;
+use crate::protocol::TokenUsage;
+use crate::stream_recording::record_response;
+use crate::stream_recording::replay_next_response;
+use crate::util::backoff;
+use crate::util::split_base64_data_url;
+
+pub(crate) async fn stream_gemini(
+    prompt: &Prompt,
+    config: &Config,
+    effort: Option<ReasoningEffortConfig>,
+    client: &reqwest::Client,
+    provider: &ModelProviderInfo,
+    conversation_id: ConversationId,
+) -> Result<ResponseStream> {
+    let mut generation_config = serde_json::Map::new();
+    if let Some(max_output_tokens) = config.model_max_output_tokens {
+        generation_config.insert("maxOutputTokens".to_string(), json!(max_output_tokens));
+    }
+    // Thought summaries are opt-in through `model_supports_reasoning_summaries`.
+    if config.model_family.supports_reasoning_summaries {
+        generation_config.insert(
+            "thinkingConfig".to_string(),
+            json!({
+                "includeThoughts": true,
+                "thinkingBudget": thinking_budget_tokens(effort),
+            }),
+        );
+    }
+
+    let mut payload = json!({
+        "systemInstruction": {
+            "parts": [{"text": prompt.get_full_instructions(&config.model_family)}],
+        },
+        "contents": create_contents(&prompt.get_formatted_input()),
+        "generationConfig": generation_config,
+    });
+    let declarations = create_function_declarations_for_gemini_api(&prompt.tools)?;
+    if !declarations.is_empty()
+        && let Some(obj) = payload.as_object_mut()
+    {
+        obj.insert(
+            "tools".to_string(),
+            json!([{"functionDeclarations": declarations}]),
+        );
+    }
+
+    debug!(
+        "POST to {}: {}",
+        provider.get_full_url(&None, &config.model),
+        serde_json::to_string_pretty(&payload).unwrap_or_default()
+    );
+
+    if let Some(recorded) = replay_next_response(conversation_id, &payload) {
+        let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+        tokio::spawn(process_gemini_sse(
+            recorded?,
+            tx_event,
+            provider.stream_idle_timeout(),
+        ));
+        return Ok(ResponseStream { rx_event });
+    }
+
+    let mut attempt = 0;
+    let max_retries = provider.request_max_retries();
+    loop {
+        attempt += 1;
+
+        let req_builder = provider
+            .create_request_builder(client, &None, &config.model)
+            .await?;
+
+        let res = req_builder
+            .header(reqwest::header::ACCEPT, "text/event-stream")
+            .json(&payload)
+            .send()
+            .await;
+
+        match res {
+            Ok(resp) if resp.status().is_success() => {
+                let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+                let stream = record_response(
+                    conversation_id,
+                    &payload,
+                    resp.bytes_stream().map_err(CodexErr::Reqwest),
+                );
+                tokio::spawn(process_gemini_sse(
+                    stream,
+                    tx_event,
+                    provider.stream_idle_timeout(),
+                ));
+                return Ok(ResponseStream { rx_event });
+            }
+            Ok(res) => {
+                let status = res.status();
+                if !(status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()) {
+                    let body = (res.text().await).unwrap_or_default();
+                    return Err(CodexErr::UnexpectedStatus(status, body));
+                }
+
+                if attempt > max_retries {
+                    return Err(CodexErr::RetryLimit(status));
+                }
+
+                let retry_after_secs = res
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|s| s.parse::<u64>().ok());
+
+                let delay = retry_after_secs
+                    .map(|s| Duration::from_millis(s * 1_000))
+                    .unwrap_or_else(|| backoff(attempt));
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => {
+                if attempt > max_retries {
+                    return Err(e.into());
+                }
+                let delay = backoff(attempt);
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+}
+
+/// Converts the conversation history into Gemini `contents`. Consecutive
+/// items of the same role are merged into a single entry. Function responses
+/// are matched to their call by name, as Gemini does not use call ids.
+fn create_contents(input: &[ResponseItem]) -> Vec<Value> {
+    let mut contents: Vec<Value> = Vec::new();
+    let mut push_part = |role: &str, part: Value| {
+        if let Some(last) = contents.last_mut()
+            && last["role"] == role
+            && let Some(parts) = last["parts"].as_array_mut()
+        {
+            parts.push(part);
+            return;
+        }
+        contents.push(json!({"role": role, "parts": [part]}));
+    };
+    let mut call_names: HashMap<&str, &str> = HashMap::new();
+
+    for item in input {
+        match item {
+            ResponseItem::Message { role, content, .. } => {
+                let role = if role == "assistant" { "model" } else { "user" };
+                for part in content.iter().filter_map(content_part) {
+                    push_part(role, part);
+                }
+            }
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                thought_signature,
+                ..
+            } => {
+                call_names.insert(call_id, name);
+                let args = serde_json::from_str::<Value>(arguments)
+                    .ok()
+                    .filter(Value::is_object)
+                    .unwrap_or_else(|| json!({}));
+                let mut part = json!({"functionCall": {"name": name, "args": args}});
+                // Gemini rejects calls of the current turn sent back without
+                // the signature of the thoughts that led to them.
+                if let Some(signature) = thought_signature {
+                    part["thoughtSignature"] = json!(signature);
+                }
+                push_part("model", part);
+            }
+            ResponseItem::FunctionCallOutput { call_id, output } => {
+                let name = call_names
+                    .get(call_id.as_str())
+                    .copied()
+                    .unwrap_or_default();
+                let response = if output.success == Some(false) {
+                    json!({"error": output.content})
+                } else {
+                    json!({"output": output.content})
+                };
+                push_part(
+                    "user",
+                    json!({"functionResponse": {"name": name, "response": response}}),
+                );
+            }
+            ResponseItem::Reasoning { .. }
+            | ResponseItem::LocalShellCall { .. }
+            | ResponseItem::CustomToolCall { .. }
+            | ResponseItem::CustomToolCallOutput { .. }
+            | ResponseItem::WebSearchCall { .. }
+            | ResponseItem::Other => {
+                // Thoughts are not sent back and the other items cannot be
+                // produced by this API; omit them.
+                continue;
+            }
+        }
+    }
+    contents
+}
+
+fn content_part(item: &ContentItem) -> Option<Value> {
+    match item {
+        ContentItem::InputText { text } | ContentItem::OutputText { text } => {
+            (!text.is_empty()).then(|| json!({"text": text}))
+        }
+        // Gemini only accepts inline image data or files uploaded to Google.
+        ContentItem::InputImage { image_url } => split_base64_data_url(image_url)
+            .map(|(mime_type, data)| json!({"inlineData": {"mimeType": mime_type, "data": data}})),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerateContentResponse {
+    #[serde(default)]
+    candidates: Vec<Candidate>,
+    usage_metadata: Option<UsageMetadata>,
+    response_id: Option<String>,
+    error: Option<ApiError>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Candidate {
+    content: Option<Content>,
+    finish_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Content {
+    #[serde(default)]
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Part {
+    text: Option<String>,
+    #[serde(default)]
+    thought: bool,
+    /// Opaque signature of the model's thoughts, set on function call parts.
+    thought_signature: Option<String>,
+    function_call: Option<FunctionCall>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FunctionCall {
+    id: Option<String>,
+    name: String,
+    #[serde(default)]
+    args: Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UsageMetadata {
+    #[serde(default)]
+    prompt_token_count: u64,
+    #[serde(default)]
+    cached_content_token_count: u64,
+    #[serde(default)]
+    candidates_token_count: u64,
+    #[serde(default)]
+    thoughts_token_count: u64,
+    total_token_count: Option<u64>,
+}
+
+impl From<UsageMetadata> for TokenUsage {
+    fn from(usage: UsageMetadata) -> Self {
+        // Like OpenAI's `output_tokens`, the output includes the thoughts.
+        let output_tokens = usage.candidates_token_count + usage.thoughts_token_count;
+        TokenUsage {
+            input_tokens: usage.prompt_token_count,
+            cached_input_tokens: usage.cached_content_token_count,
+            output_tokens,
+            reasoning_output_tokens: usage.thoughts_token_count,
+            total_tokens: usage
+                .total_token_count
+                .unwrap_or(usage.prompt_token_count + output_tokens),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiError {
+    message: String,
+}
+
+/// SSE processor for `streamGenerateContent?alt=sse`. Text and thought parts
+/// are forwarded as deltas and accumulated into a single message and
+/// reasoning item, which are flushed before every function call and at the
+/// end of the stream.
+async fn process_gemini_sse<S>(
+    stream: S,
+    tx_event: mpsc::Sender<Result<ResponseEvent>>,
+    idle_timeout: Duration,
+) where
+    S: Stream<Item = Result<Bytes>> + Unpin,
+{
+    let mut stream = stream.eventsource();
+
+    let mut response_id = String::new();
+    let mut token_usage: Option<TokenUsage> = None;
+    let mut assistant_text = String::new();
+    let mut reasoning_text = String::new();
+    let mut finished = false;
+
+    loop {
+        let sse = match timeout(idle_timeout, stream.next()).await {
+            Ok(Some(Ok(ev))) => ev,
+            Ok(Some(Err(e))) => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(e.to_string(), None)))
+                    .await;
+                return;
+            }
+            Ok(None) if finished => {
+                for item in take_items(&mut reasoning_text, &mut assistant_text) {
+                    let _ = tx_event.send(Ok(ResponseEvent::OutputItemDone(item))).await;
+                }
+                let _ = tx_event
+                    .send(Ok(ResponseEvent::Completed {
+                        response_id,
+                        token_usage,
+                    }))
+                    .await;
+                return;
+            }
+            Ok(None) => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(
+                        "stream closed before finishReason".into(),
+                        None,
+                    )))
+                    .await;
+                return;
+            }
+            Err(_) => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(
+                        "idle timeout waiting for SSE".into(),
+                        None,
+                    )))
+                    .await;
+                return;
+            }
+        };
+
+        let chunk: GenerateContentResponse = match serde_json::from_str(&sse.data) {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                debug!("Failed to parse Gemini SSE chunk: {e}, data: {}", &sse.data);
+                continue;
+            }
+        };
+        trace!("gemini received SSE chunk: {chunk:?}");
+
+        if let Some(error) = chunk.error {
+            let _ = tx_event
+                .send(Err(CodexErr::Stream(error.message, None)))
+                .await;
+            return;
+        }
+        if let Some(id) = chunk.response_id {
+            response_id = id;
+        }
+        if let Some(usage) = chunk.usage_metadata {
+            token_usage = Some(usage.into());
+        }
+
+        let Some(candidate) = chunk.candidates.into_iter().next() else {
+            continue;
+        };
+        finished |= candidate.finish_reason.is_some();
+        for part in candidate.content.into_iter().flat_map(|c| c.parts) {
+            let mut events = Vec::new();
+            if let Some(call) = part.function_call {
+                // Keep the output in order: whatever the model produced
+                // before the call is recorded first.
+                events.extend(
+                    take_items(&mut reasoning_text, &mut assistant_text)
+                        .into_iter()
+                        .map(ResponseEvent::OutputItemDone),
+                );
+                events.push(ResponseEvent::OutputItemDone(ResponseItem::FunctionCall {
+                    id: None,
+                    arguments: call.args.to_string(),
+                    name: call.name,
+                    call_id: call
+                        .id
+                        .unwrap_or_else(|| format!("call_{}", Uuid::new_v4().simple())),
+                    thought_signature: part.thought_signature,
+                }));
+            } else if let Some(text) = part.text.filter(|text| !text.is_empty()) {
+                if part.thought {
+                    reasoning_text.push_str(&text);
+                    events.push(ResponseEvent::ReasoningSummaryDelta(text));
+                } else {
+                    assistant_text.push_str(&text);
+                    events.push(ResponseEvent::OutputTextDelta(text));
+                }
+            }
+            for event in events {
+                if tx_event.send(Ok(event)).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Drains the accumulated thoughts and text into a reasoning item and an
+/// assistant message, in that order.
+fn take_items(reasoning_text: &mut String, assistant_text: &mut String) -> Vec<ResponseItem> {
+    let mut items = Vec::new();
+    if !reasoning_text.is_empty() {
+        items.push(ResponseItem::Reasoning {
+            id: String::new(),
+            summary: vec![ReasoningItemReasoningSummary::SummaryText {
+                text: std::mem::take(reasoning_text),
+            }],
+            content: None,
+            encrypted_content: None,
+        });
+    }
+    if !assistant_text.is_empty() {
+        items.push(ResponseItem::Message {
+            id: None,
+            role: "assistant".to_string(),
+            content: vec![ContentItem::OutputText {
+                text: std::mem::take(assistant_text),
+            }],
+        });
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn create_contents_maps_roles_and_function_responses() {
+        let input = vec![
+            ResponseItem::Message {
+                id: None,
+                role: "user".to_string(),
+                content: vec![ContentItem::InputText {
+                    text: "list files".to_string(),
+                }],
+            },
+            ResponseItem::Message {
+                id: None,
+                role: "assistant".to_string(),
+                content: vec![ContentItem::OutputText {
+                    text: "Listing.".to_string(),
+                }],
+            },
+            ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: r#"{"command":["ls"]}"#.to_string(),
+                call_id: "call_1".to_string(),
+                thought_signature: Some("c2lnbmF0dXJl".to_string()),
+            },
+            ResponseItem::FunctionCallOutput {
+                call_id: "call_1".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "README.md".to_string(),
+                    success: Some(true),
+                },
+            },
+        ];
+
+        assert_eq!(
+            create_contents(&input),
+            vec![
+                json!({"role": "user", "parts": [{"text": "list files"}]}),
+                json!({"role": "model", "parts": [
+                    {"text": "Listing."},
+                    {
+                        "functionCall": {"name": "shell", "args": {"command": ["ls"]}},
+                        "thoughtSignature": "c2lnbmF0dXJl",
+                    },
+                ]}),
+                json!({"role": "user", "parts": [
+                    {"functionResponse": {"name": "shell", "response": {"output": "README.md"}}},
+                ]}),
+            ]
+        );
+    }
+
+    #[test]
+    fn usage_includes_thoughts_in_output() {
+        let usage: UsageMetadata = serde_json::from_value(json!({
+            "promptTokenCount": 100,
+            "cachedContentTokenCount": 40,
+            "candidatesTokenCount": 7,
+            "thoughtsTokenCount": 20,
+            "totalTokenCount": 127,
+        }))
+        .unwrap();
+        assert_eq!(
+            TokenUsage::from(usage),
+            TokenUsage {
+                input_tokens: 100,
+                cached_input_tokens: 40,
+                output_tokens: 27,
+                reasoning_output_tokens: 20,
+                total_tokens: 127,
+            }
+        );
+    }
+}
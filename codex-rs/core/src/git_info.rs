@@ -44,6 +44,11 @@ pub fn get_git_repo_root(base_dir: &Path) -> Option<PathBuf> {
 /// Timeout for git commands to prevent freezing on large repositories
 const GIT_COMMAND_TIMEOUT: TokioDuration = TokioDuration::from_secs(5);
 
+/// Timeout for git commands that change the repository. Checking out, merging
+/// or hashing every file of a large repository takes longer than the quick
+/// queries above.
+const GIT_LONG_COMMAND_TIMEOUT: TokioDuration = TokioDuration::from_secs(60);
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct GitDiffToRemote {
     pub sha: GitSha,
@@ -137,6 +142,35 @@ async fn run_git_command_with_timeout(args: &[&str], cwd: &Path) -> Option<std::
     }
 }
 
+/// Runs git in `cwd` and returns its trimmed stdout, or its stderr on
+/// failure. Meant for commands that change the repository, which may take up
+/// to [`GIT_LONG_COMMAND_TIMEOUT`].
+pub(crate) async fn run_git(args: &[&str], cwd: &Path) -> Result<String, String> {
+    run_git_with_env(args, cwd, &[]).await
+}
+
+/// Like [`run_git`], with the environment variables `envs` set for git.
+pub(crate) async fn run_git_with_env(
+    args: &[&str],
+    cwd: &Path,
+    envs: &[(&str, &Path)],
+) -> Result<String, String> {
+    let mut command = Command::new("git");
+    command.args(args).current_dir(cwd);
+    for (key, value) in envs {
+        command.env(key, value);
+    }
+    let output = timeout(GIT_LONG_COMMAND_TIMEOUT, command.output())
+        .await
+        .map_err(|_| format!("`git {}` timed out", args.join(" ")))?
+        .map_err(|e| format!("failed to run git: {e}"))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
 async fn get_git_remotes(cwd: &Path) -> Option<Vec<String>> {
     let output = run_git_command_with_timeout(&["remote"], cwd).await?;
     if !output.status.success() {
@@ -160,7 +194,7 @@ async fn get_git_remotes(cwd: &Path) -> Option<Vec<String>> {
 /// 1) The symbolic ref at `refs/remotes/<remote>/HEAD` for the first remote (origin prioritized)
 /// 2) `git remote show <remote>` parsed for "HEAD branch: <name>"
 /// 3) Local fallback to existing `main` or `master` if present
-async fn get_default_branch(cwd: &Path) -> Option<String> {
+pub async fn get_default_branch(cwd: &Path) -> Option<String> {
     // Prefer the first remote (with origin prioritized)
     let remotes = get_git_remotes(cwd).await.unwrap_or_default();
     for remote in remotes {
@@ -0,0 +1,308 @@
+//! Isolating a conversation in a dedicated `git worktree`.
+//!
+//! When a conversation is started with a worktree, a new branch is created
+//! from `HEAD` of the repository containing `cwd` and checked out under
+//! `$CODEX_HOME/worktrees`. The conversation works there, so several
+//! conversations can edit the same repository without touching each other's
+//! files. Once done, [`resolve_worktree`] commits what is left in the worktree
+//! and merges or cherry-picks the branch into the original checkout, or
+//! discards it, and removes both the worktree and the branch.
+//!
+//! Uncommitted changes of the original checkout are not copied into the
+//! worktree.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::protocol::SandboxPolicy;
+use codex_protocol::protocol::WorktreeInfo;
+use codex_protocol::protocol::WorktreeResolution;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::git_info::run_git;
+
+/// Prefix of the branches created for conversations.
+const BRANCH_PREFIX: &str = "codex/";
+
+/// Creates a worktree on a fresh branch for the repository that contains
+/// `cwd`.
+pub async fn create_worktree(cwd: &Path, codex_home: &Path) -> Result<WorktreeInfo, String> {
+    let repo_root = PathBuf::from(run_git(&["rev-parse", "--show-toplevel"], cwd).await?);
+    let base_commit = run_git(&["rev-parse", "HEAD"], &repo_root).await?;
+
+    let id = Uuid::new_v4().simple().to_string();
+    let id = &id[..8];
+    let repo_name = repo_root
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "repo".to_string());
+    let branch = format!("{BRANCH_PREFIX}{id}");
+    let path = codex_home
+        .join("worktrees")
+        .join(format!("{repo_name}-{id}"));
+    std::fs::create_dir_all(codex_home.join("worktrees"))
+        .map_err(|e| format!("failed to create worktrees directory: {e}"))?;
+
+    let path_str = path.to_string_lossy();
+    run_git(
+        &["worktree", "add", "-b", &branch, &path_str, &base_commit],
+        &repo_root,
+    )
+    .await?;
+
+    Ok(WorktreeInfo {
+        path,
+        branch,
+        base_commit,
+        repo_root,
+    })
+}
+
+/// Maps `cwd` inside the original checkout to the same directory inside the
+/// worktree.
+pub fn worktree_cwd(worktree: &WorktreeInfo, cwd: &Path) -> PathBuf {
+    let cwd = std::fs::canonicalize(cwd).unwrap_or_else(|_| cwd.to_path_buf());
+    let repo_root =
+        std::fs::canonicalize(&worktree.repo_root).unwrap_or_else(|_| worktree.repo_root.clone());
+    match cwd.strip_prefix(&repo_root) {
+        Ok(relative) => worktree.path.join(relative),
+        Err(_) => worktree.path.clone(),
+    }
+}
+
+/// Points `config` at the worktree: `cwd` moves into it, and writable roots
+/// inside the original checkout are replaced by the worktree itself.
+pub(crate) fn apply_worktree_to_config(config: &mut Config, worktree: &WorktreeInfo) {
+    config.cwd = worktree_cwd(worktree, &config.cwd);
+    if let SandboxPolicy::WorkspaceWrite { writable_roots, .. } = &mut config.sandbox_policy {
+        let repo_root = std::fs::canonicalize(&worktree.repo_root)
+            .unwrap_or_else(|_| worktree.repo_root.clone());
+        writable_roots.retain(|root| {
+            let root = std::fs::canonicalize(root).unwrap_or_else(|_| root.clone());
+            !root.starts_with(&repo_root)
+        });
+        writable_roots.push(worktree.path.clone());
+    }
+}
+
+/// Brings the worktree's changes back according to `resolution` and removes
+/// the worktree and its branch. Returns a summary of what was done.
+///
+/// If merging or cherry-picking fails, the operation is aborted and the
+/// worktree is kept so that it can be resolved by hand or with another
+/// resolution.
+pub async fn resolve_worktree(
+    worktree: &WorktreeInfo,
+    resolution: WorktreeResolution,
+) -> Result<String, String> {
+    let WorktreeInfo {
+        path,
+        branch,
+        base_commit,
+        repo_root,
+    } = worktree;
+
+    let summary = match resolution {
+        WorktreeResolution::Discard => format!("Discarded the changes on {branch}"),
+        WorktreeResolution::Merge | WorktreeResolution::CherryPick => {
+            commit_pending_changes(path, branch).await?;
+            let range = format!("{base_commit}..{branch}");
+            let commits: usize = run_git(&["rev-list", "--count", &range], repo_root)
+                .await?
+                .parse()
+                .map_err(|e| format!("unexpected output of git rev-list: {e}"))?;
+            let target = run_git(&["rev-parse", "--abbrev-ref", "HEAD"], repo_root).await?;
+
+            if commits == 0 {
+                format!("No changes were made on {branch}")
+            } else if resolution == WorktreeResolution::Merge {
+                if let Err(err) = run_git(&["merge", "--no-edit", branch], repo_root).await {
+                    let _ = run_git(&["merge", "--abort"], repo_root).await;
+                    return Err(format!("failed to merge {branch} into {target}: {err}"));
+                }
+                format!("Merged {commits} commit(s) from {branch} into {target}")
+            } else {
+                if let Err(err) = run_git(&["cherry-pick", &range], repo_root).await {
+                    let _ = run_git(&["cherry-pick", "--abort"], repo_root).await;
+                    return Err(format!(
+                        "failed to cherry-pick {branch} onto {target}: {err}"
+                    ));
+                }
+                format!("Cherry-picked {commits} commit(s) from {branch} onto {target}")
+            }
+        }
+    };
+
+    let path_str = path.to_string_lossy();
+    run_git(&["worktree", "remove", "--force", &path_str], repo_root).await?;
+    run_git(&["branch", "-D", branch], repo_root).await?;
+    Ok(summary)
+}
+
+/// Commits everything that was changed in the worktree but not committed.
+async fn commit_pending_changes(path: &Path, branch: &str) -> Result<(), String> {
+    if run_git(&["status", "--porcelain"], path).await?.is_empty() {
+        return Ok(());
+    }
+    run_git(&["add", "-A"], path).await?;
+    run_git(
+        &["commit", "-m", &format!("Changes made on {branch}")],
+        path,
+    )
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use tempfile::TempDir;
+    use tokio::process::Command;
+
+    async fn git(args: &[&str], cwd: &Path) {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(cwd)
+            .output()
+            .await
+            .expect("failed to run git");
+        assert!(
+            output.status.success(),
+            "git {args:?} failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    async fn create_test_git_repo(temp_dir: &TempDir) -> PathBuf {
+        let repo = temp_dir.path().join("repo");
+        fs::create_dir_all(repo.join("src")).expect("create repo");
+        git(&["init"], &repo).await;
+        git(&["config", "user.name", "Test User"], &repo).await;
+        git(&["config", "user.email", "test@example.com"], &repo).await;
+        fs::write(repo.join("src/lib.rs"), "original\n").expect("write file");
+        git(&["add", "."], &repo).await;
+        git(&["commit", "-m", "Initial commit"], &repo).await;
+        repo
+    }
+
+    async fn branch_exists(repo: &Path, branch: &str) -> bool {
+        run_git(&["rev-parse", "--verify", branch], repo)
+            .await
+            .is_ok()
+    }
+
+    #[tokio::test]
+    async fn create_worktree_checks_out_a_new_branch_under_codex_home() {
+        let temp_dir = TempDir::new().expect("tempdir");
+        let repo = create_test_git_repo(&temp_dir).await;
+        let codex_home = temp_dir.path().join("home");
+
+        let worktree = create_worktree(&repo.join("src"), &codex_home)
+            .await
+            .expect("create worktree");
+
+        assert!(worktree.path.starts_with(codex_home.join("worktrees")));
+        assert!(worktree.branch.starts_with(BRANCH_PREFIX));
+        assert_eq!(
+            fs::read_to_string(worktree.path.join("src/lib.rs")).ok(),
+            Some("original\n".to_string())
+        );
+        assert_eq!(
+            worktree_cwd(&worktree, &repo.join("src")),
+            worktree.path.join("src")
+        );
+        assert!(branch_exists(&repo, &worktree.branch).await);
+    }
+
+    #[tokio::test]
+    async fn merge_brings_uncommitted_changes_back_and_removes_worktree() {
+        let temp_dir = TempDir::new().expect("tempdir");
+        let repo = create_test_git_repo(&temp_dir).await;
+        let worktree = create_worktree(&repo, &temp_dir.path().join("home"))
+            .await
+            .expect("create worktree");
+        fs::write(worktree.path.join("src/lib.rs"), "changed\n").expect("write file");
+
+        let summary = resolve_worktree(&worktree, WorktreeResolution::Merge)
+            .await
+            .expect("merge");
+
+        assert!(summary.starts_with("Merged 1 commit(s)"), "{summary}");
+        assert_eq!(
+            fs::read_to_string(repo.join("src/lib.rs")).ok(),
+            Some("changed\n".to_string())
+        );
+        assert!(!worktree.path.exists());
+        assert!(!branch_exists(&repo, &worktree.branch).await);
+    }
+
+    #[tokio::test]
+    async fn cherry_pick_applies_commits_onto_moved_branch() {
+        let temp_dir = TempDir::new().expect("tempdir");
+        let repo = create_test_git_repo(&temp_dir).await;
+        let worktree = create_worktree(&repo, &temp_dir.path().join("home"))
+            .await
+            .expect("create worktree");
+        fs::write(worktree.path.join("new.txt"), "new\n").expect("write file");
+        fs::write(repo.join("other.txt"), "other\n").expect("write file");
+        git(&["add", "."], &repo).await;
+        git(&["commit", "-m", "Concurrent change"], &repo).await;
+
+        let summary = resolve_worktree(&worktree, WorktreeResolution::CherryPick)
+            .await
+            .expect("cherry-pick");
+
+        assert!(
+            summary.starts_with("Cherry-picked 1 commit(s)"),
+            "{summary}"
+        );
+        assert!(repo.join("new.txt").exists());
+        assert!(repo.join("other.txt").exists());
+        assert!(!worktree.path.exists());
+    }
+
+    #[tokio::test]
+    async fn discard_drops_changes() {
+        let temp_dir = TempDir::new().expect("tempdir");
+        let repo = create_test_git_repo(&temp_dir).await;
+        let worktree = create_worktree(&repo, &temp_dir.path().join("home"))
+            .await
+            .expect("create worktree");
+        fs::write(worktree.path.join("src/lib.rs"), "changed\n").expect("write file");
+
+        resolve_worktree(&worktree, WorktreeResolution::Discard)
+            .await
+            .expect("discard");
+
+        assert_eq!(
+            fs::read_to_string(repo.join("src/lib.rs")).ok(),
+            Some("original\n".to_string())
+        );
+        assert!(!worktree.path.exists());
+        assert!(!branch_exists(&repo, &worktree.branch).await);
+    }
+
+    #[tokio::test]
+    async fn failed_merge_keeps_worktree() {
+        let temp_dir = TempDir::new().expect("tempdir");
+        let repo = create_test_git_repo(&temp_dir).await;
+        let worktree = create_worktree(&repo, &temp_dir.path().join("home"))
+            .await
+            .expect("create worktree");
+        fs::write(worktree.path.join("src/lib.rs"), "theirs\n").expect("write file");
+        fs::write(repo.join("src/lib.rs"), "ours\n").expect("write file");
+        git(&["commit", "-am", "Conflicting change"], &repo).await;
+
+        let result = resolve_worktree(&worktree, WorktreeResolution::Merge).await;
+
+        assert!(result.is_err(), "{result:?}");
+        assert!(worktree.path.exists());
+        assert_eq!(
+            fs::read_to_string(repo.join("src/lib.rs")).ok(),
+            Some("ours\n".to_string())
+        );
+    }
+}
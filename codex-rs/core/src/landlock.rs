@@ -6,6 +6,12 @@ use std::path::Path;
 use std::path::PathBuf;
 use tokio::process::Child;
 
+/// Special argument used to re-invoke the Codex executable as the Linux
+/// sandbox helper. This is needed when the caller cannot override argv[0]
+/// (e.g., when the command is spawned in a PTY), which is what
+/// [`spawn_command_under_linux_sandbox`] relies on.
+pub const CODEX_LINUX_SANDBOX_ARG1: &str = "--codex-run-as-linux-sandbox";
+
 /// Spawn a shell tool command under the Linux Landlock+seccomp sandbox helper
 /// (codex-linux-sandbox).
 ///
@@ -39,7 +45,7 @@ where
 }
 
 /// Converts the sandbox policy into the CLI invocation for `codex-linux-sandbox`.
-fn create_linux_sandbox_command_args(
+pub(crate) fn create_linux_sandbox_command_args(
     command: Vec<String>,
     sandbox_policy: &SandboxPolicy,
     cwd: &Path,
@@ -5,10 +5,12 @@
 // the TUI or the tracing stack).
 #![deny(clippy::print_stdout, clippy::print_stderr)]
 
+mod anthropic_messages;
 mod apply_patch;
 pub mod auth;
 pub mod bash;
 mod chat_completions;
+mod checkpoint;
 mod client;
 mod client_common;
 pub mod codex;
@@ -21,21 +23,28 @@ pub mod config_profile;
 pub mod config_types;
 mod conversation_history;
 pub mod custom_prompts;
+mod delegate;
 mod environment_context;
 pub mod error;
 pub mod exec;
 mod exec_command;
 pub mod exec_env;
+mod exec_policy;
 mod flags;
+mod gemini;
 pub mod git_info;
+pub mod git_worktree;
 pub mod internal_storage;
 mod is_safe_command;
 pub mod landlock;
 mod mcp_connection_manager;
+mod mcp_resources;
 mod mcp_tool_call;
 mod message_history;
 mod model_provider_info;
+mod network_proxy;
 pub mod parse_command;
+mod stream_recording;
 mod truncate;
 mod unified_exec;
 mod user_instructions;
@@ -58,6 +67,7 @@ mod openai_model_info;
 mod openai_tools;
 pub mod plan_tool;
 pub mod project_doc;
+pub mod review;
 mod rollout;
 pub(crate) mod safety;
 pub mod seatbelt;
@@ -70,14 +80,24 @@ pub use rollout::ARCHIVED_SESSIONS_SUBDIR;
 pub use rollout::RolloutRecorder;
 pub use rollout::SESSIONS_SUBDIR;
 pub use rollout::SessionMeta;
+pub use rollout::export::export_rollout;
 pub use rollout::find_conversation_path_by_id_str;
+pub use rollout::index::SessionSearch;
 pub use rollout::list::ConversationItem;
 pub use rollout::list::ConversationsPage;
 pub use rollout::list::Cursor;
+pub use rollout::patch_diff::AppliedPatchDiff;
+pub use rollout::read_rollout_text;
+pub use rollout::retention::PruneReport;
+pub use rollout::retention::archive_rollout;
+pub use rollout::retention::delete_rollout;
+pub use rollout::retention::is_recorded_rollout;
+pub use rollout::retention::prune_sessions;
 mod user_notification;
 pub mod util;
 
 pub use apply_patch::CODEX_APPLY_PATCH_ARG1;
+pub use landlock::CODEX_LINUX_SANDBOX_ARG1;
 pub use safety::get_platform_sandbox;
 // Re-export the protocol types from the standalone `codex-protocol` crate so existing
 // `codex_core::protocol::...` references continue to work across the workspace.
@@ -4,11 +4,18 @@
 //! configured server (keyed by the *server name*). It offers convenience
 //! helpers to query the available tools across *all* servers and returns them
 //! in a single aggregated map using the fully-qualified tool name
-//! `"<server><MCP_TOOL_NAME_DELIMITER><tool>"` as the key.
+//! `"<server><MCP_TOOL_NAME_DELIMITER><tool>"` as the key. Servers may be
+//! local subprocesses speaking stdio or remote services reached over HTTP.
+//!
+//! Resources and prompts are listed once at startup for the servers that
+//! advertise them and are cached per server name.
 
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::ffi::OsString;
+use std::future::Future;
+use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -17,7 +24,14 @@ use anyhow::Result;
 use anyhow::anyhow;
 use codex_mcp_client::McpClient;
 use mcp_types::ClientCapabilities;
+use mcp_types::GetPromptResult;
 use mcp_types::Implementation;
+use mcp_types::ListPromptsRequestParams;
+use mcp_types::ListResourcesRequestParams;
+use mcp_types::Prompt;
+use mcp_types::ReadResourceResult;
+use mcp_types::Resource;
+use mcp_types::ServerCapabilities;
 use mcp_types::Tool;
 
 use serde_json::json;
@@ -27,7 +41,14 @@ use tokio::task::JoinSet;
 use tracing::info;
 use tracing::warn;
 
+use crate::config_types::McpHttpTransport;
 use crate::config_types::McpServerConfig;
+use crate::config_types::McpServerSandbox;
+use crate::config_types::McpServerTransportConfig;
+use crate::config_types::McpToolApproval;
+use crate::landlock::CODEX_LINUX_SANDBOX_ARG1;
+use crate::landlock::create_linux_sandbox_command_args;
+use crate::protocol::SandboxPolicy;
 
 /// Delimiter used to separate the server name from the tool name in a fully
 /// qualified tool name.
@@ -40,6 +61,10 @@ const MAX_TOOL_NAME_LENGTH: usize = 64;
 /// Default timeout for initializing MCP server & initially listing tools.
 const DEFAULT_STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
 
+/// Upper bound on the number of pages requested from a paginated list
+/// endpoint, in case a server keeps returning a cursor.
+const MAX_LIST_PAGES: usize = 100;
+
 /// Map that holds a startup error for every MCP server that could **not** be
 /// spawned successfully.
 pub type ClientStartErrors = HashMap<String, anyhow::Error>;
@@ -85,6 +110,8 @@ struct ToolInfo {
 struct ManagedClient {
     client: Arc<McpClient>,
     startup_timeout: Duration,
+    capabilities: ServerCapabilities,
+    config: McpServerConfig,
 }
 
 /// A thin wrapper around a set of running [`McpClient`] instances.
@@ -98,6 +125,12 @@ pub(crate) struct McpConnectionManager {
 
     /// Fully qualified tool name -> tool instance.
     tools: HashMap<String, ToolInfo>,
+
+    /// Server name -> resources listed at startup.
+    resources: HashMap<String, Vec<Resource>>,
+
+    /// Server name -> prompts listed at startup.
+    prompts: HashMap<String, Vec<Prompt>>,
 }
 
 impl McpConnectionManager {
@@ -106,11 +139,15 @@ impl McpConnectionManager {
     /// * `mcp_servers` – Map loaded from the user configuration where *keys*
     ///   are human-readable server identifiers and *values* are the spawn
     ///   instructions.
+    /// * `codex_linux_sandbox_exe` and `cwd` – Used to launch stdio servers
+    ///   that set `sandbox`, with `cwd` as their writable workspace.
     ///
     /// Servers that fail to start are reported in `ClientStartErrors`: the
     /// user should be informed about these errors.
     pub async fn new(
         mcp_servers: HashMap<String, McpServerConfig>,
+        codex_linux_sandbox_exe: Option<PathBuf>,
+        cwd: PathBuf,
     ) -> Result<(Self, ClientStartErrors)> {
         // Early exit if no servers are configured.
         if mcp_servers.is_empty() {
@@ -120,6 +157,7 @@ impl McpConnectionManager {
         // Launch all configured servers concurrently.
         let mut join_set = JoinSet::new();
         let mut errors = ClientStartErrors::new();
+        let mut configs: HashMap<String, McpServerConfig> = HashMap::new();
 
         for (server_name, cfg) in mcp_servers {
             // Validate server name before spawning
@@ -136,17 +174,46 @@ impl McpConnectionManager {
                 .startup_timeout_ms
                 .map(Duration::from_millis)
                 .unwrap_or(DEFAULT_STARTUP_TIMEOUT);
+            configs.insert(server_name.clone(), cfg.clone());
+            let codex_linux_sandbox_exe = codex_linux_sandbox_exe.clone();
+            let cwd = cwd.clone();
 
             join_set.spawn(async move {
-                let McpServerConfig {
-                    command, args, env, ..
-                } = cfg;
-                let client_res = McpClient::new_stdio_client(
-                    command.into(),
-                    args.into_iter().map(OsString::from).collect(),
-                    env,
-                )
-                .await;
+                let client_res = match cfg.transport {
+                    McpServerTransportConfig::Stdio { command, args, env } => {
+                        match stdio_server_command(
+                            command,
+                            args,
+                            cfg.sandbox.as_ref(),
+                            codex_linux_sandbox_exe.as_deref(),
+                            &cwd,
+                        ) {
+                            Ok((program, args)) => McpClient::new_stdio_client(program, args, env)
+                                .await
+                                .map_err(anyhow::Error::from),
+                            Err(e) => Err(e),
+                        }
+                    }
+                    McpServerTransportConfig::Http {
+                        url,
+                        transport,
+                        http_headers,
+                        env_http_headers,
+                        bearer_token_env_var,
+                    } => match resolve_http_headers(
+                        http_headers,
+                        env_http_headers,
+                        bearer_token_env_var,
+                    ) {
+                        Ok(headers) => match transport {
+                            McpHttpTransport::StreamableHttp => {
+                                McpClient::new_streamable_http_client(url, headers).await
+                            }
+                            McpHttpTransport::Sse => McpClient::new_sse_client(url, headers).await,
+                        },
+                        Err(e) => Err(e),
+                    },
+                };
                 match client_res {
                     Ok(client) => {
                         // Initialize the client.
@@ -179,11 +246,14 @@ impl McpConnectionManager {
                             )
                             .await
                         {
-                            Ok(_response) => (server_name, Ok((client, startup_timeout))),
+                            Ok(response) => (
+                                server_name,
+                                Ok((client, startup_timeout, response.capabilities)),
+                            ),
                             Err(e) => (server_name, Err(e)),
                         }
                     }
-                    Err(e) => (server_name, Err(e.into())),
+                    Err(e) => (server_name, Err(e)),
                 }
             });
         }
@@ -199,17 +269,22 @@ impl McpConnectionManager {
                 }
             };
 
-            match client_res {
-                Ok((client, startup_timeout)) => {
+            match (client_res, configs.remove(&server_name)) {
+                (Ok((client, startup_timeout, capabilities)), Some(config)) => {
                     clients.insert(
                         server_name,
                         ManagedClient {
                             client: Arc::new(client),
                             startup_timeout,
+                            capabilities,
+                            config,
                         },
                     );
                 }
-                Err(e) => {
+                (Ok(_), None) => {
+                    warn!("MCP server '{server_name}' started without a configuration");
+                }
+                (Err(e), _) => {
                     errors.insert(server_name, e);
                 }
             }
@@ -225,7 +300,28 @@ impl McpConnectionManager {
 
         let tools = qualify_tools(all_tools);
 
-        Ok((Self { clients, tools }, errors))
+        let resources = list_per_server(
+            &clients,
+            |managed| managed.capabilities.resources.is_some(),
+            |client, timeout| async move { list_resources_paginated(&client, timeout).await },
+        )
+        .await;
+        let prompts = list_per_server(
+            &clients,
+            |managed| managed.capabilities.prompts.is_some(),
+            |client, timeout| async move { list_prompts_paginated(&client, timeout).await },
+        )
+        .await;
+
+        Ok((
+            Self {
+                clients,
+                tools,
+                resources,
+                prompts,
+            },
+            errors,
+        ))
     }
 
     /// Returns a single map that contains **all** tools. Each key is the
@@ -245,19 +341,85 @@ impl McpConnectionManager {
         arguments: Option<serde_json::Value>,
         timeout: Option<Duration>,
     ) -> Result<mcp_types::CallToolResult> {
-        let client = self
-            .clients
-            .get(server)
-            .ok_or_else(|| anyhow!("unknown MCP server '{server}'"))?
-            .client
-            .clone();
-
-        client
+        self.client(server)?
             .call_tool(tool.to_string(), arguments, timeout)
             .await
             .with_context(|| format!("tool call failed for `{server}/{tool}`"))
     }
 
+    /// Whether calling `tool` of `server` needs the user's approval, as set by
+    /// the server's `approval` setting.
+    pub fn requires_approval(&self, server: &str, tool: &str) -> bool {
+        let Some(managed) = self.clients.get(server) else {
+            return false;
+        };
+        let tool = self
+            .tools
+            .values()
+            .find(|info| info.server_name == server && info.tool_name == tool)
+            .map(|info| &info.tool);
+        tool_requires_approval(managed.config.approval, tool)
+    }
+
+    /// Returns the resources of every server that offers any, keyed by
+    /// server name.
+    pub fn list_all_resources(&self) -> HashMap<String, Vec<Resource>> {
+        self.resources.clone()
+    }
+
+    pub fn has_resources(&self) -> bool {
+        self.resources
+            .values()
+            .any(|resources| !resources.is_empty())
+    }
+
+    /// Whether `server` listed a resource with exactly this `uri`.
+    pub fn has_resource(&self, server: &str, uri: &str) -> bool {
+        self.resources
+            .get(server)
+            .is_some_and(|resources| resources.iter().any(|resource| resource.uri == uri))
+    }
+
+    /// Read the resource identified by `uri` from `server`.
+    pub async fn read_resource(
+        &self,
+        server: &str,
+        uri: &str,
+        timeout: Option<Duration>,
+    ) -> Result<ReadResourceResult> {
+        self.client(server)?
+            .read_resource(uri.to_string(), timeout)
+            .await
+            .with_context(|| format!("resource read failed for `{server}/{uri}`"))
+    }
+
+    /// Returns the prompts of every server that offers any, keyed by server
+    /// name.
+    pub fn list_all_prompts(&self) -> HashMap<String, Vec<Prompt>> {
+        self.prompts.clone()
+    }
+
+    /// Render the prompt `name` of `server` with the given arguments.
+    pub async fn get_prompt(
+        &self,
+        server: &str,
+        name: &str,
+        arguments: Option<serde_json::Value>,
+        timeout: Option<Duration>,
+    ) -> Result<GetPromptResult> {
+        self.client(server)?
+            .get_prompt(name.to_string(), arguments, timeout)
+            .await
+            .with_context(|| format!("prompt request failed for `{server}/{name}`"))
+    }
+
+    fn client(&self, server: &str) -> Result<Arc<McpClient>> {
+        self.clients
+            .get(server)
+            .map(|managed| managed.client.clone())
+            .ok_or_else(|| anyhow!("unknown MCP server '{server}'"))
+    }
+
     pub fn parse_tool_name(&self, tool_name: &str) -> Option<(String, String)> {
         self.tools
             .get(tool_name)
@@ -300,7 +462,11 @@ async fn list_all_tools(clients: &HashMap<String, ManagedClient>) -> Result<Vec<
             continue;
         };
 
+        let config = clients.get(&server_name).map(|managed| &managed.config);
         for tool in list_result.tools {
+            if config.is_some_and(|config| !config.is_tool_enabled(&tool.name)) {
+                continue;
+            }
             let tool_info = ToolInfo {
                 server_name: server_name.clone(),
                 tool_name: tool.name.clone(),
@@ -319,6 +485,108 @@ async fn list_all_tools(clients: &HashMap<String, ManagedClient>) -> Result<Vec<
     Ok(aggregated)
 }
 
+/// Run `list` concurrently against every server accepted by `supports` and
+/// collect the non-empty results by server name. Failures are logged and the
+/// server is left out.
+async fn list_per_server<T, F, Fut>(
+    clients: &HashMap<String, ManagedClient>,
+    supports: impl Fn(&ManagedClient) -> bool,
+    list: F,
+) -> HashMap<String, Vec<T>>
+where
+    T: Send + 'static,
+    F: Fn(Arc<McpClient>, Duration) -> Fut,
+    Fut: Future<Output = Result<Vec<T>>> + Send + 'static,
+{
+    let mut join_set = JoinSet::new();
+    for (server_name, managed_client) in clients {
+        if !supports(managed_client) {
+            continue;
+        }
+        let server_name = server_name.clone();
+        let fut = list(
+            managed_client.client.clone(),
+            managed_client.startup_timeout,
+        );
+        join_set.spawn(async move { (server_name, fut.await) });
+    }
+
+    let mut aggregated = HashMap::new();
+    while let Some(join_res) = join_set.join_next().await {
+        match join_res {
+            Ok((server_name, Ok(items))) => {
+                if !items.is_empty() {
+                    aggregated.insert(server_name, items);
+                }
+            }
+            Ok((server_name, Err(e))) => {
+                warn!("Failed to list MCP server '{server_name}': {e:#}");
+            }
+            Err(e) => warn!("Task panic when listing MCP server: {e:#}"),
+        }
+    }
+    aggregated
+}
+
+async fn list_resources_paginated(client: &McpClient, timeout: Duration) -> Result<Vec<Resource>> {
+    let mut resources = Vec::new();
+    let mut cursor = None;
+    for _ in 0..MAX_LIST_PAGES {
+        let params = cursor.map(|cursor| ListResourcesRequestParams {
+            cursor: Some(cursor),
+        });
+        let result = client.list_resources(params, Some(timeout)).await?;
+        resources.extend(result.resources);
+        cursor = result.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+    Ok(resources)
+}
+
+async fn list_prompts_paginated(client: &McpClient, timeout: Duration) -> Result<Vec<Prompt>> {
+    let mut prompts = Vec::new();
+    let mut cursor = None;
+    for _ in 0..MAX_LIST_PAGES {
+        let params = cursor.map(|cursor| ListPromptsRequestParams {
+            cursor: Some(cursor),
+        });
+        let result = client.list_prompts(params, Some(timeout)).await?;
+        prompts.extend(result.prompts);
+        cursor = result.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+    Ok(prompts)
+}
+
+/// Combine the static headers, headers sourced from environment variables and
+/// the bearer token configured for an HTTP MCP server.
+fn resolve_http_headers(
+    http_headers: Option<HashMap<String, String>>,
+    env_http_headers: Option<HashMap<String, String>>,
+    bearer_token_env_var: Option<String>,
+) -> Result<HashMap<String, String>> {
+    let mut headers = http_headers.unwrap_or_default();
+    for (header, env_var) in env_http_headers.unwrap_or_default() {
+        if let Ok(value) = std::env::var(&env_var)
+            && !value.trim().is_empty()
+        {
+            headers.insert(header, value);
+        }
+    }
+    if let Some(env_var) = bearer_token_env_var {
+        let token = std::env::var(&env_var)
+            .ok()
+            .filter(|token| !token.trim().is_empty())
+            .ok_or_else(|| anyhow!("environment variable `{env_var}` is not set"))?;
+        headers.insert("Authorization".to_string(), format!("Bearer {token}"));
+    }
+    Ok(headers)
+}
+
 fn is_valid_mcp_server_name(server_name: &str) -> bool {
     !server_name.is_empty()
         && server_name
@@ -326,9 +594,71 @@ fn is_valid_mcp_server_name(server_name: &str) -> bool {
             .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
 }
 
+/// Returns the program and arguments that launch a stdio server. Servers
+/// with a `sandbox` are launched through `codex-linux-sandbox`, with a
+/// workspace-write policy rooted at `cwd`.
+fn stdio_server_command(
+    command: String,
+    args: Vec<String>,
+    sandbox: Option<&McpServerSandbox>,
+    codex_linux_sandbox_exe: Option<&Path>,
+    cwd: &Path,
+) -> Result<(OsString, Vec<OsString>)> {
+    let Some(sandbox) = sandbox else {
+        return Ok((
+            command.into(),
+            args.into_iter().map(OsString::from).collect(),
+        ));
+    };
+    if !cfg!(target_os = "linux") {
+        return Err(anyhow!("sandboxed MCP servers are only supported on Linux"));
+    }
+    let Some(codex_linux_sandbox_exe) = codex_linux_sandbox_exe else {
+        return Err(anyhow!(
+            "cannot sandbox MCP server: codex-linux-sandbox executable not available"
+        ));
+    };
+
+    let sandbox_policy = SandboxPolicy::WorkspaceWrite {
+        writable_roots: sandbox.writable_roots.clone(),
+        network_access: sandbox.network_access,
+        exclude_tmpdir_env_var: false,
+        exclude_slash_tmp: false,
+        readable_roots: None,
+        deny_read: Vec::new(),
+        allowed_hosts: Vec::new(),
+    };
+    let command = std::iter::once(command).chain(args).collect();
+    // The helper is selected with an argument rather than argv[0], which
+    // `McpClient` does not let us set.
+    let args = std::iter::once(CODEX_LINUX_SANDBOX_ARG1.to_string())
+        .chain(create_linux_sandbox_command_args(
+            command,
+            &sandbox_policy,
+            cwd,
+        ))
+        .map(OsString::from)
+        .collect();
+    Ok((codex_linux_sandbox_exe.as_os_str().to_owned(), args))
+}
+
+/// Whether `approval` asks the user before calling `tool`. Tools that are
+/// not known are treated as not read-only.
+fn tool_requires_approval(approval: McpToolApproval, tool: Option<&Tool>) -> bool {
+    match approval {
+        McpToolApproval::Always => true,
+        McpToolApproval::Never => false,
+        McpToolApproval::Untrusted => !tool
+            .and_then(|tool| tool.annotations.as_ref())
+            .and_then(|annotations| annotations.read_only_hint)
+            .unwrap_or(false),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use mcp_types::ToolAnnotations;
     use mcp_types::ToolInputSchema;
 
     fn create_test_tool(server_name: &str, tool_name: &str) -> ToolInfo {
@@ -350,6 +680,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_resolve_http_headers() {
+        let headers = resolve_http_headers(
+            Some(HashMap::from([("X-Team".to_string(), "infra".to_string())])),
+            Some(HashMap::from([(
+                "X-Missing".to_string(),
+                "CODEX_TEST_MCP_HEADER_THAT_IS_NOT_SET".to_string(),
+            )])),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            headers,
+            HashMap::from([("X-Team".to_string(), "infra".to_string())])
+        );
+
+        let err = resolve_http_headers(
+            None,
+            None,
+            Some("CODEX_TEST_MCP_TOKEN_THAT_IS_NOT_SET".to_string()),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "environment variable `CODEX_TEST_MCP_TOKEN_THAT_IS_NOT_SET` is not set"
+        );
+    }
+
     #[test]
     fn test_qualify_tools_short_non_duplicated_names() {
         let tools = vec![
@@ -412,4 +770,100 @@ mod tests {
             "my_server__yet_another_e1c3987bd9c50b826cbe1687966f79f0c602d19ca"
         );
     }
+
+    #[test]
+    fn test_tool_requires_approval() {
+        let unannotated = create_test_tool("server", "write").tool;
+        let mut read_only = create_test_tool("server", "read").tool;
+        read_only.annotations = Some(ToolAnnotations {
+            destructive_hint: None,
+            idempotent_hint: None,
+            open_world_hint: None,
+            read_only_hint: Some(true),
+            title: None,
+        });
+
+        assert!(tool_requires_approval(
+            McpToolApproval::Always,
+            Some(&read_only)
+        ));
+        assert!(!tool_requires_approval(
+            McpToolApproval::Never,
+            Some(&unannotated)
+        ));
+        assert!(!tool_requires_approval(
+            McpToolApproval::Untrusted,
+            Some(&read_only)
+        ));
+        assert!(tool_requires_approval(
+            McpToolApproval::Untrusted,
+            Some(&unannotated)
+        ));
+        assert!(tool_requires_approval(McpToolApproval::Untrusted, None));
+    }
+
+    #[test]
+    fn test_stdio_server_command_without_sandbox() {
+        let (program, args) = stdio_server_command(
+            "npx".to_string(),
+            vec!["-y".to_string(), "mcp-server".to_string()],
+            None,
+            Some(Path::new("/usr/bin/codex")),
+            Path::new("/workspace"),
+        )
+        .expect("command");
+        assert_eq!(program, OsString::from("npx"));
+        assert_eq!(
+            args,
+            vec![OsString::from("-y"), OsString::from("mcp-server")]
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_stdio_server_command_with_sandbox() {
+        let sandbox = McpServerSandbox {
+            writable_roots: vec![PathBuf::from("/cache")],
+            network_access: false,
+        };
+        let (program, args) = stdio_server_command(
+            "npx".to_string(),
+            vec!["-y".to_string(), "mcp-server".to_string()],
+            Some(&sandbox),
+            Some(Path::new("/usr/bin/codex")),
+            Path::new("/workspace"),
+        )
+        .expect("command");
+        assert_eq!(program, OsString::from("/usr/bin/codex"));
+
+        let args: Vec<String> = args
+            .into_iter()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(args[0], CODEX_LINUX_SANDBOX_ARG1);
+        assert_eq!(args[1], "/workspace");
+        let policy: SandboxPolicy = serde_json::from_str(&args[2]).expect("policy");
+        assert_eq!(
+            policy,
+            SandboxPolicy::WorkspaceWrite {
+                writable_roots: vec![PathBuf::from("/cache")],
+                network_access: false,
+                exclude_tmpdir_env_var: false,
+                exclude_slash_tmp: false,
+                readable_roots: None,
+                deny_read: Vec::new(),
+                allowed_hosts: Vec::new(),
+            }
+        );
+        assert_eq!(args[3..], ["--", "npx", "-y", "mcp-server"]);
+
+        let missing_exe = stdio_server_command(
+            "npx".to_string(),
+            Vec::new(),
+            Some(&sandbox),
+            None,
+            Path::new("/workspace"),
+        );
+        assert!(missing_exe.is_err());
+    }
 }
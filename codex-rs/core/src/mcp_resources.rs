@@ -0,0 +1,318 @@
+//! Built-in tools that let the model list and read the resources offered by
+//! MCP servers, and resolution of `@server:uri` resource mentions in user
+//! input.
+
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::FunctionCallOutputPayload;
+use codex_protocol::models::ResponseInputItem;
+use codex_protocol::models::ResponseItem;
+use mcp_types::ReadResourceResult;
+use mcp_types::ReadResourceResultContents;
+use mcp_types::Resource;
+use serde::Deserialize;
+use serde::Serialize;
+use tracing::warn;
+
+use crate::mcp_connection_manager::McpConnectionManager;
+use crate::openai_tools::JsonSchema;
+use crate::openai_tools::OpenAiTool;
+use crate::openai_tools::ResponsesApiTool;
+use crate::protocol::InputItem;
+
+pub(crate) const LIST_MCP_RESOURCES_TOOL_NAME: &str = "list_mcp_resources";
+pub(crate) const READ_MCP_RESOURCE_TOOL_NAME: &str = "read_mcp_resource";
+
+pub(crate) static LIST_MCP_RESOURCES_TOOL: LazyLock<OpenAiTool> = LazyLock::new(|| {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "server".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Only list the resources of this MCP server. Omit to list all servers.".to_string(),
+            ),
+        },
+    );
+
+    OpenAiTool::Function(ResponsesApiTool {
+        name: LIST_MCP_RESOURCES_TOOL_NAME.to_string(),
+        description: "Lists the resources (documents, schemas, etc.) offered by the configured MCP servers. Read one with read_mcp_resource.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false),
+        },
+    })
+});
+
+pub(crate) static READ_MCP_RESOURCE_TOOL: LazyLock<OpenAiTool> = LazyLock::new(|| {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "server".to_string(),
+        JsonSchema::String {
+            description: Some("Name of the MCP server that offers the resource".to_string()),
+        },
+    );
+    properties.insert(
+        "uri".to_string(),
+        JsonSchema::String {
+            description: Some("URI of the resource, as returned by list_mcp_resources".to_string()),
+        },
+    );
+
+    OpenAiTool::Function(ResponsesApiTool {
+        name: READ_MCP_RESOURCE_TOOL_NAME.to_string(),
+        description: "Reads the contents of a resource offered by an MCP server.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["server".to_string(), "uri".to_string()]),
+            additional_properties: Some(false),
+        },
+    })
+});
+
+#[derive(Debug, Default, Deserialize)]
+struct ListMcpResourcesArgs {
+    #[serde(default)]
+    server: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReadMcpResourceArgs {
+    server: String,
+    uri: String,
+}
+
+#[derive(Serialize)]
+struct ListedResource<'a> {
+    server: &'a str,
+    #[serde(flatten)]
+    resource: &'a Resource,
+}
+
+pub(crate) fn handle_list_mcp_resources(
+    manager: &McpConnectionManager,
+    arguments: String,
+    call_id: String,
+) -> ResponseInputItem {
+    let args = if arguments.trim().is_empty() {
+        ListMcpResourcesArgs::default()
+    } else {
+        match serde_json::from_str::<ListMcpResourcesArgs>(&arguments) {
+            Ok(args) => args,
+            Err(e) => return failure(call_id, format!("failed to parse function arguments: {e}")),
+        }
+    };
+
+    let all_resources = manager.list_all_resources();
+    if let Some(server) = &args.server
+        && !all_resources.contains_key(server)
+    {
+        return failure(
+            call_id,
+            format!("MCP server '{server}' does not offer any resources"),
+        );
+    }
+
+    let mut servers: Vec<&String> = all_resources
+        .keys()
+        .filter(|server| args.server.as_ref().is_none_or(|wanted| wanted == *server))
+        .collect();
+    servers.sort();
+    let listed: Vec<ListedResource> = servers
+        .into_iter()
+        .flat_map(|server| {
+            all_resources[server]
+                .iter()
+                .map(move |resource| ListedResource { server, resource })
+        })
+        .collect();
+
+    match serde_json::to_string(&listed) {
+        Ok(content) => ResponseInputItem::FunctionCallOutput {
+            call_id,
+            output: FunctionCallOutputPayload {
+                content,
+                success: Some(true),
+            },
+        },
+        Err(e) => failure(call_id, format!("failed to serialize resources: {e}")),
+    }
+}
+
+pub(crate) async fn handle_read_mcp_resource(
+    manager: &McpConnectionManager,
+    arguments: String,
+    call_id: String,
+) -> ResponseInputItem {
+    let args = match serde_json::from_str::<ReadMcpResourceArgs>(&arguments) {
+        Ok(args) => args,
+        Err(e) => return failure(call_id, format!("failed to parse function arguments: {e}")),
+    };
+
+    match manager.read_resource(&args.server, &args.uri, None).await {
+        Ok(result) => ResponseInputItem::FunctionCallOutput {
+            call_id,
+            output: FunctionCallOutputPayload {
+                content: format_resource_contents(&result),
+                success: Some(true),
+            },
+        },
+        Err(e) => failure(call_id, format!("{e:#}")),
+    }
+}
+
+fn failure(call_id: String, content: String) -> ResponseInputItem {
+    ResponseInputItem::FunctionCallOutput {
+        call_id,
+        output: FunctionCallOutputPayload {
+            content,
+            success: Some(false),
+        },
+    }
+}
+
+/// Flattens the contents of a resource into text. Binary contents are
+/// replaced with a short placeholder.
+pub(crate) fn format_resource_contents(result: &ReadResourceResult) -> String {
+    result
+        .contents
+        .iter()
+        .map(|contents| match contents {
+            ReadResourceResultContents::TextResourceContents(text) => text.text.clone(),
+            ReadResourceResultContents::BlobResourceContents(blob) => format!(
+                "[binary content of {} ({}) omitted]",
+                blob.uri,
+                blob.mime_type.as_deref().unwrap_or("unknown type")
+            ),
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Reads every resource mentioned as `@server:uri` in the text of `input` and
+/// returns their contents as a user message to record after the input.
+/// Mentions that do not name a resource listed by the server are left alone,
+/// so e.g. e-mail addresses are not mistaken for resources.
+pub(crate) async fn resolve_resource_mentions(
+    manager: &McpConnectionManager,
+    input: &[InputItem],
+) -> Option<ResponseItem> {
+    let mut mentions: Vec<(String, String)> = Vec::new();
+    for item in input {
+        if let InputItem::Text { text } = item {
+            for mention in
+                find_resource_mentions(text, |server, uri| manager.has_resource(server, uri))
+            {
+                if !mentions.contains(&mention) {
+                    mentions.push(mention);
+                }
+            }
+        }
+    }
+    if mentions.is_empty() {
+        return None;
+    }
+
+    let mut content = Vec::with_capacity(mentions.len());
+    for (server, uri) in mentions {
+        let body = match manager.read_resource(&server, &uri, None).await {
+            Ok(result) => format_resource_contents(&result),
+            Err(e) => {
+                warn!("failed to read mentioned MCP resource: {e:#}");
+                format!("failed to read resource: {e:#}")
+            }
+        };
+        content.push(ContentItem::InputText {
+            text: format!(
+                "<mcp_resource server=\"{server}\" uri=\"{uri}\">\n{body}\n</mcp_resource>"
+            ),
+        });
+    }
+    Some(ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content,
+    })
+}
+
+/// Finds the `@server:uri` tokens in `text` for which `is_known` returns
+/// true. Trailing punctuation is ignored when the full token is unknown.
+fn find_resource_mentions(
+    text: &str,
+    is_known: impl Fn(&str, &str) -> bool,
+) -> Vec<(String, String)> {
+    const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', ')', '\'', '"'];
+
+    let mut mentions = Vec::new();
+    for token in text.split_whitespace() {
+        let Some((server, uri)) = token
+            .strip_prefix('@')
+            .and_then(|mention| mention.split_once(':'))
+        else {
+            continue;
+        };
+        if server.is_empty() || uri.is_empty() {
+            continue;
+        }
+        let trimmed = uri.trim_end_matches(TRAILING_PUNCTUATION);
+        let uri = if is_known(server, uri) {
+            uri
+        } else if !trimmed.is_empty() && is_known(server, trimmed) {
+            trimmed
+        } else {
+            continue;
+        };
+        mentions.push((server.to_string(), uri.to_string()));
+    }
+    mentions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_types::BlobResourceContents;
+    use mcp_types::TextResourceContents;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn finds_known_resource_mentions() {
+        let known = |server: &str, uri: &str| server == "docs" && uri == "docs://api/v1";
+        assert_eq!(
+            vec![
+                ("docs".to_string(), "docs://api/v1".to_string()),
+                ("docs".to_string(), "docs://api/v1".to_string()),
+            ],
+            find_resource_mentions(
+                "see @docs:docs://api/v1 and @docs:docs://api/v1. but not @docs:docs://other or me@example.com",
+                known,
+            )
+        );
+    }
+
+    #[test]
+    fn formats_text_and_binary_contents() {
+        let result = ReadResourceResult {
+            contents: vec![
+                ReadResourceResultContents::TextResourceContents(TextResourceContents {
+                    mime_type: Some("text/markdown".to_string()),
+                    text: "# API".to_string(),
+                    uri: "docs://api".to_string(),
+                }),
+                ReadResourceResultContents::BlobResourceContents(BlobResourceContents {
+                    blob: "AAAA".to_string(),
+                    mime_type: Some("image/png".to_string()),
+                    uri: "docs://logo".to_string(),
+                }),
+            ],
+        };
+        assert_eq!(
+            "# API\n\n[binary content of docs://logo (image/png) omitted]",
+            format_resource_contents(&result)
+        );
+    }
+}
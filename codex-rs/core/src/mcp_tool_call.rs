@@ -9,11 +9,13 @@ use crate::protocol::EventMsg;
 use crate::protocol::McpInvocation;
 use crate::protocol::McpToolCallBeginEvent;
 use crate::protocol::McpToolCallEndEvent;
+use crate::protocol::ReviewDecision;
 use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseInputItem;
 
 /// Handles the specified tool call dispatches the appropriate
-/// `McpToolCallBegin` and `McpToolCallEnd` events to the `Session`.
+/// `McpToolCallBegin` and `McpToolCallEnd` events to the `Session`. Asks the
+/// user first if the server's `approval` setting requires it.
 pub(crate) async fn handle_mcp_tool_call(
     sess: &Session,
     sub_id: &str,
@@ -49,6 +51,24 @@ pub(crate) async fn handle_mcp_tool_call(
         arguments: arguments_value.clone(),
     };
 
+    if sess.mcp_tool_call_requires_approval(&server, &tool_name) {
+        let rx_approve = sess
+            .request_mcp_tool_call_approval(sub_id.to_string(), call_id.clone(), invocation.clone())
+            .await;
+        match rx_approve.await.unwrap_or_default() {
+            ReviewDecision::Approved => {}
+            ReviewDecision::ApprovedForSession => {
+                sess.add_approved_mcp_tool(server.clone(), tool_name.clone());
+            }
+            ReviewDecision::Denied | ReviewDecision::Abort => {
+                return ResponseInputItem::McpToolCallOutput {
+                    call_id,
+                    result: Err("tool call rejected by user".to_string()),
+                };
+            }
+        }
+    }
+
     let tool_call_begin_event = EventMsg::McpToolCallBegin(McpToolCallBeginEvent {
         call_id: call_id.clone(),
         invocation: invocation.clone(),
@@ -13,11 +13,21 @@
 //! trailing `\n`) and write it with a **single `write(2)` system call** while
 //! the file descriptor is opened with the `O_APPEND` flag. POSIX guarantees
 //! that writes up to `PIPE_BUF` bytes are atomic in that case.
+//!
+//! When `history.max_bytes` is set and an append pushes the file past that
+//! size, the oldest entries are dropped by writing the remaining ones to a
+//! temporary file and renaming it over `history.jsonl` while the advisory
+//! lock is still held. The rename gives the file a new identifier, so
+//! lookups made against the old one return nothing instead of the wrong entry.
 
 use std::fs::File;
 use std::fs::OpenOptions;
+use std::io::Read;
 use std::io::Result;
+use std::io::Seek;
+use std::io::SeekFrom;
 use std::io::Write;
+use std::path::Path;
 use std::path::PathBuf;
 
 use serde::Deserialize;
@@ -29,6 +39,7 @@ use tokio::io::AsyncReadExt;
 
 use crate::config::Config;
 use crate::config_types::HistoryPersistence;
+use crate::config_types::SensitivePattern;
 
 use codex_protocol::mcp_protocol::ConversationId;
 #[cfg(unix)]
@@ -42,6 +53,13 @@ const HISTORY_FILENAME: &str = "history.jsonl";
 const MAX_RETRIES: usize = 10;
 const RETRY_SLEEP: Duration = Duration::from_millis(100);
 
+/// When the history file outgrows `max_bytes`, it is compacted to this
+/// fraction of the cap so that the next few appends do not each trigger
+/// another rewrite.
+const COMPACTION_TARGET_PERCENT: usize = 80;
+
+const REDACTED: &str = "[REDACTED]";
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct HistoryEntry {
     pub session_id: String,
@@ -73,7 +91,7 @@ pub(crate) async fn append_entry(
         }
     }
 
-    // TODO: check `text` for sensitive patterns
+    let text = redact_sensitive_text(text, &config.history.sensitive_patterns);
 
     // Resolve `~/.codex/history.jsonl` and ensure the parent directory exists.
     let path = history_filepath(config);
@@ -91,7 +109,7 @@ pub(crate) async fn append_entry(
     let entry = HistoryEntry {
         session_id: conversation_id.to_string(),
         ts,
-        text: text.to_string(),
+        text,
     };
     let mut line = serde_json::to_string(&entry)
         .map_err(|e| std::io::Error::other(format!("failed to serialise history entry: {e}")))?;
@@ -110,15 +128,28 @@ pub(crate) async fn append_entry(
     // Ensure permissions.
     ensure_owner_only_permissions(&history_file).await?;
 
+    let max_bytes = config.history.max_bytes;
+
     // Perform a blocking write under an advisory write lock using std::fs.
     tokio::task::spawn_blocking(move || -> Result<()> {
         // Retry a few times to avoid indefinite blocking when contended.
         for _ in 0..MAX_RETRIES {
             match history_file.try_lock() {
                 Ok(()) => {
+                    // Another process may have compacted the history while we
+                    // were waiting, leaving us with a lock on the replaced file.
+                    if !is_current_file(&history_file, &path)? {
+                        history_file = options.open(&path)?;
+                        continue;
+                    }
+
                     // While holding the exclusive lock, write the full line.
                     history_file.write_all(line.as_bytes())?;
                     history_file.flush()?;
+
+                    if let Some(max_bytes) = max_bytes {
+                        compact_if_over_limit(&mut history_file, &path, max_bytes)?;
+                    }
                     return Ok(());
                 }
                 Err(std::fs::TryLockError::WouldBlock) => {
@@ -138,6 +169,73 @@ pub(crate) async fn append_entry(
     Ok(())
 }
 
+/// Replace every match of the configured `sensitive_patterns` with
+/// `[REDACTED]`.
+fn redact_sensitive_text(text: &str, sensitive_patterns: &[SensitivePattern]) -> String {
+    let mut text = text.to_string();
+    for pattern in sensitive_patterns {
+        text = pattern.regex().replace_all(&text, REDACTED).into_owned();
+    }
+    text
+}
+
+/// Returns `true` if `file` is still the file found at `path`.
+#[cfg(unix)]
+pub(crate) fn is_current_file(file: &File, path: &Path) -> Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+
+    let open = file.metadata()?;
+    match std::fs::metadata(path) {
+        Ok(current) => Ok(open.dev() == current.dev() && open.ino() == current.ino()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn is_current_file(_file: &File, _path: &Path) -> Result<bool> {
+    Ok(true)
+}
+
+/// If the locked `history_file` is larger than `max_bytes`, atomically
+/// replace it with only its newest entries. Must be called while holding the
+/// exclusive lock on `history_file`.
+fn compact_if_over_limit(history_file: &mut File, path: &Path, max_bytes: usize) -> Result<()> {
+    if history_file.metadata()?.len() <= max_bytes as u64 {
+        return Ok(());
+    }
+
+    let mut contents = Vec::new();
+    history_file.seek(SeekFrom::Start(0))?;
+    history_file.read_to_end(&mut contents)?;
+
+    let target_bytes = max_bytes.saturating_mul(COMPACTION_TARGET_PERCENT) / 100;
+    let retained = newest_entries_within(&contents, target_bytes);
+
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    // `NamedTempFile` is created with owner-only permissions.
+    let mut compacted = tempfile::NamedTempFile::new_in(dir)?;
+    compacted.write_all(retained)?;
+    compacted.as_file().sync_all()?;
+    compacted.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// Returns the longest suffix of `contents` that starts at an entry boundary
+/// and is at most `budget` bytes long.
+fn newest_entries_within(contents: &[u8], budget: usize) -> &[u8] {
+    let min_start = contents.len().saturating_sub(budget);
+    let start = if min_start == 0 {
+        0
+    } else {
+        contents[min_start - 1..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(contents.len(), |idx| min_start + idx)
+    };
+    &contents[start..]
+}
+
 /// Asynchronously fetch the history file's *identifier* (inode on Unix) and
 /// the current number of entries by counting newline characters.
 pub(crate) async fn history_metadata(config: &Config) -> (u64, usize) {
@@ -284,3 +382,107 @@ async fn ensure_owner_only_permissions(_file: &File) -> Result<()> {
     // For now, on non-Unix, simply succeed.
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigOverrides;
+    use crate::config::ConfigToml;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn test_config(codex_home: &TempDir, max_bytes: Option<usize>) -> Config {
+        let mut config = Config::load_from_base_config_with_overrides(
+            ConfigToml::default(),
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect("load default test config");
+        config.history.max_bytes = max_bytes;
+        config.history.sensitive_patterns = vec![sensitive_pattern(r"sk-[A-Za-z0-9]+")];
+        config
+    }
+
+    fn read_texts(config: &Config) -> Vec<String> {
+        std::fs::read_to_string(history_filepath(config))
+            .expect("read history file")
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<HistoryEntry>(line)
+                    .expect("parse history entry")
+                    .text
+            })
+            .collect()
+    }
+
+    #[test]
+    fn keeps_newest_entries_that_fit() {
+        let contents = b"aaaa\nbbbb\ncccc\n";
+        assert_eq!(b"bbbb\ncccc\n", newest_entries_within(contents, 12));
+        assert_eq!(b"bbbb\ncccc\n", newest_entries_within(contents, 10));
+        assert_eq!(b"cccc\n", newest_entries_within(contents, 9));
+        assert_eq!(b"", newest_entries_within(contents, 4));
+        assert_eq!(contents, newest_entries_within(contents, 100));
+    }
+
+    fn sensitive_pattern(pattern: &str) -> SensitivePattern {
+        SensitivePattern::try_from(pattern.to_string()).expect("valid pattern")
+    }
+
+    #[test]
+    fn redacts_sensitive_patterns() {
+        let patterns = vec![
+            sensitive_pattern(r"sk-[A-Za-z0-9]+"),
+            sensitive_pattern("(?i)password=\\S+"),
+        ];
+        assert_eq!(
+            "export KEY=[REDACTED] [REDACTED] && echo [REDACTED]",
+            redact_sensitive_text(
+                "export KEY=sk-abc123 PASSWORD=hunter2 && echo sk-XYZ",
+                &patterns
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn append_entry_redacts_and_drops_oldest_entries_past_max_bytes() {
+        let codex_home = TempDir::new().expect("create temp dir");
+        let conversation_id = ConversationId::new();
+
+        let entry_len = {
+            let config = test_config(&codex_home, None);
+            append_entry("first", &conversation_id, &config)
+                .await
+                .expect("append");
+            std::fs::metadata(history_filepath(&config))
+                .expect("stat history")
+                .len() as usize
+        };
+
+        // Room for the first four entries below but not for a fifth.
+        let max_bytes = entry_len * 4 + entry_len / 2 + "token [REDACTED]".len();
+        let config = test_config(&codex_home, Some(max_bytes));
+        for text in ["second", "third", "token sk-secret1"] {
+            append_entry(text, &conversation_id, &config)
+                .await
+                .expect("append");
+        }
+        assert_eq!(
+            vec!["first", "second", "third", "token [REDACTED]"],
+            read_texts(&config)
+        );
+        let (log_id_before, _) = history_metadata(&config).await;
+
+        append_entry("fifth", &conversation_id, &config)
+            .await
+            .expect("append");
+        assert_eq!(
+            vec!["third", "token [REDACTED]", "fifth"],
+            read_texts(&config)
+        );
+
+        let (log_id_after, count) = history_metadata(&config).await;
+        assert_eq!(3, count);
+        assert_ne!(log_id_before, log_id_after);
+    }
+}
@@ -25,9 +25,10 @@ const MAX_REQUEST_MAX_RETRIES: u64 = 100;
 /// Wire protocol that the provider speaks. Most third-party services only
 /// implement the classic OpenAI Chat Completions JSON schema, whereas OpenAI
 /// itself (and a handful of others) additionally expose the more modern
-/// *Responses* API. The two protocols use different request/response shapes
-/// and *cannot* be auto-detected at runtime, therefore each provider entry
-/// must declare which one it expects.
+/// *Responses* API. Anthropic and Google models can also be used natively
+/// through their own streaming APIs. The protocols use different
+/// request/response shapes and *cannot* be auto-detected at runtime, therefore
+/// each provider entry must declare which one it expects.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum WireApi {
@@ -37,6 +38,12 @@ pub enum WireApi {
     /// Regular Chat Completions compatible with `/v1/chat/completions`.
     #[default]
     Chat,
+
+    /// The Anthropic Messages API exposed at `/v1/messages`.
+    Anthropic,
+
+    /// The Gemini API exposed at `/v1beta/models/{model}:streamGenerateContent`.
+    Gemini,
 }
 
 /// Serializable representation of a provider definition.
@@ -89,10 +96,11 @@ pub struct ModelProviderInfo {
 }
 
 impl ModelProviderInfo {
-    /// Construct a `POST` RequestBuilder for the given URL using the provided
-    /// reqwest Client applying:
+    /// Construct a `POST` RequestBuilder for the given model using the
+    /// provided reqwest Client applying:
     ///   • provider-specific headers (static + env based)
-    ///   • Bearer auth header when an API key is available.
+    ///   • Bearer auth header when an API key is available (`x-api-key` for
+    ///     Anthropic and `x-goog-api-key` for Gemini).
     ///   • Auth token for OAuth.
     ///
     /// If the provider declares an `env_key` but the variable is missing/empty, returns an [`Err`] identical to the
@@ -101,6 +109,7 @@ impl ModelProviderInfo {
         &'a self,
         client: &'a reqwest::Client,
         auth: &Option<CodexAuth>,
+        model: &str,
     ) -> crate::error::Result<reqwest::RequestBuilder> {
         let effective_auth = match self.api_key() {
             Ok(Some(key)) => Some(CodexAuth::from_api_key(&key)),
@@ -114,12 +123,17 @@ impl ModelProviderInfo {
             }
         };
 
-        let url = self.get_full_url(&effective_auth);
+        let url = self.get_full_url(&effective_auth, model);
 
         let mut builder = client.post(url);
 
         if let Some(auth) = effective_auth.as_ref() {
-            builder = builder.bearer_auth(auth.get_token().await?);
+            let token = auth.get_token().await?;
+            builder = match self.wire_api {
+                WireApi::Responses | WireApi::Chat => builder.bearer_auth(token),
+                WireApi::Anthropic => builder.header("x-api-key", token),
+                WireApi::Gemini => builder.header("x-goog-api-key", token),
+            };
         }
 
         Ok(self.apply_http_headers(builder))
@@ -138,17 +152,22 @@ impl ModelProviderInfo {
             })
     }
 
-    pub(crate) fn get_full_url(&self, auth: &Option<CodexAuth>) -> String {
-        let default_base_url = if matches!(
-            auth,
-            Some(CodexAuth {
-                mode: AuthMode::ChatGPT,
-                ..
-            })
-        ) {
-            "https://chatgpt.com/backend-api/codex"
-        } else {
-            "https://api.openai.com/v1"
+    pub(crate) fn get_full_url(&self, auth: &Option<CodexAuth>, model: &str) -> String {
+        let default_base_url = match self.wire_api {
+            WireApi::Anthropic => "https://api.anthropic.com/v1",
+            WireApi::Gemini => "https://generativelanguage.googleapis.com/v1beta",
+            WireApi::Responses | WireApi::Chat
+                if matches!(
+                    auth,
+                    Some(CodexAuth {
+                        mode: AuthMode::ChatGPT,
+                        ..
+                    })
+                ) =>
+            {
+                "https://chatgpt.com/backend-api/codex"
+            }
+            WireApi::Responses | WireApi::Chat => "https://api.openai.com/v1",
         };
         let query_string = self.get_query_string();
         let base_url = self
@@ -159,6 +178,15 @@ impl ModelProviderInfo {
         match self.wire_api {
             WireApi::Responses => format!("{base_url}/responses{query_string}"),
             WireApi::Chat => format!("{base_url}/chat/completions{query_string}"),
+            WireApi::Anthropic => format!("{base_url}/messages{query_string}"),
+            WireApi::Gemini => {
+                // `alt=sse` switches the response to server-sent events.
+                let query_string = match query_string.strip_prefix('?') {
+                    Some(params) => format!("?alt=sse&{params}"),
+                    None => "?alt=sse".to_string(),
+                };
+                format!("{base_url}/models/{model}:streamGenerateContent{query_string}")
+            }
         }
     }
 
@@ -177,6 +205,19 @@ impl ModelProviderInfo {
             .unwrap_or(false)
     }
 
+    /// Returns true if the provider config sets the header `name` itself.
+    pub(crate) fn has_http_header(&self, name: &str) -> bool {
+        self.http_headers
+            .iter()
+            .flat_map(|headers| headers.keys())
+            .chain(
+                self.env_http_headers
+                    .iter()
+                    .flat_map(|headers| headers.keys()),
+            )
+            .any(|header| header.eq_ignore_ascii_case(name))
+    }
+
     /// Apply provider-specific HTTP headers (both static and environment-based)
     /// onto an existing `reqwest::RequestBuilder` and return the updated
     /// builder.
@@ -511,4 +552,45 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             );
         }
     }
+
+    #[test]
+    fn test_native_wire_api_urls() {
+        let provider =
+            |wire_api, query_params: Option<HashMap<String, String>>| ModelProviderInfo {
+                name: "native".into(),
+                base_url: None,
+                env_key: None,
+                env_key_instructions: None,
+                wire_api,
+                query_params,
+                http_headers: None,
+                env_http_headers: None,
+                request_max_retries: None,
+                stream_max_retries: None,
+                stream_idle_timeout_ms: None,
+                requires_openai_auth: false,
+            };
+
+        assert_eq!(
+            provider(WireApi::Anthropic, None).get_full_url(&None, "claude-sonnet-4-0"),
+            "https://api.anthropic.com/v1/messages"
+        );
+        assert_eq!(
+            provider(WireApi::Gemini, None).get_full_url(&None, "gemini-2.5-pro"),
+            "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.5-pro:streamGenerateContent?alt=sse"
+        );
+        assert_eq!(
+            provider(
+                WireApi::Gemini,
+                Some(HashMap::from([("key".to_string(), "abc".to_string())]))
+            )
+            .get_full_url(&None, "gemini-2.5-pro"),
+            "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.5-pro:streamGenerateContent?alt=sse&key=abc"
+        );
+
+        let wire_api: WireApi = serde_json::from_str("\"anthropic\"").unwrap();
+        assert_eq!(wire_api, WireApi::Anthropic);
+        let wire_api: WireApi = serde_json::from_str("\"gemini\"").unwrap();
+        assert_eq!(wire_api, WireApi::Gemini);
+    }
 }
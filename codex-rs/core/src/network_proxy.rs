@@ -0,0 +1,317 @@
+//! Host-side HTTP proxy that lets sandboxed commands reach an allowlist of
+//! hosts.
+//!
+//! The proxy listens on a Unix domain socket so that it can be reached from
+//! inside a network namespace that has no other route out. It understands
+//! `CONNECT host:port` (used for HTTPS) and absolute-form plain HTTP requests.
+//! Requests for hosts that do not match the allowlist are answered with
+//! `403 Forbidden` and remembered so they can be reported once the command
+//! finishes.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use tempfile::TempDir;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufReader;
+use tokio::net::TcpStream;
+use tokio::net::UnixListener;
+use tokio::net::UnixStream;
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+/// Upper bound on the size of a request head, to avoid buffering unbounded
+/// input from a misbehaving client.
+const MAX_REQUEST_HEAD_BYTES: usize = 64 * 1024;
+
+const SOCKET_FILE_NAME: &str = "proxy.sock";
+
+pub(crate) struct NetworkProxy {
+    // Keeps the socket's directory alive for as long as the proxy runs.
+    _socket_dir: TempDir,
+    socket_path: PathBuf,
+    denied_hosts: Arc<Mutex<Vec<String>>>,
+    accept_task: JoinHandle<()>,
+}
+
+impl NetworkProxy {
+    /// Starts a proxy that only forwards connections to `allowed_hosts`.
+    pub(crate) fn start(allowed_hosts: Vec<String>) -> io::Result<Self> {
+        let socket_dir = tempfile::Builder::new().prefix("codex-proxy").tempdir()?;
+        let socket_path = socket_dir.path().join(SOCKET_FILE_NAME);
+        let listener = UnixListener::bind(&socket_path)?;
+        let allowed_hosts = Arc::new(allowed_hosts);
+        let denied_hosts = Arc::new(Mutex::new(Vec::new()));
+
+        let accept_task = tokio::spawn({
+            let denied_hosts = Arc::clone(&denied_hosts);
+            async move {
+                loop {
+                    let Ok((stream, _)) = listener.accept().await else {
+                        continue;
+                    };
+                    let allowed_hosts = Arc::clone(&allowed_hosts);
+                    let denied_hosts = Arc::clone(&denied_hosts);
+                    tokio::spawn(async move {
+                        if let Err(e) =
+                            handle_connection(stream, &allowed_hosts, &denied_hosts).await
+                        {
+                            debug!("network proxy connection failed: {e}");
+                        }
+                    });
+                }
+            }
+        });
+
+        Ok(Self {
+            _socket_dir: socket_dir,
+            socket_path,
+            denied_hosts,
+            accept_task,
+        })
+    }
+
+    pub(crate) fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+
+    /// Returns the distinct hosts that were refused so far, in the order in
+    /// which they were first requested.
+    pub(crate) fn denied_hosts(&self) -> Vec<String> {
+        self.denied_hosts
+            .lock()
+            .map(|hosts| hosts.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for NetworkProxy {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+    }
+}
+
+/// Returns `true` if `host` matches one of the `allowed_hosts` patterns.
+/// Patterns are exact host names or `*.domain` wildcards, which match any
+/// subdomain of `domain` but not `domain` itself.
+pub(crate) fn is_host_allowed(host: &str, allowed_hosts: &[String]) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    allowed_hosts.iter().any(|pattern| {
+        let pattern = pattern.trim_end_matches('.').to_ascii_lowercase();
+        match pattern.strip_prefix("*.") {
+            Some(domain) => host
+                .strip_suffix(domain)
+                .is_some_and(|prefix| prefix.len() > 1 && prefix.ends_with('.')),
+            None => host == pattern,
+        }
+    })
+}
+
+struct ProxyRequest {
+    host: String,
+    port: u16,
+    is_connect: bool,
+}
+
+async fn handle_connection(
+    stream: UnixStream,
+    allowed_hosts: &[String],
+    denied_hosts: &Mutex<Vec<String>>,
+) -> io::Result<()> {
+    let mut client = BufReader::new(stream);
+    let mut head = Vec::new();
+    loop {
+        let read = client.read_until(b'\n', &mut head).await?;
+        if read == 0 {
+            return Ok(());
+        }
+        if head.ends_with(b"\r\n\r\n") || head.ends_with(b"\n\n") {
+            break;
+        }
+        if head.len() > MAX_REQUEST_HEAD_BYTES {
+            return write_status(client.get_mut(), "431 Request Header Fields Too Large").await;
+        }
+    }
+
+    let Some(request) = parse_request_line(&head) else {
+        return write_status(client.get_mut(), "400 Bad Request").await;
+    };
+
+    if !is_host_allowed(&request.host, allowed_hosts) {
+        if let Ok(mut hosts) = denied_hosts.lock()
+            && !hosts.contains(&request.host)
+        {
+            hosts.push(request.host.clone());
+        }
+        return write_status(client.get_mut(), "403 Forbidden").await;
+    }
+
+    let mut upstream = match TcpStream::connect((request.host.as_str(), request.port)).await {
+        Ok(upstream) => upstream,
+        Err(e) => {
+            debug!("network proxy could not reach {}: {e}", request.host);
+            return write_status(client.get_mut(), "502 Bad Gateway").await;
+        }
+    };
+
+    if request.is_connect {
+        client
+            .get_mut()
+            .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+            .await?;
+    } else {
+        upstream.write_all(&head).await?;
+    }
+    // Forward anything the client sent after the request head.
+    upstream.write_all(client.buffer()).await?;
+    let mut client = client.into_inner();
+    tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+    Ok(())
+}
+
+async fn write_status(stream: &mut UnixStream, status: &str) -> io::Result<()> {
+    let response = format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+fn parse_request_line(head: &[u8]) -> Option<ProxyRequest> {
+    let head = std::str::from_utf8(head).ok()?;
+    let mut parts = head.lines().next()?.split_whitespace();
+    let method = parts.next()?;
+    let target = parts.next()?;
+
+    if method.eq_ignore_ascii_case("CONNECT") {
+        let (host, port) = split_host_port(target)?;
+        return Some(ProxyRequest {
+            host,
+            port: port?,
+            is_connect: true,
+        });
+    }
+
+    let rest = target.strip_prefix("http://")?;
+    let authority = rest.split('/').next()?;
+    // Drop any userinfo from the authority.
+    let authority = authority.rsplit('@').next()?;
+    let (host, port) = split_host_port(authority)?;
+    Some(ProxyRequest {
+        host,
+        port: port.unwrap_or(80),
+        is_connect: false,
+    })
+}
+
+/// Splits `host[:port]`, handling bracketed IPv6 literals. Returns `None` for
+/// an empty host or an unparsable port.
+fn split_host_port(authority: &str) -> Option<(String, Option<u16>)> {
+    let (host, port) = if let Some(rest) = authority.strip_prefix('[') {
+        let (host, rest) = rest.split_once(']')?;
+        (host, rest.strip_prefix(':'))
+    } else {
+        match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        }
+    };
+    if host.is_empty() {
+        return None;
+    }
+    let port = match port {
+        Some(port) => Some(port.parse().ok()?),
+        None => None,
+    };
+    Some((host.to_string(), port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    fn hosts(hosts: &[&str]) -> Vec<String> {
+        hosts.iter().map(|host| host.to_string()).collect()
+    }
+
+    #[test]
+    fn matches_exact_and_wildcard_hosts() {
+        let allowed = hosts(&["crates.io", "*.registry.internal"]);
+
+        assert!(is_host_allowed("crates.io", &allowed));
+        assert!(is_host_allowed("Crates.IO.", &allowed));
+        assert!(is_host_allowed("npm.registry.internal", &allowed));
+        assert!(is_host_allowed("a.b.registry.internal", &allowed));
+
+        assert!(!is_host_allowed("static.crates.io", &allowed));
+        assert!(!is_host_allowed("registry.internal", &allowed));
+        assert!(!is_host_allowed("evilregistry.internal", &allowed));
+        assert!(!is_host_allowed("example.com", &allowed));
+    }
+
+    #[test]
+    fn parses_connect_and_absolute_form_requests() {
+        let request = parse_request_line(b"CONNECT crates.io:443 HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(
+            ("crates.io", 443, true),
+            (request.host.as_str(), request.port, request.is_connect)
+        );
+
+        let request =
+            parse_request_line(b"GET http://user@[::1]:8080/index HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(
+            ("::1", 8080, false),
+            (request.host.as_str(), request.port, request.is_connect)
+        );
+
+        let request = parse_request_line(b"GET http://example.com HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(("example.com", 80), (request.host.as_str(), request.port));
+
+        assert!(parse_request_line(b"CONNECT crates.io HTTP/1.1\r\n\r\n").is_none());
+        assert!(parse_request_line(b"GET /relative HTTP/1.1\r\n\r\n").is_none());
+    }
+
+    #[tokio::test]
+    async fn tunnels_allowed_hosts_and_records_denied_ones() {
+        let upstream = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let upstream_port = upstream.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut stream, _) = upstream.accept().await.unwrap();
+            let mut buf = [0u8; 4];
+            stream.read_exact(&mut buf).await.unwrap();
+            stream.write_all(&buf).await.unwrap();
+        });
+
+        let proxy = NetworkProxy::start(hosts(&["127.0.0.1"])).unwrap();
+
+        let mut allowed = UnixStream::connect(proxy.socket_path()).await.unwrap();
+        allowed
+            .write_all(format!("CONNECT 127.0.0.1:{upstream_port} HTTP/1.1\r\n\r\nping").as_bytes())
+            .await
+            .unwrap();
+        let mut response = vec![0u8; "HTTP/1.1 200 Connection Established\r\n\r\nping".len()];
+        allowed.read_exact(&mut response).await.unwrap();
+        assert_eq!(
+            "HTTP/1.1 200 Connection Established\r\n\r\nping",
+            String::from_utf8_lossy(&response)
+        );
+
+        for _ in 0..2 {
+            let mut denied = UnixStream::connect(proxy.socket_path()).await.unwrap();
+            denied
+                .write_all(b"CONNECT example.com:443 HTTP/1.1\r\n\r\n")
+                .await
+                .unwrap();
+            let mut response = String::new();
+            denied.read_to_string(&mut response).await.unwrap();
+            assert!(response.starts_with("HTTP/1.1 403 Forbidden"), "{response}");
+        }
+
+        assert_eq!(vec!["example.com".to_string()], proxy.denied_hosts());
+    }
+}
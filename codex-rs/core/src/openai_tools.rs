@@ -5,6 +5,7 @@ use serde_json::json;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 
+use crate::delegate::MAX_DELEGATE_TASKS;
 use crate::model_family::ModelFamily;
 use crate::plan_tool::PLAN_TOOL;
 use crate::protocol::AskForApproval;
@@ -70,6 +71,7 @@ pub(crate) struct ToolsConfig {
     pub apply_patch_tool_type: Option<ApplyPatchToolType>,
     pub web_search_request: bool,
     pub include_view_image_tool: bool,
+    pub include_delegate_tool: bool,
     pub experimental_unified_exec_tool: bool,
 }
 
@@ -82,6 +84,7 @@ pub(crate) struct ToolsConfigParams<'a> {
     pub(crate) include_web_search_request: bool,
     pub(crate) use_streamable_shell_tool: bool,
     pub(crate) include_view_image_tool: bool,
+    pub(crate) include_delegate_tool: bool,
     pub(crate) experimental_unified_exec_tool: bool,
 }
 
@@ -96,6 +99,7 @@ impl ToolsConfig {
             include_web_search_request,
             use_streamable_shell_tool,
             include_view_image_tool,
+            include_delegate_tool,
             experimental_unified_exec_tool,
         } = params;
         let mut shell_type = if *use_streamable_shell_tool {
@@ -129,6 +133,7 @@ impl ToolsConfig {
             apply_patch_tool_type,
             web_search_request: *include_web_search_request,
             include_view_image_tool: *include_view_image_tool,
+            include_delegate_tool: *include_delegate_tool,
             experimental_unified_exec_tool: *experimental_unified_exec_tool,
         }
     }
@@ -323,6 +328,70 @@ fn create_view_image_tool() -> OpenAiTool {
         },
     })
 }
+fn create_delegate_tool() -> OpenAiTool {
+    let mut task_properties = BTreeMap::new();
+    task_properties.insert(
+        "prompt".to_string(),
+        JsonSchema::String {
+            description: Some("The task for the worker, as a user message".to_string()),
+        },
+    );
+    task_properties.insert(
+        "instructions".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Additional instructions for the worker, appended to the user instructions"
+                    .to_string(),
+            ),
+        },
+    );
+    task_properties.insert(
+        "tools".to_string(),
+        JsonSchema::Array {
+            items: Box::new(JsonSchema::String { description: None }),
+            description: Some(
+                "Names of the optional tools and MCP servers the worker may use (\"update_plan\", \"apply_patch\", \"view_image\", \"web_search\" or an MCP server name). The shell is always available. Defaults to all of yours."
+                    .to_string(),
+            ),
+        },
+    );
+    task_properties.insert(
+        "sandbox".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Sandbox for the worker's commands: \"read-only\", \"workspace-write\" or \"danger-full-access\". Cannot be broader than yours. Defaults to yours. Running without a sandbox is refused unless commands you run are never sent for approval."
+                    .to_string(),
+            ),
+        },
+    );
+
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "tasks".to_string(),
+        JsonSchema::Array {
+            items: Box::new(JsonSchema::Object {
+                properties: task_properties,
+                required: Some(vec!["prompt".to_string()]),
+                additional_properties: Some(false),
+            }),
+            description: Some(format!(
+                "Tasks to run in parallel, one worker each (at most {MAX_DELEGATE_TASKS})"
+            )),
+        },
+    );
+
+    OpenAiTool::Function(ResponsesApiTool {
+        name: "delegate".to_string(),
+        description: "Delegates self-contained tasks to sub-agents that run in parallel, each in its own conversation in the current working directory. Workers cannot ask for approval. Returns the final message of each worker.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["tasks".to_string()]),
+            additional_properties: Some(false),
+        },
+    })
+}
+
 /// TODO(dylan): deprecate once we get rid of json tool
 #[derive(Serialize, Deserialize)]
 pub(crate) struct ApplyPatchToolArgs {
@@ -375,6 +444,66 @@ pub(crate) fn create_tools_json_for_chat_completions_api(
     Ok(tools_json)
 }
 
+/// Returns JSON values that are compatible with tool use in the Anthropic
+/// Messages API:
+/// https://docs.anthropic.com/en/docs/agents-and-tools/tool-use/overview
+pub(crate) fn create_tools_json_for_anthropic_messages_api(
+    tools: &Vec<OpenAiTool>,
+) -> crate::error::Result<Vec<serde_json::Value>> {
+    let tools_json = create_tools_json_for_responses_api(tools)?
+        .into_iter()
+        .filter(|tool| tool.get("type") == Some(&JsonValue::String("function".to_string())))
+        .map(|tool| {
+            json!({
+                "name": tool["name"],
+                "description": tool["description"],
+                "input_schema": tool["parameters"],
+            })
+        })
+        .collect();
+    Ok(tools_json)
+}
+
+/// Returns the function declarations for the `tools` field of a Gemini API
+/// request:
+/// https://ai.google.dev/gemini-api/docs/function-calling
+pub(crate) fn create_function_declarations_for_gemini_api(
+    tools: &Vec<OpenAiTool>,
+) -> crate::error::Result<Vec<serde_json::Value>> {
+    let declarations = create_tools_json_for_responses_api(tools)?
+        .into_iter()
+        .filter(|tool| tool.get("type") == Some(&JsonValue::String("function".to_string())))
+        .map(|tool| {
+            let mut parameters = tool["parameters"].clone();
+            // Gemini rejects schemas that use `additionalProperties`.
+            strip_additional_properties(&mut parameters);
+            json!({
+                "name": tool["name"],
+                "description": tool["description"],
+                "parameters": parameters,
+            })
+        })
+        .collect();
+    Ok(declarations)
+}
+
+fn strip_additional_properties(value: &mut JsonValue) {
+    match value {
+        JsonValue::Object(map) => {
+            map.remove("additionalProperties");
+            for v in map.values_mut() {
+                strip_additional_properties(v);
+            }
+        }
+        JsonValue::Array(items) => {
+            for v in items {
+                strip_additional_properties(v);
+            }
+        }
+        _ => {}
+    }
+}
+
 pub(crate) fn mcp_tool_to_openai_tool(
     fully_qualified_name: String,
     tool: mcp_types::Tool,
@@ -580,6 +709,10 @@ pub(crate) fn get_openai_tools(
     if config.include_view_image_tool {
         tools.push(create_view_image_tool());
     }
+
+    if config.include_delegate_tool {
+        tools.push(create_delegate_tool());
+    }
     if let Some(mcp_tools) = mcp_tools {
         // Ensure deterministic ordering to maximize prompt cache hits.
         let mut entries: Vec<(String, mcp_types::Tool)> = mcp_tools.into_iter().collect();
@@ -643,6 +776,7 @@ mod tests {
             include_web_search_request: true,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_delegate_tool: false,
             experimental_unified_exec_tool: true,
         });
         let tools = get_openai_tools(&config, Some(HashMap::new()));
@@ -665,6 +799,7 @@ mod tests {
             include_web_search_request: true,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_delegate_tool: false,
             experimental_unified_exec_tool: true,
         });
         let tools = get_openai_tools(&config, Some(HashMap::new()));
@@ -675,6 +810,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_openai_tools_includes_delegate_when_enabled() {
+        let model_family = find_family_for_model("o3").expect("o3 should be a valid model family");
+        let config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::ReadOnly,
+            include_plan_tool: false,
+            include_apply_patch_tool: false,
+            include_web_search_request: false,
+            use_streamable_shell_tool: false,
+            include_view_image_tool: true,
+            include_delegate_tool: true,
+            experimental_unified_exec_tool: false,
+        });
+        let tools = get_openai_tools(&config, Some(HashMap::new()));
+
+        assert_eq_tool_names(&tools, &["shell", "view_image", "delegate"]);
+    }
+
     #[test]
     fn test_get_openai_tools_mcp_tools() {
         let model_family = find_family_for_model("o3").expect("o3 should be a valid model family");
@@ -687,6 +842,7 @@ mod tests {
             include_web_search_request: true,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_delegate_tool: false,
             experimental_unified_exec_tool: true,
         });
         let tools = get_openai_tools(
@@ -793,6 +949,7 @@ mod tests {
             include_web_search_request: false,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_delegate_tool: false,
             experimental_unified_exec_tool: true,
         });
 
@@ -871,6 +1028,7 @@ mod tests {
             include_web_search_request: true,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_delegate_tool: false,
             experimental_unified_exec_tool: true,
         });
 
@@ -934,6 +1092,7 @@ mod tests {
             include_web_search_request: true,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_delegate_tool: false,
             experimental_unified_exec_tool: true,
         });
 
@@ -992,6 +1151,7 @@ mod tests {
             include_web_search_request: true,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_delegate_tool: false,
             experimental_unified_exec_tool: true,
         });
 
@@ -1053,6 +1213,7 @@ mod tests {
             include_web_search_request: true,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_delegate_tool: false,
             experimental_unified_exec_tool: true,
         });
 
@@ -1106,6 +1267,9 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            readable_roots: None,
+            deny_read: Vec::new(),
+            allowed_hosts: Vec::new(),
         };
         let tool = super::create_shell_tool_for_sandbox(&sandbox_policy);
         let OpenAiTool::Function(ResponsesApiTool {
@@ -1148,4 +1312,58 @@ mod tests {
 
         assert_eq!(description, "Runs a shell command and returns its output.");
     }
+
+    #[test]
+    fn test_tools_json_for_native_wire_apis() {
+        let mut properties = BTreeMap::new();
+        properties.insert(
+            "path".to_string(),
+            JsonSchema::String {
+                description: Some("File to read".to_string()),
+            },
+        );
+        let tools = vec![
+            OpenAiTool::Function(ResponsesApiTool {
+                name: "read_file".to_string(),
+                description: "Reads a file.".to_string(),
+                strict: false,
+                parameters: JsonSchema::Object {
+                    properties,
+                    required: Some(vec!["path".to_string()]),
+                    additional_properties: Some(false),
+                },
+            }),
+            OpenAiTool::WebSearch {},
+        ];
+
+        assert_eq!(
+            create_tools_json_for_anthropic_messages_api(&tools).unwrap(),
+            vec![json!({
+                "name": "read_file",
+                "description": "Reads a file.",
+                "input_schema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {"type": "string", "description": "File to read"}
+                    },
+                    "required": ["path"],
+                    "additionalProperties": false,
+                },
+            })]
+        );
+        assert_eq!(
+            create_function_declarations_for_gemini_api(&tools).unwrap(),
+            vec![json!({
+                "name": "read_file",
+                "description": "Reads a file.",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "path": {"type": "string", "description": "File to read"}
+                    },
+                    "required": ["path"],
+                },
+            })]
+        );
+    }
 }
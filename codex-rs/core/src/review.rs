@@ -0,0 +1,151 @@
+//! Helpers shared by the front-ends that start code reviews (`/review`,
+//! `codex review`) and present their findings.
+
+use std::path::Path;
+
+use crate::protocol::ReviewCodeLocation;
+use crate::protocol::ReviewFinding;
+use crate::protocol::ReviewLineRange;
+use crate::protocol::ReviewRequest;
+
+/// The set of changes a review session should look at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReviewTarget {
+    /// Staged, unstaged and untracked changes in the working tree.
+    UncommittedChanges,
+    /// Changes on the current branch since it diverged from this branch.
+    BaseBranch(String),
+    /// The changes introduced by a single commit.
+    Commit(String),
+}
+
+impl ReviewTarget {
+    /// Builds the request to send with `Op::Review`.
+    pub fn review_request(&self) -> ReviewRequest {
+        match self {
+            ReviewTarget::UncommittedChanges => ReviewRequest {
+                prompt: "Review the current code changes (staged, unstaged, and untracked files) \
+                         and provide prioritized findings. Inspect them with `git status`, \
+                         `git diff`, and `git diff --staged`."
+                    .to_string(),
+                user_facing_hint: "current changes".to_string(),
+            },
+            ReviewTarget::BaseBranch(base) => ReviewRequest {
+                prompt: format!(
+                    "Review the code changes against the base branch '{base}'. Start by finding \
+                     the merge base between the current branch and {base} (for example with \
+                     `git merge-base HEAD {base}`), then run `git diff` against that commit to \
+                     see the changes that would be merged. Provide prioritized, actionable \
+                     findings."
+                ),
+                user_facing_hint: format!("changes against '{base}'"),
+            },
+            ReviewTarget::Commit(sha) => {
+                let short_sha: String = sha.chars().take(7).collect();
+                ReviewRequest {
+                    prompt: format!(
+                        "Review the code changes introduced by commit {sha}. Inspect them with \
+                         `git show {sha}` and provide prioritized, actionable findings."
+                    ),
+                    user_facing_hint: format!("commit {short_sha}"),
+                }
+            }
+        }
+    }
+}
+
+/// Orders findings from most to least urgent (P0 first). Findings with the
+/// same priority keep the order in which the model reported them.
+pub fn sort_findings_by_priority(findings: &mut [ReviewFinding]) {
+    findings.sort_by_key(|finding| finding.priority);
+}
+
+/// Returns the finding's file path relative to `cwd`, or the absolute path
+/// when the file lies outside of it.
+pub fn relative_file_path<'a>(location: &'a ReviewCodeLocation, cwd: &Path) -> &'a Path {
+    location
+        .absolute_file_path
+        .strip_prefix(cwd)
+        .unwrap_or(&location.absolute_file_path)
+}
+
+/// Formats a finding's location as `path:start-end`, relative to `cwd` when
+/// the file is inside it, so terminals and editors can open it as a link.
+pub fn format_code_location(location: &ReviewCodeLocation, cwd: &Path) -> String {
+    let path = relative_file_path(location, cwd);
+    let ReviewLineRange { start, end } = location.line_range;
+    if end > start {
+        format!("{}:{start}-{end}", path.display())
+    } else {
+        format!("{}:{start}", path.display())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn finding(title: &str, priority: i32, start: u32, end: u32) -> ReviewFinding {
+        ReviewFinding {
+            title: title.to_string(),
+            body: String::new(),
+            confidence_score: 0.5,
+            priority,
+            code_location: ReviewCodeLocation {
+                absolute_file_path: PathBuf::from("/repo/src/lib.rs"),
+                line_range: ReviewLineRange { start, end },
+            },
+        }
+    }
+
+    #[test]
+    fn review_requests_describe_the_target() {
+        assert_eq!(
+            "changes against 'main'",
+            ReviewTarget::BaseBranch("main".to_string())
+                .review_request()
+                .user_facing_hint
+        );
+        let request = ReviewTarget::Commit("0123456789abcdef".to_string()).review_request();
+        assert_eq!("commit 0123456", request.user_facing_hint);
+        assert!(request.prompt.contains("git show 0123456789abcdef"));
+        assert_eq!(
+            "current changes",
+            ReviewTarget::UncommittedChanges
+                .review_request()
+                .user_facing_hint
+        );
+    }
+
+    #[test]
+    fn sorts_findings_by_priority_keeping_model_order() {
+        let mut findings = vec![
+            finding("[P2] a", 2, 1, 1),
+            finding("[P0] b", 0, 1, 1),
+            finding("[P2] c", 2, 1, 1),
+            finding("[P1] d", 1, 1, 1),
+        ];
+        sort_findings_by_priority(&mut findings);
+        let titles: Vec<&str> = findings.iter().map(|f| f.title.as_str()).collect();
+        assert_eq!(vec!["[P0] b", "[P1] d", "[P2] a", "[P2] c"], titles);
+    }
+
+    #[test]
+    fn formats_locations_relative_to_cwd() {
+        let cwd = Path::new("/repo");
+        assert_eq!(
+            "src/lib.rs:10-20",
+            format_code_location(&finding("", 0, 10, 20).code_location, cwd)
+        );
+        assert_eq!(
+            "src/lib.rs:7",
+            format_code_location(&finding("", 0, 7, 7).code_location, cwd)
+        );
+        assert_eq!(
+            "/repo/src/lib.rs:7",
+            format_code_location(&finding("", 0, 7, 7).code_location, Path::new("/other"))
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! Gzip compression of rollout files.
+//!
+//! Old rollouts may be compressed by the retention policy, which replaces
+//! `rollout-<ts>-<uuid>.jsonl` with `rollout-<ts>-<uuid>.jsonl.gz`. Readers go
+//! through [`read_rollout_text`] so they handle both. A compressed rollout is
+//! decompressed again when its session is resumed, since the recorder appends
+//! to the file.
+
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+
+/// Extension added to the name of a compressed rollout file.
+pub const COMPRESSED_ROLLOUT_EXTENSION: &str = "gz";
+
+/// Whether the rollout file at `path` is gzip-compressed.
+pub fn is_compressed_rollout(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == COMPRESSED_ROLLOUT_EXTENSION)
+}
+
+/// Path of the compressed form of the uncompressed rollout file at `path`.
+pub(crate) fn compressed_rollout_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{COMPRESSED_ROLLOUT_EXTENSION}"));
+    path.with_file_name(name)
+}
+
+/// Reads the contents of a rollout file, decompressing it if needed.
+pub async fn read_rollout_text(path: &Path) -> io::Result<String> {
+    if !is_compressed_rollout(path) {
+        return tokio::fs::read_to_string(path).await;
+    }
+    let bytes = tokio::fs::read(path).await?;
+    let mut text = String::new();
+    GzDecoder::new(bytes.as_slice()).read_to_string(&mut text)?;
+    Ok(text)
+}
+
+/// Replaces the rollout file at `path` with a compressed copy and returns the
+/// path of the copy.
+pub(crate) async fn compress_rollout(path: &Path) -> io::Result<PathBuf> {
+    let compressed_path = compressed_rollout_path(path);
+
+    let bytes = tokio::fs::read(path).await?;
+    let compressed = tokio::task::spawn_blocking(move || {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&bytes)?;
+        encoder.finish()
+    })
+    .await
+    .map_err(io::Error::other)??;
+
+    replace_file(path, &compressed_path, &compressed).await?;
+    Ok(compressed_path)
+}
+
+/// Replaces the compressed rollout file at `path` with a decompressed copy
+/// and returns the path of the copy.
+pub(crate) async fn decompress_rollout(path: &Path) -> io::Result<PathBuf> {
+    let decompressed_path = path.with_extension("");
+    let text = read_rollout_text(path).await?;
+    replace_file(path, &decompressed_path, text.as_bytes()).await?;
+    Ok(decompressed_path)
+}
+
+/// Writes `contents` to `new_path` and removes `old_path`, keeping its
+/// modification time, which the retention policy goes by. The contents are
+/// written to a temporary file first so a crash never leaves a truncated
+/// rollout behind.
+async fn replace_file(old_path: &Path, new_path: &Path, contents: &[u8]) -> io::Result<()> {
+    let modified = tokio::fs::metadata(old_path).await?.modified()?;
+    let mut tmp_name = new_path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = new_path.with_file_name(tmp_name);
+    tokio::fs::write(&tmp_path, contents).await?;
+    tokio::fs::File::options()
+        .write(true)
+        .open(&tmp_path)
+        .await?
+        .into_std()
+        .await
+        .set_modified(modified)?;
+    tokio::fs::rename(&tmp_path, new_path).await?;
+    tokio::fs::remove_file(old_path).await
+}
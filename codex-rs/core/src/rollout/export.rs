@@ -0,0 +1,846 @@
+//! Export of recorded sessions for people to read.
+//!
+//! A rollout file is first reduced to a [`SessionExport`]: the metadata of the
+//! session, its transcript as a list of entries (messages, reasoning
+//! summaries, commands, patches and other tool calls) and its token usage.
+//! That is then rendered as Markdown, as a self-contained HTML page, or
+//! serialized as JSON.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::mcp_protocol::ConversationId;
+use codex_protocol::mcp_protocol::ExportFormat;
+use codex_protocol::models::FunctionCallOutputPayload;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::models::ShellToolCallParams;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use serde::Deserialize;
+use serde::Serialize;
+use tracing::warn;
+
+use super::compress::read_rollout_text;
+use super::patch_diff::AppliedPatchDiff;
+use crate::protocol::EventMsg;
+use crate::protocol::InputMessageKind;
+use crate::protocol::TokenUsage;
+use crate::protocol::USER_MESSAGE_BEGIN;
+
+/// Reads the rollout file at `path` and renders it in `format`.
+pub async fn export_rollout(path: &Path, format: ExportFormat) -> io::Result<String> {
+    let text = read_rollout_text(path).await?;
+    let items: Vec<RolloutItem> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str::<RolloutLine>(line) {
+            Ok(line) => Some(line.item),
+            Err(e) => {
+                warn!("failed to parse rollout line: {e}");
+                None
+            }
+        })
+        .collect();
+    render_export(&items, format)
+}
+
+fn render_export(items: &[RolloutItem], format: ExportFormat) -> io::Result<String> {
+    let export = SessionExport::from_items(items);
+    Ok(match format {
+        ExportFormat::Markdown => export.to_markdown(),
+        ExportFormat::Html => export.to_html(),
+        ExportFormat::Json => serde_json::to_string_pretty(&export)?,
+    })
+}
+
+#[derive(Debug, Default, Serialize)]
+struct SessionExport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<ConversationId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timestamp: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cwd: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    git_branch: Option<String>,
+    models: Vec<String>,
+    entries: Vec<ExportEntry>,
+    /// Tokens used over the whole session.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token_usage: Option<TokenUsage>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ExportEntry {
+    UserMessage {
+        text: String,
+    },
+    AgentMessage {
+        text: String,
+    },
+    Reasoning {
+        text: String,
+    },
+    Command {
+        command: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        exit_code: Option<i32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+    },
+    Patch {
+        patch: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+    },
+    ToolCall {
+        name: String,
+        arguments: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+    },
+    /// The patches applied during one turn.
+    Diff {
+        unified_diff: String,
+    },
+}
+
+/// Payload of the output of a shell call, see `format_exec_output` in
+/// `codex.rs`.
+#[derive(Deserialize)]
+struct ExecOutput {
+    output: String,
+    metadata: ExecMetadata,
+}
+
+#[derive(Deserialize)]
+struct ExecMetadata {
+    exit_code: i32,
+}
+
+#[derive(Deserialize)]
+struct ApplyPatchArgs {
+    input: String,
+}
+
+impl SessionExport {
+    fn from_items(items: &[RolloutItem]) -> Self {
+        let mut export = Self::default();
+        // Entries of calls waiting for their output, by call id.
+        let mut pending_calls: HashMap<String, usize> = HashMap::new();
+        // Patches applied during the current turn, shown before the next user
+        // message.
+        let mut turn_diff = AppliedPatchDiff::new();
+
+        for item in items {
+            let entry = match item {
+                RolloutItem::SessionMeta(meta_line) => {
+                    // Forked sessions repeat the metadata of the session they
+                    // were forked from; keep the first.
+                    if export.id.is_none() {
+                        export.id = Some(meta_line.meta.id);
+                        export.timestamp = Some(meta_line.meta.timestamp.clone());
+                        export.cwd = Some(meta_line.meta.cwd.clone());
+                        export.git_branch =
+                            meta_line.git.as_ref().and_then(|git| git.branch.clone());
+                    }
+                    continue;
+                }
+                RolloutItem::TurnContext(context) => {
+                    if !export.models.contains(&context.model) {
+                        export.models.push(context.model.clone());
+                    }
+                    continue;
+                }
+                RolloutItem::Compacted(_) => continue,
+                RolloutItem::EventMsg(event) => match event {
+                    EventMsg::UserMessage(event)
+                        if matches!(event.kind, None | Some(InputMessageKind::Plain)) =>
+                    {
+                        export.push_diff(turn_diff.take_diff());
+                        let text = match event.message.find(USER_MESSAGE_BEGIN) {
+                            Some(idx) => &event.message[idx + USER_MESSAGE_BEGIN.len()..],
+                            None => event.message.as_str(),
+                        };
+                        ExportEntry::UserMessage {
+                            text: text.trim().to_string(),
+                        }
+                    }
+                    EventMsg::AgentMessage(event) => ExportEntry::AgentMessage {
+                        text: event.message.trim().to_string(),
+                    },
+                    EventMsg::AgentReasoning(event) => ExportEntry::Reasoning {
+                        text: event.text.trim().to_string(),
+                    },
+                    EventMsg::TokenCount(event) => {
+                        if let Some(info) = &event.info {
+                            export.token_usage = Some(info.total_token_usage.clone());
+                        }
+                        continue;
+                    }
+                    _ => continue,
+                },
+                RolloutItem::ResponseItem(item) => {
+                    turn_diff.record(item);
+                    match item {
+                        ResponseItem::FunctionCall {
+                            name,
+                            arguments,
+                            call_id,
+                            ..
+                        } => {
+                            pending_calls.insert(call_id.clone(), export.entries.len());
+                            function_call_entry(name, arguments)
+                        }
+                        ResponseItem::LocalShellCall {
+                            call_id, action, ..
+                        } => {
+                            let LocalShellAction::Exec(exec) = action;
+                            if let Some(call_id) = call_id {
+                                pending_calls.insert(call_id.clone(), export.entries.len());
+                            }
+                            command_entry(&exec.command)
+                        }
+                        ResponseItem::CustomToolCall {
+                            name,
+                            input,
+                            call_id,
+                            ..
+                        } => {
+                            pending_calls.insert(call_id.clone(), export.entries.len());
+                            if name == "apply_patch" {
+                                ExportEntry::Patch {
+                                    patch: input.clone(),
+                                    output: None,
+                                }
+                            } else {
+                                ExportEntry::ToolCall {
+                                    name: name.clone(),
+                                    arguments: input.clone(),
+                                    output: None,
+                                }
+                            }
+                        }
+                        ResponseItem::FunctionCallOutput {
+                            call_id,
+                            output: FunctionCallOutputPayload { content, .. },
+                        }
+                        | ResponseItem::CustomToolCallOutput {
+                            call_id,
+                            output: content,
+                        } => {
+                            if let Some(entry) = pending_calls
+                                .remove(call_id)
+                                .and_then(|idx| export.entries.get_mut(idx))
+                            {
+                                entry.set_output(content);
+                            }
+                            continue;
+                        }
+                        _ => continue,
+                    }
+                }
+            };
+            export.entries.push(entry);
+        }
+
+        export.push_diff(turn_diff.take_diff());
+        export
+    }
+
+    fn push_diff(&mut self, unified_diff: String) {
+        if !unified_diff.is_empty() {
+            self.entries.push(ExportEntry::Diff { unified_diff });
+        }
+    }
+
+    fn title(&self) -> String {
+        match &self.id {
+            Some(id) => format!("Codex session {id}"),
+            None => "Codex session".to_string(),
+        }
+    }
+
+    /// The metadata shown under the title, as label, value and whether the
+    /// value is code.
+    fn details(&self) -> Vec<(&'static str, String, bool)> {
+        let mut details = Vec::new();
+        if let Some(timestamp) = self.timestamp.as_ref().filter(|ts| !ts.is_empty()) {
+            details.push(("Started", timestamp.clone(), false));
+        }
+        if let Some(cwd) = &self.cwd {
+            details.push(("Working directory", cwd.display().to_string(), true));
+        }
+        if let Some(branch) = &self.git_branch {
+            details.push(("Git branch", branch.clone(), true));
+        }
+        if !self.models.is_empty() {
+            details.push(("Model", self.models.join(", "), false));
+        }
+        details
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n", self.title());
+        let details = self.details();
+        if !details.is_empty() {
+            out.push('\n');
+            for (label, value, code) in details {
+                if code {
+                    out.push_str(&format!("- {label}: `{value}`\n"));
+                } else {
+                    out.push_str(&format!("- {label}: {value}\n"));
+                }
+            }
+        }
+
+        for entry in &self.entries {
+            out.push('\n');
+            match entry {
+                ExportEntry::UserMessage { text } => {
+                    out.push_str(&format!("## User\n\n{text}\n"));
+                }
+                ExportEntry::AgentMessage { text } => {
+                    out.push_str(&format!("## Codex\n\n{text}\n"));
+                }
+                ExportEntry::Reasoning { text } => {
+                    let quoted: Vec<String> = format!("**Reasoning:** {text}")
+                        .lines()
+                        .map(|line| format!("> {line}").trim_end().to_string())
+                        .collect();
+                    out.push_str(&quoted.join("\n"));
+                    out.push('\n');
+                }
+                ExportEntry::Command {
+                    command,
+                    exit_code,
+                    output,
+                } => {
+                    out.push_str(&markdown_code_block("sh", &format!("$ {command}")));
+                    if let Some(output) = output {
+                        let summary = match exit_code {
+                            Some(code) => format!("Output (exit code {code})"),
+                            None => "Output".to_string(),
+                        };
+                        out.push_str(&markdown_details(&summary, "text", output));
+                    }
+                }
+                ExportEntry::Patch { patch, output } => {
+                    out.push_str("**Patch**\n\n");
+                    out.push_str(&markdown_code_block("diff", patch));
+                    if let Some(output) = output {
+                        out.push_str(&markdown_details("Result", "text", output));
+                    }
+                }
+                ExportEntry::ToolCall {
+                    name,
+                    arguments,
+                    output,
+                } => {
+                    out.push_str(&format!("**Tool call:** `{name}`\n\n"));
+                    out.push_str(&markdown_code_block("json", arguments));
+                    if let Some(output) = output {
+                        out.push_str(&markdown_details("Result", "text", output));
+                    }
+                }
+                ExportEntry::Diff { unified_diff } => {
+                    out.push_str("### Changes\n\n");
+                    out.push_str(&markdown_code_block("diff", unified_diff));
+                }
+            }
+        }
+
+        if let Some(usage) = &self.token_usage {
+            out.push_str("\n## Token usage\n\n");
+            out.push_str("| Input | Cached input | Output | Reasoning output | Total |\n");
+            out.push_str("| ---: | ---: | ---: | ---: | ---: |\n");
+            out.push_str(&format!(
+                "| {} | {} | {} | {} | {} |\n",
+                usage.input_tokens,
+                usage.cached_input_tokens,
+                usage.output_tokens,
+                usage.reasoning_output_tokens,
+                usage.total_tokens
+            ));
+        }
+        out
+    }
+
+    fn to_html(&self) -> String {
+        let title = escape_html(&self.title());
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>\n"
+        );
+        let details = self.details();
+        if !details.is_empty() {
+            out.push_str("<ul class=\"meta\">\n");
+            for (label, value, code) in details {
+                let value = escape_html(&value);
+                if code {
+                    out.push_str(&format!("<li>{label}: <code>{value}</code></li>\n"));
+                } else {
+                    out.push_str(&format!("<li>{label}: {value}</li>\n"));
+                }
+            }
+            out.push_str("</ul>\n");
+        }
+
+        for entry in &self.entries {
+            match entry {
+                ExportEntry::UserMessage { text } => {
+                    out.push_str(&format!(
+                        "<section class=\"user\">\n<h2>User</h2>\n<div class=\"text\">{}</div>\n</section>\n",
+                        escape_html(text)
+                    ));
+                }
+                ExportEntry::AgentMessage { text } => {
+                    out.push_str(&format!(
+                        "<section class=\"codex\">\n<h2>Codex</h2>\n<div class=\"text\">{}</div>\n</section>\n",
+                        escape_html(text)
+                    ));
+                }
+                ExportEntry::Reasoning { text } => {
+                    out.push_str(&format!(
+                        "<blockquote class=\"reasoning\"><strong>Reasoning:</strong> {}</blockquote>\n",
+                        escape_html(text)
+                    ));
+                }
+                ExportEntry::Command {
+                    command,
+                    exit_code,
+                    output,
+                } => {
+                    let status = match exit_code {
+                        Some(0) => " ok",
+                        Some(_) => " failed",
+                        None => "",
+                    };
+                    out.push_str(&format!(
+                        "<div class=\"command{status}\">\n<pre><code>$ {}</code></pre>\n",
+                        escape_html(command)
+                    ));
+                    if let Some(output) = output {
+                        let summary = match exit_code {
+                            Some(code) => format!("Output (exit code {code})"),
+                            None => "Output".to_string(),
+                        };
+                        out.push_str(&html_details(&summary, &escape_html(output)));
+                    }
+                    out.push_str("</div>\n");
+                }
+                ExportEntry::Patch { patch, output } => {
+                    out.push_str("<div class=\"patch\">\n<h3>Patch</h3>\n");
+                    out.push_str(&html_diff(patch));
+                    if let Some(output) = output {
+                        out.push_str(&html_details("Result", &escape_html(output)));
+                    }
+                    out.push_str("</div>\n");
+                }
+                ExportEntry::ToolCall {
+                    name,
+                    arguments,
+                    output,
+                } => {
+                    out.push_str(&format!(
+                        "<div class=\"tool\">\n<h3>Tool call: <code>{}</code></h3>\n<pre><code>{}</code></pre>\n",
+                        escape_html(name),
+                        escape_html(arguments)
+                    ));
+                    if let Some(output) = output {
+                        out.push_str(&html_details("Result", &escape_html(output)));
+                    }
+                    out.push_str("</div>\n");
+                }
+                ExportEntry::Diff { unified_diff } => {
+                    out.push_str("<div class=\"changes\">\n<h3>Changes</h3>\n");
+                    out.push_str(&html_diff(unified_diff));
+                    out.push_str("</div>\n");
+                }
+            }
+        }
+
+        if let Some(usage) = &self.token_usage {
+            out.push_str(&format!(
+                "<h2>Token usage</h2>\n<table class=\"usage\">\n<tr><th>Input</th><th>Cached input</th><th>Output</th><th>Reasoning output</th><th>Total</th></tr>\n<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n</table>\n",
+                usage.input_tokens,
+                usage.cached_input_tokens,
+                usage.output_tokens,
+                usage.reasoning_output_tokens,
+                usage.total_tokens
+            ));
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+impl ExportEntry {
+    fn set_output(&mut self, content: &str) {
+        match self {
+            ExportEntry::Command {
+                exit_code, output, ..
+            } => match serde_json::from_str::<ExecOutput>(content) {
+                Ok(exec) => {
+                    *exit_code = Some(exec.metadata.exit_code);
+                    *output = Some(exec.output);
+                }
+                Err(_) => *output = Some(content.to_string()),
+            },
+            // Patches are applied as commands too, but only their output
+            // is of interest.
+            ExportEntry::Patch { output, .. } => {
+                *output = Some(match serde_json::from_str::<ExecOutput>(content) {
+                    Ok(exec) => exec.output,
+                    Err(_) => content.to_string(),
+                });
+            }
+            ExportEntry::ToolCall { output, .. } => {
+                *output = Some(content.to_string());
+            }
+            _ => {}
+        }
+    }
+}
+
+fn function_call_entry(name: &str, arguments: &str) -> ExportEntry {
+    match name {
+        "shell" | "container.exec" => {
+            if let Ok(params) = serde_json::from_str::<ShellToolCallParams>(arguments) {
+                return command_entry(&params.command);
+            }
+        }
+        "apply_patch" => {
+            if let Ok(args) = serde_json::from_str::<ApplyPatchArgs>(arguments) {
+                return ExportEntry::Patch {
+                    patch: args.input,
+                    output: None,
+                };
+            }
+        }
+        _ => {}
+    }
+    ExportEntry::ToolCall {
+        name: name.to_string(),
+        arguments: arguments.to_string(),
+        output: None,
+    }
+}
+
+fn command_entry(command: &[String]) -> ExportEntry {
+    match command {
+        // The model may also call apply_patch through the shell tool.
+        [program, patch] if program == "apply_patch" => ExportEntry::Patch {
+            patch: patch.clone(),
+            output: None,
+        },
+        _ => ExportEntry::Command {
+            command: display_command(command),
+            exit_code: None,
+            output: None,
+        },
+    }
+}
+
+/// The command as typed in a shell: the script of `bash -lc <script>`, or
+/// the arguments quoted and joined.
+fn display_command(command: &[String]) -> String {
+    match command {
+        [shell, flag, script]
+            if matches!(shell.as_str(), "bash" | "sh" | "zsh")
+                && matches!(flag.as_str(), "-lc" | "-c") =>
+        {
+            script.clone()
+        }
+        _ => shlex::try_join(command.iter().map(String::as_str))
+            .unwrap_or_else(|_| command.join(" ")),
+    }
+}
+
+/// A fenced code block, with a fence longer than any run of backticks in
+/// `code`.
+fn markdown_code_block(lang: &str, code: &str) -> String {
+    let mut longest = 0;
+    let mut run = 0;
+    for c in code.chars() {
+        run = if c == '`' { run + 1 } else { 0 };
+        longest = longest.max(run);
+    }
+    let fence = "`".repeat((longest + 1).max(3));
+    format!("{fence}{lang}\n{}\n{fence}\n", code.trim_end_matches('\n'))
+}
+
+/// A collapsed code block, which GitHub and most Markdown viewers render as a
+/// disclosure widget.
+fn markdown_details(summary: &str, lang: &str, code: &str) -> String {
+    format!(
+        "\n<details>\n<summary>{summary}</summary>\n\n{}\n</details>\n",
+        markdown_code_block(lang, code)
+    )
+}
+
+fn html_details(summary: &str, escaped: &str) -> String {
+    format!("<details>\n<summary>{summary}</summary>\n<pre>{escaped}</pre>\n</details>\n")
+}
+
+fn html_diff(diff: &str) -> String {
+    let mut out = String::from("<pre class=\"diff\">");
+    for line in diff.lines() {
+        let class = if line.starts_with("+++") || line.starts_with("---") {
+            Some("file")
+        } else if line.starts_with('+') {
+            Some("add")
+        } else if line.starts_with('-') {
+            Some("del")
+        } else if line.starts_with("@@") || line.starts_with("***") {
+            Some("hunk")
+        } else {
+            None
+        };
+        let line = escape_html(line);
+        match class {
+            Some(class) => out.push_str(&format!("<span class=\"{class}\">{line}</span>\n")),
+            None => {
+                out.push_str(&line);
+                out.push('\n');
+            }
+        }
+    }
+    out.push_str("</pre>\n");
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+const HTML_STYLE: &str = "
+body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Helvetica, Arial, sans-serif; max-width: 960px; margin: 2em auto; padding: 0 1em; color: #1f2328; line-height: 1.5; }
+h2 { font-size: 1.1em; margin: 1.5em 0 0.3em; }
+h3 { font-size: 1em; margin: 1em 0 0.3em; }
+.meta { color: #59636e; padding-left: 1.2em; }
+.text { white-space: pre-wrap; }
+section.user { border-left: 3px solid #0969da; padding-left: 0.8em; }
+section.codex { border-left: 3px solid #8250df; padding-left: 0.8em; }
+.reasoning { color: #59636e; font-style: italic; margin: 0.8em 0; padding-left: 0.8em; border-left: 3px solid #d1d9e0; white-space: pre-wrap; }
+pre { background: #f6f8fa; padding: 0.6em; overflow-x: auto; font-size: 0.85em; }
+.command.ok > pre { border-left: 3px solid #1a7f37; }
+.command.failed > pre { border-left: 3px solid #d1242f; }
+summary { cursor: pointer; color: #59636e; }
+.diff .add { color: #1a7f37; }
+.diff .del { color: #d1242f; }
+.diff .hunk { color: #8250df; }
+.diff .file { font-weight: bold; }
+table.usage { border-collapse: collapse; }
+table.usage th, table.usage td { border: 1px solid #d1d9e0; padding: 0.3em 0.8em; text-align: right; }
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::AgentMessageEvent;
+    use crate::protocol::AgentReasoningEvent;
+    use crate::protocol::TokenCountEvent;
+    use crate::protocol::TokenUsageInfo;
+    use crate::protocol::UserMessageEvent;
+    use pretty_assertions::assert_eq;
+
+    const PATCH: &str = "*** Begin Patch\n*** Add File: fixed.txt\n+fixed\n*** End Patch";
+
+    fn sample_items() -> Vec<RolloutItem> {
+        vec![
+            RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+                message: "<environment_context>cwd</environment_context>".to_string(),
+                kind: Some(InputMessageKind::EnvironmentContext),
+                images: None,
+            })),
+            RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+                message: "Fix the <tokenizer>".to_string(),
+                kind: Some(InputMessageKind::Plain),
+                images: None,
+            })),
+            RolloutItem::EventMsg(EventMsg::AgentReasoning(AgentReasoningEvent {
+                text: "Looking at the tests".to_string(),
+            })),
+            RolloutItem::ResponseItem(ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: r#"{"command":["bash","-lc","cargo test"]}"#.to_string(),
+                call_id: "call-1".to_string(),
+                thought_signature: None,
+            }),
+            RolloutItem::ResponseItem(ResponseItem::FunctionCallOutput {
+                call_id: "call-1".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: r#"{"output":"1 failed","metadata":{"exit_code":101,"duration_seconds":1.5}}"#
+                        .to_string(),
+                    success: None,
+                },
+            }),
+            RolloutItem::ResponseItem(ResponseItem::CustomToolCall {
+                id: None,
+                status: None,
+                call_id: "call-2".to_string(),
+                name: "apply_patch".to_string(),
+                input: PATCH.to_string(),
+            }),
+            RolloutItem::ResponseItem(ResponseItem::CustomToolCallOutput {
+                call_id: "call-2".to_string(),
+                output: r#"{"output":"Success.","metadata":{"exit_code":0,"duration_seconds":0.1}}"#
+                    .to_string(),
+            }),
+            RolloutItem::EventMsg(EventMsg::AgentMessage(AgentMessageEvent {
+                message: "Fixed it.".to_string(),
+            })),
+            RolloutItem::EventMsg(EventMsg::TokenCount(TokenCountEvent {
+                info: Some(TokenUsageInfo {
+                    total_token_usage: TokenUsage {
+                        input_tokens: 100,
+                        cached_input_tokens: 20,
+                        output_tokens: 30,
+                        reasoning_output_tokens: 10,
+                        total_tokens: 130,
+                    },
+                    last_token_usage: TokenUsage::default(),
+                    model_context_window: None,
+                }),
+            })),
+        ]
+    }
+
+    #[test]
+    fn collects_entries_and_matches_call_outputs() {
+        let export = SessionExport::from_items(&sample_items());
+        assert_eq!(
+            export.entries,
+            vec![
+                ExportEntry::UserMessage {
+                    text: "Fix the <tokenizer>".to_string()
+                },
+                ExportEntry::Reasoning {
+                    text: "Looking at the tests".to_string()
+                },
+                ExportEntry::Command {
+                    command: "cargo test".to_string(),
+                    exit_code: Some(101),
+                    output: Some("1 failed".to_string()),
+                },
+                ExportEntry::Patch {
+                    patch: PATCH.to_string(),
+                    output: Some("Success.".to_string()),
+                },
+                ExportEntry::AgentMessage {
+                    text: "Fixed it.".to_string()
+                },
+                ExportEntry::Diff {
+                    unified_diff: "diff --git a/fixed.txt b/fixed.txt\nnew file mode 100644\n\
+                                   --- /dev/null\n+++ b/fixed.txt\n@@ -0,0 +1,1 @@\n+fixed\n"
+                        .to_string()
+                },
+            ]
+        );
+        assert_eq!(
+            export.token_usage.map(|usage| usage.total_tokens),
+            Some(130)
+        );
+    }
+
+    #[test]
+    fn renders_markdown() {
+        let markdown = render_export(&sample_items(), ExportFormat::Markdown).unwrap();
+        assert_eq!(
+            markdown,
+            "# Codex session
+
+## User
+
+Fix the <tokenizer>
+
+> **Reasoning:** Looking at the tests
+
+```sh
+$ cargo test
+```
+
+<details>
+<summary>Output (exit code 101)</summary>
+
+```text
+1 failed
+```
+
+</details>
+
+**Patch**
+
+```diff
+*** Begin Patch
+*** Add File: fixed.txt
++fixed
+*** End Patch
+```
+
+<details>
+<summary>Result</summary>
+
+```text
+Success.
+```
+
+</details>
+
+## Codex
+
+Fixed it.
+
+### Changes
+
+```diff
+diff --git a/fixed.txt b/fixed.txt
+new file mode 100644
+--- /dev/null
++++ b/fixed.txt
+@@ -0,0 +1,1 @@
++fixed
+```
+
+## Token usage
+
+| Input | Cached input | Output | Reasoning output | Total |
+| ---: | ---: | ---: | ---: | ---: |
+| 100 | 20 | 30 | 10 | 130 |
+"
+        );
+    }
+
+    #[test]
+    fn html_escapes_content() {
+        let html = render_export(&sample_items(), ExportFormat::Html).unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<div class=\"text\">Fix the &lt;tokenizer&gt;</div>"));
+        assert!(html.contains("<summary>Output (exit code 101)</summary>"));
+        assert!(html.contains("<span class=\"add\">+fixed</span>"));
+    }
+
+    #[test]
+    fn code_block_fence_outgrows_backticks() {
+        assert_eq!(
+            markdown_code_block("md", "```rust\nfn main() {}\n```"),
+            "````md\n```rust\nfn main() {}\n```\n````\n"
+        );
+    }
+}
@@ -0,0 +1,608 @@
+//! Search index over the recorded sessions in `~/.codex/sessions`.
+//!
+//! The index is an append-only JSONL file next to the rollouts. Each line
+//! adds to the entry of one rollout file: its metadata when the session
+//! starts, then the text of its messages and the models it used as it goes
+//! on. Writers hold an exclusive lock on the file, so concurrent Codex
+//! processes neither interleave their lines nor lose them when the index is
+//! rewritten, as happens when rollouts are deleted, archived or compressed.
+//! Rollouts recorded before the index existed are indexed the first time it
+//! is searched. Searching also compacts the index once most of its lines are
+//! superseded, or when it still refers to rollouts that no longer exist.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde::Serialize;
+use time::Date;
+use time::Duration;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+use time::macros::format_description;
+use tracing::warn;
+
+use super::SESSIONS_SUBDIR;
+use super::compress::read_rollout_text;
+use super::list::ConversationItem;
+use super::list::ConversationsPage;
+use super::list::Cursor;
+use super::list::HEAD_RECORD_LIMIT;
+use super::list::build_next_cursor;
+use super::list::collect_rollout_files;
+use super::list::read_head_and_flags;
+use crate::message_history::is_current_file;
+use crate::protocol::EventMsg;
+use crate::protocol::InputMessageKind;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+
+/// Name of the index file in the sessions directory.
+pub const SESSION_INDEX_FILE: &str = "index.jsonl";
+
+/// Filters for [`search_conversations`]. Unset filters match every session.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionSearch {
+    /// Words that must all appear in the session's messages, ignoring case.
+    pub query: Option<String>,
+    /// Only sessions started in this directory or one below it.
+    pub cwd: Option<PathBuf>,
+    /// Only sessions started on this git branch.
+    pub git_branch: Option<String>,
+    /// Only sessions that used this model.
+    pub model: Option<String>,
+    /// Only sessions started at or after this time.
+    pub since: Option<OffsetDateTime>,
+    /// Only sessions started before this time.
+    pub until: Option<OffsetDateTime>,
+}
+
+impl SessionSearch {
+    /// Whether no filter is set.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Parses the lower bound of a date range: an RFC 3339 timestamp, or a
+    /// `YYYY-MM-DD` date standing for the start of that day (UTC).
+    pub fn parse_since(value: &str) -> Option<OffsetDateTime> {
+        OffsetDateTime::parse(value, &Rfc3339)
+            .ok()
+            .or_else(|| Some(parse_date(value)?.midnight().assume_utc()))
+    }
+
+    /// Parses the upper bound of a date range: an RFC 3339 timestamp, or a
+    /// `YYYY-MM-DD` date standing for the end of that day (UTC).
+    pub fn parse_until(value: &str) -> Option<OffsetDateTime> {
+        OffsetDateTime::parse(value, &Rfc3339)
+            .ok()
+            .or_else(|| Some(parse_date(value)?.midnight().assume_utc() + Duration::days(1)))
+    }
+
+    fn matches(&self, entry: &IndexEntry, started: OffsetDateTime) -> bool {
+        let query_matches = self.query.as_deref().is_none_or(|query| {
+            query
+                .split_whitespace()
+                .all(|word| entry.text.contains(&word.to_lowercase()))
+        });
+        query_matches
+            && self.cwd.as_ref().is_none_or(|cwd| {
+                entry
+                    .cwd
+                    .as_ref()
+                    .is_some_and(|entry_cwd| entry_cwd.starts_with(cwd))
+            })
+            && self
+                .git_branch
+                .as_ref()
+                .is_none_or(|branch| entry.git_branch.as_ref() == Some(branch))
+            && self
+                .model
+                .as_ref()
+                .is_none_or(|model| entry.models.contains(model))
+            && self.since.is_none_or(|since| started >= since)
+            && self.until.is_none_or(|until| started < until)
+    }
+}
+
+fn parse_date(value: &str) -> Option<Date> {
+    Date::parse(value, format_description!("[year]-[month]-[day]")).ok()
+}
+
+/// One line of the index file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct IndexRecord {
+    /// Rollout file, relative to the sessions directory.
+    path: PathBuf,
+    /// Set on the first record of a session. Replaces anything indexed for
+    /// the same file before, so a rollout indexed again is not counted twice.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    start: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timestamp: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cwd: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    git_branch: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    models: Vec<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    text: String,
+}
+
+impl IndexRecord {
+    /// The part of `item` that is indexed, if any.
+    fn from_item(path: &Path, item: &RolloutItem) -> Option<Self> {
+        let record = match item {
+            RolloutItem::SessionMeta(meta_line) => Self {
+                start: true,
+                timestamp: Some(meta_line.meta.timestamp.clone()),
+                cwd: Some(meta_line.meta.cwd.clone()),
+                git_branch: meta_line.git.as_ref().and_then(|git| git.branch.clone()),
+                ..Default::default()
+            },
+            RolloutItem::TurnContext(context) => Self {
+                models: vec![context.model.clone()],
+                ..Default::default()
+            },
+            RolloutItem::EventMsg(EventMsg::UserMessage(event))
+                if matches!(event.kind, None | Some(InputMessageKind::Plain)) =>
+            {
+                Self {
+                    text: event.message.clone(),
+                    ..Default::default()
+                }
+            }
+            RolloutItem::EventMsg(EventMsg::AgentMessage(event)) => Self {
+                text: event.message.clone(),
+                ..Default::default()
+            },
+            _ => return None,
+        };
+        Some(Self {
+            path: path.to_path_buf(),
+            ..record
+        })
+    }
+}
+
+/// What is known about one rollout file.
+#[derive(Debug, Default)]
+struct IndexEntry {
+    /// Whether the entry starts with the session's metadata, i.e. whether the
+    /// whole rollout has been indexed.
+    complete: bool,
+    /// Number of index records the entry was built from.
+    records: usize,
+    timestamp: Option<OffsetDateTime>,
+    cwd: Option<PathBuf>,
+    git_branch: Option<String>,
+    models: Vec<String>,
+    /// Lowercased text of the messages.
+    text: String,
+}
+
+impl IndexEntry {
+    /// Adds `record` to the entry and returns how many of the records applied
+    /// before it are superseded by it.
+    fn apply(&mut self, record: IndexRecord) -> usize {
+        let mut superseded = 0;
+        if record.start {
+            superseded = self.records;
+            *self = Self {
+                complete: true,
+                ..Default::default()
+            };
+        }
+        self.records += 1;
+        if let Some(timestamp) = record.timestamp {
+            self.timestamp = OffsetDateTime::parse(&timestamp, &Rfc3339).ok();
+        }
+        if record.cwd.is_some() {
+            self.cwd = record.cwd;
+        }
+        if record.git_branch.is_some() {
+            self.git_branch = record.git_branch;
+        }
+        for model in record.models {
+            if !self.models.contains(&model) {
+                self.models.push(model);
+            }
+        }
+        if !record.text.is_empty() {
+            if !self.text.is_empty() {
+                self.text.push('\n');
+            }
+            self.text.push_str(&record.text.to_lowercase());
+        }
+        superseded
+    }
+}
+
+/// Appends the indexed parts of the items recorded to one rollout file.
+pub(crate) struct SessionIndexWriter {
+    index_path: PathBuf,
+    rollout_path: PathBuf,
+    started: bool,
+    last_model: Option<String>,
+}
+
+impl SessionIndexWriter {
+    /// Returns `None` if `rollout_path` is not in the sessions directory of
+    /// `codex_home`.
+    pub(crate) fn new(codex_home: &Path, rollout_path: &Path) -> Option<Self> {
+        let sessions = codex_home.join(SESSIONS_SUBDIR);
+        let rollout_path = rollout_path.strip_prefix(&sessions).ok()?.to_path_buf();
+        Some(Self {
+            index_path: sessions.join(SESSION_INDEX_FILE),
+            rollout_path,
+            started: false,
+            last_model: None,
+        })
+    }
+
+    pub(crate) async fn record(&mut self, item: &RolloutItem) {
+        let Some(record) = IndexRecord::from_item(&self.rollout_path, item) else {
+            return;
+        };
+        // A forked session records the metadata of the session it was forked
+        // from after its own, which must not reset the entry.
+        if record.start {
+            if self.started {
+                return;
+            }
+            self.started = true;
+        }
+        // Turn contexts repeat the model on every turn; only note changes.
+        if let Some(model) = record.models.first() {
+            if self.last_model.as_ref() == Some(model) {
+                return;
+            }
+            self.last_model = Some(model.clone());
+        }
+        if let Err(e) = append_records(&self.index_path, &[record]).await {
+            warn!("failed to update session index: {e}");
+        }
+    }
+}
+
+/// Search the recorded conversations. Results are ordered like
+/// [`super::list::get_conversations`] and paginated with the same cursors.
+pub(crate) async fn search_conversations(
+    codex_home: &Path,
+    search: &SessionSearch,
+    page_size: usize,
+    cursor: Option<&Cursor>,
+) -> io::Result<ConversationsPage> {
+    let root = codex_home.join(SESSIONS_SUBDIR);
+    if !root.exists() {
+        return Ok(ConversationsPage::default());
+    }
+
+    let index_path = root.join(SESSION_INDEX_FILE);
+    let LoadedIndex {
+        mut entries,
+        records,
+        superseded,
+    } = load_index(&index_path).await?;
+
+    let mut files = collect_rollout_files(&root).await?;
+    files.sort_by_key(|(ts, id, _)| (Reverse(*ts), Reverse(*id)));
+
+    // Indexing a rollout again leaves its earlier records behind, and
+    // rollouts removed without updating the index leave theirs.
+    let existing: HashSet<&Path> = files
+        .iter()
+        .filter_map(|(_, _, path)| path.strip_prefix(&root).ok())
+        .collect();
+    let has_missing = entries
+        .keys()
+        .any(|path| !existing.contains(path.as_path()));
+    if has_missing || superseded > records - superseded {
+        if let Err(e) = compact_index(&root).await {
+            warn!("failed to compact session index: {e}");
+        }
+        entries.retain(|path, _| existing.contains(path.as_path()));
+    }
+
+    // Index the rollouts recorded before the index existed.
+    let mut backfilled = Vec::new();
+    for (_, _, path) in &files {
+        let Ok(relative) = path.strip_prefix(&root) else {
+            continue;
+        };
+        if entries.get(relative).is_some_and(|entry| entry.complete) {
+            continue;
+        }
+        match index_rollout_file(path, relative).await {
+            Ok(record) => backfilled.push(record),
+            Err(e) => warn!("failed to index {path:?}: {e}"),
+        }
+    }
+    if !backfilled.is_empty() {
+        if let Err(e) = append_records(&index_path, &backfilled).await {
+            warn!("failed to update session index: {e}");
+        }
+        for record in backfilled {
+            entries
+                .entry(record.path.clone())
+                .or_default()
+                .apply(record);
+        }
+    }
+
+    let mut items: Vec<ConversationItem> = Vec::with_capacity(page_size);
+    let mut scanned_files = 0usize;
+    for (ts, id, path) in files {
+        if items.len() == page_size {
+            break;
+        }
+        if let Some(cursor) = cursor
+            && !cursor.is_before(ts, id)
+        {
+            continue;
+        }
+        let Some(entry) = path
+            .strip_prefix(&root)
+            .ok()
+            .and_then(|relative| entries.get(relative))
+        else {
+            continue;
+        };
+        if !search.matches(entry, entry.timestamp.unwrap_or(ts)) {
+            continue;
+        }
+        scanned_files += 1;
+        let (head, saw_session_meta, saw_user_event) =
+            read_head_and_flags(&path, HEAD_RECORD_LIMIT)
+                .await
+                .unwrap_or((Vec::new(), false, false));
+        if saw_session_meta && saw_user_event {
+            items.push(ConversationItem { path, head });
+        }
+    }
+
+    let next_cursor = if items.len() == page_size {
+        build_next_cursor(&items)
+    } else {
+        None
+    };
+    Ok(ConversationsPage {
+        items,
+        next_cursor,
+        num_scanned_files: scanned_files,
+        reached_scan_cap: false,
+    })
+}
+
+/// What [`load_index`] read from the index file.
+struct LoadedIndex {
+    entries: HashMap<PathBuf, IndexEntry>,
+    /// Number of records in the file.
+    records: usize,
+    /// Number of those superseded by a later `start` record for their file.
+    superseded: usize,
+}
+
+async fn load_index(index_path: &Path) -> io::Result<LoadedIndex> {
+    let index_path = index_path.to_path_buf();
+    let text = tokio::task::spawn_blocking(move || -> io::Result<String> {
+        let Some(mut file) = open_locked_index(&index_path, false)? else {
+            return Ok(String::new());
+        };
+        let mut text = String::new();
+        file.read_to_string(&mut text)?;
+        Ok(text)
+    })
+    .await
+    .map_err(io::Error::other)??;
+    let mut loaded = LoadedIndex {
+        entries: HashMap::new(),
+        records: 0,
+        superseded: 0,
+    };
+    for line in text.lines() {
+        // A line may be incomplete if a writer crashed.
+        let Ok(record) = serde_json::from_str::<IndexRecord>(line) else {
+            continue;
+        };
+        loaded.records += 1;
+        loaded.superseded += loaded
+            .entries
+            .entry(record.path.clone())
+            .or_default()
+            .apply(record);
+    }
+    Ok(loaded)
+}
+
+/// Rewrites the index without the records superseded by a later `start`
+/// record and without the records of rollouts that no longer exist.
+async fn compact_index(sessions: &Path) -> io::Result<()> {
+    let sessions_dir = sessions.to_path_buf();
+    rewrite_index(&sessions.join(SESSION_INDEX_FILE), move |records| {
+        let last_start: HashMap<PathBuf, usize> = records
+            .iter()
+            .enumerate()
+            .filter(|(_, record)| record.start)
+            .map(|(i, record)| (record.path.clone(), i))
+            .collect();
+        records
+            .into_iter()
+            .enumerate()
+            .filter(|(i, record)| {
+                last_start.get(&record.path).is_none_or(|start| i >= start)
+                    && sessions_dir.join(&record.path).exists()
+            })
+            .map(|(_, record)| record)
+            .collect()
+    })
+    .await
+}
+
+/// Updates the index after rollout files were deleted or moved: what is
+/// indexed for a path in `moved` is dropped if it maps to `None`, or follows
+/// the file to its new path otherwise. Paths outside the sessions directory,
+/// such as archived rollouts, are not indexed.
+pub(crate) async fn update_index_paths(
+    codex_home: &Path,
+    moved: HashMap<PathBuf, Option<PathBuf>>,
+) -> io::Result<()> {
+    let sessions = codex_home.join(SESSIONS_SUBDIR);
+    let relative = |path: &Path| path.strip_prefix(&sessions).ok().map(Path::to_path_buf);
+    let moved: HashMap<PathBuf, Option<PathBuf>> = moved
+        .into_iter()
+        .filter_map(|(from, to)| Some((relative(&from)?, to.as_deref().and_then(relative))))
+        .collect();
+    if moved.is_empty() {
+        return Ok(());
+    }
+    rewrite_index(&sessions.join(SESSION_INDEX_FILE), move |records| {
+        records
+            .into_iter()
+            .filter_map(|mut record| match moved.get(&record.path) {
+                None => Some(record),
+                Some(None) => None,
+                Some(Some(to)) => {
+                    record.path = to.clone();
+                    Some(record)
+                }
+            })
+            .collect()
+    })
+    .await
+}
+
+/// Replaces the records of the index with what `f` returns for them. Lines
+/// that do not parse are dropped.
+async fn rewrite_index<F>(index_path: &Path, f: F) -> io::Result<()>
+where
+    F: FnOnce(Vec<IndexRecord>) -> Vec<IndexRecord> + Send + 'static,
+{
+    let index_path = index_path.to_path_buf();
+    tokio::task::spawn_blocking(move || -> io::Result<()> {
+        let Some(mut file) = open_locked_index(&index_path, true)? else {
+            return Ok(());
+        };
+        let mut text = String::new();
+        file.seek(SeekFrom::Start(0))?;
+        file.read_to_string(&mut text)?;
+
+        let records = text
+            .lines()
+            .filter_map(|line| serde_json::from_str::<IndexRecord>(line).ok())
+            .collect();
+        let mut rewritten = String::with_capacity(text.len());
+        for record in f(records) {
+            rewritten.push_str(&serde_json::to_string(&record)?);
+            rewritten.push('\n');
+        }
+        if rewritten == text {
+            return Ok(());
+        }
+
+        // Written aside and renamed over the index while still holding the
+        // lock; writers waiting for it notice the replacement and reopen.
+        let dir = index_path.parent().unwrap_or_else(|| Path::new("."));
+        let mut replacement = tempfile::NamedTempFile::new_in(dir)?;
+        replacement.write_all(rewritten.as_bytes())?;
+        replacement.as_file().sync_all()?;
+        replacement.persist(&index_path).map_err(|e| e.error)?;
+        Ok(())
+    })
+    .await
+    .map_err(io::Error::other)?
+}
+
+/// Opens the index file and locks it, exclusively for writing (creating it
+/// if needed) or shared for reading (returning `None` if it does not exist).
+/// Retries if the file was replaced while waiting for the lock.
+fn open_locked_index(index_path: &Path, exclusive: bool) -> io::Result<Option<File>> {
+    loop {
+        let file = if exclusive {
+            File::options()
+                .read(true)
+                .append(true)
+                .create(true)
+                .open(index_path)?
+        } else {
+            match File::open(index_path) {
+                Ok(file) => file,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+                Err(e) => return Err(e),
+            }
+        };
+        if exclusive {
+            file.lock()?;
+        } else {
+            file.lock_shared()?;
+        }
+        if is_current_file(&file, index_path)? {
+            return Ok(Some(file));
+        }
+    }
+}
+
+/// Builds a single record holding everything indexed for the rollout file at
+/// `path`.
+async fn index_rollout_file(path: &Path, relative: &Path) -> io::Result<IndexRecord> {
+    let text = read_rollout_text(path).await?;
+    let mut indexed = IndexRecord {
+        path: relative.to_path_buf(),
+        start: true,
+        ..Default::default()
+    };
+    let mut texts = Vec::new();
+    for line in text.lines() {
+        let Ok(rollout_line) = serde_json::from_str::<RolloutLine>(line) else {
+            continue;
+        };
+        let Some(record) = IndexRecord::from_item(relative, &rollout_line.item) else {
+            continue;
+        };
+        // Keep the metadata of the first session meta line; forked sessions
+        // repeat the meta of the one they were forked from.
+        if record.start && indexed.timestamp.is_none() {
+            indexed.timestamp = record.timestamp;
+            indexed.cwd = record.cwd;
+            indexed.git_branch = record.git_branch;
+        }
+        for model in record.models {
+            if !indexed.models.contains(&model) {
+                indexed.models.push(model);
+            }
+        }
+        if !record.text.is_empty() {
+            texts.push(record.text);
+        }
+    }
+    indexed.text = texts.join("\n");
+    Ok(indexed)
+}
+
+async fn append_records(index_path: &Path, records: &[IndexRecord]) -> io::Result<()> {
+    let mut buf = String::new();
+    for record in records {
+        buf.push_str(&serde_json::to_string(record)?);
+        buf.push('\n');
+    }
+    let index_path = index_path.to_path_buf();
+    tokio::task::spawn_blocking(move || -> io::Result<()> {
+        if let Some(mut file) = open_locked_index(&index_path, true)? {
+            file.write_all(buf.as_bytes())?;
+            file.flush()?;
+        }
+        Ok(())
+    })
+    .await
+    .map_err(io::Error::other)?
+}
@@ -14,6 +14,8 @@ use time::macros::format_description;
 use uuid::Uuid;
 
 use super::SESSIONS_SUBDIR;
+use super::compress::is_compressed_rollout;
+use super::compress::read_rollout_text;
 use crate::protocol::EventMsg;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::protocol::RolloutLine;
@@ -42,7 +44,8 @@ pub struct ConversationItem {
 
 /// Hard cap to bound worst‑case work per request.
 const MAX_SCAN_FILES: usize = 100;
-const HEAD_RECORD_LIMIT: usize = 10;
+/// Number of records read from the start of a rollout for its preview.
+pub(super) const HEAD_RECORD_LIMIT: usize = 10;
 
 /// Pagination cursor identifying a file by timestamp and UUID.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -55,6 +58,12 @@ impl Cursor {
     fn new(ts: OffsetDateTime, id: Uuid) -> Self {
         Self { ts, id }
     }
+
+    /// Whether a file with this timestamp and UUID comes after the cursor in
+    /// listing order.
+    pub(super) fn is_before(&self, ts: OffsetDateTime, id: Uuid) -> bool {
+        ts < self.ts || (ts == self.ts && id < self.id)
+    }
 }
 
 impl serde::Serialize for Cursor {
@@ -112,7 +121,7 @@ pub(crate) async fn get_conversations(
 /// Returns the entire file contents as a String.
 #[allow(dead_code)]
 pub(crate) async fn get_conversation(path: &Path) -> io::Result<String> {
-    tokio::fs::read_to_string(path).await
+    read_rollout_text(path).await
 }
 
 /// Load conversation file paths from disk using directory traversal.
@@ -149,7 +158,7 @@ async fn traverse_directories_for_paths(
                     break 'outer;
                 }
                 let mut day_files = collect_files(day_path, |name_str, path| {
-                    if !name_str.starts_with("rollout-") || !name_str.ends_with(".jsonl") {
+                    if !is_rollout_file_name(name_str) {
                         return None;
                     }
 
@@ -215,13 +224,39 @@ fn parse_cursor(token: &str) -> Option<Cursor> {
     Some(Cursor::new(ts, uuid))
 }
 
-fn build_next_cursor(items: &[ConversationItem]) -> Option<Cursor> {
+pub(super) fn build_next_cursor(items: &[ConversationItem]) -> Option<Cursor> {
     let last = items.last()?;
     let file_name = last.path.file_name()?.to_string_lossy();
     let (ts, id) = parse_timestamp_uuid_from_filename(&file_name)?;
     Some(Cursor::new(ts, id))
 }
 
+/// Collects the rollout files under the sessions directory `root` with the
+/// timestamp and UUID parsed from their names, in no particular order.
+pub(super) async fn collect_rollout_files(
+    root: &Path,
+) -> io::Result<Vec<(OffsetDateTime, Uuid, PathBuf)>> {
+    let mut files = Vec::new();
+    for (_year, year_path) in collect_dirs_desc(root, |s| s.parse::<u16>().ok()).await? {
+        for (_month, month_path) in collect_dirs_desc(&year_path, |s| s.parse::<u8>().ok()).await? {
+            for (_day, day_path) in collect_dirs_desc(&month_path, |s| s.parse::<u8>().ok()).await?
+            {
+                files.extend(
+                    collect_files(&day_path, |name_str, path| {
+                        if !is_rollout_file_name(name_str) {
+                            return None;
+                        }
+                        parse_timestamp_uuid_from_filename(name_str)
+                            .map(|(ts, id)| (ts, id, path.to_path_buf()))
+                    })
+                    .await?,
+                );
+            }
+        }
+    }
+    Ok(files)
+}
+
 /// Collects immediate subdirectories of `parent`, parses their (string) names with `parse`,
 /// and returns them sorted descending by the parsed key.
 async fn collect_dirs_desc<T, F>(parent: &Path, parse: F) -> io::Result<Vec<(T, PathBuf)>>
@@ -269,8 +304,15 @@ where
     Ok(collected)
 }
 
-fn parse_timestamp_uuid_from_filename(name: &str) -> Option<(OffsetDateTime, Uuid)> {
-    // Expected: rollout-YYYY-MM-DDThh-mm-ss-<uuid>.jsonl
+/// Whether `name` is the name of a rollout file, compressed or not.
+pub(super) fn is_rollout_file_name(name: &str) -> bool {
+    name.starts_with("rollout-") && (name.ends_with(".jsonl") || name.ends_with(".jsonl.gz"))
+}
+
+pub(super) fn parse_timestamp_uuid_from_filename(name: &str) -> Option<(OffsetDateTime, Uuid)> {
+    // Expected: rollout-YYYY-MM-DDThh-mm-ss-<uuid>.jsonl, possibly followed
+    // by .gz
+    let name = name.strip_suffix(".gz").unwrap_or(name);
     let core = name.strip_prefix("rollout-")?.strip_suffix(".jsonl")?;
 
     // Scan from the right for a '-' such that the suffix parses as a UUID.
@@ -286,14 +328,19 @@ fn parse_timestamp_uuid_from_filename(name: &str) -> Option<(OffsetDateTime, Uui
     Some((ts, uuid))
 }
 
-async fn read_head_and_flags(
+pub(super) async fn read_head_and_flags(
     path: &Path,
     max_records: usize,
 ) -> io::Result<(Vec<serde_json::Value>, bool, bool)> {
     use tokio::io::AsyncBufReadExt;
 
-    let file = tokio::fs::File::open(path).await?;
-    let reader = tokio::io::BufReader::new(file);
+    let reader: Box<dyn tokio::io::AsyncBufRead + Send + Unpin> = if is_compressed_rollout(path) {
+        Box::new(io::Cursor::new(read_rollout_text(path).await?.into_bytes()))
+    } else {
+        Box::new(tokio::io::BufReader::new(
+            tokio::fs::File::open(path).await?,
+        ))
+    };
     let mut lines = reader.lines();
     let mut head: Vec<serde_json::Value> = Vec::new();
     let mut saw_session_meta = false;
@@ -3,11 +3,17 @@
 pub const SESSIONS_SUBDIR: &str = "sessions";
 pub const ARCHIVED_SESSIONS_SUBDIR: &str = "archived_sessions";
 
+pub mod compress;
+pub mod export;
+pub mod index;
 pub mod list;
+pub mod patch_diff;
 pub(crate) mod policy;
 pub mod recorder;
+pub mod retention;
 
 pub use codex_protocol::protocol::SessionMeta;
+pub use compress::read_rollout_text;
 pub use list::find_conversation_path_by_id_str;
 pub use recorder::RolloutRecorder;
 pub use recorder::RolloutRecorderParams;
@@ -0,0 +1,242 @@
+//! Diff of the edits Codex made, rebuilt from a recorded session.
+//!
+//! Rollouts keep the `apply_patch` calls and their outputs but not the edited
+//! files, so [`AppliedPatchDiff`] renders each patch that applied successfully
+//! as a git-style diff. Hunks are headed by the patch's context line instead
+//! of line numbers.
+
+use std::collections::HashMap;
+
+use codex_apply_patch::ApplyPatchArgs;
+use codex_apply_patch::Hunk;
+use codex_apply_patch::MaybeApplyPatch;
+use codex_apply_patch::maybe_parse_apply_patch;
+use codex_apply_patch::parse_patch;
+use codex_protocol::models::FunctionCallOutputPayload;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use serde_json::Value;
+use similar::ChangeTag;
+use similar::TextDiff;
+
+/// Collects the patches applied by the response items it is fed, in order. A
+/// patch counts once the output of its call reports a zero exit code.
+#[derive(Debug, Default)]
+pub struct AppliedPatchDiff {
+    /// Patches of calls waiting for their output, by call id.
+    pending: HashMap<String, Vec<Hunk>>,
+    diff: String,
+}
+
+impl AppliedPatchDiff {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records an `apply_patch` call, or the output telling whether its patch
+    /// was applied. Other items are ignored.
+    pub fn record(&mut self, item: &ResponseItem) {
+        let (call_id, patch) = match item {
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                ..
+            } => (call_id, function_call_patch(name, arguments)),
+            ResponseItem::CustomToolCall {
+                name,
+                input,
+                call_id,
+                ..
+            } if name == "apply_patch" => (call_id, parse_patch(input).ok()),
+            ResponseItem::LocalShellCall {
+                call_id: Some(call_id),
+                action: LocalShellAction::Exec(exec),
+                ..
+            } => (call_id, shell_patch(&exec.command)),
+            ResponseItem::FunctionCallOutput {
+                call_id,
+                output: FunctionCallOutputPayload { content, .. },
+            }
+            | ResponseItem::CustomToolCallOutput {
+                call_id,
+                output: content,
+            } => {
+                if let Some(hunks) = self.pending.remove(call_id)
+                    && patch_applied(content)
+                {
+                    push_hunks(&hunks, &mut self.diff);
+                }
+                return;
+            }
+            _ => return,
+        };
+        if let Some(ApplyPatchArgs { hunks, .. }) = patch {
+            self.pending.insert(call_id.clone(), hunks);
+        }
+    }
+
+    /// Takes the diff of the patches applied since the last call.
+    pub fn take_diff(&mut self) -> String {
+        std::mem::take(&mut self.diff)
+    }
+}
+
+/// The patch of an `apply_patch` function call, or of a shell call running
+/// `apply_patch`.
+fn function_call_patch(name: &str, arguments: &str) -> Option<ApplyPatchArgs> {
+    let arguments: Value = serde_json::from_str(arguments).ok()?;
+    if name == "apply_patch" {
+        return parse_patch(arguments.get("input")?.as_str()?).ok();
+    }
+    let command: Vec<String> = serde_json::from_value(arguments.get("command")?.clone()).ok()?;
+    shell_patch(&command)
+}
+
+fn shell_patch(command: &[String]) -> Option<ApplyPatchArgs> {
+    match maybe_parse_apply_patch(command) {
+        MaybeApplyPatch::Body(args) => Some(args),
+        _ => None,
+    }
+}
+
+/// Patches are applied as commands, whose output carries the exit code.
+fn patch_applied(output: &str) -> bool {
+    serde_json::from_str::<Value>(output)
+        .is_ok_and(|output| output["metadata"]["exit_code"].as_i64() == Some(0))
+}
+
+fn push_hunks(hunks: &[Hunk], out: &mut String) {
+    for hunk in hunks {
+        match hunk {
+            Hunk::AddFile { path, contents } => {
+                let path = path.display();
+                out.push_str(&format!(
+                    "diff --git a/{path} b/{path}\nnew file mode 100644\n\
+                     --- /dev/null\n+++ b/{path}\n"
+                ));
+                out.push_str(&format!("@@ -0,0 +1,{} @@\n", contents.lines().count()));
+                for line in contents.lines() {
+                    out.push_str(&format!("+{line}\n"));
+                }
+            }
+            Hunk::DeleteFile { path } => {
+                let path = path.display();
+                out.push_str(&format!(
+                    "diff --git a/{path} b/{path}\ndeleted file mode 100644\n\
+                     --- a/{path}\n+++ /dev/null\n"
+                ));
+            }
+            Hunk::UpdateFile {
+                path,
+                move_path,
+                chunks,
+            } => {
+                let new_path = move_path.as_ref().unwrap_or(path).display();
+                let path = path.display();
+                out.push_str(&format!(
+                    "diff --git a/{path} b/{new_path}\n--- a/{path}\n+++ b/{new_path}\n"
+                ));
+                for chunk in chunks {
+                    match &chunk.change_context {
+                        Some(context) => out.push_str(&format!("@@ {context}\n")),
+                        None => out.push_str("@@\n"),
+                    }
+                    let old_lines: Vec<&str> = chunk.old_lines.iter().map(String::as_str).collect();
+                    let new_lines: Vec<&str> = chunk.new_lines.iter().map(String::as_str).collect();
+                    for change in TextDiff::from_slices(&old_lines, &new_lines).iter_all_changes() {
+                        let sign = match change.tag() {
+                            ChangeTag::Equal => ' ',
+                            ChangeTag::Delete => '-',
+                            ChangeTag::Insert => '+',
+                        };
+                        out.push(sign);
+                        out.push_str(change.value());
+                        out.push('\n');
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::ContentItem;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn apply_patch_call(call_id: &str, patch: &str) -> ResponseItem {
+        ResponseItem::FunctionCall {
+            id: None,
+            name: "apply_patch".to_string(),
+            arguments: json!({ "input": patch }).to_string(),
+            call_id: call_id.to_string(),
+            thought_signature: None,
+        }
+    }
+
+    fn exec_output(call_id: &str, exit_code: i32) -> ResponseItem {
+        ResponseItem::FunctionCallOutput {
+            call_id: call_id.to_string(),
+            output: FunctionCallOutputPayload {
+                content: json!({
+                    "output": "",
+                    "metadata": { "exit_code": exit_code, "duration_seconds": 0.1 },
+                })
+                .to_string(),
+                success: None,
+            },
+        }
+    }
+
+    #[test]
+    fn renders_the_applied_patches() {
+        let items = vec![
+            apply_patch_call(
+                "call-1",
+                "*** Begin Patch\n*** Update File: src/lib.rs\n@@ fn main() {\n\
+                 -    old();\n+    new();\n     done();\n*** End Patch",
+            ),
+            exec_output("call-1", 0),
+            apply_patch_call(
+                "call-2",
+                "*** Begin Patch\n*** Delete File: missing.rs\n*** End Patch",
+            ),
+            exec_output("call-2", 1),
+            ResponseItem::CustomToolCall {
+                id: None,
+                status: None,
+                call_id: "call-3".to_string(),
+                name: "apply_patch".to_string(),
+                input: "*** Begin Patch\n*** Add File: notes.md\n+hello\n*** End Patch".to_string(),
+            },
+            ResponseItem::CustomToolCallOutput {
+                call_id: "call-3".to_string(),
+                output: json!({ "output": "", "metadata": { "exit_code": 0 } }).to_string(),
+            },
+            ResponseItem::Message {
+                id: None,
+                role: "assistant".to_string(),
+                content: vec![ContentItem::OutputText {
+                    text: "done".to_string(),
+                }],
+            },
+        ];
+
+        let mut diff = AppliedPatchDiff::new();
+        for item in &items {
+            diff.record(item);
+        }
+        assert_eq!(
+            diff.take_diff(),
+            "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n\
+             @@ fn main() {\n-    old();\n+    new();\n     done();\n\
+             diff --git a/notes.md b/notes.md\nnew file mode 100644\n\
+             --- /dev/null\n+++ b/notes.md\n\
+             @@ -0,0 +1,1 @@\n+hello\n"
+        );
+        assert_eq!(diff.take_diff(), "");
+    }
+}
@@ -53,11 +53,17 @@ pub(crate) fn should_persist_event_msg(ev: &EventMsg) -> bool {
         | EventMsg::McpToolCallEnd(_)
         | EventMsg::WebSearchBegin(_)
         | EventMsg::WebSearchEnd(_)
+        | EventMsg::DelegateBegin(_)
+        | EventMsg::DelegateEvent(_)
+        | EventMsg::DelegateEnd(_)
+        | EventMsg::WorktreeResolved(_)
+        | EventMsg::CheckpointRestored(_)
         | EventMsg::ExecCommandBegin(_)
         | EventMsg::ExecCommandOutputDelta(_)
         | EventMsg::ExecCommandEnd(_)
         | EventMsg::ExecApprovalRequest(_)
         | EventMsg::ApplyPatchApprovalRequest(_)
+        | EventMsg::McpToolCallApprovalRequest(_)
         | EventMsg::BackgroundEvent(_)
         | EventMsg::StreamError(_)
         | EventMsg::PatchApplyBegin(_)
@@ -65,9 +71,12 @@ pub(crate) fn should_persist_event_msg(ev: &EventMsg) -> bool {
         | EventMsg::TurnDiff(_)
         | EventMsg::GetHistoryEntryResponse(_)
         | EventMsg::McpListToolsResponse(_)
+        | EventMsg::McpListResourcesResponse(_)
         | EventMsg::ListCustomPromptsResponse(_)
+        | EventMsg::GetMcpPromptResponse(_)
         | EventMsg::PlanUpdate(_)
         | EventMsg::TurnAborted(_)
+        | EventMsg::InputQueueUpdated(_)
         | EventMsg::ShutdownComplete
         | EventMsg::ConversationPath(_) => false,
     }
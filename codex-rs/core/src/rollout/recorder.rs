@@ -21,10 +21,19 @@ use tracing::info;
 use tracing::warn;
 
 use super::SESSIONS_SUBDIR;
+use super::compress::compressed_rollout_path;
+use super::compress::decompress_rollout;
+use super::compress::is_compressed_rollout;
+use super::compress::read_rollout_text;
+use super::index::SessionIndexWriter;
+use super::index::SessionSearch;
+use super::index::search_conversations;
 use super::list::ConversationsPage;
 use super::list::Cursor;
 use super::list::get_conversations;
 use super::policy::is_persisted_response_item;
+use super::retention::RolloutLock;
+use super::retention::prune_sessions;
 use crate::config::Config;
 use crate::default_client::ORIGINATOR;
 use crate::git_info::collect_git_info;
@@ -109,11 +118,22 @@ impl RolloutRecorder {
         get_conversations(codex_home, page_size, cursor).await
     }
 
+    /// Search the conversations under the provided Codex home directory with
+    /// the session index, which is brought up to date first.
+    pub async fn search_conversations(
+        codex_home: &Path,
+        search: &SessionSearch,
+        page_size: usize,
+        cursor: Option<&Cursor>,
+    ) -> std::io::Result<ConversationsPage> {
+        search_conversations(codex_home, search, page_size, cursor).await
+    }
+
     /// Attempt to create a new [`RolloutRecorder`]. If the sessions directory
     /// cannot be created or the rollout file cannot be opened we return the
     /// error so the caller can decide whether to disable persistence.
     pub async fn new(config: &Config, params: RolloutRecorderParams) -> std::io::Result<Self> {
-        let (file, rollout_path, meta) = match params {
+        let (file, rollout_path, meta, lock) = match params {
             RolloutRecorderParams::Create {
                 conversation_id,
                 instructions,
@@ -124,6 +144,19 @@ impl RolloutRecorder {
                     conversation_id: session_id,
                     timestamp,
                 } = create_log_file(config, conversation_id)?;
+                let lock = RolloutLock::acquire(&path).await?;
+
+                // Apply the retention policy in the background whenever a new
+                // session starts.
+                if config.sessions.is_enabled() {
+                    let codex_home = config.codex_home.clone();
+                    let retention = config.sessions.clone();
+                    tokio::task::spawn(async move {
+                        if let Err(e) = prune_sessions(&codex_home, &retention, false).await {
+                            warn!("failed to apply the session retention policy: {e}");
+                        }
+                    });
+                }
 
                 let timestamp_format: &[FormatItem] = format_description!(
                     "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z"
@@ -144,20 +177,42 @@ impl RolloutRecorder {
                         cli_version: env!("CARGO_PKG_VERSION").to_string(),
                         instructions,
                     }),
+                    lock,
+                )
+            }
+            RolloutRecorderParams::Resume { path } => {
+                // Taken first so the retention policy cannot delete or
+                // compress the rollout while it is being reopened.
+                let lock = RolloutLock::acquire(&path).await?;
+                // New items are appended to the rollout, which cannot be done
+                // to a compressed one. The rollout may also have been
+                // compressed since `path` was listed.
+                let uncompressed_path = if is_compressed_rollout(&path) {
+                    path.with_extension("")
+                } else {
+                    path.clone()
+                };
+                let compressed_path = compressed_rollout_path(&uncompressed_path);
+                let path = if !uncompressed_path.exists() && compressed_path.exists() {
+                    decompress_rollout(&compressed_path).await?
+                } else {
+                    uncompressed_path
+                };
+                (
+                    tokio::fs::OpenOptions::new()
+                        .append(true)
+                        .open(&path)
+                        .await?,
+                    path,
+                    None,
+                    lock,
                 )
             }
-            RolloutRecorderParams::Resume { path } => (
-                tokio::fs::OpenOptions::new()
-                    .append(true)
-                    .open(&path)
-                    .await?,
-                path,
-                None,
-            ),
         };
 
         // Clone the cwd for the spawned task to collect git info asynchronously
         let cwd = config.cwd.clone();
+        let index = SessionIndexWriter::new(&config.codex_home, &rollout_path);
 
         // A reasonably-sized bounded channel. If the buffer fills up the send
         // future will yield, which is fine – we only need to ensure we do not
@@ -167,7 +222,7 @@ impl RolloutRecorder {
         // Spawn a Tokio task that owns the file handle and performs async
         // writes. Using `tokio::fs::File` keeps everything on the async I/O
         // driver instead of blocking the runtime.
-        tokio::task::spawn(rollout_writer(file, rx, meta, cwd));
+        tokio::task::spawn(rollout_writer(file, lock, rx, meta, cwd, index));
 
         Ok(Self { tx, rollout_path })
     }
@@ -204,7 +259,7 @@ impl RolloutRecorder {
 
     pub(crate) async fn get_rollout_history(path: &Path) -> std::io::Result<InitialHistory> {
         info!("Resuming rollout from {path:?}");
-        let text = tokio::fs::read_to_string(path).await?;
+        let text = read_rollout_text(path).await?;
         if text.trim().is_empty() {
             return Err(IoError::other("empty session file"));
         }
@@ -347,11 +402,17 @@ fn create_log_file(
 
 async fn rollout_writer(
     file: tokio::fs::File,
+    lock: RolloutLock,
     mut rx: mpsc::Receiver<RolloutCmd>,
     mut meta: Option<SessionMeta>,
     cwd: std::path::PathBuf,
+    index: Option<SessionIndexWriter>,
 ) -> std::io::Result<()> {
-    let mut writer = JsonlWriter { file };
+    let mut writer = JsonlWriter {
+        file,
+        _lock: lock,
+        index,
+    };
 
     // If we have a meta, collect git info asynchronously and write meta first
     if let Some(session_meta) = meta.take() {
@@ -396,6 +457,9 @@ async fn rollout_writer(
 
 struct JsonlWriter {
     file: tokio::fs::File,
+    /// Keeps the retention policy away from the rollout while it is written.
+    _lock: RolloutLock,
+    index: Option<SessionIndexWriter>,
 }
 
 impl JsonlWriter {
@@ -411,7 +475,11 @@ impl JsonlWriter {
             timestamp,
             item: rollout_item,
         };
-        self.write_line(&line).await
+        self.write_line(&line).await?;
+        if let Some(index) = &mut self.index {
+            index.record(&line.item).await;
+        }
+        Ok(())
     }
     async fn write_line(&mut self, item: &impl serde::Serialize) -> std::io::Result<()> {
         let mut json = serde_json::to_string(item)?;
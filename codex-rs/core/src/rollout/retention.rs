@@ -0,0 +1,411 @@
+//! Retention policy for recorded sessions.
+//!
+//! The `[sessions]` table of `config.toml` bounds how long rollouts are kept
+//! and how much space they take, and may compress old ones. The policy goes by
+//! the time a rollout was last written to, so sessions that were resumed
+//! recently are treated as recent.
+//!
+//! Rollouts that a running Codex process may still append to are never
+//! touched: those written to in the last few minutes, and those whose
+//! recorder holds a [`RolloutLock`]. The lock is a shared lock on a
+//! `<rollout>.lock` file next to the rollout rather than on the rollout
+//! itself, because file locks are mandatory on Windows and would keep other
+//! processes from reading it.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::fs::TryLockError;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use tracing::warn;
+
+use super::ARCHIVED_SESSIONS_SUBDIR;
+use super::SESSIONS_SUBDIR;
+use super::compress::compress_rollout;
+use super::compress::is_compressed_rollout;
+use super::index::update_index_paths;
+use super::list::collect_rollout_files;
+use super::list::is_rollout_file_name;
+use crate::config_types::SessionRetention;
+use crate::message_history::is_current_file;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Rollouts written to more recently than this are left alone, in case their
+/// session is still running.
+const ACTIVE_ROLLOUT_GRACE: Duration = Duration::from_secs(10 * 60);
+
+/// Extension added to the name of a rollout file to name its lock file.
+const LOCK_FILE_EXTENSION: &str = "lock";
+
+/// What [`prune_sessions`] did or, for a dry run, would do.
+#[derive(Debug, Default, PartialEq)]
+pub struct PruneReport {
+    /// Rollout files deleted.
+    pub deleted: Vec<PathBuf>,
+    /// Size of the deleted files in bytes.
+    pub deleted_bytes: u64,
+    /// Rollout files compressed, by their path before compression.
+    pub compressed: Vec<PathBuf>,
+}
+
+struct RolloutFile {
+    path: PathBuf,
+    modified: SystemTime,
+    size: u64,
+    archived: bool,
+}
+
+/// Applies `retention` to the rollouts in `codex_home`: deletes those not
+/// written to in `max_age_days`, then the least recently written ones until
+/// all of them fit in `max_total_bytes`, and finally compresses those not
+/// written to in `compress_after_days`. With `dry_run`, only reports what
+/// would be done.
+pub async fn prune_sessions(
+    codex_home: &Path,
+    retention: &SessionRetention,
+    dry_run: bool,
+) -> io::Result<PruneReport> {
+    let sessions_root = codex_home.join(SESSIONS_SUBDIR);
+    let mut files = collect_files(codex_home).await?;
+    // Least recently written first, which is the order they are deleted in.
+    files.sort_by_key(|file| file.modified);
+
+    let now = SystemTime::now();
+    let older_than = |file: &RolloutFile, age: Duration| {
+        now.duration_since(file.modified)
+            .is_ok_and(|file_age| file_age > age)
+    };
+    let older_than_days = |file: &RolloutFile, days: u64| {
+        older_than(
+            file,
+            Duration::from_secs(days.saturating_mul(SECONDS_PER_DAY)),
+        )
+    };
+    let idle = |file: &RolloutFile| older_than(file, ACTIVE_ROLLOUT_GRACE);
+
+    let counted = |file: &RolloutFile| !(file.archived && retention.keep_archived);
+    let deletable = |file: &RolloutFile| counted(file) && idle(file);
+    let mut delete: Vec<bool> = files
+        .iter()
+        .map(|file| {
+            deletable(file)
+                && retention
+                    .max_age_days
+                    .is_some_and(|days| older_than_days(file, days))
+        })
+        .collect();
+    if let Some(max_total_bytes) = retention.max_total_bytes {
+        let mut total: u64 = files
+            .iter()
+            .zip(&delete)
+            .filter(|(file, deleted)| counted(file) && !**deleted)
+            .map(|(file, _)| file.size)
+            .sum();
+        for (file, deleted) in files.iter().zip(delete.iter_mut()) {
+            if total <= max_total_bytes {
+                break;
+            }
+            if deletable(file) && !*deleted {
+                *deleted = true;
+                total -= file.size;
+            }
+        }
+    }
+
+    let mut report = PruneReport::default();
+    let mut moved: HashMap<PathBuf, Option<PathBuf>> = HashMap::new();
+    for (file, deleted) in files.iter().zip(delete) {
+        let compress = !deleted
+            && retention.compress_after_days.is_some_and(|days| {
+                !is_compressed_rollout(&file.path) && idle(file) && older_than_days(file, days)
+            });
+        if !deleted && !compress {
+            continue;
+        }
+        // Held until the file has been deleted or compressed, so a session
+        // resumed meanwhile waits for it. A dry run changes nothing, so it
+        // only checks for a recorder without creating a lock file.
+        let _lock = if dry_run {
+            match is_rollout_locked(&file.path) {
+                Ok(false) => None,
+                Ok(true) => continue,
+                Err(e) => {
+                    warn!("failed to check the lock of {:?}: {e}", file.path);
+                    continue;
+                }
+            }
+        } else {
+            match lock_idle_rollout(&file.path) {
+                Ok(Some(lock)) => Some(lock),
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!("failed to lock {:?}: {e}", file.path);
+                    continue;
+                }
+            }
+        };
+        if deleted {
+            if !dry_run && let Err(e) = remove_rollout(&sessions_root, &file.path).await {
+                warn!("failed to delete {:?}: {e}", file.path);
+                continue;
+            }
+            moved.insert(file.path.clone(), None);
+            report.deleted.push(file.path.clone());
+            report.deleted_bytes += file.size;
+        } else {
+            if !dry_run {
+                match compress_rollout(&file.path).await {
+                    Ok(compressed_path) => {
+                        moved.insert(file.path.clone(), Some(compressed_path));
+                    }
+                    Err(e) => {
+                        warn!("failed to compress {:?}: {e}", file.path);
+                        continue;
+                    }
+                }
+            }
+            report.compressed.push(file.path.clone());
+        }
+    }
+    if !dry_run && let Err(e) = update_index_paths(codex_home, moved).await {
+        warn!("failed to update the session index: {e}");
+    }
+    Ok(report)
+}
+
+/// Lock a recorder holds on the rollout it writes to, so [`prune_sessions`]
+/// leaves the rollout alone. Released, and its lock file removed, when
+/// dropped.
+pub(crate) struct RolloutLock {
+    file: File,
+    path: PathBuf,
+}
+
+impl RolloutLock {
+    /// Takes a shared lock for the rollout file at `rollout_path`, compressed
+    /// or not, waiting for a prune that is deleting or compressing it to
+    /// finish.
+    pub(crate) async fn acquire(rollout_path: &Path) -> io::Result<Self> {
+        let path = lock_file_path(rollout_path);
+        tokio::task::spawn_blocking(move || {
+            loop {
+                let file = open_lock_file(&path)?;
+                file.lock_shared()?;
+                // The holder we waited for may have removed the lock file.
+                if is_current_file(&file, &path)? {
+                    return Ok(Self { file, path });
+                }
+            }
+        })
+        .await
+        .map_err(io::Error::other)?
+    }
+}
+
+impl Drop for RolloutLock {
+    fn drop(&mut self) {
+        // Remove the lock file unless someone else, such as a second recorder
+        // of the same rollout, holds it too.
+        let _ = self.file.unlock();
+        if self.file.try_lock().is_ok() && is_current_file(&self.file, &self.path).unwrap_or(false)
+        {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Takes an exclusive lock for the rollout file at `rollout_path`, or returns
+/// `None` if a recorder holds it.
+fn lock_idle_rollout(rollout_path: &Path) -> io::Result<Option<RolloutLock>> {
+    let path = lock_file_path(rollout_path);
+    let file = open_lock_file(&path)?;
+    match file.try_lock() {
+        // A lock file that was replaced meanwhile may be held by a recorder
+        // under its new inode; leave the rollout for another time.
+        Ok(()) if is_current_file(&file, &path)? => Ok(Some(RolloutLock { file, path })),
+        Ok(()) | Err(TryLockError::WouldBlock) => Ok(None),
+        Err(TryLockError::Error(e)) => Err(e),
+    }
+}
+
+/// Whether a recorder holds the lock of the rollout file at `rollout_path`.
+/// Unlike [`lock_idle_rollout`], never creates the lock file.
+fn is_rollout_locked(rollout_path: &Path) -> io::Result<bool> {
+    let file = match File::open(lock_file_path(rollout_path)) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e),
+    };
+    match file.try_lock() {
+        Ok(()) => Ok(false),
+        Err(TryLockError::WouldBlock) => Ok(true),
+        Err(TryLockError::Error(e)) => Err(e),
+    }
+}
+
+fn open_lock_file(path: &Path) -> io::Result<File> {
+    File::options()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+}
+
+/// The lock file of a rollout is shared by its compressed and uncompressed
+/// forms, so compressing one cannot race with resuming it.
+fn lock_file_path(rollout_path: &Path) -> PathBuf {
+    let rollout_path = if is_compressed_rollout(rollout_path) {
+        rollout_path.with_extension("")
+    } else {
+        rollout_path.to_path_buf()
+    };
+    let mut name = rollout_path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{LOCK_FILE_EXTENSION}"));
+    rollout_path.with_file_name(name)
+}
+
+/// Whether `path` is a rollout file in the sessions or archived sessions
+/// directory of `codex_home`.
+pub fn is_recorded_rollout(codex_home: &Path, path: &Path) -> bool {
+    path.is_file() && recorded_rollout_path(codex_home, path).is_ok()
+}
+
+/// Returns `path`, which may be relative or go through symlinks, as a path
+/// in the sessions or archived sessions directory of `codex_home`, or fails
+/// if it is not a rollout file in either.
+fn recorded_rollout_path(codex_home: &Path, path: &Path) -> io::Result<PathBuf> {
+    let not_recorded = || {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is not a recorded session", path.display()),
+        )
+    };
+    let is_rollout_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(is_rollout_file_name);
+    if !is_rollout_name {
+        return Err(not_recorded());
+    }
+    let canonical = path.canonicalize()?;
+    for subdir in [SESSIONS_SUBDIR, ARCHIVED_SESSIONS_SUBDIR] {
+        let root = codex_home.join(subdir);
+        if let Ok(canonical_root) = root.canonicalize()
+            && let Ok(relative) = canonical.strip_prefix(&canonical_root)
+        {
+            return Ok(root.join(relative));
+        }
+    }
+    Err(not_recorded())
+}
+
+/// Like [`lock_idle_rollout`], but fails if a recorder holds the lock.
+fn lock_unused_rollout(path: &Path) -> io::Result<RolloutLock> {
+    lock_idle_rollout(path)?.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::ResourceBusy,
+            format!("{} is in use by a running session", path.display()),
+        )
+    })
+}
+
+/// Moves the rollout file at `path` into the archived sessions directory and
+/// returns its new path. Fails if its session is running.
+pub async fn archive_rollout(codex_home: &Path, path: &Path) -> io::Result<PathBuf> {
+    let path = &recorded_rollout_path(codex_home, path)?;
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::other(format!("{} has no file name", path.display())))?;
+    let archive_folder = codex_home.join(ARCHIVED_SESSIONS_SUBDIR);
+    tokio::fs::create_dir_all(&archive_folder).await?;
+    let archived_path = archive_folder.join(file_name);
+    let _lock = lock_unused_rollout(path)?;
+    tokio::fs::rename(path, &archived_path).await?;
+    let _ = tokio::fs::remove_file(lock_file_path(path)).await;
+    remove_empty_parents(&codex_home.join(SESSIONS_SUBDIR), path).await;
+    // Archived sessions are not searched.
+    update_index_paths(codex_home, HashMap::from([(path.to_path_buf(), None)])).await?;
+    Ok(archived_path)
+}
+
+/// Deletes the rollout file at `path`, which must be in the sessions or
+/// archived sessions directory of `codex_home`. Fails if its session is
+/// running.
+pub async fn delete_rollout(codex_home: &Path, path: &Path) -> io::Result<()> {
+    let path = &recorded_rollout_path(codex_home, path)?;
+    let _lock = lock_unused_rollout(path)?;
+    remove_rollout(&codex_home.join(SESSIONS_SUBDIR), path).await?;
+    update_index_paths(codex_home, HashMap::from([(path.to_path_buf(), None)])).await
+}
+
+/// Deletes the rollout file at `path` and its lock file, which the caller
+/// holds, along with the date directories of the sessions directory it
+/// leaves empty.
+async fn remove_rollout(sessions_root: &Path, path: &Path) -> io::Result<()> {
+    tokio::fs::remove_file(path).await?;
+    let _ = tokio::fs::remove_file(lock_file_path(path)).await;
+    remove_empty_parents(sessions_root, path).await;
+    Ok(())
+}
+
+/// Removes the directories between `path` and `sessions_root` that are empty.
+async fn remove_empty_parents(sessions_root: &Path, path: &Path) {
+    let mut dir = path.parent();
+    while let Some(current) = dir {
+        if current == sessions_root || !current.starts_with(sessions_root) {
+            break;
+        }
+        // Fails, and stops, on the first directory that is not empty.
+        if tokio::fs::remove_dir(current).await.is_err() {
+            break;
+        }
+        dir = current.parent();
+    }
+}
+
+async fn collect_files(codex_home: &Path) -> io::Result<Vec<RolloutFile>> {
+    let mut paths: Vec<(PathBuf, bool)> = Vec::new();
+    let sessions_root = codex_home.join(SESSIONS_SUBDIR);
+    if sessions_root.exists() {
+        paths.extend(
+            collect_rollout_files(&sessions_root)
+                .await?
+                .into_iter()
+                .map(|(_, _, path)| (path, false)),
+        );
+    }
+    match tokio::fs::read_dir(codex_home.join(ARCHIVED_SESSIONS_SUBDIR)).await {
+        Ok(mut dir) => {
+            while let Some(entry) = dir.next_entry().await? {
+                if entry.file_name().to_str().is_some_and(is_rollout_file_name) {
+                    paths.push((entry.path(), true));
+                }
+            }
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+
+    let mut files = Vec::with_capacity(paths.len());
+    for (path, archived) in paths {
+        let metadata = match tokio::fs::metadata(&path).await {
+            Ok(metadata) => metadata,
+            // Another process may have removed it meanwhile.
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        files.push(RolloutFile {
+            path,
+            modified: metadata.modified()?,
+            size: metadata.len(),
+            archived,
+        });
+    }
+    Ok(files)
+}
@@ -4,6 +4,9 @@ use std::fs::File;
 use std::fs::{self};
 use std::io::Write;
 use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
 
 use tempfile::TempDir;
 use time::OffsetDateTime;
@@ -12,11 +15,36 @@ use time::format_description::FormatItem;
 use time::macros::format_description;
 use uuid::Uuid;
 
+use crate::config_types::SessionRetention;
+use crate::protocol::AgentMessageEvent;
+use crate::protocol::AskForApproval;
+use crate::protocol::EventMsg;
+use crate::protocol::InputMessageKind;
+use crate::protocol::SandboxPolicy;
+use crate::protocol::UserMessageEvent;
+use crate::rollout::compress::compress_rollout;
+use crate::rollout::compress::read_rollout_text;
+use crate::rollout::index::SESSION_INDEX_FILE;
+use crate::rollout::index::SessionIndexWriter;
+use crate::rollout::index::SessionSearch;
+use crate::rollout::index::search_conversations;
 use crate::rollout::list::ConversationItem;
 use crate::rollout::list::ConversationsPage;
 use crate::rollout::list::Cursor;
 use crate::rollout::list::get_conversation;
 use crate::rollout::list::get_conversations;
+use crate::rollout::retention::PruneReport;
+use crate::rollout::retention::RolloutLock;
+use crate::rollout::retention::delete_rollout;
+use crate::rollout::retention::prune_sessions;
+use codex_protocol::config_types::ReasoningSummary;
+use codex_protocol::mcp_protocol::ConversationId;
+use codex_protocol::protocol::GitInfo;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use codex_protocol::protocol::SessionMeta;
+use codex_protocol::protocol::SessionMetaLine;
+use codex_protocol::protocol::TurnContextItem;
 
 fn write_session_file(
     root: &Path,
@@ -443,3 +471,544 @@ async fn test_stable_ordering_same_second_pagination() {
     };
     assert_eq!(page2, expected_page2);
 }
+
+struct SearchableSession<'a> {
+    ts_str: &'a str,
+    uuid: Uuid,
+    cwd: &'a str,
+    branch: &'a str,
+    model: &'a str,
+    user_message: &'a str,
+    agent_message: &'a str,
+}
+
+fn write_searchable_session_file(root: &Path, session: &SearchableSession) -> PathBuf {
+    let format: &[FormatItem] =
+        format_description!("[year]-[month]-[day]T[hour]-[minute]-[second]");
+    let dt = PrimitiveDateTime::parse(session.ts_str, format)
+        .unwrap()
+        .assume_utc();
+    let dir = root
+        .join("sessions")
+        .join(format!("{:04}", dt.year()))
+        .join(format!("{:02}", u8::from(dt.month())))
+        .join(format!("{:02}", dt.day()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("rollout-{}-{}.jsonl", session.ts_str, session.uuid));
+
+    let timestamp = dt
+        .format(&time::format_description::well_known::Rfc3339)
+        .unwrap();
+    let items = [
+        RolloutItem::SessionMeta(SessionMetaLine {
+            meta: SessionMeta {
+                id: ConversationId::from(session.uuid),
+                timestamp: timestamp.clone(),
+                cwd: PathBuf::from(session.cwd),
+                ..Default::default()
+            },
+            git: Some(GitInfo {
+                commit_hash: None,
+                branch: Some(session.branch.to_string()),
+                repository_url: None,
+            }),
+        }),
+        RolloutItem::TurnContext(TurnContextItem {
+            cwd: PathBuf::from(session.cwd),
+            approval_policy: AskForApproval::OnRequest,
+            sandbox_policy: SandboxPolicy::ReadOnly,
+            model: session.model.to_string(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        }),
+        RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+            message: session.user_message.to_string(),
+            kind: Some(InputMessageKind::Plain),
+            images: None,
+        })),
+        RolloutItem::EventMsg(EventMsg::AgentMessage(AgentMessageEvent {
+            message: session.agent_message.to_string(),
+        })),
+    ];
+    let mut file = File::create(&path).unwrap();
+    for item in items {
+        let line = RolloutLine {
+            timestamp: timestamp.clone(),
+            item,
+        };
+        writeln!(file, "{}", serde_json::to_string(&line).unwrap()).unwrap();
+    }
+    path
+}
+
+async fn search_paths(home: &Path, search: &SessionSearch) -> Vec<PathBuf> {
+    search_conversations(home, search, 10, None)
+        .await
+        .unwrap()
+        .items
+        .into_iter()
+        .map(|item| item.path)
+        .collect()
+}
+
+#[tokio::test]
+async fn test_search_conversations_filters() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+
+    let parser = write_searchable_session_file(
+        home,
+        &SearchableSession {
+            ts_str: "2025-01-01T12-00-00",
+            uuid: Uuid::from_u128(1),
+            cwd: "/work/parser",
+            branch: "main",
+            model: "o3",
+            user_message: "Fix the Tokenizer panic",
+            agent_message: "The lexer now handles empty input.",
+        },
+    );
+    let docs = write_searchable_session_file(
+        home,
+        &SearchableSession {
+            ts_str: "2025-02-01T12-00-00",
+            uuid: Uuid::from_u128(2),
+            cwd: "/work/docs",
+            branch: "docs-refresh",
+            model: "gpt-5",
+            user_message: "Update the README",
+            agent_message: "Rewrote the tokenizer section.",
+        },
+    );
+
+    // Words match user and agent messages, ignoring case and order.
+    let by_query = |query: &str| SessionSearch {
+        query: Some(query.to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        search_paths(home, &by_query("tokenizer")).await,
+        vec![docs.clone(), parser.clone()]
+    );
+    assert_eq!(
+        search_paths(home, &by_query("LEXER tokenizer")).await,
+        vec![parser.clone()]
+    );
+    assert!(search_paths(home, &by_query("missing")).await.is_empty());
+
+    let search = SessionSearch {
+        cwd: Some(PathBuf::from("/work")),
+        git_branch: Some("docs-refresh".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(search_paths(home, &search).await, vec![docs.clone()]);
+
+    let search = SessionSearch {
+        model: Some("o3".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(search_paths(home, &search).await, vec![parser.clone()]);
+
+    let search = SessionSearch {
+        since: SessionSearch::parse_since("2025-01-15"),
+        ..Default::default()
+    };
+    assert_eq!(search_paths(home, &search).await, vec![docs.clone()]);
+
+    // A date as the upper bound includes that whole day.
+    let search = SessionSearch {
+        until: SessionSearch::parse_until("2025-01-01"),
+        ..Default::default()
+    };
+    assert_eq!(search_paths(home, &search).await, vec![parser.clone()]);
+
+    // The first search indexed both rollouts once; later ones reuse that.
+    let index = fs::read_to_string(home.join("sessions").join(SESSION_INDEX_FILE)).unwrap();
+    assert_eq!(index.lines().count(), 2);
+}
+
+#[tokio::test]
+async fn test_search_conversations_pagination() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+
+    let paths: Vec<PathBuf> = (1..=3)
+        .map(|day| {
+            write_searchable_session_file(
+                home,
+                &SearchableSession {
+                    ts_str: &format!("2025-01-0{day}T12-00-00"),
+                    uuid: Uuid::from_u128(day),
+                    cwd: "/work",
+                    branch: "main",
+                    model: "o3",
+                    user_message: "hello",
+                    agent_message: "hi",
+                },
+            )
+        })
+        .collect();
+    let search = SessionSearch {
+        query: Some("hello".to_string()),
+        ..Default::default()
+    };
+
+    let page1 = search_conversations(home, &search, 2, None).await.unwrap();
+    let page1_paths: Vec<PathBuf> = page1.items.iter().map(|item| item.path.clone()).collect();
+    assert_eq!(page1_paths, vec![paths[2].clone(), paths[1].clone()]);
+
+    let page2 = search_conversations(home, &search, 2, page1.next_cursor.as_ref())
+        .await
+        .unwrap();
+    let page2_paths: Vec<PathBuf> = page2.items.iter().map(|item| item.path.clone()).collect();
+    assert_eq!(page2_paths, vec![paths[0].clone()]);
+    assert_eq!(page2.next_cursor, None);
+}
+
+#[tokio::test]
+async fn test_session_index_writer_records_new_messages() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+
+    let path = write_searchable_session_file(
+        home,
+        &SearchableSession {
+            ts_str: "2025-01-01T12-00-00",
+            uuid: Uuid::from_u128(1),
+            cwd: "/work",
+            branch: "main",
+            model: "o3",
+            user_message: "hello",
+            agent_message: "hi",
+        },
+    );
+    // Index the rollout as it is now.
+    let search = SessionSearch {
+        query: Some("flaky".to_string()),
+        ..Default::default()
+    };
+    assert!(search_paths(home, &search).await.is_empty());
+
+    // Messages recorded later are appended to the index as they are written.
+    let mut writer = SessionIndexWriter::new(home, &path).unwrap();
+    writer
+        .record(&RolloutItem::EventMsg(EventMsg::UserMessage(
+            UserMessageEvent {
+                message: "Now fix the flaky test".to_string(),
+                kind: Some(InputMessageKind::Plain),
+                images: None,
+            },
+        )))
+        .await;
+    assert_eq!(search_paths(home, &search).await, vec![path]);
+}
+
+fn session_file_path(root: &Path, ts_str: &str, uuid: Uuid) -> PathBuf {
+    let (date, _) = ts_str.split_once('T').unwrap();
+    let mut dir = root.join("sessions");
+    for part in date.split('-') {
+        dir.push(part);
+    }
+    dir.join(format!("rollout-{ts_str}-{uuid}.jsonl"))
+}
+
+fn set_age_days(path: &Path, days: u64) {
+    let modified = SystemTime::now() - Duration::from_secs(days * 24 * 60 * 60);
+    File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_compressed_rollout_is_listed_and_read() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+
+    let uuid = Uuid::new_v4();
+    let ts = "2025-05-01T09-00-00";
+    write_session_file(home, ts, uuid, 1).unwrap();
+    let path = session_file_path(home, ts, uuid);
+    let expected_content = fs::read_to_string(&path).unwrap();
+
+    let compressed_path = compress_rollout(&path).await.unwrap();
+    assert!(!path.exists());
+    assert_eq!(
+        compressed_path.file_name().unwrap().to_str().unwrap(),
+        format!("rollout-{ts}-{uuid}.jsonl.gz")
+    );
+
+    let page = get_conversations(home, 10, None).await.unwrap();
+    let paths: Vec<PathBuf> = page.items.iter().map(|item| item.path.clone()).collect();
+    assert_eq!(paths, vec![compressed_path.clone()]);
+    assert_eq!(
+        read_rollout_text(&compressed_path).await.unwrap(),
+        expected_content
+    );
+}
+
+#[tokio::test]
+async fn test_prune_sessions_by_age_and_size() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+
+    let old = Uuid::new_v4();
+    let middle = Uuid::new_v4();
+    let recent = Uuid::new_v4();
+    let archived = Uuid::new_v4();
+    write_session_file(home, "2025-01-01T00-00-00", old, 1).unwrap();
+    write_session_file(home, "2025-01-02T00-00-00", middle, 1).unwrap();
+    write_session_file(home, "2025-01-03T00-00-00", recent, 1).unwrap();
+    write_session_file(home, "2025-01-04T00-00-00", archived, 1).unwrap();
+    let old_path = session_file_path(home, "2025-01-01T00-00-00", old);
+    let middle_path = session_file_path(home, "2025-01-02T00-00-00", middle);
+    let recent_path = session_file_path(home, "2025-01-03T00-00-00", recent);
+    let archived_path = home
+        .join("archived_sessions")
+        .join(format!("rollout-2025-01-04T00-00-00-{archived}.jsonl"));
+    fs::create_dir_all(archived_path.parent().unwrap()).unwrap();
+    fs::rename(
+        session_file_path(home, "2025-01-04T00-00-00", archived),
+        &archived_path,
+    )
+    .unwrap();
+    set_age_days(&old_path, 60);
+    set_age_days(&middle_path, 20);
+    set_age_days(&recent_path, 1);
+    set_age_days(&archived_path, 90);
+
+    // The age limit removes the oldest session; the size limit leaves room for
+    // a single one of the others. The archived session is kept and not
+    // counted.
+    let file_size = fs::metadata(&recent_path).unwrap().len();
+    let retention = SessionRetention {
+        max_age_days: Some(30),
+        max_total_bytes: Some(file_size),
+        compress_after_days: Some(7),
+        ..SessionRetention::default()
+    };
+
+    let dry_run = prune_sessions(home, &retention, true).await.unwrap();
+    let expected = PruneReport {
+        deleted: vec![old_path.clone(), middle_path.clone()],
+        deleted_bytes: fs::metadata(&old_path).unwrap().len()
+            + fs::metadata(&middle_path).unwrap().len(),
+        compressed: vec![archived_path.clone()],
+    };
+    assert_eq!(dry_run, expected);
+    assert!(old_path.exists() && middle_path.exists() && archived_path.exists());
+    // A dry run does not lock the rollouts, which would create lock files.
+    assert!(!old_path.with_extension("jsonl.lock").exists());
+
+    let report = prune_sessions(home, &retention, false).await.unwrap();
+    assert_eq!(report, expected);
+    assert!(!old_path.exists());
+    assert!(!middle_path.exists());
+    // Date directories left empty are removed.
+    assert!(
+        !home
+            .join("sessions")
+            .join("2025")
+            .join("01")
+            .join("01")
+            .exists()
+    );
+    assert!(recent_path.exists());
+    assert!(!archived_path.exists());
+    assert!(
+        home.join("archived_sessions")
+            .join(format!("rollout-2025-01-04T00-00-00-{archived}.jsonl.gz"))
+            .exists()
+    );
+}
+
+#[tokio::test]
+async fn test_delete_rollout_rejects_other_files() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+
+    let uuid = Uuid::new_v4();
+    let ts = "2025-06-01T12-00-00";
+    write_session_file(home, ts, uuid, 0).unwrap();
+    let rollout_path = session_file_path(home, ts, uuid);
+
+    // Not a rollout file name, even though it is in the sessions directory.
+    let other_in_sessions = home.join("sessions").join("notes.txt");
+    fs::write(&other_in_sessions, "keep me").unwrap();
+    // A rollout file name, but outside the sessions directories.
+    let outside = home.join(format!("rollout-{ts}-{uuid}.jsonl"));
+    fs::copy(&rollout_path, &outside).unwrap();
+
+    for path in [&other_in_sessions, &outside] {
+        let err = delete_rollout(home, path).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(path.exists());
+    }
+
+    delete_rollout(home, &rollout_path).await.unwrap();
+    assert!(!rollout_path.exists());
+}
+
+#[tokio::test]
+async fn test_prune_sessions_skips_live_rollouts() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+
+    let locked = Uuid::new_v4();
+    let recent = Uuid::new_v4();
+    write_session_file(home, "2025-02-01T00-00-00", locked, 1).unwrap();
+    write_session_file(home, "2025-02-02T00-00-00", recent, 1).unwrap();
+    let locked_path = session_file_path(home, "2025-02-01T00-00-00", locked);
+    let recent_path = session_file_path(home, "2025-02-02T00-00-00", recent);
+    set_age_days(&locked_path, 60);
+
+    let retention = SessionRetention {
+        max_age_days: Some(30),
+        max_total_bytes: Some(0),
+        compress_after_days: Some(0),
+        ..SessionRetention::default()
+    };
+
+    // One rollout is held by a recorder, the other was just written to.
+    let lock = RolloutLock::acquire(&locked_path).await.unwrap();
+    let dry_run = prune_sessions(home, &retention, true).await.unwrap();
+    assert_eq!(dry_run, PruneReport::default());
+    let report = prune_sessions(home, &retention, false).await.unwrap();
+    assert_eq!(report, PruneReport::default());
+    assert!(locked_path.exists());
+    assert!(recent_path.exists());
+    assert!(matches!(
+        delete_rollout(home, &locked_path).await,
+        Err(e) if e.kind() == std::io::ErrorKind::ResourceBusy
+    ));
+
+    // Once the recorder is gone, the old rollout is pruned.
+    drop(lock);
+    let report = prune_sessions(home, &retention, false).await.unwrap();
+    assert_eq!(report.deleted, vec![locked_path.clone()]);
+    assert!(!locked_path.exists());
+    assert!(recent_path.exists());
+}
+
+#[tokio::test]
+async fn test_removed_and_compressed_rollouts_leave_the_index() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+
+    let removed = write_searchable_session_file(
+        home,
+        &SearchableSession {
+            ts_str: "2025-03-01T10-00-00",
+            uuid: Uuid::new_v4(),
+            cwd: "/work/secret",
+            branch: "main",
+            model: "gpt-5",
+            user_message: "my password is hunter2",
+            agent_message: "noted",
+        },
+    );
+    let compressed = write_searchable_session_file(
+        home,
+        &SearchableSession {
+            ts_str: "2025-03-02T10-00-00",
+            uuid: Uuid::new_v4(),
+            cwd: "/work/parser",
+            branch: "main",
+            model: "gpt-5",
+            user_message: "fix the tokenizer",
+            agent_message: "done",
+        },
+    );
+    // Searching indexes both.
+    let search = SessionSearch::default();
+    assert_eq!(search_paths(home, &search).await.len(), 2);
+
+    delete_rollout(home, &removed).await.unwrap();
+    set_age_days(&compressed, 30);
+    let retention = SessionRetention {
+        compress_after_days: Some(7),
+        ..SessionRetention::default()
+    };
+    prune_sessions(home, &retention, false).await.unwrap();
+
+    let index = fs::read_to_string(home.join("sessions").join(SESSION_INDEX_FILE)).unwrap();
+    assert!(!index.contains("hunter2"));
+    assert!(!index.contains(removed.file_name().unwrap().to_str().unwrap()));
+    assert!(index.contains(".jsonl.gz"));
+
+    let tokenizer = SessionSearch {
+        query: Some("tokenizer".to_string()),
+        ..SessionSearch::default()
+    };
+    let mut compressed_name = compressed.file_name().unwrap().to_os_string();
+    compressed_name.push(".gz");
+    assert_eq!(
+        search_paths(home, &tokenizer).await,
+        vec![compressed.with_file_name(compressed_name)]
+    );
+}
+
+#[tokio::test]
+async fn test_search_compacts_the_index() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+    let index_path = home.join("sessions").join(SESSION_INDEX_FILE);
+
+    let kept = write_searchable_session_file(
+        home,
+        &SearchableSession {
+            ts_str: "2025-04-01T10-00-00",
+            uuid: Uuid::from_u128(1),
+            cwd: "/work",
+            branch: "main",
+            model: "o3",
+            user_message: "hello",
+            agent_message: "hi",
+        },
+    );
+    let removed = write_searchable_session_file(
+        home,
+        &SearchableSession {
+            ts_str: "2025-04-02T10-00-00",
+            uuid: Uuid::from_u128(2),
+            cwd: "/work",
+            branch: "main",
+            model: "o3",
+            user_message: "bye",
+            agent_message: "see you",
+        },
+    );
+    let search = SessionSearch::default();
+    assert_eq!(search_paths(home, &search).await.len(), 2);
+
+    // Indexing a rollout again supersedes what was indexed for it before.
+    let first_line = fs::read_to_string(&kept).unwrap();
+    let meta = serde_json::from_str::<RolloutLine>(first_line.lines().next().unwrap())
+        .unwrap()
+        .item;
+    for _ in 0..3 {
+        let mut writer = SessionIndexWriter::new(home, &kept).unwrap();
+        writer.record(&meta).await;
+        writer
+            .record(&RolloutItem::EventMsg(EventMsg::UserMessage(
+                UserMessageEvent {
+                    message: "again".to_string(),
+                    kind: Some(InputMessageKind::Plain),
+                    images: None,
+                },
+            )))
+            .await;
+    }
+    assert_eq!(fs::read_to_string(&index_path).unwrap().lines().count(), 8);
+    assert_eq!(search_paths(home, &search).await.len(), 2);
+    assert_eq!(fs::read_to_string(&index_path).unwrap().lines().count(), 3);
+
+    // Rollouts removed behind the index's back are dropped from it.
+    fs::remove_file(&removed).unwrap();
+    assert_eq!(search_paths(home, &search).await, vec![kept]);
+    let index = fs::read_to_string(&index_path).unwrap();
+    assert_eq!(index.lines().count(), 2);
+    assert!(!index.contains(removed.file_name().unwrap().to_str().unwrap()));
+}
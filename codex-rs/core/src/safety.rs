@@ -7,6 +7,8 @@ use codex_apply_patch::ApplyPatchAction;
 use codex_apply_patch::ApplyPatchFileChange;
 
 use crate::exec::SandboxType;
+use crate::exec_policy::ExecPolicy;
+use crate::exec_policy::ExecPolicyVerdict;
 use crate::is_safe_command::is_known_safe_command;
 use crate::protocol::AskForApproval;
 use crate::protocol::SandboxPolicy;
@@ -76,15 +78,59 @@ pub fn assess_patch_safety(
 /// true:
 ///
 /// - the user has explicitly approved the command
+/// - the command matches the user's exec policy (in `$CODEX_HOME`, not the
+///   project's) and does not write files
 /// - the command is on the "known safe" list
 /// - `DangerFullAccess` was specified and `UnlessTrusted` was not
-pub fn assess_command_safety(
+///
+/// Commands that match the exec policy and only write files inside the
+/// writable roots, or that only the project's exec policy allows, are
+/// auto-approved, but still run in the sandbox. Commands
+/// the exec policy forbids are rejected outright.
+pub(crate) fn assess_command_safety(
     command: &[String],
     approval_policy: AskForApproval,
     sandbox_policy: &SandboxPolicy,
     approved: &HashSet<Vec<String>>,
+    exec_policy: Option<&ExecPolicy>,
+    cwd: &Path,
     with_escalated_permissions: bool,
 ) -> SafetyCheck {
+    if let Some(exec_policy) = exec_policy {
+        match exec_policy.evaluate(command, sandbox_policy, cwd) {
+            ExecPolicyVerdict::Forbidden { reason } => {
+                return SafetyCheck::Reject {
+                    reason: format!("forbidden by exec policy: {reason}"),
+                };
+            }
+            ExecPolicyVerdict::Allowed {
+                writes_files: false,
+                from_project: false,
+            } => {
+                return SafetyCheck::AutoApprove {
+                    sandbox_type: SandboxType::None,
+                };
+            }
+            ExecPolicyVerdict::Allowed { .. } if !with_escalated_permissions => {
+                // The written paths were checked lexically, but they may be
+                // symlinks or hard links to files outside the writable roots,
+                // so keep the sandbox in place. Commands allowed by a project
+                // policy also stay sandboxed: the policy comes with the
+                // repository, and only the user's own policy may lift it.
+                let sandbox_type = match sandbox_policy {
+                    SandboxPolicy::DangerFullAccess => Some(SandboxType::None),
+                    SandboxPolicy::ReadOnly | SandboxPolicy::WorkspaceWrite { .. } => {
+                        get_platform_sandbox()
+                    }
+                };
+                if let Some(sandbox_type) = sandbox_type {
+                    return SafetyCheck::AutoApprove { sandbox_type };
+                }
+            }
+            ExecPolicyVerdict::Allowed { .. } | ExecPolicyVerdict::Unmatched => {}
+        }
+    }
+
     // A command is "trusted" because either:
     // - it belongs to a set of commands we consider "safe" by default, or
     // - the user has explicitly approved the command for this session
@@ -276,6 +322,9 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            readable_roots: None,
+            deny_read: Vec::new(),
+            allowed_hosts: Vec::new(),
         };
 
         assert!(is_write_patch_constrained_to_writable_paths(
@@ -297,6 +346,9 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            readable_roots: None,
+            deny_read: Vec::new(),
+            allowed_hosts: Vec::new(),
         };
         assert!(is_write_patch_constrained_to_writable_paths(
             &add_outside,
@@ -319,6 +371,8 @@ mod tests {
             approval_policy,
             &sandbox_policy,
             &approved,
+            None,
+            Path::new("/"),
             request_escalated_privileges,
         );
 
@@ -338,6 +392,8 @@ mod tests {
             approval_policy,
             &sandbox_policy,
             &approved,
+            None,
+            Path::new("/"),
             request_escalated_privileges,
         );
 
@@ -347,4 +403,100 @@ mod tests {
         };
         assert_eq!(safety_check, expected);
     }
+
+    #[test]
+    fn test_exec_policy_decisions() {
+        let exec_policy = ExecPolicy::from_source(
+            r#"
+define_program(
+    program="ls",
+    options=[flag("-l")],
+    args=[ARG_RFILES_OR_CWD],
+)
+
+forbid_program_regex(
+    regex="^rm$",
+    reason="not on this machine",
+)
+"#,
+        );
+        let approved: HashSet<Vec<String>> = HashSet::new();
+        let cwd = TempDir::new().unwrap();
+
+        // Matched, read-only commands run without a sandbox even when the
+        // approval policy would otherwise ask.
+        let safety_check = assess_command_safety(
+            &["ls".to_string(), "-l".to_string()],
+            AskForApproval::UnlessTrusted,
+            &SandboxPolicy::ReadOnly,
+            &approved,
+            Some(&exec_policy),
+            cwd.path(),
+            false,
+        );
+        assert_eq!(
+            safety_check,
+            SafetyCheck::AutoApprove {
+                sandbox_type: SandboxType::None
+            }
+        );
+
+        let safety_check = assess_command_safety(
+            &["rm".to_string(), "-rf".to_string(), "src".to_string()],
+            AskForApproval::OnRequest,
+            &SandboxPolicy::DangerFullAccess,
+            &approved,
+            Some(&exec_policy),
+            cwd.path(),
+            false,
+        );
+        assert_eq!(
+            safety_check,
+            SafetyCheck::Reject {
+                reason: "forbidden by exec policy: not on this machine".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_project_exec_policy_keeps_sandbox() {
+        let codex_home = TempDir::new().unwrap();
+        let project = TempDir::new().unwrap();
+        std::fs::create_dir(project.path().join(".codex")).unwrap();
+        std::fs::write(
+            project.path().join(".codex").join("exec.policy"),
+            r#"
+define_program(
+    program="curl",
+    options=[flag("-s")],
+    args=[ARG_OPAQUE_VALUE],
+)
+"#,
+        )
+        .unwrap();
+        let (exec_policy, errors) = ExecPolicy::load(codex_home.path(), project.path());
+        assert!(errors.is_empty());
+        let approved: HashSet<Vec<String>> = HashSet::new();
+
+        // A command allowed by the project policy that writes no files is
+        // auto-approved, but not taken out of the sandbox.
+        let safety_check = assess_command_safety(
+            &[
+                "curl".to_string(),
+                "-s".to_string(),
+                "https://example.com".to_string(),
+            ],
+            AskForApproval::UnlessTrusted,
+            &SandboxPolicy::ReadOnly,
+            &approved,
+            exec_policy.as_ref(),
+            project.path(),
+            false,
+        );
+        let expected = match get_platform_sandbox() {
+            Some(sandbox_type) => SafetyCheck::AutoApprove { sandbox_type },
+            None => SafetyCheck::AskUser,
+        };
+        assert_eq!(safety_check, expected);
+    }
 }
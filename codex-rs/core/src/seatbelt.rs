@@ -14,7 +14,7 @@ const MACOS_SEATBELT_BASE_POLICY: &str = include_str!("seatbelt_base_policy.sbpl
 /// to defend against an attacker trying to inject a malicious version on the
 /// PATH. If /usr/bin/sandbox-exec has been tampered with, then the attacker
 /// already has root access.
-const MACOS_PATH_TO_SEATBELT_EXECUTABLE: &str = "/usr/bin/sandbox-exec";
+pub(crate) const MACOS_PATH_TO_SEATBELT_EXECUTABLE: &str = "/usr/bin/sandbox-exec";
 
 pub async fn spawn_command_under_seatbelt(
     command: Vec<String>,
@@ -38,7 +38,7 @@ pub async fn spawn_command_under_seatbelt(
     .await
 }
 
-fn create_seatbelt_command_args(
+pub(crate) fn create_seatbelt_command_args(
     command: Vec<String>,
     sandbox_policy: &SandboxPolicy,
     cwd: &Path,
@@ -96,10 +96,43 @@ fn create_seatbelt_command_args(
         }
     };
 
-    let file_read_policy = if sandbox_policy.has_full_disk_read_access() {
-        "; allow read-only file operations\n(allow file-read*)"
-    } else {
-        ""
+    let (file_read_policy, read_cli_args) = match sandbox_policy.get_readable_roots_with_cwd(cwd) {
+        None => (
+            "; allow read-only file operations\n(allow file-read*)".to_string(),
+            Vec::<String>::new(),
+        ),
+        Some(readable_roots) => {
+            let mut cli_args: Vec<String> = Vec::new();
+            let mut readable_folders: Vec<String> = Vec::new();
+            for (index, root) in readable_roots.iter().enumerate() {
+                let canonical_root = root.canonicalize().unwrap_or_else(|_| root.clone());
+                let root_param = format!("READABLE_ROOT_{index}");
+                cli_args.push(format!(
+                    "-D{root_param}={}",
+                    canonical_root.to_string_lossy()
+                ));
+                readable_folders.push(format!("(subpath (param \"{root_param}\"))"));
+            }
+
+            let mut require_parts = vec![format!("(require-any {})", readable_folders.join(" "))];
+            for (index, denied) in sandbox_policy.get_denied_read_paths().iter().enumerate() {
+                let canonical_denied = denied.canonicalize().unwrap_or_else(|_| denied.clone());
+                let denied_param = format!("DENIED_READ_{index}");
+                cli_args.push(format!(
+                    "-D{denied_param}={}",
+                    canonical_denied.to_string_lossy()
+                ));
+                require_parts.push(format!(
+                    "(require-not (subpath (param \"{denied_param}\")))"
+                ));
+            }
+
+            let file_read_policy = format!(
+                "; allow reads only under the readable roots\n(allow file-read-metadata)\n(allow file-read*\n(require-all {} )\n)",
+                require_parts.join(" ")
+            );
+            (file_read_policy, cli_args)
+        }
     };
 
     // TODO(mbolin): apply_patch calls must also honor the SandboxPolicy.
@@ -115,6 +148,7 @@ fn create_seatbelt_command_args(
 
     let mut seatbelt_args: Vec<String> = vec!["-p".to_string(), full_policy];
     seatbelt_args.extend(extra_cli_args);
+    seatbelt_args.extend(read_cli_args);
     seatbelt_args.push("--".to_string());
     seatbelt_args.extend(command);
     seatbelt_args
@@ -157,6 +191,9 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            readable_roots: None,
+            deny_read: Vec::new(),
+            allowed_hosts: Vec::new(),
         };
 
         let args = create_seatbelt_command_args(
@@ -207,6 +244,42 @@ mod tests {
         assert_eq!(expected_args, args);
     }
 
+    #[test]
+    fn create_seatbelt_args_with_restricted_reads() {
+        let tmp = TempDir::new().expect("tempdir");
+        let readable = tmp.path().join("readable");
+        let secret = readable.join("secret");
+        fs::create_dir_all(&secret).expect("create secret dir");
+        let readable_canon = readable.canonicalize().expect("canonicalize readable");
+        let secret_canon = secret.canonicalize().expect("canonicalize secret");
+
+        let policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![],
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+            readable_roots: Some(vec![readable]),
+            deny_read: vec![secret],
+            allowed_hosts: Vec::new(),
+        };
+
+        let args = create_seatbelt_command_args(
+            vec!["/bin/echo".to_string(), "hello".to_string()],
+            &policy,
+            tmp.path(),
+        );
+
+        let policy_text = &args[1];
+        assert!(!policy_text.contains("(allow file-read*)"));
+        assert!(policy_text.contains(r#"(require-not (subpath (param "DENIED_READ_0")))"#));
+        assert!(args.contains(&format!(
+            "-DDENIED_READ_0={}",
+            secret_canon.to_string_lossy()
+        )));
+        assert!(args.iter().any(|arg| arg.starts_with("-DREADABLE_ROOT_")
+            && arg.ends_with(&*readable_canon.to_string_lossy())));
+    }
+
     #[test]
     fn create_seatbelt_args_for_cwd_as_git_repo() {
         if cfg!(target_os = "windows") {
@@ -232,6 +305,9 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            readable_roots: None,
+            deny_read: Vec::new(),
+            allowed_hosts: Vec::new(),
         };
 
         let args = create_seatbelt_command_args(
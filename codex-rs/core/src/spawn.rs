@@ -22,6 +22,11 @@ pub const CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR: &str = "CODEX_SANDBOX_NETWORK_
 /// accommodate sandboxing configuration and other sandboxing mechanisms.
 pub const CODEX_SANDBOX_ENV_VAR: &str = "CODEX_SANDBOX";
 
+/// Path of the Unix socket on which Codex serves the filtering proxy for a
+/// sandbox policy with `allowed_hosts`. Consumed by `codex-linux-sandbox`,
+/// which exposes the proxy to the sandboxed command over loopback.
+pub const CODEX_NETWORK_PROXY_SOCKET_ENV_VAR: &str = "CODEX_NETWORK_PROXY_SOCKET";
+
 #[derive(Debug, Clone, Copy)]
 pub enum StdioPolicy {
     RedirectForShellTool,
@@ -0,0 +1,444 @@
+//! Record and replay of model streams.
+//!
+//! When `CODEX_RS_RECORD_DIR` is set, the request body and the raw SSE
+//! response of every model request are written to `turn-0001/`,
+//! `turn-0002/`, ... under a directory named after the conversation, so that
+//! concurrent conversations (e.g. workers or reviews) are numbered
+//! independently. The conversations are listed in `conversations.txt` in the
+//! order they started.
+//!
+//! When `CODEX_RS_REPLAY_DIR` is set, no requests are sent and the recorded
+//! responses are served back in the same order. A conversation replays the
+//! recording of the same id if there is one, and otherwise the next recorded
+//! conversation that has not been claimed yet. Replay fails as soon as a
+//! request differs from the recorded `request.json`, since the recorded
+//! response would not answer it.
+
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+
+use bytes::Bytes;
+use codex_protocol::mcp_protocol::ConversationId;
+use futures::Stream;
+use futures::StreamExt;
+use futures::stream::BoxStream;
+use tracing::warn;
+
+use crate::error::CodexErr;
+use crate::error::Result;
+use crate::flags::CODEX_RS_RECORD_DIR;
+use crate::flags::CODEX_RS_REPLAY_DIR;
+
+const CONVERSATIONS_FILE: &str = "conversations.txt";
+const REQUEST_FILE: &str = "request.json";
+const RESPONSE_FILE: &str = "response.sse";
+
+/// The raw body of a streaming response.
+pub(crate) type ByteStream = BoxStream<'static, Result<Bytes>>;
+
+static STREAM_RECORDING: LazyLock<Option<StreamRecording>> =
+    LazyLock::new(|| match (*CODEX_RS_REPLAY_DIR, *CODEX_RS_RECORD_DIR) {
+        (Some(dir), record_dir) => {
+            if record_dir.is_some() {
+                warn!("both CODEX_RS_REPLAY_DIR and CODEX_RS_RECORD_DIR are set; replaying");
+            }
+            Some(StreamRecording::new(RecordingMode::Replay, dir))
+        }
+        (None, Some(dir)) => Some(StreamRecording::new(RecordingMode::Record, dir)),
+        (None, None) => None,
+    });
+
+/// In replay mode, returns the recorded response to serve instead of sending
+/// `payload`, the next request of `conversation_id`.
+pub(crate) fn replay_next_response(
+    conversation_id: ConversationId,
+    payload: &serde_json::Value,
+) -> Option<Result<ByteStream>> {
+    STREAM_RECORDING
+        .as_ref()?
+        .replay_next_response(conversation_id, payload)
+}
+
+/// In record mode, writes `payload` and a copy of `stream` to the directory
+/// of the next turn of `conversation_id`. Returns `stream` unchanged
+/// otherwise.
+pub(crate) fn record_response<S>(
+    conversation_id: ConversationId,
+    payload: &serde_json::Value,
+    stream: S,
+) -> ByteStream
+where
+    S: Stream<Item = Result<Bytes>> + Send + 'static,
+{
+    match STREAM_RECORDING.as_ref() {
+        Some(recording) => recording.record_response(conversation_id, payload, stream),
+        None => stream.boxed(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RecordingMode {
+    Record,
+    Replay,
+}
+
+struct StreamRecording {
+    mode: RecordingMode,
+    dir: PathBuf,
+    state: Mutex<RecordingState>,
+}
+
+#[derive(Default)]
+struct RecordingState {
+    /// Recorded conversation replayed by each running conversation, and the
+    /// number of its turns recorded or replayed so far.
+    conversations: HashMap<ConversationId, (String, usize)>,
+    /// In replay mode, the recorded conversations not claimed yet, in the
+    /// order they started. Read on first use.
+    unclaimed: Option<Vec<String>>,
+}
+
+impl StreamRecording {
+    fn new(mode: RecordingMode, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            mode,
+            dir: dir.into(),
+            state: Mutex::new(RecordingState::default()),
+        }
+    }
+
+    /// Returns the recorded conversation `conversation_id` maps to and the
+    /// directory of its next turn.
+    fn next_turn_dir(&self, conversation_id: ConversationId) -> std::io::Result<(String, PathBuf)> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let RecordingState {
+            conversations,
+            unclaimed,
+        } = &mut *state;
+        let (recorded_id, turns) = match conversations.entry(conversation_id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let recorded_id = match self.mode {
+                    RecordingMode::Record => {
+                        std::fs::create_dir_all(&self.dir)?;
+                        let mut list = OpenOptions::new()
+                            .create(true)
+                            .append(true)
+                            .open(self.dir.join(CONVERSATIONS_FILE))?;
+                        writeln!(list, "{conversation_id}")?;
+                        conversation_id.to_string()
+                    }
+                    RecordingMode::Replay => {
+                        let unclaimed = match unclaimed {
+                            Some(unclaimed) => unclaimed,
+                            None => unclaimed.insert(self.read_conversations()?),
+                        };
+                        let own_id = conversation_id.to_string();
+                        let position = unclaimed
+                            .iter()
+                            .position(|id| *id == own_id)
+                            .or_else(|| (!unclaimed.is_empty()).then_some(0))
+                            .ok_or_else(|| {
+                                std::io::Error::new(
+                                    std::io::ErrorKind::NotFound,
+                                    format!(
+                                        "no recorded conversation left in {}",
+                                        self.dir.display()
+                                    ),
+                                )
+                            })?;
+                        unclaimed.remove(position)
+                    }
+                };
+                entry.insert((recorded_id, 0))
+            }
+        };
+        *turns += 1;
+        let turn_dir = self
+            .dir
+            .join(recorded_id.as_str())
+            .join(format!("turn-{turns:04}"));
+        Ok((recorded_id.clone(), turn_dir))
+    }
+
+    fn read_conversations(&self) -> std::io::Result<Vec<String>> {
+        let path = self.dir.join(CONVERSATIONS_FILE);
+        let contents = std::fs::read_to_string(&path).map_err(|e| {
+            std::io::Error::new(e.kind(), format!("cannot read {}: {e}", path.display()))
+        })?;
+        Ok(contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    fn replay_next_response(
+        &self,
+        conversation_id: ConversationId,
+        payload: &serde_json::Value,
+    ) -> Option<Result<ByteStream>> {
+        if self.mode != RecordingMode::Replay {
+            return None;
+        }
+
+        Some(
+            self.read_recorded_response(conversation_id, payload)
+                .map(|body| futures::stream::iter([Ok(Bytes::from(body))]).boxed())
+                .map_err(CodexErr::Io),
+        )
+    }
+
+    fn read_recorded_response(
+        &self,
+        conversation_id: ConversationId,
+        payload: &serde_json::Value,
+    ) -> std::io::Result<Vec<u8>> {
+        let (recorded_id, turn_dir) = self.next_turn_dir(conversation_id)?;
+
+        let request_path = turn_dir.join(REQUEST_FILE);
+        let recorded_request = std::fs::read_to_string(&request_path).map_err(|e| {
+            std::io::Error::new(
+                e.kind(),
+                format!("no recorded request at {}: {e}", request_path.display()),
+            )
+        })?;
+        // The conversation id ends up in requests (e.g. as the prompt cache
+        // key), and differs when another conversation replays the recording.
+        let recorded_request: serde_json::Value = serde_json::from_str(
+            &recorded_request.replace(&recorded_id, &conversation_id.to_string()),
+        )?;
+        if recorded_request != *payload {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "request does not match the recording at {}; the session diverged from the recorded one",
+                    request_path.display()
+                ),
+            ));
+        }
+
+        let response_path = turn_dir.join(RESPONSE_FILE);
+        std::fs::read(&response_path).map_err(|e| {
+            std::io::Error::new(
+                e.kind(),
+                format!("no recorded response at {}: {e}", response_path.display()),
+            )
+        })
+    }
+
+    fn record_response<S>(
+        &self,
+        conversation_id: ConversationId,
+        payload: &serde_json::Value,
+        stream: S,
+    ) -> ByteStream
+    where
+        S: Stream<Item = Result<Bytes>> + Send + 'static,
+    {
+        if self.mode != RecordingMode::Record {
+            return stream.boxed();
+        }
+
+        let turn = self
+            .next_turn_dir(conversation_id)
+            .and_then(|(_, turn_dir)| {
+                let file = create_turn_files(&turn_dir, payload)?;
+                Ok((turn_dir, file))
+            });
+        let (turn_dir, mut response_file) = match turn {
+            Ok(turn) => turn,
+            Err(e) => {
+                // Recording is a debugging aid; never fail the turn over it.
+                warn!("failed to record turn of {conversation_id}: {e}");
+                return stream.boxed();
+            }
+        };
+        stream
+            .inspect(move |chunk| {
+                if let Ok(bytes) = chunk
+                    && let Err(e) = response_file.write_all(bytes)
+                {
+                    warn!("failed to record response to {}: {e}", turn_dir.display());
+                }
+            })
+            .boxed()
+    }
+}
+
+/// Writes the request of a turn and returns the file for its response.
+fn create_turn_files(turn_dir: &Path, payload: &serde_json::Value) -> std::io::Result<File> {
+    std::fs::create_dir_all(turn_dir)?;
+    std::fs::write(
+        turn_dir.join(REQUEST_FILE),
+        serde_json::to_string_pretty(payload)?,
+    )?;
+    File::create(turn_dir.join(RESPONSE_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    async fn record(
+        recording: &StreamRecording,
+        conversation_id: ConversationId,
+        payload: serde_json::Value,
+        body: &str,
+    ) {
+        let chunks = vec![Ok(Bytes::from(body.to_string()))];
+        let teed =
+            recording.record_response(conversation_id, &payload, futures::stream::iter(chunks));
+        let received: Vec<Bytes> = teed.try_collect().await.unwrap();
+        assert_eq!(received.concat(), body.as_bytes());
+    }
+
+    async fn replay(
+        recording: &StreamRecording,
+        conversation_id: ConversationId,
+        payload: serde_json::Value,
+    ) -> Result<String> {
+        let body: Vec<Bytes> = recording
+            .replay_next_response(conversation_id, &payload)
+            .unwrap()?
+            .try_collect()
+            .await?;
+        Ok(String::from_utf8(body.concat()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn recorded_turns_are_replayed_in_order() {
+        let dir = TempDir::new().unwrap();
+        let recording = StreamRecording::new(RecordingMode::Record, dir.path());
+        let conversation_id = ConversationId::new();
+        assert!(
+            recording
+                .replay_next_response(conversation_id, &json!({}))
+                .is_none()
+        );
+
+        for body in ["first", "second"] {
+            record(&recording, conversation_id, json!({ "input": body }), body).await;
+        }
+        let request: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(
+                dir.path()
+                    .join(conversation_id.to_string())
+                    .join("turn-0002")
+                    .join(REQUEST_FILE),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(request, json!({ "input": "second" }));
+
+        let replay_recording = StreamRecording::new(RecordingMode::Replay, dir.path());
+        for body in ["first", "second"] {
+            assert_eq!(
+                replay(&replay_recording, conversation_id, json!({ "input": body }))
+                    .await
+                    .unwrap(),
+                body
+            );
+        }
+        // Running past the end of the recording is an error.
+        assert!(matches!(
+            replay(
+                &replay_recording,
+                conversation_id,
+                json!({ "input": "third" })
+            )
+            .await,
+            Err(CodexErr::Io(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn conversations_are_numbered_and_replayed_separately() {
+        let dir = TempDir::new().unwrap();
+        let recording = StreamRecording::new(RecordingMode::Record, dir.path());
+        let parent = ConversationId::new();
+        let worker = ConversationId::new();
+        record(
+            &recording,
+            parent,
+            json!({ "key": parent.to_string() }),
+            "parent 1",
+        )
+        .await;
+        record(
+            &recording,
+            worker,
+            json!({ "key": worker.to_string() }),
+            "worker 1",
+        )
+        .await;
+        record(&recording, parent, json!({ "input": "again" }), "parent 2").await;
+
+        // New conversations claim the recorded ones in the order they started,
+        // and their ids stand in for the recorded ones.
+        let replay_recording = StreamRecording::new(RecordingMode::Replay, dir.path());
+        let new_parent = ConversationId::new();
+        let new_worker = ConversationId::new();
+        let replayed = [
+            replay(
+                &replay_recording,
+                new_parent,
+                json!({ "key": new_parent.to_string() }),
+            )
+            .await
+            .unwrap(),
+            replay(
+                &replay_recording,
+                new_worker,
+                json!({ "key": new_worker.to_string() }),
+            )
+            .await
+            .unwrap(),
+            replay(&replay_recording, new_parent, json!({ "input": "again" }))
+                .await
+                .unwrap(),
+        ];
+        assert_eq!(replayed, ["parent 1", "worker 1", "parent 2"]);
+    }
+
+    #[tokio::test]
+    async fn replay_fails_when_the_request_differs() {
+        let dir = TempDir::new().unwrap();
+        let recording = StreamRecording::new(RecordingMode::Record, dir.path());
+        let conversation_id = ConversationId::new();
+        record(
+            &recording,
+            conversation_id,
+            json!({ "input": "hello" }),
+            "hi",
+        )
+        .await;
+
+        let replay_recording = StreamRecording::new(RecordingMode::Replay, dir.path());
+        let err = replay(
+            &replay_recording,
+            conversation_id,
+            json!({ "input": "goodbye" }),
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("does not match the recording"),
+            "unexpected error: {err}"
+        );
+    }
+}
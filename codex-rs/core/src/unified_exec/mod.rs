@@ -1,4 +1,3 @@
-use portable_pty::CommandBuilder;
 use portable_pty::PtySize;
 use portable_pty::native_pty_system;
 use std::collections::HashMap;
@@ -17,6 +16,7 @@ use tokio::task::JoinHandle;
 use tokio::time::Duration;
 use tokio::time::Instant;
 
+use crate::exec::PtySandbox;
 use crate::exec_command::ExecCommandSession;
 use crate::truncate::truncate_middle;
 
@@ -33,6 +33,8 @@ pub(crate) struct UnifiedExecRequest<'a> {
     pub session_id: Option<i32>,
     pub input_chunks: &'a [String],
     pub timeout_ms: Option<u64>,
+    /// Sandbox for the command when this request starts a new session.
+    pub sandbox: PtySandbox<'a>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -193,7 +195,7 @@ impl UnifiedExecSessionManager {
         } else {
             let command = request.input_chunks.to_vec();
             let new_id = self.next_session_id.fetch_add(1, Ordering::SeqCst);
-            let session = create_unified_exec_session(&command).await?;
+            let session = create_unified_exec_session(&command, request.sandbox).await?;
             let managed_session = ManagedUnifiedExecSession::new(session);
             let (buffer, notify) = managed_session.output_handles();
             writer_tx = managed_session.writer_sender();
@@ -297,6 +299,7 @@ impl UnifiedExecSessionManager {
 
 async fn create_unified_exec_session(
     command: &[String],
+    sandbox: PtySandbox<'_>,
 ) -> Result<ExecCommandSession, UnifiedExecError> {
     if command.is_empty() {
         return Err(UnifiedExecError::MissingCommandLine);
@@ -313,11 +316,9 @@ async fn create_unified_exec_session(
         })
         .map_err(UnifiedExecError::create_session)?;
 
-    // Safe thanks to the check at the top of the function.
-    let mut command_builder = CommandBuilder::new(command[0].clone());
-    for arg in &command[1..] {
-        command_builder.arg(arg);
-    }
+    let command_builder = sandbox
+        .command_builder(command.to_vec())
+        .map_err(|err| UnifiedExecError::create_session(err.into()))?;
 
     let mut child = pair
         .slave
@@ -394,6 +395,22 @@ async fn create_unified_exec_session(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::exec::SandboxType;
+    use crate::protocol::SandboxPolicy;
+    use std::path::Path;
+    use std::path::PathBuf;
+
+    static NO_SANDBOX_POLICY: SandboxPolicy = SandboxPolicy::DangerFullAccess;
+    static NO_CODEX_LINUX_SANDBOX_EXE: Option<PathBuf> = None;
+
+    fn no_sandbox() -> PtySandbox<'static> {
+        PtySandbox {
+            sandbox_type: SandboxType::None,
+            sandbox_policy: &NO_SANDBOX_POLICY,
+            codex_linux_sandbox_exe: &NO_CODEX_LINUX_SANDBOX_EXE,
+            cwd: Path::new("/"),
+        }
+    }
 
     #[test]
     fn push_chunk_trims_only_excess_bytes() {
@@ -412,6 +429,32 @@ mod tests {
         assert_eq!(buffer.chunks.pop_back().unwrap(), vec![b'b']);
     }
 
+    #[tokio::test]
+    async fn new_session_requires_linux_sandbox_helper() {
+        let manager = UnifiedExecSessionManager::default();
+        let sandbox_policy = SandboxPolicy::new_read_only_policy();
+
+        let result = manager
+            .handle_request(UnifiedExecRequest {
+                session_id: None,
+                input_chunks: &["bash".to_string(), "-i".to_string()],
+                timeout_ms: Some(100),
+                sandbox: PtySandbox {
+                    sandbox_type: SandboxType::LinuxSeccomp,
+                    sandbox_policy: &sandbox_policy,
+                    codex_linux_sandbox_exe: &None,
+                    cwd: Path::new("/"),
+                },
+            })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(UnifiedExecError::CreateSession { .. })
+        ));
+        assert!(manager.sessions.lock().await.is_empty());
+    }
+
     #[cfg(unix)]
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     async fn unified_exec_persists_across_requests_jif() -> Result<(), UnifiedExecError> {
@@ -422,6 +465,7 @@ mod tests {
                 session_id: None,
                 input_chunks: &["bash".to_string(), "-i".to_string()],
                 timeout_ms: Some(2_500),
+                sandbox: no_sandbox(),
             })
             .await?;
         let session_id = open_shell.session_id.expect("expected session_id");
@@ -434,6 +478,7 @@ mod tests {
                     "CODEX_INTERACTIVE_SHELL_VAR=codex\n".to_string(),
                 ],
                 timeout_ms: Some(2_500),
+                sandbox: no_sandbox(),
             })
             .await?;
 
@@ -442,6 +487,7 @@ mod tests {
                 session_id: Some(session_id),
                 input_chunks: &["echo $CODEX_INTERACTIVE_SHELL_VAR\n".to_string()],
                 timeout_ms: Some(2_500),
+                sandbox: no_sandbox(),
             })
             .await?;
         assert!(out_2.output.contains("codex"));
@@ -459,6 +505,7 @@ mod tests {
                 session_id: None,
                 input_chunks: &["/bin/bash".to_string(), "-i".to_string()],
                 timeout_ms: Some(2_500),
+                sandbox: no_sandbox(),
             })
             .await?;
         let session_a = shell_a.session_id.expect("expected session id");
@@ -468,6 +515,7 @@ mod tests {
                 session_id: Some(session_a),
                 input_chunks: &["export CODEX_INTERACTIVE_SHELL_VAR=codex\n".to_string()],
                 timeout_ms: Some(2_500),
+                sandbox: no_sandbox(),
             })
             .await?;
 
@@ -479,6 +527,7 @@ mod tests {
                     "$CODEX_INTERACTIVE_SHELL_VAR\n".to_string(),
                 ],
                 timeout_ms: Some(2_500),
+                sandbox: no_sandbox(),
             })
             .await?;
         assert!(!out_2.output.contains("codex"));
@@ -488,6 +537,7 @@ mod tests {
                 session_id: Some(session_a),
                 input_chunks: &["echo $CODEX_INTERACTIVE_SHELL_VAR\n".to_string()],
                 timeout_ms: Some(2_500),
+                sandbox: no_sandbox(),
             })
             .await?;
         assert!(out_3.output.contains("codex"));
@@ -505,6 +555,7 @@ mod tests {
                 session_id: None,
                 input_chunks: &["bash".to_string(), "-i".to_string()],
                 timeout_ms: Some(2_500),
+                sandbox: no_sandbox(),
             })
             .await?;
         let session_id = open_shell.session_id.expect("expected session id");
@@ -517,6 +568,7 @@ mod tests {
                     "CODEX_INTERACTIVE_SHELL_VAR=codex\n".to_string(),
                 ],
                 timeout_ms: Some(2_500),
+                sandbox: no_sandbox(),
             })
             .await?;
 
@@ -525,6 +577,7 @@ mod tests {
                 session_id: Some(session_id),
                 input_chunks: &["sleep 5 && echo $CODEX_INTERACTIVE_SHELL_VAR\n".to_string()],
                 timeout_ms: Some(10),
+                sandbox: no_sandbox(),
             })
             .await?;
         assert!(!out_2.output.contains("codex"));
@@ -537,6 +590,7 @@ mod tests {
                 session_id: Some(session_id),
                 input_chunks: &empty,
                 timeout_ms: Some(100),
+                sandbox: no_sandbox(),
             })
             .await?;
 
@@ -555,6 +609,7 @@ mod tests {
                 session_id: None,
                 input_chunks: &["echo".to_string(), "codex".to_string()],
                 timeout_ms: Some(120_000),
+                sandbox: no_sandbox(),
             })
             .await?;
 
@@ -575,6 +630,7 @@ mod tests {
                 session_id: None,
                 input_chunks: &["/bin/echo".to_string(), "codex".to_string()],
                 timeout_ms: Some(2_500),
+                sandbox: no_sandbox(),
             })
             .await?;
 
@@ -596,6 +652,7 @@ mod tests {
                 session_id: None,
                 input_chunks: &["/bin/bash".to_string(), "-i".to_string()],
                 timeout_ms: Some(2_500),
+                sandbox: no_sandbox(),
             })
             .await?;
         let session_id = open_shell.session_id.expect("expected session id");
@@ -605,6 +662,7 @@ mod tests {
                 session_id: Some(session_id),
                 input_chunks: &["exit\n".to_string()],
                 timeout_ms: Some(2_500),
+                sandbox: no_sandbox(),
             })
             .await?;
 
@@ -615,6 +673,7 @@ mod tests {
                 session_id: Some(session_id),
                 input_chunks: &[],
                 timeout_ms: Some(100),
+                sandbox: no_sandbox(),
             })
             .await
             .expect_err("expected unknown session error");
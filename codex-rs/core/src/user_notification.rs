@@ -1,4 +1,14 @@
+use std::path::PathBuf;
+
+use codex_protocol::protocol::TurnAbortReason;
 use serde::Serialize;
+use tracing::error;
+use tracing::warn;
+
+use crate::config_types::NotificationKind;
+use crate::config_types::NotificationWebhook;
+use crate::config_types::Notifications;
+use crate::default_client::create_client;
 
 /// User can configure a program that will receive notifications. Each
 /// notification is serialized as JSON and passed as an argument to the
@@ -16,11 +26,137 @@ pub(crate) enum UserNotification {
         /// The last message sent by the assistant in the turn.
         last_assistant_message: Option<String>,
     },
+
+    /// The agent is blocked until the user approves or denies a command.
+    #[serde(rename_all = "kebab-case")]
+    ExecApprovalRequest {
+        turn_id: String,
+        call_id: String,
+        command: Vec<String>,
+        cwd: PathBuf,
+        reason: Option<String>,
+    },
+
+    /// The agent is blocked until the user approves or denies a patch.
+    #[serde(rename_all = "kebab-case")]
+    ApplyPatchApprovalRequest {
+        turn_id: String,
+        call_id: String,
+
+        /// Files the patch would add, update or delete.
+        files: Vec<PathBuf>,
+        reason: Option<String>,
+    },
+
+    /// The connection to the model failed and the turn is being retried.
+    #[serde(rename_all = "kebab-case")]
+    StreamError { turn_id: String, message: String },
+
+    /// The turn ended before the agent finished it.
+    #[serde(rename_all = "kebab-case")]
+    TurnAborted {
+        turn_id: String,
+        reason: TurnAbortReason,
+    },
+}
+
+impl UserNotification {
+    pub(crate) fn kind(&self) -> NotificationKind {
+        match self {
+            UserNotification::AgentTurnComplete { .. } => NotificationKind::AgentTurnComplete,
+            UserNotification::ExecApprovalRequest { .. } => NotificationKind::ExecApprovalRequest,
+            UserNotification::ApplyPatchApprovalRequest { .. } => {
+                NotificationKind::ApplyPatchApprovalRequest
+            }
+            UserNotification::StreamError { .. } => NotificationKind::StreamError,
+            UserNotification::TurnAborted { .. } => NotificationKind::TurnAborted,
+        }
+    }
+}
+
+/// Delivers notifications of the configured kinds to the `notify` program
+/// and/or the webhook. Failures are logged but otherwise ignored so that
+/// notification issues do not interfere with the main workflow.
+#[derive(Debug, Default)]
+pub(crate) struct UserNotifier {
+    /// External notifier command (will be passed as args to exec()). When
+    /// `None` this sink is disabled.
+    notify: Option<Vec<String>>,
+    kinds: Vec<NotificationKind>,
+    webhook: Option<NotificationWebhook>,
+    client: reqwest::Client,
+}
+
+impl UserNotifier {
+    pub(crate) fn new(notify: Option<Vec<String>>, notifications: &Notifications) -> Self {
+        Self {
+            notify: notify.filter(|command| !command.is_empty()),
+            kinds: notifications.kinds.clone(),
+            webhook: notifications.webhook.clone(),
+            client: create_client(),
+        }
+    }
+
+    pub(crate) fn notify(&self, notification: &UserNotification) {
+        if !self.kinds.contains(&notification.kind()) {
+            return;
+        }
+        if self.notify.is_none() && self.webhook.is_none() {
+            return;
+        }
+
+        let Ok(json) = serde_json::to_string(notification) else {
+            error!("failed to serialise notification payload");
+            return;
+        };
+
+        if let Some(notify_command) = &self.notify {
+            let mut command = std::process::Command::new(&notify_command[0]);
+            if notify_command.len() > 1 {
+                command.args(&notify_command[1..]);
+            }
+            command.arg(&json);
+
+            // Fire-and-forget – we do not wait for completion.
+            if let Err(e) = command.spawn() {
+                warn!("failed to spawn notifier '{}': {e}", notify_command[0]);
+            }
+        }
+
+        if let Some(webhook) = &self.webhook {
+            let mut request = self
+                .client
+                .post(&webhook.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(json);
+            for (name, value) in &webhook.http_headers {
+                request = request.header(name, value);
+            }
+            let url = webhook.url.clone();
+            tokio::spawn(async move {
+                match request.send().await {
+                    Ok(response) if !response.status().is_success() => {
+                        warn!("notification webhook {url} returned {}", response.status());
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("failed to deliver notification to {url}: {e}"),
+                }
+            });
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
+    use std::time::Duration;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::body_json;
+    use wiremock::matchers::header;
+    use wiremock::matchers::method;
 
     #[test]
     fn test_user_notification() {
@@ -37,4 +173,76 @@ mod tests {
             r#"{"type":"agent-turn-complete","turn-id":"12345","input-messages":["Rename `foo` to `bar` and update the callsites."],"last-assistant-message":"Rename complete and verified `cargo build` succeeds."}"#
         );
     }
+
+    #[test]
+    fn serializes_blocking_notifications() {
+        let notification = UserNotification::ExecApprovalRequest {
+            turn_id: "7".to_string(),
+            call_id: "call-1".to_string(),
+            command: vec!["cargo".to_string(), "publish".to_string()],
+            cwd: PathBuf::from("/repo"),
+            reason: None,
+        };
+        assert_eq!(
+            serde_json::to_string(&notification).unwrap(),
+            r#"{"type":"exec-approval-request","turn-id":"7","call-id":"call-1","command":["cargo","publish"],"cwd":"/repo","reason":null}"#
+        );
+
+        let notification = UserNotification::TurnAborted {
+            turn_id: "7".to_string(),
+            reason: TurnAbortReason::Interrupted,
+        };
+        assert_eq!(
+            serde_json::to_string(&notification).unwrap(),
+            r#"{"type":"turn-aborted","turn-id":"7","reason":"interrupted"}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn posts_configured_kinds_to_webhook() {
+        let server = MockServer::start().await;
+        let stream_error = UserNotification::StreamError {
+            turn_id: "3".to_string(),
+            message: "stream disconnected".to_string(),
+        };
+        Mock::given(method("POST"))
+            .and(header("authorization", "Bearer secret"))
+            .and(body_json(serde_json::to_value(&stream_error).unwrap()))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let notifier = UserNotifier::new(
+            None,
+            &Notifications {
+                kinds: vec![NotificationKind::StreamError],
+                webhook: Some(NotificationWebhook {
+                    url: format!("{}/hook", server.uri()),
+                    http_headers: HashMap::from([(
+                        "Authorization".to_string(),
+                        "Bearer secret".to_string(),
+                    )]),
+                }),
+            },
+        );
+        // Not in `kinds`, so it must not be delivered.
+        notifier.notify(&UserNotification::AgentTurnComplete {
+            turn_id: "3".to_string(),
+            input_messages: Vec::new(),
+            last_assistant_message: None,
+        });
+        notifier.notify(&stream_error);
+
+        for _ in 0..50 {
+            if !server.received_requests().await.unwrap().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        // Give a wrongly delivered notification time to arrive as well.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+        server.verify().await;
+    }
 }
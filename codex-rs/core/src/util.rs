@@ -11,3 +11,9 @@ pub(crate) fn backoff(attempt: u64) -> Duration {
     let jitter = rand::rng().random_range(0.9..1.1);
     Duration::from_millis((base as f64 * jitter) as u64)
 }
+
+/// Splits a `data:<media type>;base64,<data>` URL into its media type and
+/// base64 payload.
+pub(crate) fn split_base64_data_url(url: &str) -> Option<(&str, &str)> {
+    url.strip_prefix("data:")?.split_once(";base64,")
+}
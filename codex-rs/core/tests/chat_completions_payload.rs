@@ -139,6 +139,7 @@ fn function_call() -> ResponseItem {
         name: "f".to_string(),
         arguments: "{}".to_string(),
         call_id: "c1".to_string(),
+        thought_signature: None,
     }
 }
 
@@ -6,6 +6,14 @@ use codex_core::CodexConversation;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::config::ConfigToml;
+use serde_json::Value;
+use serde_json::json;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::Request;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
 
 /// Returns a default `Config` whose on-disk state is confined to the provided
 /// temporary directory. Using a per-test directory keeps tests hermetic and
@@ -124,3 +132,46 @@ where
         }
     }
 }
+
+/// Starts an MCP server speaking Streamable HTTP at `/mcp` that offers the
+/// tools `echo` and `shout`. Every tool call returns the same text.
+pub async fn start_stub_mcp_server() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/mcp"))
+        .respond_with(stub_mcp_response)
+        .mount(&server)
+        .await;
+    server
+}
+
+/// Answers an MCP request, echoing its id.
+fn stub_mcp_response(request: &Request) -> ResponseTemplate {
+    let body: Value = serde_json::from_slice(&request.body).expect("MCP request is JSON");
+    let Some(id) = body.get("id").cloned() else {
+        // Notifications are only acknowledged.
+        return ResponseTemplate::new(202);
+    };
+    let result = match body["method"].as_str() {
+        Some("initialize") => json!({
+            "capabilities": { "tools": {} },
+            "protocolVersion": "2025-03-26",
+            "serverInfo": { "name": "stub", "version": "1.0.0" },
+        }),
+        Some("tools/list") => json!({
+            "tools": [
+                { "name": "echo", "inputSchema": { "type": "object" } },
+                { "name": "shout", "inputSchema": { "type": "object" } },
+            ],
+        }),
+        Some("tools/call") => json!({
+            "content": [{ "type": "text", "text": "stub tool output" }],
+        }),
+        _ => json!({}),
+    };
+    ResponseTemplate::new(200).set_body_json(json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "result": result,
+    }))
+}
@@ -0,0 +1,139 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+//! Files changed during a turn, including by shell commands, can be restored
+//! to the checkpoint taken before the turn.
+
+use std::path::Path;
+use std::process::Command;
+
+use codex_core::CodexAuth;
+use codex_core::ConversationManager;
+use codex_core::ModelProviderInfo;
+use codex_core::built_in_model_providers;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::protocol::SandboxPolicy;
+use codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
+use core_test_support::load_default_config_for_test;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use tempfile::TempDir;
+use wiremock::MockServer;
+use wiremock::matchers::any;
+use wiremock::matchers::body_string_contains;
+
+use super::compact::ev_assistant_message;
+use super::compact::ev_completed;
+use super::compact::mount_sse_once;
+use super::compact::sse;
+
+fn create_repo(dir: &Path) {
+    let output = Command::new("git")
+        .arg("init")
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git init failed: {output:?}");
+    std::fs::write(dir.join("README.md"), "original\n").unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn undo_restores_files_changed_by_shell_commands() {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let server = MockServer::start().await;
+    // The follow-up request, carrying the output of the command.
+    mount_sse_once(
+        &server,
+        body_string_contains("function_call_output"),
+        sse(vec![
+            ev_assistant_message("m1", "edited"),
+            ev_completed("r2"),
+        ]),
+    )
+    .await;
+    // The first request runs a command that edits and creates files.
+    let arguments = json!({
+        "command": ["bash", "-c", "echo changed > README.md && echo new > created.txt"],
+    })
+    .to_string();
+    mount_sse_once(
+        &server,
+        any(),
+        sse(vec![
+            json!({
+                "type": "response.output_item.done",
+                "item": {
+                    "type": "function_call",
+                    "call_id": "call-shell",
+                    "name": "shell",
+                    "arguments": arguments,
+                }
+            }),
+            ev_completed("r1"),
+        ]),
+    )
+    .await;
+
+    let home = TempDir::new().unwrap();
+    let repo = TempDir::new().unwrap();
+    create_repo(repo.path());
+    let mut config = load_default_config_for_test(&home);
+    config.model_provider = ModelProviderInfo {
+        base_url: Some(format!("{}/v1", server.uri())),
+        ..built_in_model_providers()["openai"].clone()
+    };
+    config.cwd = repo.path().to_path_buf();
+    config.approval_policy = AskForApproval::Never;
+    config.sandbox_policy = SandboxPolicy::DangerFullAccess;
+    config.checkpoints = true;
+    let codex = ConversationManager::with_auth(CodexAuth::from_api_key("dummy"))
+        .new_conversation(config)
+        .await
+        .unwrap()
+        .conversation;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "edit the files".into(),
+            }],
+        })
+        .await
+        .unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    assert_eq!(
+        std::fs::read_to_string(repo.path().join("README.md")).unwrap(),
+        "changed\n"
+    );
+    assert!(repo.path().join("created.txt").exists());
+
+    codex.submit(Op::Undo).await.unwrap();
+    let EventMsg::CheckpointRestored(restored) = wait_for_event(&codex, |ev| {
+        matches!(ev, EventMsg::CheckpointRestored(_) | EventMsg::Error(_))
+    })
+    .await
+    else {
+        panic!("expected the checkpoint to be restored");
+    };
+    assert_eq!(restored.turn, 0);
+    assert_eq!(
+        std::fs::read_to_string(repo.path().join("README.md")).unwrap(),
+        "original\n"
+    );
+    assert!(!repo.path().join("created.txt").exists());
+
+    // The checkpoint is gone once it was restored.
+    codex
+        .submit(Op::RestoreCheckpoint { turn: 0 })
+        .await
+        .unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::Error(_))).await;
+}
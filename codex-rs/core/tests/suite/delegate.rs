@@ -0,0 +1,169 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+//! The `delegate` tool runs workers in sub-conversations and returns their
+//! final messages.
+
+use std::time::Duration;
+
+use codex_core::CodexAuth;
+use codex_core::ConversationManager;
+use codex_core::ModelProviderInfo;
+use codex_core::built_in_model_providers;
+use codex_core::protocol::DelegateEventEvent;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
+use core_test_support::load_default_config_for_test;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+use tempfile::TempDir;
+use wiremock::MockServer;
+use wiremock::matchers::any;
+use wiremock::matchers::body_string_contains;
+
+use super::compact::ev_assistant_message;
+use super::compact::ev_completed;
+use super::compact::mount_sse_once;
+use super::compact::sse;
+
+const WORKER_PROMPT: &str = "WORKER_TASK";
+const WORKER_REPLY: &str = "WORKER_REPLY";
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn delegate_runs_worker_and_returns_its_final_message() {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let server = MockServer::start().await;
+    // The parent's follow-up request, carrying the output of the call.
+    mount_sse_once(
+        &server,
+        body_string_contains("function_call_output"),
+        sse(vec![
+            ev_assistant_message("m2", "all done"),
+            ev_completed("r3"),
+        ]),
+    )
+    .await;
+    // The worker's request.
+    mount_sse_once(
+        &server,
+        body_string_contains(WORKER_PROMPT),
+        sse(vec![
+            ev_assistant_message("m1", WORKER_REPLY),
+            ev_completed("r2"),
+        ]),
+    )
+    .await;
+    // The parent's first request.
+    let arguments = json!({
+        "tasks": [{ "prompt": WORKER_PROMPT, "sandbox": "read-only" }]
+    })
+    .to_string();
+    mount_sse_once(
+        &server,
+        any(),
+        sse(vec![
+            json!({
+                "type": "response.output_item.done",
+                "item": {
+                    "type": "function_call",
+                    "call_id": "call-delegate",
+                    "name": "delegate",
+                    "arguments": arguments,
+                }
+            }),
+            ev_completed("r1"),
+        ]),
+    )
+    .await;
+
+    let model_provider = ModelProviderInfo {
+        base_url: Some(format!("{}/v1", server.uri())),
+        ..built_in_model_providers()["openai"].clone()
+    };
+    let home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&home);
+    config.model_provider = model_provider;
+    config.include_delegate_tool = true;
+    let codex = ConversationManager::with_auth(CodexAuth::from_api_key("dummy"))
+        .new_conversation(config)
+        .await
+        .unwrap()
+        .conversation;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "fan out".into(),
+            }],
+        })
+        .await
+        .unwrap();
+
+    let mut delegate_events = Vec::new();
+    loop {
+        let event = tokio::time::timeout(Duration::from_secs(10), codex.next_event())
+            .await
+            .expect("timeout waiting for event")
+            .unwrap();
+        match event.msg {
+            EventMsg::TaskComplete(_) => break,
+            EventMsg::DelegateBegin(ev) => {
+                assert_eq!(ev.call_id, "call-delegate");
+                assert_eq!(ev.tasks, vec![WORKER_PROMPT.to_string()]);
+                delegate_events.push("begin".to_string());
+            }
+            EventMsg::DelegateEvent(DelegateEventEvent {
+                call_id,
+                worker,
+                msg,
+            }) => {
+                assert_eq!((call_id.as_str(), worker), ("call-delegate", 0));
+                delegate_events.push(msg.to_string());
+            }
+            EventMsg::DelegateEnd(ev) => {
+                assert_eq!(ev.results, vec![Ok(WORKER_REPLY.to_string())]);
+                delegate_events.push("end".to_string());
+            }
+            _ => {}
+        }
+    }
+
+    // The worker's events are nested between begin and end.
+    assert_eq!(delegate_events.first().map(String::as_str), Some("begin"));
+    assert_eq!(delegate_events.last().map(String::as_str), Some("end"));
+    for kind in ["task_started", "agent_message", "task_complete"] {
+        assert!(
+            delegate_events.iter().any(|event| event == kind),
+            "missing nested {kind}: {delegate_events:?}"
+        );
+    }
+
+    // The parent's follow-up request carries the worker's final message.
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 3);
+    let follow_up = requests
+        .iter()
+        .map(|request| request.body_json::<Value>().unwrap())
+        .find(|body| body.to_string().contains("function_call_output"))
+        .expect("follow-up request");
+    let output = follow_up["input"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|item| item["type"] == "function_call_output")
+        .unwrap()["output"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert_eq!(
+        serde_json::from_str::<Value>(&output).unwrap(),
+        json!({ "results": [{ "task": 1, "message": WORKER_REPLY }] })
+    );
+}
@@ -0,0 +1,174 @@
+//! Streams a turn from a mock Gemini API server.
+
+use std::sync::Arc;
+
+use codex_core::ContentItem;
+use codex_core::ModelClient;
+use codex_core::ModelProviderInfo;
+use codex_core::Prompt;
+use codex_core::ResponseEvent;
+use codex_core::ResponseItem;
+use codex_core::WireApi;
+use codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
+use codex_protocol::mcp_protocol::ConversationId;
+use codex_protocol::models::ReasoningItemReasoningSummary;
+use core_test_support::load_default_config_for_test;
+use futures::StreamExt;
+use serde_json::Value;
+use tempfile::TempDir;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::header_exists;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+use wiremock::matchers::query_param;
+
+const SSE_BODY: &str = concat!(
+    "data: {\"candidates\":[{\"content\":{\"role\":\"model\",\"parts\":[{\"text\":\"List the files.\",\"thought\":true}]}}],\"responseId\":\"resp_1\"}\n\n",
+    "data: {\"candidates\":[{\"content\":{\"role\":\"model\",\"parts\":[{\"text\":\"Running \"}]}}],\"responseId\":\"resp_1\"}\n\n",
+    "data: {\"candidates\":[{\"content\":{\"role\":\"model\",\"parts\":[{\"text\":\"ls\"},{\"functionCall\":{\"name\":\"shell\",\"args\":{\"command\":[\"ls\"]}},\"thoughtSignature\":\"c2lnbmF0dXJl\"}]},\"finishReason\":\"STOP\"}],\"usageMetadata\":{\"promptTokenCount\":100,\"cachedContentTokenCount\":40,\"candidatesTokenCount\":10,\"thoughtsTokenCount\":20,\"totalTokenCount\":130},\"responseId\":\"resp_1\"}\n\n",
+);
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn streams_thoughts_text_and_function_call() {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1beta/models/gemini-2.5-pro:streamGenerateContent"))
+        .and(query_param("alt", "sse"))
+        .and(header_exists("x-goog-api-key"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_raw(SSE_BODY, "text/event-stream"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = ModelProviderInfo {
+        name: "gemini".into(),
+        base_url: Some(format!("{}/v1beta", server.uri())),
+        // Any variable that is set works as the API key.
+        env_key: Some("PATH".into()),
+        env_key_instructions: None,
+        wire_api: WireApi::Gemini,
+        query_params: None,
+        http_headers: None,
+        env_http_headers: None,
+        request_max_retries: Some(0),
+        stream_max_retries: Some(0),
+        stream_idle_timeout_ms: Some(5_000),
+        requires_openai_auth: false,
+    };
+
+    let codex_home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&codex_home);
+    config.model = "gemini-2.5-pro".to_string();
+    config.model_family.slug = config.model.clone();
+    config.model_family.supports_reasoning_summaries = true;
+    config.model_provider = provider.clone();
+    let effort = config.model_reasoning_effort;
+    let summary = config.model_reasoning_summary;
+    let client = ModelClient::new(
+        Arc::new(config),
+        None,
+        provider,
+        effort,
+        summary,
+        ConversationId::new(),
+    );
+
+    let mut prompt = Prompt::default();
+    prompt.input = vec![ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: "list files".to_string(),
+        }],
+    }];
+
+    let mut stream = client.stream(&prompt).await.unwrap();
+    let mut events = Vec::new();
+    while let Some(event) = stream.next().await {
+        events.push(event.unwrap());
+    }
+
+    assert_eq!(events.len(), 7, "unexpected events: {events:?}");
+    assert!(
+        matches!(&events[0], ResponseEvent::ReasoningSummaryDelta(delta) if delta == "List the files.")
+    );
+    assert!(matches!(&events[1], ResponseEvent::OutputTextDelta(delta) if delta == "Running "));
+    assert!(matches!(&events[2], ResponseEvent::OutputTextDelta(delta) if delta == "ls"));
+    match &events[3] {
+        ResponseEvent::OutputItemDone(ResponseItem::Reasoning { summary, .. }) => assert_eq!(
+            summary,
+            &vec![ReasoningItemReasoningSummary::SummaryText {
+                text: "List the files.".to_string()
+            }]
+        ),
+        other => panic!("expected reasoning item, got {other:?}"),
+    }
+    match &events[4] {
+        ResponseEvent::OutputItemDone(ResponseItem::Message { content, .. }) => assert_eq!(
+            content,
+            &vec![ContentItem::OutputText {
+                text: "Running ls".to_string()
+            }]
+        ),
+        other => panic!("expected message item, got {other:?}"),
+    }
+    match &events[5] {
+        ResponseEvent::OutputItemDone(ResponseItem::FunctionCall {
+            name,
+            arguments,
+            call_id,
+            thought_signature,
+            ..
+        }) => {
+            assert_eq!(name, "shell");
+            assert_eq!(arguments, r#"{"command":["ls"]}"#);
+            assert!(!call_id.is_empty());
+            assert_eq!(thought_signature.as_deref(), Some("c2lnbmF0dXJl"));
+        }
+        other => panic!("expected function call, got {other:?}"),
+    }
+    match &events[6] {
+        ResponseEvent::Completed {
+            response_id,
+            token_usage: Some(usage),
+        } => {
+            assert_eq!(response_id, "resp_1");
+            assert_eq!(usage.input_tokens, 100);
+            assert_eq!(usage.cached_input_tokens, 40);
+            assert_eq!(usage.output_tokens, 30);
+            assert_eq!(usage.reasoning_output_tokens, 20);
+        }
+        other => panic!("expected completed with usage, got {other:?}"),
+    }
+
+    let requests = server.received_requests().await.unwrap();
+    let body: Value = requests[0].body_json().unwrap();
+    assert_eq!(
+        body["generationConfig"]["thinkingConfig"]["includeThoughts"],
+        true
+    );
+    assert!(
+        body["systemInstruction"]["parts"][0]["text"]
+            .as_str()
+            .is_some_and(|s| !s.is_empty())
+    );
+    assert_eq!(
+        body["contents"],
+        serde_json::json!([
+            {"role": "user", "parts": [{"text": "list files"}]}
+        ])
+    );
+}
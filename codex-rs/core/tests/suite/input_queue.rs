@@ -0,0 +1,322 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+//! Steering the running task with user input and queueing input as the next task.
+
+use std::time::Duration;
+
+use codex_core::CodexAuth;
+use codex_core::CodexConversation;
+use codex_core::ConversationManager;
+use codex_core::ModelProviderInfo;
+use codex_core::WireApi;
+use codex_core::protocol::Event;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::InputQueueUpdatedEvent;
+use codex_core::protocol::Op;
+use codex_core::protocol::QueuedInput;
+use codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
+use core_test_support::load_default_config_for_test;
+use core_test_support::load_sse_fixture_with_id;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::body_string_contains;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+/// Delay of the first response, so that input can be sent while it runs.
+const FIRST_RESPONSE_DELAY: Duration = Duration::from_millis(500);
+
+fn sse_completed(id: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200)
+        .insert_header("content-type", "text/event-stream")
+        .set_body_raw(
+            load_sse_fixture_with_id("tests/fixtures/completed_template.json", id),
+            "text/event-stream",
+        )
+}
+
+fn text(text: &str) -> Vec<InputItem> {
+    vec![InputItem::Text {
+        text: text.to_string(),
+    }]
+}
+
+async fn start_conversation(
+    server: &MockServer,
+    home: &TempDir,
+) -> std::sync::Arc<CodexConversation> {
+    let provider = ModelProviderInfo {
+        name: "mock-openai".into(),
+        base_url: Some(format!("{}/v1", server.uri())),
+        env_key: Some("PATH".into()),
+        env_key_instructions: None,
+        wire_api: WireApi::Responses,
+        query_params: None,
+        http_headers: None,
+        env_http_headers: None,
+        request_max_retries: Some(0),
+        stream_max_retries: Some(0),
+        stream_idle_timeout_ms: Some(5_000),
+        requires_openai_auth: false,
+    };
+    let mut config = load_default_config_for_test(home);
+    config.model_provider = provider;
+    ConversationManager::with_auth(CodexAuth::from_api_key("Test API Key"))
+        .new_conversation(config)
+        .await
+        .unwrap()
+        .conversation
+}
+
+/// Waits until the first request reached the server, so that input sent
+/// afterwards cannot be part of it.
+async fn wait_for_first_request(server: &MockServer) {
+    for _ in 0..100 {
+        if !server.received_requests().await.unwrap().is_empty() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("no request reached the server");
+}
+
+/// Collects events until `count` tasks have completed.
+async fn events_until_tasks_complete(codex: &CodexConversation, count: usize) -> Vec<Event> {
+    let mut events = Vec::new();
+    let mut completed = 0;
+    while completed < count {
+        let event = tokio::time::timeout(Duration::from_secs(10), codex.next_event())
+            .await
+            .expect("timeout waiting for event")
+            .unwrap();
+        if matches!(event.msg, EventMsg::TaskComplete(_)) {
+            completed += 1;
+        }
+        events.push(event);
+    }
+    events
+}
+
+fn input_queue_updates(events: &[Event]) -> Vec<(&str, &InputQueueUpdatedEvent)> {
+    events
+        .iter()
+        .filter_map(|event| match &event.msg {
+            EventMsg::InputQueueUpdated(update) => Some((event.id.as_str(), update)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn user_input_steers_running_task() {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .and(body_string_contains("steer me"))
+        .respond_with(sse_completed("resp_2"))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .respond_with(sse_completed("resp_1").set_delay(FIRST_RESPONSE_DELAY))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let home = TempDir::new().unwrap();
+    let codex = start_conversation(&server, &home).await;
+
+    let task_id = codex
+        .submit(Op::UserInput {
+            items: text("first message"),
+        })
+        .await
+        .unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskStarted(_))).await;
+    wait_for_first_request(&server).await;
+    let steer_id = codex
+        .submit(Op::UserInput {
+            items: text("steer me"),
+        })
+        .await
+        .unwrap();
+
+    // Although the first response has no tool calls, the steering input runs
+    // another turn of the same task instead of starting a new one.
+    let events = events_until_tasks_complete(&codex, 1).await;
+    assert_eq!(events.last().unwrap().id, task_id);
+    assert!(
+        !events
+            .iter()
+            .any(|event| matches!(event.msg, EventMsg::TaskStarted(_))),
+        "{events:?}"
+    );
+    assert_eq!(
+        input_queue_updates(&events),
+        vec![
+            (
+                steer_id.as_str(),
+                &InputQueueUpdatedEvent {
+                    steering: vec![text("steer me")],
+                    queued: Vec::new(),
+                }
+            ),
+            (task_id.as_str(), &InputQueueUpdatedEvent::default()),
+        ]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn queued_input_runs_as_next_task_and_can_be_edited() {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .and(body_string_contains("edited message"))
+        .respond_with(sse_completed("resp_2"))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .respond_with(sse_completed("resp_1").set_delay(FIRST_RESPONSE_DELAY))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let home = TempDir::new().unwrap();
+    let codex = start_conversation(&server, &home).await;
+
+    let task_id = codex
+        .submit(Op::UserInput {
+            items: text("first message"),
+        })
+        .await
+        .unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskStarted(_))).await;
+    wait_for_first_request(&server).await;
+    let kept_id = codex
+        .submit(Op::QueueUserInput {
+            items: text("queued message"),
+        })
+        .await
+        .unwrap();
+    let removed_id = codex
+        .submit(Op::QueueUserInput {
+            items: text("removed message"),
+        })
+        .await
+        .unwrap();
+    let update_id = codex
+        .submit(Op::UpdateQueuedInput {
+            id: kept_id.clone(),
+            items: text("edited message"),
+        })
+        .await
+        .unwrap();
+    let remove_id = codex
+        .submit(Op::RemoveQueuedInput {
+            id: removed_id.clone(),
+        })
+        .await
+        .unwrap();
+
+    let events = events_until_tasks_complete(&codex, 2).await;
+    let completed: Vec<&str> = events
+        .iter()
+        .filter(|event| matches!(event.msg, EventMsg::TaskComplete(_)))
+        .map(|event| event.id.as_str())
+        .collect();
+    assert_eq!(completed, vec![task_id.as_str(), kept_id.as_str()]);
+
+    let queued = |items: Vec<(&str, &str)>| InputQueueUpdatedEvent {
+        steering: Vec::new(),
+        queued: items
+            .into_iter()
+            .map(|(id, message)| QueuedInput {
+                id: id.to_string(),
+                items: text(message),
+            })
+            .collect(),
+    };
+    let updates: Vec<(&str, InputQueueUpdatedEvent)> = input_queue_updates(&events)
+        .into_iter()
+        .map(|(id, update)| (id, update.clone()))
+        .collect();
+    assert_eq!(
+        updates,
+        vec![
+            (
+                kept_id.as_str(),
+                queued(vec![(kept_id.as_str(), "queued message")])
+            ),
+            (
+                removed_id.as_str(),
+                queued(vec![
+                    (kept_id.as_str(), "queued message"),
+                    (removed_id.as_str(), "removed message")
+                ])
+            ),
+            (
+                update_id.as_str(),
+                queued(vec![
+                    (kept_id.as_str(), "edited message"),
+                    (removed_id.as_str(), "removed message")
+                ])
+            ),
+            (
+                remove_id.as_str(),
+                queued(vec![(kept_id.as_str(), "edited message")])
+            ),
+            (kept_id.as_str(), queued(Vec::new())),
+        ]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn queued_input_starts_right_away_when_idle() {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .respond_with(sse_completed("resp_1"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let home = TempDir::new().unwrap();
+    let codex = start_conversation(&server, &home).await;
+
+    let queued_id = codex
+        .submit(Op::QueueUserInput {
+            items: text("queued message"),
+        })
+        .await
+        .unwrap();
+    let events = events_until_tasks_complete(&codex, 1).await;
+    assert_eq!(events.last().unwrap().id, queued_id);
+}
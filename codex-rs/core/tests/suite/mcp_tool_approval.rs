@@ -0,0 +1,263 @@
+#![expect(clippy::unwrap_used)]
+
+use codex_core::CodexAuth;
+use codex_core::CodexConversation;
+use codex_core::ConversationManager;
+use codex_core::ModelProviderInfo;
+use codex_core::built_in_model_providers;
+use codex_core::config_types::McpHttpTransport;
+use codex_core::config_types::McpServerConfig;
+use codex_core::config_types::McpServerTransportConfig;
+use codex_core::config_types::McpToolApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::McpToolCallApprovalRequestEvent;
+use codex_core::protocol::Op;
+use codex_core::protocol::ReviewDecision;
+use codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
+use core_test_support::load_default_config_for_test;
+use core_test_support::start_stub_mcp_server;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+use std::time::Duration;
+use tempfile::TempDir;
+use wiremock::MockServer;
+use wiremock::matchers::any;
+use wiremock::matchers::body_string_contains;
+
+use super::compact::ev_assistant_message;
+use super::compact::ev_completed;
+use super::compact::mount_sse_once;
+use super::compact::sse;
+
+/// Names of the tools the stub MCP server was asked to call.
+async fn called_tools(mcp_server: &MockServer) -> Vec<String> {
+    mcp_server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter_map(|request| {
+            let body: Value = serde_json::from_slice(&request.body).ok()?;
+            (body["method"] == "tools/call")
+                .then(|| body["params"]["name"].as_str().unwrap().to_string())
+        })
+        .collect()
+}
+
+fn ev_function_call(call_id: &str, name: &str) -> Value {
+    json!({
+        "type": "response.output_item.done",
+        "item": {
+            "type": "function_call",
+            "call_id": call_id,
+            "name": name,
+            "arguments": "{}"
+        }
+    })
+}
+
+/// Output the model received for the tool call `call_id`.
+async fn function_call_output(model_server: &MockServer, call_id: &str) -> Value {
+    model_server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter_map(|request| serde_json::from_slice::<Value>(&request.body).ok())
+        .flat_map(|body| body["input"].as_array().cloned().unwrap_or_default())
+        .find(|item| item["type"] == "function_call_output" && item["call_id"] == call_id)
+        .map(|item| item["output"].clone())
+        .unwrap_or_else(|| panic!("no output for {call_id}"))
+}
+
+async fn start_conversation(
+    model_server: &MockServer,
+    mcp_server: &MockServer,
+    home: &TempDir,
+) -> std::sync::Arc<CodexConversation> {
+    let model_provider = ModelProviderInfo {
+        base_url: Some(format!("{}/v1", model_server.uri())),
+        ..built_in_model_providers()["openai"].clone()
+    };
+    let mut config = load_default_config_for_test(home);
+    config.model_provider = model_provider;
+    config.mcp_servers.insert(
+        "stub".to_string(),
+        McpServerConfig {
+            transport: McpServerTransportConfig::Http {
+                url: format!("{}/mcp", mcp_server.uri()),
+                transport: McpHttpTransport::StreamableHttp,
+                http_headers: None,
+                env_http_headers: None,
+                bearer_token_env_var: None,
+            },
+            startup_timeout_ms: None,
+            enabled_tools: None,
+            disabled_tools: Vec::new(),
+            approval: McpToolApproval::Always,
+            sandbox: None,
+        },
+    );
+    let conversation_manager = ConversationManager::with_auth(CodexAuth::from_api_key("dummy"));
+    conversation_manager
+        .new_conversation(config)
+        .await
+        .unwrap()
+        .conversation
+}
+
+/// Runs one turn, answering the approval requests in order with `decisions`,
+/// and returns the requests.
+async fn run_turn(
+    codex: &CodexConversation,
+    decisions: Vec<ReviewDecision>,
+) -> Vec<McpToolCallApprovalRequestEvent> {
+    codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "use the stub tools".into(),
+            }],
+        })
+        .await
+        .unwrap();
+
+    let mut decisions = decisions.into_iter();
+    let mut requests = Vec::new();
+    loop {
+        let event = tokio::time::timeout(Duration::from_secs(10), codex.next_event())
+            .await
+            .expect("timeout waiting for event")
+            .unwrap();
+        match event.msg {
+            EventMsg::McpToolCallApprovalRequest(request) => {
+                let decision = decisions.next().expect("unexpected approval request");
+                requests.push(request);
+                codex
+                    .submit(Op::McpToolCallApproval {
+                        id: event.id,
+                        decision,
+                    })
+                    .await
+                    .unwrap();
+            }
+            EventMsg::TaskComplete(_) => return requests,
+            _ => {}
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn denied_mcp_tool_call_reports_rejection_to_model() {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let model_server = MockServer::start().await;
+    let mcp_server = start_stub_mcp_server().await;
+    mount_sse_once(
+        &model_server,
+        body_string_contains("call-1"),
+        sse(vec![
+            ev_assistant_message("m1", "the tool was rejected"),
+            ev_completed("r2"),
+        ]),
+    )
+    .await;
+    mount_sse_once(
+        &model_server,
+        any(),
+        sse(vec![
+            ev_function_call("call-1", "stub__echo"),
+            ev_completed("r1"),
+        ]),
+    )
+    .await;
+
+    let home = TempDir::new().unwrap();
+    let codex = start_conversation(&model_server, &mcp_server, &home).await;
+    let requests = run_turn(&codex, vec![ReviewDecision::Denied]).await;
+
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].call_id, "call-1");
+    assert_eq!(requests[0].invocation.server, "stub");
+    assert_eq!(requests[0].invocation.tool, "echo");
+    assert_eq!(
+        function_call_output(&model_server, "call-1").await,
+        json!("tool call rejected by user")
+    );
+    assert_eq!(called_tools(&mcp_server).await, Vec::<String>::new());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn approval_for_session_applies_to_the_approved_tool_only() {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let model_server = MockServer::start().await;
+    let mcp_server = start_stub_mcp_server().await;
+    // Each follow-up request carries the outputs of all earlier calls, so the
+    // most specific matcher is mounted first.
+    mount_sse_once(
+        &model_server,
+        body_string_contains("call-3"),
+        sse(vec![ev_assistant_message("m1", "done"), ev_completed("r4")]),
+    )
+    .await;
+    mount_sse_once(
+        &model_server,
+        body_string_contains("call-2"),
+        sse(vec![
+            ev_function_call("call-3", "stub__shout"),
+            ev_completed("r3"),
+        ]),
+    )
+    .await;
+    mount_sse_once(
+        &model_server,
+        body_string_contains("call-1"),
+        sse(vec![
+            ev_function_call("call-2", "stub__echo"),
+            ev_completed("r2"),
+        ]),
+    )
+    .await;
+    mount_sse_once(
+        &model_server,
+        any(),
+        sse(vec![
+            ev_function_call("call-1", "stub__echo"),
+            ev_completed("r1"),
+        ]),
+    )
+    .await;
+
+    let home = TempDir::new().unwrap();
+    let codex = start_conversation(&model_server, &mcp_server, &home).await;
+    let requests = run_turn(
+        &codex,
+        vec![ReviewDecision::ApprovedForSession, ReviewDecision::Denied],
+    )
+    .await;
+
+    // The second call of `echo` is not asked about, but `shout` of the same
+    // server still is.
+    let asked: Vec<&str> = requests
+        .iter()
+        .map(|request| request.call_id.as_str())
+        .collect();
+    assert_eq!(asked, vec!["call-1", "call-3"]);
+    assert_eq!(called_tools(&mcp_server).await, vec!["echo", "echo"]);
+    assert_eq!(
+        function_call_output(&model_server, "call-3").await,
+        json!("tool call rejected by user")
+    );
+}
@@ -1,13 +1,19 @@
 // Aggregates all former standalone integration tests as modules.
 
+mod anthropic_messages;
+mod checkpoints;
 mod cli_stream;
 mod client;
 mod compact;
 mod compact_resume_fork;
+mod delegate;
 mod exec;
 mod exec_stream_events;
 mod fork_conversation;
+mod gemini;
+mod input_queue;
 mod live_cli;
+mod mcp_tool_approval;
 mod model_overrides;
 mod prompt_caching;
 mod review;
@@ -15,3 +21,4 @@ mod rollout_list_find;
 mod seatbelt;
 mod stream_error_allows_next_turn;
 mod stream_no_completed;
+mod worktree;
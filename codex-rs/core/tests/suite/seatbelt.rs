@@ -80,6 +80,9 @@ async fn if_parent_of_repo_is_writable_then_dot_git_folder_is_writable() {
         network_access: false,
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        readable_roots: None,
+        deny_read: Vec::new(),
+        allowed_hosts: Vec::new(),
     };
 
     test_scenario
@@ -106,6 +109,9 @@ async fn if_git_repo_is_writable_root_then_dot_git_folder_is_read_only() {
         network_access: false,
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        readable_roots: None,
+        deny_read: Vec::new(),
+        allowed_hosts: Vec::new(),
     };
 
     test_scenario
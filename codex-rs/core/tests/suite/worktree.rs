@@ -0,0 +1,126 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+//! Conversations started with a git worktree work on their own branch and
+//! bring their changes back when the worktree is resolved.
+
+use std::path::Path;
+use std::process::Command;
+
+use codex_core::CodexAuth;
+use codex_core::ConversationManager;
+use codex_core::NewConversation;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::protocol::WorktreeResolution;
+use core_test_support::load_default_config_for_test;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+
+fn git(args: &[&str], cwd: &Path) {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {args:?} failed: {output:?}");
+}
+
+fn create_repo(dir: &Path) {
+    git(&["init"], dir);
+    git(&["config", "user.name", "Test User"], dir);
+    git(&["config", "user.email", "test@example.com"], dir);
+    std::fs::write(dir.join("README.md"), "original\n").unwrap();
+    git(&["add", "."], dir);
+    git(&["commit", "-m", "Initial commit"], dir);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn conversation_runs_in_worktree_and_merges_changes_back() {
+    let home = TempDir::new().unwrap();
+    let repo = TempDir::new().unwrap();
+    create_repo(repo.path());
+
+    let mut config = load_default_config_for_test(&home);
+    config.cwd = repo.path().to_path_buf();
+    config.worktree = true;
+    let NewConversation {
+        conversation,
+        session_configured,
+        ..
+    } = ConversationManager::with_auth(CodexAuth::from_api_key("dummy"))
+        .new_conversation(config)
+        .await
+        .unwrap();
+
+    let worktree = session_configured.worktree.expect("worktree");
+    assert!(worktree.path.starts_with(home.path().join("worktrees")));
+    assert_eq!(
+        std::fs::read_to_string(worktree.path.join("README.md")).unwrap(),
+        "original\n"
+    );
+
+    // Changes in the worktree do not affect the original checkout...
+    std::fs::write(worktree.path.join("README.md"), "changed\n").unwrap();
+    assert_eq!(
+        std::fs::read_to_string(repo.path().join("README.md")).unwrap(),
+        "original\n"
+    );
+
+    // ...until they are merged back.
+    conversation
+        .submit(Op::ResolveWorktree {
+            resolution: WorktreeResolution::Merge,
+        })
+        .await
+        .unwrap();
+    let EventMsg::WorktreeResolved(resolved) = wait_for_event(&conversation, |ev| {
+        matches!(ev, EventMsg::WorktreeResolved(_) | EventMsg::Error(_))
+    })
+    .await
+    else {
+        panic!("expected the worktree to be resolved");
+    };
+    assert_eq!(resolved.resolution, WorktreeResolution::Merge);
+    assert_eq!(
+        std::fs::read_to_string(repo.path().join("README.md")).unwrap(),
+        "changed\n"
+    );
+    assert!(!worktree.path.exists());
+
+    // The worktree can only be resolved once.
+    conversation
+        .submit(Op::ResolveWorktree {
+            resolution: WorktreeResolution::Discard,
+        })
+        .await
+        .unwrap();
+    wait_for_event(&conversation, |ev| matches!(ev, EventMsg::Error(_))).await;
+
+    // Nothing runs in the removed worktree anymore.
+    for op in [
+        Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "hello".to_string(),
+            }],
+        },
+        Op::QueueUserInput {
+            items: vec![InputItem::Text {
+                text: "hello".to_string(),
+            }],
+        },
+    ] {
+        conversation.submit(op).await.unwrap();
+        let EventMsg::Error(error) = wait_for_event(&conversation, |ev| {
+            matches!(ev, EventMsg::Error(_) | EventMsg::TaskStarted(_))
+        })
+        .await
+        else {
+            panic!("expected the input to be rejected");
+        };
+        assert_eq!(
+            error.message,
+            "the worktree of this conversation was removed; start a new conversation"
+        );
+    }
+}
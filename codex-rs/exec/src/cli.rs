@@ -1,6 +1,7 @@
 use clap::Parser;
 use clap::ValueEnum;
 use codex_common::CliConfigOverrides;
+use codex_core::review::ReviewTarget;
 use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
@@ -77,6 +78,9 @@ pub struct Cli {
 pub enum Command {
     /// Resume a previous session by id or pick the most recent with --last.
     Resume(ResumeArgs),
+
+    /// Review code changes and report prioritized findings.
+    Review(ReviewArgs),
 }
 
 #[derive(Parser, Debug)]
@@ -95,6 +99,51 @@ pub struct ResumeArgs {
     pub prompt: Option<String>,
 }
 
+#[derive(Parser, Debug, Clone)]
+pub struct ReviewArgs {
+    /// Review the changes on the current branch against this base branch.
+    #[arg(long = "base", value_name = "BRANCH", conflicts_with_all = ["commit", "uncommitted"])]
+    pub base: Option<String>,
+
+    /// Review the changes introduced by this commit.
+    #[arg(long = "commit", value_name = "SHA", conflicts_with = "uncommitted")]
+    pub commit: Option<String>,
+
+    /// Review staged, unstaged and untracked changes (the default).
+    #[arg(long = "uncommitted", default_value_t = false)]
+    pub uncommitted: bool,
+
+    /// Write the findings to this file as a SARIF 2.1.0 log.
+    #[arg(long = "sarif", value_name = "FILE")]
+    pub sarif_file: Option<PathBuf>,
+
+    /// Print each finding as a GitHub Actions annotation
+    /// (`::warning file=...`).
+    #[arg(long = "github-annotations", default_value_t = false)]
+    pub github_annotations: bool,
+
+    /// Exit with a non-zero status if any finding has this priority or a more
+    /// urgent one (0 = P0 only, 3 = any finding).
+    #[arg(
+        long = "fail-on-priority",
+        value_name = "PRIORITY",
+        value_parser = clap::value_parser!(i32).range(0..=3)
+    )]
+    pub fail_on_priority: Option<i32>,
+}
+
+impl ReviewArgs {
+    pub fn target(&self) -> ReviewTarget {
+        if let Some(base) = &self.base {
+            ReviewTarget::BaseBranch(base.clone())
+        } else if let Some(commit) = &self.commit {
+            ReviewTarget::Commit(commit.clone())
+        } else {
+            ReviewTarget::UncommittedChanges
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
 #[value(rename_all = "kebab-case")]
 pub enum Color {
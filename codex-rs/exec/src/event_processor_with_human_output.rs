@@ -8,17 +8,24 @@ use codex_core::protocol::AgentReasoningDeltaEvent;
 use codex_core::protocol::AgentReasoningRawContentDeltaEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::CheckpointRestoredEvent;
+use codex_core::protocol::DelegateBeginEvent;
+use codex_core::protocol::DelegateEndEvent;
+use codex_core::protocol::DelegateEventEvent;
 use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
+use codex_core::protocol::ExitedReviewModeEvent;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::McpInvocation;
+use codex_core::protocol::McpToolCallApprovalRequestEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
+use codex_core::protocol::ReviewOutputEvent;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::protocol::StreamErrorEvent;
 use codex_core::protocol::TaskCompleteEvent;
@@ -26,6 +33,9 @@ use codex_core::protocol::TurnAbortReason;
 use codex_core::protocol::TurnDiffEvent;
 use codex_core::protocol::WebSearchBeginEvent;
 use codex_core::protocol::WebSearchEndEvent;
+use codex_core::protocol::WorktreeResolvedEvent;
+use codex_core::review::format_code_location;
+use codex_core::review::sort_findings_by_priority;
 use codex_protocol::num_format::format_with_separators;
 use owo_colors::OwoColorize;
 use owo_colors::Style;
@@ -66,6 +76,8 @@ pub(crate) struct EventProcessorWithHumanOutput {
     reasoning_started: bool,
     raw_reasoning_started: bool,
     last_message_path: Option<PathBuf>,
+    /// Review findings are printed relative to this directory.
+    cwd: PathBuf,
 }
 
 impl EventProcessorWithHumanOutput {
@@ -94,6 +106,7 @@ impl EventProcessorWithHumanOutput {
                 reasoning_started: false,
                 raw_reasoning_started: false,
                 last_message_path,
+                cwd: config.cwd.clone(),
             }
         } else {
             Self {
@@ -112,6 +125,7 @@ impl EventProcessorWithHumanOutput {
                 reasoning_started: false,
                 raw_reasoning_started: false,
                 last_message_path,
+                cwd: config.cwd.clone(),
             }
         }
     }
@@ -299,6 +313,7 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 aggregated_output,
                 duration,
                 exit_code,
+                denied_hosts,
                 ..
             }) => {
                 let exec_command = self.call_id_to_command.remove(&call_id);
@@ -328,6 +343,13 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     }
                 }
                 println!("{}", truncated_output.style(self.dimmed));
+                if !denied_hosts.is_empty() {
+                    let message = format!(
+                        "sandbox denied network access to: {}",
+                        denied_hosts.join(", ")
+                    );
+                    println!("{}", message.style(self.red));
+                }
             }
             EventMsg::McpToolCallBegin(McpToolCallBeginEvent {
                 call_id: _,
@@ -374,6 +396,64 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             EventMsg::WebSearchEnd(WebSearchEndEvent { call_id: _, query }) => {
                 ts_println!(self, "🌐 Searched: {query}");
             }
+            EventMsg::DelegateBegin(DelegateBeginEvent { call_id: _, tasks }) => {
+                ts_println!(
+                    self,
+                    "{} {} workers",
+                    "delegate".style(self.magenta),
+                    tasks.len()
+                );
+                for (index, prompt) in tasks.iter().enumerate() {
+                    let summary = prompt.lines().next().unwrap_or_default();
+                    println!(
+                        "  {} {summary}",
+                        format!("[{}]", index + 1).style(self.dimmed)
+                    );
+                }
+            }
+            EventMsg::DelegateEvent(DelegateEventEvent {
+                call_id: _,
+                worker,
+                msg,
+            }) => {
+                // Only show the commands workers run; their full transcripts
+                // are recorded in their own sessions.
+                if let EventMsg::ExecCommandBegin(ExecCommandBeginEvent { command, .. }) = *msg {
+                    ts_println!(
+                        self,
+                        "{} {}",
+                        format!("[{}] exec", worker + 1).style(self.magenta),
+                        escape_command(&command).style(self.bold),
+                    );
+                }
+            }
+            EventMsg::DelegateEnd(DelegateEndEvent {
+                call_id: _,
+                results,
+            }) => {
+                for (index, result) in results.iter().enumerate() {
+                    let worker = format!("[{}]", index + 1);
+                    match result {
+                        Ok(message) => {
+                            ts_println!(
+                                self,
+                                "{} {}",
+                                worker.style(self.green),
+                                "done".style(self.bold)
+                            );
+                            println!("{}", message.style(self.dimmed));
+                        }
+                        Err(error) => {
+                            ts_println!(
+                                self,
+                                "{} {}",
+                                worker.style(self.red),
+                                error.style(self.red)
+                            );
+                        }
+                    }
+                }
+            }
             EventMsg::PatchApplyBegin(PatchApplyBeginEvent {
                 call_id,
                 auto_approved,
@@ -501,6 +581,17 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             EventMsg::ApplyPatchApprovalRequest(_) => {
                 // Should we exit?
             }
+            EventMsg::McpToolCallApprovalRequest(McpToolCallApprovalRequestEvent {
+                invocation,
+                ..
+            }) => {
+                ts_println!(
+                    self,
+                    "{} {}",
+                    format_mcp_invocation(&invocation).style(self.bold),
+                    "denied: approval required".style(self.red),
+                );
+            }
             EventMsg::AgentReasoning(agent_reasoning_event) => {
                 if self.show_agent_reasoning {
                     if !self.reasoning_started {
@@ -525,6 +616,7 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     history_entry_count: _,
                     initial_messages: _,
                     rollout_path: _,
+                    worktree,
                 } = session_configured_event;
 
                 ts_println!(
@@ -535,6 +627,14 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 );
 
                 ts_println!(self, "model: {}", model);
+                if let Some(worktree) = worktree {
+                    ts_println!(
+                        self,
+                        "worktree: {} (branch {})",
+                        worktree.path.display(),
+                        worktree.branch
+                    );
+                }
                 println!();
             }
             EventMsg::PlanUpdate(plan_update_event) => {
@@ -548,9 +648,32 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             EventMsg::McpListToolsResponse(_) => {
                 // Currently ignored in exec output.
             }
+            EventMsg::McpListResourcesResponse(_) => {
+                // Currently ignored in exec output.
+            }
             EventMsg::ListCustomPromptsResponse(_) => {
                 // Currently ignored in exec output.
             }
+            EventMsg::GetMcpPromptResponse(_) => {
+                // Currently ignored in exec output.
+            }
+            EventMsg::InputQueueUpdated(_) => {
+                // Currently ignored in exec output.
+            }
+            EventMsg::WorktreeResolved(WorktreeResolvedEvent {
+                resolution: _,
+                summary,
+            }) => {
+                ts_println!(self, "{} {summary}", "worktree".style(self.magenta));
+            }
+            EventMsg::CheckpointRestored(CheckpointRestoredEvent { turn }) => {
+                ts_println!(
+                    self,
+                    "{} restored files to before message {}",
+                    "undo".style(self.magenta),
+                    turn + 1
+                );
+            }
             EventMsg::TurnAborted(abort_reason) => match abort_reason.reason {
                 TurnAbortReason::Interrupted => {
                     ts_println!(self, "task interrupted");
@@ -562,13 +685,66 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             EventMsg::ShutdownComplete => return CodexStatus::Shutdown,
             EventMsg::ConversationPath(_) => {}
             EventMsg::UserMessage(_) => {}
-            EventMsg::EnteredReviewMode(_) => {}
-            EventMsg::ExitedReviewMode(_) => {}
+            EventMsg::EnteredReviewMode(review_request) => {
+                ts_println!(
+                    self,
+                    "{} {}",
+                    "review".style(self.magenta),
+                    review_request.user_facing_hint.style(self.bold)
+                );
+            }
+            EventMsg::ExitedReviewMode(ExitedReviewModeEvent {
+                review_output: Some(review_output),
+            }) => {
+                self.print_review_output(review_output);
+            }
+            EventMsg::ExitedReviewMode(ExitedReviewModeEvent {
+                review_output: None,
+            }) => {
+                ts_println!(
+                    self,
+                    "{}",
+                    "review ended without a result".style(self.dimmed)
+                );
+            }
         }
         CodexStatus::Running
     }
 }
 
+impl EventProcessorWithHumanOutput {
+    fn print_review_output(&self, review_output: ReviewOutputEvent) {
+        let ReviewOutputEvent {
+            mut findings,
+            overall_correctness,
+            overall_explanation,
+            overall_confidence_score: _,
+        } = review_output;
+        ts_println!(
+            self,
+            "{} {}",
+            "review result:".style(self.magenta),
+            overall_correctness.style(self.bold)
+        );
+        if !overall_explanation.is_empty() {
+            println!("{overall_explanation}");
+        }
+
+        sort_findings_by_priority(&mut findings);
+        for finding in findings {
+            println!();
+            println!("{}", finding.title.style(self.bold));
+            println!(
+                "  {}",
+                format_code_location(&finding.code_location, &self.cwd).style(self.cyan)
+            );
+            for line in finding.body.lines() {
+                println!("  {}", line.style(self.dimmed));
+            }
+        }
+    }
+}
+
 fn escape_command(command: &[String]) -> String {
     try_join(command.iter().map(|s| s.as_str())).unwrap_or_else(|_| command.join(" "))
 }
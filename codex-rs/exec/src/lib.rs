@@ -2,12 +2,17 @@ mod cli;
 mod event_processor;
 mod event_processor_with_human_output;
 mod event_processor_with_json_output;
+mod review_report;
 
 use std::io::IsTerminal;
 use std::io::Read;
+use std::path::Path;
 use std::path::PathBuf;
 
+use anyhow::Context;
 pub use cli::Cli;
+pub use cli::Command;
+pub use cli::ReviewArgs;
 use codex_core::AuthManager;
 use codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID;
 use codex_core::ConversationManager;
@@ -18,8 +23,11 @@ use codex_core::git_info::get_git_repo_root;
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
+use codex_core::protocol::ExitedReviewModeEvent;
 use codex_core::protocol::InputItem;
 use codex_core::protocol::Op;
+use codex_core::protocol::ReviewDecision;
+use codex_core::protocol::ReviewOutputEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_ollama::DEFAULT_OSS_MODEL;
 use codex_protocol::config_types::SandboxMode;
@@ -54,18 +62,38 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         config_overrides,
     } = cli;
 
+    // `codex exec review` builds its own prompt from the review target.
+    let review_args = match &command {
+        Some(ExecCommand::Review(args)) => Some(args.clone()),
+        _ => None,
+    };
+    let review_request = review_args
+        .as_ref()
+        .map(|args| args.target().review_request());
+    // Annotations are workflow commands on stdout, where they would be mixed
+    // into the JSONL events.
+    if json_mode
+        && review_args
+            .as_ref()
+            .is_some_and(|args| args.github_annotations)
+    {
+        eprintln!("--github-annotations cannot be combined with --json.");
+        std::process::exit(1);
+    }
+
     // Determine the prompt source (parent or subcommand) and read from stdin if needed.
     let prompt_arg = match &command {
         // Allow prompt before the subcommand by falling back to the parent-level prompt
         // when the Resume subcommand did not provide its own prompt.
         Some(ExecCommand::Resume(args)) => args.prompt.clone().or(prompt),
-        None => prompt,
+        Some(ExecCommand::Review(_)) | None => prompt,
     };
 
-    let prompt = match prompt_arg {
-        Some(p) if p != "-" => p,
+    let prompt = match (&review_request, prompt_arg) {
+        (Some(review_request), _) => review_request.prompt.clone(),
+        (None, Some(p)) if p != "-" => p,
         // Either `-` was passed or no positional arg.
-        maybe_dash => {
+        (None, maybe_dash) => {
             // When no arg (None) **and** stdin is a TTY, bail out early – unless the
             // user explicitly forced reading via `-`.
             let force_stdin = matches!(maybe_dash.as_deref(), Some("-"));
@@ -162,6 +190,7 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         include_view_image_tool: None,
         show_raw_agent_reasoning: oss.then_some(true),
         tools_web_search_request: None,
+        worktree: None,
     };
     // Parse `-c` overrides.
     let cli_kv_overrides = match config_overrides.parse_overrides() {
@@ -198,6 +227,7 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         std::process::exit(1);
     }
 
+    let cwd = config.cwd.clone();
     let conversation_manager =
         ConversationManager::new(AuthManager::shared(config.codex_home.clone()));
 
@@ -286,13 +316,35 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         }
     }
 
-    // Send the prompt.
-    let items: Vec<InputItem> = vec![InputItem::Text { text: prompt }];
-    let initial_prompt_task_id = conversation.submit(Op::UserInput { items }).await?;
-    info!("Sent prompt with event ID: {initial_prompt_task_id}");
+    // Send the prompt, or start the review.
+    if let Some(review_request) = review_request {
+        let review_task_id = conversation.submit(Op::Review { review_request }).await?;
+        info!("Sent review request with event ID: {review_task_id}");
+    } else {
+        let items: Vec<InputItem> = vec![InputItem::Text { text: prompt }];
+        let initial_prompt_task_id = conversation.submit(Op::UserInput { items }).await?;
+        info!("Sent prompt with event ID: {initial_prompt_task_id}");
+    }
 
     // Run the loop until the task is complete.
+    let mut review_output = None;
     while let Some(event) = rx.recv().await {
+        if let EventMsg::ExitedReviewMode(ExitedReviewModeEvent {
+            review_output: Some(output),
+        }) = &event.msg
+        {
+            review_output = Some(output.clone());
+        }
+        if let EventMsg::McpToolCallApprovalRequest(_) = &event.msg {
+            // There is no one to ask in a headless run, so deny MCP tool
+            // calls that require approval.
+            conversation
+                .submit(Op::McpToolCallApproval {
+                    id: event.id.clone(),
+                    decision: ReviewDecision::Denied,
+                })
+                .await?;
+        }
         let shutdown: CodexStatus = event_processor.process_event(event);
         match shutdown {
             CodexStatus::Running => continue,
@@ -305,6 +357,49 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         }
     }
 
+    if let Some(review_args) = review_args {
+        report_review(&review_args, review_output.as_ref(), &cwd)?;
+    }
+
+    Ok(())
+}
+
+/// Writes the CI reports requested on `codex exec review` and exits with a
+/// non-zero status when `--fail-on-priority` is exceeded.
+fn report_review(
+    args: &ReviewArgs,
+    review_output: Option<&ReviewOutputEvent>,
+    cwd: &Path,
+) -> anyhow::Result<()> {
+    let Some(review_output) = review_output else {
+        if args.fail_on_priority.is_some() {
+            eprintln!("Review ended without a result.");
+            std::process::exit(1);
+        }
+        return Ok(());
+    };
+
+    let report_root = review_report::report_root(cwd);
+    if let Some(sarif_file) = &args.sarif_file {
+        let sarif = review_report::sarif_log(review_output, &report_root);
+        std::fs::write(sarif_file, serde_json::to_string_pretty(&sarif)?)
+            .with_context(|| format!("failed to write SARIF log to {}", sarif_file.display()))?;
+    }
+
+    if args.github_annotations {
+        for annotation in review_report::github_annotations(review_output, &report_root) {
+            println!("{annotation}");
+        }
+    }
+
+    if let Some(threshold) = args.fail_on_priority {
+        let failing = review_report::findings_at_or_above_priority(review_output, threshold);
+        if failing > 0 {
+            eprintln!("Review found {failing} finding(s) with priority P{threshold} or higher.");
+            std::process::exit(1);
+        }
+    }
+
     Ok(())
 }
 
@@ -0,0 +1,269 @@
+//! Machine-readable reports of review findings for CI: SARIF 2.1.0 logs and
+//! GitHub Actions workflow-command annotations.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_core::git_info::get_git_repo_root;
+use codex_core::protocol::ReviewFinding;
+use codex_core::protocol::ReviewOutputEvent;
+use codex_core::review::relative_file_path;
+use serde_json::Value;
+use serde_json::json;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_RULE_ID: &str = "codex-review";
+
+/// Severity shared by SARIF results and GitHub annotations, derived from a
+/// finding's priority (P0 is the most urgent).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl Severity {
+    fn for_priority(priority: i32) -> Self {
+        match priority {
+            i32::MIN..=1 => Severity::Error,
+            2 => Severity::Warning,
+            _ => Severity::Note,
+        }
+    }
+
+    fn sarif_level(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+        }
+    }
+
+    fn github_command(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "notice",
+        }
+    }
+}
+
+/// Directory the paths in reports are relative to: the root of the git
+/// repository containing `cwd`, since that is what code scanning and GitHub
+/// annotations resolve them against, or `cwd` itself outside a repository.
+pub(crate) fn report_root(cwd: &Path) -> PathBuf {
+    get_git_repo_root(cwd).unwrap_or_else(|| cwd.to_path_buf())
+}
+
+/// Builds a SARIF 2.1.0 log with one result per finding. Paths are written
+/// relative to `root` (see [`report_root`]).
+pub(crate) fn sarif_log(review_output: &ReviewOutputEvent, root: &Path) -> Value {
+    let results: Vec<Value> = review_output
+        .findings
+        .iter()
+        .map(|finding| sarif_result(finding, root))
+        .collect();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "codex",
+                    "informationUri": "https://github.com/openai/codex",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": [{
+                        "id": SARIF_RULE_ID,
+                        "name": "CodeReviewFinding",
+                        "shortDescription": { "text": "Issue found by a Codex code review" },
+                    }],
+                },
+            },
+            "results": results,
+            "properties": {
+                "overallCorrectness": review_output.overall_correctness,
+                "overallExplanation": review_output.overall_explanation,
+                "overallConfidenceScore": review_output.overall_confidence_score,
+            },
+        }],
+    })
+}
+
+fn sarif_result(finding: &ReviewFinding, root: &Path) -> Value {
+    let (start, end) = line_span(finding);
+    let message = if finding.body.trim().is_empty() {
+        finding.title.clone()
+    } else {
+        format!("{}\n\n{}", finding.title, finding.body.trim())
+    };
+    json!({
+        "ruleId": SARIF_RULE_ID,
+        "level": Severity::for_priority(finding.priority).sarif_level(),
+        "message": { "text": message },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": {
+                    "uri": relative_file_path(&finding.code_location, root).to_string_lossy(),
+                },
+                "region": { "startLine": start, "endLine": end },
+            },
+        }],
+        "properties": {
+            "priority": finding.priority,
+            "confidenceScore": finding.confidence_score,
+        },
+    })
+}
+
+/// Formats each finding as a GitHub Actions workflow command, e.g.
+/// `::warning file=src/lib.rs,line=3,endLine=5,title=[P2] ...::body`, with
+/// paths relative to `root` (see [`report_root`]).
+pub(crate) fn github_annotations(review_output: &ReviewOutputEvent, root: &Path) -> Vec<String> {
+    review_output
+        .findings
+        .iter()
+        .map(|finding| {
+            let (start, end) = line_span(finding);
+            let file = relative_file_path(&finding.code_location, root).to_string_lossy();
+            let body = finding.body.trim();
+            let message = if body.is_empty() {
+                &finding.title
+            } else {
+                body
+            };
+            format!(
+                "::{} file={},line={start},endLine={end},title={}::{}",
+                Severity::for_priority(finding.priority).github_command(),
+                escape_property(&file),
+                escape_property(&finding.title),
+                escape_data(message),
+            )
+        })
+        .collect()
+}
+
+/// Counts the findings whose priority is at or above `threshold`, i.e. whose
+/// priority number is less than or equal to it.
+pub(crate) fn findings_at_or_above_priority(
+    review_output: &ReviewOutputEvent,
+    threshold: i32,
+) -> usize {
+    review_output
+        .findings
+        .iter()
+        .filter(|finding| finding.priority <= threshold)
+        .count()
+}
+
+/// Both SARIF and GitHub use 1-based lines; models occasionally report 0 or a
+/// reversed range.
+fn line_span(finding: &ReviewFinding) -> (u32, u32) {
+    let range = &finding.code_location.line_range;
+    let start = range.start.max(1);
+    (start, range.end.max(start))
+}
+
+fn escape_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::protocol::ReviewCodeLocation;
+    use codex_core::protocol::ReviewLineRange;
+
+    fn review_output() -> ReviewOutputEvent {
+        let finding =
+            |title: &str, body: &str, priority: i32, start: u32, end: u32| ReviewFinding {
+                title: title.to_string(),
+                body: body.to_string(),
+                confidence_score: 0.5,
+                priority,
+                code_location: ReviewCodeLocation {
+                    absolute_file_path: PathBuf::from("/repo/src/lib.rs"),
+                    line_range: ReviewLineRange { start, end },
+                },
+            };
+        ReviewOutputEvent {
+            findings: vec![
+                finding(
+                    "[P0] Off-by-one",
+                    "Skips the last item.\nSee loop.",
+                    0,
+                    3,
+                    5,
+                ),
+                finding("[P3] Naming, style", "", 3, 0, 0),
+            ],
+            overall_correctness: "patch is incorrect".to_string(),
+            overall_explanation: "One bug.".to_string(),
+            overall_confidence_score: 0.8,
+        }
+    }
+
+    #[test]
+    fn formats_github_annotations() {
+        assert_eq!(
+            vec![
+                "::error file=src/lib.rs,line=3,endLine=5,title=[P0] Off-by-one::Skips the last item.%0ASee loop."
+                    .to_string(),
+                "::notice file=src/lib.rs,line=1,endLine=1,title=[P3] Naming%2C style::[P3] Naming, style"
+                    .to_string(),
+            ],
+            github_annotations(&review_output(), Path::new("/repo"))
+        );
+    }
+
+    #[test]
+    fn builds_sarif_results() {
+        let log = sarif_log(&review_output(), Path::new("/repo"));
+        assert_eq!("2.1.0", log["version"]);
+        let results = &log["runs"][0]["results"];
+        assert_eq!(
+            json!({
+                "ruleId": "codex-review",
+                "level": "error",
+                "message": { "text": "[P0] Off-by-one\n\nSkips the last item.\nSee loop." },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": "src/lib.rs" },
+                        "region": { "startLine": 3, "endLine": 5 },
+                    },
+                }],
+                "properties": { "priority": 0, "confidenceScore": 0.5 },
+            }),
+            results[0]
+        );
+        assert_eq!("note", results[1]["level"]);
+    }
+
+    #[test]
+    fn counts_findings_at_or_above_threshold() {
+        let output = review_output();
+        assert_eq!(1, findings_at_or_above_priority(&output, 0));
+        assert_eq!(1, findings_at_or_above_priority(&output, 2));
+        assert_eq!(2, findings_at_or_above_priority(&output, 3));
+    }
+
+    #[test]
+    fn reports_are_relative_to_the_repository_root() {
+        let repo = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(repo.path().join(".git")).unwrap();
+        let subdir = repo.path().join("crates").join("cli");
+        std::fs::create_dir_all(&subdir).unwrap();
+        assert_eq!(repo.path(), report_root(&subdir));
+
+        let outside = tempfile::TempDir::new().unwrap();
+        assert_eq!(outside.path(), report_root(outside.path()));
+    }
+}
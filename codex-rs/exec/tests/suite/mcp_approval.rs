@@ -0,0 +1,118 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+use anyhow::Context;
+use assert_cmd::prelude::*;
+use core_test_support::start_stub_mcp_server;
+use serde_json::Value;
+use serde_json::json;
+use std::process::Command;
+use tempfile::TempDir;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::body_string_contains;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+fn sse_response(events: Vec<Value>) -> ResponseTemplate {
+    let body: String = events
+        .iter()
+        .map(|event| {
+            format!(
+                "event: {}\ndata: {event}\n\n",
+                event["type"].as_str().unwrap()
+            )
+        })
+        .collect();
+    ResponseTemplate::new(200)
+        .insert_header("content-type", "text/event-stream")
+        .set_body_raw(body, "text/event-stream")
+}
+
+fn ev_completed(id: &str) -> Value {
+    json!({
+        "type": "response.completed",
+        "response": {
+            "id": id,
+            "usage": {"input_tokens":0,"input_tokens_details":null,"output_tokens":0,"output_tokens_details":null,"total_tokens":0}
+        }
+    })
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exec_denies_mcp_tool_calls_that_require_approval() -> anyhow::Result<()> {
+    let mcp_server = start_stub_mcp_server().await;
+
+    let model_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .and(body_string_contains("call-1"))
+        .respond_with(sse_response(vec![
+            json!({
+                "type": "response.output_item.done",
+                "item": {
+                    "type": "message",
+                    "role": "assistant",
+                    "id": "m1",
+                    "content": [{"type": "output_text", "text": "the tool was denied"}]
+                }
+            }),
+            ev_completed("r2"),
+        ]))
+        .mount(&model_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .respond_with(sse_response(vec![
+            json!({
+                "type": "response.output_item.done",
+                "item": {
+                    "type": "function_call",
+                    "call_id": "call-1",
+                    "name": "stub__echo",
+                    "arguments": "{}"
+                }
+            }),
+            ev_completed("r1"),
+        ]))
+        .mount(&model_server)
+        .await;
+
+    let home = TempDir::new()?;
+    let output = Command::cargo_bin("codex-exec")
+        .context("should find binary for codex-exec")?
+        .env("CODEX_HOME", home.path())
+        .env("OPENAI_API_KEY", "dummy")
+        .env("OPENAI_BASE_URL", format!("{}/v1", model_server.uri()))
+        .arg("--skip-git-repo-check")
+        .arg("-C")
+        .arg(home.path())
+        .arg("-c")
+        .arg(format!("mcp_servers.stub.url=\"{}/mcp\"", mcp_server.uri()))
+        .arg("-c")
+        .arg("mcp_servers.stub.approval=\"always\"")
+        .arg("use the stub tool")
+        .output()?;
+    assert!(output.status.success(), "{output:?}");
+    assert!(String::from_utf8(output.stdout)?.contains("the tool was denied"));
+
+    let called = mcp_server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter_map(|request| serde_json::from_slice::<Value>(&request.body).ok())
+        .any(|body| body["method"] == "tools/call");
+    assert!(!called, "the tool must not be called without approval");
+
+    let output = model_server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter_map(|request| serde_json::from_slice::<Value>(&request.body).ok())
+        .flat_map(|body| body["input"].as_array().cloned().unwrap_or_default())
+        .find(|item| item["type"] == "function_call_output" && item["call_id"] == "call-1")
+        .map(|item| item["output"].clone());
+    assert_eq!(output, Some(json!("tool call rejected by user")));
+    Ok(())
+}
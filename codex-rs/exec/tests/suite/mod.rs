@@ -1,5 +1,8 @@
 // Aggregates all former standalone integration tests as modules.
 mod apply_patch;
 mod common;
+mod mcp_approval;
+mod record_replay;
 mod resume;
+mod review;
 mod sandbox;
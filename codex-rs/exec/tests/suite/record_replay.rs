@@ -0,0 +1,72 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+use anyhow::Context;
+use assert_cmd::prelude::*;
+use std::process::Command;
+use tempfile::TempDir;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+fn exec_command(home: &TempDir) -> anyhow::Result<Command> {
+    let mut cmd = Command::cargo_bin("codex-exec").context("should find binary for codex-exec")?;
+    cmd.env("CODEX_HOME", home.path())
+        .env("OPENAI_API_KEY", "dummy")
+        .arg("--skip-git-repo-check")
+        .arg("-C")
+        .arg(env!("CARGO_MANIFEST_DIR"));
+    Ok(cmd)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exec_replays_recorded_session_offline() -> anyhow::Result<()> {
+    let fixture = std::fs::read_to_string(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/cli_responses_fixture.sse"),
+    )?;
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_raw(fixture.clone(), "text/event-stream"),
+        )
+        // Only the recording run may reach the server.
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let recording = TempDir::new()?;
+    let output = exec_command(&TempDir::new()?)?
+        .env("CODEX_RS_RECORD_DIR", recording.path())
+        .env("OPENAI_BASE_URL", format!("{}/v1", server.uri()))
+        .arg("record me")
+        .output()?;
+    assert!(output.status.success(), "{output:?}");
+    assert!(String::from_utf8(output.stdout)?.contains("fixture hello"));
+
+    // Turns are recorded per conversation, and the run had just one.
+    let conversations = std::fs::read_to_string(recording.path().join("conversations.txt"))?;
+    let conversations: Vec<&str> = conversations.lines().collect();
+    assert_eq!(conversations.len(), 1, "{conversations:?}");
+    let conversation_dir = recording.path().join(conversations[0]);
+    let turn_dir = conversation_dir.join("turn-0001");
+    let request = std::fs::read_to_string(turn_dir.join("request.json"))?;
+    assert!(request.contains("record me"), "{request}");
+    assert_eq!(
+        std::fs::read_to_string(turn_dir.join("response.sse"))?,
+        fixture
+    );
+    assert!(!conversation_dir.join("turn-0002").exists());
+
+    let output = exec_command(&TempDir::new()?)?
+        .env("CODEX_RS_REPLAY_DIR", recording.path())
+        .env("OPENAI_BASE_URL", "http://unused.local")
+        .arg("record me")
+        .output()?;
+    assert!(output.status.success(), "{output:?}");
+    assert!(String::from_utf8(output.stdout)?.contains("fixture hello"));
+    Ok(())
+}
@@ -0,0 +1,162 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+use anyhow::Context;
+use assert_cmd::prelude::*;
+use serde_json::Value;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn review_command(home: &TempDir) -> anyhow::Result<Command> {
+    let fixture = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/review_responses_fixture.sse");
+    let mut cmd = Command::cargo_bin("codex-exec").context("should find binary for codex-exec")?;
+    cmd.env("CODEX_HOME", home.path())
+        .env("OPENAI_API_KEY", "dummy")
+        .env("CODEX_RS_SSE_FIXTURE", &fixture)
+        .env("OPENAI_BASE_URL", "http://unused.local")
+        .arg("--skip-git-repo-check")
+        .arg("-C")
+        .arg("/repo");
+    Ok(cmd)
+}
+
+#[test]
+fn exec_review_emits_structured_findings_in_json_mode() -> anyhow::Result<()> {
+    let home = TempDir::new()?;
+    let output = review_command(&home)?
+        .arg("--json")
+        .arg("review")
+        .arg("--commit")
+        .arg("0123456789abcdef")
+        .output()?;
+    assert!(output.status.success(), "{output:?}");
+
+    let events: Vec<Value> = String::from_utf8(output.stdout)?
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    let prompt = events
+        .iter()
+        .find_map(|event| event.get("prompt").and_then(Value::as_str))
+        .expect("prompt line");
+    assert!(prompt.contains("git show 0123456789abcdef"), "{prompt}");
+
+    let entered = events
+        .iter()
+        .find(|event| event["msg"]["type"] == "entered_review_mode")
+        .expect("entered_review_mode event");
+    assert_eq!(entered["msg"]["user_facing_hint"], "commit 0123456");
+
+    let exited = events
+        .iter()
+        .find(|event| event["msg"]["type"] == "exited_review_mode")
+        .expect("exited_review_mode event");
+    assert_eq!(
+        exited["msg"]["review_output"]["overall_correctness"],
+        "patch is incorrect"
+    );
+    assert_eq!(
+        exited["msg"]["review_output"]["findings"][1]["code_location"]["line_range"]["start"],
+        3
+    );
+    Ok(())
+}
+
+#[test]
+fn exec_review_prints_prioritized_findings() -> anyhow::Result<()> {
+    let home = TempDir::new()?;
+    let output = review_command(&home)?
+        .arg("--color")
+        .arg("never")
+        .arg("review")
+        .arg("--base")
+        .arg("main")
+        .output()?;
+    assert!(output.status.success(), "{output:?}");
+
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.contains("review changes against 'main'"), "{stdout}");
+    let p0 = stdout
+        .find("[P0] Off-by-one in loop bound\n  src/lib.rs:3-5\n")
+        .expect("P0 finding with relative location");
+    let p2 = stdout
+        .find("[P2] Prefer early return\n  src/main.rs:8\n")
+        .expect("P2 finding with relative location");
+    assert!(p0 < p2, "findings should be ordered by priority: {stdout}");
+    Ok(())
+}
+
+#[test]
+fn exec_review_rejects_multiple_targets() -> anyhow::Result<()> {
+    let home = TempDir::new()?;
+    review_command(&home)?
+        .arg("review")
+        .arg("--base")
+        .arg("main")
+        .arg("--commit")
+        .arg("abc")
+        .assert()
+        .failure();
+    Ok(())
+}
+
+#[test]
+fn exec_review_writes_sarif_and_github_annotations() -> anyhow::Result<()> {
+    let home = TempDir::new()?;
+    let sarif_path = home.path().join("review.sarif");
+    let output = review_command(&home)?
+        .arg("--color")
+        .arg("never")
+        .arg("review")
+        .arg("--sarif")
+        .arg(&sarif_path)
+        .arg("--github-annotations")
+        .output()?;
+    assert!(output.status.success(), "{output:?}");
+
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(
+        stdout.contains(
+            "::error file=src/lib.rs,line=3,endLine=5,title=[P0] Off-by-one in loop bound::"
+        ),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains(
+            "::warning file=src/main.rs,line=8,endLine=8,title=[P2] Prefer early return::"
+        ),
+        "{stdout}"
+    );
+
+    let sarif: Value = serde_json::from_str(&std::fs::read_to_string(&sarif_path)?)?;
+    assert_eq!(sarif["version"], "2.1.0");
+    let results = sarif["runs"][0]["results"].as_array().expect("results");
+    assert_eq!(results.len(), 2);
+    assert_eq!(
+        results[1]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+        "src/lib.rs"
+    );
+    assert_eq!(
+        results[1]["locations"][0]["physicalLocation"]["region"]["startLine"],
+        3
+    );
+    Ok(())
+}
+
+#[test]
+fn exec_review_fails_on_priority_threshold() -> anyhow::Result<()> {
+    let home = TempDir::new()?;
+    review_command(&home)?
+        .arg("review")
+        .arg("--fail-on-priority")
+        .arg("0")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicates::str::contains(
+            "Review found 1 finding(s) with priority P0 or higher.",
+        ));
+
+    // Without a threshold the same findings do not fail the run.
+    review_command(&home)?.arg("review").assert().success();
+    Ok(())
+}
@@ -58,6 +58,9 @@ async fn python_multiprocessing_lock_works_under_sandbox() {
         network_access: false,
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
+        readable_roots: None,
+        deny_read: Vec::new(),
+        allowed_hosts: Vec::new(),
     };
 
     let python_code = r#"import multiprocessing
@@ -27,11 +27,18 @@ use seccompiler::apply_filter;
 
 /// Apply sandbox policies inside this thread so only the child inherits
 /// them, not the entire CLI process.
+///
+/// `network_proxied` indicates that the process already runs in a network
+/// namespace whose only way out is the filtering proxy, in which case
+/// sockets are allowed and only namespace escapes are blocked.
 pub(crate) fn apply_sandbox_policy_to_current_thread(
     sandbox_policy: &SandboxPolicy,
     cwd: &Path,
+    network_proxied: bool,
 ) -> Result<()> {
-    if !sandbox_policy.has_full_network_access() {
+    if network_proxied {
+        install_namespace_seccomp_filter_on_current_thread()?;
+    } else if !sandbox_policy.has_full_network_access() {
         install_network_seccomp_filter_on_current_thread()?;
     }
 
@@ -41,22 +48,29 @@ pub(crate) fn apply_sandbox_policy_to_current_thread(
             .into_iter()
             .map(|writable_root| writable_root.root)
             .collect();
-        install_filesystem_landlock_rules_on_current_thread(writable_roots)?;
+        install_filesystem_landlock_rules_on_current_thread(
+            writable_roots,
+            sandbox_policy.get_readable_roots_with_cwd(cwd),
+            &sandbox_policy.get_denied_read_paths(),
+        )?;
     }
 
-    // TODO(ragona): Add appropriate restrictions if
-    // `sandbox_policy.has_full_disk_read_access()` is `false`.
-
     Ok(())
 }
 
-/// Installs Landlock file-system rules on the current thread allowing read
-/// access to the entire file-system while restricting write access to
-/// `/dev/null` and the provided list of `writable_roots`.
+/// Installs Landlock file-system rules on the current thread restricting
+/// write access to `/dev/null` and the provided list of `writable_roots`.
+/// Read access is granted to the entire file-system when `readable_roots` is
+/// `None`, and otherwise only to the readable and writable roots minus
+/// `denied_paths`.
 ///
 /// # Errors
 /// Returns [`CodexErr::Sandbox`] variants when the ruleset fails to apply.
-fn install_filesystem_landlock_rules_on_current_thread(writable_roots: Vec<PathBuf>) -> Result<()> {
+fn install_filesystem_landlock_rules_on_current_thread(
+    writable_roots: Vec<PathBuf>,
+    readable_roots: Option<Vec<PathBuf>>,
+    denied_paths: &[PathBuf],
+) -> Result<()> {
     let abi = ABI::V5;
     let access_rw = AccessFs::from_all(abi);
     let access_ro = AccessFs::from_read(abi);
@@ -65,10 +79,32 @@ fn install_filesystem_landlock_rules_on_current_thread(writable_roots: Vec<PathB
         .set_compatibility(CompatLevel::BestEffort)
         .handle_access(access_rw)?
         .create()?
-        .add_rules(landlock::path_beneath_rules(&["/"], access_ro))?
         .add_rules(landlock::path_beneath_rules(&["/dev/null"], access_rw))?
         .set_no_new_privs(true);
 
+    let writable_roots = match readable_roots {
+        None => {
+            ruleset = ruleset.add_rules(landlock::path_beneath_rules(&["/"], access_ro))?;
+            writable_roots
+        }
+        Some(readable_roots) => {
+            let readable = carve_out_denied_paths(readable_roots, denied_paths);
+            let writable = carve_out_denied_paths(writable_roots, denied_paths);
+            let listable_dirs: Vec<PathBuf> = readable
+                .listable_dirs
+                .into_iter()
+                .chain(writable.listable_dirs)
+                .collect();
+            ruleset = ruleset
+                .add_rules(landlock::path_beneath_rules(&readable.roots, access_ro))?
+                .add_rules(landlock::path_beneath_rules(
+                    &listable_dirs,
+                    AccessFs::ReadDir,
+                ))?;
+            writable.roots
+        }
+    };
+
     if !writable_roots.is_empty() {
         ruleset = ruleset.add_rules(landlock::path_beneath_rules(&writable_roots, access_rw))?;
     }
@@ -82,6 +118,49 @@ fn install_filesystem_landlock_rules_on_current_thread(writable_roots: Vec<PathB
     Ok(())
 }
 
+/// Roots left after removing denied paths. Landlock rules can only grant
+/// access to whole hierarchies, so a root that contains a denied path is
+/// replaced by its children (recursively) and is itself only kept listable.
+#[derive(Debug, Default)]
+struct CarvedRoots {
+    roots: Vec<PathBuf>,
+    listable_dirs: Vec<PathBuf>,
+}
+
+fn carve_out_denied_paths(roots: Vec<PathBuf>, denied_paths: &[PathBuf]) -> CarvedRoots {
+    let denied_paths: Vec<PathBuf> = denied_paths
+        .iter()
+        .map(|path| path.canonicalize().unwrap_or_else(|_| path.clone()))
+        .collect();
+    let mut carved = CarvedRoots::default();
+    for root in roots {
+        carve_root(root, &denied_paths, &mut carved);
+    }
+    carved
+}
+
+fn carve_root(root: PathBuf, denied_paths: &[PathBuf], carved: &mut CarvedRoots) {
+    // Resolve symlinks so that a link pointing into a denied path is caught,
+    // and skip paths that do not exist since Landlock cannot open them.
+    let Ok(root) = root.canonicalize() else {
+        return;
+    };
+    if denied_paths.iter().any(|denied| root.starts_with(denied)) {
+        return;
+    }
+    if !denied_paths.iter().any(|denied| denied.starts_with(&root)) {
+        carved.roots.push(root);
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(&root) else {
+        return;
+    };
+    carved.listable_dirs.push(root);
+    for entry in entries.flatten() {
+        carve_root(entry.path(), denied_paths, carved);
+    }
+}
+
 /// Installs a seccomp filter that blocks outbound network access except for
 /// AF_UNIX domain sockets.
 fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(), SandboxErr> {
@@ -124,6 +203,24 @@ fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(),
     rules.insert(libc::SYS_socket, vec![unix_only_rule.clone()]);
     rules.insert(libc::SYS_socketpair, vec![unix_only_rule]); // always deny (Unix can use socketpair but fine, keep open?)
 
+    apply_deny_rules_on_current_thread(rules)
+}
+
+/// Installs a seccomp filter for processes whose network namespace is
+/// already isolated: sockets are allowed, but joining another namespace (and
+/// ptrace) is not.
+fn install_namespace_seccomp_filter_on_current_thread() -> std::result::Result<(), SandboxErr> {
+    let mut rules: BTreeMap<i64, Vec<SeccompRule>> = BTreeMap::new();
+    rules.insert(libc::SYS_setns, vec![]);
+    rules.insert(libc::SYS_ptrace, vec![]);
+    apply_deny_rules_on_current_thread(rules)
+}
+
+/// Applies a seccomp filter that returns `EPERM` for syscalls matching
+/// `rules` and allows everything else.
+fn apply_deny_rules_on_current_thread(
+    rules: BTreeMap<i64, Vec<SeccompRule>>,
+) -> std::result::Result<(), SandboxErr> {
     let filter = SeccompFilter::new(
         rules,
         SeccompAction::Allow,                     // default – allow
@@ -1,8 +1,13 @@
 use clap::Parser;
+use codex_core::CODEX_LINUX_SANDBOX_ARG1;
+use codex_core::spawn::CODEX_NETWORK_PROXY_SOCKET_ENV_VAR;
 use std::ffi::CString;
+use std::ffi::OsString;
+use std::path::Path;
 use std::path::PathBuf;
 
 use crate::landlock::apply_sandbox_policy_to_current_thread;
+use crate::proxy_bridge::route_network_through_proxy;
 
 #[derive(Debug, Parser)]
 pub struct LandlockCommand {
@@ -22,9 +27,14 @@ pub fn run_main() -> ! {
         sandbox_policy_cwd,
         sandbox_policy,
         command,
-    } = LandlockCommand::parse();
+    } = LandlockCommand::parse_from(sandbox_args());
 
-    if let Err(e) = apply_sandbox_policy_to_current_thread(&sandbox_policy, &sandbox_policy_cwd) {
+    let network_proxied = setup_network_proxy(&sandbox_policy);
+    if let Err(e) = apply_sandbox_policy_to_current_thread(
+        &sandbox_policy,
+        &sandbox_policy_cwd,
+        network_proxied,
+    ) {
         panic!("error running landlock: {e:?}");
     }
 
@@ -52,3 +62,42 @@ pub fn run_main() -> ! {
     let err = std::io::Error::last_os_error();
     panic!("Failed to execvp {}: {err}", command[0].as_str());
 }
+
+/// Routes the command's network traffic through the proxy advertised in
+/// [`CODEX_NETWORK_PROXY_SOCKET_ENV_VAR`] when the policy has an allowlist.
+/// Returns `false` if networking must instead be blocked entirely.
+fn setup_network_proxy(sandbox_policy: &codex_core::protocol::SandboxPolicy) -> bool {
+    let proxy_socket = std::env::var_os(CODEX_NETWORK_PROXY_SOCKET_ENV_VAR);
+    // SAFETY: the sandbox helper is single-threaded at this point.
+    unsafe { std::env::remove_var(CODEX_NETWORK_PROXY_SOCKET_ENV_VAR) };
+
+    let Some(proxy_socket) = proxy_socket else {
+        return false;
+    };
+    if sandbox_policy.get_allowed_hosts().is_empty() {
+        return false;
+    }
+    match route_network_through_proxy(Path::new(&proxy_socket)) {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!(
+                "codex-linux-sandbox: could not isolate the network for allowed_hosts, blocking all network access: {e}"
+            );
+            false
+        }
+    }
+}
+
+/// Returns the process arguments with the [`CODEX_LINUX_SANDBOX_ARG1`] marker
+/// removed, if present, so both the `codex-linux-sandbox` alias and the
+/// `codex --codex-run-as-linux-sandbox` form parse the same way.
+fn sandbox_args() -> Vec<OsString> {
+    let mut args: Vec<OsString> = std::env::args_os().collect();
+    if args
+        .get(1)
+        .is_some_and(|arg| arg == CODEX_LINUX_SANDBOX_ARG1)
+    {
+        args.remove(1);
+    }
+    args
+}
@@ -0,0 +1,144 @@
+//! Exposes Codex's filtering network proxy to a sandboxed command.
+//!
+//! The command is moved into a fresh network namespace whose only interface
+//! is loopback, so the proxy is its only way out. A small bridge process
+//! stays in that namespace and forwards every connection made to a loopback
+//! port to the proxy's Unix socket, which is reachable by path from any
+//! network namespace.
+
+use std::io;
+use std::net::Ipv4Addr;
+use std::net::Shutdown;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::os::fd::AsRawFd;
+use std::os::fd::FromRawFd;
+use std::os::fd::OwnedFd;
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+
+/// Environment variables that point common HTTP clients at the bridge.
+const PROXY_ENV_VARS: &[&str] = &[
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "ALL_PROXY",
+    "http_proxy",
+    "https_proxy",
+    "all_proxy",
+];
+
+/// Moves the current process into a new network namespace and starts the
+/// bridge to `proxy_socket`, then exports the proxy environment variables
+/// for the command that is about to be exec'd.
+///
+/// Must be called while the process is still single-threaded.
+pub(crate) fn route_network_through_proxy(proxy_socket: &Path) -> io::Result<()> {
+    enter_network_namespace()?;
+    bring_up_loopback()?;
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let port = listener.local_addr()?.port();
+    let parent_pid = unsafe { libc::getpid() };
+    match unsafe { libc::fork() } {
+        -1 => return Err(io::Error::last_os_error()),
+        0 => run_bridge(listener, proxy_socket, parent_pid),
+        _ => drop(listener),
+    }
+
+    let proxy_url = format!("http://127.0.0.1:{port}");
+    for name in PROXY_ENV_VARS {
+        // SAFETY: the sandbox helper is single-threaded at this point.
+        unsafe { std::env::set_var(name, &proxy_url) };
+    }
+    Ok(())
+}
+
+fn enter_network_namespace() -> io::Result<()> {
+    if unsafe { libc::unshare(libc::CLONE_NEWNET) } == 0 {
+        return Ok(());
+    }
+
+    // Without CAP_SYS_ADMIN a network namespace can only be created together
+    // with a user namespace, in which we map ourselves to our own ids so
+    // file ownership inside the sandbox is unchanged.
+    let uid = unsafe { libc::geteuid() };
+    let gid = unsafe { libc::getegid() };
+    if unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    std::fs::write("/proc/self/setgroups", "deny")?;
+    std::fs::write("/proc/self/uid_map", format!("{uid} {uid} 1"))?;
+    std::fs::write("/proc/self/gid_map", format!("{gid} {gid} 1"))?;
+    Ok(())
+}
+
+/// The loopback interface of a new network namespace starts out down.
+fn bring_up_loopback() -> io::Result<()> {
+    let fd = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+
+    let mut request: libc::ifreq = unsafe { std::mem::zeroed() };
+    for (dst, src) in request.ifr_name.iter_mut().zip(b"lo") {
+        *dst = *src as libc::c_char;
+    }
+    if unsafe { libc::ioctl(socket.as_raw_fd(), libc::SIOCGIFFLAGS as _, &mut request) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    unsafe {
+        request.ifr_ifru.ifru_flags |= (libc::IFF_UP | libc::IFF_RUNNING) as libc::c_short;
+    }
+    if unsafe { libc::ioctl(socket.as_raw_fd(), libc::SIOCSIFFLAGS as _, &request) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Body of the forked bridge process. It dies with the sandboxed command and
+/// never writes to the command's stdout or stderr.
+fn run_bridge(listener: TcpListener, proxy_socket: &Path, parent_pid: libc::pid_t) -> ! {
+    unsafe {
+        libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL);
+        if libc::getppid() != parent_pid {
+            libc::_exit(0);
+        }
+    }
+    redirect_stdio_to_dev_null();
+
+    for client in listener.incoming().flatten() {
+        let proxy_socket = proxy_socket.to_path_buf();
+        std::thread::spawn(move || forward_connection(client, &proxy_socket));
+    }
+    unsafe { libc::_exit(0) }
+}
+
+fn redirect_stdio_to_dev_null() {
+    let Ok(dev_null) = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/null")
+    else {
+        return;
+    };
+    for fd in [libc::STDIN_FILENO, libc::STDOUT_FILENO, libc::STDERR_FILENO] {
+        unsafe { libc::dup2(dev_null.as_raw_fd(), fd) };
+    }
+}
+
+fn forward_connection(client: TcpStream, proxy_socket: &Path) -> io::Result<()> {
+    let upstream = UnixStream::connect(proxy_socket)?;
+    let mut client_reader = client.try_clone()?;
+    let mut upstream_writer = upstream.try_clone()?;
+    let uploader = std::thread::spawn(move || {
+        let _ = io::copy(&mut client_reader, &mut upstream_writer);
+        let _ = upstream_writer.shutdown(Shutdown::Write);
+    });
+
+    let (mut client_writer, mut upstream_reader) = (client, upstream);
+    let _ = io::copy(&mut upstream_reader, &mut client_writer);
+    let _ = client_writer.shutdown(Shutdown::Write);
+    let _ = uploader.join();
+    Ok(())
+}
@@ -33,17 +33,7 @@ fn create_env_from_core_vars() -> HashMap<String, String> {
     create_env(&policy)
 }
 
-#[expect(clippy::print_stdout, clippy::expect_used, clippy::unwrap_used)]
 async fn run_cmd(cmd: &[&str], writable_roots: &[PathBuf], timeout_ms: u64) {
-    let params = ExecParams {
-        command: cmd.iter().map(|elm| elm.to_string()).collect(),
-        cwd: std::env::current_dir().expect("cwd should exist"),
-        timeout_ms: Some(timeout_ms),
-        env: create_env_from_core_vars(),
-        with_escalated_permissions: None,
-        justification: None,
-    };
-
     let sandbox_policy = SandboxPolicy::WorkspaceWrite {
         writable_roots: writable_roots.to_vec(),
         network_access: false,
@@ -52,13 +42,30 @@ async fn run_cmd(cmd: &[&str], writable_roots: &[PathBuf], timeout_ms: u64) {
         // writing to in the sandbox.
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        readable_roots: None,
+        deny_read: Vec::new(),
+        allowed_hosts: Vec::new(),
+    };
+    run_cmd_with_policy(cmd, &sandbox_policy, timeout_ms).await;
+}
+
+#[expect(clippy::print_stdout, clippy::expect_used, clippy::unwrap_used)]
+async fn run_cmd_with_policy(cmd: &[&str], sandbox_policy: &SandboxPolicy, timeout_ms: u64) {
+    let params = ExecParams {
+        command: cmd.iter().map(|elm| elm.to_string()).collect(),
+        cwd: std::env::current_dir().expect("cwd should exist"),
+        timeout_ms: Some(timeout_ms),
+        env: create_env_from_core_vars(),
+        with_escalated_permissions: None,
+        justification: None,
     };
+
     let sandbox_program = env!("CARGO_BIN_EXE_codex-linux-sandbox");
     let codex_linux_sandbox_exe = Some(PathBuf::from(sandbox_program));
     let res = process_exec_tool_call(
         params,
         SandboxType::LinuxSeccomp,
-        &sandbox_policy,
+        sandbox_policy,
         &codex_linux_sandbox_exe,
         None,
     )
@@ -120,6 +127,83 @@ async fn test_writable_root() {
     .await;
 }
 
+fn restricted_read_policy(readable_roots: Vec<PathBuf>, deny_read: Vec<PathBuf>) -> SandboxPolicy {
+    SandboxPolicy::WorkspaceWrite {
+        writable_roots: vec![],
+        network_access: false,
+        exclude_tmpdir_env_var: true,
+        exclude_slash_tmp: true,
+        readable_roots: Some(readable_roots),
+        deny_read,
+        allowed_hosts: Vec::new(),
+    }
+}
+
+#[tokio::test]
+#[should_panic]
+async fn test_read_outside_readable_roots() {
+    let tmpfile = NamedTempFile::new().unwrap();
+    let tmpfile_path = tmpfile.path().to_string_lossy();
+    run_cmd_with_policy(
+        &["cat", &tmpfile_path],
+        &restricted_read_policy(vec![], vec![]),
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_readable_root() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let file_path = tmpdir.path().join("notes.txt");
+    std::fs::write(&file_path, "hello").unwrap();
+    run_cmd_with_policy(
+        &["cat", &file_path.to_string_lossy()],
+        &restricted_read_policy(vec![tmpdir.path().to_path_buf()], vec![]),
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+#[tokio::test]
+#[should_panic]
+async fn test_deny_read_inside_readable_root() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let secrets = tmpdir.path().join("secrets");
+    std::fs::create_dir(&secrets).unwrap();
+    let secret_file = secrets.join("token");
+    std::fs::write(&secret_file, "hunter2").unwrap();
+    run_cmd_with_policy(
+        &["cat", &secret_file.to_string_lossy()],
+        &restricted_read_policy(vec![tmpdir.path().to_path_buf()], vec![secrets]),
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_deny_read_keeps_siblings_readable() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let secrets = tmpdir.path().join("secrets");
+    std::fs::create_dir(&secrets).unwrap();
+    let file_path = tmpdir.path().join("notes.txt");
+    std::fs::write(&file_path, "hello").unwrap();
+    run_cmd_with_policy(
+        &[
+            "bash",
+            "-c",
+            &format!(
+                "ls {} && cat {}",
+                tmpdir.path().to_string_lossy(),
+                file_path.to_string_lossy()
+            ),
+        ],
+        &restricted_read_policy(vec![tmpdir.path().to_path_buf()], vec![secrets]),
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
 #[tokio::test]
 #[should_panic(expected = "Sandbox(Timeout")]
 async fn test_timeout() {
@@ -229,3 +313,103 @@ async fn sandbox_blocks_dev_tcp_redirection() {
     // all images ship bash, so we guard against 127 as well.
     assert_network_blocked(&["bash", "-c", "echo hi > /dev/tcp/127.0.0.1/80"]).await;
 }
+
+/// Serves a single canned HTTP response on 127.0.0.2, which sits outside the
+/// sandbox's network namespace and is not covered by common `NO_PROXY`
+/// defaults.
+#[expect(clippy::unwrap_used)]
+async fn spawn_http_server() -> u16 {
+    use tokio::io::AsyncReadExt;
+    use tokio::io::AsyncWriteExt;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.2:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf).await;
+            let _ = stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+                .await;
+        }
+    });
+    port
+}
+
+#[expect(clippy::unwrap_used)]
+async fn run_curl_with_allowed_hosts(
+    curl_args: &[&str],
+    allowed_hosts: &[&str],
+) -> codex_core::exec::ExecToolCallOutput {
+    let mut env = create_env_from_core_vars();
+    env.retain(|name, _| !name.eq_ignore_ascii_case("no_proxy"));
+    let params = ExecParams {
+        command: std::iter::once("curl")
+            .chain(curl_args.iter().copied())
+            .map(str::to_string)
+            .collect(),
+        cwd: std::env::current_dir().unwrap(),
+        timeout_ms: Some(NETWORK_TIMEOUT_MS),
+        env,
+        with_escalated_permissions: None,
+        justification: None,
+    };
+    let sandbox_policy = SandboxPolicy::WorkspaceWrite {
+        writable_roots: vec![],
+        network_access: false,
+        exclude_tmpdir_env_var: true,
+        exclude_slash_tmp: true,
+        readable_roots: None,
+        deny_read: Vec::new(),
+        allowed_hosts: allowed_hosts.iter().map(|host| host.to_string()).collect(),
+    };
+    let sandbox_program = env!("CARGO_BIN_EXE_codex-linux-sandbox");
+    let codex_linux_sandbox_exe = Some(PathBuf::from(sandbox_program));
+    match process_exec_tool_call(
+        params,
+        SandboxType::LinuxSeccomp,
+        &sandbox_policy,
+        &codex_linux_sandbox_exe,
+        None,
+    )
+    .await
+    {
+        Ok(output) => output,
+        Err(CodexErr::Sandbox(SandboxErr::Denied { output })) => *output,
+        Err(err) => panic!("unexpected error: {err:?}"),
+    }
+}
+
+#[tokio::test]
+async fn sandbox_reaches_allowed_host_through_proxy() {
+    let port = spawn_http_server().await;
+    let url = format!("http://127.0.0.2:{port}/");
+
+    let output = run_curl_with_allowed_hosts(&["-sf", &url], &["127.0.0.2"]).await;
+    if output.exit_code == 127 {
+        eprintln!("skipping test: curl not found");
+        return;
+    }
+    assert_eq!(0, output.exit_code, "stderr: {}", output.stderr.text);
+    assert_eq!("ok", output.stdout.text);
+    assert!(output.denied_hosts.is_empty());
+
+    // Bypassing the proxy must not work, even for an allowed host.
+    let output =
+        run_curl_with_allowed_hosts(&["-sf", "--noproxy", "*", &url], &["127.0.0.2"]).await;
+    assert_ne!(0, output.exit_code);
+}
+
+#[tokio::test]
+async fn sandbox_reports_hosts_denied_by_proxy() {
+    let port = spawn_http_server().await;
+    let url = format!("http://127.0.0.2:{port}/");
+
+    let output = run_curl_with_allowed_hosts(&["-sf", &url], &["*.registry.internal"]).await;
+    if output.exit_code == 127 {
+        eprintln!("skipping test: curl not found");
+        return;
+    }
+    assert_ne!(0, output.exit_code);
+    assert_eq!(vec!["127.0.0.2".to_string()], output.denied_hosts);
+}
@@ -0,0 +1,783 @@
+//! HTTP transports for MCP servers that run as long-lived services.
+//!
+//! Two transports are supported:
+//!
+//! * **Streamable HTTP** (MCP 2025-03-26 and later): every client message is
+//!   `POST`ed to a single endpoint. The server answers with either a JSON body
+//!   or a `text/event-stream` that carries the response (possibly preceded by
+//!   notifications). The `Mcp-Session-Id` returned when initializing is echoed
+//!   on every subsequent request.
+//! * **HTTP+SSE** (MCP 2024-11-05): the client keeps a `GET` event stream
+//!   open. The server announces an `endpoint` to `POST` messages to and
+//!   delivers all replies over the event stream.
+//!
+//! Both transports reconnect with exponential backoff: connection failures
+//! are retried, an interrupted Streamable HTTP response is resumed with
+//! `Last-Event-ID` when the server assigned event ids, and a dropped HTTP+SSE
+//! stream is re-established.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use eventsource_stream::Eventsource;
+use futures::StreamExt;
+use mcp_types::InitializeRequest;
+use mcp_types::InitializedNotification;
+use mcp_types::JSONRPC_VERSION;
+use mcp_types::JSONRPCError;
+use mcp_types::JSONRPCErrorError;
+use mcp_types::JSONRPCMessage;
+use mcp_types::ModelContextProtocolNotification;
+use mcp_types::ModelContextProtocolRequest;
+use mcp_types::RequestId;
+use reqwest::Method;
+use reqwest::RequestBuilder;
+use reqwest::Response;
+use reqwest::StatusCode;
+use reqwest::Url;
+use reqwest::header::ACCEPT;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::header::HeaderMap;
+use reqwest::header::HeaderName;
+use reqwest::header::HeaderValue;
+use tokio::sync::Mutex;
+use tokio::sync::RwLock;
+use tokio::sync::mpsc;
+use tokio::sync::watch;
+use tracing::debug;
+use tracing::error;
+use tracing::warn;
+
+use crate::McpClient;
+use crate::mcp_client::CHANNEL_CAPACITY;
+use crate::mcp_client::PendingRequests;
+use crate::mcp_client::dispatch_incoming;
+
+/// Number of consecutive reconnection attempts before giving up.
+const MAX_RECONNECT_ATTEMPTS: u32 = 5;
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_millis(200);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(10);
+
+const SESSION_ID_HEADER: &str = "mcp-session-id";
+const PROTOCOL_VERSION_HEADER: &str = "mcp-protocol-version";
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+const EVENT_STREAM_MIME_TYPE: &str = "text/event-stream";
+const JSON_MIME_TYPE: &str = "application/json";
+
+/// JSON-RPC "internal error" code used when the transport itself fails.
+const INTERNAL_ERROR_CODE: i64 = -32603;
+
+impl McpClient {
+    /// Connect to an MCP server that speaks the Streamable HTTP transport at
+    /// `url`. `headers` are sent with every request (e.g. `Authorization`).
+    /// Caller is responsible for sending the `initialize` request. See
+    /// [`initialize`](Self::initialize) for details.
+    pub async fn new_streamable_http_client(
+        url: String,
+        headers: HashMap<String, String>,
+    ) -> Result<Self> {
+        let url = Url::parse(&url).with_context(|| format!("invalid MCP server url `{url}`"))?;
+        let headers = header_map(headers)?;
+        let (outgoing_tx, mut outgoing_rx) = mpsc::channel::<JSONRPCMessage>(CHANNEL_CAPACITY);
+        let pending: PendingRequests = Arc::new(Mutex::new(HashMap::new()));
+
+        let connection = Arc::new(StreamableHttpConnection {
+            http: reqwest::Client::new(),
+            url,
+            headers,
+            session_id: RwLock::new(None),
+            protocol_version: RwLock::new(None),
+            pending: pending.clone(),
+        });
+
+        // Notifications, responses and the initialize handshake are posted in
+        // the order they were sent. Once the handshake is complete, each
+        // request is posted from its own task so that a long-running tool call
+        // does not hold up unrelated requests.
+        let writer_handle = tokio::spawn(async move {
+            let mut initialized = false;
+            while let Some(message) = outgoing_rx.recv().await {
+                if initialized && matches!(message, JSONRPCMessage::Request(_)) {
+                    let connection = connection.clone();
+                    tokio::spawn(async move { connection.send(message).await });
+                    continue;
+                }
+                initialized |= matches!(
+                    &message,
+                    JSONRPCMessage::Notification(notification)
+                        if notification.method == InitializedNotification::METHOD
+                );
+                connection.send(message).await;
+            }
+        });
+
+        Ok(Self::from_http_transport(
+            outgoing_tx,
+            pending,
+            vec![writer_handle],
+        ))
+    }
+
+    /// Connect to an MCP server that speaks the legacy HTTP+SSE transport,
+    /// where `url` is the server's event stream endpoint. `headers` are sent
+    /// with every request (e.g. `Authorization`).
+    ///
+    /// If the event stream drops, the client reconnects and posts subsequent
+    /// messages to the newly announced endpoint. Servers that keep per-stream
+    /// state may require the caller to initialize again.
+    pub async fn new_sse_client(url: String, headers: HashMap<String, String>) -> Result<Self> {
+        let url = Url::parse(&url).with_context(|| format!("invalid MCP server url `{url}`"))?;
+        let headers = header_map(headers)?;
+        let http = reqwest::Client::new();
+        let (outgoing_tx, mut outgoing_rx) = mpsc::channel::<JSONRPCMessage>(CHANNEL_CAPACITY);
+        let pending: PendingRequests = Arc::new(Mutex::new(HashMap::new()));
+        let (endpoint_tx, mut endpoint_rx) = watch::channel::<Option<Url>>(None);
+
+        let reader_handle = tokio::spawn(run_sse_event_stream(
+            http.clone(),
+            url,
+            headers.clone(),
+            endpoint_tx,
+            pending.clone(),
+        ));
+
+        // Messages are posted sequentially: the server replies over the event
+        // stream, so each `POST` returns as soon as the message is accepted.
+        let writer_handle = {
+            let pending = pending.clone();
+            tokio::spawn(async move {
+                while let Some(message) = outgoing_rx.recv().await {
+                    let request_id = request_id(&message);
+                    let endpoint = match endpoint_rx.wait_for(Option::is_some).await {
+                        Ok(endpoint) => endpoint.clone(),
+                        Err(_) => None,
+                    };
+                    let result = match endpoint {
+                        Some(endpoint) => post_to_sse_endpoint(&http, endpoint, &headers, &message)
+                            .await
+                            .map_err(|e| format!("{e:#}")),
+                        None => Err("MCP event stream is closed".to_string()),
+                    };
+                    if let Err(e) = result {
+                        warn!("failed to post MCP message: {e}");
+                        if let Some(id) = request_id {
+                            fail_request(id, e, &pending).await;
+                        }
+                    }
+                }
+            })
+        };
+
+        Ok(Self::from_http_transport(
+            outgoing_tx,
+            pending,
+            vec![reader_handle, writer_handle],
+        ))
+    }
+}
+
+/// Shared state for a Streamable HTTP connection.
+struct StreamableHttpConnection {
+    http: reqwest::Client,
+    url: Url,
+    headers: HeaderMap,
+    /// Assigned by the server in its reply to `initialize`, if at all.
+    session_id: RwLock<Option<String>>,
+    /// Protocol version negotiated by `initialize`.
+    protocol_version: RwLock<Option<String>>,
+    pending: PendingRequests,
+}
+
+impl StreamableHttpConnection {
+    async fn send(&self, message: JSONRPCMessage) {
+        let request_id = request_id(&message);
+        let is_initialize = matches!(
+            &message,
+            JSONRPCMessage::Request(request) if request.method == InitializeRequest::METHOD
+        );
+        if let Err(e) = self
+            .post(&message, request_id.as_ref(), is_initialize)
+            .await
+        {
+            warn!("MCP request to {} failed: {e:#}", self.url);
+            if let Some(id) = request_id {
+                fail_request(id, format!("{e:#}"), &self.pending).await;
+            }
+        }
+    }
+
+    async fn request(&self, method: Method) -> RequestBuilder {
+        let mut builder = self
+            .http
+            .request(method, self.url.clone())
+            .headers(self.headers.clone())
+            .header(
+                ACCEPT,
+                format!("{JSON_MIME_TYPE}, {EVENT_STREAM_MIME_TYPE}"),
+            );
+        if let Some(session_id) = self.session_id.read().await.as_ref() {
+            builder = builder.header(SESSION_ID_HEADER, session_id);
+        }
+        if let Some(protocol_version) = self.protocol_version.read().await.as_ref() {
+            builder = builder.header(PROTOCOL_VERSION_HEADER, protocol_version);
+        }
+        builder
+    }
+
+    async fn post(
+        &self,
+        message: &JSONRPCMessage,
+        request_id: Option<&RequestId>,
+        is_initialize: bool,
+    ) -> Result<()> {
+        let body = serde_json::to_vec(message)?;
+        let builder = self
+            .request(Method::POST)
+            .await
+            .header(CONTENT_TYPE, JSON_MIME_TYPE)
+            .body(body);
+        let response = send_with_retries(builder).await?;
+
+        if response.status() == StatusCode::NOT_FOUND && self.session_id.read().await.is_some() {
+            bail!("MCP session expired; the server no longer recognizes the session id");
+        }
+        let response = response.error_for_status()?;
+
+        if is_initialize
+            && let Some(session_id) = response
+                .headers()
+                .get(SESSION_ID_HEADER)
+                .and_then(|value| value.to_str().ok())
+        {
+            *self.session_id.write().await = Some(session_id.to_string());
+        }
+
+        if response.status() == StatusCode::ACCEPTED {
+            return Ok(());
+        }
+
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if content_type.starts_with(EVENT_STREAM_MIME_TYPE) {
+            self.read_event_stream(response, request_id, is_initialize)
+                .await
+        } else {
+            let body = response.bytes().await?;
+            if body.is_empty() {
+                return Ok(());
+            }
+            let message = serde_json::from_slice::<JSONRPCMessage>(&body)
+                .context("failed to deserialize MCP response")?;
+            self.dispatch(message, is_initialize).await;
+            Ok(())
+        }
+    }
+
+    /// Read messages off an event stream until the response to `request_id`
+    /// arrives. If the stream is interrupted first, resume it with
+    /// `Last-Event-ID` when possible.
+    async fn read_event_stream(
+        &self,
+        mut response: Response,
+        request_id: Option<&RequestId>,
+        is_initialize: bool,
+    ) -> Result<()> {
+        let mut last_event_id: Option<String> = None;
+        let mut attempt = 0;
+        loop {
+            let mut events = response.bytes_stream().eventsource();
+            while let Some(event) = events.next().await {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(e) => {
+                        warn!("MCP event stream from {} interrupted: {e}", self.url);
+                        break;
+                    }
+                };
+                attempt = 0;
+                if !event.id.is_empty() {
+                    last_event_id = Some(event.id.clone());
+                }
+                if event.data.is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<JSONRPCMessage>(&event.data) {
+                    Ok(message) => {
+                        let is_reply = is_reply_to(&message, request_id);
+                        self.dispatch(message, is_initialize).await;
+                        if is_reply {
+                            return Ok(());
+                        }
+                    }
+                    Err(e) => error!(
+                        "failed to deserialize JSONRPCMessage: {e}; data = {}",
+                        event.data
+                    ),
+                }
+            }
+
+            if request_id.is_none() {
+                return Ok(());
+            }
+            let Some(event_id) = last_event_id.as_ref() else {
+                bail!("event stream closed before a response was received");
+            };
+            attempt += 1;
+            if attempt > MAX_RECONNECT_ATTEMPTS {
+                bail!("event stream closed before a response was received");
+            }
+            tokio::time::sleep(reconnect_delay(attempt)).await;
+            debug!(
+                "resuming MCP event stream from {} after {event_id}",
+                self.url
+            );
+            let builder = self
+                .request(Method::GET)
+                .await
+                .header(ACCEPT, EVENT_STREAM_MIME_TYPE)
+                .header(LAST_EVENT_ID_HEADER, event_id);
+            response = send_with_retries(builder).await?.error_for_status()?;
+        }
+    }
+
+    async fn dispatch(&self, message: JSONRPCMessage, is_initialize: bool) {
+        if is_initialize
+            && let JSONRPCMessage::Response(response) = &message
+            && let Some(version) = response
+                .result
+                .get("protocolVersion")
+                .and_then(|v| v.as_str())
+        {
+            *self.protocol_version.write().await = Some(version.to_string());
+        }
+        dispatch_incoming(message, &self.pending).await;
+    }
+}
+
+/// Keep the HTTP+SSE event stream open, publishing the endpoint announced by
+/// the server and dispatching every message it delivers.
+async fn run_sse_event_stream(
+    http: reqwest::Client,
+    url: Url,
+    headers: HeaderMap,
+    endpoint_tx: watch::Sender<Option<Url>>,
+    pending: PendingRequests,
+) {
+    let mut last_event_id: Option<String> = None;
+    let mut attempt = 0;
+    loop {
+        let mut builder = http
+            .get(url.clone())
+            .headers(headers.clone())
+            .header(ACCEPT, EVENT_STREAM_MIME_TYPE);
+        if let Some(event_id) = last_event_id.as_ref() {
+            builder = builder.header(LAST_EVENT_ID_HEADER, event_id);
+        }
+
+        match send_with_retries(builder)
+            .await
+            .and_then(Response::error_for_status)
+        {
+            Ok(response) => {
+                let mut events = response.bytes_stream().eventsource();
+                while let Some(event) = events.next().await {
+                    let event = match event {
+                        Ok(event) => event,
+                        Err(e) => {
+                            warn!("MCP event stream from {url} interrupted: {e}");
+                            break;
+                        }
+                    };
+                    attempt = 0;
+                    if !event.id.is_empty() {
+                        last_event_id = Some(event.id.clone());
+                    }
+                    match event.event.as_str() {
+                        "endpoint" => match url.join(event.data.trim()) {
+                            Ok(endpoint) => {
+                                debug!("MCP server announced endpoint {endpoint}");
+                                endpoint_tx.send_replace(Some(endpoint));
+                            }
+                            Err(e) => error!("invalid MCP endpoint `{}`: {e}", event.data),
+                        },
+                        "" | "message" => {
+                            match serde_json::from_str::<JSONRPCMessage>(&event.data) {
+                                Ok(message) => dispatch_incoming(message, &pending).await,
+                                Err(e) => error!(
+                                    "failed to deserialize JSONRPCMessage: {e}; data = {}",
+                                    event.data
+                                ),
+                            }
+                        }
+                        other => debug!("ignoring MCP event `{other}`"),
+                    }
+                }
+            }
+            Err(e) => warn!("failed to connect to MCP event stream {url}: {e}"),
+        }
+
+        // The endpoint belongs to the stream that just ended.
+        endpoint_tx.send_replace(None);
+        attempt += 1;
+        if attempt > MAX_RECONNECT_ATTEMPTS {
+            error!("giving up on MCP event stream {url} after {MAX_RECONNECT_ATTEMPTS} attempts");
+            fail_all_requests("MCP event stream is closed", &pending).await;
+            return;
+        }
+        tokio::time::sleep(reconnect_delay(attempt)).await;
+    }
+}
+
+async fn post_to_sse_endpoint(
+    http: &reqwest::Client,
+    endpoint: Url,
+    headers: &HeaderMap,
+    message: &JSONRPCMessage,
+) -> Result<()> {
+    let builder = http
+        .post(endpoint)
+        .headers(headers.clone())
+        .header(CONTENT_TYPE, JSON_MIME_TYPE)
+        .body(serde_json::to_vec(message)?);
+    send_with_retries(builder).await?.error_for_status()?;
+    Ok(())
+}
+
+/// Send the request, retrying connection failures with exponential backoff.
+/// Only failures to connect are retried: the server never saw those requests,
+/// whereas a request that timed out may already have run (e.g. a tool call
+/// with side effects) and must not be sent again.
+async fn send_with_retries(builder: RequestBuilder) -> reqwest::Result<Response> {
+    let mut attempt = 0;
+    loop {
+        // Bodies are always in-memory buffers, so the builder can be cloned.
+        let Some(request) = builder.try_clone() else {
+            return builder.send().await;
+        };
+        match request.send().await {
+            Err(e) if e.is_connect() && attempt < MAX_RECONNECT_ATTEMPTS => {
+                attempt += 1;
+                warn!("MCP server unreachable ({e}); retrying (attempt {attempt})");
+                tokio::time::sleep(reconnect_delay(attempt)).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn reconnect_delay(attempt: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+    INITIAL_RECONNECT_DELAY
+        .saturating_mul(factor)
+        .min(MAX_RECONNECT_DELAY)
+}
+
+fn header_map(headers: HashMap<String, String>) -> Result<HeaderMap> {
+    let mut map = HeaderMap::with_capacity(headers.len());
+    for (name, value) in headers {
+        let header_name = HeaderName::from_bytes(name.as_bytes())
+            .with_context(|| format!("invalid HTTP header name `{name}`"))?;
+        let header_value = HeaderValue::from_str(&value)
+            .with_context(|| format!("invalid value for HTTP header `{name}`"))?;
+        map.insert(header_name, header_value);
+    }
+    Ok(map)
+}
+
+fn request_id(message: &JSONRPCMessage) -> Option<RequestId> {
+    match message {
+        JSONRPCMessage::Request(request) => Some(request.id.clone()),
+        _ => None,
+    }
+}
+
+fn is_reply_to(message: &JSONRPCMessage, request_id: Option<&RequestId>) -> bool {
+    match (message, request_id) {
+        (JSONRPCMessage::Response(response), Some(id)) => &response.id == id,
+        (JSONRPCMessage::Error(error), Some(id)) => &error.id == id,
+        _ => false,
+    }
+}
+
+/// Resolve the pending request `id` with a JSON-RPC error so the caller does
+/// not wait for a reply that will never come.
+async fn fail_request(id: RequestId, message: String, pending: &PendingRequests) {
+    let error = JSONRPCError {
+        error: JSONRPCErrorError {
+            code: INTERNAL_ERROR_CODE,
+            data: None,
+            message,
+        },
+        id,
+        jsonrpc: JSONRPC_VERSION.to_string(),
+    };
+    dispatch_incoming(JSONRPCMessage::Error(error), pending).await;
+}
+
+async fn fail_all_requests(message: &str, pending: &PendingRequests) {
+    let ids: Vec<i64> = pending.lock().await.keys().copied().collect();
+    for id in ids {
+        fail_request(RequestId::Integer(id), message.to_string(), pending).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_types::ClientCapabilities;
+    use mcp_types::Implementation;
+    use mcp_types::InitializeRequestParams;
+    use mcp_types::ListToolsRequest;
+    use mcp_types::ListToolsResult;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::body_partial_json;
+    use wiremock::matchers::header;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+    use wiremock::matchers::query_param;
+
+    fn tools_list_result(id: i64) -> serde_json::Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": { "tools": [] },
+        })
+    }
+
+    #[tokio::test]
+    async fn streamable_http_json_response() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/mcp"))
+            .and(header("authorization", "Bearer secret"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(tools_list_result(1)))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = McpClient::new_streamable_http_client(
+            format!("{}/mcp", server.uri()),
+            HashMap::from([("Authorization".to_string(), "Bearer secret".to_string())]),
+        )
+        .await
+        .unwrap();
+        let result = client
+            .send_request::<ListToolsRequest>(None, Some(Duration::from_secs(5)))
+            .await
+            .unwrap();
+        assert_eq!(
+            result,
+            ListToolsResult {
+                next_cursor: None,
+                tools: vec![],
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn streamable_http_event_stream_response() {
+        let server = MockServer::start().await;
+        let body = format!(
+            "event: message\ndata: {}\n\nevent: message\ndata: {}\n\n",
+            json!({"jsonrpc": "2.0", "method": "notifications/progress", "params": {"progress": 1, "progressToken": 1}}),
+            tools_list_result(1),
+        );
+        Mock::given(method("POST"))
+            .and(path("/mcp"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body, EVENT_STREAM_MIME_TYPE))
+            .mount(&server)
+            .await;
+
+        let client =
+            McpClient::new_streamable_http_client(format!("{}/mcp", server.uri()), HashMap::new())
+                .await
+                .unwrap();
+        let result = client
+            .send_request::<ListToolsRequest>(None, Some(Duration::from_secs(5)))
+            .await
+            .unwrap();
+        assert!(result.tools.is_empty());
+    }
+
+    #[tokio::test]
+    async fn streamable_http_error_status_fails_request() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let client = McpClient::new_streamable_http_client(server.uri(), HashMap::new())
+            .await
+            .unwrap();
+        let err = client
+            .send_request::<ListToolsRequest>(None, Some(Duration::from_secs(5)))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("500"), "unexpected error: {err:#}");
+    }
+
+    #[tokio::test]
+    async fn streamable_http_posts_handshake_before_later_requests() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({"method": "initialize"})))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header(SESSION_ID_HEADER, "session-1")
+                    .set_body_json(json!({
+                        "jsonrpc": "2.0",
+                        "id": 1,
+                        "result": {
+                            "capabilities": {},
+                            "protocolVersion": "2025-03-26",
+                            "serverInfo": { "name": "stub", "version": "1.0.0" },
+                        },
+                    })),
+            )
+            .mount(&server)
+            .await;
+        // A slow acknowledgement would let a concurrently posted request
+        // overtake the notification.
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": InitializedNotification::METHOD}),
+            ))
+            .respond_with(ResponseTemplate::new(202).set_delay(Duration::from_millis(200)))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({"method": "tools/list"})))
+            .and(header(SESSION_ID_HEADER, "session-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(tools_list_result(2)))
+            .mount(&server)
+            .await;
+
+        let client = McpClient::new_streamable_http_client(server.uri(), HashMap::new())
+            .await
+            .unwrap();
+        client
+            .initialize(
+                InitializeRequestParams {
+                    capabilities: ClientCapabilities {
+                        elicitation: None,
+                        experimental: None,
+                        roots: None,
+                        sampling: None,
+                    },
+                    client_info: Implementation {
+                        name: "codex-mcp-client".to_string(),
+                        title: None,
+                        version: "0.0.0".to_string(),
+                        user_agent: None,
+                    },
+                    protocol_version: "2025-03-26".to_string(),
+                },
+                None,
+                Some(Duration::from_secs(5)),
+            )
+            .await
+            .unwrap();
+        client
+            .send_request::<ListToolsRequest>(None, Some(Duration::from_secs(5)))
+            .await
+            .unwrap();
+
+        let methods: Vec<String> = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|request| {
+                let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+                body["method"].as_str().unwrap_or_default().to_string()
+            })
+            .collect();
+        assert_eq!(
+            methods,
+            vec![
+                "initialize".to_string(),
+                InitializedNotification::METHOD.to_string(),
+                "tools/list".to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn sse_posts_to_announced_endpoint_and_reads_replies_from_stream() {
+        let server = MockServer::start().await;
+        let body = format!(
+            "event: endpoint\ndata: /messages?session=1\n\nevent: message\ndata: {}\n\n",
+            tools_list_result(1),
+        );
+        // Delay the stream so the request is pending by the time its reply
+        // is delivered.
+        Mock::given(method("GET"))
+            .and(path("/sse"))
+            .and(header("accept", EVENT_STREAM_MIME_TYPE))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw(body, EVENT_STREAM_MIME_TYPE)
+                    .set_delay(Duration::from_millis(100)),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/messages"))
+            .and(query_param("session", "1"))
+            .and(body_partial_json(json!({"method": "tools/list", "id": 1})))
+            .respond_with(ResponseTemplate::new(202))
+            .mount(&server)
+            .await;
+
+        let client = McpClient::new_sse_client(format!("{}/sse", server.uri()), HashMap::new())
+            .await
+            .unwrap();
+        let result = client
+            .send_request::<ListToolsRequest>(None, Some(Duration::from_secs(5)))
+            .await
+            .unwrap();
+        assert!(result.tools.is_empty());
+
+        // The reply may be read off the stream before the POST completes.
+        let mut posted = false;
+        for _ in 0..50 {
+            posted = server
+                .received_requests()
+                .await
+                .unwrap()
+                .iter()
+                .any(|request| request.method == Method::POST);
+            if posted {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(posted, "expected the request to be posted to the endpoint");
+    }
+
+    #[test]
+    fn rejects_invalid_headers() {
+        assert!(header_map(HashMap::from([("bad header".to_string(), "x".to_string())])).is_err());
+        assert!(header_map(HashMap::from([("x-ok".to_string(), "x".to_string())])).is_ok());
+    }
+
+    #[test]
+    fn reconnect_delay_is_capped() {
+        assert_eq!(reconnect_delay(1), INITIAL_RECONNECT_DELAY);
+        assert_eq!(reconnect_delay(2), INITIAL_RECONNECT_DELAY * 2);
+        assert_eq!(reconnect_delay(30), MAX_RECONNECT_DELAY);
+    }
+}
@@ -1,3 +1,4 @@
+mod http;
 mod mcp_client;
 
 pub use mcp_client::McpClient;
@@ -2,7 +2,8 @@
 //!
 //! The client is intentionally lightweight – it is only capable of:
 //!   1. Spawning a subprocess that launches a conforming MCP server that
-//!      communicates over stdio.
+//!      communicates over stdio, or connecting to a remote MCP server over
+//!      Streamable HTTP or the legacy HTTP+SSE transport (see [`crate::http`]).
 //!   2. Sending MCP requests and pairing them with their corresponding
 //!      responses.
 //!   3. Offering a convenience helper for the common `tools/list` request.
@@ -23,6 +24,9 @@ use anyhow::Result;
 use anyhow::anyhow;
 use mcp_types::CallToolRequest;
 use mcp_types::CallToolRequestParams;
+use mcp_types::GetPromptRequest;
+use mcp_types::GetPromptRequestParams;
+use mcp_types::GetPromptResult;
 use mcp_types::InitializeRequest;
 use mcp_types::InitializeRequestParams;
 use mcp_types::InitializedNotification;
@@ -31,11 +35,20 @@ use mcp_types::JSONRPCMessage;
 use mcp_types::JSONRPCNotification;
 use mcp_types::JSONRPCRequest;
 use mcp_types::JSONRPCResponse;
+use mcp_types::ListPromptsRequest;
+use mcp_types::ListPromptsRequestParams;
+use mcp_types::ListPromptsResult;
+use mcp_types::ListResourcesRequest;
+use mcp_types::ListResourcesRequestParams;
+use mcp_types::ListResourcesResult;
 use mcp_types::ListToolsRequest;
 use mcp_types::ListToolsRequestParams;
 use mcp_types::ListToolsResult;
 use mcp_types::ModelContextProtocolNotification;
 use mcp_types::ModelContextProtocolRequest;
+use mcp_types::ReadResourceRequest;
+use mcp_types::ReadResourceRequestParams;
+use mcp_types::ReadResourceResult;
 use mcp_types::RequestId;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
@@ -46,6 +59,7 @@ use tokio::process::Command;
 use tokio::sync::Mutex;
 use tokio::sync::mpsc;
 use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
 use tokio::time;
 use tracing::debug;
 use tracing::error;
@@ -54,25 +68,39 @@ use tracing::warn;
 
 /// Capacity of the bounded channels used for transporting messages between the
 /// client API and the IO tasks.
-const CHANNEL_CAPACITY: usize = 128;
+pub(crate) const CHANNEL_CAPACITY: usize = 128;
 
 /// Internal representation of a pending request sender.
 type PendingSender = oneshot::Sender<JSONRPCMessage>;
 
+/// Map of `request.id -> oneshot::Sender` shared between the client API and
+/// the IO tasks.
+pub(crate) type PendingRequests = Arc<Mutex<HashMap<i64, PendingSender>>>;
+
+/// Resources that keep the underlying connection alive for as long as the
+/// client exists.
+enum Transport {
+    /// Retain this child process until the client is dropped. The Tokio
+    /// runtime will make a "best effort" to reap the process after it exits,
+    /// but it is not a guarantee. See the `kill_on_drop` documentation for
+    /// details.
+    Stdio { child: tokio::process::Child },
+
+    /// Background tasks that own the HTTP connections. They are aborted when
+    /// the client is dropped.
+    Http { tasks: Vec<JoinHandle<()>> },
+}
+
 /// A running MCP client instance.
 pub struct McpClient {
-    /// Retain this child process until the client is dropped. The Tokio runtime
-    /// will make a "best effort" to reap the process after it exits, but it is
-    /// not a guarantee. See the `kill_on_drop` documentation for details.
-    #[allow(dead_code)]
-    child: tokio::process::Child,
+    transport: Transport,
 
     /// Channel for sending JSON-RPC messages *to* the background writer task.
     outgoing_tx: mpsc::Sender<JSONRPCMessage>,
 
     /// Map of `request.id -> oneshot::Sender` used to dispatch responses back
     /// to the originating caller.
-    pending: Arc<Mutex<HashMap<i64, PendingSender>>>,
+    pending: PendingRequests,
 
     /// Monotonically increasing counter used to generate request IDs.
     id_counter: AtomicI64,
@@ -110,7 +138,7 @@ impl McpClient {
             .ok_or_else(|| std::io::Error::other("failed to capture child stdout"))?;
 
         let (outgoing_tx, mut outgoing_rx) = mpsc::channel::<JSONRPCMessage>(CHANNEL_CAPACITY);
-        let pending: Arc<Mutex<HashMap<i64, PendingSender>>> = Arc::new(Mutex::new(HashMap::new()));
+        let pending: PendingRequests = Arc::new(Mutex::new(HashMap::new()));
 
         // Spawn writer task. It listens on the `outgoing_rx` channel and
         // writes messages to the child's STDIN.
@@ -147,21 +175,7 @@ impl McpClient {
                 while let Ok(Some(line)) = lines.next_line().await {
                     debug!("MCP message from server: {line}");
                     match serde_json::from_str::<JSONRPCMessage>(&line) {
-                        Ok(JSONRPCMessage::Response(resp)) => {
-                            Self::dispatch_response(resp, &pending).await;
-                        }
-                        Ok(JSONRPCMessage::Error(err)) => {
-                            Self::dispatch_error(err, &pending).await;
-                        }
-                        Ok(JSONRPCMessage::Notification(JSONRPCNotification { .. })) => {
-                            // For now we only log server-initiated notifications.
-                            info!("<- notification: {}", line);
-                        }
-                        Ok(other) => {
-                            // Batch responses and requests are currently not
-                            // expected from the server – log and ignore.
-                            info!("<- unhandled message: {:?}", other);
-                        }
+                        Ok(message) => dispatch_incoming(message, &pending).await,
                         Err(e) => {
                             error!("failed to deserialize JSONRPCMessage: {e}; line = {}", line)
                         }
@@ -177,13 +191,29 @@ impl McpClient {
         let _ = (writer_handle, reader_handle);
 
         Ok(Self {
-            child,
+            transport: Transport::Stdio { child },
             outgoing_tx,
             pending,
             id_counter: AtomicI64::new(1),
         })
     }
 
+    /// Connect to a remote MCP server. Messages are handed to the transport
+    /// tasks through `outgoing_tx` and replies are routed through `pending`,
+    /// exactly like the stdio transport. See [`crate::http`].
+    pub(crate) fn from_http_transport(
+        outgoing_tx: mpsc::Sender<JSONRPCMessage>,
+        pending: PendingRequests,
+        tasks: Vec<JoinHandle<()>>,
+    ) -> Self {
+        Self {
+            transport: Transport::Http { tasks },
+            outgoing_tx,
+            pending,
+            id_counter: AtomicI64::new(1),
+        }
+    }
+
     /// Send an arbitrary MCP request and await the typed result.
     ///
     /// If `timeout` is `None` the call waits indefinitely. If `Some(duration)`
@@ -269,14 +299,14 @@ impl McpClient {
                 let typed: R::Result = serde_json::from_value(result)?;
                 Ok(typed)
             }
-            JSONRPCMessage::Error(err) => Err(anyhow!(format!(
+            JSONRPCMessage::Error(err) => Err(anyhow!(
                 "server returned JSON-RPC error: code = {}, message = {}",
-                err.error.code, err.error.message
-            ))),
-            other => Err(anyhow!(format!(
-                "unexpected message variant received in reply path: {:?}",
-                other
-            ))),
+                err.error.code,
+                err.error.message
+            )),
+            other => Err(anyhow!(
+                "unexpected message variant received in reply path: {other:?}"
+            )),
         }
     }
 
@@ -347,60 +377,126 @@ impl McpClient {
         self.send_request::<CallToolRequest>(params, timeout).await
     }
 
-    /// Internal helper: route a JSON-RPC *response* object to the pending map.
-    async fn dispatch_response(
-        resp: JSONRPCResponse,
-        pending: &Arc<Mutex<HashMap<i64, PendingSender>>>,
-    ) {
-        let id = match resp.id {
-            RequestId::Integer(i) => i,
-            RequestId::String(_) => {
-                // We only ever generate integer IDs. Receiving a string here
-                // means we will not find a matching entry in `pending`.
-                error!("response with string ID - no matching pending request");
-                return;
-            }
-        };
+    /// Convenience wrapper around `resources/list`.
+    pub async fn list_resources(
+        &self,
+        params: Option<ListResourcesRequestParams>,
+        timeout: Option<Duration>,
+    ) -> Result<ListResourcesResult> {
+        self.send_request::<ListResourcesRequest>(params, timeout)
+            .await
+    }
 
-        let tx_opt = {
-            let mut guard = pending.lock().await;
-            guard.remove(&id)
-        };
-        if let Some(tx) = tx_opt {
-            // Ignore send errors – the receiver might have been dropped.
-            let _ = tx.send(JSONRPCMessage::Response(resp));
-        } else {
-            warn!(id, "no pending request found for response");
-        }
+    /// Convenience wrapper around `resources/read`.
+    pub async fn read_resource(
+        &self,
+        uri: String,
+        timeout: Option<Duration>,
+    ) -> Result<ReadResourceResult> {
+        let params = ReadResourceRequestParams { uri };
+        debug!("MCP resource read: {params:?}");
+        self.send_request::<ReadResourceRequest>(params, timeout)
+            .await
     }
 
-    /// Internal helper: route a JSON-RPC *error* object to the pending map.
-    async fn dispatch_error(
-        err: mcp_types::JSONRPCError,
-        pending: &Arc<Mutex<HashMap<i64, PendingSender>>>,
-    ) {
-        let id = match err.id {
-            RequestId::Integer(i) => i,
-            RequestId::String(_) => return, // see comment above
-        };
+    /// Convenience wrapper around `prompts/list`.
+    pub async fn list_prompts(
+        &self,
+        params: Option<ListPromptsRequestParams>,
+        timeout: Option<Duration>,
+    ) -> Result<ListPromptsResult> {
+        self.send_request::<ListPromptsRequest>(params, timeout)
+            .await
+    }
 
-        let tx_opt = {
-            let mut guard = pending.lock().await;
-            guard.remove(&id)
-        };
-        if let Some(tx) = tx_opt {
-            let _ = tx.send(JSONRPCMessage::Error(err));
+    /// Convenience wrapper around `prompts/get`.
+    pub async fn get_prompt(
+        &self,
+        name: String,
+        arguments: Option<serde_json::Value>,
+        timeout: Option<Duration>,
+    ) -> Result<GetPromptResult> {
+        let params = GetPromptRequestParams { name, arguments };
+        debug!("MCP prompt get: {params:?}");
+        self.send_request::<GetPromptRequest>(params, timeout).await
+    }
+}
+
+/// Route a message received from the server: responses and errors are paired
+/// with the pending request that triggered them.
+pub(crate) async fn dispatch_incoming(message: JSONRPCMessage, pending: &PendingRequests) {
+    match message {
+        JSONRPCMessage::Response(resp) => dispatch_response(resp, pending).await,
+        JSONRPCMessage::Error(err) => dispatch_error(err, pending).await,
+        JSONRPCMessage::Notification(JSONRPCNotification { method, .. }) => {
+            // For now we only log server-initiated notifications.
+            info!("<- notification: {method}");
         }
+        other => {
+            // Server-initiated requests are currently not expected from the
+            // server – log and ignore.
+            info!("<- unhandled message: {other:?}");
+        }
+    }
+}
+
+/// Internal helper: route a JSON-RPC *response* object to the pending map.
+async fn dispatch_response(resp: JSONRPCResponse, pending: &PendingRequests) {
+    let id = match resp.id {
+        RequestId::Integer(i) => i,
+        RequestId::String(_) => {
+            // We only ever generate integer IDs. Receiving a string here
+            // means we will not find a matching entry in `pending`.
+            error!("response with string ID - no matching pending request");
+            return;
+        }
+    };
+
+    let tx_opt = {
+        let mut guard = pending.lock().await;
+        guard.remove(&id)
+    };
+    if let Some(tx) = tx_opt {
+        // Ignore send errors – the receiver might have been dropped.
+        let _ = tx.send(JSONRPCMessage::Response(resp));
+    } else {
+        warn!(id, "no pending request found for response");
+    }
+}
+
+/// Internal helper: route a JSON-RPC *error* object to the pending map.
+async fn dispatch_error(err: mcp_types::JSONRPCError, pending: &PendingRequests) {
+    let id = match err.id {
+        RequestId::Integer(i) => i,
+        RequestId::String(_) => return, // see comment above
+    };
+
+    let tx_opt = {
+        let mut guard = pending.lock().await;
+        guard.remove(&id)
+    };
+    if let Some(tx) = tx_opt {
+        let _ = tx.send(JSONRPCMessage::Error(err));
     }
 }
 
 impl Drop for McpClient {
     fn drop(&mut self) {
-        // Even though we have already tagged this process with
-        // `kill_on_drop(true)` above, this extra check has the benefit of
-        // forcing the process to be reaped immediately if it has already exited
-        // instead of waiting for the Tokio runtime to reap it later.
-        let _ = self.child.try_wait();
+        match &mut self.transport {
+            Transport::Stdio { child } => {
+                // Even though we have already tagged this process with
+                // `kill_on_drop(true)` above, this extra check has the benefit
+                // of forcing the process to be reaped immediately if it has
+                // already exited instead of waiting for the Tokio runtime to
+                // reap it later.
+                let _ = child.try_wait();
+            }
+            Transport::Http { tasks } => {
+                for task in tasks {
+                    task.abort();
+                }
+            }
+        }
     }
 }
 
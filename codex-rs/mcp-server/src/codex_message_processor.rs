@@ -10,6 +10,7 @@ use codex_core::Cursor as RolloutCursor;
 use codex_core::NewConversation;
 use codex_core::RolloutRecorder;
 use codex_core::SessionMeta;
+use codex_core::SessionSearch;
 use codex_core::auth::CLIENT_ID;
 use codex_core::auth::get_auth_file;
 use codex_core::auth::login_with_api_key;
@@ -31,6 +32,7 @@ use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::InputItem as CoreInputItem;
+use codex_core::protocol::McpToolCallApprovalRequestEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::ReviewDecision;
 use codex_login::ServerOptions as LoginServerOptions;
@@ -52,6 +54,8 @@ use codex_protocol::mcp_protocol::ExecArbitraryCommandResponse;
 use codex_protocol::mcp_protocol::ExecCommandApprovalParams;
 use codex_protocol::mcp_protocol::ExecCommandApprovalResponse;
 use codex_protocol::mcp_protocol::ExecOneOffCommandParams;
+use codex_protocol::mcp_protocol::ExportConversationParams;
+use codex_protocol::mcp_protocol::ExportConversationResponse;
 use codex_protocol::mcp_protocol::GetUserAgentResponse;
 use codex_protocol::mcp_protocol::GetUserSavedConfigResponse;
 use codex_protocol::mcp_protocol::GitDiffToRemoteResponse;
@@ -64,10 +68,19 @@ use codex_protocol::mcp_protocol::LoginApiKeyParams;
 use codex_protocol::mcp_protocol::LoginApiKeyResponse;
 use codex_protocol::mcp_protocol::LoginChatGptCompleteNotification;
 use codex_protocol::mcp_protocol::LoginChatGptResponse;
+use codex_protocol::mcp_protocol::MCP_TOOL_CALL_APPROVAL_METHOD;
+use codex_protocol::mcp_protocol::McpToolCallApprovalParams;
+use codex_protocol::mcp_protocol::McpToolCallApprovalResponse;
 use codex_protocol::mcp_protocol::NewConversationParams;
 use codex_protocol::mcp_protocol::NewConversationResponse;
+use codex_protocol::mcp_protocol::QueueUserMessageParams;
+use codex_protocol::mcp_protocol::QueueUserMessageResponse;
 use codex_protocol::mcp_protocol::RemoveConversationListenerParams;
 use codex_protocol::mcp_protocol::RemoveConversationSubscriptionResponse;
+use codex_protocol::mcp_protocol::RemoveQueuedUserMessageParams;
+use codex_protocol::mcp_protocol::RemoveQueuedUserMessageResponse;
+use codex_protocol::mcp_protocol::ResolveWorktreeParams;
+use codex_protocol::mcp_protocol::ResolveWorktreeResponse;
 use codex_protocol::mcp_protocol::ResumeConversationParams;
 use codex_protocol::mcp_protocol::SendUserMessageParams;
 use codex_protocol::mcp_protocol::SendUserMessageResponse;
@@ -76,6 +89,8 @@ use codex_protocol::mcp_protocol::SendUserTurnResponse;
 use codex_protocol::mcp_protocol::ServerNotification;
 use codex_protocol::mcp_protocol::SetDefaultModelParams;
 use codex_protocol::mcp_protocol::SetDefaultModelResponse;
+use codex_protocol::mcp_protocol::UpdateQueuedUserMessageParams;
+use codex_protocol::mcp_protocol::UpdateQueuedUserMessageResponse;
 use codex_protocol::mcp_protocol::UserInfoResponse;
 use codex_protocol::mcp_protocol::UserSavedConfig;
 use codex_protocol::models::ContentItem;
@@ -161,15 +176,30 @@ impl CodexMessageProcessor {
             ClientRequest::ArchiveConversation { request_id, params } => {
                 self.archive_conversation(request_id, params).await;
             }
+            ClientRequest::ExportConversation { request_id, params } => {
+                self.export_conversation(request_id, params).await;
+            }
             ClientRequest::SendUserMessage { request_id, params } => {
                 self.send_user_message(request_id, params).await;
             }
             ClientRequest::SendUserTurn { request_id, params } => {
                 self.send_user_turn(request_id, params).await;
             }
+            ClientRequest::QueueUserMessage { request_id, params } => {
+                self.queue_user_message(request_id, params).await;
+            }
+            ClientRequest::UpdateQueuedUserMessage { request_id, params } => {
+                self.update_queued_user_message(request_id, params).await;
+            }
+            ClientRequest::RemoveQueuedUserMessage { request_id, params } => {
+                self.remove_queued_user_message(request_id, params).await;
+            }
             ClientRequest::InterruptConversation { request_id, params } => {
                 self.interrupt_conversation(request_id, params).await;
             }
+            ClientRequest::ResolveWorktree { request_id, params } => {
+                self.resolve_worktree(request_id, params).await;
+            }
             ClientRequest::AddConversationListener { request_id, params } => {
                 self.add_conversation_listener(request_id, params).await;
             }
@@ -637,6 +667,7 @@ impl CodexMessageProcessor {
                     model: session_configured.model,
                     reasoning_effort: session_configured.reasoning_effort,
                     rollout_path: session_configured.rollout_path,
+                    worktree: session_configured.worktree,
                 };
                 self.outgoing.send_response(request_id, response).await;
             }
@@ -656,21 +687,58 @@ impl CodexMessageProcessor {
         request_id: RequestId,
         params: ListConversationsParams,
     ) {
-        let page_size = params.page_size.unwrap_or(25);
+        let ListConversationsParams {
+            page_size,
+            cursor,
+            query,
+            cwd,
+            git_branch,
+            model,
+            since,
+            until,
+        } = params;
+        let page_size = page_size.unwrap_or(25);
         // Decode the optional cursor string to a Cursor via serde (Cursor implements Deserialize from string)
-        let cursor_obj: Option<RolloutCursor> = match params.cursor {
+        let cursor_obj: Option<RolloutCursor> = match cursor {
             Some(s) => serde_json::from_str::<RolloutCursor>(&format!("\"{s}\"")).ok(),
             None => None,
         };
         let cursor_ref = cursor_obj.as_ref();
 
-        let page = match RolloutRecorder::list_conversations(
-            &self.config.codex_home,
-            page_size,
-            cursor_ref,
-        )
-        .await
-        {
+        let bounds =
+            parse_search_bound("since", since, SessionSearch::parse_since).and_then(|since| {
+                parse_search_bound("until", until, SessionSearch::parse_until)
+                    .map(|until| (since, until))
+            });
+        let (since, until) = match bounds {
+            Ok(bounds) => bounds,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+        let search = SessionSearch {
+            query,
+            cwd,
+            git_branch,
+            model,
+            since,
+            until,
+        };
+
+        let page = if search.is_empty() {
+            RolloutRecorder::list_conversations(&self.config.codex_home, page_size, cursor_ref)
+                .await
+        } else {
+            RolloutRecorder::search_conversations(
+                &self.config.codex_home,
+                &search,
+                page_size,
+                cursor_ref,
+            )
+            .await
+        };
+        let page = match page {
             Ok(p) => p,
             Err(err) => {
                 let error = JSONRPCErrorError {
@@ -819,10 +887,10 @@ impl CodexMessageProcessor {
             return;
         };
 
-        if !file_name
-            .to_string_lossy()
-            .ends_with(required_suffix.as_str())
-        {
+        // Old rollouts may have been compressed by the retention policy.
+        let file_name_str = file_name.to_string_lossy();
+        let file_name_str = file_name_str.strip_suffix(".gz").unwrap_or(&file_name_str);
+        if !file_name_str.ends_with(required_suffix.as_str()) {
             let error = JSONRPCErrorError {
                 code: INVALID_REQUEST_ERROR_CODE,
                 message: format!(
@@ -886,19 +954,8 @@ impl CodexMessageProcessor {
         }
 
         // Move the .jsonl file to the archived sessions subdir.
-        let result: std::io::Result<()> = async {
-            let archive_folder = self
-                .config
-                .codex_home
-                .join(codex_core::ARCHIVED_SESSIONS_SUBDIR);
-            tokio::fs::create_dir_all(&archive_folder).await?;
-            tokio::fs::rename(&canonical_rollout_path, &archive_folder.join(&file_name)).await?;
-            Ok(())
-        }
-        .await;
-
-        match result {
-            Ok(()) => {
+        match codex_core::archive_rollout(&self.config.codex_home, &canonical_rollout_path).await {
+            Ok(_) => {
                 let response = ArchiveConversationResponse {};
                 self.outgoing.send_response(request_id, response).await;
             }
@@ -913,6 +970,54 @@ impl CodexMessageProcessor {
         }
     }
 
+    async fn export_conversation(&self, request_id: RequestId, params: ExportConversationParams) {
+        let ExportConversationParams {
+            rollout_path,
+            format,
+        } = params;
+
+        // Only export rollouts Codex recorded, or a client could read any
+        // file through this request.
+        let codex_home = tokio::fs::canonicalize(&self.config.codex_home)
+            .await
+            .unwrap_or_else(|_| self.config.codex_home.clone());
+        let canonical_rollout_path = match tokio::fs::canonicalize(&rollout_path).await {
+            Ok(path)
+                if path.starts_with(codex_home.join(codex_core::SESSIONS_SUBDIR))
+                    || path.starts_with(codex_home.join(codex_core::ARCHIVED_SESSIONS_SUBDIR)) =>
+            {
+                path
+            }
+            _ => {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: format!(
+                        "rollout path `{}` must be in sessions directory",
+                        rollout_path.display()
+                    ),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        match codex_core::export_rollout(&canonical_rollout_path, format).await {
+            Ok(content) => {
+                let response = ExportConversationResponse { content };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("failed to export conversation: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
     async fn send_user_message(&self, request_id: RequestId, params: SendUserMessageParams) {
         let SendUserMessageParams {
             conversation_id,
@@ -932,14 +1037,7 @@ impl CodexMessageProcessor {
             return;
         };
 
-        let mapped_items: Vec<CoreInputItem> = items
-            .into_iter()
-            .map(|item| match item {
-                WireInputItem::Text { text } => CoreInputItem::Text { text },
-                WireInputItem::Image { image_url } => CoreInputItem::Image { image_url },
-                WireInputItem::LocalImage { path } => CoreInputItem::LocalImage { path },
-            })
-            .collect();
+        let mapped_items = map_input_items(items);
 
         // Submit user input to the conversation.
         let _ = conversation
@@ -980,14 +1078,7 @@ impl CodexMessageProcessor {
             return;
         };
 
-        let mapped_items: Vec<CoreInputItem> = items
-            .into_iter()
-            .map(|item| match item {
-                WireInputItem::Text { text } => CoreInputItem::Text { text },
-                WireInputItem::Image { image_url } => CoreInputItem::Image { image_url },
-                WireInputItem::LocalImage { path } => CoreInputItem::LocalImage { path },
-            })
-            .collect();
+        let mapped_items = map_input_items(items);
 
         let _ = conversation
             .submit(Op::UserTurn {
@@ -1006,6 +1097,148 @@ impl CodexMessageProcessor {
             .await;
     }
 
+    async fn queue_user_message(&self, request_id: RequestId, params: QueueUserMessageParams) {
+        let QueueUserMessageParams {
+            conversation_id,
+            items,
+        } = params;
+        let Ok(conversation) = self
+            .conversation_manager
+            .get_conversation(conversation_id)
+            .await
+        else {
+            let error = JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message: format!("conversation not found: {conversation_id}"),
+                data: None,
+            };
+            self.outgoing.send_error(request_id, error).await;
+            return;
+        };
+
+        match conversation
+            .submit(Op::QueueUserInput {
+                items: map_input_items(items),
+            })
+            .await
+        {
+            Ok(queued_message_id) => {
+                self.outgoing
+                    .send_response(request_id, QueueUserMessageResponse { queued_message_id })
+                    .await;
+            }
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("failed to queue message: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
+    async fn update_queued_user_message(
+        &self,
+        request_id: RequestId,
+        params: UpdateQueuedUserMessageParams,
+    ) {
+        let UpdateQueuedUserMessageParams {
+            conversation_id,
+            queued_message_id,
+            items,
+        } = params;
+        let Ok(conversation) = self
+            .conversation_manager
+            .get_conversation(conversation_id)
+            .await
+        else {
+            let error = JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message: format!("conversation not found: {conversation_id}"),
+                data: None,
+            };
+            self.outgoing.send_error(request_id, error).await;
+            return;
+        };
+
+        // The resulting queue is reported in an `input_queue_updated` event.
+        let _ = conversation
+            .submit(Op::UpdateQueuedInput {
+                id: queued_message_id,
+                items: map_input_items(items),
+            })
+            .await;
+
+        self.outgoing
+            .send_response(request_id, UpdateQueuedUserMessageResponse {})
+            .await;
+    }
+
+    async fn remove_queued_user_message(
+        &self,
+        request_id: RequestId,
+        params: RemoveQueuedUserMessageParams,
+    ) {
+        let RemoveQueuedUserMessageParams {
+            conversation_id,
+            queued_message_id,
+        } = params;
+        let Ok(conversation) = self
+            .conversation_manager
+            .get_conversation(conversation_id)
+            .await
+        else {
+            let error = JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message: format!("conversation not found: {conversation_id}"),
+                data: None,
+            };
+            self.outgoing.send_error(request_id, error).await;
+            return;
+        };
+
+        // The resulting queue is reported in an `input_queue_updated` event.
+        let _ = conversation
+            .submit(Op::RemoveQueuedInput {
+                id: queued_message_id,
+            })
+            .await;
+
+        self.outgoing
+            .send_response(request_id, RemoveQueuedUserMessageResponse {})
+            .await;
+    }
+
+    async fn resolve_worktree(&self, request_id: RequestId, params: ResolveWorktreeParams) {
+        let ResolveWorktreeParams {
+            conversation_id,
+            resolution,
+        } = params;
+        let Ok(conversation) = self
+            .conversation_manager
+            .get_conversation(conversation_id)
+            .await
+        else {
+            let error = JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message: format!("conversation not found: {conversation_id}"),
+                data: None,
+            };
+            self.outgoing.send_error(request_id, error).await;
+            return;
+        };
+
+        // The outcome is reported in a `worktree_resolved` or `error` event.
+        let _ = conversation
+            .submit(Op::ResolveWorktree { resolution })
+            .await;
+
+        self.outgoing
+            .send_response(request_id, ResolveWorktreeResponse {})
+            .await;
+    }
+
     async fn interrupt_conversation(
         &mut self,
         request_id: RequestId,
@@ -1211,6 +1444,25 @@ async fn apply_bespoke_event_handling(
                 on_exec_approval_response(event_id, rx, conversation).await;
             });
         }
+        EventMsg::McpToolCallApprovalRequest(McpToolCallApprovalRequestEvent {
+            call_id,
+            invocation,
+        }) => {
+            let params = McpToolCallApprovalParams {
+                conversation_id,
+                call_id,
+                server: invocation.server,
+                tool: invocation.tool,
+                arguments: invocation.arguments,
+            };
+            let value = serde_json::to_value(&params).unwrap_or_default();
+            let rx = outgoing
+                .send_request(MCP_TOOL_CALL_APPROVAL_METHOD, Some(value))
+                .await;
+            tokio::spawn(async move {
+                on_mcp_tool_call_approval_response(event_id, rx, conversation).await;
+            });
+        }
         // If this is a TurnAborted, reply to any pending interrupt requests.
         EventMsg::TurnAborted(turn_aborted_event) => {
             let pending = {
@@ -1231,6 +1483,17 @@ async fn apply_bespoke_event_handling(
     }
 }
 
+fn map_input_items(items: Vec<WireInputItem>) -> Vec<CoreInputItem> {
+    items
+        .into_iter()
+        .map(|item| match item {
+            WireInputItem::Text { text } => CoreInputItem::Text { text },
+            WireInputItem::Image { image_url } => CoreInputItem::Image { image_url },
+            WireInputItem::LocalImage { path } => CoreInputItem::LocalImage { path },
+        })
+        .collect()
+}
+
 fn derive_config_from_params(
     params: NewConversationParams,
     codex_linux_sandbox_exe: Option<PathBuf>,
@@ -1245,6 +1508,7 @@ fn derive_config_from_params(
         base_instructions,
         include_plan_tool,
         include_apply_patch_tool,
+        worktree,
     } = params;
     let overrides = ConfigOverrides {
         model,
@@ -1261,6 +1525,7 @@ fn derive_config_from_params(
         include_view_image_tool: None,
         show_raw_agent_reasoning: None,
         tools_web_search_request: None,
+        worktree,
     };
 
     let cli_overrides = cli_overrides
@@ -1314,6 +1579,37 @@ async fn on_patch_approval_response(
     }
 }
 
+async fn on_mcp_tool_call_approval_response(
+    event_id: String,
+    receiver: oneshot::Receiver<mcp_types::Result>,
+    conversation: Arc<CodexConversation>,
+) {
+    // Deny the call if the client does not answer, as the agent would
+    // otherwise wait for a decision forever.
+    let decision = match receiver.await {
+        Ok(value) => serde_json::from_value::<McpToolCallApprovalResponse>(value)
+            .map(|response| response.decision)
+            .unwrap_or_else(|err| {
+                error!("failed to deserialize McpToolCallApprovalResponse: {err}");
+                ReviewDecision::Denied
+            }),
+        Err(err) => {
+            error!("request failed: {err:?}");
+            ReviewDecision::Denied
+        }
+    };
+
+    if let Err(err) = conversation
+        .submit(Op::McpToolCallApproval {
+            id: event_id,
+            decision,
+        })
+        .await
+    {
+        error!("failed to submit McpToolCallApproval: {err}");
+    }
+}
+
 async fn on_exec_approval_response(
     event_id: String,
     receiver: oneshot::Receiver<mcp_types::Result>,
@@ -1350,7 +1646,22 @@ async fn on_exec_approval_response(
     }
 }
 
-fn extract_conversation_summary(
+fn parse_search_bound<T>(
+    name: &str,
+    value: Option<String>,
+    parse: fn(&str) -> Option<T>,
+) -> Result<Option<T>, JSONRPCErrorError> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    parse(&value).map(Some).ok_or_else(|| JSONRPCErrorError {
+        code: INVALID_REQUEST_ERROR_CODE,
+        message: format!("invalid {name}: {value} (expected an RFC 3339 timestamp or YYYY-MM-DD)"),
+        data: None,
+    })
+}
+
+pub(crate) fn extract_conversation_summary(
     path: PathBuf,
     head: &[serde_json::Value],
 ) -> Option<ConversationSummary> {
@@ -165,6 +165,7 @@ impl CodexToolCallParam {
             include_view_image_tool: None,
             show_raw_agent_reasoning: None,
             tools_web_search_request: None,
+            worktree: None,
         };
 
         let cli_overrides = cli_overrides
@@ -0,0 +1,366 @@
+//! Recorded Codex conversations exposed as MCP resources.
+//!
+//! Every rollout file under `$CODEX_HOME/sessions` is listed as a
+//! `codex://conversation/<id>` resource. Reading it returns the conversation's
+//! transcript as Markdown and, when Codex edited files, the diff of those
+//! edits as a second `codex://conversation/<id>/diff` entry.
+//!
+//! The diff is rebuilt by [`AppliedPatchDiff`] from the `apply_patch` calls
+//! recorded in the rollout that applied successfully.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use codex_core::AppliedPatchDiff;
+use codex_core::Cursor as RolloutCursor;
+use codex_core::RolloutRecorder;
+use codex_core::find_conversation_path_by_id_str;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputMessageKind;
+use codex_core::protocol::RolloutItem;
+use codex_core::protocol::RolloutLine;
+use codex_core::protocol::SessionMeta;
+use codex_core::protocol::USER_MESSAGE_BEGIN;
+use codex_core::read_rollout_text;
+use codex_protocol::mcp_protocol::ConversationId;
+use mcp_types::JSONRPCErrorError;
+use mcp_types::ListResourceTemplatesResult;
+use mcp_types::ListResourcesResult;
+use mcp_types::ModelContextProtocolNotification;
+use mcp_types::ReadResourceResult;
+use mcp_types::ReadResourceResultContents;
+use mcp_types::Resource;
+use mcp_types::ResourceTemplate;
+use mcp_types::ResourceUpdatedNotification;
+use mcp_types::ResourceUpdatedNotificationParams;
+use mcp_types::TextResourceContents;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::codex_message_processor::extract_conversation_summary;
+use crate::error_code::INTERNAL_ERROR_CODE;
+use crate::error_code::INVALID_REQUEST_ERROR_CODE;
+use crate::outgoing_message::OutgoingMessageSender;
+use crate::outgoing_message::OutgoingNotification;
+
+const CONVERSATION_URI_PREFIX: &str = "codex://conversation/";
+const DIFF_URI_SUFFIX: &str = "/diff";
+const TRANSCRIPT_MIME_TYPE: &str = "text/markdown";
+const DIFF_MIME_TYPE: &str = "text/x-diff";
+const RESOURCES_PAGE_SIZE: usize = 25;
+
+/// How often the rollout file of a subscribed conversation is checked for
+/// changes.
+const SUBSCRIPTION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+pub(crate) fn transcript_uri(conversation_id: &ConversationId) -> String {
+    format!("{CONVERSATION_URI_PREFIX}{conversation_id}")
+}
+
+pub(crate) fn diff_uri(conversation_id: &ConversationId) -> String {
+    format!("{CONVERSATION_URI_PREFIX}{conversation_id}{DIFF_URI_SUFFIX}")
+}
+
+/// Returns the id of the conversation a `codex://conversation/...` URI
+/// refers to, for both the transcript and the diff URI.
+fn parse_conversation_uri(uri: &str) -> Option<ConversationId> {
+    let rest = uri.strip_prefix(CONVERSATION_URI_PREFIX)?;
+    let id = rest.strip_suffix(DIFF_URI_SUFFIX).unwrap_or(rest);
+    Uuid::parse_str(id).ok().map(ConversationId::from)
+}
+
+pub(crate) async fn list_resources(
+    codex_home: &Path,
+    cursor: Option<String>,
+) -> Result<ListResourcesResult, JSONRPCErrorError> {
+    // The cursor is the one of the rollout listing, passed through as is.
+    let cursor = cursor.and_then(|s| serde_json::from_value::<RolloutCursor>(s.into()).ok());
+    let page =
+        RolloutRecorder::list_conversations(codex_home, RESOURCES_PAGE_SIZE, cursor.as_ref())
+            .await
+            .map_err(|err| internal_error(format!("failed to list conversations: {err}")))?;
+
+    let resources = page
+        .items
+        .into_iter()
+        .filter_map(|item| extract_conversation_summary(item.path, &item.head))
+        .map(|summary| Resource {
+            annotations: None,
+            description: summary
+                .timestamp
+                .map(|timestamp| format!("Codex conversation started at {timestamp}")),
+            mime_type: Some(TRANSCRIPT_MIME_TYPE.to_string()),
+            name: summary.conversation_id.to_string(),
+            size: None,
+            title: summary.preview.lines().next().map(str::to_string),
+            uri: transcript_uri(&summary.conversation_id),
+        })
+        .collect();
+    let next_cursor = page
+        .next_cursor
+        .and_then(|cursor| match serde_json::to_value(&cursor) {
+            Ok(serde_json::Value::String(s)) => Some(s),
+            _ => None,
+        });
+
+    Ok(ListResourcesResult {
+        next_cursor,
+        resources,
+    })
+}
+
+pub(crate) fn list_resource_templates() -> ListResourceTemplatesResult {
+    ListResourceTemplatesResult {
+        next_cursor: None,
+        resource_templates: vec![
+            ResourceTemplate {
+                annotations: None,
+                description: Some("Transcript of a recorded Codex conversation.".to_string()),
+                mime_type: Some(TRANSCRIPT_MIME_TYPE.to_string()),
+                name: "conversation".to_string(),
+                title: Some("Codex conversation".to_string()),
+                uri_template: format!("{CONVERSATION_URI_PREFIX}{{conversation_id}}"),
+            },
+            ResourceTemplate {
+                annotations: None,
+                description: Some(
+                    "Unified diff of the files Codex edited in a recorded conversation."
+                        .to_string(),
+                ),
+                mime_type: Some(DIFF_MIME_TYPE.to_string()),
+                name: "conversation-diff".to_string(),
+                title: Some("Codex conversation diff".to_string()),
+                uri_template: format!(
+                    "{CONVERSATION_URI_PREFIX}{{conversation_id}}{DIFF_URI_SUFFIX}"
+                ),
+            },
+        ],
+    }
+}
+
+/// Reads `codex://conversation/<id>`, which returns the transcript followed
+/// by the diff if there is one, or `codex://conversation/<id>/diff`, which
+/// returns only the (possibly empty) diff.
+pub(crate) async fn read_resource(
+    codex_home: &Path,
+    uri: &str,
+) -> Result<ReadResourceResult, JSONRPCErrorError> {
+    let (conversation_id, path) = resolve_rollout_path(codex_home, uri).await?;
+    let text = read_rollout_text(&path)
+        .await
+        .map_err(|err| internal_error(format!("failed to read {}: {err}", path.display())))?;
+    let items = parse_rollout(&text);
+
+    let diff = TextResourceContents {
+        mime_type: Some(DIFF_MIME_TYPE.to_string()),
+        text: render_diff(&items),
+        uri: diff_uri(&conversation_id),
+    };
+    let contents = if uri.ends_with(DIFF_URI_SUFFIX) {
+        vec![diff]
+    } else {
+        let transcript = TextResourceContents {
+            mime_type: Some(TRANSCRIPT_MIME_TYPE.to_string()),
+            text: render_transcript(&conversation_id, &items),
+            uri: transcript_uri(&conversation_id),
+        };
+        if diff.text.is_empty() {
+            vec![transcript]
+        } else {
+            vec![transcript, diff]
+        }
+    };
+
+    Ok(ReadResourceResult {
+        contents: contents
+            .into_iter()
+            .map(ReadResourceResultContents::TextResourceContents)
+            .collect(),
+    })
+}
+
+/// Returns the conversation `uri` refers to and its rollout file.
+pub(crate) async fn resolve_rollout_path(
+    codex_home: &Path,
+    uri: &str,
+) -> Result<(ConversationId, PathBuf), JSONRPCErrorError> {
+    let Some(conversation_id) = parse_conversation_uri(uri) else {
+        return Err(unknown_resource(uri));
+    };
+    match find_conversation_path_by_id_str(codex_home, &conversation_id.to_string()).await {
+        Ok(Some(path)) => Ok((conversation_id, path)),
+        Ok(None) => Err(unknown_resource(uri)),
+        Err(err) => Err(internal_error(format!(
+            "failed to locate conversation {conversation_id}: {err}"
+        ))),
+    }
+}
+
+/// Spawns a task that sends `notifications/resources/updated` for `uri`
+/// whenever the rollout file at `path` changes after this call. The task runs
+/// until it is aborted.
+pub(crate) async fn watch_rollout(
+    uri: String,
+    path: PathBuf,
+    outgoing: Arc<OutgoingMessageSender>,
+) -> JoinHandle<()> {
+    let mut last = file_version(&path).await;
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SUBSCRIPTION_POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            let current = file_version(&path).await;
+            if current == last {
+                continue;
+            }
+            last = current;
+            let params = ResourceUpdatedNotificationParams { uri: uri.clone() };
+            outgoing
+                .send_notification(OutgoingNotification {
+                    method: ResourceUpdatedNotification::METHOD.to_string(),
+                    params: serde_json::to_value(params).ok(),
+                })
+                .await;
+        }
+    })
+}
+
+/// Size and modification time of a file, which change whenever the rollout
+/// recorder appends to it.
+async fn file_version(path: &Path) -> Option<(u64, SystemTime)> {
+    let metadata = tokio::fs::metadata(path).await.ok()?;
+    Some((metadata.len(), metadata.modified().ok()?))
+}
+
+fn parse_rollout(text: &str) -> Vec<RolloutItem> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str::<RolloutLine>(line) {
+            Ok(line) => Some(line.item),
+            Err(err) => {
+                tracing::warn!("failed to parse rollout line: {err}");
+                None
+            }
+        })
+        .collect()
+}
+
+fn render_transcript(conversation_id: &ConversationId, items: &[RolloutItem]) -> String {
+    let mut out = format!("# Codex conversation {conversation_id}\n");
+    if let Some(SessionMeta { timestamp, cwd, .. }) = items.iter().find_map(|item| match item {
+        RolloutItem::SessionMeta(line) => Some(&line.meta),
+        _ => None,
+    }) {
+        out.push('\n');
+        if !timestamp.is_empty() {
+            out.push_str(&format!("- Started: {timestamp}\n"));
+        }
+        out.push_str(&format!("- Working directory: `{}`\n", cwd.display()));
+    }
+
+    for item in items {
+        let RolloutItem::EventMsg(event) = item else {
+            continue;
+        };
+        let (heading, message) = match event {
+            EventMsg::UserMessage(event)
+                if matches!(event.kind, None | Some(InputMessageKind::Plain)) =>
+            {
+                let message = match event.message.find(USER_MESSAGE_BEGIN) {
+                    Some(idx) => &event.message[idx + USER_MESSAGE_BEGIN.len()..],
+                    None => event.message.as_str(),
+                };
+                ("User", message)
+            }
+            EventMsg::AgentMessage(event) => ("Codex", event.message.as_str()),
+            _ => continue,
+        };
+        out.push_str(&format!("\n## {heading}\n\n{}\n", message.trim()));
+    }
+    out
+}
+
+/// Renders the patches Codex applied, in order.
+fn render_diff(items: &[RolloutItem]) -> String {
+    let mut diff = AppliedPatchDiff::new();
+    for item in items {
+        if let RolloutItem::ResponseItem(item) = item {
+            diff.record(item);
+        }
+    }
+    diff.take_diff()
+}
+
+fn unknown_resource(uri: &str) -> JSONRPCErrorError {
+    JSONRPCErrorError {
+        code: INVALID_REQUEST_ERROR_CODE,
+        message: format!("unknown resource: {uri}"),
+        data: None,
+    }
+}
+
+fn internal_error(message: String) -> JSONRPCErrorError {
+    JSONRPCErrorError {
+        code: INTERNAL_ERROR_CODE,
+        message,
+        data: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::protocol::AgentMessageEvent;
+    use codex_core::protocol::UserMessageEvent;
+    use pretty_assertions::assert_eq;
+
+    fn user(message: &str, kind: Option<InputMessageKind>) -> RolloutItem {
+        RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+            message: message.to_string(),
+            kind,
+            images: None,
+        }))
+    }
+
+    #[test]
+    fn parses_transcript_and_diff_uris() {
+        let id = "3f941c35-29b3-493b-b0a4-e25800d9aeb0";
+        let expected = Uuid::parse_str(id).ok().map(ConversationId::from);
+        assert_eq!(
+            parse_conversation_uri(&format!("codex://conversation/{id}")),
+            expected
+        );
+        assert_eq!(
+            parse_conversation_uri(&format!("codex://conversation/{id}/diff")),
+            expected
+        );
+        assert_eq!(parse_conversation_uri("codex://conversation/nope"), None);
+        assert_eq!(parse_conversation_uri(&format!("file:///{id}")), None);
+    }
+
+    #[test]
+    fn transcript_skips_context_messages() {
+        let id = ConversationId::from(Uuid::nil());
+        let items = vec![
+            user(
+                "<environment_context>cwd</environment_context>",
+                Some(InputMessageKind::EnvironmentContext),
+            ),
+            user(
+                &format!("<prior context> {USER_MESSAGE_BEGIN}Count to 3"),
+                Some(InputMessageKind::Plain),
+            ),
+            RolloutItem::EventMsg(EventMsg::AgentMessage(AgentMessageEvent {
+                message: "1, 2, 3".to_string(),
+            })),
+        ];
+
+        assert_eq!(
+            render_transcript(&id, &items),
+            format!("# Codex conversation {id}\n\n## User\n\nCount to 3\n\n## Codex\n\n1, 2, 3\n")
+        );
+    }
+}
@@ -0,0 +1,130 @@
+//! Custom prompts from `$CODEX_HOME/prompts` exposed as MCP prompts.
+//!
+//! Custom prompts take positional arguments (`$1`..`$9` and `$ARGUMENTS`),
+//! while MCP prompts take named ones, so a prompt with placeholders declares
+//! a single `arguments` argument holding the words it is invoked with.
+
+use std::path::Path;
+
+use codex_core::custom_prompts::discover_prompts_in;
+use codex_core::custom_prompts::expand_prompt_arguments;
+use codex_protocol::custom_prompts::CustomPrompt;
+use mcp_types::ContentBlock;
+use mcp_types::GetPromptRequestParams;
+use mcp_types::GetPromptResult;
+use mcp_types::JSONRPCErrorError;
+use mcp_types::ListPromptsResult;
+use mcp_types::Prompt;
+use mcp_types::PromptArgument;
+use mcp_types::PromptMessage;
+use mcp_types::Role;
+use mcp_types::TextContent;
+
+use crate::error_code::INVALID_REQUEST_ERROR_CODE;
+
+const ARGUMENTS_ARGUMENT: &str = "arguments";
+
+pub(crate) async fn list_prompts(codex_home: &Path) -> ListPromptsResult {
+    let prompts = discover_prompts_in(&codex_home.join("prompts"))
+        .await
+        .iter()
+        .map(to_mcp_prompt)
+        .collect();
+    ListPromptsResult {
+        next_cursor: None,
+        prompts,
+    }
+}
+
+pub(crate) async fn get_prompt(
+    codex_home: &Path,
+    params: GetPromptRequestParams,
+) -> Result<GetPromptResult, JSONRPCErrorError> {
+    let GetPromptRequestParams { name, arguments } = params;
+    let Some(prompt) = discover_prompts_in(&codex_home.join("prompts"))
+        .await
+        .into_iter()
+        .find(|prompt| prompt.name == name)
+    else {
+        return Err(JSONRPCErrorError {
+            code: INVALID_REQUEST_ERROR_CODE,
+            message: format!("unknown prompt: {name}"),
+            data: None,
+        });
+    };
+
+    let arguments = arguments
+        .as_ref()
+        .and_then(|arguments| arguments.get(ARGUMENTS_ARGUMENT))
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or_default();
+    Ok(GetPromptResult {
+        description: prompt.description.clone(),
+        messages: vec![PromptMessage {
+            content: ContentBlock::TextContent(TextContent {
+                annotations: None,
+                text: expand_prompt_arguments(&prompt.content, arguments),
+                r#type: "text".to_string(),
+            }),
+            role: Role::User,
+        }],
+    })
+}
+
+fn to_mcp_prompt(prompt: &CustomPrompt) -> Prompt {
+    let arguments = takes_arguments(&prompt.content).then(|| {
+        vec![PromptArgument {
+            description: Some(
+                prompt
+                    .argument_hint
+                    .clone()
+                    .unwrap_or_else(|| "Arguments for the prompt".to_string()),
+            ),
+            name: ARGUMENTS_ARGUMENT.to_string(),
+            required: Some(false),
+            title: None,
+        }]
+    });
+    Prompt {
+        arguments,
+        description: prompt.description.clone(),
+        name: prompt.name.clone(),
+        title: None,
+    }
+}
+
+/// Whether the prompt's content has a placeholder for its arguments.
+fn takes_arguments(content: &str) -> bool {
+    content.contains("$ARGUMENTS")
+        || content
+            .as_bytes()
+            .windows(2)
+            .any(|w| w[0] == b'$' && (b'1'..=b'9').contains(&w[1]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn declares_arguments_only_for_prompts_with_placeholders() {
+        let review = CustomPrompt {
+            name: "review".to_string(),
+            content: "Review $1.".to_string(),
+            argument_hint: Some("<file>".to_string()),
+            ..Default::default()
+        };
+        let arguments = to_mcp_prompt(&review).arguments.expect("arguments");
+        assert_eq!(arguments.len(), 1);
+        assert_eq!(arguments[0].name, "arguments");
+        assert_eq!(arguments[0].description.as_deref(), Some("<file>"));
+
+        let plain = CustomPrompt {
+            name: "plain".to_string(),
+            content: "Summarize the repository.".to_string(),
+            ..Default::default()
+        };
+        assert!(to_mcp_prompt(&plain).arguments.is_none());
+    }
+}
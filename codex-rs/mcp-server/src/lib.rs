@@ -23,9 +23,12 @@ use tracing_subscriber::EnvFilter;
 mod codex_message_processor;
 mod codex_tool_config;
 mod codex_tool_runner;
+mod conversation_resources;
+mod custom_prompts;
 mod error_code;
 mod exec_approval;
 mod json_to_toml;
+mod mcp_tool_call_approval;
 pub(crate) mod message_processor;
 mod outgoing_message;
 mod patch_approval;
@@ -38,6 +41,8 @@ pub use crate::codex_tool_config::CodexToolCallParam;
 pub use crate::codex_tool_config::CodexToolCallReplyParam;
 pub use crate::exec_approval::ExecApprovalElicitRequestParams;
 pub use crate::exec_approval::ExecApprovalResponse;
+pub use crate::mcp_tool_call_approval::McpToolCallApprovalElicitRequestParams;
+pub use crate::mcp_tool_call_approval::McpToolCallApprovalResponse;
 pub use crate::patch_approval::PatchApprovalElicitRequestParams;
 pub use crate::patch_approval::PatchApprovalResponse;
 
@@ -285,6 +285,7 @@ mod tests {
                 history_entry_count: 1000,
                 initial_messages: None,
                 rollout_path: rollout_file.path().to_path_buf(),
+                worktree: None,
             }),
         };
 
@@ -319,6 +320,7 @@ mod tests {
             history_entry_count: 1000,
             initial_messages: None,
             rollout_path: rollout_file.path().to_path_buf(),
+            worktree: None,
         };
         let event = Event {
             id: "1".to_string(),
@@ -14,12 +14,14 @@ use assert_cmd::prelude::*;
 use codex_mcp_server::CodexToolCallParam;
 use codex_protocol::mcp_protocol::AddConversationListenerParams;
 use codex_protocol::mcp_protocol::ArchiveConversationParams;
+use codex_protocol::mcp_protocol::ExportConversationParams;
 use codex_protocol::mcp_protocol::CancelLoginChatGptParams;
 use codex_protocol::mcp_protocol::GetAuthStatusParams;
 use codex_protocol::mcp_protocol::InterruptConversationParams;
 use codex_protocol::mcp_protocol::ListConversationsParams;
 use codex_protocol::mcp_protocol::LoginApiKeyParams;
 use codex_protocol::mcp_protocol::NewConversationParams;
+use codex_protocol::mcp_protocol::QueueUserMessageParams;
 use codex_protocol::mcp_protocol::RemoveConversationListenerParams;
 use codex_protocol::mcp_protocol::ResumeConversationParams;
 use codex_protocol::mcp_protocol::SendUserMessageParams;
@@ -28,6 +30,7 @@ use codex_protocol::mcp_protocol::SetDefaultModelParams;
 
 use mcp_types::CallToolRequestParams;
 use mcp_types::ClientCapabilities;
+use mcp_types::GetPromptRequestParams;
 use mcp_types::Implementation;
 use mcp_types::InitializeRequestParams;
 use mcp_types::JSONRPC_VERSION;
@@ -37,7 +40,9 @@ use mcp_types::JSONRPCRequest;
 use mcp_types::JSONRPCResponse;
 use mcp_types::ModelContextProtocolNotification;
 use mcp_types::ModelContextProtocolRequest;
+use mcp_types::ReadResourceRequestParams;
 use mcp_types::RequestId;
+use mcp_types::SubscribeRequestParams;
 use pretty_assertions::assert_eq;
 use serde_json::json;
 use std::process::Command as StdCommand;
@@ -169,6 +174,10 @@ impl McpProcess {
                 id: RequestId::Integer(request_id),
                 result: json!({
                     "capabilities": {
+                        "prompts": {},
+                        "resources": {
+                            "subscribe": true
+                        },
                         "tools": {
                             "listChanged": true
                         },
@@ -231,6 +240,15 @@ impl McpProcess {
         self.send_request("archiveConversation", params).await
     }
 
+    /// Send an `exportConversation` JSON-RPC request.
+    pub async fn send_export_conversation_request(
+        &mut self,
+        params: ExportConversationParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("exportConversation", params).await
+    }
+
     /// Send an `addConversationListener` JSON-RPC request.
     pub async fn send_add_conversation_listener_request(
         &mut self,
@@ -250,6 +268,15 @@ impl McpProcess {
         self.send_request("sendUserMessage", params).await
     }
 
+    /// Send a `queueUserMessage` JSON-RPC request.
+    pub async fn send_queue_user_message_request(
+        &mut self,
+        params: QueueUserMessageParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("queueUserMessage", params).await
+    }
+
     /// Send a `removeConversationListener` JSON-RPC request.
     pub async fn send_remove_conversation_listener_request(
         &mut self,
@@ -329,6 +356,48 @@ impl McpProcess {
         self.send_request("resumeConversation", params).await
     }
 
+    /// Send a `resources/list` JSON-RPC request.
+    pub async fn send_list_resources_request(&mut self) -> anyhow::Result<i64> {
+        self.send_request(mcp_types::ListResourcesRequest::METHOD, None)
+            .await
+    }
+
+    /// Send a `resources/read` JSON-RPC request.
+    pub async fn send_read_resource_request(
+        &mut self,
+        params: ReadResourceRequestParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request(mcp_types::ReadResourceRequest::METHOD, params)
+            .await
+    }
+
+    /// Send a `resources/subscribe` JSON-RPC request.
+    pub async fn send_subscribe_request(
+        &mut self,
+        params: SubscribeRequestParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request(mcp_types::SubscribeRequest::METHOD, params)
+            .await
+    }
+
+    /// Send a `prompts/list` JSON-RPC request.
+    pub async fn send_list_prompts_request(&mut self) -> anyhow::Result<i64> {
+        self.send_request(mcp_types::ListPromptsRequest::METHOD, None)
+            .await
+    }
+
+    /// Send a `prompts/get` JSON-RPC request.
+    pub async fn send_get_prompt_request(
+        &mut self,
+        params: GetPromptRequestParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request(mcp_types::GetPromptRequest::METHOD, params)
+            .await
+    }
+
     /// Send a `loginApiKey` JSON-RPC request.
     pub async fn send_login_api_key_request(
         &mut self,
@@ -61,6 +61,7 @@ async fn test_conversation_create_and_send_message_ok() {
         model,
         reasoning_effort: _,
         rollout_path: _,
+        worktree: _,
     } = to_response::<NewConversationResponse>(new_conv_resp)
         .expect("deserialize newConversation response");
     assert_eq!(model, "o3");
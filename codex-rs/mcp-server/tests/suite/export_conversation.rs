@@ -0,0 +1,122 @@
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::mcp_protocol::ExportConversationParams;
+use codex_protocol::mcp_protocol::ExportConversationResponse;
+use codex_protocol::mcp_protocol::ExportFormat;
+use mcp_test_support::McpProcess;
+use mcp_test_support::to_response;
+use mcp_types::JSONRPCResponse;
+use mcp_types::RequestId;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::time::timeout;
+use uuid::Uuid;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn export_conversation_renders_rollout() {
+    let codex_home = TempDir::new().expect("create temp dir");
+    let (uuid, rollout_path) = create_fake_rollout(codex_home.path());
+
+    let mut mcp = McpProcess::new(codex_home.path())
+        .await
+        .expect("spawn mcp process");
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize())
+        .await
+        .expect("init timeout")
+        .expect("init failed");
+
+    let req_id = mcp
+        .send_export_conversation_request(ExportConversationParams {
+            rollout_path: rollout_path.clone(),
+            format: ExportFormat::Markdown,
+        })
+        .await
+        .expect("send exportConversation");
+    let resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(req_id)),
+    )
+    .await
+    .expect("exportConversation timeout")
+    .expect("exportConversation resp");
+    let ExportConversationResponse { content } =
+        to_response::<ExportConversationResponse>(resp).expect("deserialize response");
+    assert_eq!(
+        content,
+        format!(
+            "# Codex session {uuid}\n\n- Started: 2025-01-02T12:00:00Z\n- Working directory: `/`\n\n## User\n\nHello\n\n## Codex\n\nHi there\n"
+        )
+    );
+
+    // Files outside the sessions directories are rejected.
+    let outside = codex_home.path().join("config.toml");
+    fs::write(&outside, "").expect("write config.toml");
+    let req_id = mcp
+        .send_export_conversation_request(ExportConversationParams {
+            rollout_path: outside,
+            format: ExportFormat::Html,
+        })
+        .await
+        .expect("send exportConversation");
+    let error = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(req_id)),
+    )
+    .await
+    .expect("exportConversation timeout")
+    .expect("exportConversation error");
+    assert!(
+        error
+            .error
+            .message
+            .contains("must be in sessions directory")
+    );
+}
+
+fn create_fake_rollout(codex_home: &Path) -> (Uuid, PathBuf) {
+    let uuid = Uuid::new_v4();
+    let dir = codex_home.join("sessions/2025/01/02");
+    fs::create_dir_all(&dir).unwrap_or_else(|e| panic!("create sessions dir: {e}"));
+    let file_path = dir.join(format!("rollout-2025-01-02T12-00-00-{uuid}.jsonl"));
+
+    let timestamp = "2025-01-02T12:00:00Z";
+    let lines = [
+        json!({
+            "timestamp": timestamp,
+            "type": "session_meta",
+            "payload": {
+                "id": uuid,
+                "timestamp": timestamp,
+                "cwd": "/",
+                "originator": "codex",
+                "cli_version": "0.0.0",
+                "instructions": null
+            }
+        }),
+        json!({
+            "timestamp": timestamp,
+            "type": "event_msg",
+            "payload": {
+                "type": "user_message",
+                "message": "Hello",
+                "kind": "plain"
+            }
+        }),
+        json!({
+            "timestamp": timestamp,
+            "type": "event_msg",
+            "payload": {
+                "type": "agent_message",
+                "message": "Hi there"
+            }
+        }),
+    ];
+    let text: String = lines.iter().map(|line| format!("{line}\n")).collect();
+    fs::write(&file_path, text).unwrap_or_else(|e| panic!("write rollout file: {e}"));
+    (uuid, file_path)
+}
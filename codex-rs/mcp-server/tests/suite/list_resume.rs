@@ -55,6 +55,7 @@ async fn test_list_and_resume_conversations() {
         .send_list_conversations_request(ListConversationsParams {
             page_size: Some(2),
             cursor: None,
+            ..Default::default()
         })
         .await
         .expect("send listConversations");
@@ -80,6 +81,7 @@ async fn test_list_and_resume_conversations() {
         .send_list_conversations_request(ListConversationsParams {
             page_size: Some(2),
             cursor: next_cursor,
+            ..Default::default()
         })
         .await
         .expect("send listConversations page 2");
@@ -145,6 +147,90 @@ async fn test_list_and_resume_conversations() {
     let _: uuid::Uuid = conversation_id.into();
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_search_conversations() {
+    let codex_home = TempDir::new().expect("create temp dir");
+    create_fake_rollout(
+        codex_home.path(),
+        "2025-01-02T12-00-00",
+        "2025-01-02T12:00:00Z",
+        "Fix the tokenizer",
+    );
+    create_fake_rollout(
+        codex_home.path(),
+        "2025-01-01T12-00-00",
+        "2025-01-01T12:00:00Z",
+        "Update the tokenizer docs",
+    );
+
+    let mut mcp = McpProcess::new(codex_home.path())
+        .await
+        .expect("spawn mcp process");
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize())
+        .await
+        .expect("init timeout")
+        .expect("init failed");
+
+    let searches = [
+        (
+            ListConversationsParams {
+                query: Some("TOKENIZER docs".to_string()),
+                ..Default::default()
+            },
+            vec!["Update the tokenizer docs"],
+        ),
+        (
+            ListConversationsParams {
+                query: Some("tokenizer".to_string()),
+                since: Some("2025-01-02".to_string()),
+                ..Default::default()
+            },
+            vec!["Fix the tokenizer"],
+        ),
+        (
+            ListConversationsParams {
+                cwd: Some("/".into()),
+                until: Some("2025-01-02T00:00:00Z".to_string()),
+                ..Default::default()
+            },
+            vec!["Update the tokenizer docs"],
+        ),
+    ];
+    for (params, expected) in searches {
+        let req_id = mcp
+            .send_list_conversations_request(params)
+            .await
+            .expect("send listConversations");
+        let resp: JSONRPCResponse = timeout(
+            DEFAULT_READ_TIMEOUT,
+            mcp.read_stream_until_response_message(RequestId::Integer(req_id)),
+        )
+        .await
+        .expect("listConversations timeout")
+        .expect("listConversations resp");
+        let ListConversationsResponse { items, .. } =
+            to_response::<ListConversationsResponse>(resp).expect("deserialize response");
+        let previews: Vec<&str> = items.iter().map(|item| item.preview.as_str()).collect();
+        assert_eq!(previews, expected);
+    }
+
+    let req_id = mcp
+        .send_list_conversations_request(ListConversationsParams {
+            since: Some("last tuesday".to_string()),
+            ..Default::default()
+        })
+        .await
+        .expect("send listConversations");
+    let error = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(req_id)),
+    )
+    .await
+    .expect("listConversations timeout")
+    .expect("listConversations error");
+    assert!(error.error.message.contains("invalid since"));
+}
+
 fn create_fake_rollout(codex_home: &Path, filename_ts: &str, meta_rfc3339: &str, preview: &str) {
     let uuid = Uuid::new_v4();
     // sessions/YYYY/MM/DD/ derived from filename_ts (YYYY-MM-DDThh-mm-ss)
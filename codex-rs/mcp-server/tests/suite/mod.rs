@@ -5,9 +5,12 @@ mod codex_message_processor_flow;
 mod codex_tool;
 mod config;
 mod create_conversation;
+mod export_conversation;
 mod interrupt;
 mod list_resume;
 mod login;
+mod prompts;
+mod resources;
 mod send_message;
 mod set_default_model;
 mod user_agent;
@@ -0,0 +1,84 @@
+use std::fs;
+
+use mcp_test_support::McpProcess;
+use mcp_test_support::to_response;
+use mcp_types::ContentBlock;
+use mcp_types::GetPromptRequestParams;
+use mcp_types::GetPromptResult;
+use mcp_types::ListPromptsResult;
+use mcp_types::RequestId;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn custom_prompts_are_listed_and_expanded() {
+    let codex_home = TempDir::new().expect("create temp dir");
+    let prompts_dir = codex_home.path().join("prompts");
+    fs::create_dir_all(&prompts_dir).expect("create prompts dir");
+    fs::write(
+        prompts_dir.join("review.md"),
+        "---\ndescription: Review a file\nargument-hint: <file>\n---\nReview $1 carefully.\n",
+    )
+    .expect("write prompt");
+    fs::write(prompts_dir.join("notes.txt"), "not a prompt").expect("write file");
+
+    let mut mcp = McpProcess::new(codex_home.path())
+        .await
+        .expect("spawn mcp process");
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize())
+        .await
+        .expect("init timeout")
+        .expect("init failed");
+
+    let list_id = mcp
+        .send_list_prompts_request()
+        .await
+        .expect("send prompts/list");
+    let response = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(list_id)),
+    )
+    .await
+    .expect("prompts/list timeout")
+    .expect("prompts/list response");
+    let ListPromptsResult { prompts, .. } =
+        to_response::<ListPromptsResult>(response).expect("deserialize prompts/list");
+    assert_eq!(
+        serde_json::to_value(&prompts).expect("serialize prompts"),
+        json!([{
+            "name": "review",
+            "description": "Review a file",
+            "arguments": [{
+                "name": "arguments",
+                "description": "<file>",
+                "required": false
+            }]
+        }])
+    );
+
+    let get_id = mcp
+        .send_get_prompt_request(GetPromptRequestParams {
+            name: "review".to_string(),
+            arguments: Some(json!({ "arguments": "src/main.rs" })),
+        })
+        .await
+        .expect("send prompts/get");
+    let response = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(get_id)),
+    )
+    .await
+    .expect("prompts/get timeout")
+    .expect("prompts/get response");
+    let GetPromptResult { messages, .. } =
+        to_response::<GetPromptResult>(response).expect("deserialize prompts/get");
+    assert_eq!(messages.len(), 1);
+    match &messages[0].content {
+        ContentBlock::TextContent(text) => assert_eq!(text.text, "Review src/main.rs carefully.\n"),
+        other => panic!("unexpected prompt content: {other:?}"),
+    }
+}
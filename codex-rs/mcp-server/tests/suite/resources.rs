@@ -0,0 +1,232 @@
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use mcp_test_support::McpProcess;
+use mcp_test_support::to_response;
+use mcp_types::ListResourcesResult;
+use mcp_types::ReadResourceRequestParams;
+use mcp_types::ReadResourceResult;
+use mcp_types::ReadResourceResultContents;
+use mcp_types::RequestId;
+use mcp_types::SubscribeRequestParams;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::time::timeout;
+use uuid::Uuid;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+const PATCH: &str =
+    "*** Begin Patch\n*** Update File: hello.txt\n@@\n-hello\n+hello, world\n*** End Patch";
+
+const DIFF: &str = "diff --git a/hello.txt b/hello.txt\n--- a/hello.txt\n+++ b/hello.txt\n@@\n-hello\n+hello, world\n";
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn conversations_are_listed_and_read_as_resources() {
+    let codex_home = TempDir::new().expect("create temp dir");
+    let (conversation_id, _) = create_rollout(codex_home.path());
+    let uri = format!("codex://conversation/{conversation_id}");
+
+    let mut mcp = McpProcess::new(codex_home.path())
+        .await
+        .expect("spawn mcp process");
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize())
+        .await
+        .expect("init timeout")
+        .expect("init failed");
+
+    let list_id = mcp
+        .send_list_resources_request()
+        .await
+        .expect("send resources/list");
+    let response = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(list_id)),
+    )
+    .await
+    .expect("resources/list timeout")
+    .expect("resources/list response");
+    let ListResourcesResult { resources, .. } =
+        to_response::<ListResourcesResult>(response).expect("deserialize resources/list");
+    assert_eq!(resources.len(), 1);
+    assert_eq!(resources[0].uri, uri);
+    assert_eq!(resources[0].title.as_deref(), Some("Say hello"));
+
+    let read_id = mcp
+        .send_read_resource_request(ReadResourceRequestParams { uri: uri.clone() })
+        .await
+        .expect("send resources/read");
+    let response = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(read_id)),
+    )
+    .await
+    .expect("resources/read timeout")
+    .expect("resources/read response");
+    let ReadResourceResult { contents } =
+        to_response::<ReadResourceResult>(response).expect("deserialize resources/read");
+    let contents: Vec<(String, String)> = contents
+        .into_iter()
+        .map(|content| match content {
+            ReadResourceResultContents::TextResourceContents(text) => (text.uri, text.text),
+            ReadResourceResultContents::BlobResourceContents(blob) => {
+                panic!("unexpected blob contents: {blob:?}")
+            }
+        })
+        .collect();
+    assert_eq!(
+        contents,
+        vec![
+            (
+                uri.clone(),
+                format!(
+                    "# Codex conversation {conversation_id}\n\n\
+                     - Started: 2025-01-02T12:00:00Z\n\
+                     - Working directory: `/`\n\n\
+                     ## User\n\nSay hello\n\n\
+                     ## Codex\n\nDone, hello.txt now greets the world.\n"
+                ),
+            ),
+            (format!("{uri}/diff"), DIFF.to_string()),
+        ]
+    );
+
+    let unknown_id = mcp
+        .send_read_resource_request(ReadResourceRequestParams {
+            uri: format!("codex://conversation/{}", Uuid::new_v4()),
+        })
+        .await
+        .expect("send resources/read");
+    let error = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(unknown_id)),
+    )
+    .await
+    .expect("resources/read timeout")
+    .expect("resources/read error");
+    assert!(error.error.message.starts_with("unknown resource:"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn subscribers_are_notified_when_a_conversation_changes() {
+    let codex_home = TempDir::new().expect("create temp dir");
+    let (conversation_id, rollout_path) = create_rollout(codex_home.path());
+    let uri = format!("codex://conversation/{conversation_id}");
+
+    let mut mcp = McpProcess::new(codex_home.path())
+        .await
+        .expect("spawn mcp process");
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize())
+        .await
+        .expect("init timeout")
+        .expect("init failed");
+
+    let subscribe_id = mcp
+        .send_subscribe_request(SubscribeRequestParams { uri: uri.clone() })
+        .await
+        .expect("send resources/subscribe");
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(subscribe_id)),
+    )
+    .await
+    .expect("resources/subscribe timeout")
+    .expect("resources/subscribe response");
+
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(&rollout_path)
+        .expect("open rollout");
+    writeln!(
+        file,
+        "{}",
+        event_line(json!({"type": "agent_message", "message": "Anything else?"}))
+    )
+    .expect("append to rollout");
+
+    let notification = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_notification_message("notifications/resources/updated"),
+    )
+    .await
+    .expect("notifications/resources/updated timeout")
+    .expect("notifications/resources/updated");
+    assert_eq!(notification.params, Some(json!({ "uri": uri })));
+}
+
+fn event_line(payload: serde_json::Value) -> String {
+    json!({
+        "timestamp": "2025-01-02T12:00:00Z",
+        "type": "event_msg",
+        "payload": payload,
+    })
+    .to_string()
+}
+
+fn response_item_line(payload: serde_json::Value) -> String {
+    json!({
+        "timestamp": "2025-01-02T12:00:00Z",
+        "type": "response_item",
+        "payload": payload,
+    })
+    .to_string()
+}
+
+fn create_rollout(codex_home: &Path) -> (Uuid, PathBuf) {
+    let uuid = Uuid::new_v4();
+    let dir = codex_home.join("sessions/2025/01/02");
+    fs::create_dir_all(&dir).unwrap_or_else(|e| panic!("create sessions dir: {e}"));
+    let path = dir.join(format!("rollout-2025-01-02T12-00-00-{uuid}.jsonl"));
+
+    let lines = [
+        json!({
+            "timestamp": "2025-01-02T12:00:00Z",
+            "type": "session_meta",
+            "payload": {
+                "id": uuid,
+                "timestamp": "2025-01-02T12:00:00Z",
+                "cwd": "/",
+                "originator": "codex",
+                "cli_version": "0.0.0",
+                "instructions": null
+            }
+        })
+        .to_string(),
+        json!({
+            "timestamp": "2025-01-02T12:00:00Z",
+            "type": "response_item",
+            "payload": {
+                "type": "message",
+                "role": "user",
+                "content": [{"type": "input_text", "text": "Say hello"}]
+            }
+        })
+        .to_string(),
+        event_line(json!({"type": "user_message", "message": "Say hello", "kind": "plain"})),
+        response_item_line(json!({
+            "type": "function_call",
+            "name": "apply_patch",
+            "arguments": json!({ "input": PATCH }).to_string(),
+            "call_id": "call-1"
+        })),
+        response_item_line(json!({
+            "type": "function_call_output",
+            "call_id": "call-1",
+            "output": json!({
+                "output": "Success. Updated the following files:\nM hello.txt\n",
+                "metadata": { "exit_code": 0, "duration_seconds": 0.1 }
+            })
+            .to_string()
+        })),
+        event_line(json!({
+            "type": "agent_message",
+            "message": "Done, hello.txt now greets the world."
+        })),
+    ];
+    fs::write(&path, lines.join("\n") + "\n").unwrap_or_else(|e| panic!("write rollout: {e}"));
+    (uuid, path)
+}
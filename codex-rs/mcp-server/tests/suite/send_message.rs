@@ -6,6 +6,8 @@ use codex_protocol::mcp_protocol::ConversationId;
 use codex_protocol::mcp_protocol::InputItem;
 use codex_protocol::mcp_protocol::NewConversationParams;
 use codex_protocol::mcp_protocol::NewConversationResponse;
+use codex_protocol::mcp_protocol::QueueUserMessageParams;
+use codex_protocol::mcp_protocol::QueueUserMessageResponse;
 use codex_protocol::mcp_protocol::SendUserMessageParams;
 use codex_protocol::mcp_protocol::SendUserMessageResponse;
 use mcp_test_support::McpProcess;
@@ -126,6 +128,87 @@ async fn send_message(message: &str, conversation_id: ConversationId, mcp: &mut
     );
 }
 
+#[tokio::test]
+async fn test_queue_message_starts_turn_when_idle() {
+    let responses = vec![
+        create_final_assistant_message_sse_response("Done").expect("build mock assistant message"),
+    ];
+    let server = create_mock_chat_completions_server(responses).await;
+    let codex_home = TempDir::new().expect("create temp dir");
+    create_config_toml(codex_home.path(), &server.uri()).expect("write config.toml");
+
+    let mut mcp = McpProcess::new(codex_home.path())
+        .await
+        .expect("spawn mcp process");
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize())
+        .await
+        .expect("init timed out")
+        .expect("init failed");
+
+    let new_conv_id = mcp
+        .send_new_conversation_request(NewConversationParams::default())
+        .await
+        .expect("send newConversation");
+    let new_conv_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(new_conv_id)),
+    )
+    .await
+    .expect("newConversation timeout")
+    .expect("newConversation resp");
+    let NewConversationResponse {
+        conversation_id, ..
+    } = to_response::<_>(new_conv_resp).expect("deserialize newConversation response");
+
+    let add_listener_id = mcp
+        .send_add_conversation_listener_request(AddConversationListenerParams { conversation_id })
+        .await
+        .expect("send addConversationListener");
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(add_listener_id)),
+    )
+    .await
+    .expect("addConversationListener timeout")
+    .expect("addConversationListener resp");
+
+    let queue_id = mcp
+        .send_queue_user_message_request(QueueUserMessageParams {
+            conversation_id,
+            items: vec![InputItem::Text {
+                text: "Hello".to_string(),
+            }],
+        })
+        .await
+        .expect("send queueUserMessage");
+    let response: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(queue_id)),
+    )
+    .await
+    .expect("queueUserMessage response timeout")
+    .expect("queueUserMessage response error");
+    let QueueUserMessageResponse { queued_message_id } =
+        to_response(response).expect("deserialize queueUserMessage response");
+
+    // The conversation is idle, so the message starts a turn right away and
+    // the events of that turn carry the id of the queued message.
+    let task_complete: JSONRPCNotification = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_notification_message("codex/event/task_complete"),
+    )
+    .await
+    .expect("task_complete timeout")
+    .expect("task_complete notification");
+    let params = task_complete
+        .params
+        .expect("notification should have params");
+    assert_eq!(
+        params.get("id"),
+        Some(&serde_json::Value::String(queued_message_id))
+    );
+}
+
 #[tokio::test]
 async fn test_send_message_session_not_found() {
     // Start MCP without creating a Codex session
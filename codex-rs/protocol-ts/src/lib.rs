@@ -25,10 +25,15 @@ pub fn generate_ts(out_dir: &Path, prettier: Option<&Path>) -> Result<()> {
     codex_protocol::mcp_protocol::ListConversationsResponse::export_all_to(out_dir)?;
     codex_protocol::mcp_protocol::ResumeConversationResponse::export_all_to(out_dir)?;
     codex_protocol::mcp_protocol::ArchiveConversationResponse::export_all_to(out_dir)?;
+    codex_protocol::mcp_protocol::ExportConversationResponse::export_all_to(out_dir)?;
     codex_protocol::mcp_protocol::AddConversationSubscriptionResponse::export_all_to(out_dir)?;
     codex_protocol::mcp_protocol::RemoveConversationSubscriptionResponse::export_all_to(out_dir)?;
     codex_protocol::mcp_protocol::SendUserMessageResponse::export_all_to(out_dir)?;
     codex_protocol::mcp_protocol::SendUserTurnResponse::export_all_to(out_dir)?;
+    codex_protocol::mcp_protocol::QueueUserMessageResponse::export_all_to(out_dir)?;
+    codex_protocol::mcp_protocol::UpdateQueuedUserMessageResponse::export_all_to(out_dir)?;
+    codex_protocol::mcp_protocol::RemoveQueuedUserMessageResponse::export_all_to(out_dir)?;
+    codex_protocol::mcp_protocol::ResolveWorktreeResponse::export_all_to(out_dir)?;
     codex_protocol::mcp_protocol::InterruptConversationResponse::export_all_to(out_dir)?;
     codex_protocol::mcp_protocol::GitDiffToRemoteResponse::export_all_to(out_dir)?;
     codex_protocol::mcp_protocol::LoginApiKeyParams::export_all_to(out_dir)?;
@@ -39,6 +44,7 @@ pub fn generate_ts(out_dir: &Path, prettier: Option<&Path>) -> Result<()> {
     codex_protocol::mcp_protocol::GetAuthStatusResponse::export_all_to(out_dir)?;
     codex_protocol::mcp_protocol::ApplyPatchApprovalResponse::export_all_to(out_dir)?;
     codex_protocol::mcp_protocol::ExecCommandApprovalResponse::export_all_to(out_dir)?;
+    codex_protocol::mcp_protocol::McpToolCallApprovalResponse::export_all_to(out_dir)?;
     codex_protocol::mcp_protocol::GetUserSavedConfigResponse::export_all_to(out_dir)?;
     codex_protocol::mcp_protocol::SetDefaultModelResponse::export_all_to(out_dir)?;
     codex_protocol::mcp_protocol::GetUserAgentResponse::export_all_to(out_dir)?;
@@ -3,9 +3,30 @@ use serde::Serialize;
 use std::path::PathBuf;
 use ts_rs::TS;
 
-#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+use crate::config_types::ReasoningEffort;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, TS)]
 pub struct CustomPrompt {
     pub name: String,
     pub path: PathBuf,
+
+    /// Prompt body with any frontmatter removed. May contain `$1`..`$9` and
+    /// `$ARGUMENTS` placeholders that are replaced when the prompt is invoked.
     pub content: String,
+
+    /// Short description shown next to the prompt in the command popup.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Hint describing the arguments the prompt expects, e.g. `<file> [focus]`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub argument_hint: Option<String>,
+
+    /// Model to use for the turn started by this prompt.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+
+    /// Reasoning effort to use for the turn started by this prompt.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effort: Option<ReasoningEffort>,
 }
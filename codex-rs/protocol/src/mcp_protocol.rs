@@ -12,6 +12,8 @@ use crate::protocol::FileChange;
 use crate::protocol::ReviewDecision;
 use crate::protocol::SandboxPolicy;
 use crate::protocol::TurnAbortReason;
+use crate::protocol::WorktreeInfo;
+use crate::protocol::WorktreeResolution;
 use mcp_types::RequestId;
 use serde::Deserialize;
 use serde::Serialize;
@@ -96,6 +98,12 @@ pub enum ClientRequest {
         request_id: RequestId,
         params: ArchiveConversationParams,
     },
+    /// Render a recorded Codex conversation for people to read.
+    ExportConversation {
+        #[serde(rename = "id")]
+        request_id: RequestId,
+        params: ExportConversationParams,
+    },
     SendUserMessage {
         #[serde(rename = "id")]
         request_id: RequestId,
@@ -106,11 +114,32 @@ pub enum ClientRequest {
         request_id: RequestId,
         params: SendUserTurnParams,
     },
+    /// Queue a message to run as its own turn after the current one.
+    QueueUserMessage {
+        #[serde(rename = "id")]
+        request_id: RequestId,
+        params: QueueUserMessageParams,
+    },
+    UpdateQueuedUserMessage {
+        #[serde(rename = "id")]
+        request_id: RequestId,
+        params: UpdateQueuedUserMessageParams,
+    },
+    RemoveQueuedUserMessage {
+        #[serde(rename = "id")]
+        request_id: RequestId,
+        params: RemoveQueuedUserMessageParams,
+    },
     InterruptConversation {
         #[serde(rename = "id")]
         request_id: RequestId,
         params: InterruptConversationParams,
     },
+    ResolveWorktree {
+        #[serde(rename = "id")]
+        request_id: RequestId,
+        params: ResolveWorktreeParams,
+    },
     AddConversationListener {
         #[serde(rename = "id")]
         request_id: RequestId,
@@ -215,6 +244,11 @@ pub struct NewConversationParams {
     /// Whether to include the apply patch tool in the conversation.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub include_apply_patch_tool: Option<bool>,
+
+    /// Whether to run the conversation in a dedicated git worktree on a
+    /// fresh branch. Resolve it with `resolveWorktree` when done.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub worktree: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
@@ -226,6 +260,9 @@ pub struct NewConversationResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reasoning_effort: Option<ReasoningEffort>,
     pub rollout_path: PathBuf,
+    /// The git worktree the conversation works in, if one was requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub worktree: Option<WorktreeInfo>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, TS)]
@@ -246,6 +283,27 @@ pub struct ListConversationsParams {
     /// Opaque pagination cursor returned by a previous call.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cursor: Option<String>,
+    /// Only conversations whose messages contain all of these words,
+    /// ignoring case.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    /// Only conversations started in this directory or one below it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<PathBuf>,
+    /// Only conversations started on this git branch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_branch: Option<String>,
+    /// Only conversations that used this model.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Only conversations started at or after this RFC 3339 timestamp or
+    /// `YYYY-MM-DD` date.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    /// Only conversations started before this RFC 3339 timestamp, or on or
+    /// before this `YYYY-MM-DD` date.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
@@ -297,6 +355,34 @@ pub struct ArchiveConversationParams {
 #[serde(rename_all = "camelCase")]
 pub struct ArchiveConversationResponse {}
 
+/// Format of an exported conversation.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Markdown,
+    /// A self-contained HTML page.
+    Html,
+    Json,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportConversationParams {
+    /// Absolute path to the rollout JSONL file, in the sessions or archived
+    /// sessions directory.
+    pub rollout_path: PathBuf,
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportConversationResponse {
+    /// The rendered conversation.
+    pub content: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct RemoveConversationSubscriptionResponse {}
@@ -495,6 +581,8 @@ pub struct Tools {
     pub web_search: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub view_image: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delegate: Option<bool>,
 }
 
 /// MCP representation of a [`codex_core::config_types::SandboxWorkspaceWrite`].
@@ -536,6 +624,59 @@ pub struct SendUserTurnParams {
 #[serde(rename_all = "camelCase")]
 pub struct SendUserTurnResponse {}
 
+/// While a turn is running, `sendUserMessage` steers it, whereas a message
+/// queued with `queueUserMessage` runs as its own turn once the turns before
+/// it have finished. Both show up in `input_queue_updated` events until the
+/// model sees them.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueUserMessageParams {
+    pub conversation_id: ConversationId,
+    pub items: Vec<InputItem>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueUserMessageResponse {
+    /// Identifies the message in `input_queue_updated` events and in
+    /// `updateQueuedUserMessage`/`removeQueuedUserMessage` requests.
+    pub queued_message_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateQueuedUserMessageParams {
+    pub conversation_id: ConversationId,
+    pub queued_message_id: String,
+    pub items: Vec<InputItem>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateQueuedUserMessageResponse {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveQueuedUserMessageParams {
+    pub conversation_id: ConversationId,
+    pub queued_message_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveQueuedUserMessageResponse {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveWorktreeParams {
+    pub conversation_id: ConversationId,
+    pub resolution: WorktreeResolution,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveWorktreeResponse {}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct InterruptConversationParams {
@@ -587,6 +728,7 @@ pub enum InputItem {
 
 pub const APPLY_PATCH_APPROVAL_METHOD: &str = "applyPatchApproval";
 pub const EXEC_COMMAND_APPROVAL_METHOD: &str = "execCommandApproval";
+pub const MCP_TOOL_CALL_APPROVAL_METHOD: &str = "mcpToolCallApproval";
 
 /// Request initiated from the server and sent to the client.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
@@ -604,6 +746,12 @@ pub enum ServerRequest {
         request_id: RequestId,
         params: ExecCommandApprovalParams,
     },
+    /// Request to call a tool of an MCP server.
+    McpToolCallApproval {
+        #[serde(rename = "id")]
+        request_id: RequestId,
+        params: McpToolCallApprovalParams,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
@@ -634,6 +782,25 @@ pub struct ExecCommandApprovalParams {
     pub reason: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+pub struct McpToolCallApprovalParams {
+    pub conversation_id: ConversationId,
+    /// Use to correlate this with [codex_core::protocol::McpToolCallBeginEvent]
+    /// and [codex_core::protocol::McpToolCallEndEvent].
+    pub call_id: String,
+    /// Name of the MCP server as defined in the config.
+    pub server: String,
+    /// Name of the tool as given by the MCP server.
+    pub tool: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+pub struct McpToolCallApprovalResponse {
+    pub decision: ReviewDecision,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
 pub struct ExecCommandApprovalResponse {
     pub decision: ReviewDecision,
@@ -701,6 +868,7 @@ mod tests {
                 base_instructions: None,
                 include_plan_tool: None,
                 include_apply_patch_tool: None,
+                worktree: None,
             },
         };
         assert_eq!(
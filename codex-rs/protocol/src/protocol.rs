@@ -21,6 +21,9 @@ use crate::num_format::format_with_separators;
 use crate::parse_command::ParsedCommand;
 use crate::plan_tool::UpdatePlanArgs;
 use mcp_types::CallToolResult;
+use mcp_types::GetPromptResult;
+use mcp_types::Prompt as McpPrompt;
+use mcp_types::Resource as McpResource;
 use mcp_types::Tool as McpTool;
 use serde::Deserialize;
 use serde::Serialize;
@@ -56,6 +59,10 @@ pub enum Op {
     Interrupt,
 
     /// Input from the user
+    ///
+    /// While a task is running, the input steers it: it is added to the
+    /// conversation before the next model request of the current turn and is
+    /// reported in [`EventMsg::InputQueueUpdated`] until then.
     UserInput {
         /// User input items, see `InputItem`
         items: Vec<InputItem>,
@@ -89,6 +96,35 @@ pub enum Op {
         summary: ReasoningSummaryConfig,
     },
 
+    /// Input from the user to run as its own task once the current task, and
+    /// any input queued before it, has finished. Starts a task right away
+    /// when the agent is idle.
+    ///
+    /// The submission id identifies the queued input in
+    /// [`EventMsg::InputQueueUpdated`] and in the events of the task it
+    /// starts. Queued input runs with the turn context in effect when it was
+    /// queued. After [`Op::Interrupt`] the queue is paused until the next task
+    /// completes or more input is queued.
+    QueueUserInput {
+        /// User input items, see `InputItem`
+        items: Vec<InputItem>,
+    },
+
+    /// Replace the items of input queued with [`Op::QueueUserInput`] that
+    /// has not started yet.
+    UpdateQueuedInput {
+        /// The submission id of the queued input.
+        id: String,
+        items: Vec<InputItem>,
+    },
+
+    /// Drop input queued with [`Op::QueueUserInput`] that has not started
+    /// yet.
+    RemoveQueuedInput {
+        /// The submission id of the queued input.
+        id: String,
+    },
+
     /// Override parts of the persistent turn context for subsequent turns.
     ///
     /// All fields are optional; when omitted, the existing value is preserved.
@@ -140,6 +176,14 @@ pub enum Op {
         decision: ReviewDecision,
     },
 
+    /// Approve an MCP tool call
+    McpToolCallApproval {
+        /// The id of the submission we are approving
+        id: String,
+        /// The user's decision in response to the request.
+        decision: ReviewDecision,
+    },
+
     /// Append an entry to the persistent cross-session message history.
     ///
     /// Note the entry is not guaranteed to be logged if the user has
@@ -160,9 +204,24 @@ pub enum Op {
     /// Reply is delivered via `EventMsg::McpListToolsResponse`.
     ListMcpTools,
 
-    /// Request the list of available custom prompts.
+    /// Request the list of resources offered by the configured MCP servers.
+    /// Reply is delivered via `EventMsg::McpListResourcesResponse`.
+    ListMcpResources,
+
+    /// Request the list of available custom prompts, including the prompts
+    /// offered by MCP servers.
     ListCustomPrompts,
 
+    /// Render a prompt offered by an MCP server.
+    /// Reply is delivered via `EventMsg::GetMcpPromptResponse`.
+    GetMcpPrompt {
+        server: String,
+        name: String,
+        /// Argument name -> value.
+        #[serde(default)]
+        arguments: HashMap<String, String>,
+    },
+
     /// Request the agent to summarize the current conversation context.
     /// The agent will use its existing context (either conversation history or previous response id)
     /// to generate a summary which will be returned as an AgentMessage event.
@@ -171,6 +230,25 @@ pub enum Op {
     /// Request a code review from the agent.
     Review { review_request: ReviewRequest },
 
+    /// Bring the changes made in the conversation's git worktree back into
+    /// the repository it was created from, or throw them away, then remove
+    /// the worktree and its branch. Only valid for conversations started with
+    /// a worktree and while no task is running.
+    /// Reply is delivered via `EventMsg::WorktreeResolved`.
+    ResolveWorktree { resolution: WorktreeResolution },
+
+    /// Restore the files of the repository to the checkpoint taken before the
+    /// latest user turn. Only valid while no task is running.
+    /// Reply is delivered via `EventMsg::CheckpointRestored`.
+    Undo,
+
+    /// Restore the files of the repository to the checkpoint taken before
+    /// `turn`, the 0-based index of a user message in the conversation.
+    /// Checkpoints of that turn and all later ones are dropped. Only valid
+    /// while no task is running.
+    /// Reply is delivered via `EventMsg::CheckpointRestored`.
+    RestoreCheckpoint { turn: usize },
+
     /// Request to shut down codex instance.
     Shutdown,
 }
@@ -240,9 +318,52 @@ pub enum SandboxPolicy {
         /// writable roots on UNIX. Defaults to `false`.
         #[serde(default)]
         exclude_slash_tmp: bool,
+
+        /// When set, reads are restricted to these folders, the writable
+        /// roots and the platform's system directories (see
+        /// [`DEFAULT_READABLE_ROOTS`]) instead of the entire file-system.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        readable_roots: Option<Vec<PathBuf>>,
+
+        /// Files and folders that must not be readable from within the
+        /// sandbox, even when they are under a readable or writable root.
+        /// Only honored when `readable_roots` is set.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        deny_read: Vec<PathBuf>,
+
+        /// Hosts that may be reached through the sandbox's filtering proxy
+        /// when `network_access` is `false`. Entries are exact host names or
+        /// `*.domain` wildcards and are matched case-insensitively.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        allowed_hosts: Vec<String>,
     },
 }
 
+/// System directories that stay readable when a [`SandboxPolicy`] restricts
+/// reads, so that shells, toolchains and shared libraries keep working.
+/// Entries that do not exist on the current machine are ignored.
+#[cfg(target_os = "macos")]
+pub const DEFAULT_READABLE_ROOTS: &[&str] = &[
+    "/bin",
+    "/sbin",
+    "/usr",
+    "/opt",
+    "/System",
+    "/Library",
+    "/private/etc",
+    "/private/var/db",
+    "/dev",
+];
+
+/// System directories that stay readable when a [`SandboxPolicy`] restricts
+/// reads, so that shells, toolchains and shared libraries keep working.
+/// Entries that do not exist on the current machine are ignored.
+#[cfg(not(target_os = "macos"))]
+pub const DEFAULT_READABLE_ROOTS: &[&str] = &[
+    "/bin", "/sbin", "/usr", "/lib", "/lib32", "/lib64", "/libx32", "/etc", "/opt", "/nix", "/dev",
+    "/proc", "/sys", "/run",
+];
+
 /// A writable root path accompanied by a list of subpaths that should remain
 /// read‑only even when the root is writable. This is primarily used to ensure
 /// top‑level VCS metadata directories (e.g. `.git`) under a writable root are
@@ -297,12 +418,22 @@ impl SandboxPolicy {
             network_access: false,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            readable_roots: None,
+            deny_read: vec![],
+            allowed_hosts: vec![],
         }
     }
 
-    /// Always returns `true`; restricting read access is not supported.
+    /// Returns `false` only for `WorkspaceWrite` policies that configure
+    /// `readable_roots`.
     pub fn has_full_disk_read_access(&self) -> bool {
-        true
+        !matches!(
+            self,
+            SandboxPolicy::WorkspaceWrite {
+                readable_roots: Some(_),
+                ..
+            }
+        )
     }
 
     pub fn has_full_disk_write_access(&self) -> bool {
@@ -333,6 +464,9 @@ impl SandboxPolicy {
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
                 network_access: _,
+                readable_roots: _,
+                deny_read: _,
+                allowed_hosts: _,
             } => {
                 // Start from explicitly configured writable roots.
                 let mut roots: Vec<PathBuf> = writable_roots.clone();
@@ -382,11 +516,65 @@ impl SandboxPolicy {
             }
         }
     }
+
+    /// Returns the list of readable roots (tailored to the current working
+    /// directory), or `None` when the entire file-system is readable. Paths
+    /// returned by [`SandboxPolicy::get_denied_read_paths`] must still be
+    /// carved out of these roots.
+    pub fn get_readable_roots_with_cwd(&self, cwd: &Path) -> Option<Vec<PathBuf>> {
+        let SandboxPolicy::WorkspaceWrite {
+            readable_roots: Some(readable_roots),
+            ..
+        } = self
+        else {
+            return None;
+        };
+
+        let mut roots: Vec<PathBuf> = DEFAULT_READABLE_ROOTS
+            .iter()
+            .map(PathBuf::from)
+            .filter(|root| root.exists())
+            .collect();
+        roots.extend(readable_roots.iter().cloned());
+        roots.extend(
+            self.get_writable_roots_with_cwd(cwd)
+                .into_iter()
+                .map(|writable_root| writable_root.root),
+        );
+        Some(roots)
+    }
+
+    /// Returns the paths that must not be readable from within the sandbox.
+    /// Always empty when the policy grants full disk read access.
+    pub fn get_denied_read_paths(&self) -> Vec<PathBuf> {
+        match self {
+            SandboxPolicy::WorkspaceWrite {
+                readable_roots: Some(_),
+                deny_read,
+                ..
+            } => deny_read.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the hosts that sandboxed commands may reach through the
+    /// filtering proxy. Always empty when the policy grants full network
+    /// access, since no proxy is needed in that case.
+    pub fn get_allowed_hosts(&self) -> &[String] {
+        match self {
+            SandboxPolicy::WorkspaceWrite {
+                network_access: false,
+                allowed_hosts,
+                ..
+            } => allowed_hosts,
+            _ => &[],
+        }
+    }
 }
 
 /// User input
 #[non_exhaustive]
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum InputItem {
     Text {
@@ -465,6 +653,15 @@ pub enum EventMsg {
 
     WebSearchEnd(WebSearchEndEvent),
 
+    /// The agent started sub-agent workers with the `delegate` tool.
+    DelegateBegin(DelegateBeginEvent),
+
+    /// An event emitted by one of the workers of a `delegate` call.
+    DelegateEvent(DelegateEventEvent),
+
+    /// All workers of a `delegate` call finished.
+    DelegateEnd(DelegateEndEvent),
+
     /// Notification that the server is about to execute a command.
     ExecCommandBegin(ExecCommandBeginEvent),
 
@@ -477,6 +674,8 @@ pub enum EventMsg {
 
     ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent),
 
+    McpToolCallApprovalRequest(McpToolCallApprovalRequestEvent),
+
     BackgroundEvent(BackgroundEventEvent),
 
     /// Notification that a model stream experienced an error or disconnect
@@ -498,13 +697,24 @@ pub enum EventMsg {
     /// List of MCP tools available to the agent.
     McpListToolsResponse(McpListToolsResponseEvent),
 
+    /// List of MCP resources available to the agent.
+    McpListResourcesResponse(McpListResourcesResponseEvent),
+
     /// List of custom prompts available to the agent.
     ListCustomPromptsResponse(ListCustomPromptsResponseEvent),
 
+    /// Response to GetMcpPrompt.
+    GetMcpPromptResponse(GetMcpPromptResponseEvent),
+
     PlanUpdate(UpdatePlanArgs),
 
     TurnAborted(TurnAbortedEvent),
 
+    /// User input that has not been sent to the model yet changed: input was
+    /// steered into or consumed by the current turn, or queued, edited,
+    /// removed or started as a new task.
+    InputQueueUpdated(InputQueueUpdatedEvent),
+
     /// Notification that the agent is shutting down.
     ShutdownComplete,
 
@@ -514,7 +724,14 @@ pub enum EventMsg {
     EnteredReviewMode(ReviewRequest),
 
     /// Exited review mode with an optional final result to apply.
-    ExitedReviewMode(Option<ReviewOutputEvent>),
+    ExitedReviewMode(ExitedReviewModeEvent),
+
+    /// The conversation's git worktree was resolved and removed.
+    WorktreeResolved(WorktreeResolvedEvent),
+
+    /// Files were restored to a checkpoint in response to `Op::Undo` or
+    /// `Op::RestoreCheckpoint`.
+    CheckpointRestored(CheckpointRestoredEvent),
 }
 
 // Individual event payload types matching each `EventMsg` variant.
@@ -534,7 +751,7 @@ pub struct TaskStartedEvent {
     pub model_context_window: Option<u64>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, Default, TS)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq, TS)]
 pub struct TokenUsage {
     pub input_tokens: u64,
     pub cached_input_tokens: u64,
@@ -973,6 +1190,13 @@ pub struct ReviewRequest {
     pub user_facing_hint: String,
 }
 
+/// Sent when a review session ends. `review_output` is `None` when the review
+/// was interrupted or produced no result.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, TS)]
+pub struct ExitedReviewModeEvent {
+    pub review_output: Option<ReviewOutputEvent>,
+}
+
 /// Structured review result produced by a child review session.
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, TS)]
 pub struct ReviewOutputEvent {
@@ -1046,6 +1270,9 @@ pub struct ExecCommandEndEvent {
     pub duration: Duration,
     /// Formatted output from the command, as seen by the model.
     pub formatted_output: String,
+    /// Hosts the sandbox's network proxy refused to connect to.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub denied_hosts: Vec<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, TS)]
@@ -1094,6 +1321,14 @@ pub struct ApplyPatchApprovalRequestEvent {
     pub grant_root: Option<PathBuf>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct McpToolCallApprovalRequestEvent {
+    /// Identifier so this can be paired with the McpToolCallBegin event that
+    /// follows if the call is approved.
+    pub call_id: String,
+    pub invocation: McpInvocation,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
 pub struct BackgroundEventEvent {
     pub message: String,
@@ -1147,10 +1382,29 @@ pub struct McpListToolsResponseEvent {
     pub tools: std::collections::HashMap<String, McpTool>,
 }
 
+/// Response payload for `Op::ListMcpResources`.
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct McpListResourcesResponseEvent {
+    /// Server name -> resources offered by that server.
+    pub resources: HashMap<String, Vec<McpResource>>,
+}
+
 /// Response payload for `Op::ListCustomPrompts`.
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
 pub struct ListCustomPromptsResponseEvent {
     pub custom_prompts: Vec<CustomPrompt>,
+    /// Server name -> prompts offered by that MCP server.
+    #[serde(default)]
+    pub mcp_prompts: HashMap<String, Vec<McpPrompt>>,
+}
+
+/// Response payload for `Op::GetMcpPrompt`.
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct GetMcpPromptResponseEvent {
+    pub server: String,
+    pub name: String,
+    /// Result of the `prompts/get` request, or an error message.
+    pub result: Result<GetPromptResult, String>,
 }
 
 #[derive(Debug, Default, Clone, Deserialize, Serialize, TS)]
@@ -1177,6 +1431,52 @@ pub struct SessionConfiguredEvent {
     pub initial_messages: Option<Vec<EventMsg>>,
 
     pub rollout_path: PathBuf,
+
+    /// The git worktree the conversation works in, if it was started with
+    /// one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worktree: Option<WorktreeInfo>,
+}
+
+/// A git worktree created for a single conversation.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, TS)]
+pub struct WorktreeInfo {
+    /// Root of the worktree checkout.
+    pub path: PathBuf,
+    /// Branch created for the conversation.
+    pub branch: String,
+    /// Commit the branch was created from.
+    pub base_commit: String,
+    /// Root of the repository the worktree was created from. Changes are
+    /// brought back into the branch checked out there.
+    pub repo_root: PathBuf,
+}
+
+/// What to do with the changes made in a conversation's git worktree.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Display, TS)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum WorktreeResolution {
+    /// Merge the conversation's branch.
+    Merge,
+    /// Cherry-pick the conversation's commits on top of the checked out
+    /// branch.
+    CherryPick,
+    /// Drop all changes.
+    Discard,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct WorktreeResolvedEvent {
+    pub resolution: WorktreeResolution,
+    /// Human readable description of what was done.
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct CheckpointRestoredEvent {
+    /// 0-based index of the user message whose turn was undone.
+    pub turn: usize,
 }
 
 /// User's decision in response to an ExecApprovalRequest.
@@ -1224,11 +1524,53 @@ pub struct Chunk {
     pub inserted_lines: Vec<String>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct DelegateBeginEvent {
+    /// Identifier of the `delegate` function call.
+    pub call_id: String,
+    /// The prompt of each worker, indexed by worker.
+    pub tasks: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct DelegateEventEvent {
+    /// Identifier of the `delegate` function call.
+    pub call_id: String,
+    /// Index of the worker in [`DelegateBeginEvent::tasks`].
+    pub worker: usize,
+    /// The event emitted by the worker's conversation.
+    pub msg: Box<EventMsg>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct DelegateEndEvent {
+    /// Identifier of the `delegate` function call.
+    pub call_id: String,
+    /// The final message of each worker, or why it failed, indexed by worker.
+    pub results: Vec<Result<String, String>>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
 pub struct TurnAbortedEvent {
     pub reason: TurnAbortReason,
 }
 
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, TS)]
+pub struct InputQueueUpdatedEvent {
+    /// Input sent while a task was running that will be added to the current
+    /// turn, oldest first.
+    pub steering: Vec<Vec<InputItem>>,
+    /// Input queued with `Op::QueueUserInput`, in the order it will run.
+    pub queued: Vec<QueuedInput>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, TS)]
+pub struct QueuedInput {
+    /// The submission id of the `Op::QueueUserInput` that queued the input.
+    pub id: String,
+    pub items: Vec<InputItem>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, TS)]
 #[serde(rename_all = "snake_case")]
 pub enum TurnAbortReason {
@@ -1258,6 +1600,7 @@ mod tests {
                 history_entry_count: 0,
                 initial_messages: None,
                 rollout_path: rollout_file.path().to_path_buf(),
+                worktree: None,
             }),
         };
 
@@ -1,11 +1,15 @@
 use std::path::PathBuf;
 
 use crate::app::App;
+use crate::app_event::AppEvent;
 use crate::history_cell::UserHistoryCell;
+use crate::keymap::keymap;
 use crate::pager_overlay::Overlay;
 use crate::tui;
 use crate::tui::TuiEvent;
+use codex_core::git_info::get_git_repo_root;
 use codex_core::protocol::ConversationPathResponseEvent;
+use codex_core::protocol::Op;
 use codex_protocol::mcp_protocol::ConversationId;
 use color_eyre::eyre::Result;
 use crossterm::event::KeyCode;
@@ -39,11 +43,12 @@ impl App {
     ) -> Result<bool> {
         if self.backtrack.overlay_preview_active {
             match event {
-                TuiEvent::Key(KeyEvent {
-                    code: KeyCode::Esc,
-                    kind: KeyEventKind::Press | KeyEventKind::Repeat,
-                    ..
-                }) => {
+                TuiEvent::Key(
+                    key_event @ KeyEvent {
+                        kind: KeyEventKind::Press | KeyEventKind::Repeat,
+                        ..
+                    },
+                ) if keymap().backtrack.matches(&key_event) => {
                     self.overlay_step_backtrack(tui, event)?;
                     Ok(true)
                 }
@@ -61,11 +66,13 @@ impl App {
                     Ok(true)
                 }
             }
-        } else if let TuiEvent::Key(KeyEvent {
-            code: KeyCode::Esc,
-            kind: KeyEventKind::Press | KeyEventKind::Repeat,
-            ..
-        }) = event
+        } else if let TuiEvent::Key(
+            key_event @ KeyEvent {
+                kind: KeyEventKind::Press | KeyEventKind::Repeat,
+                ..
+            },
+        ) = event
+            && keymap().backtrack.matches(&key_event)
         {
             // First Esc in transcript overlay: begin backtrack preview at latest user message.
             self.begin_overlay_backtrack_preview(tui);
@@ -92,6 +99,8 @@ impl App {
     }
 
     /// Stage a backtrack and request conversation history from the agent.
+    /// When checkpoints are taken, the user is first asked whether the files
+    /// should be restored to their state before the selected user message.
     pub(crate) fn request_backtrack(
         &mut self,
         prefill: String,
@@ -99,9 +108,26 @@ impl App {
         nth_user_message: usize,
     ) {
         self.backtrack.pending = Some((base_id, nth_user_message, prefill));
-        self.app_event_tx.send(crate::app_event::AppEvent::CodexOp(
-            codex_core::protocol::Op::GetPath,
-        ));
+        let config = self.chat_widget.config_ref();
+        if config.checkpoints && get_git_repo_root(&config.cwd).is_some() {
+            self.chat_widget.open_backtrack_files_popup();
+        } else {
+            self.confirm_backtrack(false);
+        }
+    }
+
+    /// Continue a staged backtrack, optionally rewinding the files first.
+    pub(crate) fn confirm_backtrack(&mut self, restore_files: bool) {
+        let Some((_, nth_user_message, _)) = &self.backtrack.pending else {
+            return;
+        };
+        if restore_files {
+            self.app_event_tx
+                .send(AppEvent::CodexOp(Op::RestoreCheckpoint {
+                    turn: *nth_user_message,
+                }));
+        }
+        self.app_event_tx.send(AppEvent::CodexOp(Op::GetPath));
     }
 
     /// Open transcript overlay (enters alternate screen and shows full transcript).
@@ -39,6 +39,12 @@ pub(crate) enum AppEvent {
     /// Result of computing a `/diff` command.
     DiffResult(String),
 
+    /// Open the `/review` target picker once the repository's default branch
+    /// (if any) has been looked up.
+    OpenReviewPopup {
+        default_branch: Option<String>,
+    },
+
     InsertHistoryCell(Box<dyn HistoryCell>),
 
     StartCommitAnimation,
@@ -65,4 +71,10 @@ pub(crate) enum AppEvent {
 
     /// Forwarded conversation history snapshot from the current conversation.
     ConversationHistory(ConversationPathResponseEvent),
+
+    /// Finish the pending backtrack once the user chose whether the files
+    /// should be rewound to the selected checkpoint as well.
+    ConfirmBacktrack {
+        restore_files: bool,
+    },
 }
@@ -24,12 +24,22 @@ use ratatui::widgets::WidgetRef;
 use super::chat_composer_history::ChatComposerHistory;
 use super::command_popup::CommandItem;
 use super::command_popup::CommandPopup;
+use super::command_popup::McpPromptEntry;
+use super::command_popup::mcp_prompt_entries;
 use super::file_search_popup::FileSearchPopup;
+use super::file_search_popup::FileSearchSelection;
+use super::file_search_popup::ResourceMention;
 use super::paste_burst::CharDecision;
 use super::paste_burst::PasteBurst;
 use crate::bottom_pane::paste_burst::FlushResult;
 use crate::slash_command::SlashCommand;
+use codex_core::config_types::EditingMode;
+use codex_core::custom_prompts::expand_prompt_arguments;
+use codex_core::custom_prompts::mcp_prompt_arguments;
+use codex_protocol::config_types::ReasoningEffort;
 use codex_protocol::custom_prompts::CustomPrompt;
+use mcp_types::Prompt as McpPrompt;
+use mcp_types::Resource as McpResource;
 
 use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
@@ -38,6 +48,8 @@ use crate::bottom_pane::textarea::TextAreaState;
 use crate::clipboard_paste::normalize_pasted_path;
 use crate::clipboard_paste::pasted_image_format;
 use crate::key_hint;
+use crate::keymap::keymap;
+use crate::theme::ThemeStylize;
 use crate::ui_consts::LIVE_PREFIX_COLS;
 use codex_file_search::FileMatch;
 use std::cell::RefCell;
@@ -55,6 +67,18 @@ const LARGE_PASTE_CHAR_THRESHOLD: usize = 1000;
 #[derive(Debug, PartialEq)]
 pub enum InputResult {
     Submitted(String),
+    /// A custom prompt that asks for its own model or reasoning effort.
+    SubmittedPrompt {
+        text: String,
+        model: Option<String>,
+        effort: Option<ReasoningEffort>,
+    },
+    /// A prompt offered by an MCP server; the server renders its text.
+    McpPrompt {
+        server: String,
+        name: String,
+        arguments: HashMap<String, String>,
+    },
     Command(SlashCommand),
     None,
 }
@@ -87,6 +111,8 @@ pub(crate) struct ChatComposer {
     // When true, disables paste-burst logic and inserts characters immediately.
     disable_paste_burst: bool,
     custom_prompts: Vec<CustomPrompt>,
+    mcp_prompts: Vec<McpPromptEntry>,
+    mcp_resources: Vec<ResourceMention>,
 }
 
 /// Popup state – at most one can be visible at any time.
@@ -111,7 +137,10 @@ impl ChatComposer {
         let use_shift_enter_hint = enhanced_keys_supported;
 
         let mut this = Self {
-            textarea: TextArea::new(),
+            textarea: match keymap().editing_mode {
+                EditingMode::Emacs => TextArea::new(),
+                EditingMode::Vi => TextArea::new_vi(),
+            },
             textarea_state: RefCell::new(TextAreaState::default()),
             active_popup: ActivePopup::None,
             app_event_tx,
@@ -130,6 +159,8 @@ impl ChatComposer {
             paste_burst: PasteBurst::default(),
             disable_paste_burst: false,
             custom_prompts: Vec::new(),
+            mcp_prompts: Vec::new(),
+            mcp_resources: Vec::new(),
         };
         // Apply configuration via the setter to keep side-effects centralized.
         this.set_disable_paste_burst(disable_paste_burst);
@@ -168,6 +199,12 @@ impl ChatComposer {
         self.textarea.is_empty()
     }
 
+    /// Returns true while vi editing is in insert mode, where Esc switches
+    /// to normal mode instead of being handled by the app.
+    pub(crate) fn is_vi_insert_mode(&self) -> bool {
+        self.textarea.is_vi_insert_mode()
+    }
+
     /// Update the cached *context-left* percentage and refresh the placeholder
     /// text. The UI relies on the placeholder to convey the remaining
     /// context when the composer is empty.
@@ -399,6 +436,16 @@ impl ChatComposer {
                                 }
                             }
                         }
+                        CommandItem::McpPrompt(idx) => {
+                            if let Some(entry) = popup.mcp_prompt(idx) {
+                                let name = &entry.command;
+                                let starts_with_cmd =
+                                    first_line.trim_start().starts_with(&format!("/{name}"));
+                                if !starts_with_cmd {
+                                    self.textarea.set_text(&format!("/{name} "));
+                                }
+                            }
+                        }
                     }
                     // After completing the command, move cursor to the end.
                     if !self.textarea.text().is_empty() {
@@ -414,13 +461,18 @@ impl ChatComposer {
                 ..
             } => {
                 if let Some(sel) = popup.selected_item() {
+                    // Anything typed after the command name is passed to a
+                    // custom prompt as its arguments.
+                    let arguments = prompt_arguments(self.textarea.text());
                     // Clear textarea so no residual text remains.
                     self.textarea.set_text("");
                     // Capture any needed data from popup before clearing it.
-                    let prompt_content = match sel {
-                        CommandItem::UserPrompt(idx) => {
-                            popup.prompt_content(idx).map(|s| s.to_string())
-                        }
+                    let prompt = match sel {
+                        CommandItem::UserPrompt(idx) => popup.prompt(idx).cloned(),
+                        _ => None,
+                    };
+                    let mcp_prompt = match sel {
+                        CommandItem::McpPrompt(idx) => popup.mcp_prompt(idx).cloned(),
                         _ => None,
                     };
                     // Hide popup since an action has been dispatched.
@@ -431,10 +483,35 @@ impl ChatComposer {
                             return (InputResult::Command(cmd), true);
                         }
                         CommandItem::UserPrompt(_) => {
-                            if let Some(contents) = prompt_content {
-                                return (InputResult::Submitted(contents), true);
+                            let Some(prompt) = prompt else {
+                                return (InputResult::None, true);
+                            };
+                            let text = expand_prompt_arguments(&prompt.content, &arguments);
+                            if prompt.model.is_none() && prompt.effort.is_none() {
+                                return (InputResult::Submitted(text), true);
                             }
-                            return (InputResult::None, true);
+                            return (
+                                InputResult::SubmittedPrompt {
+                                    text,
+                                    model: prompt.model,
+                                    effort: prompt.effort,
+                                },
+                                true,
+                            );
+                        }
+                        CommandItem::McpPrompt(_) => {
+                            let Some(entry) = mcp_prompt else {
+                                return (InputResult::None, true);
+                            };
+                            let arguments = mcp_prompt_arguments(&entry.prompt, &arguments);
+                            return (
+                                InputResult::McpPrompt {
+                                    server: entry.server,
+                                    name: entry.prompt.name,
+                                    arguments,
+                                },
+                                true,
+                            );
                         }
                     }
                 }
@@ -508,12 +585,19 @@ impl ChatComposer {
                 modifiers: KeyModifiers::NONE,
                 ..
             } => {
-                let Some(sel) = popup.selected_match() else {
-                    self.active_popup = ActivePopup::None;
-                    return (InputResult::None, true);
+                let sel_path = match popup.selected_match() {
+                    Some(FileSearchSelection::File(path)) => path,
+                    Some(FileSearchSelection::Resource(mention)) => {
+                        // Keep the `@` so the resource is attached on submit.
+                        self.insert_selected_path(&format!("@{mention}"));
+                        self.active_popup = ActivePopup::None;
+                        return (InputResult::None, true);
+                    }
+                    None => {
+                        self.active_popup = ActivePopup::None;
+                        return (InputResult::None, true);
+                    }
                 };
-
-                let sel_path = sel.to_string();
                 // If selected path looks like an image (png/jpeg), attach as image instead of inserting text.
                 let is_image = Self::is_image_path(&sel_path);
                 if is_image {
@@ -730,8 +814,8 @@ impl ChatComposer {
                 kind: KeyEventKind::Press,
                 ..
             } if self.is_empty() => {
-                self.app_event_tx.send(AppEvent::ExitRequest);
-                (InputResult::None, true)
+                // Quit like `/quit` so a worktree is resolved before exiting.
+                (InputResult::Command(SlashCommand::Quit), true)
             }
             // -------------------------------------------------------------
             // History navigation (Up / Down) – only when the composer is not
@@ -759,11 +843,7 @@ impl ChatComposer {
                 }
                 self.handle_input_basic(key_event)
             }
-            KeyEvent {
-                code: KeyCode::Enter,
-                modifiers: KeyModifiers::NONE,
-                ..
-            } => {
+            _ if keymap().submit.matches(&key_event) => {
                 // If we're in a paste-like burst capture, treat Enter as part of the burst
                 // and accumulate it rather than submitting or inserting immediately.
                 // Do not treat Enter as paste inside a slash-command context.
@@ -883,7 +963,8 @@ impl ChatComposer {
         {
             let has_ctrl_or_alt =
                 modifiers.contains(KeyModifiers::CONTROL) || modifiers.contains(KeyModifiers::ALT);
-            if !has_ctrl_or_alt {
+            // Keys typed in vi normal mode are commands, never pasted text.
+            if !has_ctrl_or_alt && !self.textarea.is_vi_normal_mode() {
                 // Non-ASCII characters (e.g., from IMEs) can arrive in quick bursts and be
                 // misclassified by paste heuristics. Flush any active burst buffer and insert
                 // non-ASCII characters directly.
@@ -1156,6 +1237,7 @@ impl ChatComposer {
             _ => {
                 if input_starts_with_slash {
                     let mut command_popup = CommandPopup::new(self.custom_prompts.clone());
+                    command_popup.set_mcp_prompts(self.mcp_prompts.clone());
                     command_popup.on_composer_text_change(first_line.to_string());
                     self.active_popup = ActivePopup::Command(command_popup);
                 }
@@ -1170,6 +1252,31 @@ impl ChatComposer {
         }
     }
 
+    pub(crate) fn set_mcp_prompts(&mut self, prompts: HashMap<String, Vec<McpPrompt>>) {
+        self.mcp_prompts = mcp_prompt_entries(prompts);
+        if let ActivePopup::Command(popup) = &mut self.active_popup {
+            popup.set_mcp_prompts(self.mcp_prompts.clone());
+        }
+    }
+
+    /// Make the given MCP resources available as `@server:uri` completions.
+    pub(crate) fn set_mcp_resources(&mut self, resources: HashMap<String, Vec<McpResource>>) {
+        let mut mentions: Vec<ResourceMention> = resources
+            .into_iter()
+            .flat_map(|(server, resources)| {
+                resources.into_iter().map(move |resource| ResourceMention {
+                    mention: format!("{server}:{}", resource.uri),
+                    label: resource.title.unwrap_or(resource.name),
+                })
+            })
+            .collect();
+        mentions.sort_by(|a, b| a.mention.cmp(&b.mention));
+        self.mcp_resources = mentions;
+        if let ActivePopup::File(popup) = &mut self.active_popup {
+            popup.set_resources(self.mcp_resources.clone());
+        }
+    }
+
     /// Synchronize `self.file_search_popup` with the current text in the textarea.
     /// Note this is only called when self.active_popup is NOT Command.
     fn sync_file_search_popup(&mut self) {
@@ -1203,6 +1310,7 @@ impl ChatComposer {
             }
             _ => {
                 let mut popup = FileSearchPopup::new();
+                popup.set_resources(self.mcp_resources.clone());
                 if query.is_empty() {
                     popup.set_empty_prompt();
                 } else {
@@ -1229,6 +1337,17 @@ impl ChatComposer {
     }
 }
 
+/// Returns the text that follows the `/command` token, trimmed.
+fn prompt_arguments(text: &str) -> String {
+    let Some(rest) = text.trim_start().strip_prefix('/') else {
+        return String::new();
+    };
+    match rest.find(char::is_whitespace) {
+        Some(idx) => rest[idx..].trim().to_string(),
+        None => String::new(),
+    }
+}
+
 impl WidgetRef for ChatComposer {
     fn render_ref(&self, area: Rect, buf: &mut Buffer) {
         let (popup_constraint, hint_spacing) = match &self.active_popup {
@@ -1277,21 +1396,28 @@ impl WidgetRef for ChatComposer {
                     } else {
                         key_hint::ctrl('J')
                     };
-                    vec![
-                        key_hint::plain('⏎'),
-                        " send   ".into(),
-                        newline_hint_key,
-                        " newline   ".into(),
-                        key_hint::ctrl('T'),
-                        " transcript   ".into(),
-                        key_hint::ctrl('C'),
-                        " quit".into(),
-                    ]
+                    let keymap = keymap();
+                    let mut hint = Vec::new();
+                    if self.textarea.is_vi_normal_mode() {
+                        hint.extend(["NORMAL".accent(), "   ".into()]);
+                    }
+                    if let Some(key) = keymap.submit.hint() {
+                        hint.extend([key, " send   ".into()]);
+                    }
+                    hint.extend([newline_hint_key, " newline   ".into()]);
+                    if let Some(key) = keymap.transcript.hint() {
+                        hint.extend([key, " transcript   ".into()]);
+                    }
+                    hint.extend([key_hint::ctrl('C'), " quit".into()]);
+                    hint
                 };
 
-                if !self.ctrl_c_quit_hint && self.esc_backtrack_hint {
+                if !self.ctrl_c_quit_hint
+                    && self.esc_backtrack_hint
+                    && let Some(key) = keymap().backtrack.hint()
+                {
                     hint.push("   ".into());
-                    hint.push(key_hint::plain("Esc"));
+                    hint.push(key);
                     hint.push(" edit prev".into());
                 }
 
@@ -1332,7 +1458,7 @@ impl WidgetRef for ChatComposer {
             }
         }
         let border_style = if self.has_focus {
-            Style::default().fg(Color::Cyan)
+            Style::default().accent()
         } else {
             Style::default().add_modifier(Modifier::DIM)
         };
@@ -1805,7 +1931,7 @@ mod tests {
                 Some(CommandItem::Builtin(cmd)) => {
                     assert_eq!(cmd.command(), "model")
                 }
-                Some(CommandItem::UserPrompt(_)) => {
+                Some(CommandItem::UserPrompt(_) | CommandItem::McpPrompt(_)) => {
                     panic!("unexpected prompt selected for '/mo'")
                 }
                 None => panic!("no selected command for '/mo'"),
@@ -1855,9 +1981,12 @@ mod tests {
             InputResult::Command(cmd) => {
                 assert_eq!(cmd.command(), "init");
             }
-            InputResult::Submitted(text) => {
+            InputResult::Submitted(text) | InputResult::SubmittedPrompt { text, .. } => {
                 panic!("expected command dispatch, but composer submitted literal text: {text}")
             }
+            InputResult::McpPrompt { name, .. } => {
+                panic!("expected command dispatch, but composer requested MCP prompt: {name}")
+            }
             InputResult::None => panic!("expected Command result for '/init'"),
         }
         assert!(composer.textarea.is_empty(), "composer should be cleared");
@@ -1913,9 +2042,12 @@ mod tests {
             InputResult::Command(cmd) => {
                 assert_eq!(cmd.command(), "mention");
             }
-            InputResult::Submitted(text) => {
+            InputResult::Submitted(text) | InputResult::SubmittedPrompt { text, .. } => {
                 panic!("expected command dispatch, but composer submitted literal text: {text}")
             }
+            InputResult::McpPrompt { name, .. } => {
+                panic!("expected command dispatch, but composer requested MCP prompt: {name}")
+            }
             InputResult::None => panic!("expected Command result for '/mention'"),
         }
         assert!(composer.textarea.is_empty(), "composer should be cleared");
@@ -2328,6 +2460,7 @@ mod tests {
             name: "my-prompt".to_string(),
             path: "/tmp/my-prompt.md".to_string().into(),
             content: prompt_text.to_string(),
+            ..Default::default()
         }]);
 
         type_chars_humanlike(
@@ -2341,6 +2474,102 @@ mod tests {
         assert_eq!(InputResult::Submitted(prompt_text.to_string()), result);
     }
 
+    #[test]
+    fn custom_prompt_substitutes_arguments_and_requests_model() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+        composer.set_custom_prompts(vec![CustomPrompt {
+            name: "fix".to_string(),
+            path: "/tmp/fix.md".to_string().into(),
+            content: "Fix $1 in $2. Context: $ARGUMENTS".to_string(),
+            model: Some("gpt-5".to_string()),
+            effort: Some(ReasoningEffort::High),
+            ..Default::default()
+        }]);
+
+        type_chars_humanlike(&mut composer, &['/', 'f', 'i', 'x']);
+        composer.handle_paste(r#" "login bug" auth.rs"#.to_string());
+
+        let (result, _needs_redraw) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert_eq!(
+            InputResult::SubmittedPrompt {
+                text: r#"Fix login bug in auth.rs. Context: "login bug" auth.rs"#.to_string(),
+                model: Some("gpt-5".to_string()),
+                effort: Some(ReasoningEffort::High),
+            },
+            result
+        );
+    }
+
+    #[test]
+    fn mcp_prompt_requests_rendering_with_named_arguments() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+        let prompt: McpPrompt = serde_json::from_value(serde_json::json!({
+            "name": "notes",
+            "arguments": [{ "name": "version", "required": true }],
+        }))
+        .unwrap();
+        composer.set_mcp_prompts(HashMap::from([("docs".to_string(), vec![prompt])]));
+
+        type_chars_humanlike(&mut composer, &['/', 'd', 'o', 'c', 's', ':', 'n']);
+        composer.handle_paste(" 1.2.0".to_string());
+
+        let (result, _needs_redraw) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert_eq!(
+            InputResult::McpPrompt {
+                server: "docs".to_string(),
+                name: "notes".to_string(),
+                arguments: HashMap::from([("version".to_string(), "1.2.0".to_string())]),
+            },
+            result
+        );
+        assert!(composer.textarea.is_empty());
+    }
+
+    #[test]
+    fn selecting_mcp_resource_inserts_mention() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+        let resource: McpResource = serde_json::from_value(serde_json::json!({
+            "name": "api",
+            "title": "API reference",
+            "uri": "docs://api",
+        }))
+        .unwrap();
+        composer.set_mcp_resources(HashMap::from([("docs".to_string(), vec![resource])]));
+
+        composer.insert_str("read @refer");
+        composer.handle_key_event(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE));
+
+        assert_eq!(composer.textarea.text(), "read @docs:docs://api ");
+    }
+
     #[test]
     fn burst_paste_fast_small_buffers_and_flushes_on_stop() {
         use crossterm::event::KeyCode;
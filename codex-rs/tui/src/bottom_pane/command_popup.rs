@@ -10,20 +10,51 @@ use crate::slash_command::SlashCommand;
 use crate::slash_command::built_in_slash_commands;
 use codex_common::fuzzy_match::fuzzy_match;
 use codex_protocol::custom_prompts::CustomPrompt;
+use mcp_types::Prompt as McpPrompt;
+use std::collections::HashMap;
 use std::collections::HashSet;
 
-/// A selectable item in the popup: either a built-in command or a user prompt.
+/// A selectable item in the popup: a built-in command, a user prompt or a
+/// prompt offered by an MCP server.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) enum CommandItem {
     Builtin(SlashCommand),
     // Index into `prompts`
     UserPrompt(usize),
+    // Index into `mcp_prompts`
+    McpPrompt(usize),
+}
+
+/// A prompt offered by an MCP server, invoked as `/<server>:<prompt>`.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct McpPromptEntry {
+    pub(crate) server: String,
+    pub(crate) prompt: McpPrompt,
+    /// `<server>:<prompt>`, the name typed after the slash.
+    pub(crate) command: String,
+}
+
+/// Flatten the prompts of each MCP server into entries sorted by command.
+pub(crate) fn mcp_prompt_entries(prompts: HashMap<String, Vec<McpPrompt>>) -> Vec<McpPromptEntry> {
+    let mut entries: Vec<McpPromptEntry> = prompts
+        .into_iter()
+        .flat_map(|(server, prompts)| {
+            prompts.into_iter().map(move |prompt| McpPromptEntry {
+                command: format!("{server}:{}", prompt.name),
+                server: server.clone(),
+                prompt,
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| a.command.cmp(&b.command));
+    entries
 }
 
 pub(crate) struct CommandPopup {
     command_filter: String,
     builtins: Vec<(&'static str, SlashCommand)>,
     prompts: Vec<CustomPrompt>,
+    mcp_prompts: Vec<McpPromptEntry>,
     state: ScrollState,
 }
 
@@ -38,6 +69,7 @@ impl CommandPopup {
             command_filter: String::new(),
             builtins,
             prompts,
+            mcp_prompts: Vec::new(),
             state: ScrollState::new(),
         }
     }
@@ -53,12 +85,20 @@ impl CommandPopup {
         self.prompts = prompts;
     }
 
+    pub(crate) fn set_mcp_prompts(&mut self, mcp_prompts: Vec<McpPromptEntry>) {
+        self.mcp_prompts = mcp_prompts;
+    }
+
     pub(crate) fn prompt_name(&self, idx: usize) -> Option<&str> {
         self.prompts.get(idx).map(|p| p.name.as_str())
     }
 
-    pub(crate) fn prompt_content(&self, idx: usize) -> Option<&str> {
-        self.prompts.get(idx).map(|p| p.content.as_str())
+    pub(crate) fn prompt(&self, idx: usize) -> Option<&CustomPrompt> {
+        self.prompts.get(idx)
+    }
+
+    pub(crate) fn mcp_prompt(&self, idx: usize) -> Option<&McpPromptEntry> {
+        self.mcp_prompts.get(idx)
     }
 
     /// Update the filter string based on the current composer text. The text
@@ -113,6 +153,9 @@ impl CommandPopup {
             for idx in 0..self.prompts.len() {
                 out.push((CommandItem::UserPrompt(idx), None, 0));
             }
+            for idx in 0..self.mcp_prompts.len() {
+                out.push((CommandItem::McpPrompt(idx), None, 0));
+            }
             return out;
         }
 
@@ -126,23 +169,27 @@ impl CommandPopup {
                 out.push((CommandItem::UserPrompt(idx), Some(indices), score));
             }
         }
+        for (idx, p) in self.mcp_prompts.iter().enumerate() {
+            if let Some((indices, score)) = fuzzy_match(&p.command, filter) {
+                out.push((CommandItem::McpPrompt(idx), Some(indices), score));
+            }
+        }
         // When filtering, sort by ascending score and then by name for stability.
         out.sort_by(|a, b| {
-            a.2.cmp(&b.2).then_with(|| {
-                let an = match a.0 {
-                    CommandItem::Builtin(c) => c.command(),
-                    CommandItem::UserPrompt(i) => &self.prompts[i].name,
-                };
-                let bn = match b.0 {
-                    CommandItem::Builtin(c) => c.command(),
-                    CommandItem::UserPrompt(i) => &self.prompts[i].name,
-                };
-                an.cmp(bn)
-            })
+            a.2.cmp(&b.2)
+                .then_with(|| self.item_name(a.0).cmp(self.item_name(b.0)))
         });
         out
     }
 
+    fn item_name(&self, item: CommandItem) -> &str {
+        match item {
+            CommandItem::Builtin(c) => c.command(),
+            CommandItem::UserPrompt(i) => &self.prompts[i].name,
+            CommandItem::McpPrompt(i) => &self.mcp_prompts[i].command,
+        }
+    }
+
     fn filtered_items(&self) -> Vec<CommandItem> {
         self.filtered().into_iter().map(|(c, _, _)| c).collect()
     }
@@ -190,7 +237,13 @@ impl WidgetRef for CommandPopup {
                         name: format!("/{}", self.prompts[i].name),
                         match_indices: indices.map(|v| v.into_iter().map(|i| i + 1).collect()),
                         is_current: false,
-                        description: Some("send saved prompt".to_string()),
+                        description: Some(prompt_description(&self.prompts[i])),
+                    },
+                    CommandItem::McpPrompt(i) => GenericDisplayRow {
+                        name: format!("/{}", self.mcp_prompts[i].command),
+                        match_indices: indices.map(|v| v.into_iter().map(|i| i + 1).collect()),
+                        is_current: false,
+                        description: Some(mcp_prompt_description(&self.mcp_prompts[i].prompt)),
                     },
                 })
                 .collect()
@@ -207,6 +260,41 @@ impl WidgetRef for CommandPopup {
     }
 }
 
+fn prompt_description(prompt: &CustomPrompt) -> String {
+    let description = prompt.description.as_deref().unwrap_or("send saved prompt");
+    match &prompt.argument_hint {
+        Some(hint) => format!("{description} {hint}"),
+        None => description.to_string(),
+    }
+}
+
+/// The prompt's description followed by its arguments, e.g.
+/// `Draft release notes <version> [focus]`.
+fn mcp_prompt_description(prompt: &McpPrompt) -> String {
+    let description = prompt
+        .description
+        .as_deref()
+        .or(prompt.title.as_deref())
+        .unwrap_or("send MCP prompt");
+    let arguments: Vec<String> = prompt
+        .arguments
+        .iter()
+        .flatten()
+        .map(|argument| {
+            if argument.required == Some(true) {
+                format!("<{}>", argument.name)
+            } else {
+                format!("[{}]", argument.name)
+            }
+        })
+        .collect();
+    if arguments.is_empty() {
+        description.to_string()
+    } else {
+        format!("{description} {}", arguments.join(" "))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -223,7 +311,7 @@ mod tests {
         let matches = popup.filtered_items();
         let has_init = matches.iter().any(|item| match item {
             CommandItem::Builtin(cmd) => cmd.command() == "init",
-            CommandItem::UserPrompt(_) => false,
+            CommandItem::UserPrompt(_) | CommandItem::McpPrompt(_) => false,
         });
         assert!(
             has_init,
@@ -241,7 +329,9 @@ mod tests {
         let selected = popup.selected_item();
         match selected {
             Some(CommandItem::Builtin(cmd)) => assert_eq!(cmd.command(), "init"),
-            Some(CommandItem::UserPrompt(_)) => panic!("unexpected prompt selected for '/init'"),
+            Some(CommandItem::UserPrompt(_) | CommandItem::McpPrompt(_)) => {
+                panic!("unexpected prompt selected for '/init'")
+            }
             None => panic!("expected a selected command for exact match"),
         }
     }
@@ -253,7 +343,7 @@ mod tests {
         let matches = popup.filtered_items();
         match matches.first() {
             Some(CommandItem::Builtin(cmd)) => assert_eq!(cmd.command(), "model"),
-            Some(CommandItem::UserPrompt(_)) => {
+            Some(CommandItem::UserPrompt(_) | CommandItem::McpPrompt(_)) => {
                 panic!("unexpected prompt ranked before '/model' for '/mo'")
             }
             None => panic!("expected at least one match for '/mo'"),
@@ -267,11 +357,13 @@ mod tests {
                 name: "foo".to_string(),
                 path: "/tmp/foo.md".to_string().into(),
                 content: "hello from foo".to_string(),
+                ..Default::default()
             },
             CustomPrompt {
                 name: "bar".to_string(),
                 path: "/tmp/bar.md".to_string().into(),
                 content: "hello from bar".to_string(),
+                ..Default::default()
             },
         ];
         let popup = CommandPopup::new(prompts);
@@ -294,6 +386,7 @@ mod tests {
             name: "init".to_string(),
             path: "/tmp/init.md".to_string().into(),
             content: "should be ignored".to_string(),
+            ..Default::default()
         }]);
         let items = popup.filtered_items();
         let has_collision_prompt = items.into_iter().any(|it| match it {
@@ -305,4 +398,46 @@ mod tests {
             "prompt with builtin name should be ignored"
         );
     }
+
+    #[test]
+    fn mcp_prompts_are_listed_by_server_and_name() {
+        let prompt = |name: &str| McpPrompt {
+            arguments: None,
+            description: None,
+            name: name.to_string(),
+            title: None,
+        };
+        let mut popup = CommandPopup::new(Vec::new());
+        popup.set_mcp_prompts(mcp_prompt_entries(HashMap::from([
+            (
+                "docs".to_string(),
+                vec![prompt("release-notes"), prompt("api-summary")],
+            ),
+            ("ops".to_string(), vec![prompt("incident")]),
+        ])));
+
+        popup.on_composer_text_change("/docs:rel".to_string());
+        match popup.selected_item() {
+            Some(CommandItem::McpPrompt(i)) => {
+                let entry = popup.mcp_prompt(i).unwrap();
+                assert_eq!(entry.server, "docs");
+                assert_eq!(entry.prompt.name, "release-notes");
+            }
+            other => panic!("expected an MCP prompt, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn mcp_prompt_description_lists_arguments() {
+        let prompt: McpPrompt = serde_json::from_value(serde_json::json!({
+            "name": "release-notes",
+            "description": "Draft release notes",
+            "arguments": [{ "name": "version", "required": true }, { "name": "focus" }],
+        }))
+        .unwrap();
+        assert_eq!(
+            mcp_prompt_description(&prompt),
+            "Draft release notes <version> [focus]"
+        );
+    }
 }
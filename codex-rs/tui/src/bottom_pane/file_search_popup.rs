@@ -8,7 +8,26 @@ use super::scroll_state::ScrollState;
 use super::selection_popup_common::GenericDisplayRow;
 use super::selection_popup_common::render_rows;
 
-/// Visual state for the file-search popup.
+/// An MCP resource that can be mentioned as `@<server>:<uri>`.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ResourceMention {
+    /// `<server>:<uri>`, inserted after the `@`.
+    pub(crate) mention: String,
+    /// Title or name of the resource.
+    pub(crate) label: String,
+}
+
+/// The item selected in the popup.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum FileSearchSelection {
+    /// Path relative to the search dir.
+    File(String),
+    /// `<server>:<uri>` of an MCP resource.
+    Resource(String),
+}
+
+/// Visual state for the file-search popup. MCP resources matching the query
+/// are listed above the file matches.
 pub(crate) struct FileSearchPopup {
     /// Query corresponding to the `matches` currently shown.
     display_query: String,
@@ -19,6 +38,10 @@ pub(crate) struct FileSearchPopup {
     waiting: bool,
     /// Cached matches; paths relative to the search dir.
     matches: Vec<FileMatch>,
+    /// All MCP resources that can be mentioned.
+    resources: Vec<ResourceMention>,
+    /// Indices into `resources` of those matching `pending_query`.
+    resource_matches: Vec<usize>,
     /// Shared selection/scroll state.
     state: ScrollState,
 }
@@ -30,10 +53,18 @@ impl FileSearchPopup {
             pending_query: String::new(),
             waiting: true,
             matches: Vec::new(),
+            resources: Vec::new(),
+            resource_matches: Vec::new(),
             state: ScrollState::new(),
         }
     }
 
+    pub(crate) fn set_resources(&mut self, resources: Vec<ResourceMention>) {
+        self.resources = resources;
+        self.resource_matches = self.matching_resources(&self.pending_query);
+        self.state.clamp_selection(self.len());
+    }
+
     /// Update the query and reset state to *waiting*.
     pub(crate) fn set_query(&mut self, query: &str) {
         if query == self.pending_query {
@@ -45,6 +76,7 @@ impl FileSearchPopup {
 
         self.pending_query.clear();
         self.pending_query.push_str(query);
+        self.resource_matches = self.matching_resources(query);
 
         self.waiting = true; // waiting for new results
 
@@ -52,6 +84,8 @@ impl FileSearchPopup {
             self.matches.clear();
             self.state.reset();
         }
+        // Resource matches are available immediately.
+        self.state.clamp_selection(self.len());
     }
 
     /// Put the popup into an "idle" state used for an empty query (just "@").
@@ -61,6 +95,7 @@ impl FileSearchPopup {
         self.pending_query.clear();
         self.waiting = false;
         self.matches.clear();
+        self.resource_matches.clear();
         // Reset selection/scroll state when showing the empty prompt.
         self.state.reset();
     }
@@ -75,30 +110,57 @@ impl FileSearchPopup {
         self.display_query = query.to_string();
         self.matches = matches;
         self.waiting = false;
-        let len = self.matches.len();
+        let len = self.len();
         self.state.clamp_selection(len);
         self.state.ensure_visible(len, len.min(MAX_POPUP_ROWS));
     }
 
     /// Move selection cursor up.
     pub(crate) fn move_up(&mut self) {
-        let len = self.matches.len();
+        let len = self.len();
         self.state.move_up_wrap(len);
         self.state.ensure_visible(len, len.min(MAX_POPUP_ROWS));
     }
 
     /// Move selection cursor down.
     pub(crate) fn move_down(&mut self) {
-        let len = self.matches.len();
+        let len = self.len();
         self.state.move_down_wrap(len);
         self.state.ensure_visible(len, len.min(MAX_POPUP_ROWS));
     }
 
-    pub(crate) fn selected_match(&self) -> Option<&str> {
-        self.state
-            .selected_idx
-            .and_then(|idx| self.matches.get(idx))
-            .map(|file_match| file_match.path.as_str())
+    pub(crate) fn selected_match(&self) -> Option<FileSearchSelection> {
+        let idx = self.state.selected_idx?;
+        match self.resource_matches.get(idx) {
+            Some(&resource_idx) => Some(FileSearchSelection::Resource(
+                self.resources[resource_idx].mention.clone(),
+            )),
+            None => self
+                .matches
+                .get(idx - self.resource_matches.len())
+                .map(|file_match| FileSearchSelection::File(file_match.path.clone())),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.resource_matches.len() + self.matches.len()
+    }
+
+    /// Resources whose mention or label contains `query`, ignoring case.
+    fn matching_resources(&self, query: &str) -> Vec<usize> {
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let query = query.to_lowercase();
+        self.resources
+            .iter()
+            .enumerate()
+            .filter(|(_, resource)| {
+                resource.mention.to_lowercase().contains(&query)
+                    || resource.label.to_lowercase().contains(&query)
+            })
+            .map(|(idx, _)| idx)
+            .collect()
     }
 
     pub(crate) fn calculate_required_height(&self) -> u16 {
@@ -108,29 +170,32 @@ impl FileSearchPopup {
         // up to MAX_RESULTS regardless of the waiting flag so the list
         // remains stable while a newer search is in-flight.
 
-        self.matches.len().clamp(1, MAX_POPUP_ROWS) as u16
+        self.len().clamp(1, MAX_POPUP_ROWS) as u16
     }
 }
 
 impl WidgetRef for &FileSearchPopup {
     fn render_ref(&self, area: Rect, buf: &mut Buffer) {
         // Convert matches to GenericDisplayRow, translating indices to usize at the UI boundary.
-        let rows_all: Vec<GenericDisplayRow> = if self.matches.is_empty() {
-            Vec::new()
-        } else {
-            self.matches
-                .iter()
-                .map(|m| GenericDisplayRow {
-                    name: m.path.clone(),
-                    match_indices: m
-                        .indices
-                        .as_ref()
-                        .map(|v| v.iter().map(|&i| i as usize).collect()),
-                    is_current: false,
-                    description: None,
-                })
-                .collect()
-        };
+        let resource_rows = self.resource_matches.iter().map(|&idx| {
+            let resource = &self.resources[idx];
+            GenericDisplayRow {
+                name: resource.mention.clone(),
+                match_indices: None,
+                is_current: false,
+                description: Some(resource.label.clone()),
+            }
+        });
+        let file_rows = self.matches.iter().map(|m| GenericDisplayRow {
+            name: m.path.clone(),
+            match_indices: m
+                .indices
+                .as_ref()
+                .map(|v| v.iter().map(|&i| i as usize).collect()),
+            is_current: false,
+            description: None,
+        });
+        let rows_all: Vec<GenericDisplayRow> = resource_rows.chain(file_rows).collect();
 
         let empty_message = if self.waiting {
             "loading..."
@@ -149,3 +214,46 @@ impl WidgetRef for &FileSearchPopup {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn resources_matching_query_are_listed_before_files() {
+        let mut popup = FileSearchPopup::new();
+        popup.set_resources(vec![
+            ResourceMention {
+                mention: "docs:docs://api/v1".to_string(),
+                label: "API reference".to_string(),
+            },
+            ResourceMention {
+                mention: "docs:docs://style".to_string(),
+                label: "Style guide".to_string(),
+            },
+        ]);
+        popup.set_query("api");
+        popup.set_matches(
+            "api",
+            vec![FileMatch {
+                score: 1,
+                path: "src/api.rs".to_string(),
+                indices: None,
+            }],
+        );
+
+        assert_eq!(
+            popup.selected_match(),
+            Some(FileSearchSelection::Resource(
+                "docs:docs://api/v1".to_string()
+            ))
+        );
+        popup.move_down();
+        assert_eq!(
+            popup.selected_match(),
+            Some(FileSearchSelection::File("src/api.rs".to_string()))
+        );
+        assert_eq!(popup.calculate_required_height(), 2);
+    }
+}
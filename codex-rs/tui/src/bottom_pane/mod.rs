@@ -1,4 +1,5 @@
 //! Bottom pane: shows the ChatComposer or a BottomPaneView, if one is active.
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 use crate::app_event_sender::AppEventSender;
@@ -37,6 +38,8 @@ pub(crate) enum CancellationEvent {
 pub(crate) use chat_composer::ChatComposer;
 pub(crate) use chat_composer::InputResult;
 use codex_protocol::custom_prompts::CustomPrompt;
+use mcp_types::Prompt as McpPrompt;
+use mcp_types::Resource as McpResource;
 
 use crate::status_indicator_widget::StatusIndicatorWidget;
 use approval_modal_view::ApprovalModalView;
@@ -280,6 +283,11 @@ impl BottomPane {
         self.ctrl_c_quit_hint
     }
 
+    #[cfg(test)]
+    pub(crate) fn queued_user_messages(&self) -> &[String] {
+        &self.queued_user_messages
+    }
+
     pub(crate) fn show_esc_backtrack_hint(&mut self) {
         self.esc_backtrack_hint = true;
         self.composer.set_esc_backtrack_hint(true);
@@ -351,6 +359,16 @@ impl BottomPane {
         self.request_redraw();
     }
 
+    pub(crate) fn set_mcp_prompts(&mut self, prompts: HashMap<String, Vec<McpPrompt>>) {
+        self.composer.set_mcp_prompts(prompts);
+        self.request_redraw();
+    }
+
+    pub(crate) fn set_mcp_resources(&mut self, resources: HashMap<String, Vec<McpResource>>) {
+        self.composer.set_mcp_resources(resources);
+        self.request_redraw();
+    }
+
     pub(crate) fn composer_is_empty(&self) -> bool {
         self.composer.is_empty()
     }
@@ -360,10 +378,14 @@ impl BottomPane {
     }
 
     /// Return true when the pane is in the regular composer state without any
-    /// overlays or popups and not running a task. This is the safe context to
-    /// use Esc-Esc for backtracking from the main view.
+    /// overlays or popups and not running a task, and Esc does not switch
+    /// the composer from vi insert mode to normal mode. This is the safe
+    /// context to use Esc-Esc for backtracking from the main view.
     pub(crate) fn is_normal_backtrack_mode(&self) -> bool {
-        !self.is_task_running && self.active_view.is_none() && !self.composer.popup_active()
+        !self.is_task_running
+            && self.active_view.is_none()
+            && !self.composer.popup_active()
+            && !self.composer.is_vi_insert_mode()
     }
 
     /// Update the *context-window remaining* indicator in the composer. This
@@ -1,9 +1,10 @@
+use crate::keymap::keymap;
+use crate::theme::theme;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyModifiers;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
-use ratatui::style::Color;
 use ratatui::style::Style;
 use ratatui::widgets::StatefulWidgetRef;
 use ratatui::widgets::WidgetRef;
@@ -26,6 +27,21 @@ pub(crate) struct TextArea {
     wrap_cache: RefCell<Option<WrapCache>>,
     preferred_col: Option<usize>,
     elements: Vec<TextElement>,
+    /// Set when editing with vi-style modes instead of Emacs-style keys.
+    vi: Option<ViState>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ViMode {
+    Insert,
+    Normal,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ViState {
+    mode: ViMode,
+    /// First key of a two-key command such as `dd` or `gg`.
+    pending: Option<char>,
 }
 
 #[derive(Debug, Clone)]
@@ -48,6 +64,33 @@ impl TextArea {
             wrap_cache: RefCell::new(None),
             preferred_col: None,
             elements: Vec::new(),
+            vi: None,
+        }
+    }
+
+    /// Creates a text area edited with vi-style keys, starting in insert mode.
+    pub fn new_vi() -> Self {
+        Self {
+            vi: Some(ViState {
+                mode: ViMode::Insert,
+                pending: None,
+            }),
+            ..Self::new()
+        }
+    }
+
+    pub fn is_vi_normal_mode(&self) -> bool {
+        self.vi.is_some_and(|vi| vi.mode == ViMode::Normal)
+    }
+
+    pub fn is_vi_insert_mode(&self) -> bool {
+        self.vi.is_some_and(|vi| vi.mode == ViMode::Insert)
+    }
+
+    fn set_vi_mode(&mut self, mode: ViMode) {
+        if let Some(vi) = self.vi.as_mut() {
+            vi.mode = mode;
+            vi.pending = None;
         }
     }
 
@@ -57,6 +100,10 @@ impl TextArea {
         self.wrap_cache.replace(None);
         self.preferred_col = None;
         self.elements.clear();
+        // Clearing the text (e.g. after a submit) starts over in insert mode.
+        if text.is_empty() {
+            self.set_vi_mode(ViMode::Insert);
+        }
     }
 
     pub fn text(&self) -> &str {
@@ -203,6 +250,29 @@ impl TextArea {
     }
 
     pub fn input(&mut self, event: KeyEvent) {
+        if let Some(vi) = self.vi {
+            let with_ctrl_or_alt = event
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+            match vi.mode {
+                // Ctrl and Alt chords keep their Emacs meaning in both modes.
+                ViMode::Normal if !with_ctrl_or_alt => {
+                    self.vi_normal_input(event, vi.pending);
+                    return;
+                }
+                ViMode::Insert if event.code == KeyCode::Esc => {
+                    self.set_vi_mode(ViMode::Normal);
+                    if self.cursor_pos > self.beginning_of_current_line() {
+                        self.move_cursor_left();
+                    }
+                    return;
+                }
+                _ => {}
+            }
+        }
+        if self.keymap_input(&event) {
+            return;
+        }
         match event {
             // Some terminals (or configurations) send Control key chords as
             // C0 control characters without reporting the CONTROL modifier.
@@ -218,31 +288,15 @@ impl TextArea {
                 code: KeyCode::Char(c),
                 // Insert plain characters (and Shift-modified). Do NOT insert when ALT is held,
                 // because many terminals map Option/Meta combos to ALT+<char> (e.g. ESC f/ESC b)
-                // for word navigation. Those are handled explicitly below.
+                // for word navigation. Those are keymap actions.
                 modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT,
                 ..
             } => self.insert_str(&c.to_string()),
+            // Enter keys not bound to submitting the message.
             KeyEvent {
-                code: KeyCode::Char('j' | 'm'),
-                modifiers: KeyModifiers::CONTROL,
-                ..
-            }
-            | KeyEvent {
                 code: KeyCode::Enter,
                 ..
             } => self.insert_str("\n"),
-            KeyEvent {
-                code: KeyCode::Char('h'),
-                modifiers,
-                ..
-            } if modifiers == (KeyModifiers::CONTROL | KeyModifiers::ALT) => {
-                self.delete_backward_word()
-            },
-            KeyEvent {
-                code: KeyCode::Backspace,
-                modifiers: KeyModifiers::ALT,
-                ..
-            } => self.delete_backward_word(),
             KeyEvent {
                 code: KeyCode::Backspace,
                 ..
@@ -252,11 +306,6 @@ impl TextArea {
                 modifiers: KeyModifiers::CONTROL,
                 ..
             } => self.delete_backward(1),
-            KeyEvent {
-                code: KeyCode::Delete,
-                modifiers: KeyModifiers::ALT,
-                ..
-            }  => self.delete_forward_word(),
             KeyEvent {
                 code: KeyCode::Delete,
                 ..
@@ -267,45 +316,6 @@ impl TextArea {
                 ..
             } => self.delete_forward(1),
 
-            KeyEvent {
-                code: KeyCode::Char('w'),
-                modifiers: KeyModifiers::CONTROL,
-                ..
-            } => {
-                self.delete_backward_word();
-            }
-            // Meta-b -> move to beginning of previous word
-            // Meta-f -> move to end of next word
-            // Many terminals map Option (macOS) to Alt. Some send Alt|Shift, so match contains(ALT).
-            KeyEvent {
-                code: KeyCode::Char('b'),
-                modifiers: KeyModifiers::ALT,
-                ..
-            } => {
-                self.set_cursor(self.beginning_of_previous_word());
-            }
-            KeyEvent {
-                code: KeyCode::Char('f'),
-                modifiers: KeyModifiers::ALT,
-                ..
-            } => {
-                self.set_cursor(self.end_of_next_word());
-            }
-            KeyEvent {
-                code: KeyCode::Char('u'),
-                modifiers: KeyModifiers::CONTROL,
-                ..
-            } => {
-                self.kill_to_beginning_of_line();
-            }
-            KeyEvent {
-                code: KeyCode::Char('k'),
-                modifiers: KeyModifiers::CONTROL,
-                ..
-            } => {
-                self.kill_to_end_of_line();
-            }
-
             // Cursor movement
             KeyEvent {
                 code: KeyCode::Left,
@@ -335,33 +345,6 @@ impl TextArea {
             } => {
                 self.move_cursor_right();
             }
-            // Some terminals send Alt+Arrow for word-wise movement:
-            // Option/Left -> Alt+Left (previous word start)
-            // Option/Right -> Alt+Right (next word end)
-            KeyEvent {
-                code: KeyCode::Left,
-                modifiers: KeyModifiers::ALT,
-                ..
-            }
-            | KeyEvent {
-                code: KeyCode::Left,
-                modifiers: KeyModifiers::CONTROL,
-                ..
-            } => {
-                self.set_cursor(self.beginning_of_previous_word());
-            }
-            KeyEvent {
-                code: KeyCode::Right,
-                modifiers: KeyModifiers::ALT,
-                ..
-            }
-            | KeyEvent {
-                code: KeyCode::Right,
-                modifiers: KeyModifiers::CONTROL,
-                ..
-            } => {
-                self.set_cursor(self.end_of_next_word());
-            }
             KeyEvent {
                 code: KeyCode::Up, ..
             } => {
@@ -373,36 +356,175 @@ impl TextArea {
             } => {
                 self.move_cursor_down();
             }
-            KeyEvent {
-                code: KeyCode::Home,
-                ..
-            } => {
-                self.move_cursor_to_beginning_of_line(false);
-            }
-            KeyEvent {
-                code: KeyCode::Char('a'),
-                modifiers: KeyModifiers::CONTROL,
-                ..
-            } => {
-                self.move_cursor_to_beginning_of_line(true);
+            _o => {
+                #[cfg(feature = "debug-logs")]
+                tracing::debug!("Unhandled key event in TextArea: {:?}", _o);
             }
+        }
+    }
 
-            KeyEvent {
-                code: KeyCode::End, ..
-            } => {
-                self.move_cursor_to_end_of_line(false);
+    /// Handles the editing actions of `[tui.keymap]`. Returns whether `event`
+    /// is bound to one of them.
+    fn keymap_input(&mut self, event: &KeyEvent) -> bool {
+        let keymap = keymap();
+        // Like in Emacs, repeating a chord such as Ctrl-A at the start of a
+        // line moves on to the previous line; Home and End stay on the line.
+        let chord = matches!(event.code, KeyCode::Char(_));
+        if keymap.newline.matches(event) {
+            self.insert_str("\n");
+        } else if keymap.line_start.matches(event) {
+            self.move_cursor_to_beginning_of_line(chord);
+        } else if keymap.line_end.matches(event) {
+            self.move_cursor_to_end_of_line(chord);
+        } else if keymap.word_left.matches(event) {
+            self.set_cursor(self.beginning_of_previous_word());
+        } else if keymap.word_right.matches(event) {
+            self.set_cursor(self.end_of_next_word());
+        } else if keymap.delete_word_backward.matches(event) {
+            self.delete_backward_word();
+        } else if keymap.delete_word_forward.matches(event) {
+            self.delete_forward_word();
+        } else if keymap.kill_line_start.matches(event) {
+            self.kill_to_beginning_of_line();
+        } else if keymap.kill_line_end.matches(event) {
+            self.kill_to_end_of_line();
+        } else {
+            return false;
+        }
+        true
+    }
+
+    /// Handles a key in vi normal mode, where keys are commands. `pending`
+    /// is the first key of a two-key command typed before this one.
+    fn vi_normal_input(&mut self, event: KeyEvent, pending: Option<char>) {
+        if let Some(vi) = self.vi.as_mut() {
+            vi.pending = None;
+        }
+        let c = match event.code {
+            KeyCode::Char(c) => c,
+            KeyCode::Left | KeyCode::Backspace => 'h',
+            KeyCode::Right => 'l',
+            KeyCode::Up => 'k',
+            KeyCode::Down => 'j',
+            KeyCode::Home => '0',
+            KeyCode::End => '$',
+            KeyCode::Delete => 'x',
+            _ => return,
+        };
+        let bol = self.beginning_of_current_line();
+        let eol = self.end_of_current_line();
+        match (pending, c) {
+            (Some('d'), 'd') => self.delete_current_line(),
+            (Some('c'), 'c') | (None, 'S') => {
+                self.replace_range(bol..eol, "");
+                self.set_vi_mode(ViMode::Insert);
             }
-            KeyEvent {
-                code: KeyCode::Char('e'),
-                modifiers: KeyModifiers::CONTROL,
-                ..
-            } => {
-                self.move_cursor_to_end_of_line(true);
+            (Some('d'), 'w') => {
+                let end = self.start_of_next_word();
+                self.replace_range(self.cursor_pos..end, "");
             }
-            _o => {
-                #[cfg(feature = "debug-logs")]
-                tracing::debug!("Unhandled key event in TextArea: {:?}", _o);
+            // Like vi, `cw` keeps the whitespace after the word.
+            (Some('c'), 'w') => {
+                self.delete_forward_word();
+                self.set_vi_mode(ViMode::Insert);
+            }
+            (Some(op @ ('d' | 'c')), 'b') => {
+                self.delete_backward_word();
+                if op == 'c' {
+                    self.set_vi_mode(ViMode::Insert);
+                }
+            }
+            (Some(op @ ('d' | 'c')), '$') => {
+                self.replace_range(self.cursor_pos..eol, "");
+                if op == 'c' {
+                    self.set_vi_mode(ViMode::Insert);
+                }
+            }
+            (Some(op @ ('d' | 'c')), '0') => {
+                self.replace_range(bol..self.cursor_pos, "");
+                if op == 'c' {
+                    self.set_vi_mode(ViMode::Insert);
+                }
+            }
+            (Some('g'), 'g') => self.set_cursor(0),
+            // Any other second key cancels the pending command.
+            (Some(_), _) => {}
+            (None, 'd' | 'c' | 'g') => {
+                if let Some(vi) = self.vi.as_mut() {
+                    vi.pending = Some(c);
+                }
+            }
+            (None, 'h') => {
+                if self.cursor_pos > bol {
+                    self.move_cursor_left();
+                }
+            }
+            (None, 'l') => {
+                if self.next_atomic_boundary(self.cursor_pos) < eol {
+                    self.move_cursor_right();
+                }
+            }
+            (None, 'k') => self.move_cursor_up(),
+            (None, 'j') => self.move_cursor_down(),
+            (None, 'w') => self.set_cursor(self.start_of_next_word()),
+            (None, 'b') => self.set_cursor(self.beginning_of_previous_word()),
+            (None, 'e') => {
+                self.move_cursor_right();
+                self.set_cursor(self.end_of_next_word());
+                self.move_cursor_left();
+            }
+            (None, '0') => self.set_cursor(bol),
+            (None, '^') => self.set_cursor(self.first_non_blank_of_current_line()),
+            (None, '$') => self.set_cursor(eol),
+            (None, 'G') => self.set_cursor(self.text.len()),
+            (None, 'i') => self.set_vi_mode(ViMode::Insert),
+            (None, 'a') => {
+                if self.cursor_pos < eol {
+                    self.move_cursor_right();
+                }
+                self.set_vi_mode(ViMode::Insert);
+            }
+            (None, 'I') => {
+                self.set_cursor(self.first_non_blank_of_current_line());
+                self.set_vi_mode(ViMode::Insert);
             }
+            (None, 'A') => {
+                self.set_cursor(eol);
+                self.set_vi_mode(ViMode::Insert);
+            }
+            (None, 'o') => {
+                self.set_cursor(eol);
+                self.insert_str("\n");
+                self.set_vi_mode(ViMode::Insert);
+            }
+            (None, 'O') => {
+                self.set_cursor(bol);
+                self.insert_str("\n");
+                self.move_cursor_left();
+                self.set_vi_mode(ViMode::Insert);
+            }
+            (None, 'x') => {
+                if self.cursor_pos < eol {
+                    self.delete_forward(1);
+                }
+            }
+            (None, 'X') => {
+                if self.cursor_pos > bol {
+                    self.delete_backward(1);
+                }
+            }
+            (None, 's') => {
+                if self.cursor_pos < eol {
+                    self.delete_forward(1);
+                }
+                self.set_vi_mode(ViMode::Insert);
+            }
+            (None, 'D') => self.replace_range(self.cursor_pos..eol, ""),
+            (None, 'C') => {
+                self.replace_range(self.cursor_pos..eol, "");
+                self.set_vi_mode(ViMode::Insert);
+            }
+            _ => {}
         }
     }
 
@@ -463,6 +585,19 @@ impl TextArea {
         }
     }
 
+    /// Deletes the line the cursor is on, including its line break.
+    fn delete_current_line(&mut self) {
+        let bol = self.beginning_of_current_line();
+        let eol = self.end_of_current_line();
+        let range = if eol < self.text.len() {
+            bol..eol + 1
+        } else {
+            bol.saturating_sub(1)..eol
+        };
+        self.replace_range(range, "");
+        self.set_cursor(self.beginning_of_current_line());
+    }
+
     pub fn kill_to_beginning_of_line(&mut self) {
         let bol = self.beginning_of_current_line();
         if self.cursor_pos == bol {
@@ -811,6 +946,23 @@ impl TextArea {
         }
     }
 
+    fn start_of_next_word(&self) -> usize {
+        let rest = &self.text[self.cursor_pos..];
+        let word_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let next = rest[word_end..]
+            .find(|c: char| !c.is_whitespace())
+            .map_or(rest.len(), |i| word_end + i);
+        self.adjust_pos_out_of_elements(self.cursor_pos + next, false)
+    }
+
+    fn first_non_blank_of_current_line(&self) -> usize {
+        let bol = self.beginning_of_current_line();
+        let line = &self.text[bol..self.end_of_current_line()];
+        bol + line
+            .find(|c: char| !c.is_whitespace())
+            .unwrap_or(line.len())
+    }
+
     pub(crate) fn end_of_next_word(&self) -> usize {
         let Some(first_non_ws) = self.text[self.cursor_pos..].find(|c: char| !c.is_whitespace())
         else {
@@ -939,7 +1091,7 @@ impl TextArea {
                 }
                 let styled = &self.text[overlap_start..overlap_end];
                 let x_off = self.text[line_range.start..overlap_start].width() as u16;
-                let style = Style::default().fg(Color::Cyan);
+                let style = Style::default().fg(theme().accent);
                 buf.set_string(area.x + x_off, y, styled, style);
             }
         }
@@ -1587,6 +1739,74 @@ mod tests {
         assert_eq!(t.cursor(), "👍👍".len());
     }
 
+    fn vi_keys(t: &mut TextArea, keys: &str) {
+        for c in keys.chars() {
+            let code = match c {
+                '\u{1b}' => KeyCode::Esc,
+                c => KeyCode::Char(c),
+            };
+            t.input(KeyEvent::new(code, KeyModifiers::NONE));
+        }
+    }
+
+    #[test]
+    fn vi_mode_switches_between_insert_and_normal() {
+        let mut t = TextArea::new_vi();
+        vi_keys(&mut t, "hello world");
+        assert!(t.is_vi_insert_mode());
+
+        // Esc leaves the cursor on the last character, like vi.
+        vi_keys(&mut t, "\u{1b}");
+        assert!(t.is_vi_normal_mode());
+        assert_eq!(t.cursor(), 10);
+
+        // Keys are commands in normal mode: `b` moves back a word and `x`
+        // deletes the character under the cursor.
+        vi_keys(&mut t, "bx");
+        assert_eq!(t.text(), "hello orld");
+
+        vi_keys(&mut t, "0iwell, \u{1b}");
+        assert_eq!(t.text(), "well, hello orld");
+        vi_keys(&mut t, "A!");
+        assert_eq!(t.text(), "well, hello orld!");
+        assert!(t.is_vi_insert_mode());
+    }
+
+    #[test]
+    fn vi_mode_operators() {
+        let mut t = TextArea::new_vi();
+        t.insert_str("first line\nsecond line\nthird line");
+        vi_keys(&mut t, "\u{1b}gg");
+        assert_eq!(t.cursor(), 0);
+
+        vi_keys(&mut t, "dw");
+        assert_eq!(t.text(), "line\nsecond line\nthird line");
+
+        vi_keys(&mut t, "jdd");
+        assert_eq!(t.text(), "line\nthird line");
+        assert_eq!(t.cursor(), 5);
+
+        vi_keys(&mut t, "cwfourth\u{1b}");
+        assert_eq!(t.text(), "line\nfourth line");
+
+        vi_keys(&mut t, "ggonew\u{1b}");
+        assert_eq!(t.text(), "line\nnew\nfourth line");
+
+        // An unknown second key cancels the pending operator.
+        vi_keys(&mut t, "dzD");
+        assert_eq!(t.text(), "line\nne\nfourth line");
+    }
+
+    #[test]
+    fn vi_mode_resets_to_insert_when_cleared() {
+        let mut t = TextArea::new_vi();
+        vi_keys(&mut t, "text\u{1b}");
+        assert!(t.is_vi_normal_mode());
+        t.set_text("");
+        assert!(t.is_vi_insert_mode());
+        assert!(!TextArea::new().is_vi_normal_mode());
+    }
+
     #[test]
     fn fuzz_textarea_randomized() {
         // Deterministic seed for reproducibility
@@ -1,9 +1,11 @@
 use std::collections::HashMap;
-use std::collections::VecDeque;
+use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::Arc;
 
 use codex_core::config::Config;
+use codex_core::custom_prompts::mcp_prompt_text;
+use codex_core::git_worktree::worktree_cwd;
 use codex_core::protocol::AgentMessageDeltaEvent;
 use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningDeltaEvent;
@@ -12,16 +14,24 @@ use codex_core::protocol::AgentReasoningRawContentDeltaEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::CheckpointRestoredEvent;
+use codex_core::protocol::DelegateBeginEvent;
+use codex_core::protocol::DelegateEndEvent;
 use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
+use codex_core::protocol::ExitedReviewModeEvent;
+use codex_core::protocol::GetMcpPromptResponseEvent;
 use codex_core::protocol::InputItem;
 use codex_core::protocol::InputMessageKind;
+use codex_core::protocol::InputQueueUpdatedEvent;
 use codex_core::protocol::ListCustomPromptsResponseEvent;
+use codex_core::protocol::McpInvocation;
 use codex_core::protocol::McpListToolsResponseEvent;
+use codex_core::protocol::McpToolCallApprovalRequestEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::Op;
@@ -35,6 +45,10 @@ use codex_core::protocol::TurnDiffEvent;
 use codex_core::protocol::UserMessageEvent;
 use codex_core::protocol::WebSearchBeginEvent;
 use codex_core::protocol::WebSearchEndEvent;
+use codex_core::protocol::WorktreeInfo;
+use codex_core::protocol::WorktreeResolution;
+use codex_core::protocol::WorktreeResolvedEvent;
+use codex_core::review::ReviewTarget;
 use codex_protocol::parse_command::ParsedCommand;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
@@ -81,6 +95,7 @@ use codex_common::approval_presets::builtin_approval_presets;
 use codex_common::model_presets::ModelPreset;
 use codex_common::model_presets::builtin_model_presets;
 use codex_core::ConversationManager;
+use codex_core::git_info::get_default_branch;
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol_config_types::ReasoningEffort as ReasoningEffortConfig;
@@ -128,13 +143,23 @@ pub(crate) struct ChatWidget {
     // When resuming an existing session (selected via resume picker), avoid an
     // immediate redraw on SessionConfigured to prevent a gratuitous UI flicker.
     suppress_session_configured_redraw: bool,
-    // User messages queued while a turn is in progress
-    queued_user_messages: VecDeque<UserMessage>,
+    // Input that core has not sent to the model yet, as last reported by it
+    core_input_queue: InputQueueUpdatedEvent,
+    // Ids of queued input this widget asked core to remove
+    removed_queued_input: HashSet<String>,
+    // Git worktree the session works in until it is resolved
+    worktree: Option<WorktreeInfo>,
+    // Whether to exit once the worktree was resolved from the exit popup
+    exit_after_worktree_resolved: bool,
 }
 
 struct UserMessage {
     text: String,
     image_paths: Vec<PathBuf>,
+    /// Model and reasoning effort requested by a custom prompt for this turn
+    /// only.
+    model: Option<String>,
+    effort: Option<ReasoningEffortConfig>,
 }
 
 impl From<String> for UserMessage {
@@ -142,6 +167,8 @@ impl From<String> for UserMessage {
         Self {
             text,
             image_paths: Vec::new(),
+            model: None,
+            effort: None,
         }
     }
 }
@@ -150,8 +177,53 @@ fn create_initial_user_message(text: String, image_paths: Vec<PathBuf>) -> Optio
     if text.is_empty() && image_paths.is_empty() {
         None
     } else {
-        Some(UserMessage { text, image_paths })
+        Some(UserMessage {
+            text,
+            image_paths,
+            model: None,
+            effort: None,
+        })
+    }
+}
+
+/// Input items for a message typed in the composer.
+fn user_input_items(text: &str, image_paths: Vec<PathBuf>) -> Vec<InputItem> {
+    let mut items: Vec<InputItem> = Vec::new();
+    if !text.is_empty() {
+        items.push(InputItem::Text {
+            text: text.to_string(),
+        });
+    }
+    for path in image_paths {
+        items.push(InputItem::LocalImage { path });
     }
+    items
+}
+
+/// The text portion of user input, as shown in the transcript and when the
+/// input is edited again in the composer.
+fn input_items_prompt(items: &[InputItem]) -> String {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            InputItem::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The text of user input as shown in the queued messages list, with images
+/// shown as placeholders.
+fn input_items_text(items: &[InputItem]) -> String {
+    items
+        .iter()
+        .map(|item| match item {
+            InputItem::Text { text } => text.as_str(),
+            _ => "[image]",
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 impl ChatWidget {
@@ -168,13 +240,23 @@ impl ChatWidget {
         if let Some(messages) = initial_messages {
             self.replay_initial_messages(messages);
         }
+        let worktree = event.worktree.clone();
+        if let Some(worktree) = &worktree {
+            self.config.cwd = worktree_cwd(worktree, &self.config.cwd);
+        }
         self.add_to_history(history_cell::new_session_info(
             &self.config,
             event,
             self.show_welcome_banner,
         ));
-        // Ask codex-core to enumerate custom prompts for this session.
+        if let Some(worktree) = &worktree {
+            self.add_to_history(history_cell::new_worktree_info(worktree));
+        }
+        self.worktree = worktree;
+        // Ask codex-core to enumerate custom prompts and MCP resources for
+        // this session.
         self.submit_op(Op::ListCustomPrompts);
+        self.submit_op(Op::ListMcpResources);
         if let Some(user_message) = self.initial_user_message.take() {
             self.submit_user_message(user_message);
         }
@@ -254,9 +336,6 @@ impl ChatWidget {
         self.bottom_pane.set_task_running(false);
         self.running_commands.clear();
         self.request_redraw();
-
-        // If there is a queued user message, send exactly one now to begin the next turn.
-        self.maybe_send_next_queued_input();
     }
 
     pub(crate) fn set_token_info(&mut self, info: Option<TokenUsageInfo>) {
@@ -279,9 +358,6 @@ impl ChatWidget {
     fn on_error(&mut self, message: String) {
         self.finalize_turn_with_error_message(message);
         self.request_redraw();
-
-        // After an error ends the turn, try sending the next queued input.
-        self.maybe_send_next_queued_input();
     }
 
     /// Handle a turn aborted due to user interrupt (Esc).
@@ -293,17 +369,19 @@ impl ChatWidget {
             "Conversation interrupted - tell the model what to do differently".to_owned(),
         );
 
-        // If any messages were queued during the task, restore them into the composer.
-        if !self.queued_user_messages.is_empty() {
-            let combined = self
-                .queued_user_messages
+        // Core pauses the queue after an interrupt. Take any messages queued
+        // during the task out of it and restore them into the composer.
+        let queued = std::mem::take(&mut self.core_input_queue.queued);
+        if !queued.is_empty() {
+            let combined = queued
                 .iter()
-                .map(|m| m.text.clone())
+                .map(|q| input_items_prompt(&q.items))
                 .collect::<Vec<_>>()
                 .join("\n");
             self.bottom_pane.set_composer_text(combined);
-            // Clear the queue and update the status indicator list.
-            self.queued_user_messages.clear();
+            for q in queued {
+                self.remove_queued_input(q.id);
+            }
             self.refresh_queued_user_messages();
         }
 
@@ -332,6 +410,19 @@ impl ChatWidget {
         );
     }
 
+    fn on_mcp_tool_call_approval_request(
+        &mut self,
+        id: String,
+        ev: McpToolCallApprovalRequestEvent,
+    ) {
+        let id2 = id.clone();
+        let ev2 = ev.clone();
+        self.defer_or_handle(
+            |q| q.push_mcp_tool_call_approval(id, ev),
+            |s| s.handle_mcp_tool_call_approval_now(id2, ev2),
+        );
+    }
+
     fn on_exec_command_begin(&mut self, ev: ExecCommandBeginEvent) {
         self.flush_answer_stream_with_separator();
         let ev2 = ev.clone();
@@ -382,6 +473,16 @@ impl ChatWidget {
         self.flush_answer_stream_with_separator();
     }
 
+    fn on_delegate_begin(&mut self, ev: DelegateBeginEvent) {
+        self.flush_answer_stream_with_separator();
+        self.add_to_history(history_cell::new_delegate_begin(&ev.tasks));
+    }
+
+    fn on_delegate_end(&mut self, ev: DelegateEndEvent) {
+        self.flush_answer_stream_with_separator();
+        self.add_to_history(history_cell::new_delegate_end(&ev.results));
+    }
+
     fn on_web_search_end(&mut self, ev: WebSearchEndEvent) {
         self.flush_answer_stream_with_separator();
         self.add_to_history(history_cell::new_web_search_call(format!(
@@ -554,6 +655,27 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    pub(crate) fn handle_mcp_tool_call_approval_now(
+        &mut self,
+        id: String,
+        ev: McpToolCallApprovalRequestEvent,
+    ) {
+        self.flush_answer_stream_with_separator();
+        let McpInvocation {
+            server,
+            tool,
+            arguments,
+        } = ev.invocation;
+        let request = ApprovalRequest::McpToolCall {
+            id,
+            server,
+            tool,
+            arguments: arguments.map(|arguments| arguments.to_string()),
+        };
+        self.bottom_pane.push_approval_request(request);
+        self.request_redraw();
+    }
+
     pub(crate) fn handle_exec_begin_now(&mut self, ev: ExecCommandBeginEvent) {
         // Ensure the status indicator is visible while the command runs.
         self.running_commands.insert(
@@ -663,7 +785,10 @@ impl ChatWidget {
             reasoning_buffer: String::new(),
             full_reasoning_buffer: String::new(),
             conversation_id: None,
-            queued_user_messages: VecDeque::new(),
+            core_input_queue: InputQueueUpdatedEvent::default(),
+            removed_queued_input: HashSet::new(),
+            worktree: None,
+            exit_after_worktree_resolved: false,
             show_welcome_banner: true,
             suppress_session_configured_redraw: false,
         }
@@ -715,7 +840,10 @@ impl ChatWidget {
             reasoning_buffer: String::new(),
             full_reasoning_buffer: String::new(),
             conversation_id: None,
-            queued_user_messages: VecDeque::new(),
+            core_input_queue: InputQueueUpdatedEvent::default(),
+            removed_queued_input: HashSet::new(),
+            worktree: None,
+            exit_after_worktree_resolved: false,
             show_welcome_banner: false,
             suppress_session_configured_redraw: true,
         }
@@ -763,35 +891,58 @@ impl ChatWidget {
                 modifiers: KeyModifiers::ALT,
                 kind: KeyEventKind::Press,
                 ..
-            } if !self.queued_user_messages.is_empty() => {
-                // Prefer the most recently queued item.
-                if let Some(user_message) = self.queued_user_messages.pop_back() {
-                    self.bottom_pane.set_composer_text(user_message.text);
+            } if !self.core_input_queue.queued.is_empty() => {
+                // Prefer the most recently queued item. It leaves the queue
+                // while edited so that it cannot start with its old text;
+                // submitting it queues it again.
+                if let Some(queued) = self.core_input_queue.queued.pop() {
+                    self.bottom_pane
+                        .set_composer_text(input_items_prompt(&queued.items));
+                    self.remove_queued_input(queued.id);
                     self.refresh_queued_user_messages();
                     self.request_redraw();
                 }
             }
-            _ => {
-                match self.bottom_pane.handle_key_event(key_event) {
-                    InputResult::Submitted(text) => {
-                        // If a task is running, queue the user input to be sent after the turn completes.
-                        let user_message = UserMessage {
-                            text,
-                            image_paths: self.bottom_pane.take_recent_submission_images(),
-                        };
-                        if self.bottom_pane.is_task_running() {
-                            self.queued_user_messages.push_back(user_message);
-                            self.refresh_queued_user_messages();
-                        } else {
-                            self.submit_user_message(user_message);
-                        }
-                    }
-                    InputResult::Command(cmd) => {
-                        self.dispatch_command(cmd);
-                    }
-                    InputResult::None => {}
+            _ => match self.bottom_pane.handle_key_event(key_event) {
+                InputResult::Submitted(text) => {
+                    let user_message = UserMessage {
+                        text,
+                        image_paths: self.bottom_pane.take_recent_submission_images(),
+                        model: None,
+                        effort: None,
+                    };
+                    self.submit_or_queue_user_message(user_message);
                 }
-            }
+                InputResult::SubmittedPrompt {
+                    text,
+                    model,
+                    effort,
+                } => {
+                    let user_message = UserMessage {
+                        text,
+                        image_paths: self.bottom_pane.take_recent_submission_images(),
+                        model,
+                        effort,
+                    };
+                    self.submit_or_queue_user_message(user_message);
+                }
+                InputResult::McpPrompt {
+                    server,
+                    name,
+                    arguments,
+                } => {
+                    // The text is submitted once the server has rendered it.
+                    self.submit_op(Op::GetMcpPrompt {
+                        server,
+                        name,
+                        arguments,
+                    });
+                }
+                InputResult::Command(cmd) => {
+                    self.dispatch_command(cmd);
+                }
+                InputResult::None => {}
+            },
         }
     }
 
@@ -832,6 +983,17 @@ impl ChatWidget {
                 self.clear_token_usage();
                 self.app_event_tx.send(AppEvent::CodexOp(Op::Compact));
             }
+            SlashCommand::Undo => {
+                self.app_event_tx.send(AppEvent::CodexOp(Op::Undo));
+            }
+            SlashCommand::Review => {
+                let tx = self.app_event_tx.clone();
+                let cwd = self.config.cwd.clone();
+                tokio::spawn(async move {
+                    let default_branch = get_default_branch(&cwd).await;
+                    tx.send(AppEvent::OpenReviewPopup { default_branch });
+                });
+            }
             SlashCommand::Model => {
                 self.open_model_popup();
             }
@@ -839,7 +1001,11 @@ impl ChatWidget {
                 self.open_approvals_popup();
             }
             SlashCommand::Quit => {
-                self.app_event_tx.send(AppEvent::ExitRequest);
+                if self.worktree.is_some() {
+                    self.open_worktree_popup();
+                } else {
+                    self.app_event_tx.send(AppEvent::ExitRequest);
+                }
             }
             SlashCommand::Logout => {
                 if let Err(e) = codex_core::auth::logout(&self.config.codex_home) {
@@ -955,27 +1121,93 @@ impl ChatWidget {
         self.app_event_tx.send(AppEvent::InsertHistoryCell(cell));
     }
 
-    fn submit_user_message(&mut self, user_message: UserMessage) {
-        let UserMessage { text, image_paths } = user_message;
-        let mut items: Vec<InputItem> = Vec::new();
+    fn submit_or_queue_user_message(&mut self, user_message: UserMessage) {
+        // If a task is running, queue the user input in core to start the next turn.
+        if self.bottom_pane.is_task_running() {
+            self.queue_user_message(user_message);
+        } else {
+            self.submit_user_message(user_message);
+        }
+    }
 
-        if !text.is_empty() {
-            items.push(InputItem::Text { text: text.clone() });
+    /// Queues a message in core. Its prompt is added to the transcript once
+    /// core starts it.
+    fn queue_user_message(&mut self, user_message: UserMessage) {
+        let UserMessage {
+            text,
+            image_paths,
+            model,
+            effort,
+        } = user_message;
+        let items = user_input_items(&text, image_paths);
+        if items.is_empty() {
+            return;
         }
 
-        for path in image_paths {
-            items.push(InputItem::LocalImage { path });
+        // Queued input runs with the turn context in effect when it was
+        // queued, so a custom prompt's model and effort are set just for it.
+        let overridden = model.is_some() || effort.is_some();
+        if overridden {
+            self.submit_op(Op::OverrideTurnContext {
+                cwd: None,
+                approval_policy: None,
+                sandbox_policy: None,
+                model,
+                effort: effort.map(Some),
+                summary: None,
+            });
+        }
+        self.submit_op(Op::QueueUserInput { items });
+        if overridden {
+            self.submit_op(Op::OverrideTurnContext {
+                cwd: None,
+                approval_policy: None,
+                sandbox_policy: None,
+                model: Some(self.config.model.clone()),
+                effort: Some(self.config.model_reasoning_effort),
+                summary: None,
+            });
+        }
+
+        // Persist the text to cross-session message history.
+        if !text.is_empty() {
+            self.submit_op(Op::AddToHistory { text });
         }
+    }
 
+    fn remove_queued_input(&mut self, id: String) {
+        self.removed_queued_input.insert(id.clone());
+        self.submit_op(Op::RemoveQueuedInput { id });
+    }
+
+    fn submit_user_message(&mut self, user_message: UserMessage) {
+        let UserMessage {
+            text,
+            image_paths,
+            model,
+            effort,
+        } = user_message;
+        let items = user_input_items(&text, image_paths);
         if items.is_empty() {
             return;
         }
 
-        self.codex_op_tx
-            .send(Op::UserInput { items })
-            .unwrap_or_else(|e| {
-                tracing::error!("failed to send message: {e}");
-            });
+        let op = if model.is_some() || effort.is_some() {
+            Op::UserTurn {
+                items,
+                cwd: self.config.cwd.clone(),
+                approval_policy: self.config.approval_policy,
+                sandbox_policy: self.config.sandbox_policy.clone(),
+                model: model.unwrap_or_else(|| self.config.model.clone()),
+                effort: effort.or(self.config.model_reasoning_effort),
+                summary: self.config.model_reasoning_summary,
+            }
+        } else {
+            Op::UserInput { items }
+        };
+        self.codex_op_tx.send(op).unwrap_or_else(|e| {
+            tracing::error!("failed to send message: {e}");
+        });
 
         // Persist the text to cross-session message history.
         if !text.is_empty() {
@@ -1055,6 +1287,7 @@ impl ChatWidget {
                 }
             },
             EventMsg::PlanUpdate(update) => self.on_plan_update(update),
+            EventMsg::InputQueueUpdated(ev) => self.on_input_queue_updated(ev),
             EventMsg::ExecApprovalRequest(ev) => {
                 // For replayed events, synthesize an empty id (these should not occur).
                 self.on_exec_approval_request(id.unwrap_or_default(), ev)
@@ -1062,6 +1295,9 @@ impl ChatWidget {
             EventMsg::ApplyPatchApprovalRequest(ev) => {
                 self.on_apply_patch_approval_request(id.unwrap_or_default(), ev)
             }
+            EventMsg::McpToolCallApprovalRequest(ev) => {
+                self.on_mcp_tool_call_approval_request(id.unwrap_or_default(), ev)
+            }
             EventMsg::ExecCommandBegin(ev) => self.on_exec_command_begin(ev),
             EventMsg::ExecCommandOutputDelta(delta) => self.on_exec_command_output_delta(delta),
             EventMsg::PatchApplyBegin(ev) => self.on_patch_apply_begin(ev),
@@ -1071,9 +1307,19 @@ impl ChatWidget {
             EventMsg::McpToolCallEnd(ev) => self.on_mcp_tool_call_end(ev),
             EventMsg::WebSearchBegin(ev) => self.on_web_search_begin(ev),
             EventMsg::WebSearchEnd(ev) => self.on_web_search_end(ev),
+            EventMsg::DelegateBegin(ev) => self.on_delegate_begin(ev),
+            // Workers' transcripts are recorded in their own sessions.
+            EventMsg::DelegateEvent(_) => {}
+            EventMsg::DelegateEnd(ev) => self.on_delegate_end(ev),
+            EventMsg::WorktreeResolved(ev) => self.on_worktree_resolved(ev),
+            EventMsg::CheckpointRestored(ev) => self.on_checkpoint_restored(ev),
             EventMsg::GetHistoryEntryResponse(ev) => self.on_get_history_entry_response(ev),
             EventMsg::McpListToolsResponse(ev) => self.on_list_mcp_tools(ev),
+            EventMsg::McpListResourcesResponse(ev) => {
+                self.bottom_pane.set_mcp_resources(ev.resources)
+            }
             EventMsg::ListCustomPromptsResponse(ev) => self.on_list_custom_prompts(ev),
+            EventMsg::GetMcpPromptResponse(ev) => self.on_get_mcp_prompt_response(ev),
             EventMsg::ShutdownComplete => self.on_shutdown_complete(),
             EventMsg::TurnDiff(TurnDiffEvent { unified_diff }) => self.on_turn_diff(unified_diff),
             EventMsg::BackgroundEvent(BackgroundEventEvent { message }) => {
@@ -1089,8 +1335,19 @@ impl ChatWidget {
                 self.app_event_tx
                     .send(crate::app_event::AppEvent::ConversationHistory(ev));
             }
-            EventMsg::EnteredReviewMode(_) => {}
-            EventMsg::ExitedReviewMode(_) => {}
+            EventMsg::EnteredReviewMode(review_request) => {
+                self.add_to_history(history_cell::new_review_started(
+                    &review_request.user_facing_hint,
+                ));
+            }
+            EventMsg::ExitedReviewMode(ExitedReviewModeEvent { review_output }) => {
+                if let Some(review_output) = review_output {
+                    self.add_to_history(history_cell::new_review_output(
+                        review_output,
+                        &self.config.cwd,
+                    ));
+                }
+            }
         }
     }
 
@@ -1122,24 +1379,37 @@ impl ChatWidget {
         }
     }
 
-    // If idle and there are queued inputs, submit exactly one to start the next turn.
-    fn maybe_send_next_queued_input(&mut self) {
-        if self.bottom_pane.is_task_running() {
-            return;
-        }
-        if let Some(user_message) = self.queued_user_messages.pop_front() {
-            self.submit_user_message(user_message);
+    fn on_input_queue_updated(&mut self, ev: InputQueueUpdatedEvent) {
+        let previous = std::mem::replace(&mut self.core_input_queue, ev);
+        let still_queued =
+            |queue: &InputQueueUpdatedEvent, id: &str| queue.queued.iter().any(|q| q.id == id);
+        // Queued input that left the queue without being removed from here
+        // has started a turn, so its prompt joins the transcript now.
+        for queued in previous.queued {
+            if still_queued(&self.core_input_queue, &queued.id)
+                || self.removed_queued_input.contains(&queued.id)
+            {
+                continue;
+            }
+            let prompt = input_items_prompt(&queued.items);
+            if !prompt.is_empty() {
+                self.add_to_history(history_cell::new_user_prompt(prompt));
+            }
         }
-        // Update the list to reflect the remaining queued messages (if any).
+        let queue = &self.core_input_queue;
+        self.removed_queued_input
+            .retain(|id| still_queued(queue, id));
         self.refresh_queued_user_messages();
     }
 
-    /// Rebuild and update the queued user messages from the current queue.
+    /// Rebuild and update the queued user messages from the input pending in
+    /// core.
     fn refresh_queued_user_messages(&mut self) {
-        let messages: Vec<String> = self
-            .queued_user_messages
+        let InputQueueUpdatedEvent { steering, queued } = &self.core_input_queue;
+        let messages: Vec<String> = steering
             .iter()
-            .map(|m| m.text.clone())
+            .chain(queued.iter().map(|q| &q.items))
+            .map(|items| input_items_text(items))
             .collect();
         self.bottom_pane.set_queued_user_messages(messages);
     }
@@ -1265,6 +1535,157 @@ impl ChatWidget {
         );
     }
 
+    /// Open a popup to choose which changes `/review` should look at.
+    pub(crate) fn open_review_popup(&mut self, default_branch: Option<String>) {
+        let mut targets = vec![(
+            ReviewTarget::UncommittedChanges,
+            "Review uncommitted changes".to_string(),
+            "staged, unstaged and untracked files",
+        )];
+        if let Some(branch) = default_branch {
+            targets.push((
+                ReviewTarget::BaseBranch(branch.clone()),
+                format!("Review against {branch}"),
+                "changes on this branch since it diverged",
+            ));
+        }
+        targets.push((
+            ReviewTarget::Commit("HEAD".to_string()),
+            "Review the latest commit".to_string(),
+            "changes introduced by HEAD",
+        ));
+
+        let items: Vec<SelectionItem> = targets
+            .into_iter()
+            .map(|(target, name, description)| {
+                let review_request = target.review_request();
+                let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                    tx.send(AppEvent::CodexOp(Op::Review {
+                        review_request: review_request.clone(),
+                    }));
+                })];
+                SelectionItem {
+                    name,
+                    description: Some(description.to_string()),
+                    is_current: false,
+                    actions,
+                }
+            })
+            .collect();
+
+        self.bottom_pane.show_selection_view(
+            "Select a review target".to_string(),
+            None,
+            Some("Press Enter to confirm or Esc to go back".to_string()),
+            items,
+        );
+    }
+
+    /// Ask what to do with the changes made in the session's worktree before
+    /// exiting.
+    fn open_worktree_popup(&mut self) {
+        let Some(worktree) = &self.worktree else {
+            return;
+        };
+        let options = [
+            (
+                Some(WorktreeResolution::Merge),
+                "Merge",
+                "merge the branch into the original checkout",
+            ),
+            (
+                Some(WorktreeResolution::CherryPick),
+                "Cherry-pick",
+                "apply its commits on top of the original checkout",
+            ),
+            (
+                Some(WorktreeResolution::Discard),
+                "Discard",
+                "delete the worktree and all its changes",
+            ),
+            (
+                None,
+                "Keep",
+                "exit and leave the worktree and branch in place",
+            ),
+        ];
+        let items: Vec<SelectionItem> = options
+            .into_iter()
+            .map(|(resolution, name, description)| {
+                let actions: Vec<SelectionAction> = vec![Box::new(move |tx| match resolution {
+                    Some(resolution) => {
+                        tx.send(AppEvent::CodexOp(Op::ResolveWorktree { resolution }))
+                    }
+                    None => tx.send(AppEvent::ExitRequest),
+                })];
+                SelectionItem {
+                    name: name.to_string(),
+                    description: Some(description.to_string()),
+                    is_current: false,
+                    actions,
+                }
+            })
+            .collect();
+
+        self.exit_after_worktree_resolved = true;
+        self.bottom_pane.show_selection_view(
+            format!("Changes on {}", worktree.branch),
+            Some(format!("Worktree: {}", worktree.path.display())),
+            Some("Press Enter to confirm or Esc to go back".to_string()),
+            items,
+        );
+    }
+
+    /// Ask whether backtracking should also rewind the files in the working
+    /// tree to the checkpoint taken before the selected message.
+    pub(crate) fn open_backtrack_files_popup(&mut self) {
+        let options = [
+            (
+                true,
+                "Restore files",
+                "rewind the conversation and the files to before this message",
+            ),
+            (
+                false,
+                "Keep files",
+                "rewind only the conversation and leave the files as they are",
+            ),
+        ];
+        let items: Vec<SelectionItem> = options
+            .into_iter()
+            .map(|(restore_files, name, description)| {
+                let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                    tx.send(AppEvent::ConfirmBacktrack { restore_files })
+                })];
+                SelectionItem {
+                    name: name.to_string(),
+                    description: Some(description.to_string()),
+                    is_current: false,
+                    actions,
+                }
+            })
+            .collect();
+
+        self.bottom_pane.show_selection_view(
+            "Go back to this message".to_string(),
+            Some("A checkpoint of the files was taken before it was sent".to_string()),
+            Some("Press Enter to confirm or Esc to go back".to_string()),
+            items,
+        );
+    }
+
+    fn on_worktree_resolved(&mut self, ev: WorktreeResolvedEvent) {
+        self.worktree = None;
+        self.add_to_history(history_cell::new_worktree_resolved(ev.summary));
+        if self.exit_after_worktree_resolved {
+            self.app_event_tx.send(AppEvent::ExitRequest);
+        }
+    }
+
+    fn on_checkpoint_restored(&mut self, ev: CheckpointRestoredEvent) {
+        self.add_to_history(history_cell::new_checkpoint_restored(ev.turn));
+    }
+
     /// Set the approval policy in the widget's config copy.
     pub(crate) fn set_approval_policy(&mut self, policy: AskForApproval) {
         self.config.approval_policy = policy;
@@ -1320,6 +1741,10 @@ impl ChatWidget {
             return;
         }
 
+        if self.worktree.is_some() {
+            self.open_worktree_popup();
+            return;
+        }
         self.submit_op(Op::Shutdown);
     }
 
@@ -1368,6 +1793,26 @@ impl ChatWidget {
         debug!("received {len} custom prompts");
         // Forward to bottom pane so the slash popup can show them now.
         self.bottom_pane.set_custom_prompts(ev.custom_prompts);
+        self.bottom_pane.set_mcp_prompts(ev.mcp_prompts);
+    }
+
+    fn on_get_mcp_prompt_response(&mut self, ev: GetMcpPromptResponseEvent) {
+        let GetMcpPromptResponseEvent {
+            server,
+            name,
+            result,
+        } = ev;
+        match result {
+            Ok(result) => {
+                let text = mcp_prompt_text(&result);
+                if text.trim().is_empty() {
+                    self.add_error_message(format!("MCP prompt /{server}:{name} has no text"));
+                } else {
+                    self.submit_or_queue_user_message(text.into());
+                }
+            }
+            Err(e) => self.add_error_message(format!("MCP prompt /{server}:{name} failed: {e}")),
+        }
     }
 
     /// Programmatically submit a user text message as if typed in the
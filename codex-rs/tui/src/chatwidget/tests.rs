@@ -13,6 +13,7 @@ use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningDeltaEvent;
 use codex_core::protocol::AgentReasoningEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
+use codex_core::protocol::CheckpointRestoredEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecApprovalRequestEvent;
@@ -22,6 +23,7 @@ use codex_core::protocol::FileChange;
 use codex_core::protocol::InputMessageKind;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
+use codex_core::protocol::QueuedInput;
 use codex_core::protocol::StreamErrorEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TaskStartedEvent;
@@ -152,6 +154,7 @@ fn resumed_initial_messages_render_history() {
             }),
         ]),
         rollout_path: rollout_file.path().to_path_buf(),
+        worktree: None,
     };
 
     chat.handle_codex_event(Event {
@@ -241,7 +244,10 @@ fn make_chatwidget_manual() -> (
         conversation_id: None,
         frame_requester: FrameRequester::test_dummy(),
         show_welcome_banner: true,
-        queued_user_messages: VecDeque::new(),
+        core_input_queue: InputQueueUpdatedEvent::default(),
+        removed_queued_input: HashSet::new(),
+        worktree: None,
+        exit_after_worktree_resolved: false,
         suppress_session_configured_redraw: false,
     };
     (widget, rx, op_rx)
@@ -419,6 +425,7 @@ fn end_exec(chat: &mut ChatWidget, call_id: &str, stdout: &str, stderr: &str, ex
             exit_code,
             duration: std::time::Duration::from_millis(5),
             formatted_output: aggregated,
+            denied_hosts: Vec::new(),
         }),
     });
 }
@@ -456,9 +463,28 @@ fn open_fixture(name: &str) -> File {
     File::open(name).expect("open fixture file")
 }
 
+fn queued_text(id: &str, text: &str) -> QueuedInput {
+    QueuedInput {
+        id: id.to_string(),
+        items: vec![InputItem::Text {
+            text: text.to_string(),
+        }],
+    }
+}
+
+fn set_core_queue(chat: &mut ChatWidget, queued: Vec<QueuedInput>) {
+    chat.handle_codex_event(Event {
+        id: "queue".into(),
+        msg: EventMsg::InputQueueUpdated(InputQueueUpdatedEvent {
+            steering: Vec::new(),
+            queued,
+        }),
+    });
+}
+
 #[test]
 fn empty_enter_during_task_does_not_queue() {
-    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    let (mut chat, _rx, mut op_rx) = make_chatwidget_manual();
 
     // Simulate running task so submissions would normally be queued.
     chat.bottom_pane.set_task_running(true);
@@ -467,37 +493,111 @@ fn empty_enter_during_task_does_not_queue() {
     chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
 
     // Ensure nothing was queued.
-    assert!(chat.queued_user_messages.is_empty());
+    assert!(op_rx.try_recv().is_err(), "unexpected outbound op");
+}
+
+#[test]
+fn message_typed_during_task_is_queued_in_core() {
+    let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual();
+    chat.bottom_pane.set_task_running(true);
+
+    chat.bottom_pane.set_composer_text("next step".to_string());
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+    assert_eq!(
+        op_rx.try_recv().ok(),
+        Some(Op::QueueUserInput {
+            items: vec![InputItem::Text {
+                text: "next step".to_string(),
+            }],
+        })
+    );
+    // The prompt joins the transcript only once core starts it.
+    assert!(drain_insert_history(&mut rx).is_empty());
+
+    set_core_queue(&mut chat, vec![queued_text("sub-1", "next step")]);
+    assert_eq!(chat.bottom_pane.queued_user_messages(), ["next step"]);
+    assert!(drain_insert_history(&mut rx).is_empty());
+
+    set_core_queue(&mut chat, Vec::new());
+    assert!(chat.bottom_pane.queued_user_messages().is_empty());
+    let history = drain_insert_history(&mut rx);
+    assert_eq!(history.len(), 1);
+    assert!(lines_to_single_string(&history[0]).contains("next step"));
 }
 
 #[test]
 fn alt_up_edits_most_recent_queued_message() {
-    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual();
 
     // Simulate a running task so messages would normally be queued.
     chat.bottom_pane.set_task_running(true);
 
     // Seed two queued messages.
-    chat.queued_user_messages
-        .push_back(UserMessage::from("first queued".to_string()));
-    chat.queued_user_messages
-        .push_back(UserMessage::from("second queued".to_string()));
-    chat.refresh_queued_user_messages();
+    set_core_queue(
+        &mut chat,
+        vec![
+            queued_text("sub-1", "first queued"),
+            queued_text("sub-2", "second queued"),
+        ],
+    );
 
     // Press Alt+Up to edit the most recent (last) queued message.
     chat.handle_key_event(KeyEvent::new(KeyCode::Up, KeyModifiers::ALT));
 
-    // Composer should now contain the last queued message.
+    // Composer should now contain the last queued message, which is removed
+    // from the queue in core.
     assert_eq!(
         chat.bottom_pane.composer_text(),
         "second queued".to_string()
     );
-    // And the queue should now contain only the remaining (older) item.
-    assert_eq!(chat.queued_user_messages.len(), 1);
     assert_eq!(
-        chat.queued_user_messages.front().unwrap().text,
-        "first queued"
+        op_rx.try_recv().ok(),
+        Some(Op::RemoveQueuedInput {
+            id: "sub-2".to_string(),
+        })
     );
+    assert_eq!(chat.bottom_pane.queued_user_messages(), ["first queued"]);
+
+    // Core confirming the removal does not add the message to the transcript.
+    set_core_queue(&mut chat, vec![queued_text("sub-1", "first queued")]);
+    assert!(drain_insert_history(&mut rx).is_empty());
+}
+
+#[test]
+fn input_queued_in_core_is_shown() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    chat.bottom_pane.set_task_running(true);
+
+    chat.handle_codex_event(Event {
+        id: "sub-2".into(),
+        msg: EventMsg::InputQueueUpdated(InputQueueUpdatedEvent {
+            steering: vec![vec![InputItem::Text {
+                text: "steer".to_string(),
+            }]],
+            queued: vec![QueuedInput {
+                id: "sub-1".to_string(),
+                items: vec![
+                    InputItem::Text {
+                        text: "look at".to_string(),
+                    },
+                    InputItem::LocalImage {
+                        path: PathBuf::from("screenshot.png"),
+                    },
+                ],
+            }],
+        }),
+    });
+    assert_eq!(
+        chat.bottom_pane.queued_user_messages(),
+        ["steer", "look at [image]"]
+    );
+
+    chat.handle_codex_event(Event {
+        id: "sub-3".into(),
+        msg: EventMsg::InputQueueUpdated(InputQueueUpdatedEvent::default()),
+    });
+    assert!(chat.bottom_pane.queued_user_messages().is_empty());
 }
 
 #[test]
@@ -633,6 +733,114 @@ fn disabled_slash_command_while_task_running_snapshot() {
     assert_snapshot!(blob);
 }
 
+#[test]
+fn quit_with_worktree_asks_before_exiting() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.worktree = Some(WorktreeInfo {
+        path: PathBuf::from("/tmp/worktrees/repo-1234"),
+        branch: "codex/1234".to_string(),
+        base_commit: "abc".to_string(),
+        repo_root: PathBuf::from("/tmp/repo"),
+    });
+
+    chat.dispatch_command(SlashCommand::Quit);
+    while let Ok(event) = rx.try_recv() {
+        assert!(
+            !matches!(event, AppEvent::ExitRequest),
+            "should not exit before the worktree is resolved"
+        );
+    }
+
+    chat.handle_codex_event(Event {
+        id: "1".into(),
+        msg: EventMsg::WorktreeResolved(WorktreeResolvedEvent {
+            resolution: WorktreeResolution::Discard,
+            summary: "Discarded the changes on codex/1234".to_string(),
+        }),
+    });
+    let mut exited = false;
+    while let Ok(event) = rx.try_recv() {
+        exited |= matches!(event, AppEvent::ExitRequest);
+    }
+    assert!(exited, "expected to exit once the worktree was resolved");
+    assert!(chat.worktree.is_none());
+}
+
+#[test]
+fn ctrl_c_and_ctrl_d_with_worktree_ask_before_exiting() {
+    for key in [KeyCode::Char('c'), KeyCode::Char('d')] {
+        let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual();
+        chat.worktree = Some(WorktreeInfo {
+            path: PathBuf::from("/tmp/worktrees/repo-1234"),
+            branch: "codex/1234".to_string(),
+            base_commit: "abc".to_string(),
+            repo_root: PathBuf::from("/tmp/repo"),
+        });
+
+        chat.handle_key_event(KeyEvent::new(key, KeyModifiers::CONTROL));
+        while let Ok(event) = rx.try_recv() {
+            assert!(
+                !matches!(event, AppEvent::ExitRequest),
+                "{key:?} should not exit before the worktree is resolved"
+            );
+        }
+        while let Ok(op) = op_rx.try_recv() {
+            assert!(
+                !matches!(op, Op::Shutdown),
+                "{key:?} should not shut down before the worktree is resolved"
+            );
+        }
+        assert!(chat.exit_after_worktree_resolved, "{key:?} should ask");
+    }
+}
+
+#[test]
+fn backtrack_asks_before_restoring_files() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+
+    chat.open_backtrack_files_popup();
+    assert!(
+        rx.try_recv().is_err(),
+        "nothing should happen before a choice"
+    );
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    let mut choices = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        if let AppEvent::ConfirmBacktrack { restore_files } = event {
+            choices.push(restore_files);
+        }
+    }
+    assert_eq!(choices, vec![false]);
+}
+
+#[test]
+fn undo_requests_checkpoint_restore_and_reports_it() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+
+    chat.dispatch_command(SlashCommand::Undo);
+    let mut undo_requested = false;
+    while let Ok(event) = rx.try_recv() {
+        undo_requested |= matches!(event, AppEvent::CodexOp(Op::Undo));
+    }
+    assert!(undo_requested, "expected /undo to submit Op::Undo");
+
+    chat.handle_codex_event(Event {
+        id: "1".into(),
+        msg: EventMsg::CheckpointRestored(CheckpointRestoredEvent { turn: 2 }),
+    });
+    let cells = drain_insert_history(&mut rx);
+    let text = cells
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect::<String>();
+    assert!(
+        text.contains("restored files to their state before message 3"),
+        "unexpected history: {text}"
+    );
+}
+
 #[tokio::test(flavor = "current_thread")]
 async fn binary_size_transcript_snapshot() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
@@ -920,11 +1128,13 @@ fn interrupt_restores_queued_messages_into_composer() {
     chat.bottom_pane.set_task_running(true);
 
     // Queue two user messages while the task is running.
-    chat.queued_user_messages
-        .push_back(UserMessage::from("first queued".to_string()));
-    chat.queued_user_messages
-        .push_back(UserMessage::from("second queued".to_string()));
-    chat.refresh_queued_user_messages();
+    set_core_queue(
+        &mut chat,
+        vec![
+            queued_text("sub-1", "first queued"),
+            queued_text("sub-2", "second queued"),
+        ],
+    );
 
     // Deliver a TurnAborted event with Interrupted reason (as if Esc was pressed).
     chat.handle_codex_event(Event {
@@ -940,11 +1150,19 @@ fn interrupt_restores_queued_messages_into_composer() {
         "first queued\nsecond queued"
     );
 
-    // Queue should be cleared and no new user input should have been auto-submitted.
-    assert!(chat.queued_user_messages.is_empty());
-    assert!(
-        op_rx.try_recv().is_err(),
-        "unexpected outbound op after interrupt"
+    // The messages are removed from the queue in core and nothing else is
+    // submitted.
+    assert!(chat.bottom_pane.queued_user_messages().is_empty());
+    assert_eq!(
+        std::iter::from_fn(|| op_rx.try_recv().ok()).collect::<Vec<_>>(),
+        vec![
+            Op::RemoveQueuedInput {
+                id: "sub-1".to_string(),
+            },
+            Op::RemoveQueuedInput {
+                id: "sub-2".to_string(),
+            },
+        ]
     );
 
     // Drain rx to avoid unused warnings.
@@ -1686,6 +1904,7 @@ fn chatwidget_exec_and_status_layout_vt100_snapshot() {
             exit_code: 0,
             duration: std::time::Duration::from_millis(16000),
             formatted_output: String::new(),
+            denied_hosts: Vec::new(),
         }),
     });
     chat.handle_codex_event(Event {
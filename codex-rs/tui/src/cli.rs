@@ -26,6 +26,11 @@ pub struct Cli {
     #[clap(skip)]
     pub resume_session_id: Option<String>,
 
+    /// Internal: words to search the recorded sessions for. Set by
+    /// `codex resume --search`; not exposed as a public flag.
+    #[clap(skip)]
+    pub resume_search: Option<String>,
+
     /// Model the agent should use.
     #[arg(long, short = 'm')]
     pub model: Option<String>,
@@ -71,6 +76,12 @@ pub struct Cli {
     #[arg(long = "search", default_value_t = false)]
     pub web_search: bool,
 
+    /// Work in a dedicated git worktree on a fresh branch so that other
+    /// sessions in the same repository are not affected. On `/quit` you can
+    /// merge, cherry-pick or discard the changes.
+    #[arg(long = "worktree", default_value_t = false)]
+    pub worktree: bool,
+
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
 }
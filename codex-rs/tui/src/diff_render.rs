@@ -1,4 +1,3 @@
-use ratatui::style::Color;
 use ratatui::style::Modifier;
 use ratatui::style::Style;
 use ratatui::style::Stylize;
@@ -10,6 +9,9 @@ use std::path::PathBuf;
 
 use crate::exec_command::relativize_to_home;
 use crate::history_cell::PatchEventType;
+use crate::syntax_highlight::Highlighter;
+use crate::syntax_highlight::current_palette;
+use crate::theme::ThemeStylize;
 use codex_core::git_info::get_git_repo_root;
 use codex_core::protocol::FileChange;
 
@@ -45,7 +47,6 @@ pub(crate) fn create_diff_summary(
 // Shared row for per-file presentation
 #[derive(Clone)]
 struct Row {
-    #[allow(dead_code)]
     path: PathBuf,
     move_path: Option<PathBuf>,
     added: usize,
@@ -94,13 +95,14 @@ fn render_changes_block(
 ) -> Vec<RtLine<'static>> {
     let mut out: Vec<RtLine<'static>> = Vec::new();
     let term_cols = wrap_cols;
+    let palette = current_palette();
 
     fn render_line_count_summary(added: usize, removed: usize) -> Vec<RtSpan<'static>> {
         let mut spans = Vec::new();
         spans.push("(".into());
-        spans.push(format!("+{added}").green());
+        spans.push(format!("+{added}").success());
         spans.push(" ".into());
-        spans.push(format!("-{removed}").red());
+        spans.push(format!("-{removed}").error());
         spans.push(")".into());
         spans
     }
@@ -189,6 +191,14 @@ fn render_changes_block(
             out.push(RtLine::from(header));
         }
 
+        // Lines of the old and new versions of the file are highlighted
+        // separately so that each side keeps its own multi-line state.
+        let mut new_highlighter = palette.and_then(|palette| {
+            Highlighter::for_path(r.move_path.as_ref().unwrap_or(&r.path), palette)
+        });
+        let mut old_highlighter =
+            palette.and_then(|palette| Highlighter::for_path(&r.path, palette));
+
         match r.change {
             FileChange::Add { content } => {
                 for (i, raw) in content.lines().enumerate() {
@@ -196,6 +206,7 @@ fn render_changes_block(
                         i + 1,
                         DiffLineType::Insert,
                         raw,
+                        new_highlighter.as_mut(),
                         term_cols,
                     ));
                 }
@@ -206,6 +217,7 @@ fn render_changes_block(
                         i + 1,
                         DiffLineType::Delete,
                         raw,
+                        old_highlighter.as_mut(),
                         term_cols,
                     ));
                 }
@@ -218,6 +230,13 @@ fn render_changes_block(
                             out.push(RtLine::from(vec!["    ".into(), "⋮".dim()]));
                         }
                         is_first_hunk = false;
+                        // Hunks do not start where the previous one ended.
+                        for highlighter in [&mut new_highlighter, &mut old_highlighter]
+                            .into_iter()
+                            .flatten()
+                        {
+                            highlighter.reset();
+                        }
 
                         let mut old_ln = h.old_range().start();
                         let mut new_ln = h.new_range().start();
@@ -229,6 +248,7 @@ fn render_changes_block(
                                         new_ln,
                                         DiffLineType::Insert,
                                         s,
+                                        new_highlighter.as_mut(),
                                         term_cols,
                                     ));
                                     new_ln += 1;
@@ -239,16 +259,21 @@ fn render_changes_block(
                                         old_ln,
                                         DiffLineType::Delete,
                                         s,
+                                        old_highlighter.as_mut(),
                                         term_cols,
                                     ));
                                     old_ln += 1;
                                 }
                                 diffy::Line::Context(text) => {
                                     let s = text.trim_end_matches('\n');
+                                    if let Some(highlighter) = old_highlighter.as_mut() {
+                                        highlighter.highlight_line(s);
+                                    }
                                     out.extend(push_wrapped_diff_line(
                                         new_ln,
                                         DiffLineType::Context,
                                         s,
+                                        new_highlighter.as_mut(),
                                         term_cols,
                                     ));
                                     old_ln += 1;
@@ -299,6 +324,7 @@ fn push_wrapped_diff_line(
     line_number: usize,
     kind: DiffLineType,
     text: &str,
+    highlighter: Option<&mut Highlighter>,
     term_cols: usize,
 ) -> Vec<RtLine<'static>> {
     let indent = "    ";
@@ -318,6 +344,25 @@ fn push_wrapped_diff_line(
         DiffLineType::Delete => ('-', style_del()),
         DiffLineType::Context => (' ', style_context()),
     };
+    if let Some(highlighter) = highlighter {
+        // Tokens are colored on top of the line's own style, so text the
+        // highlighter leaves plain still reads as added or deleted.
+        let spans = highlighter
+            .highlight_line(text)
+            .into_iter()
+            .map(|span| {
+                let style = line_style.patch(span.style);
+                span.style(style)
+            })
+            .collect();
+        return wrap_highlighted_diff_line(
+            &ln_str,
+            gap_after_ln,
+            RtSpan::styled(sign_char.to_string(), line_style),
+            spans,
+            term_cols.saturating_sub(prefix_cols + 1).max(1),
+        );
+    }
     let mut lines: Vec<RtLine<'static>> = Vec::new();
 
     loop {
@@ -358,6 +403,51 @@ fn push_wrapped_diff_line(
     lines
 }
 
+/// Like the plain path of [`push_wrapped_diff_line`], but wraps content that
+/// was already split into styled spans.
+fn wrap_highlighted_diff_line(
+    ln_str: &str,
+    gap_after_ln: usize,
+    sign: RtSpan<'static>,
+    spans: Vec<RtSpan<'static>>,
+    available_content_cols: usize,
+) -> Vec<RtLine<'static>> {
+    let indent = "    ";
+    let mut lines: Vec<RtLine<'static>> = Vec::new();
+    let mut row: Vec<RtSpan<'static>> = vec![
+        RtSpan::styled(
+            format!("{indent}{ln_str}{}", " ".repeat(gap_after_ln)),
+            style_gutter(),
+        ),
+        sign,
+    ];
+    let mut row_cols = 0;
+    for span in spans {
+        let mut remaining: &str = &span.content;
+        while !remaining.is_empty() {
+            if row_cols == available_content_cols {
+                lines.push(RtLine::from(std::mem::take(&mut row)));
+                row.push(RtSpan::styled(
+                    format!("{indent}{} ", " ".repeat(ln_str.len() + gap_after_ln)),
+                    style_gutter(),
+                ));
+                row_cols = 0;
+            }
+            let split_at_byte_index = remaining
+                .char_indices()
+                .nth(available_content_cols - row_cols)
+                .map(|(i, _)| i)
+                .unwrap_or(remaining.len());
+            let (chunk, rest) = remaining.split_at(split_at_byte_index);
+            row_cols += chunk.chars().count();
+            row.push(RtSpan::styled(chunk.to_string(), span.style));
+            remaining = rest;
+        }
+    }
+    lines.push(RtLine::from(row));
+    lines
+}
+
 fn style_gutter() -> Style {
     Style::default().add_modifier(Modifier::DIM)
 }
@@ -367,11 +457,11 @@ fn style_context() -> Style {
 }
 
 fn style_add() -> Style {
-    Style::default().fg(Color::Green)
+    Style::default().success()
 }
 
 fn style_del() -> Style {
-    Style::default().fg(Color::Red)
+    Style::default().error()
 }
 
 #[cfg(test)]
@@ -380,6 +470,7 @@ mod tests {
     use insta::assert_snapshot;
     use ratatui::Terminal;
     use ratatui::backend::TestBackend;
+    use ratatui::style::Color;
     use ratatui::text::Text;
     use ratatui::widgets::Paragraph;
     use ratatui::widgets::WidgetRef;
@@ -462,12 +553,47 @@ mod tests {
         let long_line = "this is a very long line that should wrap across multiple terminal columns and continue";
 
         // Call the wrapping function directly so we can precisely control the width
-        let lines = push_wrapped_diff_line(1, DiffLineType::Insert, long_line, 80);
+        let lines = push_wrapped_diff_line(1, DiffLineType::Insert, long_line, None, 80);
 
         // Render into a small terminal to capture the visual layout
         snapshot_lines("wrap_behavior_insert", lines, 90, 8);
     }
 
+    #[test]
+    fn highlighted_lines_wrap_like_plain_lines() {
+        let line = "    let message = \"a string long enough to wrap\"; // and a comment";
+        let mut highlighter = Highlighter::for_path(
+            Path::new("main.rs"),
+            crate::syntax_highlight::Palette::ansi(),
+        )
+        .expect("rust is supported");
+        let text = |lines: Vec<RtLine<'static>>| -> Vec<String> {
+            lines
+                .iter()
+                .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+                .collect()
+        };
+
+        let plain = push_wrapped_diff_line(7, DiffLineType::Insert, line, None, 40);
+        let highlighted =
+            push_wrapped_diff_line(7, DiffLineType::Insert, line, Some(&mut highlighter), 40);
+
+        assert_eq!(text(highlighted.clone()), text(plain));
+        let spans: Vec<&RtSpan<'static>> =
+            highlighted.iter().flat_map(|l| l.spans.iter()).collect();
+        assert!(
+            spans
+                .iter()
+                .any(|s| s.content == "let" && s.style.fg == Some(Color::Magenta))
+        );
+        // Plain tokens keep the color of inserted lines.
+        assert!(
+            spans
+                .iter()
+                .any(|s| s.content.contains("message") && s.style.fg == Some(Color::Green))
+        );
+    }
+
     #[test]
     fn ui_snapshot_single_line_replacement_counts() {
         // Reproduce: one deleted line replaced by one inserted line, no extra context
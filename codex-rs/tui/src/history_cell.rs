@@ -7,6 +7,7 @@ use crate::render::line_utils::prefix_lines;
 use crate::render::line_utils::push_owned_lines;
 use crate::slash_command::SlashCommand;
 use crate::text_formatting::format_and_truncate_tool_result;
+use crate::theme::ThemeStylize;
 use crate::ui_consts::LIVE_PREFIX_COLS;
 use crate::wrapping::RtOptions;
 use crate::wrapping::word_wrap_line;
@@ -18,6 +19,7 @@ use codex_common::elapsed::format_duration;
 use codex_core::auth::get_auth_file;
 use codex_core::auth::try_read_auth_json;
 use codex_core::config::Config;
+use codex_core::config_types::McpServerTransportConfig;
 use codex_core::config_types::ReasoningSummaryFormat;
 use codex_core::plan_tool::PlanItemArg;
 use codex_core::plan_tool::StepStatus;
@@ -25,9 +27,13 @@ use codex_core::plan_tool::UpdatePlanArgs;
 use codex_core::project_doc::discover_project_doc_paths;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::McpInvocation;
+use codex_core::protocol::ReviewOutputEvent;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::protocol::TokenUsage;
+use codex_core::protocol::WorktreeInfo;
+use codex_core::review::format_code_location;
+use codex_core::review::sort_findings_by_priority;
 use codex_protocol::mcp_protocol::ConversationId;
 use codex_protocol::num_format::format_with_separators;
 use codex_protocol::parse_command::ParsedCommand;
@@ -115,14 +121,14 @@ impl HistoryCell for UserHistoryCell {
         );
 
         for line in wrapped {
-            lines.push(vec!["▌ ".cyan().dim(), line.to_string().dim()].into());
+            lines.push(vec!["▌ ".accent().dim(), line.to_string().dim()].into());
         }
         lines
     }
 
     fn transcript_lines(&self) -> Vec<Line<'static>> {
         let mut lines: Vec<Line<'static>> = Vec::new();
-        lines.push("user".cyan().bold().into());
+        lines.push("user".accent().bold().into());
         lines.extend(self.message.lines().map(|l| l.to_string().into()));
         lines
     }
@@ -160,7 +166,7 @@ impl HistoryCell for AgentMessageCell {
     fn transcript_lines(&self) -> Vec<Line<'static>> {
         let mut out: Vec<Line<'static>> = Vec::new();
         if self.is_first_line {
-            out.push("codex".magenta().bold().into());
+            out.push("codex".brand().bold().into());
         }
         out.extend(self.lines.clone());
         out
@@ -244,7 +250,7 @@ impl HistoryCell for ExecCell {
             let cmd_display = strip_bash_lc_and_escape(&call.command);
             for (i, part) in cmd_display.lines().enumerate() {
                 if i == 0 {
-                    lines.push(vec!["$ ".magenta(), part.to_string().into()].into());
+                    lines.push(vec!["$ ".brand(), part.to_string().into()].into());
                 } else {
                     lines.push(vec!["    ".into(), part.to_string().into()].into());
                 }
@@ -257,10 +263,10 @@ impl HistoryCell for ExecCell {
                     .map(format_duration)
                     .unwrap_or_else(|| "unknown".to_string());
                 let mut result: Line = if output.exit_code == 0 {
-                    Line::from("✓".green().bold())
+                    Line::from("✓".success().bold())
                 } else {
                     Line::from(vec![
-                        "✗".red().bold(),
+                        "✗".error().bold(),
                         format!(" ({})", output.exit_code).into(),
                     ])
                 };
@@ -374,7 +380,7 @@ impl ExecCell {
             };
             for (title, line) in call_lines {
                 let line = Line::from(line);
-                let initial_indent = Line::from(vec![title.cyan(), " ".into()]);
+                let initial_indent = Line::from(vec![title.accent(), " ".into()]);
                 let subsequent_indent = " ".repeat(initial_indent.width()).into();
                 let wrapped = word_wrap_line(
                     &line,
@@ -398,8 +404,8 @@ impl ExecCell {
         };
         let success = call.output.as_ref().map(|o| o.exit_code == 0);
         let bullet = match success {
-            Some(true) => "•".green().bold(),
-            Some(false) => "•".red().bold(),
+            Some(true) => "•".success().bold(),
+            Some(false) => "•".error().bold(),
             None => spinner(call.start_time),
         };
         let title = if self.is_active() { "Running" } else { "Ran" };
@@ -622,6 +628,7 @@ pub(crate) fn new_session_info(
         history_entry_count: _,
         initial_messages: _,
         rollout_path: _,
+        worktree: _,
     } = event;
     if is_first_event {
         let cwd_str = match relativize_to_home(&config.cwd) {
@@ -671,7 +678,7 @@ pub(crate) fn new_session_info(
         PlainHistoryCell { lines: Vec::new() }
     } else {
         let lines = vec![
-            "model changed:".magenta().bold().into(),
+            "model changed:".brand().bold().into(),
             format!("requested: {}", config.model).into(),
             format!("used: {model}").into(),
         ];
@@ -679,6 +686,37 @@ pub(crate) fn new_session_info(
     }
 }
 
+pub(crate) fn new_worktree_info(worktree: &WorktreeInfo) -> PlainHistoryCell {
+    let lines: Vec<Line<'static>> = vec![
+        Line::from(vec![
+            "worktree".brand(),
+            " working on branch ".dim(),
+            worktree.branch.clone().into(),
+            " in ".dim(),
+            worktree.path.display().to_string().into(),
+        ]),
+        Line::from(
+            "  /quit to merge, cherry-pick or discard the changes"
+                .to_string()
+                .dim(),
+        ),
+    ];
+    PlainHistoryCell { lines }
+}
+
+pub(crate) fn new_worktree_resolved(summary: String) -> PlainHistoryCell {
+    let lines: Vec<Line<'static>> = vec![Line::from(vec!["worktree ".brand(), summary.into()])];
+    PlainHistoryCell { lines }
+}
+
+pub(crate) fn new_checkpoint_restored(turn: usize) -> PlainHistoryCell {
+    let lines: Vec<Line<'static>> = vec![Line::from(vec![
+        "undo ".brand(),
+        format!("restored files to their state before message {}", turn + 1).into(),
+    ])];
+    PlainHistoryCell { lines }
+}
+
 pub(crate) fn new_user_prompt(message: String) -> UserHistoryCell {
     UserHistoryCell { message }
 }
@@ -712,7 +750,7 @@ fn spinner(start_time: Option<Instant>) -> Span<'static> {
 }
 
 pub(crate) fn new_active_mcp_tool_call(invocation: McpInvocation) -> PlainHistoryCell {
-    let title_line = Line::from(vec!["tool".magenta(), " running...".dim()]);
+    let title_line = Line::from(vec!["tool".brand(), " running...".dim()]);
     let lines: Vec<Line> = vec![title_line, format_mcp_invocation(invocation)];
 
     PlainHistoryCell { lines }
@@ -723,6 +761,37 @@ pub(crate) fn new_web_search_call(query: String) -> PlainHistoryCell {
     PlainHistoryCell { lines }
 }
 
+pub(crate) fn new_delegate_begin(tasks: &[String]) -> PlainHistoryCell {
+    let mut lines: Vec<Line<'static>> = vec![Line::from(vec![
+        "delegate".brand(),
+        format!(" started {} workers", tasks.len()).dim(),
+    ])];
+    for (index, prompt) in tasks.iter().enumerate() {
+        let summary = prompt.lines().next().unwrap_or_default().to_string();
+        lines.push(Line::from(vec![
+            format!("  {}. ", index + 1).dim(),
+            summary.into(),
+        ]));
+    }
+    PlainHistoryCell { lines }
+}
+
+pub(crate) fn new_delegate_end(results: &[Result<String, String>]) -> PlainHistoryCell {
+    let mut lines: Vec<Line<'static>> =
+        vec![Line::from(vec!["delegate".brand(), " finished".dim()])];
+    for (index, result) in results.iter().enumerate() {
+        let worker = format!("  {}. ", index + 1).dim();
+        lines.push(match result {
+            Ok(message) => {
+                let summary = message.lines().next().unwrap_or_default().to_string();
+                Line::from(vec![worker, "✓ ".success(), summary.into()])
+            }
+            Err(error) => Line::from(vec![worker, "✗ ".error(), error.clone().error()]),
+        });
+    }
+    PlainHistoryCell { lines }
+}
+
 /// If the first content is an image, return a new cell with the image.
 /// TODO(rgwood-dd): Handle images properly even if they're not the first result.
 fn try_new_completed_mcp_tool_call_with_image_output(
@@ -777,12 +846,12 @@ pub(crate) fn new_completed_mcp_tool_call(
     let duration = format_duration(duration);
     let status_str = if success { "success" } else { "failed" };
     let title_line = Line::from(vec![
-        "tool".magenta(),
+        "tool".brand(),
         " ".into(),
         if success {
-            status_str.green()
+            status_str.success()
         } else {
-            status_str.red()
+            status_str.error()
         },
         format!(", duration: {duration}").dim(),
     ]);
@@ -829,7 +898,7 @@ pub(crate) fn new_completed_mcp_tool_call(
             }
         }
         Err(e) => {
-            lines.push(vec!["Error: ".red().bold(), e.into()].into());
+            lines.push(vec!["Error: ".error().bold(), e.into()].into());
         }
     };
 
@@ -842,7 +911,7 @@ pub(crate) fn new_status_output(
     session_id: &Option<ConversationId>,
 ) -> PlainHistoryCell {
     let mut lines: Vec<Line<'static>> = Vec::new();
-    lines.push("/status".magenta().into());
+    lines.push("/status".brand().into());
 
     let config_entries = create_config_summary_entries(config);
     let lookup = |k: &str| -> String {
@@ -1006,7 +1075,7 @@ pub(crate) fn new_status_output(
 /// Render a summary of configured MCP servers from the current `Config`.
 pub(crate) fn empty_mcp_output() -> PlainHistoryCell {
     let lines: Vec<Line<'static>> = vec![
-        "/mcp".magenta().into(),
+        "/mcp".brand().into(),
         "".into(),
         vec!["🔌  ".into(), "MCP Tools".bold()].into(),
         "".into(),
@@ -1028,7 +1097,7 @@ pub(crate) fn new_mcp_tools_output(
     tools: std::collections::HashMap<String, mcp_types::Tool>,
 ) -> PlainHistoryCell {
     let mut lines: Vec<Line<'static>> = vec![
-        "/mcp".magenta().into(),
+        "/mcp".brand().into(),
         "".into(),
         vec!["🔌  ".into(), "MCP Tools".bold()].into(),
         "".into(),
@@ -1051,10 +1120,17 @@ pub(crate) fn new_mcp_tools_output(
 
         lines.push(vec!["  • Server: ".into(), server.clone().into()].into());
 
-        if !cfg.command.is_empty() {
-            let cmd_display = format!("{} {}", cfg.command, cfg.args.join(" "));
+        match &cfg.transport {
+            McpServerTransportConfig::Stdio { command, args, .. } => {
+                if !command.is_empty() {
+                    let cmd_display = format!("{command} {}", args.join(" "));
 
-            lines.push(vec!["    • Command: ".into(), cmd_display.into()].into());
+                    lines.push(vec!["    • Command: ".into(), cmd_display.into()].into());
+                }
+            }
+            McpServerTransportConfig::Http { url, .. } => {
+                lines.push(vec!["    • URL: ".into(), url.clone().into()].into());
+            }
         }
 
         if names.is_empty() {
@@ -1082,7 +1158,7 @@ pub(crate) fn new_error_event(message: String) -> PlainHistoryCell {
     // Use a hair space (U+200A) to create a subtle, near-invisible separation
     // before the text. VS16 is intentionally omitted to keep spacing tighter
     // in terminals like Ghostty.
-    let lines: Vec<Line<'static>> = vec![vec![format!("■ {message}").red()].into()];
+    let lines: Vec<Line<'static>> = vec![vec![format!("■ {message}").error()].into()];
     PlainHistoryCell { lines }
 }
 
@@ -1116,7 +1192,7 @@ impl HistoryCell for PlanUpdateCell {
         let render_step = |status: &StepStatus, text: &str| -> Vec<Line<'static>> {
             let (box_str, step_style) = match status {
                 StepStatus::Completed => ("✔ ", Style::default().crossed_out().dim()),
-                StepStatus::InProgress => ("□ ", Style::default().cyan().bold()),
+                StepStatus::InProgress => ("□ ", Style::default().accent().bold()),
                 StepStatus::Pending => ("□ ", Style::default().dim()),
             };
             let wrap_width = (width as usize)
@@ -1157,6 +1233,85 @@ impl HistoryCell for PlanUpdateCell {
     }
 }
 
+pub(crate) fn new_review_started(user_facing_hint: &str) -> PlainHistoryCell {
+    let lines: Vec<Line<'static>> = vec![
+        vec![
+            "• ".into(),
+            "Reviewing ".bold(),
+            user_facing_hint.to_string().into(),
+        ]
+        .into(),
+    ];
+    PlainHistoryCell { lines }
+}
+
+/// Render the result of a review session: the overall verdict followed by
+/// the findings, most urgent first, each with a `path:line` location relative
+/// to `cwd`.
+pub(crate) fn new_review_output(review_output: ReviewOutputEvent, cwd: &Path) -> ReviewOutputCell {
+    let mut review_output = review_output;
+    sort_findings_by_priority(&mut review_output.findings);
+    ReviewOutputCell {
+        review_output,
+        cwd: cwd.to_path_buf(),
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ReviewOutputCell {
+    review_output: ReviewOutputEvent,
+    cwd: PathBuf,
+}
+
+impl HistoryCell for ReviewOutputCell {
+    fn display_lines(&self, width: u16) -> Vec<Line<'static>> {
+        let wrap_width = width.saturating_sub(4).max(1) as usize;
+        let wrap = |text: &str, style: Style| -> Vec<Line<'static>> {
+            textwrap::wrap(text, wrap_width)
+                .into_iter()
+                .map(|s| s.to_string().set_style(style).into())
+                .collect()
+        };
+
+        let ReviewOutputEvent {
+            findings,
+            overall_correctness,
+            overall_explanation,
+            overall_confidence_score: _,
+        } = &self.review_output;
+
+        let mut lines: Vec<Line<'static>> = vec![];
+        let mut header: Vec<Span<'static>> = vec!["• ".into(), "Code review".bold()];
+        if !overall_correctness.is_empty() {
+            header.push(": ".into());
+            header.push(overall_correctness.clone().into());
+        }
+        lines.push(header.into());
+
+        let mut indented_lines = vec![];
+        let explanation = overall_explanation.trim();
+        if !explanation.is_empty() {
+            indented_lines.extend(wrap(explanation, Style::default().dim().italic()));
+        }
+        if findings.is_empty() {
+            indented_lines.push(Line::from("(no findings)".dim().italic()));
+        }
+        for finding in findings {
+            indented_lines.extend(wrap(&finding.title, Style::default().bold()));
+            let location = format_code_location(&finding.code_location, &self.cwd);
+            indented_lines.push(Line::from(vec![
+                "  ".into(),
+                location.accent().underlined(),
+            ]));
+            let body = wrap(finding.body.trim(), Style::default().dim());
+            indented_lines.extend(prefix_lines(body, "  ".into(), "  ".into()));
+        }
+        lines.extend(prefix_lines(indented_lines, "  └ ".into(), "    ".into()));
+
+        lines
+    }
+}
+
 /// Create a new `PendingPatch` cell that lists the file‑level summary of
 /// a proposed patch. The summary lines should already be formatted (e.g.
 /// "A path/to/file.rs").
@@ -1176,7 +1331,7 @@ pub(crate) fn new_patch_apply_failure(stderr: String) -> PlainHistoryCell {
     let mut lines: Vec<Line<'static>> = Vec::new();
 
     // Failure title
-    lines.push(Line::from("✘ Failed to apply patch".magenta().bold()));
+    lines.push(Line::from("✘ Failed to apply patch".brand().bold()));
 
     if !stderr.trim().is_empty() {
         lines.extend(output_lines(
@@ -1223,7 +1378,7 @@ pub(crate) fn new_reasoning_block(
     config: &Config,
 ) -> TranscriptOnlyHistoryCell {
     let mut lines: Vec<Line<'static>> = Vec::new();
-    lines.push(Line::from("thinking".magenta().italic()));
+    lines.push(Line::from("thinking".brand().italic()));
     append_markdown(&full_reasoning_buffer, &mut lines, config);
     TranscriptOnlyHistoryCell { lines }
 }
@@ -1251,11 +1406,11 @@ pub(crate) fn new_reasoning_summary_block(
                     let summary_buffer = full_reasoning_buffer[after_close_idx..].to_string();
 
                     let mut header_lines: Vec<Line<'static>> = Vec::new();
-                    header_lines.push(Line::from("Thinking".magenta().italic()));
+                    header_lines.push(Line::from("Thinking".brand().italic()));
                     append_markdown(&header_buffer, &mut header_lines, config);
 
                     let mut summary_lines: Vec<Line<'static>> = Vec::new();
-                    summary_lines.push(Line::from("Thinking".magenta().bold()));
+                    summary_lines.push(Line::from("Thinking".brand().bold()));
                     append_markdown(&summary_buffer, &mut summary_lines, config);
 
                     return vec![
@@ -1355,9 +1510,9 @@ fn format_mcp_invocation<'a>(invocation: McpInvocation) -> Line<'a> {
         .unwrap_or_default();
 
     let invocation_spans = vec![
-        invocation.server.clone().cyan(),
+        invocation.server.clone().accent(),
         ".".into(),
-        invocation.tool.cyan(),
+        invocation.tool.accent(),
         "(".into(),
         args_str.dim(),
         ")".into(),
@@ -1930,4 +2085,49 @@ mod tests {
             vec!["codex", "Thinking", "We should fix the bug next."]
         )
     }
+
+    #[test]
+    fn review_output_lists_findings_by_priority() {
+        use codex_core::protocol::ReviewCodeLocation;
+        use codex_core::protocol::ReviewFinding;
+        use codex_core::protocol::ReviewLineRange;
+
+        let finding =
+            |title: &str, priority: i32, path: &str, start: u32, end: u32| ReviewFinding {
+                title: title.to_string(),
+                body: format!("Details for {title}."),
+                confidence_score: 0.8,
+                priority,
+                code_location: ReviewCodeLocation {
+                    absolute_file_path: PathBuf::from(path),
+                    line_range: ReviewLineRange { start, end },
+                },
+            };
+        let cell = new_review_output(
+            ReviewOutputEvent {
+                findings: vec![
+                    finding("[P2] Prefer early return", 2, "/repo/src/main.rs", 8, 8),
+                    finding("[P0] Off-by-one", 0, "/repo/src/lib.rs", 3, 5),
+                ],
+                overall_correctness: "patch is incorrect".to_string(),
+                overall_explanation: "The loop skips the last element.".to_string(),
+                overall_confidence_score: 0.9,
+            },
+            Path::new("/repo"),
+        );
+
+        assert_eq!(
+            render_lines(&cell.display_lines(80)),
+            vec![
+                "• Code review: patch is incorrect",
+                "  └ The loop skips the last element.",
+                "    [P0] Off-by-one",
+                "      src/lib.rs:3-5",
+                "      Details for [P0] Off-by-one.",
+                "    [P2] Prefer early return",
+                "      src/main.rs:8",
+                "      Details for [P2] Prefer early return.",
+            ]
+        );
+    }
 }
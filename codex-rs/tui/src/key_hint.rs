@@ -1,4 +1,4 @@
-use ratatui::style::Color;
+use crate::theme::theme;
 use ratatui::style::Style;
 use ratatui::text::Span;
 use std::fmt::Display;
@@ -25,7 +25,7 @@ const SHIFT_PREFIX: &str = "⇧";
 const SHIFT_PREFIX: &str = "Shift+";
 
 fn key_hint_style() -> Style {
-    Style::default().fg(Color::Cyan)
+    Style::default().fg(theme().accent)
 }
 
 fn modifier_span(prefix: &str, key: impl Display) -> Span<'static> {
@@ -47,3 +47,22 @@ pub(crate) fn shift(key: impl Display) -> Span<'static> {
 pub(crate) fn plain(key: impl Display) -> Span<'static> {
     Span::styled(format!("{key}"), key_hint_style())
 }
+
+/// Hint for a key pressed together with any of Ctrl, Alt and Shift.
+pub(crate) fn chord(ctrl: bool, alt: bool, shift: bool, key: impl Display) -> Span<'static> {
+    let mut prefix = String::new();
+    for (held, modifier) in [
+        (ctrl, CTRL_PREFIX),
+        (alt, ALT_PREFIX),
+        (shift, SHIFT_PREFIX),
+    ] {
+        if held {
+            prefix.push_str(modifier);
+        }
+    }
+    if prefix.is_empty() {
+        plain(key)
+    } else {
+        modifier_span(&prefix, key)
+    }
+}
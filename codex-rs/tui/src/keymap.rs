@@ -0,0 +1,428 @@
+//! Key bindings of the TUI, configured by the `[tui.keymap]` table.
+//!
+//! The keymap is parsed once at startup and then read through [`keymap()`]
+//! by the widgets that handle the bound actions. Until it is set (e.g. in
+//! tests) the default bindings apply.
+
+use std::sync::OnceLock;
+
+use codex_core::config_types::EditingMode;
+use codex_core::config_types::KeyBindingsToml;
+use codex_core::config_types::TuiKeymap;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyModifiers;
+use ratatui::text::Span;
+
+use crate::key_hint;
+
+static KEYMAP: OnceLock<Keymap> = OnceLock::new();
+
+/// Sets the keymap used by the TUI. Only the first call has an effect.
+pub(crate) fn set_keymap(keymap: Keymap) {
+    let _ = KEYMAP.set(keymap);
+}
+
+/// Returns the keymap used by the TUI.
+pub(crate) fn keymap() -> &'static Keymap {
+    KEYMAP.get_or_init(Keymap::default)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Keymap {
+    pub(crate) transcript: KeyBindings,
+    pub(crate) backtrack: KeyBindings,
+    pub(crate) submit: KeyBindings,
+    pub(crate) approve: KeyBindings,
+    pub(crate) approve_for_session: KeyBindings,
+    pub(crate) deny: KeyBindings,
+    pub(crate) newline: KeyBindings,
+    pub(crate) line_start: KeyBindings,
+    pub(crate) line_end: KeyBindings,
+    pub(crate) word_left: KeyBindings,
+    pub(crate) word_right: KeyBindings,
+    pub(crate) delete_word_backward: KeyBindings,
+    pub(crate) delete_word_forward: KeyBindings,
+    pub(crate) kill_line_start: KeyBindings,
+    pub(crate) kill_line_end: KeyBindings,
+    pub(crate) scroll_up: KeyBindings,
+    pub(crate) scroll_down: KeyBindings,
+    pub(crate) page_up: KeyBindings,
+    pub(crate) page_down: KeyBindings,
+    pub(crate) scroll_to_top: KeyBindings,
+    pub(crate) scroll_to_bottom: KeyBindings,
+    pub(crate) close_pager: KeyBindings,
+    pub(crate) editing_mode: EditingMode,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let keys = |keys: &[(KeyCode, KeyModifiers)]| {
+            KeyBindings(
+                keys.iter()
+                    .map(|&(code, modifiers)| KeyBinding::new(code, modifiers))
+                    .collect(),
+            )
+        };
+        let key = |code: KeyCode, modifiers: KeyModifiers| keys(&[(code, modifiers)]);
+        const NONE: KeyModifiers = KeyModifiers::NONE;
+        const CTRL: KeyModifiers = KeyModifiers::CONTROL;
+        const ALT: KeyModifiers = KeyModifiers::ALT;
+        Self {
+            transcript: key(KeyCode::Char('t'), CTRL),
+            backtrack: key(KeyCode::Esc, NONE),
+            submit: key(KeyCode::Enter, NONE),
+            approve: key(KeyCode::Char('y'), NONE),
+            approve_for_session: key(KeyCode::Char('a'), NONE),
+            deny: key(KeyCode::Char('n'), NONE),
+            newline: keys(&[(KeyCode::Char('j'), CTRL), (KeyCode::Char('m'), CTRL)]),
+            line_start: keys(&[(KeyCode::Char('a'), CTRL), (KeyCode::Home, NONE)]),
+            line_end: keys(&[(KeyCode::Char('e'), CTRL), (KeyCode::End, NONE)]),
+            word_left: keys(&[
+                (KeyCode::Char('b'), ALT),
+                (KeyCode::Left, ALT),
+                (KeyCode::Left, CTRL),
+            ]),
+            word_right: keys(&[
+                (KeyCode::Char('f'), ALT),
+                (KeyCode::Right, ALT),
+                (KeyCode::Right, CTRL),
+            ]),
+            delete_word_backward: keys(&[
+                (KeyCode::Char('w'), CTRL),
+                (KeyCode::Backspace, ALT),
+                (KeyCode::Char('h'), CTRL | ALT),
+            ]),
+            delete_word_forward: key(KeyCode::Delete, ALT),
+            kill_line_start: key(KeyCode::Char('u'), CTRL),
+            kill_line_end: key(KeyCode::Char('k'), CTRL),
+            scroll_up: key(KeyCode::Up, NONE),
+            scroll_down: key(KeyCode::Down, NONE),
+            page_up: key(KeyCode::PageUp, NONE),
+            page_down: keys(&[(KeyCode::PageDown, NONE), (KeyCode::Char(' '), NONE)]),
+            scroll_to_top: key(KeyCode::Home, NONE),
+            scroll_to_bottom: key(KeyCode::End, NONE),
+            close_pager: keys(&[(KeyCode::Char('q'), NONE), (KeyCode::Char('c'), CTRL)]),
+            editing_mode: EditingMode::Emacs,
+        }
+    }
+}
+
+impl Keymap {
+    /// Builds the keymap from the `[tui.keymap]` table, keeping the default
+    /// bindings of the actions it does not set.
+    pub(crate) fn from_config(config: &TuiKeymap) -> Result<Self, String> {
+        let defaults = Self::default();
+        let bindings = |name: &str, keys: &Option<KeyBindingsToml>, default: KeyBindings| {
+            let Some(keys) = keys else {
+                return Ok(default);
+            };
+            keys.keys()
+                .iter()
+                .map(|key| {
+                    KeyBinding::parse(key)
+                        .map_err(|e| format!("invalid key `{key}` for tui.keymap.{name}: {e}"))
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(KeyBindings)
+        };
+        Ok(Self {
+            transcript: bindings("transcript", &config.transcript, defaults.transcript)?,
+            backtrack: bindings("backtrack", &config.backtrack, defaults.backtrack)?,
+            submit: bindings("submit", &config.submit, defaults.submit)?,
+            approve: bindings("approve", &config.approve, defaults.approve)?,
+            approve_for_session: bindings(
+                "approve_for_session",
+                &config.approve_for_session,
+                defaults.approve_for_session,
+            )?,
+            deny: bindings("deny", &config.deny, defaults.deny)?,
+            newline: bindings("newline", &config.newline, defaults.newline)?,
+            line_start: bindings("line_start", &config.line_start, defaults.line_start)?,
+            line_end: bindings("line_end", &config.line_end, defaults.line_end)?,
+            word_left: bindings("word_left", &config.word_left, defaults.word_left)?,
+            word_right: bindings("word_right", &config.word_right, defaults.word_right)?,
+            delete_word_backward: bindings(
+                "delete_word_backward",
+                &config.delete_word_backward,
+                defaults.delete_word_backward,
+            )?,
+            delete_word_forward: bindings(
+                "delete_word_forward",
+                &config.delete_word_forward,
+                defaults.delete_word_forward,
+            )?,
+            kill_line_start: bindings(
+                "kill_line_start",
+                &config.kill_line_start,
+                defaults.kill_line_start,
+            )?,
+            kill_line_end: bindings(
+                "kill_line_end",
+                &config.kill_line_end,
+                defaults.kill_line_end,
+            )?,
+            scroll_up: bindings("scroll_up", &config.scroll_up, defaults.scroll_up)?,
+            scroll_down: bindings("scroll_down", &config.scroll_down, defaults.scroll_down)?,
+            page_up: bindings("page_up", &config.page_up, defaults.page_up)?,
+            page_down: bindings("page_down", &config.page_down, defaults.page_down)?,
+            scroll_to_top: bindings(
+                "scroll_to_top",
+                &config.scroll_to_top,
+                defaults.scroll_to_top,
+            )?,
+            scroll_to_bottom: bindings(
+                "scroll_to_bottom",
+                &config.scroll_to_bottom,
+                defaults.scroll_to_bottom,
+            )?,
+            close_pager: bindings("close_pager", &config.close_pager, defaults.close_pager)?,
+            editing_mode: config.editing_mode,
+        })
+    }
+}
+
+/// The keys bound to one action. An empty list leaves the action unbound.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct KeyBindings(Vec<KeyBinding>);
+
+impl KeyBindings {
+    pub(crate) fn matches(&self, event: &KeyEvent) -> bool {
+        self.0.iter().any(|binding| binding.matches(event))
+    }
+
+    /// Hint for the first key bound to the action, if any.
+    pub(crate) fn hint(&self) -> Option<Span<'static>> {
+        self.0.first().map(KeyBinding::hint)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub(crate) const fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+
+    /// Parses keys such as `y`, `esc`, `ctrl-t`, `alt-enter` or `ctrl+shift-up`.
+    pub(crate) fn parse(key: &str) -> Result<Self, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = key.trim();
+        loop {
+            let lowercase = rest.to_ascii_lowercase();
+            let Some((name, modifier)) = [
+                ("ctrl", KeyModifiers::CONTROL),
+                ("control", KeyModifiers::CONTROL),
+                ("alt", KeyModifiers::ALT),
+                ("meta", KeyModifiers::ALT),
+                ("option", KeyModifiers::ALT),
+                ("shift", KeyModifiers::SHIFT),
+            ]
+            .into_iter()
+            .find(|(name, _)| {
+                lowercase.starts_with(name)
+                    && matches!(lowercase[name.len()..].chars().next(), Some('-' | '+'))
+                    && lowercase.len() > name.len() + 1
+            }) else {
+                break;
+            };
+            modifiers |= modifier;
+            rest = &rest[name.len() + 1..];
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c.to_ascii_lowercase()),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "space" => KeyCode::Char(' '),
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "page-up" => KeyCode::PageUp,
+                "pagedown" | "page-down" => KeyCode::PageDown,
+                "" => return Err("missing key".to_string()),
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=24) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key name `{rest}`")),
+                },
+            },
+        };
+        Ok(Self { code, modifiers })
+    }
+
+    /// Character keys match regardless of case and of Shift, so that `y`
+    /// also matches `Y`.
+    pub(crate) fn matches(&self, event: &KeyEvent) -> bool {
+        match (self.code, event.code) {
+            (KeyCode::Char(bound), KeyCode::Char(pressed)) => {
+                bound == pressed.to_ascii_lowercase()
+                    && self.modifiers.difference(KeyModifiers::SHIFT)
+                        == event.modifiers.difference(KeyModifiers::SHIFT)
+            }
+            (bound, pressed) => bound == pressed && self.modifiers == event.modifiers,
+        }
+    }
+
+    pub(crate) fn hint(&self) -> Span<'static> {
+        let key = match self.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(c) if self.modifiers.is_empty() => c.to_string(),
+            KeyCode::Char(c) => c.to_ascii_uppercase().to_string(),
+            KeyCode::Enter => "⏎".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::PageUp => "PgUp".to_string(),
+            KeyCode::PageDown => "PgDn".to_string(),
+            KeyCode::BackTab => "Shift+Tab".to_string(),
+            KeyCode::F(n) => format!("F{n}"),
+            other => format!("{other:?}"),
+        };
+        key_hint::chord(
+            self.modifiers.contains(KeyModifiers::CONTROL),
+            self.modifiers.contains(KeyModifiers::ALT),
+            self.modifiers.contains(KeyModifiers::SHIFT),
+            key,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn parses_keys_with_modifiers() {
+        assert_eq!(
+            KeyBinding::parse("ctrl-t"),
+            Ok(KeyBinding::new(KeyCode::Char('t'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(
+            KeyBinding::parse("Ctrl+Shift-Up"),
+            Ok(KeyBinding::new(
+                KeyCode::Up,
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT
+            ))
+        );
+        assert_eq!(
+            KeyBinding::parse("alt-enter"),
+            Ok(KeyBinding::new(KeyCode::Enter, KeyModifiers::ALT))
+        );
+        assert_eq!(
+            KeyBinding::parse("ctrl--"),
+            Ok(KeyBinding::new(KeyCode::Char('-'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(
+            KeyBinding::parse("f5"),
+            Ok(KeyBinding::new(KeyCode::F(5), KeyModifiers::NONE))
+        );
+        assert!(KeyBinding::parse("ctrl-").is_err());
+        assert!(KeyBinding::parse("hyper-x").is_err());
+    }
+
+    #[test]
+    fn character_keys_ignore_case_and_shift() {
+        let binding = KeyBinding::parse("y").expect("valid key");
+        assert!(binding.matches(&press(KeyCode::Char('y'), KeyModifiers::NONE)));
+        assert!(binding.matches(&press(KeyCode::Char('Y'), KeyModifiers::SHIFT)));
+        assert!(!binding.matches(&press(KeyCode::Char('y'), KeyModifiers::CONTROL)));
+
+        let enter = KeyBinding::parse("enter").expect("valid key");
+        assert!(!enter.matches(&press(KeyCode::Enter, KeyModifiers::SHIFT)));
+    }
+
+    #[test]
+    fn config_overrides_only_the_actions_it_sets() {
+        let keymap = Keymap::from_config(&TuiKeymap {
+            transcript: Some(KeyBindingsToml::Many(vec![
+                "ctrl-o".to_string(),
+                "f2".to_string(),
+            ])),
+            ..Default::default()
+        })
+        .expect("valid keymap");
+
+        assert!(
+            keymap
+                .transcript
+                .matches(&press(KeyCode::F(2), KeyModifiers::NONE))
+        );
+        assert!(
+            !keymap
+                .transcript
+                .matches(&press(KeyCode::Char('t'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(keymap.backtrack, Keymap::default().backtrack);
+
+        let error = Keymap::from_config(&TuiKeymap {
+            deny: Some(KeyBindingsToml::One("ctrl-nope".to_string())),
+            ..Default::default()
+        })
+        .expect_err("invalid key");
+        assert_eq!(
+            error,
+            "invalid key `ctrl-nope` for tui.keymap.deny: unknown key name `nope`"
+        );
+    }
+
+    #[test]
+    fn composer_and_pager_actions_are_configurable() {
+        let keymap = Keymap::from_config(&TuiKeymap {
+            newline: Some(KeyBindingsToml::One("shift-enter".to_string())),
+            close_pager: Some(KeyBindingsToml::One("esc".to_string())),
+            ..Default::default()
+        })
+        .expect("valid keymap");
+
+        assert!(
+            keymap
+                .newline
+                .matches(&press(KeyCode::Enter, KeyModifiers::SHIFT))
+        );
+        assert!(
+            !keymap
+                .newline
+                .matches(&press(KeyCode::Char('j'), KeyModifiers::CONTROL))
+        );
+        assert!(
+            keymap
+                .close_pager
+                .matches(&press(KeyCode::Esc, KeyModifiers::NONE))
+        );
+        assert!(
+            !keymap
+                .close_pager
+                .matches(&press(KeyCode::Char('q'), KeyModifiers::NONE))
+        );
+        assert_eq!(keymap.page_down, Keymap::default().page_down);
+    }
+
+    #[test]
+    fn hints_match_the_built_in_key_hints() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.transcript.hint(), Some(key_hint::ctrl('T')));
+        assert_eq!(keymap.submit.hint(), Some(key_hint::plain('⏎')));
+        assert_eq!(keymap.backtrack.hint(), Some(key_hint::plain("Esc")));
+    }
+}
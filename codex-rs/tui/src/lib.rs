@@ -8,6 +8,7 @@ use codex_core::AuthManager;
 use codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID;
 use codex_core::CodexAuth;
 use codex_core::RolloutRecorder;
+use codex_core::SessionSearch;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::config::ConfigToml;
@@ -45,6 +46,7 @@ mod get_git_diff;
 mod history_cell;
 pub mod insert_history;
 mod key_hint;
+mod keymap;
 pub mod live_wrap;
 mod markdown;
 mod markdown_render;
@@ -59,7 +61,9 @@ mod shimmer;
 mod slash_command;
 mod status_indicator_widget;
 mod streaming;
+mod syntax_highlight;
 mod text_formatting;
+mod theme;
 mod tui;
 mod ui_consts;
 mod user_approval_widget;
@@ -136,6 +140,7 @@ pub async fn run_main(
         include_view_image_tool: None,
         show_raw_agent_reasoning: cli.oss.then_some(true),
         tools_web_search_request: cli.web_search.then_some(true),
+        worktree: cli.worktree.then_some(true),
     };
     let raw_overrides = cli.config_overrides.raw_overrides.clone();
     let overrides_cli = codex_common::CliConfigOverrides { raw_overrides };
@@ -161,6 +166,21 @@ pub async fn run_main(
         }
     };
 
+    #[allow(clippy::print_stderr)]
+    match (
+        keymap::Keymap::from_config(&config.tui.keymap),
+        theme::Theme::from_config(&config.tui.theme),
+    ) {
+        (Ok(keymap), Ok(theme)) => {
+            keymap::set_keymap(keymap);
+            theme::set_theme(theme);
+        }
+        (Err(err), _) | (_, Err(err)) => {
+            eprintln!("Error loading configuration: {err}");
+            std::process::exit(1);
+        }
+    }
+
     // we load config.toml here to determine project state.
     #[allow(clippy::print_stderr)]
     let config_toml = {
@@ -268,6 +288,7 @@ async fn run_ratatui_app(
         tracing::error!("panic: {info}");
         prev_hook(info);
     }));
+    syntax_highlight::set_theme(config.tui.syntax_theme);
     let mut terminal = tui::init()?;
     terminal.clear()?;
 
@@ -354,7 +375,17 @@ async fn run_ratatui_app(
             }
         }
     } else if cli.resume_last {
-        match RolloutRecorder::list_conversations(&config.codex_home, 1, None).await {
+        let page = match cli.resume_search.clone() {
+            Some(query) => {
+                let search = SessionSearch {
+                    query: Some(query),
+                    ..Default::default()
+                };
+                RolloutRecorder::search_conversations(&config.codex_home, &search, 1, None).await
+            }
+            None => RolloutRecorder::list_conversations(&config.codex_home, 1, None).await,
+        };
+        match page {
             Ok(page) => page
                 .items
                 .first()
@@ -363,7 +394,13 @@ async fn run_ratatui_app(
             Err(_) => resume_picker::ResumeSelection::StartFresh,
         }
     } else if cli.resume_picker {
-        match resume_picker::run_resume_picker(&mut tui, &config.codex_home).await? {
+        match resume_picker::run_resume_picker(
+            &mut tui,
+            &config.codex_home,
+            cli.resume_search.clone(),
+        )
+        .await?
+        {
             resume_picker::ResumeSelection::Exit => {
                 restore();
                 session_log::log_session_end();
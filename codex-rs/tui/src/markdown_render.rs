@@ -1,4 +1,7 @@
 use crate::citation_regex::CITATION_REGEX;
+use crate::syntax_highlight::Highlighter;
+use crate::syntax_highlight::Palette;
+use crate::syntax_highlight::current_palette;
 use pulldown_cmark::CodeBlockKind;
 use pulldown_cmark::CowStr;
 use pulldown_cmark::Event;
@@ -37,7 +40,7 @@ pub(crate) fn render_markdown_text(input: &str) -> Text<'static> {
     let mut options = Options::empty();
     options.insert(Options::ENABLE_STRIKETHROUGH);
     let parser = Parser::new_ext(input, options);
-    let mut w = Writer::new(parser, None, None);
+    let mut w = Writer::new(parser, None, None, current_palette());
     w.run();
     w.text
 }
@@ -54,6 +57,7 @@ pub(crate) fn render_markdown_text_with_citations(
         parser,
         scheme.map(|s| s.to_string()),
         Some(cwd.to_path_buf()),
+        current_palette(),
     );
     w.run();
     w.text
@@ -75,13 +79,23 @@ where
     scheme: Option<String>,
     cwd: Option<std::path::PathBuf>,
     in_code_block: bool,
+    /// Colors for fenced code blocks, or `None` to leave them unhighlighted.
+    palette: Option<Palette>,
+    /// Highlighter for the fenced code block being written, if its language
+    /// is known and highlighting is enabled.
+    code_highlighter: Option<Highlighter>,
 }
 
 impl<'a, I> Writer<'a, I>
 where
     I: Iterator<Item = Event<'a>>,
 {
-    fn new(iter: I, scheme: Option<String>, cwd: Option<std::path::PathBuf>) -> Self {
+    fn new(
+        iter: I,
+        scheme: Option<String>,
+        cwd: Option<std::path::PathBuf>,
+        palette: Option<Palette>,
+    ) -> Self {
         Self {
             iter,
             text: Text::default(),
@@ -95,6 +109,8 @@ where
             scheme,
             cwd,
             in_code_block: false,
+            palette,
+            code_highlighter: None,
         }
     }
 
@@ -269,11 +285,15 @@ where
                     content = s;
                 }
             }
-            let span = Span::styled(
-                content,
-                self.inline_styles.last().copied().unwrap_or_default(),
-            );
-            self.push_span(span);
+            let style = self.inline_styles.last().copied().unwrap_or_default();
+            if let Some(highlighter) = self.code_highlighter.as_mut() {
+                for span in highlighter.highlight_line(&content) {
+                    let span_style = style.patch(span.style);
+                    self.push_span(span.style(span_style));
+                }
+            } else {
+                self.push_span(Span::styled(content, style));
+            }
         }
         self.needs_newline = false;
     }
@@ -354,11 +374,15 @@ where
         self.needs_newline = false;
     }
 
-    fn start_codeblock(&mut self, _lang: Option<String>, indent: Option<Span<'static>>) {
+    fn start_codeblock(&mut self, lang: Option<String>, indent: Option<Span<'static>>) {
         if !self.text.lines.is_empty() {
             self.push_blank_line();
         }
         self.in_code_block = true;
+        self.code_highlighter = lang
+            .as_deref()
+            .zip(self.palette)
+            .and_then(|(lang, palette)| Highlighter::for_info_string(lang, palette));
         self.indent_stack.push(IndentContext::new(
             vec![indent.unwrap_or_default()],
             None,
@@ -376,6 +400,7 @@ where
         // self.push_line("```".into());
         self.needs_newline = true;
         self.in_code_block = false;
+        self.code_highlighter = None;
         self.indent_stack.pop();
     }
 
@@ -1,10 +1,13 @@
 use pretty_assertions::assert_eq;
+use pulldown_cmark::Parser;
 use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::text::Span;
 use ratatui::text::Text;
 
+use crate::markdown_render::Writer;
 use crate::markdown_render::render_markdown_text;
+use crate::syntax_highlight::Palette;
 use insta::assert_snapshot;
 
 #[test]
@@ -658,6 +661,26 @@ fn code_block_unhighlighted() {
     assert_eq!(text, expected);
 }
 
+#[test]
+fn code_block_highlighted() {
+    let mut w = Writer::new(
+        Parser::new("```rust\nlet x = 1; // one\n```\n"),
+        None,
+        None,
+        Some(Palette::ansi()),
+    );
+    w.run();
+    let expected = Text::from_iter([Line::from_iter([
+        Span::from(""),
+        "let".magenta(),
+        " x = ".into(),
+        "1".cyan(),
+        "; ".into(),
+        "// one".dim().italic(),
+    ])]);
+    assert_eq!(w.text, expected);
+}
+
 #[test]
 fn code_block_multiple_lines_root() {
     let md = "```\nfirst\nsecond\n```\n";
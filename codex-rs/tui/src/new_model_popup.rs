@@ -1,3 +1,4 @@
+use crate::theme::ThemeStylize;
 use crate::tui::FrameRequester;
 use crate::tui::Tui;
 use crate::tui::TuiEvent;
@@ -100,8 +101,8 @@ impl WidgetRef for &ModelUpgradePopup {
             |index: usize, option: ModelUpgradeOption, text: &str| -> Line<'static> {
                 if self.highlighted == option {
                     Line::from(vec![
-                        format!("> {}. ", index + 1).cyan(),
-                        text.to_owned().cyan(),
+                        format!("> {}. ", index + 1).accent(),
+                        text.to_owned().accent(),
                     ])
                 } else {
                     format!("  {}. {text}", index + 1).into()
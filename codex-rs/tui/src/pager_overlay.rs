@@ -3,15 +3,16 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use crate::history_cell::HistoryCell;
+use crate::keymap::KeyBindings;
+use crate::keymap::keymap;
 use crate::render::line_utils::push_owned_lines;
+use crate::theme::ThemeStylize;
 use crate::tui;
 use crate::tui::TuiEvent;
-use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
-use ratatui::style::Color;
 use ratatui::style::Style;
 use ratatui::style::Styled;
 use ratatui::style::Stylize;
@@ -50,23 +51,46 @@ impl Overlay {
     }
 }
 
-// Common pager navigation hints rendered on the first line
-const PAGER_KEY_HINTS: &[(&str, &str)] = &[
-    ("↑/↓", "scroll"),
-    ("PgUp/PgDn", "page"),
-    ("Home/End", "jump"),
-];
+// Common pager navigation hints rendered on the first line, naming the first
+// key bound to each action.
+fn pager_key_hints() -> Vec<(String, &'static str)> {
+    let keymap = keymap();
+    let pair = |first: &KeyBindings, second: &KeyBindings| match (first.hint(), second.hint()) {
+        (Some(first), Some(second)) => Some(format!("{}/{}", first.content, second.content)),
+        (Some(only), None) | (None, Some(only)) => Some(only.content.into_owned()),
+        (None, None) => None,
+    };
+    [
+        (pair(&keymap.scroll_up, &keymap.scroll_down), "scroll"),
+        (pair(&keymap.page_up, &keymap.page_down), "page"),
+        (
+            pair(&keymap.scroll_to_top, &keymap.scroll_to_bottom),
+            "jump",
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(key, desc)| Some((key?, desc)))
+    .collect()
+}
+
+// Hint for the key closing the pager.
+fn close_pager_hint() -> Option<String> {
+    keymap()
+        .close_pager
+        .hint()
+        .map(|hint| hint.content.into_owned())
+}
 
 // Render a single line of key hints from (key, description) pairs.
-fn render_key_hints(area: Rect, buf: &mut Buffer, pairs: &[(&str, &str)]) {
-    let key_hint_style = Style::default().fg(Color::Cyan);
+fn render_key_hints<K: AsRef<str>>(area: Rect, buf: &mut Buffer, pairs: &[(K, &str)]) {
+    let key_hint_style = Style::default().accent();
     let mut spans: Vec<Span<'static>> = vec![" ".into()];
     let mut first = true;
     for (key, desc) in pairs {
         if !first {
             spans.push("   ".into());
         }
-        spans.push(Span::from(key.to_string()).set_style(key_hint_style));
+        spans.push(Span::from(key.as_ref().to_string()).set_style(key_hint_style));
         spans.push(" ".into());
         spans.push(Span::from(desc.to_string()));
         first = false;
@@ -186,54 +210,26 @@ impl PagerView {
     }
 
     fn handle_key_event(&mut self, tui: &mut tui::Tui, key_event: KeyEvent) -> Result<()> {
-        match key_event {
-            KeyEvent {
-                code: KeyCode::Up,
-                kind: KeyEventKind::Press | KeyEventKind::Repeat,
-                ..
-            } => {
-                self.scroll_offset = self.scroll_offset.saturating_sub(1);
-            }
-            KeyEvent {
-                code: KeyCode::Down,
-                kind: KeyEventKind::Press | KeyEventKind::Repeat,
-                ..
-            } => {
-                self.scroll_offset = self.scroll_offset.saturating_add(1);
-            }
-            KeyEvent {
-                code: KeyCode::PageUp,
-                kind: KeyEventKind::Press | KeyEventKind::Repeat,
-                ..
-            } => {
-                let area = self.scroll_area(tui.terminal.viewport_area);
-                self.scroll_offset = self.scroll_offset.saturating_sub(area.height as usize);
-            }
-            KeyEvent {
-                code: KeyCode::PageDown | KeyCode::Char(' '),
-                kind: KeyEventKind::Press | KeyEventKind::Repeat,
-                ..
-            } => {
-                let area = self.scroll_area(tui.terminal.viewport_area);
-                self.scroll_offset = self.scroll_offset.saturating_add(area.height as usize);
-            }
-            KeyEvent {
-                code: KeyCode::Home,
-                kind: KeyEventKind::Press | KeyEventKind::Repeat,
-                ..
-            } => {
-                self.scroll_offset = 0;
-            }
-            KeyEvent {
-                code: KeyCode::End,
-                kind: KeyEventKind::Press | KeyEventKind::Repeat,
-                ..
-            } => {
-                self.scroll_offset = usize::MAX;
-            }
-            _ => {
-                return Ok(());
-            }
+        if !matches!(key_event.kind, KeyEventKind::Press | KeyEventKind::Repeat) {
+            return Ok(());
+        }
+        let keymap = keymap();
+        if keymap.scroll_up.matches(&key_event) {
+            self.scroll_offset = self.scroll_offset.saturating_sub(1);
+        } else if keymap.scroll_down.matches(&key_event) {
+            self.scroll_offset = self.scroll_offset.saturating_add(1);
+        } else if keymap.page_up.matches(&key_event) {
+            let area = self.scroll_area(tui.terminal.viewport_area);
+            self.scroll_offset = self.scroll_offset.saturating_sub(area.height as usize);
+        } else if keymap.page_down.matches(&key_event) {
+            let area = self.scroll_area(tui.terminal.viewport_area);
+            self.scroll_offset = self.scroll_offset.saturating_add(area.height as usize);
+        } else if keymap.scroll_to_top.matches(&key_event) {
+            self.scroll_offset = 0;
+        } else if keymap.scroll_to_bottom.matches(&key_event) {
+            self.scroll_offset = usize::MAX;
+        } else {
+            return Ok(());
         }
         tui.frame_requester()
             .schedule_frame_in(Duration::from_millis(16));
@@ -422,8 +418,16 @@ impl TranscriptOverlay {
     fn render_hints(&self, area: Rect, buf: &mut Buffer) {
         let line1 = Rect::new(area.x, area.y, area.width, 1);
         let line2 = Rect::new(area.x, area.y.saturating_add(1), area.width, 1);
-        render_key_hints(line1, buf, PAGER_KEY_HINTS);
-        let mut pairs: Vec<(&str, &str)> = vec![("q", "quit"), ("Esc", "edit prev")];
+        render_key_hints(line1, buf, &pager_key_hints());
+        let close_key = close_pager_hint();
+        let backtrack_key = keymap().backtrack.hint().map(|hint| hint.content);
+        let mut pairs: Vec<(&str, &str)> = Vec::new();
+        if let Some(key) = &close_key {
+            pairs.push((key, "quit"));
+        }
+        if let Some(key) = &backtrack_key {
+            pairs.push((key, "edit prev"));
+        }
         if self.highlight_cell.is_some() {
             pairs.push(("⏎", "edit message"));
         }
@@ -444,22 +448,16 @@ impl TranscriptOverlay {
         match event {
             TuiEvent::Key(key_event) => match key_event {
                 KeyEvent {
-                    code: KeyCode::Char('q'),
-                    kind: KeyEventKind::Press,
-                    ..
-                }
-                | KeyEvent {
-                    code: KeyCode::Char('t'),
-                    modifiers: crossterm::event::KeyModifiers::CONTROL,
                     kind: KeyEventKind::Press,
                     ..
+                } if keymap().close_pager.matches(&key_event) => {
+                    self.is_done = true;
+                    Ok(())
                 }
-                | KeyEvent {
-                    code: KeyCode::Char('c'),
-                    modifiers: crossterm::event::KeyModifiers::CONTROL,
+                KeyEvent {
                     kind: KeyEventKind::Press,
                     ..
-                } => {
+                } if keymap().transcript.matches(&key_event) => {
                     self.is_done = true;
                     Ok(())
                 }
@@ -495,8 +493,11 @@ impl StaticOverlay {
     fn render_hints(&self, area: Rect, buf: &mut Buffer) {
         let line1 = Rect::new(area.x, area.y, area.width, 1);
         let line2 = Rect::new(area.x, area.y.saturating_add(1), area.width, 1);
-        render_key_hints(line1, buf, PAGER_KEY_HINTS);
-        let pairs = [("q", "quit")];
+        render_key_hints(line1, buf, &pager_key_hints());
+        let pairs: Vec<(String, &str)> = close_pager_hint()
+            .map(|key| (key, "quit"))
+            .into_iter()
+            .collect();
         render_key_hints(line2, buf, &pairs);
     }
 
@@ -514,16 +515,9 @@ impl StaticOverlay {
         match event {
             TuiEvent::Key(key_event) => match key_event {
                 KeyEvent {
-                    code: KeyCode::Char('q'),
-                    kind: KeyEventKind::Press,
-                    ..
-                }
-                | KeyEvent {
-                    code: KeyCode::Char('c'),
-                    modifiers: crossterm::event::KeyModifiers::CONTROL,
                     kind: KeyEventKind::Press,
                     ..
-                } => {
+                } if keymap().close_pager.matches(&key_event) => {
                     self.is_done = true;
                     Ok(())
                 }
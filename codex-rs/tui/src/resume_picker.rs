@@ -1,5 +1,9 @@
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
 
 use chrono::DateTime;
 use chrono::Utc;
@@ -7,6 +11,7 @@ use codex_core::ConversationItem;
 use codex_core::ConversationsPage;
 use codex_core::Cursor;
 use codex_core::RolloutRecorder;
+use codex_core::SessionSearch;
 use color_eyre::eyre::Result;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
@@ -16,9 +21,13 @@ use ratatui::layout::Layout;
 use ratatui::layout::Rect;
 use ratatui::style::Stylize as _;
 use ratatui::text::Line;
+use tokio::select;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::mpsc::unbounded_channel;
 use tokio_stream::StreamExt;
 
 use crate::text_formatting::truncate_text;
+use crate::theme::ThemeStylize;
 use crate::tui::FrameRequester;
 use crate::tui::Tui;
 use crate::tui::TuiEvent;
@@ -29,6 +38,9 @@ use codex_protocol::protocol::USER_MESSAGE_BEGIN;
 
 const PAGE_SIZE: usize = 25;
 
+/// How long typing must pause before the sessions are searched.
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(200);
+
 #[derive(Debug, Clone)]
 pub enum ResumeSelection {
     StartFresh,
@@ -36,31 +48,50 @@ pub enum ResumeSelection {
     Exit,
 }
 
-/// Interactive session picker that lists recorded rollout files with simple
+/// Interactive session picker that lists recorded rollout files with full-text
 /// search and pagination. Shows the first user input as the preview, relative
-/// time (e.g., "5 seconds ago"), and the absolute path.
-pub async fn run_resume_picker(tui: &mut Tui, codex_home: &Path) -> Result<ResumeSelection> {
+/// time (e.g., "5 seconds ago"), and the absolute path. `query` pre-fills the
+/// search.
+pub async fn run_resume_picker(
+    tui: &mut Tui,
+    codex_home: &Path,
+    query: Option<String>,
+) -> Result<ResumeSelection> {
     let alt = AltScreenGuard::enter(tui);
-    let mut state = PickerState::new(codex_home.to_path_buf(), alt.tui.frame_requester());
-    state.load_page(None).await?;
+    let (page_loaded_tx, mut page_loaded_rx) = unbounded_channel();
+    let mut state = PickerState::new(
+        codex_home.to_path_buf(),
+        alt.tui.frame_requester(),
+        page_loaded_tx,
+    );
+    state.query = query.unwrap_or_default();
+    state.load_page(None, Duration::ZERO);
     state.request_frame();
 
     let mut events = alt.tui.event_stream();
-    while let Some(ev) = events.next().await {
-        match ev {
-            TuiEvent::Key(key) => {
-                if matches!(key.kind, KeyEventKind::Release) {
-                    continue;
-                }
-                if let Some(sel) = state.handle_key(key).await? {
-                    return Ok(sel);
+    loop {
+        select! {
+            ev = events.next() => {
+                let Some(ev) = ev else {
+                    break;
+                };
+                match ev {
+                    TuiEvent::Key(key) => {
+                        if matches!(key.kind, KeyEventKind::Release) {
+                            continue;
+                        }
+                        if let Some(sel) = state.handle_key(key) {
+                            return Ok(sel);
+                        }
+                    }
+                    TuiEvent::Draw => {
+                        draw_picker(alt.tui, &state)?;
+                    }
+                    // Ignore paste and attach-image in picker
+                    _ => {}
                 }
             }
-            TuiEvent::Draw => {
-                draw_picker(alt.tui, &state)?;
-            }
-            // Ignore paste and attach-image in picker
-            _ => {}
+            Some(loaded) = page_loaded_rx.recv() => state.on_page_loaded(loaded),
         }
     }
 
@@ -92,11 +123,22 @@ struct PickerState {
     // pagination
     pagination: Pagination,
     // data
-    all_rows: Vec<Row>, // unfiltered rows for current page
-    filtered_rows: Vec<Row>,
+    rows: Vec<Row>,
     selected: usize,
-    // search
+    // search over the session index; empty lists every session
     query: String,
+    // pages are loaded in the background and sent here
+    page_loaded_tx: UnboundedSender<PageLoaded>,
+    // the latest page requested; pages of earlier requests are dropped
+    latest_request: Arc<AtomicU64>,
+    // why the latest page could not be loaded
+    error: Option<String>,
+}
+
+/// A page loaded for the request numbered `request`.
+struct PageLoaded {
+    request: u64,
+    result: std::io::Result<ConversationsPage>,
 }
 
 #[derive(Debug, Clone)]
@@ -115,7 +157,11 @@ struct Row {
 }
 
 impl PickerState {
-    fn new(codex_home: PathBuf, requester: FrameRequester) -> Self {
+    fn new(
+        codex_home: PathBuf,
+        requester: FrameRequester,
+        page_loaded_tx: UnboundedSender<PageLoaded>,
+    ) -> Self {
         Self {
             codex_home,
             requester,
@@ -125,10 +171,12 @@ impl PickerState {
                 next_cursor: None,
                 page_index: 0,
             },
-            all_rows: Vec::new(),
-            filtered_rows: Vec::new(),
+            rows: Vec::new(),
             selected: 0,
             query: String::new(),
+            page_loaded_tx,
+            latest_request: Arc::new(AtomicU64::new(0)),
+            error: None,
         }
     }
 
@@ -136,19 +184,19 @@ impl PickerState {
         self.requester.schedule_frame();
     }
 
-    async fn handle_key(&mut self, key: KeyEvent) -> Result<Option<ResumeSelection>> {
+    fn handle_key(&mut self, key: KeyEvent) -> Option<ResumeSelection> {
         match key.code {
-            KeyCode::Esc => return Ok(Some(ResumeSelection::StartFresh)),
+            KeyCode::Esc => return Some(ResumeSelection::StartFresh),
             KeyCode::Char('c')
                 if key
                     .modifiers
                     .contains(crossterm::event::KeyModifiers::CONTROL) =>
             {
-                return Ok(Some(ResumeSelection::Exit));
+                return Some(ResumeSelection::Exit);
             }
             KeyCode::Enter => {
-                if let Some(row) = self.filtered_rows.get(self.selected) {
-                    return Ok(Some(ResumeSelection::Resume(row.path.clone())));
+                if let Some(row) = self.rows.get(self.selected) {
+                    return Some(ResumeSelection::Resume(row.path.clone()));
                 }
             }
             KeyCode::Up => {
@@ -158,20 +206,21 @@ impl PickerState {
                 self.request_frame();
             }
             KeyCode::Down => {
-                if self.selected + 1 < self.filtered_rows.len() {
+                if self.selected + 1 < self.rows.len() {
                     self.selected += 1;
                 }
                 self.request_frame();
             }
             KeyCode::Left | KeyCode::Char('a') => {
-                self.prev_page().await?;
+                self.prev_page();
             }
             KeyCode::Right | KeyCode::Char('d') => {
-                self.next_page().await?;
+                self.next_page();
             }
             KeyCode::Backspace => {
-                self.query.pop();
-                self.apply_filter();
+                if self.query.pop().is_some() {
+                    self.search();
+                }
             }
             KeyCode::Char(c) => {
                 // basic text input for search
@@ -181,17 +230,17 @@ impl PickerState {
                     && !key.modifiers.contains(crossterm::event::KeyModifiers::ALT)
                 {
                     self.query.push(c);
-                    self.apply_filter();
+                    self.search();
                 }
             }
             _ => {}
         }
-        Ok(None)
+        None
     }
 
-    async fn prev_page(&mut self) -> Result<()> {
+    fn prev_page(&mut self) {
         if self.pagination.page_index == 0 {
-            return Ok(());
+            return;
         }
         // current_anchor points to the page we just loaded; backstack[page_index-1] is the anchor to reload
         if self.pagination.page_index > 0 {
@@ -203,12 +252,11 @@ impl PickerState {
                 .cloned()
                 .flatten();
             self.pagination.current_anchor = anchor.clone();
-            self.load_page(anchor.as_ref()).await?;
+            self.load_page(anchor, Duration::ZERO);
         }
-        Ok(())
     }
 
-    async fn next_page(&mut self) -> Result<()> {
+    fn next_page(&mut self) {
         if let Some(next) = self.pagination.next_cursor.clone() {
             // Record the anchor for the page we are moving to at index new_index
             let new_index = self.pagination.page_index + 1;
@@ -219,40 +267,76 @@ impl PickerState {
             self.pagination.current_anchor = Some(next.clone());
             self.pagination.page_index = new_index;
             let anchor = self.pagination.current_anchor.clone();
-            self.load_page(anchor.as_ref()).await?;
+            self.load_page(anchor, Duration::ZERO);
         }
-        Ok(())
     }
 
-    async fn load_page(&mut self, anchor: Option<&Cursor>) -> Result<()> {
-        let page = RolloutRecorder::list_conversations(&self.codex_home, PAGE_SIZE, anchor).await?;
-        self.pagination.next_cursor = page.next_cursor.clone();
-        self.all_rows = to_rows(page);
-        self.apply_filter();
-        // reset selection on new page
-        self.selected = 0;
-        Ok(())
+    /// Reloads the first page for the current query once typing pauses.
+    fn search(&mut self) {
+        self.pagination = Pagination {
+            current_anchor: None,
+            backstack: vec![None],
+            next_cursor: None,
+            page_index: 0,
+        };
+        self.load_page(None, SEARCH_DEBOUNCE);
     }
 
-    fn apply_filter(&mut self) {
-        if self.query.is_empty() {
-            self.filtered_rows = self.all_rows.clone();
-        } else {
-            let q = self.query.to_lowercase();
-            self.filtered_rows = self
-                .all_rows
-                .iter()
-                .filter(|r| r.preview.to_lowercase().contains(&q))
-                .cloned()
-                .collect();
+    /// Loads the page at `anchor` in the background after `delay`, unless
+    /// another page is requested meanwhile.
+    fn load_page(&mut self, anchor: Option<Cursor>, delay: Duration) {
+        let request = self.latest_request.fetch_add(1, Ordering::SeqCst) + 1;
+        let latest_request = Arc::clone(&self.latest_request);
+        let codex_home = self.codex_home.clone();
+        let query = self.query.clone();
+        let page_loaded_tx = self.page_loaded_tx.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            if latest_request.load(Ordering::SeqCst) != request {
+                return;
+            }
+            let result = fetch_page(&codex_home, &query, anchor.as_ref()).await;
+            let _ = page_loaded_tx.send(PageLoaded { request, result });
+        });
+    }
+
+    fn on_page_loaded(&mut self, loaded: PageLoaded) {
+        if loaded.request != self.latest_request.load(Ordering::SeqCst) {
+            return;
         }
-        if self.selected >= self.filtered_rows.len() {
-            self.selected = self.filtered_rows.len().saturating_sub(1);
+        match loaded.result {
+            Ok(page) => {
+                self.pagination.next_cursor = page.next_cursor.clone();
+                self.rows = to_rows(page);
+                // reset selection on new page
+                self.selected = 0;
+                self.error = None;
+            }
+            Err(e) => {
+                tracing::error!("failed to load sessions: {e}");
+                self.error = Some(format!("Failed to load sessions: {e}"));
+            }
         }
         self.request_frame();
     }
 }
 
+async fn fetch_page(
+    codex_home: &Path,
+    query: &str,
+    anchor: Option<&Cursor>,
+) -> std::io::Result<ConversationsPage> {
+    if query.trim().is_empty() {
+        RolloutRecorder::list_conversations(codex_home, PAGE_SIZE, anchor).await
+    } else {
+        let search = SessionSearch {
+            query: Some(query.to_string()),
+            ..Default::default()
+        };
+        RolloutRecorder::search_conversations(codex_home, &search, PAGE_SIZE, anchor).await
+    }
+}
+
 fn to_rows(page: ConversationsPage) -> Vec<Row> {
     page.items.into_iter().map(|it| head_to_row(&it)).collect()
 }
@@ -331,13 +415,13 @@ fn draw_picker(tui: &mut Tui, state: &PickerState) -> std::io::Result<()> {
 
         // Header
         frame.render_widget_ref(
-            Line::from(vec!["Resume a previous session".bold().cyan()]),
+            Line::from(vec!["Resume a previous session".bold().accent()]),
             header,
         );
 
         // Search line
         let q = if state.query.is_empty() {
-            "Type to search".dim().to_string()
+            "Type to search messages".dim().to_string()
         } else {
             format!("Search: {}", state.query)
         };
@@ -365,7 +449,11 @@ fn draw_picker(tui: &mut Tui, state: &PickerState) -> std::io::Result<()> {
 }
 
 fn render_list(frame: &mut crate::custom_terminal::Frame, area: Rect, state: &PickerState) {
-    let rows = &state.filtered_rows;
+    if let Some(error) = &state.error {
+        frame.render_widget_ref(Line::from(error.clone().error()), area);
+        return;
+    }
+    let rows = &state.rows;
     if rows.is_empty() {
         frame.render_widget_ref(Line::from("No sessions found".italic().dim()), area);
         return;
@@ -494,4 +582,53 @@ mod tests {
         assert!(rows[0].preview.contains('A'));
         assert!(rows[1].preview.contains('B'));
     }
+
+    fn page(previews: &[&str]) -> ConversationsPage {
+        ConversationsPage {
+            items: previews
+                .iter()
+                .map(|preview| ConversationItem {
+                    path: PathBuf::from(format!("/tmp/{preview}.jsonl")),
+                    head: head_with_ts_and_user_text("2025-01-01T00:00:00Z", &[preview]),
+                })
+                .collect(),
+            next_cursor: None,
+            num_scanned_files: 0,
+            reached_scan_cap: false,
+        }
+    }
+
+    #[test]
+    fn only_the_latest_page_is_shown_and_errors_are_kept() {
+        let (page_loaded_tx, _page_loaded_rx) = unbounded_channel();
+        let mut state = PickerState::new(
+            PathBuf::from("/tmp/codex-home"),
+            FrameRequester::test_dummy(),
+            page_loaded_tx,
+        );
+        state.latest_request.store(2, Ordering::SeqCst);
+
+        state.on_page_loaded(PageLoaded {
+            request: 1,
+            result: Ok(page(&["stale"])),
+        });
+        assert!(state.rows.is_empty());
+
+        state.on_page_loaded(PageLoaded {
+            request: 2,
+            result: Ok(page(&["fresh"])),
+        });
+        assert_eq!(state.rows.len(), 1);
+        assert_eq!(state.rows[0].preview, "fresh");
+
+        state.on_page_loaded(PageLoaded {
+            request: 2,
+            result: Err(std::io::Error::other("index is corrupt")),
+        });
+        assert_eq!(
+            state.error.as_deref(),
+            Some("Failed to load sessions: index is corrupt")
+        );
+        assert_eq!(state.rows.len(), 1);
+    }
 }
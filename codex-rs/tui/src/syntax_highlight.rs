@@ -0,0 +1,708 @@
+//! Syntax highlighting for fenced code blocks and diff bodies.
+//!
+//! This is a small lexical highlighter rather than a full parser: each
+//! supported language is described by its keywords, comment markers and
+//! string delimiters, which is enough to color keywords, types, literals,
+//! strings and comments. State is carried from one line to the next so that
+//! block comments and multi-line strings are colored correctly when lines are
+//! highlighted in order.
+//!
+//! Highlighter crates such as `syntect` bundle grammars and a regex engine
+//! that would add megabytes to the binary, and their themes are true-color
+//! palettes; tokens here only need the few semantic colors of `styles.md`.
+
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::theme::theme;
+use codex_core::config_types::SyntaxTheme;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::text::Span;
+
+static THEME: OnceLock<SyntaxTheme> = OnceLock::new();
+
+/// Sets the theme from the `[tui]` config. Only the first call has an effect.
+pub(crate) fn set_theme(theme: SyntaxTheme) {
+    let _ = THEME.set(theme);
+}
+
+/// Returns the palette to highlight with, or `None` if highlighting is turned
+/// off, the terminal does not support colors or no theme has been set (as in
+/// tests, which pass a palette explicitly when they want highlighting).
+pub(crate) fn current_palette() -> Option<Palette> {
+    let theme = *THEME.get()?;
+    supports_color::on_cached(supports_color::Stream::Stdout)?;
+    Palette::for_theme(theme)
+}
+
+/// Styles for each kind of token.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Palette {
+    keyword: Style,
+    type_name: Style,
+    literal: Style,
+    string: Style,
+    comment: Style,
+}
+
+impl Palette {
+    fn for_theme(theme: SyntaxTheme) -> Option<Self> {
+        match theme {
+            SyntaxTheme::None => None,
+            SyntaxTheme::Ansi => Some(Self::ansi()),
+        }
+    }
+
+    /// The semantic colors of `styles.md`, as configured by `[tui.theme]`.
+    pub(crate) fn ansi() -> Self {
+        let theme = theme();
+        Self {
+            keyword: Style::default().fg(theme.brand),
+            type_name: Style::default().fg(theme.accent),
+            literal: Style::default().fg(theme.accent),
+            string: Style::default().fg(theme.success),
+            comment: Style::default().add_modifier(Modifier::DIM | Modifier::ITALIC),
+        }
+    }
+
+    fn style(&self, kind: TokenKind) -> Style {
+        match kind {
+            TokenKind::Plain => Style::default(),
+            TokenKind::Keyword => self.keyword,
+            TokenKind::Type => self.type_name,
+            TokenKind::Literal => self.literal,
+            TokenKind::String => self.string,
+            TokenKind::Comment => self.comment,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TokenKind {
+    Plain,
+    Keyword,
+    Type,
+    Literal,
+    String,
+    Comment,
+}
+
+struct StringDelimiter {
+    open: &'static str,
+    close: &'static str,
+    multiline: bool,
+    escapes: bool,
+}
+
+const fn delimiter(quote: &'static str, multiline: bool, escapes: bool) -> StringDelimiter {
+    StringDelimiter {
+        open: quote,
+        close: quote,
+        multiline,
+        escapes,
+    }
+}
+
+/// Keywords, types and literals are lists of words separated by spaces.
+struct Language {
+    keywords: &'static str,
+    types: &'static str,
+    literals: &'static str,
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    /// Longer delimiters must come before their prefixes.
+    strings: &'static [StringDelimiter],
+    /// Identifiers starting with an uppercase letter are types.
+    capitalized_types: bool,
+    /// `'x'` is a character literal but `'a` (a Rust lifetime) is not.
+    char_literals: bool,
+    /// `$name` is a variable.
+    sigil_variables: bool,
+    case_insensitive_keywords: bool,
+}
+
+const BASE: Language = Language {
+    keywords: "",
+    types: "",
+    literals: "",
+    line_comments: &[],
+    block_comment: None,
+    strings: &[],
+    capitalized_types: false,
+    char_literals: false,
+    sigil_variables: false,
+    case_insensitive_keywords: false,
+};
+
+const C_STRINGS: &[StringDelimiter] = &[delimiter("\"", false, true), delimiter("'", false, true)];
+
+const RUST: Language = Language {
+    keywords: "as async await break const continue crate dyn else enum extern fn for if impl in \
+               let loop match mod move mut pub ref return self Self static struct super trait \
+               type unsafe use where while yield",
+    types: "bool char f32 f64 i8 i16 i32 i64 i128 isize str u8 u16 u32 u64 u128 usize",
+    literals: "true false None Some Ok Err",
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    strings: &[delimiter("\"", true, true)],
+    capitalized_types: true,
+    char_literals: true,
+    ..BASE
+};
+
+const PYTHON: Language = Language {
+    keywords: "and as assert async await break case class continue def del elif else except \
+               finally for from global if import in is lambda match nonlocal not or pass raise \
+               return try while with yield",
+    types: "bool bytes dict float int list object set str tuple",
+    literals: "True False None",
+    line_comments: &["#"],
+    strings: &[
+        delimiter("\"\"\"", true, true),
+        delimiter("'''", true, true),
+        delimiter("\"", false, true),
+        delimiter("'", false, true),
+    ],
+    capitalized_types: true,
+    ..BASE
+};
+
+const JAVASCRIPT: Language = Language {
+    keywords: "abstract as async await break case catch class const continue debugger declare \
+               default delete do else enum export extends finally for from function if implements \
+               import in instanceof interface let namespace new of private protected public \
+               readonly return static super switch this throw try type typeof var void while with \
+               yield",
+    types: "any bigint boolean never number object string symbol unknown",
+    literals: "true false null undefined NaN Infinity",
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    strings: &[
+        delimiter("`", true, true),
+        delimiter("\"", false, true),
+        delimiter("'", false, true),
+    ],
+    capitalized_types: true,
+    ..BASE
+};
+
+const GO: Language = Language {
+    keywords: "break case chan const continue default defer else fallthrough for func go goto if \
+               import interface map package range return select struct switch type var",
+    types: "any bool byte complex64 complex128 error float32 float64 int int8 int16 int32 int64 \
+            rune string uint uint8 uint16 uint32 uint64 uintptr",
+    literals: "true false nil iota",
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    strings: &[
+        delimiter("`", true, false),
+        delimiter("\"", false, true),
+        delimiter("'", false, true),
+    ],
+    ..BASE
+};
+
+const C: Language = Language {
+    keywords: "auto break case catch class const constexpr continue default delete do else enum \
+               explicit extern for friend goto if inline namespace new noexcept operator override \
+               private protected public register return sizeof static struct switch template this \
+               throw try typedef typename union using virtual volatile while",
+    types: "bool char double float int long short signed size_t unsigned void",
+    literals: "true false NULL nullptr",
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    strings: C_STRINGS,
+    ..BASE
+};
+
+const JAVA: Language = Language {
+    keywords: "abstract assert break case catch class continue default do else enum extends final \
+               finally for if implements import instanceof interface native new package permits \
+               private protected public record return sealed static super switch synchronized \
+               this throw throws transient try var volatile while yield",
+    types: "boolean byte char double float int long short void",
+    literals: "true false null",
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    strings: &[
+        delimiter("\"\"\"", true, true),
+        delimiter("\"", false, true),
+        delimiter("'", false, true),
+    ],
+    capitalized_types: true,
+    ..BASE
+};
+
+const SHELL: Language = Language {
+    keywords: "alias break case continue do done elif else esac exit export fi for function if in \
+               local readonly return shift source then until unset while",
+    line_comments: &["#"],
+    strings: &[delimiter("\"", true, true), delimiter("'", true, false)],
+    sigil_variables: true,
+    ..BASE
+};
+
+const RUBY: Language = Language {
+    keywords: "alias and begin break case class def do else elsif end ensure for if in module \
+               next not or redo require rescue retry return self super then undef unless until \
+               when while yield",
+    literals: "true false nil",
+    line_comments: &["#"],
+    strings: C_STRINGS,
+    capitalized_types: true,
+    ..BASE
+};
+
+const JSON: Language = Language {
+    literals: "true false null",
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    strings: &[delimiter("\"", false, true)],
+    ..BASE
+};
+
+const TOML: Language = Language {
+    literals: "true false",
+    line_comments: &["#"],
+    strings: &[
+        delimiter("\"\"\"", true, true),
+        delimiter("'''", true, false),
+        delimiter("\"", false, true),
+        delimiter("'", false, false),
+    ],
+    ..BASE
+};
+
+const YAML: Language = Language {
+    literals: "true false null yes no",
+    line_comments: &["#"],
+    strings: &[delimiter("\"", false, true), delimiter("'", false, false)],
+    ..BASE
+};
+
+const SQL: Language = Language {
+    keywords: "add all alter and as asc begin by case commit create default delete desc distinct \
+               drop else end exists foreign from group having in index inner insert into is join \
+               key left limit not offset on or order outer primary references returning right \
+               rollback select set table then union update values when where with",
+    types: "bigint boolean char date integer int numeric real text timestamp varchar",
+    literals: "true false null",
+    line_comments: &["--"],
+    block_comment: Some(("/*", "*/")),
+    strings: &[delimiter("'", false, false), delimiter("\"", false, false)],
+    case_insensitive_keywords: true,
+    ..BASE
+};
+
+/// Looks up a language by the name used in a fenced code block's info
+/// string or by a file extension.
+fn language_for_name(name: &str) -> Option<&'static Language> {
+    let language = match name.to_ascii_lowercase().as_str() {
+        "rust" | "rs" => &RUST,
+        "python" | "py" | "python3" | "pyi" => &PYTHON,
+        "javascript" | "js" | "jsx" | "mjs" | "cjs" | "typescript" | "ts" | "tsx" => &JAVASCRIPT,
+        "go" | "golang" => &GO,
+        "c" | "h" | "cpp" | "c++" | "cc" | "cxx" | "hpp" | "hh" => &C,
+        "java" => &JAVA,
+        "sh" | "bash" | "zsh" | "shell" => &SHELL,
+        "ruby" | "rb" => &RUBY,
+        "json" | "jsonc" => &JSON,
+        "toml" => &TOML,
+        "yaml" | "yml" => &YAML,
+        "sql" => &SQL,
+        _ => return None,
+    };
+    Some(language)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    Normal,
+    BlockComment,
+    /// Inside a string opened by `Language::strings[index]`.
+    String(usize),
+}
+
+/// Highlights the lines of one code block or file, in order.
+pub(crate) struct Highlighter {
+    language: &'static Language,
+    palette: Palette,
+    state: State,
+}
+
+impl Highlighter {
+    /// Returns a highlighter for the language named by a fenced code block's
+    /// info string (e.g. `rust` in "```rust,ignore"), if it is supported.
+    pub(crate) fn for_info_string(info: &str, palette: Palette) -> Option<Self> {
+        let name = info
+            .split(|c: char| c.is_whitespace() || c == ',' || c == '{')
+            .next()
+            .unwrap_or_default();
+        Some(Self::new(
+            language_for_name(name.trim_start_matches('.'))?,
+            palette,
+        ))
+    }
+
+    /// Returns a highlighter for the file at `path` based on its extension.
+    pub(crate) fn for_path(path: &Path, palette: Palette) -> Option<Self> {
+        let extension = path.extension()?.to_str()?;
+        Some(Self::new(language_for_name(extension)?, palette))
+    }
+
+    fn new(language: &'static Language, palette: Palette) -> Self {
+        Self {
+            language,
+            palette,
+            state: State::Normal,
+        }
+    }
+
+    /// Forgets the state carried over from previous lines, e.g. at the start
+    /// of a diff hunk.
+    pub(crate) fn reset(&mut self) {
+        self.state = State::Normal;
+    }
+
+    /// Highlights the next line, which must not contain a newline.
+    pub(crate) fn highlight_line(&mut self, line: &str) -> Vec<Span<'static>> {
+        let mut spans: Vec<Span<'static>> = Vec::new();
+        for (kind, text) in self.tokenize(line) {
+            let style = self.palette.style(kind);
+            match spans.last_mut() {
+                Some(last) if last.style == style => last.content.to_mut().push_str(text),
+                _ => spans.push(Span::styled(text.to_string(), style)),
+            }
+        }
+        spans
+    }
+
+    /// Splits `line` into tokens, merging adjacent tokens of the same kind.
+    fn tokenize<'a>(&mut self, line: &'a str) -> Vec<(TokenKind, &'a str)> {
+        let language = self.language;
+        let mut tokens: Vec<(TokenKind, usize, usize)> = Vec::new();
+        let mut pos = 0;
+        while pos < line.len() {
+            let rest = &line[pos..];
+            let (kind, len) = match self.state {
+                State::BlockComment => {
+                    let close = language.block_comment.map_or("", |(_, close)| close);
+                    match rest.find(close) {
+                        Some(end) => {
+                            self.state = State::Normal;
+                            (TokenKind::Comment, end + close.len())
+                        }
+                        None => (TokenKind::Comment, rest.len()),
+                    }
+                }
+                State::String(index) => match string_end(rest, &language.strings[index]) {
+                    Some(end) => {
+                        self.state = State::Normal;
+                        (TokenKind::String, end)
+                    }
+                    None => (TokenKind::String, rest.len()),
+                },
+                State::Normal => self.next_token(line, pos),
+            };
+            match tokens.last_mut() {
+                Some((last_kind, _, end)) if *last_kind == kind => *end = pos + len,
+                _ => tokens.push((kind, pos, pos + len)),
+            }
+            pos += len;
+        }
+        if let State::String(index) = self.state
+            && !language.strings[index].multiline
+        {
+            self.state = State::Normal;
+        }
+        tokens
+            .into_iter()
+            .map(|(kind, start, end)| (kind, &line[start..end]))
+            .collect()
+    }
+
+    /// Lexes the token starting at `pos` outside of comments and strings and
+    /// returns its kind and length in bytes.
+    fn next_token(&mut self, line: &str, pos: usize) -> (TokenKind, usize) {
+        let language = self.language;
+        let rest = &line[pos..];
+        let previous = line[..pos].chars().next_back();
+
+        for marker in language.line_comments {
+            // `#` only starts a comment at the start of a word, as in `$#`.
+            let at_word_start = previous.is_none_or(char::is_whitespace);
+            if rest.starts_with(marker) && (*marker != "#" || at_word_start) {
+                return (TokenKind::Comment, rest.len());
+            }
+        }
+        if let Some((open, _)) = language.block_comment
+            && rest.starts_with(open)
+        {
+            self.state = State::BlockComment;
+            return (TokenKind::Comment, open.len());
+        }
+        if language.char_literals && rest.starts_with('\'') {
+            return match char_literal_len(rest) {
+                Some(len) => (TokenKind::String, len),
+                None => (TokenKind::Plain, 1),
+            };
+        }
+        if let Some(index) = language
+            .strings
+            .iter()
+            .position(|delimiter| rest.starts_with(delimiter.open))
+        {
+            self.state = State::String(index);
+            return (TokenKind::String, language.strings[index].open.len());
+        }
+
+        let Some(first) = rest.chars().next() else {
+            return (TokenKind::Plain, 0);
+        };
+        let continues_word = previous.is_some_and(is_identifier_char);
+        if language.sigil_variables && first == '$' {
+            let len = 1 + identifier_len(&rest[1..]);
+            let kind = if len > 1 {
+                TokenKind::Type
+            } else {
+                TokenKind::Plain
+            };
+            return (kind, len);
+        }
+        if first.is_ascii_digit() && !continues_word {
+            return (TokenKind::Literal, number_len(rest));
+        }
+        if (first.is_alphabetic() || first == '_') && !continues_word {
+            let len = identifier_len(rest);
+            return (classify_identifier(language, &rest[..len]), len);
+        }
+        (TokenKind::Plain, first.len_utf8())
+    }
+}
+
+fn classify_identifier(language: &Language, word: &str) -> TokenKind {
+    let lowercase;
+    let keyword = if language.case_insensitive_keywords {
+        lowercase = word.to_ascii_lowercase();
+        lowercase.as_str()
+    } else {
+        word
+    };
+    let listed = |words: &str| words.split_ascii_whitespace().any(|w| w == keyword);
+    if listed(language.keywords) {
+        TokenKind::Keyword
+    } else if listed(language.literals) {
+        TokenKind::Literal
+    } else if listed(language.types)
+        || (language.capitalized_types && word.starts_with(|c: char| c.is_uppercase()))
+    {
+        TokenKind::Type
+    } else {
+        TokenKind::Plain
+    }
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn identifier_len(s: &str) -> usize {
+    s.find(|c: char| !is_identifier_char(c)).unwrap_or(s.len())
+}
+
+/// Length of a number such as `42`, `0x1f`, `1_000u32` or `1.5e3`.
+fn number_len(s: &str) -> usize {
+    let mut chars = s.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let is_decimal_point =
+            c == '.' && chars.peek().is_some_and(|(_, next)| next.is_ascii_digit());
+        if !(is_identifier_char(c) || is_decimal_point) {
+            return i;
+        }
+    }
+    s.len()
+}
+
+/// Returns the length of the string's remainder up to and including its
+/// closing delimiter, if the delimiter is on this line.
+fn string_end(s: &str, delimiter: &StringDelimiter) -> Option<usize> {
+    let mut chars = s.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if delimiter.escapes && c == '\\' {
+            chars.next();
+        } else if s[i..].starts_with(delimiter.close) {
+            return Some(i + delimiter.close.len());
+        }
+    }
+    None
+}
+
+/// Returns the length of a character literal such as `'a'` or `'\n'` at the
+/// start of `s`, or `None` if the quote starts a lifetime.
+fn char_literal_len(s: &str) -> Option<usize> {
+    let mut chars = s.char_indices().skip(1);
+    let (_, first) = chars.next()?;
+    if first == '\\' {
+        // Escapes such as `'\''`, `'\n'` or `'\u{1F600}'`.
+        chars.next()?;
+        chars.take(10).find(|(_, c)| *c == '\'').map(|(i, _)| i + 1)
+    } else {
+        match chars.next() {
+            Some((i, '\'')) => Some(i + 1),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use ratatui::style::Color;
+
+    fn tokens(info: &str, lines: &[&str]) -> Vec<Vec<(TokenKind, String)>> {
+        let mut highlighter =
+            Highlighter::for_info_string(info, Palette::ansi()).expect("supported language");
+        lines
+            .iter()
+            .map(|line| {
+                highlighter
+                    .tokenize(line)
+                    .into_iter()
+                    .filter(|(kind, _)| *kind != TokenKind::Plain)
+                    .map(|(kind, text)| (kind, text.to_string()))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn token(kind: TokenKind, text: &str) -> (TokenKind, String) {
+        (kind, text.to_string())
+    }
+
+    #[test]
+    fn rust_keywords_types_literals_and_comments() {
+        assert_eq!(
+            tokens(
+                "rust",
+                &["fn main() -> Option<u8> { let c = 'x'; Some(0x1f) } // done"]
+            ),
+            vec![vec![
+                token(TokenKind::Keyword, "fn"),
+                token(TokenKind::Type, "Option"),
+                token(TokenKind::Type, "u8"),
+                token(TokenKind::Keyword, "let"),
+                token(TokenKind::String, "'x'"),
+                token(TokenKind::Literal, "Some"),
+                token(TokenKind::Literal, "0x1f"),
+                token(TokenKind::Comment, "// done"),
+            ]]
+        );
+    }
+
+    #[test]
+    fn rust_lifetimes_are_not_char_literals() {
+        assert_eq!(
+            tokens("rust", &["fn f<'a>(s: &'a str) -> char { '\\n' }"]),
+            vec![vec![
+                token(TokenKind::Keyword, "fn"),
+                token(TokenKind::Type, "str"),
+                token(TokenKind::Type, "char"),
+                token(TokenKind::String, "'\\n'"),
+            ]]
+        );
+    }
+
+    #[test]
+    fn block_comments_and_strings_continue_across_lines() {
+        assert_eq!(
+            tokens(
+                "rust",
+                &[
+                    "/* start",
+                    "still comment */ let s = \"one",
+                    "two\\\" three\";"
+                ]
+            ),
+            vec![
+                vec![token(TokenKind::Comment, "/* start")],
+                vec![
+                    token(TokenKind::Comment, "still comment */"),
+                    token(TokenKind::Keyword, "let"),
+                    token(TokenKind::String, "\"one"),
+                ],
+                vec![token(TokenKind::String, "two\\\" three\"")],
+            ]
+        );
+    }
+
+    #[test]
+    fn single_line_strings_end_with_the_line() {
+        assert_eq!(
+            tokens("python", &["x = 'open", "return None  # comment"]),
+            vec![
+                vec![token(TokenKind::String, "'open")],
+                vec![
+                    token(TokenKind::Keyword, "return"),
+                    token(TokenKind::Literal, "None"),
+                    token(TokenKind::Comment, "# comment"),
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn shell_variables_and_hashes_inside_words() {
+        assert_eq!(
+            tokens("bash", &["if [ $# -gt 0 ]; then echo \"$HOME\"; fi # args"]),
+            vec![vec![
+                token(TokenKind::Keyword, "if"),
+                token(TokenKind::Literal, "0"),
+                token(TokenKind::Keyword, "then"),
+                token(TokenKind::String, "\"$HOME\""),
+                token(TokenKind::Keyword, "fi"),
+                token(TokenKind::Comment, "# args"),
+            ]]
+        );
+    }
+
+    #[test]
+    fn sql_keywords_are_case_insensitive() {
+        assert_eq!(
+            tokens("sql", &["SELECT name FROM users -- all"]),
+            vec![vec![
+                token(TokenKind::Keyword, "SELECT"),
+                token(TokenKind::Keyword, "FROM"),
+                token(TokenKind::Comment, "-- all"),
+            ]]
+        );
+    }
+
+    #[test]
+    fn languages_are_found_by_info_string_and_extension() {
+        let palette = Palette::ansi();
+        assert!(Highlighter::for_info_string("rust,ignore", palette).is_some());
+        assert!(Highlighter::for_info_string("TypeScript title=\"x\"", palette).is_some());
+        assert!(Highlighter::for_info_string("", palette).is_none());
+        assert!(Highlighter::for_info_string("brainfuck", palette).is_none());
+        assert!(Highlighter::for_path(Path::new("src/lib.rs"), palette).is_some());
+        assert!(Highlighter::for_path(Path::new("Makefile"), palette).is_none());
+    }
+
+    #[test]
+    fn adjacent_tokens_with_the_same_style_share_a_span() {
+        let mut highlighter =
+            Highlighter::for_info_string("python", Palette::ansi()).expect("supported language");
+        let spans = highlighter.highlight_line("return x + 1");
+        let contents: Vec<&str> = spans.iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(contents, vec!["return", " x + ", "1"]);
+        assert_eq!(spans[0].style, Style::default().fg(Color::Magenta));
+    }
+
+    #[test]
+    fn themes_select_the_palette() {
+        assert_eq!(Palette::for_theme(SyntaxTheme::Ansi), Some(Palette::ansi()));
+        assert_eq!(Palette::for_theme(SyntaxTheme::None), None);
+    }
+}
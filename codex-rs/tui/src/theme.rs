@@ -0,0 +1,122 @@
+//! Colors of the TUI, configured by the `[tui.theme]` table.
+//!
+//! Widgets use the semantic colors from `styles.md` through
+//! [`ThemeStylize`], e.g. `"user".accent()` rather than `"user".cyan()`, so
+//! that users can adjust them to their terminal's color scheme. Until the
+//! theme is set (e.g. in tests) the default ANSI colors apply.
+
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use codex_core::config_types::TuiTheme;
+use ratatui::style::Color;
+use ratatui::style::Stylize;
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// Sets the theme used by the TUI. Only the first call has an effect.
+pub(crate) fn set_theme(theme: Theme) {
+    let _ = THEME.set(theme);
+}
+
+/// Returns the theme used by the TUI.
+pub(crate) fn theme() -> &'static Theme {
+    THEME.get_or_init(Theme::default)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Theme {
+    /// Key hints, selections and status indicators.
+    pub(crate) accent: Color,
+    /// Successes and additions.
+    pub(crate) success: Color,
+    /// Errors, failures and deletions.
+    pub(crate) error: Color,
+    /// Labels of Codex itself.
+    pub(crate) brand: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            accent: Color::Cyan,
+            success: Color::Green,
+            error: Color::Red,
+            brand: Color::Magenta,
+        }
+    }
+}
+
+impl Theme {
+    /// Builds the theme from the `[tui.theme]` table, keeping the default
+    /// colors it does not set.
+    pub(crate) fn from_config(config: &TuiTheme) -> Result<Self, String> {
+        let defaults = Self::default();
+        let color = |name: &str, value: &Option<String>, default: Color| match value {
+            Some(value) => Color::from_str(value)
+                .map_err(|_| format!("invalid color `{value}` for tui.theme.{name}")),
+            None => Ok(default),
+        };
+        Ok(Self {
+            accent: color("accent", &config.accent, defaults.accent)?,
+            success: color("success", &config.success, defaults.success)?,
+            error: color("error", &config.error, defaults.error)?,
+            brand: color("brand", &config.brand, defaults.brand)?,
+        })
+    }
+}
+
+/// Shorthands for the theme's colors, available wherever [`Stylize`] is.
+pub(crate) trait ThemeStylize<'a, T>: Stylize<'a, T> {
+    fn accent(self) -> T {
+        self.fg(theme().accent)
+    }
+
+    fn success(self) -> T {
+        self.fg(theme().success)
+    }
+
+    fn error(self) -> T {
+        self.fg(theme().error)
+    }
+
+    fn brand(self) -> T {
+        self.fg(theme().brand)
+    }
+}
+
+impl<'a, T, U> ThemeStylize<'a, T> for U where U: Stylize<'a, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn config_overrides_only_the_colors_it_sets() {
+        let theme = Theme::from_config(&TuiTheme {
+            accent: Some("light-blue".to_string()),
+            error: Some("#d75f5f".to_string()),
+            ..Default::default()
+        })
+        .expect("valid theme");
+
+        assert_eq!(theme.accent, Color::LightBlue);
+        assert_eq!(theme.error, Color::from_u32(0x00d75f5f));
+        assert_eq!(theme.success, Theme::default().success);
+
+        assert_eq!(
+            Theme::from_config(&TuiTheme {
+                brand: Some("purplish".to_string()),
+                ..Default::default()
+            }),
+            Err("invalid color `purplish` for tui.theme.brand".to_string())
+        );
+    }
+
+    #[test]
+    fn shorthands_use_the_default_theme() {
+        assert_eq!("user".accent(), "user".cyan());
+        assert_eq!("✗".error(), "✗".red());
+    }
+}
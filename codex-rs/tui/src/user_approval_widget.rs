@@ -30,7 +30,12 @@ use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
 use crate::exec_command::strip_bash_lc_and_escape;
 use crate::history_cell;
+use crate::keymap::KeyBindings;
+use crate::keymap::Keymap;
+use crate::keymap::keymap;
 use crate::text_formatting::truncate_text;
+use crate::theme::ThemeStylize;
+use crate::theme::theme;
 
 /// Request coming from the agent that needs user approval.
 pub(crate) enum ApprovalRequest {
@@ -44,15 +49,21 @@ pub(crate) enum ApprovalRequest {
         reason: Option<String>,
         grant_root: Option<PathBuf>,
     },
+    McpToolCall {
+        id: String,
+        server: String,
+        tool: String,
+        arguments: Option<String>,
+    },
 }
 
 /// Options displayed in the *select* mode.
 ///
-/// The `key` is matched case-insensitively.
+/// `keys` picks the option's bindings from the keymap.
 struct SelectOption {
     label: Line<'static>,
     description: &'static str,
-    key: KeyCode,
+    keys: fn(&Keymap) -> &KeyBindings,
     decision: ReviewDecision,
 }
 
@@ -61,19 +72,19 @@ static COMMAND_SELECT_OPTIONS: LazyLock<Vec<SelectOption>> = LazyLock::new(|| {
         SelectOption {
             label: Line::from(vec!["Y".underlined(), "es".into()]),
             description: "Approve and run the command",
-            key: KeyCode::Char('y'),
+            keys: |keymap| &keymap.approve,
             decision: ReviewDecision::Approved,
         },
         SelectOption {
             label: Line::from(vec!["A".underlined(), "lways".into()]),
             description: "Approve the command for the remainder of this session",
-            key: KeyCode::Char('a'),
+            keys: |keymap| &keymap.approve_for_session,
             decision: ReviewDecision::ApprovedForSession,
         },
         SelectOption {
             label: Line::from(vec!["N".underlined(), "o, provide feedback".into()]),
             description: "Do not run the command; provide feedback",
-            key: KeyCode::Char('n'),
+            keys: |keymap| &keymap.deny,
             decision: ReviewDecision::Abort,
         },
     ]
@@ -84,13 +95,36 @@ static PATCH_SELECT_OPTIONS: LazyLock<Vec<SelectOption>> = LazyLock::new(|| {
         SelectOption {
             label: Line::from(vec!["Y".underlined(), "es".into()]),
             description: "Approve and apply the changes",
-            key: KeyCode::Char('y'),
+            keys: |keymap| &keymap.approve,
             decision: ReviewDecision::Approved,
         },
         SelectOption {
             label: Line::from(vec!["N".underlined(), "o, provide feedback".into()]),
             description: "Do not apply the changes; provide feedback",
-            key: KeyCode::Char('n'),
+            keys: |keymap| &keymap.deny,
+            decision: ReviewDecision::Abort,
+        },
+    ]
+});
+
+static MCP_TOOL_CALL_SELECT_OPTIONS: LazyLock<Vec<SelectOption>> = LazyLock::new(|| {
+    vec![
+        SelectOption {
+            label: Line::from(vec!["Y".underlined(), "es".into()]),
+            description: "Approve and call the tool",
+            keys: |keymap| &keymap.approve,
+            decision: ReviewDecision::Approved,
+        },
+        SelectOption {
+            label: Line::from(vec!["A".underlined(), "lways".into()]),
+            description: "Approve calls to this tool for the remainder of this session",
+            keys: |keymap| &keymap.approve_for_session,
+            decision: ReviewDecision::ApprovedForSession,
+        },
+        SelectOption {
+            label: Line::from(vec!["N".underlined(), "o, provide feedback".into()]),
+            description: "Do not call the tool; provide feedback",
+            keys: |keymap| &keymap.deny,
             decision: ReviewDecision::Abort,
         },
     ]
@@ -142,12 +176,28 @@ impl UserApprovalWidget {
 
                 Paragraph::new(contents).wrap(Wrap { trim: false })
             }
+            ApprovalRequest::McpToolCall {
+                server,
+                tool,
+                arguments,
+                ..
+            } => {
+                let mut contents: Vec<Line> = vec![Line::from(vec![
+                    server.clone().bold(),
+                    ".".into(),
+                    tool.clone().bold(),
+                    format!("({})", arguments.as_deref().unwrap_or_default()).dim(),
+                ])];
+                contents.push(Line::from(""));
+                Paragraph::new(contents).wrap(Wrap { trim: false })
+            }
         };
 
         Self {
             select_options: match &approval_request {
                 ApprovalRequest::Exec { .. } => &COMMAND_SELECT_OPTIONS,
                 ApprovalRequest::ApplyPatch { .. } => &PATCH_SELECT_OPTIONS,
+                ApprovalRequest::McpToolCall { .. } => &MCP_TOOL_CALL_SELECT_OPTIONS,
             },
             approval_request,
             app_event_tx,
@@ -173,16 +223,6 @@ impl UserApprovalWidget {
         }
     }
 
-    /// Normalize a key for comparison.
-    /// - For `KeyCode::Char`, converts to lowercase for case-insensitive matching.
-    /// - Other key codes are returned unchanged.
-    fn normalize_keycode(code: KeyCode) -> KeyCode {
-        match code {
-            KeyCode::Char(c) => KeyCode::Char(c.to_ascii_lowercase()),
-            other => other,
-        }
-    }
-
     /// Handle Ctrl-C pressed by the user while the modal is visible.
     /// Behaves like pressing Escape: abort the request and close the modal.
     pub(crate) fn on_ctrl_c(&mut self) {
@@ -205,12 +245,11 @@ impl UserApprovalWidget {
             KeyCode::Esc => {
                 self.send_decision(ReviewDecision::Abort);
             }
-            other => {
-                let normalized = Self::normalize_keycode(other);
+            _ => {
                 if let Some(opt) = self
                     .select_options
                     .iter()
-                    .find(|opt| Self::normalize_keycode(opt.key) == normalized)
+                    .find(|opt| (opt.keys)(keymap()).matches(&key_event))
                 {
                     self.send_decision(opt.decision);
                 }
@@ -240,7 +279,7 @@ impl UserApprovalWidget {
                 match decision {
                     ReviewDecision::Approved => {
                         result_spans.extend(vec![
-                            "✔ ".fg(Color::Green),
+                            "✔ ".success(),
                             "You ".into(),
                             "approved".bold(),
                             " codex to run ".into(),
@@ -250,7 +289,7 @@ impl UserApprovalWidget {
                     }
                     ReviewDecision::ApprovedForSession => {
                         result_spans.extend(vec![
-                            "✔ ".fg(Color::Green),
+                            "✔ ".success(),
                             "You ".into(),
                             "approved".bold(),
                             " codex to run ".into(),
@@ -260,7 +299,7 @@ impl UserApprovalWidget {
                     }
                     ReviewDecision::Denied => {
                         result_spans.extend(vec![
-                            "✗ ".fg(Color::Red),
+                            "✗ ".error(),
                             "You ".into(),
                             "did not approve".bold(),
                             " codex to run ".into(),
@@ -269,7 +308,7 @@ impl UserApprovalWidget {
                     }
                     ReviewDecision::Abort => {
                         result_spans.extend(vec![
-                            "✗ ".fg(Color::Red),
+                            "✗ ".error(),
                             "You ".into(),
                             "canceled".bold(),
                             " the request to run ".into(),
@@ -294,6 +333,9 @@ impl UserApprovalWidget {
             ApprovalRequest::ApplyPatch { .. } => {
                 // No history line for patch approval decisions.
             }
+            ApprovalRequest::McpToolCall { .. } => {
+                // The tool call's own history cell shows whether it ran.
+            }
         }
 
         let op = match &self.approval_request {
@@ -305,6 +347,10 @@ impl UserApprovalWidget {
                 id: id.clone(),
                 decision,
             },
+            ApprovalRequest::McpToolCall { id, .. } => Op::McpToolCallApproval {
+                id: id.clone(),
+                decision,
+            },
         };
 
         self.app_event_tx.send(AppEvent::CodexOp(op));
@@ -340,7 +386,7 @@ impl WidgetRef for &UserApprovalWidget {
             .enumerate()
             .map(|(idx, opt)| {
                 let style = if idx == self.selected_option {
-                    Style::new().bg(Color::Cyan).fg(Color::Black)
+                    Style::new().bg(theme().accent).fg(Color::Black)
                 } else {
                     Style::new().add_modifier(Modifier::DIM)
                 };
@@ -357,6 +403,7 @@ impl WidgetRef for &UserApprovalWidget {
         let title = match &self.approval_request {
             ApprovalRequest::Exec { .. } => "Allow command?",
             ApprovalRequest::ApplyPatch { .. } => "Apply changes?",
+            ApprovalRequest::McpToolCall { .. } => "Allow tool call?",
         };
         Line::from(title).render(title_area, buf);
 
@@ -379,7 +426,7 @@ impl WidgetRef for &UserApprovalWidget {
 
         Block::bordered()
             .border_type(BorderType::QuadrantOutside)
-            .border_style(Style::default().fg(Color::Cyan))
+            .border_style(Style::default().accent())
             .borders(Borders::LEFT)
             .render_ref(
                 Rect::new(0, response_chunk.y, 1, response_chunk.height),